@@ -1,6 +1,29 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
 fn main() {
     println!(
         "cargo:rustc-env=TARGET={}",
         std::env::var("TARGET").unwrap()
     );
+
+    embed_snapshot();
+}
+
+/// Embeds the startup snapshot passed with the `DUNE_SNAPSHOT` env variable.
+fn embed_snapshot() {
+    println!("cargo:rustc-check-cfg=cfg(dune_snapshot)");
+    println!("cargo:rerun-if-env-changed=DUNE_SNAPSHOT");
+
+    let path = match env::var("DUNE_SNAPSHOT") {
+        Ok(path) if !path.is_empty() => path,
+        _ => return,
+    };
+
+    let output = PathBuf::from(env::var("OUT_DIR").unwrap()).join("dune.snapshot");
+    fs::copy(&path, output).expect("Failed to copy the startup snapshot");
+
+    println!("cargo:rerun-if-changed={path}");
+    println!("cargo:rustc-cfg=dune_snapshot");
 }
@@ -0,0 +1,10 @@
+// Prints the arguments passed to the script.
+//
+// $ dune run examples/argv.js one --two=2 -x
+
+const [execPath, script, ...args] = process.argv;
+
+console.log('execPath:', execPath);
+console.log('argv0:', process.argv0);
+console.log('script:', script);
+console.log('args:', args);
@@ -0,0 +1,24 @@
+// Flushes buffered work when the event-loop becomes idle.
+//
+// $ dune run examples/beforeExit.js
+
+const queue = [];
+let flushes = 0;
+
+setTimeout(() => queue.push('first'), 10);
+setTimeout(() => queue.push('second'), 20);
+
+process.on('beforeExit', (code) => {
+  // Nothing left to flush, let the process exit.
+  if (queue.length === 0) {
+    console.log(`Exiting after ${flushes} flush(es) with code ${code}.`);
+    return;
+  }
+
+  // Scheduling async work keeps the process alive.
+  const items = queue.splice(0);
+  setTimeout(() => {
+    flushes++;
+    console.log('Flushed:', items);
+  }, 10);
+});
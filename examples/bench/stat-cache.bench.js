@@ -0,0 +1,39 @@
+import fs from 'fs';
+import bench from 'bench';
+
+// Build a deep dependency tree (one module per directory level) to resolve.
+// Note: The tree lives in a fixed temp directory, so it's reused across runs.
+const DEPTH = 32;
+const tmp = process.env.TMPDIR || process.env.TEMP || '/tmp';
+const root = `${tmp}/dune-bench-stat-cache`;
+const modules = [];
+
+let dir = root;
+for (let i = 0; i < DEPTH; i++) {
+  dir = `${dir}/dep_${i}`;
+  fs.mkdirSync(dir, { recursive: true });
+  fs.writeFileSync(`${dir}/index.js`, `export default ${i};`);
+  modules.push(`${dir}/index.js`);
+}
+
+// Note: Resolving a module probes a few candidates before finding the file.
+const candidates = modules.flatMap((path) => [
+  path.replace(/\.js$/, ''),
+  path.replace(/\.js$/, '.ts'),
+  path,
+]);
+
+function resolveTree(cache) {
+  let found = 0;
+  for (const path of candidates) {
+    try {
+      if (fs.statSync(path, { cache }).isFile) found++;
+    } catch {
+      continue;
+    }
+  }
+  return found;
+}
+
+bench('resolve deep tree (no cache)', () => resolveTree(false));
+bench('resolve deep tree (cached)', () => resolveTree(true));
@@ -0,0 +1,17 @@
+import bench, { doNotOptimize } from 'bench';
+
+const words = Array.from({ length: 1000 }, (_, i) => `word-${i}`);
+
+bench('join words with Array.join', () => {
+  doNotOptimize(words.join(' '));
+});
+
+bench('join words with string concatenation', () => {
+  let text = '';
+  for (const word of words) text += word + ' ';
+  doNotOptimize(text);
+});
+
+bench('join words asynchronously', { time: 250 }, async () => {
+  doNotOptimize(await Promise.resolve(words.join(' ')));
+});
@@ -0,0 +1,30 @@
+#!/bin/bash
+
+# Compares the startup time of the current tree against a base revision.
+#
+# Usage: ./scripts/bench-startup.sh <BASE_REF> [RUNS]
+
+# Check if hyperfine is installed.
+if ! command -v hyperfine >/dev/null; then
+  echo "Error: hyperfine is required to benchmark the startup." 1>&2
+  echo "=>> You can install hyperfine via \"cargo install hyperfine\"." 1>&2
+  exit 1
+fi
+
+base=${1:?Usage: $0 <BASE_REF> [RUNS]}
+runs=${2:-50}
+workdir=$(mktemp -d)
+
+trap 'git worktree remove --force "$workdir/base" >/dev/null 2>&1; rm -rf "$workdir"' EXIT
+
+# Build both binaries without a snapshot, so only the module loading differs.
+git worktree add --detach "$workdir/base" "$base" >/dev/null || exit 1
+(cd "$workdir/base" && DUNE_SNAPSHOT= cargo build --release) || exit 1
+DUNE_SNAPSHOT= cargo build --release || exit 1
+
+# A script that touches none of the lazily loaded modules.
+echo 'console.log("ready");' >"$workdir/empty.js"
+
+hyperfine --warmup 5 --runs "$runs" -N \
+  --command-name "base ($base)" "$workdir/base/target/release/dune run $workdir/empty.js" \
+  --command-name "current" "./target/release/dune run $workdir/empty.js"
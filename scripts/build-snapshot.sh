@@ -0,0 +1,13 @@
+#!/bin/bash
+
+# Set the snapshot location (default: target/dune.snapshot).
+snapshot=${1:-$(pwd)/target/dune.snapshot}
+
+# Build a binary without a snapshot (a stale one may not match the bindings).
+DUNE_SNAPSHOT= cargo build --release
+
+# Create the startup snapshot.
+./target/release/dune snapshot --output "$snapshot"
+
+# Build the binary again, embedding the snapshot.
+DUNE_SNAPSHOT="$snapshot" cargo build --release
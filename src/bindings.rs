@@ -1,27 +1,75 @@
+use crate::blob;
+use crate::buffer;
+use crate::compression;
+use crate::cookies;
+use crate::crypto;
+use crate::csv;
 use crate::dns;
 use crate::errors::extract_error_code;
-use crate::errors::report_and_exit;
+use crate::errors::resource_limit_hint;
 use crate::errors::IoError;
 use crate::exceptions;
 use crate::file;
+use crate::hooks;
 use crate::http_parser;
+use crate::log;
+use crate::modules;
 use crate::net;
 use crate::perf_hooks;
+use crate::poll;
 use crate::process;
 use crate::promise;
 use crate::runtime::check_exceptions;
 use crate::runtime::JsRuntime;
 use crate::signals;
 use crate::stdio;
+use crate::text_encoding;
 use crate::timers;
+use crate::udp;
+use anyhow::anyhow;
 use anyhow::Error;
+use anyhow::Result;
 use lazy_static::lazy_static;
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ffi::c_void;
+use std::rc::Rc;
 
 /// Function pointer for the bindings initializers.
 type BindingInitFn = fn(&mut v8::HandleScope<'_>) -> v8::Global<v8::Object>;
 
+/// Function pointer returning the native functions of a binding.
+type ExternalReferencesFn = fn() -> Vec<v8::ExternalReference<'static>>;
+
+/// Lists native functions, so they can be referenced from the startup snapshot.
+macro_rules! external_references {
+    ($($function:expr),* $(,)?) => {
+        vec![$(v8::ExternalReference {
+            function: v8::MapFnTo::map_fn_to($function),
+        }),*]
+    };
+}
+
+pub(crate) use external_references;
+
+/// A native function called from JavaScript (same signature as the built-in bindings).
+pub type NativeFunction = fn(&mut v8::HandleScope, v8::FunctionCallbackArguments, v8::ReturnValue);
+
+/// A named set of native functions, exposed to JavaScript as a global object.
+///
+/// Callbacks always run on the runtime's thread (V8 isolates can't be shared between
+/// threads) and block the event-loop while running, so long work should be moved to
+/// a thread of its own. Being plain function pointers they can't capture any state,
+/// use `JsRuntime::state(scope)`, a `thread_local!` or a static instead.
+#[derive(Debug, Clone)]
+pub struct Extension {
+    /// The name of the global object (e.g. `host`).
+    pub name: &'static str,
+    /// The functions defined on the object, by name.
+    pub functions: Vec<(&'static str, NativeFunction)>,
+}
+
 lazy_static! {
     pub static ref BINDINGS: HashMap<&'static str, BindingInitFn> = {
         let bindings: Vec<(&'static str, BindingInitFn)> = vec![
@@ -29,19 +77,71 @@ lazy_static! {
             ("timers", timers::initialize),
             ("fs", file::initialize),
             ("perf_hooks", perf_hooks::initialize),
+            ("poll", poll::initialize),
             ("dns", dns::initialize),
             ("net", net::initialize),
+            ("udp", udp::initialize),
             ("promise", promise::initialize),
             ("http_parser", http_parser::initialize),
+            ("cookies", cookies::initialize),
             ("signals", signals::initialize),
             ("exceptions", exceptions::initialize),
+            ("modules", modules::initialize),
+            ("text_encoding", text_encoding::initialize),
+            ("blob", blob::initialize),
+            ("buffer", buffer::initialize),
+            ("compression", compression::initialize),
+            ("crypto", crypto::initialize),
+            ("csv", csv::initialize),
+            ("log", log::initialize),
         ];
         HashMap::from_iter(bindings.into_iter())
     };
+
+    /// Every native function that can be referenced from the startup snapshot.
+    ///
+    /// Note: The snapshot refers to them by index, so it has to be created again
+    /// whenever this list (or the order of it) changes.
+    pub static ref EXTERNAL_REFERENCES: v8::ExternalReferences = {
+        let bindings: Vec<ExternalReferencesFn> = vec![
+            stdio::external_references,
+            timers::external_references,
+            file::external_references,
+            perf_hooks::external_references,
+            poll::external_references,
+            dns::external_references,
+            net::external_references,
+            udp::external_references,
+            promise::external_references,
+            http_parser::external_references,
+            cookies::external_references,
+            signals::external_references,
+            exceptions::external_references,
+            modules::external_references,
+            text_encoding::external_references,
+            blob::external_references,
+            buffer::external_references,
+            compression::external_references,
+            crypto::external_references,
+            csv::external_references,
+            log::external_references,
+            process::external_references,
+            hooks::external_references,
+        ];
+
+        let mut references =
+            external_references![global_print, global_report_error, global_queue_micro];
+
+        references.extend(bindings.into_iter().flat_map(|references| references()));
+        v8::ExternalReferences::new(&references)
+    };
 }
 
 /// Populates a new JavaScript context with low-level Rust bindings.
-pub fn create_new_context<'s>(scope: &mut v8::HandleScope<'s, ()>) -> v8::Local<'s, v8::Context> {
+pub fn create_new_context<'s>(
+    scope: &mut v8::HandleScope<'s, ()>,
+    extensions: &[Extension],
+) -> v8::Local<'s, v8::Context> {
     // Here we need an EscapableHandleScope so V8 doesn't drop the
     // newly created HandleScope on return. (https://v8.dev/docs/embed#handles-and-garbage-collection)
     let scope = &mut v8::EscapableHandleScope::new(scope);
@@ -57,17 +157,78 @@ pub fn create_new_context<'s>(scope: &mut v8::HandleScope<'s, ()>) -> v8::Local<
 
     // Expose low-level functions to JavaScript.
     process::initialize(scope, global);
+
+    set_extensions_to(scope, global, extensions);
+    scope.escape(context)
+}
+
+/// Restores the JavaScript context saved in the startup snapshot.
+pub fn restore_context<'s>(
+    scope: &mut v8::HandleScope<'s, ()>,
+    extensions: &[Extension],
+) -> v8::Local<'s, v8::Context> {
+    let scope = &mut v8::EscapableHandleScope::new(scope);
+
+    // Note: In isolates created from a snapshot, the new context is a copy of
+    // the snapshot's default one (already populated with the bindings).
+    let context = v8::Context::new(scope, Default::default());
+    let global = context.global(scope);
+    let scope = &mut v8::ContextScope::new(scope, context);
+
+    set_extensions_to(scope, global, extensions);
     scope.escape(context)
 }
 
-// Simple print function bound to Rust's println! macro.
+/// Exposes the embedder's functions to JavaScript.
+fn set_extensions_to(
+    scope: &mut v8::HandleScope<'_>,
+    global: v8::Local<v8::Object>,
+    extensions: &[Extension],
+) {
+    for extension in extensions {
+        let target = v8::Object::new(scope);
+        for (name, callback) in extension.functions.iter() {
+            set_native_function_to(scope, target, name, *callback);
+        }
+        set_property_to(scope, global, extension.name, target.into());
+    }
+}
+
+/// Adds a native function pointer with the given name into the given object.
+fn set_native_function_to(
+    scope: &mut v8::HandleScope<'_>,
+    target: v8::Local<v8::Object>,
+    name: &str,
+    callback: NativeFunction,
+) {
+    // Note: V8 only accepts zero-sized callbacks, so the pointer is passed as data
+    // to a trampoline that calls it.
+    let data = v8::External::new(scope, callback as *mut c_void);
+    let builder = v8::FunctionBuilder::new(call_native_function).data(data.into());
+
+    let key = v8::String::new(scope, name).unwrap();
+    let value = v8::FunctionBuilder::<v8::Function>::build(builder, scope).unwrap();
+    target.set(scope, key.into(), value.into());
+}
+
+fn call_native_function(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    rv: v8::ReturnValue,
+) {
+    let data = v8::Local::<v8::External>::try_from(args.data()).unwrap();
+    let callback = unsafe { std::mem::transmute::<*mut c_void, NativeFunction>(data.value()) };
+    callback(scope, args, rv);
+}
+
+// Simple print function that writes a line to the stdout.
 fn global_print(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     _: v8::ReturnValue,
 ) {
     let value = args.get(0).to_rust_string_lossy(scope);
-    println!("{value}");
+    stdio::write_stdout(scope, format!("{value}\n").as_bytes());
 }
 
 // This method may be used to report errors to global event handlers.
@@ -84,8 +245,10 @@ fn global_report_error(
     state.exceptions.capture_exception(exception);
     drop(state);
 
+    // Note: Stops the running JS, the same way exiting the process would.
     if let Some(error) = check_exceptions(scope) {
-        report_and_exit(error);
+        state_rc.borrow_mut().set_fatal_error(error);
+        scope.terminate_execution();
     }
 }
 
@@ -167,6 +330,34 @@ pub fn set_internal_ref<T>(
     target.set_internal_field(index, v8_ext.into());
 }
 
+/// Stores a Rust type inside a v8 object, dropping it once the object is
+/// garbage collected.
+pub fn set_internal_ref_with_finalizer<T: 'static>(
+    scope: &mut v8::HandleScope<'_>,
+    target: v8::Local<v8::Object>,
+    index: usize,
+    data: T,
+) {
+    let addr = Box::into_raw(Box::new(data));
+    let v8_ext = v8::External::new(scope, addr as *mut c_void);
+
+    target.set_internal_field(index, v8_ext.into());
+
+    // Note: The finalizer only runs while the weak handle is alive, so the
+    // finalizer itself holds on to it (and drops it once it has run).
+    let weak_slot = Rc::new(RefCell::new(None));
+    let finalizer = {
+        let weak_slot = weak_slot.clone();
+        Box::new(move |_: &mut v8::Isolate| {
+            drop(unsafe { Box::from_raw(addr) });
+            weak_slot.borrow_mut().take();
+        })
+    };
+
+    let weak = v8::Weak::with_finalizer(scope, target, finalizer);
+    weak_slot.borrow_mut().replace(weak);
+}
+
 /// Gets a previously stored Rust type from a v8 object.
 pub fn get_internal_ref<'s, T>(
     scope: &mut v8::HandleScope<'s>,
@@ -193,6 +384,37 @@ pub fn set_exception_code(
             let value = v8::String::new(scope, &format!("ERR_{code}")).unwrap();
             exception.set(scope, key.into(), value.into());
         }
+        // Point to the open files limit, since the OS message alone is obscure.
+        if let Some(hint) = resource_limit_hint(error) {
+            append_to_message(scope, exception, hint);
+        }
+    }
+}
+
+/// Appends a line to the exception's message (and the stack that repeats it).
+fn append_to_message(
+    scope: &mut v8::HandleScope<'_>,
+    exception: v8::Local<v8::Object>,
+    text: &str,
+) {
+    for name in ["message", "stack"] {
+        let key = v8::String::new(scope, name).unwrap();
+        let Some(value) = exception.get(scope, key.into()) else {
+            continue;
+        };
+        if !value.is_string() {
+            continue;
+        }
+        let value = value.to_rust_string_lossy(scope);
+        let value = match name {
+            "message" => format!("{value}\n{text}"),
+            _ => match value.split_once('\n') {
+                Some((head, frames)) => format!("{head}\n{text}\n{frames}"),
+                None => format!("{value}\n{text}"),
+            },
+        };
+        let value = v8::String::new(scope, &value).unwrap();
+        exception.set(scope, key.into(), value.into());
     }
 }
 
@@ -211,3 +433,9 @@ pub fn throw_type_error(scope: &mut v8::HandleScope, message: &str) {
     let exception = v8::Exception::type_error(scope, message);
     scope.throw_exception(exception);
 }
+
+/// Deserializes the (bincode) result of a thread-pool operation, so a
+/// malformed payload rejects the operation instead of crashing the runtime.
+pub fn decode_result<T: DeserializeOwned>(bytes: Vec<u8>) -> Result<T> {
+    bincode::deserialize(&bytes).map_err(|e| anyhow!("Failed to decode operation result: {e}"))
+}
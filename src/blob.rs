@@ -0,0 +1,53 @@
+// Blob APIs
+//
+// This module provides the native byte store behind the WHATWG Blob and File objects.
+// https://w3c.github.io/FileAPI/#blob-section
+
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "concat", concat);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![concat]
+}
+
+/// Joins a list of byte views into a single Rust-owned ArrayBuffer.
+fn concat(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the blob parts as byte views.
+    let parts = v8::Local::<v8::Array>::try_from(args.get(0)).unwrap();
+    let parts: Vec<v8::Local<v8::ArrayBufferView>> = (0..parts.length())
+        .map(|i| parts.get_index(scope, i).unwrap())
+        .map(|part| part.try_into().unwrap())
+        .collect();
+
+    // Allocate the whole store once and copy every part in place.
+    let size = parts.iter().map(|part| part.byte_length()).sum();
+    let mut bytes = vec![0; size];
+    let mut offset = 0;
+
+    for part in parts {
+        offset += part.copy_contents(&mut bytes[offset..]);
+    }
+
+    // Note: The bytes are handed over to v8 (without copying) so the store
+    // is released when the garbage collector drops the last Blob using it.
+    let store = bytes.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+
+    rv.set(buffer.into());
+}
@@ -0,0 +1,137 @@
+// Buffer APIs
+//
+// This module provides the encoding-heavy paths behind the Node.js compatible Buffer.
+// https://nodejs.org/api/buffer.html#buffers-and-character-encodings
+
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+use crate::bindings::throw_exception;
+use anyhow::anyhow;
+use base64::alphabet;
+use base64::engine::general_purpose::GeneralPurpose;
+use base64::engine::general_purpose::GeneralPurposeConfig;
+use base64::engine::general_purpose::STANDARD;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use std::fmt::Write;
+
+/// A base64 engine as forgiving as Node.js (no padding, loose trailing bits).
+const LENIENT_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_allow_trailing_bits(true)
+        .with_decode_padding_mode(DecodePaddingMode::RequireNone),
+);
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "encode", encode);
+    set_function_to(scope, target, "decode", decode);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![encode, decode]
+}
+
+/// Parses hex pairs, stopping at the first invalid one (like Node.js).
+fn from_hex(input: &str) -> Vec<u8> {
+    input
+        .as_bytes()
+        .chunks_exact(2)
+        .map_while(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+/// Decodes both base64 alphabets, skipping anything that's not part of them.
+fn from_base64(input: &str) -> Vec<u8> {
+    let mut chars: Vec<u8> = input
+        .bytes()
+        .take_while(|byte| *byte != b'=')
+        .filter_map(|byte| match byte {
+            b'-' => Some(b'+'),
+            b'_' => Some(b'/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'/' => Some(byte),
+            _ => None,
+        })
+        .collect();
+
+    // A single trailing character can't encode a full byte.
+    if chars.len() % 4 == 1 {
+        chars.pop();
+    }
+
+    LENIENT_BASE64.decode(chars).unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(output, "{byte:02x}");
+    }
+    output
+}
+
+/// Encodes a string into bytes, using the given encoding.
+fn encode(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the string and the encoding.
+    let input = args.get(0).to_rust_string_lossy(scope);
+    let encoding = args.get(1).to_rust_string_lossy(scope);
+
+    let bytes = match encoding.as_str() {
+        "hex" => from_hex(&input),
+        "base64" | "base64url" => from_base64(&input),
+        _ => input.into_bytes(),
+    };
+
+    // Note: The bytes are handed over to v8 without copying them.
+    let store = bytes.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+
+    rv.set(buffer.into());
+}
+
+/// Decodes a byte view into a string, using the given encoding.
+fn decode(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get data as ArrayBufferView.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(0).try_into().unwrap();
+    let encoding = args.get(1).to_rust_string_lossy(scope);
+
+    let mut bytes = vec![0; data.byte_length()];
+    data.copy_contents(&mut bytes);
+
+    let output = match encoding.as_str() {
+        "hex" => to_hex(&bytes),
+        "base64" => STANDARD.encode(&bytes),
+        "base64url" => URL_SAFE_NO_PAD.encode(&bytes),
+        _ => String::from_utf8_lossy(&bytes).into_owned(),
+    };
+
+    // Note: V8 strings have a max length (around 512MB).
+    match v8::String::new(scope, &output) {
+        Some(output) => rv.set(output.into()),
+        None => {
+            let e = anyhow!("Cannot create a string longer than the max length.");
+            throw_exception(scope, &e);
+        }
+    }
+}
@@ -1,19 +1,32 @@
 use crate::bundle;
+use crate::cache;
 use crate::compile;
 use crate::dotenv;
+use crate::dune_dir;
+use crate::errors::exit_with_error;
 use crate::errors::generic_error;
+use crate::errors::report_error;
+use crate::errors::set_error_format;
 use crate::errors::unwrap_or_exit;
+use crate::errors::ErrorFormat;
+use crate::info;
+use crate::loaders::Reload;
 use crate::modules::resolve_import;
 use crate::modules::ImportMap;
 use crate::repl;
 use crate::runtime::JsRuntime;
 use crate::runtime::JsRuntimeOptions;
+use crate::snapshot;
 use crate::upgrade;
+use crate::vendor;
+use crate::verbosity::set_verbosity;
+use crate::verbosity::Verbosity;
 use crate::watcher;
 use anyhow::bail;
 use anyhow::Result;
 use clap::ArgAction;
 use clap::Args;
+use clap::ColorChoice;
 use clap::Parser;
 use clap::Subcommand;
 use clap::ValueHint;
@@ -24,6 +37,8 @@ use std::fs;
 use std::net::SocketAddrV4;
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::time::Duration;
+use swc_ecma_ast::EsVersion;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
@@ -51,27 +66,61 @@ enum Command {
         arg_required_else_help = true
     )]
     Compile(CompileArgs),
+    #[command(
+        about = "Vendor remote dependencies into a local directory",
+        arg_required_else_help = true
+    )]
+    Vendor(VendorArgs),
+    #[command(
+        about = "Show the dependency tree of a module without running it",
+        arg_required_else_help = true
+    )]
+    Info(InfoArgs),
+    #[command(
+        about = "Manage the cache of URL imports",
+        subcommand,
+        arg_required_else_help = true
+    )]
+    Cache(CacheCommand),
     #[command(
         about = "Execute tests using the built-in test runner",
         arg_required_else_help = true
     )]
     Test(TestArgs),
+    #[command(about = "Run benchmarks using the built-in bench runner")]
+    Bench(BenchArgs),
     #[command(about = "Upgrade to the latest dune version")]
     Upgrade,
     #[command(about = "Start the REPL (read, eval, print, loop)")]
-    Repl,
+    Repl(ReplArgs),
+    #[command(
+        about = "Create a startup snapshot (see scripts/build-snapshot.sh)",
+        hide = true
+    )]
+    Snapshot(SnapshotArgs),
+}
+
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    #[command(about = "List the cached URL imports")]
+    List,
+    #[command(about = "Remove cached URL imports")]
+    Clean(CacheCleanArgs),
 }
 
 #[derive(Debug, Args)]
 struct GlobalArgs {
     #[arg(
-        help = "Reload every URL import (cache is ignored)",
-        action = ArgAction::SetTrue,
+        help = "Reload URL imports, optionally only the ones starting with the given prefixes",
+        value_name = "URL_PREFIX",
         short,
         long,
+        num_args = 0..,
+        require_equals = true,
+        value_delimiter = ',',
         global = true
     )]
-    reload: Option<bool>,
+    reload: Option<Vec<String>>,
     #[arg(
         help = "Make the Math.random() method predictable",
         long = "seed",
@@ -79,6 +128,22 @@ struct GlobalArgs {
         global = true
     )]
     seed: Option<i64>,
+    #[arg(
+        help = "Make crypto random values predictable too (insecure, for tests only)",
+        action = ArgAction::SetTrue,
+        long = "seed-entropy",
+        requires = "seed",
+        global = true
+    )]
+    seed_entropy: Option<bool>,
+    #[arg(
+        help = "Set the directory for cached modules and other files (default: ~/.dune)",
+        long = "cache-dir",
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        global = true
+    )]
+    cache_dir: Option<PathBuf>,
     #[arg(
         help = "Load configuration from local file",
         long = "env-file",
@@ -98,6 +163,14 @@ struct GlobalArgs {
         global = true
     )]
     import_map: Option<PathBuf>,
+    #[arg(
+        help = "Execute a module before the entry point (can be repeated)",
+        long = "import",
+        value_name = "SPECIFIER",
+        action = ArgAction::Append,
+        global = true
+    )]
+    preload: Vec<String>,
     #[arg(
         help = "Set the number of threads used for I/O",
         long = "threadpool-size",
@@ -105,6 +178,13 @@ struct GlobalArgs {
         global = true
     )]
     thread_pool_size: Option<usize>,
+    #[arg(
+        help = "Limit the number of fs (or dns) tasks running at once on the thread-pool",
+        long = "threadpool-task-limit",
+        value_name = "NUMBER",
+        global = true
+    )]
+    task_limit: Option<usize>,
     #[arg(
         help = "Enable inspector agent (default: 127.0.0.1:9229)",
         value_name = "ADDRESS",
@@ -134,12 +214,120 @@ struct GlobalArgs {
         global = true
     )]
     expose_gc: Option<bool>,
+    #[arg(
+        help = "Pass extra flags to V8 (advanced, unsupported), e.g. --v8-flags=--jitless",
+        long = "v8-flags",
+        value_name = "FLAGS",
+        require_equals = true,
+        allow_hyphen_values = true,
+        value_delimiter = ',',
+        global = true
+    )]
+    v8_flags: Vec<String>,
+    #[arg(
+        help = "Run JavaScript without V8's JIT compilers (slower, smaller attack surface)",
+        action = ArgAction::SetTrue,
+        long = "jitless",
+        global = true
+    )]
+    jitless: Option<bool>,
+    #[arg(
+        help = "Make eval() and new Function() throw an EvalError",
+        action = ArgAction::SetTrue,
+        long = "disallow-code-generation",
+        global = true
+    )]
+    disallow_code_generation: Option<bool>,
+    #[arg(
+        help = "Write a CPU profile (folded stacks) on exit",
+        long = "prof",
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        require_equals = true,
+        default_missing_value = "dune.folded",
+        num_args = ..=1,
+        global = true
+    )]
+    prof: Option<PathBuf>,
+    #[arg(
+        help = "Set the CPU profiler's sampling interval (in microseconds)",
+        long = "prof-interval",
+        value_name = "NUMBER",
+        global = true
+    )]
+    prof_interval: Option<u64>,
+    #[arg(
+        help = "Load modules one at a time, in a deterministic order",
+        action = ArgAction::SetTrue,
+        long = "frozen",
+        global = true
+    )]
+    frozen: Option<bool>,
+    #[arg(
+        help = "Report the timing and cache status of module loads",
+        long = "trace-imports",
+        value_name = "FILE",
+        value_hint = ValueHint::FilePath,
+        require_equals = true,
+        num_args = ..=1,
+        global = true
+    )]
+    trace_imports: Option<Option<PathBuf>>,
+    #[arg(
+        help = "Control when colored output is used",
+        long = "color",
+        value_name = "WHEN",
+        require_equals = true,
+        value_enum,
+        global = true
+    )]
+    color: Option<ColorChoice>,
+    #[arg(
+        help = "Disable colored output (same as --color=never)",
+        action = ArgAction::SetTrue,
+        long = "no-color",
+        global = true
+    )]
+    no_color: Option<bool>,
+    #[arg(
+        help = "Report uncaught errors as human-readable text or JSON",
+        long = "error-format",
+        value_name = "FORMAT",
+        require_equals = true,
+        value_enum,
+        global = true
+    )]
+    error_format: Option<ErrorFormat>,
+    #[arg(
+        help = "Suppress the runtime's own messages (e.g. downloads, debugger address)",
+        action = ArgAction::SetTrue,
+        short,
+        long = "quiet",
+        conflicts_with = "verbose",
+        global = true
+    )]
+    quiet: Option<bool>,
+    #[arg(
+        help = "Trace module loads, cache hits and (in debug builds) event-loop ticks",
+        action = ArgAction::SetTrue,
+        short,
+        long = "verbose",
+        global = true
+    )]
+    verbose: Option<bool>,
 }
 
 #[derive(Debug, Parser)]
 struct RunArgs {
-    #[arg(help = "The script that will run", required = true)]
-    script: String,
+    #[arg(
+        help = "The script that will run (followed by the arguments passed to it)",
+        value_name = "SCRIPT",
+        required = true,
+        num_args = 1..,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    script: Vec<String>,
     #[arg(
         help = "Watch for file changes and restart process automatically",
         value_name = "FILES",
@@ -170,10 +358,117 @@ struct BundleArgs {
         long,
     )]
     minify: Option<bool>,
+    #[arg(
+        help = "Shorten variable and function names (swc's mangle pass)",
+        action = ArgAction::SetTrue,
+        long
+    )]
+    mangle: bool,
+    #[arg(
+        help = "Drop dead code and inline values (swc's compress passes)",
+        action = ArgAction::SetTrue,
+        long
+    )]
+    compress: bool,
+    #[arg(
+        help = "Preserve function and class names when mangling/compressing",
+        action = ArgAction::SetTrue,
+        long
+    )]
+    keep_names: bool,
+    #[arg(
+        help = "The ECMAScript version of the output (e.g. es2020)",
+        long,
+        value_name = "VERSION",
+        value_parser = bundle::parse_target
+    )]
+    target: Option<EsVersion>,
+    #[arg(
+        help = "Replace a global (e.g. process.env.NODE_ENV) with a constant",
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = bundle::parse_define
+    )]
+    define: Vec<(String, String)>,
 }
 
 type CompileArgs = BundleArgs;
 
+#[derive(Debug, Parser)]
+struct VendorArgs {
+    #[arg(help = "The entry point script", required = true)]
+    entry: String,
+    #[arg(
+        help = "The directory where remote modules will be written",
+        short,
+        long,
+        value_name = "DIR",
+        default_value = "vendor",
+        value_hint = ValueHint::DirPath,
+    )]
+    output: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+struct InfoArgs {
+    #[arg(help = "The entry point script", required = true)]
+    entry: String,
+    #[arg(
+        help = "Output the module graph as JSON",
+        action = ArgAction::SetTrue,
+        long = "json"
+    )]
+    json: Option<bool>,
+}
+
+#[derive(Debug, Parser)]
+struct CacheCleanArgs {
+    #[arg(
+        help = "Only remove URL imports starting with the given prefixes",
+        value_name = "URL_PREFIX",
+        long = "url",
+        value_delimiter = ','
+    )]
+    url: Vec<String>,
+    #[arg(
+        help = "Only remove URL imports downloaded before the given time (e.g. 12h, 7d, 2w)",
+        value_name = "DURATION",
+        long = "older-than",
+        value_parser = cache::parse_duration
+    )]
+    older_than: Option<Duration>,
+    #[arg(
+        help = "Don't ask for confirmation",
+        action = ArgAction::SetTrue,
+        short,
+        long = "yes"
+    )]
+    yes: Option<bool>,
+}
+
+#[derive(Debug, Default, Parser)]
+struct ReplArgs {
+    #[arg(
+        help = "Max time to wait for in-flight tasks when the REPL exits (in milliseconds)",
+        long = "drain-timeout",
+        value_name = "NUMBER"
+    )]
+    drain_timeout: Option<u64>,
+}
+
+#[derive(Debug, Parser)]
+struct SnapshotArgs {
+    #[arg(
+        help = "The file where the snapshot will be written",
+        short,
+        long,
+        value_name = "FILE",
+        default_value = "dune.snapshot",
+        value_hint = ValueHint::FilePath,
+    )]
+    output: PathBuf,
+}
+
 #[derive(Debug, Parser)]
 struct TestArgs {
     #[arg(
@@ -200,6 +495,30 @@ struct TestArgs {
     filter: Option<String>,
 }
 
+#[derive(Debug, Parser)]
+struct BenchArgs {
+    #[arg(
+        help = "Path to a benchmark file or directory",
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        required = false
+    )]
+    path: Option<PathBuf>,
+    #[arg(
+        help = "Run benchmarks with this regex pattern in their name",
+        value_name = "FILTER",
+        require_equals = true,
+        long
+    )]
+    filter: Option<String>,
+    #[arg(
+        help = "Output the results as JSON",
+        action = ArgAction::SetTrue,
+        long
+    )]
+    json: bool,
+}
+
 const PORT_RANGE: RangeInclusive<usize> = 1..=65535;
 
 fn parse_inspect_address(s: &str) -> Result<SocketAddrV4> {
@@ -219,6 +538,15 @@ fn parse_inspect_address(s: &str) -> Result<SocketAddrV4> {
     bail!("Value can't be parsed into an IPv4 address")
 }
 
+/// Builds the reload policy for URL imports from the `--reload` flag.
+fn reload_policy(globals: &GlobalArgs) -> Reload {
+    match globals.reload.as_ref() {
+        Some(prefixes) if prefixes.is_empty() => Reload::All,
+        Some(prefixes) => Reload::Prefixes(prefixes.clone()),
+        None => Reload::None,
+    }
+}
+
 fn load_import_map(filename: Option<&PathBuf>) -> Option<ImportMap> {
     filename.map(|file| {
         let contents = fs::read_to_string(file).map_err(|e| e.into());
@@ -227,28 +555,51 @@ fn load_import_map(filename: Option<&PathBuf>) -> Option<ImportMap> {
     })
 }
 
+/// Resolves the modules requested with `--import` (like the entry point).
+fn resolve_preloads(globals: &GlobalArgs, import_map: Option<&ImportMap>) -> Vec<String> {
+    let resolve = |specifier: &String| {
+        let import_map = import_map.cloned();
+        resolve_import(None, specifier, false, import_map.clone())
+            .or_else(|_| resolve_import(None, &format!("./{specifier}"), false, import_map))
+            .map_err(|e| generic_error(format!("Cannot preload \"{specifier}\": {e}")))
+    };
+
+    globals
+        .preload
+        .iter()
+        .map(|specifier| unwrap_or_exit(resolve(specifier)))
+        .collect()
+}
+
+/// Executes the `--import` modules (in order), exiting if any of them fails.
+fn preload_modules(runtime: &mut JsRuntime, preloads: &[String]) {
+    for path in preloads {
+        if let Err(e) = runtime.preload_module(path) {
+            runtime.write_reports();
+            exit_with_error(&e);
+        }
+    }
+}
+
 fn run_command(args: &RunArgs, globals: &GlobalArgs) {
     // Try load the requested import-map.
     let import_map = load_import_map(globals.import_map.as_ref());
 
+    // Note: Everything after the script is passed to the script itself.
+    let (script, script_args) = args.script.split_first().unwrap();
+
     // NOTE: The following code tries to resolve the given filename
     // to an absolute path. If the first time fails we will append `./` to
     // it first, and retry the resolution in case the user forgot to specify it.
     let filename = unwrap_or_exit(
-        resolve_import(None, &args.script, true, import_map.clone()).or_else(|_| {
-            resolve_import(
-                None,
-                &format!("./{}", args.script),
-                true,
-                import_map.clone(),
-            )
-        }),
+        resolve_import(None, script, true, import_map.clone())
+            .or_else(|_| resolve_import(None, &format!("./{}", script), true, import_map.clone())),
     );
 
     // Check if we have to run on `watch` mode.
     if args.watch.is_some() {
         let watch_paths = args.watch.to_owned().unwrap();
-        match watcher::start(&filename, watch_paths) {
+        match watcher::start(&filename, &args.script, watch_paths) {
             Ok(_) => return,
             Err(e) => {
                 eprintln!("{}: {}", "Error".red().bold(), e);
@@ -266,6 +617,8 @@ fn run_command(args: &RunArgs, globals: &GlobalArgs) {
         }
     }
 
+    let preloads = resolve_preloads(globals, import_map.as_ref());
+
     // Check if we need to enable the inspector.
     let inspect = globals
         .inspect
@@ -280,23 +633,43 @@ fn run_command(args: &RunArgs, globals: &GlobalArgs) {
 
     let options = JsRuntimeOptions {
         seed: globals.seed.to_owned(),
-        reload: globals.reload.unwrap_or_default(),
+        seed_entropy: globals.seed_entropy.unwrap_or_default(),
+        reload: reload_policy(globals),
         num_threads: globals.thread_pool_size.to_owned(),
+        task_limit: globals.task_limit.to_owned(),
         import_map,
         inspect,
         root,
+        argv: std::iter::once(filename.clone())
+            .chain(script_args.iter().cloned())
+            .collect(),
         test_mode: false,
         expose_gc: globals.expose_gc.unwrap_or_default(),
+        v8_flags: globals.v8_flags.clone(),
+        jitless: globals.jitless.unwrap_or_default(),
+        disallow_code_generation: globals.disallow_code_generation.unwrap_or_default(),
+        prof: globals.prof.to_owned(),
+        prof_interval: globals.prof_interval.to_owned(),
+        frozen: globals.frozen.unwrap_or_default(),
+        trace_imports: globals.trace_imports.is_some(),
+        trace_imports_file: globals.trace_imports.clone().flatten(),
     };
 
     // Create new JS runtime.
-    let mut runtime = JsRuntime::with_options(options);
+    let mut runtime = unwrap_or_exit(JsRuntime::with_options(options));
+    preload_modules(&mut runtime, &preloads);
+
     let mod_result = runtime.execute_module(&filename, None);
 
     match mod_result {
-        Ok(_) => runtime.run_event_loop(),
-        Err(e) => eprintln!("{e:?}"),
+        Ok(_) => unwrap_or_exit(runtime.run_event_loop()),
+        Err(e) => {
+            runtime.write_reports();
+            report_error(&e);
+        }
     };
+
+    std::process::exit(runtime.exit_code());
 }
 
 fn test_command(args: &TestArgs, globals: &GlobalArgs) {
@@ -329,6 +702,8 @@ fn test_command(args: &TestArgs, globals: &GlobalArgs) {
         None => "undefined".into(),
     };
 
+    let preloads = resolve_preloads(globals, import_map.as_ref());
+
     // Check if we need to enable the inspector.
     let inspect = globals
         .inspect
@@ -356,26 +731,127 @@ fn test_command(args: &TestArgs, globals: &GlobalArgs) {
     // Build JS runtime options.
     let options = JsRuntimeOptions {
         seed: globals.seed.to_owned(),
-        reload: globals.reload.unwrap_or_default(),
+        seed_entropy: globals.seed_entropy.unwrap_or_default(),
+        reload: reload_policy(globals),
         num_threads: globals.thread_pool_size.to_owned(),
+        task_limit: globals.task_limit.to_owned(),
         test_mode: true,
+        argv: vec![String::from(test_path.to_string_lossy())],
         import_map,
         inspect,
         expose_gc: globals.expose_gc.unwrap_or_default(),
+        v8_flags: globals.v8_flags.clone(),
+        jitless: globals.jitless.unwrap_or_default(),
+        disallow_code_generation: globals.disallow_code_generation.unwrap_or_default(),
+        prof: globals.prof.to_owned(),
+        prof_interval: globals.prof_interval.to_owned(),
+        frozen: globals.frozen.unwrap_or_default(),
+        trace_imports: globals.trace_imports.is_some(),
+        trace_imports_file: globals.trace_imports.clone().flatten(),
         ..Default::default()
     };
 
     // Create new JS runtime.
-    let mut runtime = JsRuntime::with_options(options);
+    let mut runtime = unwrap_or_exit(JsRuntime::with_options(options));
+    preload_modules(&mut runtime, &preloads);
+
     let mod_result = runtime.execute_module("dune:environment/test", Some(&script));
 
     match mod_result {
-        Ok(_) => runtime.run_event_loop(),
-        Err(e) => eprintln!("{e:?}"),
+        Ok(_) => unwrap_or_exit(runtime.run_event_loop()),
+        Err(e) => {
+            runtime.write_reports();
+            report_error(&e);
+        }
     };
+
+    std::process::exit(runtime.exit_code());
 }
 
-fn repl_command(globals: &GlobalArgs) {
+fn bench_command(args: &BenchArgs, globals: &GlobalArgs) {
+    // Get the path we need to import JavaScript benchmarks from.
+    let cwd = env::current_dir().unwrap();
+
+    // Try load the requested import-map.
+    let import_map = load_import_map(globals.import_map.as_ref());
+
+    // Get the input path as an absolute location.
+    let bench_path = match args.path.as_ref().unwrap_or(&cwd).absolutize() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{}", generic_error(e.to_string()));
+            std::process::exit(1);
+        }
+    };
+
+    // Load custom .env file if specified.
+    if let Some(path) = globals.env_file.as_ref() {
+        // Try to parse the .env file.
+        if let Err(e) = dotenv::load_env_file(path) {
+            eprintln!("{}: {}", "Error".red().bold(), e);
+            std::process::exit(1);
+        }
+    }
+
+    let filter = match args.filter.as_ref() {
+        Some(value) => format!("new RegExp({})", value),
+        None => "undefined".into(),
+    };
+
+    let preloads = resolve_preloads(globals, import_map.as_ref());
+
+    // Note: See `test_command` on why the entry is passed as an env variable.
+    let bench_path = bench_path.to_string_lossy().to_string();
+    env::set_var("BENCH_ENTRY_PATH", &bench_path);
+
+    // Build JavaScript bench script.
+    let script = format!(
+        "
+        import {{ mainRunner }} from 'bench';
+        mainRunner.json = {};
+        mainRunner.filter = {};
+        await mainRunner.importBenches(process.env.BENCH_ENTRY_PATH);
+        await mainRunner.run();
+    ",
+        args.json, filter,
+    );
+
+    // Build JS runtime options.
+    let options = JsRuntimeOptions {
+        seed: globals.seed.to_owned(),
+        seed_entropy: globals.seed_entropy.unwrap_or_default(),
+        reload: reload_policy(globals),
+        num_threads: globals.thread_pool_size.to_owned(),
+        task_limit: globals.task_limit.to_owned(),
+        test_mode: true,
+        argv: vec![bench_path],
+        import_map,
+        expose_gc: globals.expose_gc.unwrap_or_default(),
+        v8_flags: globals.v8_flags.clone(),
+        jitless: globals.jitless.unwrap_or_default(),
+        disallow_code_generation: globals.disallow_code_generation.unwrap_or_default(),
+        frozen: globals.frozen.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    // Create new JS runtime.
+    let mut runtime = unwrap_or_exit(JsRuntime::with_options(options));
+    preload_modules(&mut runtime, &preloads);
+
+    let mod_result = runtime.execute_module("dune:environment/bench", Some(&script));
+
+    match mod_result {
+        Ok(_) => unwrap_or_exit(runtime.run_event_loop()),
+        Err(e) => {
+            runtime.write_reports();
+            report_error(&e);
+        }
+    };
+
+    std::process::exit(runtime.exit_code());
+}
+
+fn repl_command(args: &ReplArgs, globals: &GlobalArgs) {
     // Build a JS runtime based on CLI arguments.
     if let Some(path) = globals.env_file.as_ref() {
         // Try to parse the .env file.
@@ -387,16 +863,29 @@ fn repl_command(globals: &GlobalArgs) {
 
     let options = JsRuntimeOptions {
         num_threads: globals.thread_pool_size.to_owned(),
+        task_limit: globals.task_limit.to_owned(),
         expose_gc: globals.expose_gc.unwrap_or_default(),
+        v8_flags: globals.v8_flags.clone(),
+        jitless: globals.jitless.unwrap_or_default(),
+        disallow_code_generation: globals.disallow_code_generation.unwrap_or_default(),
         seed: globals.seed.to_owned(),
+        seed_entropy: globals.seed_entropy.unwrap_or_default(),
+        drain_timeout: args.drain_timeout.to_owned(),
         ..Default::default()
     };
 
     // Start REPL.
-    let runtime = JsRuntime::with_options(options);
+    let runtime = unwrap_or_exit(JsRuntime::with_options(options));
     repl::start(runtime);
 }
 
+fn snapshot_command(args: &SnapshotArgs) {
+    if let Err(e) = snapshot::write_snapshot(&args.output) {
+        eprintln!("{:?}", generic_error(e.to_string()));
+        std::process::exit(1);
+    }
+}
+
 fn upgrade_command() {
     match upgrade::run_upgrade() {
         Ok(_) => println!("Upgraded successfully"),
@@ -423,12 +912,17 @@ fn output_bundle(source: &str, output: Option<&PathBuf>) {
 fn bundle_command(args: &BundleArgs, globals: &GlobalArgs) {
     // Try load the requested import-map.
     let import_map = load_import_map(globals.import_map.as_ref());
-    let skip_cache = globals.reload.unwrap_or_default();
+    let reload = reload_policy(globals);
     let minify = args.minify.unwrap_or_default();
 
     let options = bundle::Options {
-        skip_cache,
+        reload,
         minify,
+        mangle: args.mangle,
+        compress: args.compress,
+        keep_names: args.keep_names,
+        target: args.target,
+        define: args.define.clone(),
         import_map,
     };
 
@@ -441,11 +935,16 @@ fn bundle_command(args: &BundleArgs, globals: &GlobalArgs) {
 fn compile_command(args: &CompileArgs, globals: &GlobalArgs) {
     // Try load the requested import-map.
     let import_map = load_import_map(globals.import_map.as_ref());
-    let skip_cache = globals.reload.unwrap_or_default();
+    let reload = reload_policy(globals);
 
     let options = compile::Options {
-        skip_cache,
+        reload,
         minify: true,
+        mangle: args.mangle,
+        compress: args.compress,
+        keep_names: args.keep_names,
+        target: args.target,
+        define: args.define.clone(),
         import_map,
     };
 
@@ -454,17 +953,103 @@ fn compile_command(args: &CompileArgs, globals: &GlobalArgs) {
     }
 }
 
+fn vendor_command(args: &VendorArgs, globals: &GlobalArgs) {
+    // Try load the requested import-map.
+    let import_map = load_import_map(globals.import_map.as_ref());
+    let reload = reload_policy(globals);
+
+    let options = vendor::Options {
+        reload,
+        output: args.output.clone(),
+        import_map,
+    };
+
+    if let Err(e) = vendor::run_vendor(&args.entry, &options) {
+        eprintln!("{:?}", generic_error(e.to_string()));
+    }
+}
+
+fn cache_command(command: &CacheCommand) {
+    let result = match command {
+        CacheCommand::List => cache::run_list(),
+        CacheCommand::Clean(args) => cache::run_clean(&cache::CleanOptions {
+            prefixes: args.url.clone(),
+            older_than: args.older_than,
+            yes: args.yes.unwrap_or_default(),
+        }),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{:?}", generic_error(e.to_string()));
+    }
+}
+
+fn verbosity_level(globals: &GlobalArgs) -> Verbosity {
+    match (globals.quiet, globals.verbose) {
+        (Some(true), _) => Verbosity::Quiet,
+        (_, Some(true)) => Verbosity::Verbose,
+        _ => Verbosity::Normal,
+    }
+}
+
+fn info_command(args: &InfoArgs, globals: &GlobalArgs) {
+    // Try load the requested import-map.
+    let import_map = load_import_map(globals.import_map.as_ref());
+    let reload = reload_policy(globals);
+
+    let options = info::Options {
+        reload,
+        import_map,
+        json: args.json.unwrap_or_default(),
+    };
+
+    if let Err(e) = info::run_info(&args.entry, &options) {
+        eprintln!("{:?}", generic_error(e.to_string()));
+    }
+}
+
+/// Forces colored output on (or off) for both Rust and JavaScript code.
+///
+/// Note: Without a flag the `colored` crate decides, which already checks for
+/// a terminal and the `NO_COLOR` env variable.
+fn set_color_override(globals: &GlobalArgs) {
+    let color = match globals.no_color.unwrap_or_default() {
+        true => Some(ColorChoice::Never),
+        false => globals.color,
+    };
+
+    match color {
+        Some(ColorChoice::Always) => colored::control::set_override(true),
+        Some(ColorChoice::Never) => colored::control::set_override(false),
+        Some(ColorChoice::Auto) | None => {}
+    }
+}
+
 pub fn process_cli_arguments() {
     let cli = Cli::parse();
     let globals = &cli.global_args;
 
+    set_color_override(globals);
+    set_error_format(globals.error_format.unwrap_or_default());
+    set_verbosity(verbosity_level(globals));
+
+    // Relocate the dune directory if requested.
+    if let Some(path) = globals.cache_dir.clone() {
+        dune_dir::set_root(path);
+    }
+
     match cli.command {
         Some(Command::Run(args)) => run_command(&args, globals),
         Some(Command::Bundle(args)) => bundle_command(&args, globals),
         Some(Command::Compile(args)) => compile_command(&args, globals),
+        Some(Command::Vendor(args)) => vendor_command(&args, globals),
+        Some(Command::Info(args)) => info_command(&args, globals),
+        Some(Command::Cache(command)) => cache_command(&command),
         Some(Command::Test(args)) => test_command(&args, globals),
-        Some(Command::Repl) => repl_command(globals),
+        Some(Command::Bench(args)) => bench_command(&args, globals),
+        Some(Command::Repl(args)) => repl_command(&args, globals),
         Some(Command::Upgrade) => upgrade_command(),
-        None => repl_command(globals),
+        Some(Command::Snapshot(args)) => snapshot_command(&args),
+        None => repl_command(&ReplArgs::default(), globals),
     };
 }
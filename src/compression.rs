@@ -0,0 +1,502 @@
+// Compression
+//
+// This module provides the native (streaming) codecs behind the WHATWG
+// CompressionStream and DecompressionStream classes.
+//
+// https://compression.spec.whatwg.org/
+
+use crate::bindings::external_references;
+use crate::bindings::get_internal_ref;
+use crate::bindings::set_function_to;
+use crate::bindings::set_internal_ref_with_finalizer;
+use crate::bindings::throw_type_error;
+use crate::limiter::TaskClass;
+use crate::runtime::JsFuture;
+use crate::runtime::JsRuntime;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use dune_event_loop::LoopHandle;
+use dune_event_loop::TaskResult;
+use std::io::Write;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "createCompressor", create_compressor);
+    set_function_to(scope, target, "createDecompressor", create_decompressor);
+    set_function_to(scope, target, "write", write);
+    set_function_to(scope, target, "finish", finish);
+    set_function_to(scope, target, "close", close);
+    set_function_to(scope, target, "compress", compress);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        create_compressor,
+        create_decompressor,
+        write,
+        finish,
+        close,
+        compress
+    ]
+}
+
+/// Creates a compressor for the given format.
+fn create_compressor(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the format and the (optional) compression level.
+    let name = args.get(0).to_rust_string_lossy(scope);
+    let level = get_level(scope, args.get(1));
+
+    let Some(format) = Format::from_name(&name) else {
+        throw_type_error(
+            scope,
+            &format!("Unsupported compression format: \"{name}\""),
+        );
+        return;
+    };
+
+    let wrapper = wrap_codec(scope, Codec::encoder(format, level));
+    rv.set(wrapper.into());
+}
+
+/// Creates a decompressor for the given format.
+fn create_decompressor(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the format.
+    let name = args.get(0).to_rust_string_lossy(scope);
+
+    let Some(format) = Format::from_name(&name) else {
+        throw_type_error(
+            scope,
+            &format!("Unsupported compression format: \"{name}\""),
+        );
+        return;
+    };
+
+    let wrapper = wrap_codec(scope, Codec::decoder(format));
+    rv.set(wrapper.into());
+}
+
+/// Feeds a chunk of bytes to a codec, returning the produced output.
+fn write(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the codec wrapper and the data.
+    let wrapper = args.get(0).to_object(scope).unwrap();
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    let state = get_internal_ref::<Option<Codec>>(scope, wrapper, 0);
+    let result = match state.as_mut() {
+        Some(codec) => codec.write(&buffer),
+        None => Err(anyhow!("The stream has already been finished.")),
+    };
+
+    match result {
+        Ok(output) => rv.set(to_uint8_array(scope, output).into()),
+        Err(e) => {
+            // Note: A codec that failed can't be used anymore.
+            state.take();
+            throw_type_error(scope, &e.to_string());
+        }
+    }
+}
+
+/// Flushes a codec, returning the remaining output.
+fn finish(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the codec wrapper.
+    let wrapper = args.get(0).to_object(scope).unwrap();
+    let state = get_internal_ref::<Option<Codec>>(scope, wrapper, 0);
+
+    let result = match state.take() {
+        Some(codec) => codec.finish(),
+        None => Err(anyhow!("The stream has already been finished.")),
+    };
+
+    match result {
+        Ok(output) => rv.set(to_uint8_array(scope, output).into()),
+        Err(e) => throw_type_error(scope, &e.to_string()),
+    }
+}
+
+/// Drops a codec (e.g. when its stream is cancelled) without flushing it.
+fn close(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get the codec wrapper.
+    let wrapper = args.get(0).to_object(scope).unwrap();
+    get_internal_ref::<Option<Codec>>(scope, wrapper, 0).take();
+}
+
+/// Describes what will run after the async compression completes.
+struct CompressFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for CompressFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        match self.maybe_result.take().unwrap() {
+            Ok(output) => {
+                let output = to_uint8_array(scope, output);
+                self.promise.open(scope).resolve(scope, output.into());
+            }
+            Err(e) => {
+                let message = v8::String::new(scope, &e.to_string()).unwrap();
+                let exception = v8::Exception::type_error(scope, message);
+                self.promise.open(scope).reject(scope, exception);
+            }
+        };
+    }
+}
+
+/// Compresses a whole buffer on the thread-pool.
+fn compress(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the data, the format and the (optional) compression level.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(0).try_into().unwrap();
+    let name = args.get(1).to_rust_string_lossy(scope);
+    let level = get_level(scope, args.get(2));
+
+    let Some(format) = Format::from_name(&name) else {
+        throw_type_error(
+            scope,
+            &format!("Unsupported compression format: \"{name}\""),
+        );
+        return;
+    };
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || Some(compress_op(format, level, &buffer));
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = CompressFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Compression, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Compresses a whole buffer at once.
+fn compress_op(format: Format, level: Option<u32>, data: &[u8]) -> Result<Vec<u8>> {
+    let mut codec = Codec::encoder(format, level);
+    let mut output = codec.write(data)?;
+    output.extend(codec.finish()?);
+    Ok(output)
+}
+
+/// Gets the (optional) compression level argument.
+fn get_level(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> Option<u32> {
+    match value.is_null_or_undefined() {
+        true => None,
+        false => value.uint32_value(scope),
+    }
+}
+
+/// Wraps a codec into a JavaScript object.
+fn wrap_codec<'s>(scope: &mut v8::HandleScope<'s>, codec: Codec) -> v8::Local<'s, v8::Object> {
+    let wrapper = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    wrapper.set_internal_field_count(1);
+
+    let wrapper = wrapper.new_instance(scope).unwrap();
+    // Note: Streams that are abandoned (e.g. a partially read fetch body) never
+    // finish their codec, so it's dropped once the wrapper is garbage collected.
+    set_internal_ref_with_finalizer(scope, wrapper, 0, Some(codec));
+
+    wrapper
+}
+
+/// Hands a byte vector over to v8 as a Uint8Array (without copying).
+fn to_uint8_array<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    bytes: Vec<u8>,
+) -> v8::Local<'s, v8::Uint8Array> {
+    let length = bytes.len();
+    let store = bytes.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+    v8::Uint8Array::new(scope, buffer, 0, length).unwrap()
+}
+
+/// The supported compression formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Gzip,
+    Deflate,
+    DeflateRaw,
+    Brotli,
+}
+
+impl Format {
+    /// Parses a format name (as used by the WHATWG compression streams).
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gzip" => Some(Format::Gzip),
+            "deflate" => Some(Format::Deflate),
+            "deflate-raw" => Some(Format::DeflateRaw),
+            "br" | "brotli" => Some(Format::Brotli),
+            _ => None,
+        }
+    }
+}
+
+/// A streaming (de)compressor writing its output into a buffer.
+enum Codec {
+    GzipEncoder(flate2::write::GzEncoder<Vec<u8>>),
+    GzipDecoder(flate2::write::GzDecoder<Vec<u8>>),
+    DeflateEncoder(flate2::write::ZlibEncoder<Vec<u8>>),
+    DeflateDecoder(Inflater),
+    DeflateRawEncoder(flate2::write::DeflateEncoder<Vec<u8>>),
+    DeflateRawDecoder(Inflater),
+    BrotliEncoder(Box<brotli::CompressorWriter<Vec<u8>>>),
+    BrotliDecoder(Box<brotli::DecompressorWriter<Vec<u8>>>),
+}
+
+/// Size of the internal buffer used by the brotli codecs.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+impl Codec {
+    /// Creates a compressor for the given format.
+    fn encoder(format: Format, level: Option<u32>) -> Self {
+        use flate2::Compression;
+        let flate_level = level.map_or(Compression::default(), |l| Compression::new(l.min(9)));
+        match format {
+            Format::Gzip => Codec::GzipEncoder(flate2::write::GzEncoder::new(vec![], flate_level)),
+            Format::Deflate => {
+                Codec::DeflateEncoder(flate2::write::ZlibEncoder::new(vec![], flate_level))
+            }
+            Format::DeflateRaw => {
+                Codec::DeflateRawEncoder(flate2::write::DeflateEncoder::new(vec![], flate_level))
+            }
+            Format::Brotli => {
+                let quality = level.unwrap_or(6).min(11);
+                let writer = brotli::CompressorWriter::new(vec![], BROTLI_BUFFER_SIZE, quality, 22);
+                Codec::BrotliEncoder(Box::new(writer))
+            }
+        }
+    }
+
+    /// Creates a decompressor for the given format.
+    fn decoder(format: Format) -> Self {
+        match format {
+            Format::Gzip => Codec::GzipDecoder(flate2::write::GzDecoder::new(vec![])),
+            Format::Deflate => Codec::DeflateDecoder(Inflater::new(true)),
+            Format::DeflateRaw => Codec::DeflateRawDecoder(Inflater::new(false)),
+            Format::Brotli => {
+                let writer = brotli::DecompressorWriter::new(vec![], BROTLI_BUFFER_SIZE);
+                Codec::BrotliDecoder(Box::new(writer))
+            }
+        }
+    }
+
+    /// Feeds a chunk to the codec, returning the output produced so far.
+    fn write(&mut self, data: &[u8]) -> Result<Vec<u8>> {
+        macro_rules! write_and_take {
+            ($writer:expr) => {{
+                $writer.write_all(data)?;
+                Ok(std::mem::take($writer.get_mut()))
+            }};
+        }
+        match self {
+            Codec::GzipEncoder(w) => write_and_take!(w),
+            Codec::GzipDecoder(w) => write_and_take!(w),
+            Codec::DeflateEncoder(w) => write_and_take!(w),
+            Codec::DeflateDecoder(inflater) => inflater.write(data),
+            Codec::DeflateRawEncoder(w) => write_and_take!(w),
+            Codec::DeflateRawDecoder(inflater) => inflater.write(data),
+            Codec::BrotliEncoder(w) => write_and_take!(w),
+            Codec::BrotliDecoder(w) => write_and_take!(w),
+        }
+    }
+
+    /// Flushes the codec, returning the remaining output.
+    fn finish(self) -> Result<Vec<u8>> {
+        let output = match self {
+            Codec::GzipEncoder(w) => w.finish()?,
+            Codec::GzipDecoder(w) => w.finish()?,
+            Codec::DeflateEncoder(w) => w.finish()?,
+            Codec::DeflateDecoder(inflater) => inflater.finish()?,
+            Codec::DeflateRawEncoder(w) => w.finish()?,
+            Codec::DeflateRawDecoder(inflater) => inflater.finish()?,
+            Codec::BrotliEncoder(w) => w.into_inner(),
+            Codec::BrotliDecoder(mut w) => {
+                if w.close().is_err() {
+                    bail!("The compressed data is incomplete.");
+                }
+                match w.into_inner() {
+                    Ok(output) => output,
+                    Err(_) => bail!("The compressed data is incomplete."),
+                }
+            }
+        };
+        Ok(output)
+    }
+}
+
+/// A zlib (or raw deflate) decompressor that knows when the stream has ended.
+///
+/// Note: Unlike gzip, these formats have no length trailer, so a truncated stream
+/// can only be detected by checking that the final block was reached.
+struct Inflater {
+    inner: flate2::Decompress,
+    ended: bool,
+}
+
+/// Size of the chunks the inflated output is written in.
+const INFLATE_CHUNK_SIZE: usize = 32 * 1024;
+
+impl Inflater {
+    fn new(zlib_header: bool) -> Self {
+        Inflater {
+            inner: flate2::Decompress::new(zlib_header),
+            ended: false,
+        }
+    }
+
+    fn write(&mut self, mut data: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::with_capacity(INFLATE_CHUNK_SIZE);
+
+        // Note: Any data after the end of the stream is ignored.
+        while !self.ended {
+            output.reserve(INFLATE_CHUNK_SIZE);
+
+            let total_in = self.inner.total_in();
+            let status =
+                self.inner
+                    .decompress_vec(data, &mut output, flate2::FlushDecompress::None)?;
+
+            data = &data[(self.inner.total_in() - total_in) as usize..];
+            self.ended = status == flate2::Status::StreamEnd;
+
+            // Stop once all the input is consumed (and no output is pending).
+            if data.is_empty() && output.len() < output.capacity() {
+                break;
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        if !self.ended {
+            bail!("The compressed data is incomplete.");
+        }
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(format: Format) {
+        let input = "Hello World! ".repeat(1000);
+        let mut encoder = Codec::encoder(format, None);
+        let mut compressed = vec![];
+        for chunk in input.as_bytes().chunks(100) {
+            compressed.extend(encoder.write(chunk).unwrap());
+        }
+        compressed.extend(encoder.finish().unwrap());
+        assert!(compressed.len() < input.len());
+
+        let mut decoder = Codec::decoder(format);
+        let mut output = vec![];
+        for chunk in compressed.chunks(7) {
+            output.extend(decoder.write(chunk).unwrap());
+        }
+        output.extend(decoder.finish().unwrap());
+        assert_eq!(output, input.as_bytes());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        roundtrip(Format::Gzip);
+        roundtrip(Format::Deflate);
+        roundtrip(Format::DeflateRaw);
+        roundtrip(Format::Brotli);
+    }
+
+    #[test]
+    fn test_compress_op() {
+        let input = "Hello World! ".repeat(1000);
+        let compressed = compress_op(Format::Gzip, Some(9), input.as_bytes()).unwrap();
+
+        let mut decoder = Codec::decoder(Format::Gzip);
+        let mut output = decoder.write(&compressed).unwrap();
+        output.extend(decoder.finish().unwrap());
+        assert_eq!(output, input.as_bytes());
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        for format in [Format::Gzip, Format::Deflate, Format::Brotli] {
+            let mut encoder = Codec::encoder(format, None);
+            let mut compressed = encoder.write(b"Hello World").unwrap();
+            compressed.extend(encoder.finish().unwrap());
+            compressed.truncate(compressed.len() - 4);
+            let mut decoder = Codec::decoder(format);
+            let result = decoder.write(&compressed).and_then(|_| decoder.finish());
+            assert!(result.is_err(), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut decoder = Codec::decoder(Format::Gzip);
+        let result = decoder
+            .write(b"not gzip data")
+            .and_then(|_| decoder.finish());
+        assert!(result.is_err());
+        assert_eq!(Format::from_name("br"), Some(Format::Brotli));
+        assert_eq!(Format::from_name("zstd"), None);
+    }
+}
@@ -0,0 +1,296 @@
+// HTTP Cookies
+//
+// This module provides the native helpers behind the cookie utilities of the
+// `http` module, namely parsing a `Cookie` header and serializing a `Set-Cookie`
+// one (following the grammar of RFC 6265).
+//
+// https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+
+use crate::bindings::external_references;
+use crate::bindings::set_constant_to;
+use crate::bindings::set_function_to;
+use crate::bindings::throw_type_error;
+use anyhow::bail;
+use anyhow::Result;
+use percent_encoding::percent_decode_str;
+use percent_encoding::utf8_percent_encode;
+use percent_encoding::AsciiSet;
+use percent_encoding::NON_ALPHANUMERIC;
+
+/// Characters escaped in cookie values (same as JavaScript's `encodeURIComponent`).
+const COOKIE_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "parse", parse);
+    set_function_to(scope, target, "serialize", serialize);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![parse, serialize]
+}
+
+/// The attributes of a `Set-Cookie` header.
+#[derive(Debug, Default)]
+struct CookieOptions {
+    max_age: Option<i64>,
+    expires: Option<String>,
+    domain: Option<String>,
+    path: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<String>,
+}
+
+/// Parses a `Cookie` header into a name/value map.
+fn parse(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the cookie header's value.
+    let header = args.get(0).to_rust_string_lossy(scope);
+    let target = v8::Object::new(scope);
+
+    for (name, value) in parse_cookie(&header) {
+        let value = v8::String::new(scope, &value).unwrap();
+        set_constant_to(scope, target, &name, value.into());
+    }
+
+    rv.set(target.into());
+}
+
+/// Serializes a cookie (and its attributes) into a `Set-Cookie` header value.
+fn serialize(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the cookie's name and value.
+    let name = args.get(0).to_rust_string_lossy(scope);
+    let value = args.get(1).to_rust_string_lossy(scope);
+    let options = args.get(2).to_object(scope).unwrap();
+
+    let mut get_option = |key: &str| {
+        let key = v8::String::new(scope, key).unwrap();
+        options
+            .get(scope, key.into())
+            .filter(|value| !value.is_null_or_undefined())
+    };
+
+    let max_age = get_option("maxAge");
+    let expires = get_option("expires");
+    let domain = get_option("domain");
+    let path = get_option("path");
+    let secure = get_option("secure");
+    let http_only = get_option("httpOnly");
+    let same_site = get_option("sameSite");
+
+    let options = CookieOptions {
+        max_age: max_age.and_then(|value| value.integer_value(scope)),
+        expires: expires.map(|value| value.to_rust_string_lossy(scope)),
+        domain: domain.map(|value| value.to_rust_string_lossy(scope)),
+        path: path.map(|value| value.to_rust_string_lossy(scope)),
+        secure: secure.is_some_and(|value| value.boolean_value(scope)),
+        http_only: http_only.is_some_and(|value| value.boolean_value(scope)),
+        same_site: same_site.map(|value| value.to_rust_string_lossy(scope)),
+    };
+
+    match serialize_cookie(&name, &value, &options) {
+        Ok(cookie) => rv.set(v8::String::new(scope, &cookie).unwrap().into()),
+        Err(e) => throw_type_error(scope, &e.to_string()),
+    }
+}
+
+/// Extracts the name/value pairs of a `Cookie` header.
+fn parse_cookie(header: &str) -> Vec<(String, String)> {
+    let mut cookies: Vec<(String, String)> = vec![];
+
+    for pair in header.split(';') {
+        // Note: Pairs without an equal sign are not valid cookies.
+        let Some((name, value)) = pair.split_once('=') else {
+            continue;
+        };
+
+        let name = name.trim();
+        let value = value.trim();
+
+        // Only the first occurrence of a name is kept (as the most specific).
+        if name.is_empty() || cookies.iter().any(|(key, _)| key == name) {
+            continue;
+        }
+
+        // Values may optionally be wrapped in double quotes.
+        let value = match value.len() > 1 && value.starts_with('"') && value.ends_with('"') {
+            true => &value[1..value.len() - 1],
+            false => value,
+        };
+
+        // Values that are not properly encoded are kept as they are.
+        let value = match percent_decode_str(value).decode_utf8() {
+            Ok(decoded) => decoded.to_string(),
+            Err(_) => value.to_owned(),
+        };
+
+        cookies.push((name.to_owned(), value));
+    }
+
+    cookies
+}
+
+/// Builds the value of a `Set-Cookie` header.
+fn serialize_cookie(name: &str, value: &str, options: &CookieOptions) -> Result<String> {
+    if !is_token(name) {
+        bail!("Invalid cookie name: \"{name}\"");
+    }
+
+    let value = utf8_percent_encode(value, COOKIE_VALUE).to_string();
+    let mut cookie = format!("{name}={value}");
+
+    if let Some(max_age) = options.max_age {
+        cookie.push_str(&format!("; Max-Age={max_age}"));
+    }
+
+    if let Some(expires) = options.expires.as_deref() {
+        if !is_attribute_value(expires) {
+            bail!("Invalid cookie expires: \"{expires}\"");
+        }
+        cookie.push_str(&format!("; Expires={expires}"));
+    }
+
+    if let Some(domain) = options.domain.as_deref() {
+        let valid = !domain.is_empty()
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        if !valid {
+            bail!("Invalid cookie domain: \"{domain}\"");
+        }
+        cookie.push_str(&format!("; Domain={domain}"));
+    }
+
+    if let Some(path) = options.path.as_deref() {
+        if !is_attribute_value(path) {
+            bail!("Invalid cookie path: \"{path}\"");
+        }
+        cookie.push_str(&format!("; Path={path}"));
+    }
+
+    if options.http_only {
+        cookie.push_str("; HttpOnly");
+    }
+
+    if options.secure {
+        cookie.push_str("; Secure");
+    }
+
+    if let Some(same_site) = options.same_site.as_deref() {
+        let same_site = match same_site.to_ascii_lowercase().as_str() {
+            "strict" | "true" => "Strict",
+            "lax" => "Lax",
+            "none" => "None",
+            _ => bail!("Invalid cookie sameSite: \"{same_site}\""),
+        };
+        cookie.push_str(&format!("; SameSite={same_site}"));
+    }
+
+    Ok(cookie)
+}
+
+/// Checks if a string is a valid token (RFC 7230 section 3.2.6).
+fn is_token(value: &str) -> bool {
+    let separators = "()<>@,;:\\\"/[]?={} \t";
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !separators.contains(c))
+}
+
+/// Checks if a string can be used as an attribute value (no CTLs or `;`).
+fn is_attribute_value(value: &str) -> bool {
+    value.chars().all(|c| !c.is_ascii_control() && c != ';')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cookie() {
+        let cookies = parse_cookie("foo=bar; equation=E%3Dmc%5E2;quoted=\"a b\"");
+        assert_eq!(
+            cookies,
+            vec![
+                ("foo".into(), "bar".into()),
+                ("equation".into(), "E=mc^2".into()),
+                ("quoted".into(), "a b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_cookie_edge_cases() {
+        // Duplicates, invalid pairs and invalid encodings.
+        let cookies = parse_cookie("a=1; a=2; invalid; b=%E0%A4%A; =empty; c=");
+        assert_eq!(
+            cookies,
+            vec![
+                ("a".into(), "1".into()),
+                ("b".into(), "%E0%A4%A".into()),
+                ("c".into(), "".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_cookie() {
+        let options = CookieOptions {
+            max_age: Some(3600),
+            domain: Some("example.com".into()),
+            path: Some("/".into()),
+            secure: true,
+            http_only: true,
+            same_site: Some("lax".into()),
+            ..Default::default()
+        };
+
+        let cookie = serialize_cookie("session", "a b;c", &options).unwrap();
+        let expected = "session=a%20b%3Bc; Max-Age=3600; Domain=example.com; Path=/; HttpOnly; Secure; SameSite=Lax";
+
+        assert_eq!(cookie, expected);
+    }
+
+    #[test]
+    fn test_serialize_invalid_cookie() {
+        let options = CookieOptions::default();
+        assert!(serialize_cookie("in valid", "value", &options).is_err());
+        assert!(serialize_cookie("", "value", &options).is_err());
+
+        let options = CookieOptions {
+            path: Some("/; Secure".into()),
+            ..Default::default()
+        };
+        assert!(serialize_cookie("name", "value", &options).is_err());
+
+        let options = CookieOptions {
+            same_site: Some("sometimes".into()),
+            ..Default::default()
+        };
+        assert!(serialize_cookie("name", "value", &options).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+// Crypto
+//
+// This module provides the random number generator behind the Web Crypto
+// getRandomValues and randomUUID methods.
+//
+// https://w3c.github.io/webcrypto/#crypto-interface
+
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+use crate::bindings::throw_type_error;
+use crate::JsRuntime;
+use rand::rngs::OsRng;
+use rand::rngs::StdRng;
+use rand::RngCore;
+use rand::SeedableRng;
+
+/// The max number of bytes that can be requested at once.
+const MAX_RANDOM_BYTES: u32 = 65536;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "randomBytes", random_bytes);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![random_bytes]
+}
+
+/// The source of the random values handed to JavaScript.
+pub enum Entropy {
+    /// The operating system's CSPRNG.
+    Os,
+    /// A seeded PRNG (reproducible, thus insecure) used only for tests.
+    Seeded(Box<StdRng>),
+}
+
+impl Entropy {
+    /// Creates an entropy source, seeded only when a seed is provided.
+    pub fn new(seed: Option<i64>) -> Self {
+        match seed {
+            Some(seed) => Entropy::Seeded(Box::new(StdRng::seed_from_u64(seed as u64))),
+            None => Entropy::Os,
+        }
+    }
+
+    /// Fills the buffer with random bytes.
+    pub fn fill(&mut self, buffer: &mut [u8]) {
+        match self {
+            Entropy::Os => OsRng.fill_bytes(buffer),
+            Entropy::Seeded(rng) => rng.fill_bytes(buffer),
+        }
+    }
+}
+
+/// Returns a Uint8Array filled with random bytes.
+fn random_bytes(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the number of requested bytes.
+    let size = args.get(0).uint32_value(scope).unwrap_or_default();
+
+    if size > MAX_RANDOM_BYTES {
+        throw_type_error(
+            scope,
+            &format!("The requested length exceeds {MAX_RANDOM_BYTES} bytes."),
+        );
+        return;
+    }
+
+    let state_rc = JsRuntime::state(scope);
+    let mut bytes = vec![0; size as usize];
+
+    state_rc.borrow_mut().entropy.fill(&mut bytes);
+
+    let store = bytes.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+    let array = v8::Uint8Array::new(scope, buffer, 0, size as usize).unwrap();
+
+    rv.set(array.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_entropy_is_reproducible() {
+        let mut first = Entropy::new(Some(42));
+        let mut second = Entropy::new(Some(42));
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+
+        first.fill(&mut a);
+        second.fill(&mut b);
+        assert_eq!(a, b);
+
+        // A different seed produces different values.
+        Entropy::new(Some(7)).fill(&mut b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_os_entropy() {
+        let mut entropy = Entropy::new(None);
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+
+        entropy.fill(&mut a);
+        entropy.fill(&mut b);
+        assert_ne!(a, b);
+    }
+}
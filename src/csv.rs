@@ -0,0 +1,336 @@
+// CSV
+//
+// This module provides an incremental CSV parser (so large files can be read
+// as a stream) and a writer, behind the `csv` core module.
+//
+// https://www.rfc-editor.org/rfc/rfc4180
+
+use crate::bindings::external_references;
+use crate::bindings::get_internal_ref;
+use crate::bindings::set_function_to;
+use crate::bindings::set_internal_ref;
+use crate::bindings::throw_type_error;
+use anyhow::Result;
+use csv_core::ReadRecordResult;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "createParser", create_parser);
+    set_function_to(scope, target, "parse", parse);
+    set_function_to(scope, target, "finish", finish);
+    set_function_to(scope, target, "stringify", stringify);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![create_parser, parse, finish, stringify]
+}
+
+/// Creates a new (incremental) CSV parser.
+fn create_parser(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the delimiter and the quote characters.
+    let Some((delimiter, quote)) = get_dialect(scope, &args, 0) else {
+        return;
+    };
+
+    let wrapper = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    wrapper.set_internal_field_count(1);
+
+    let wrapper = wrapper.new_instance(scope).unwrap();
+    set_internal_ref(scope, wrapper, 0, Some(Parser::new(delimiter, quote)));
+
+    rv.set(wrapper.into());
+}
+
+/// Feeds a chunk (string or bytes) to the parser, returning complete records.
+fn parse(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the parser wrapper and the data.
+    let wrapper = args.get(0).to_object(scope).unwrap();
+    let data = args.get(1);
+
+    let chunk = match v8::Local::<v8::ArrayBufferView>::try_from(data) {
+        Ok(view) => {
+            let mut buffer = vec![0; view.byte_length()];
+            view.copy_contents(&mut buffer);
+            buffer
+        }
+        Err(_) => data.to_rust_string_lossy(scope).into_bytes(),
+    };
+
+    let state = get_internal_ref::<Option<Parser>>(scope, wrapper, 0);
+    let records = match state.as_mut() {
+        Some(parser) => parser.feed(&chunk),
+        None => {
+            throw_type_error(scope, "The parser has already been finished.");
+            return;
+        }
+    };
+
+    rv.set(to_records_array(scope, records).into());
+}
+
+/// Signals the end of input, returning the last record (if any).
+fn finish(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the parser wrapper.
+    let wrapper = args.get(0).to_object(scope).unwrap();
+    let state = get_internal_ref::<Option<Parser>>(scope, wrapper, 0);
+
+    let records = match state.take() {
+        Some(mut parser) => parser.finish(),
+        None => {
+            throw_type_error(scope, "The parser has already been finished.");
+            return;
+        }
+    };
+
+    rv.set(to_records_array(scope, records).into());
+}
+
+/// Serializes a list of records (arrays of strings) to CSV text.
+fn stringify(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the records and the dialect.
+    let Ok(rows) = v8::Local::<v8::Array>::try_from(args.get(0)) else {
+        throw_type_error(scope, "The records must be an array.");
+        return;
+    };
+
+    let Some((delimiter, quote)) = get_dialect(scope, &args, 1) else {
+        return;
+    };
+
+    let mut records = Vec::with_capacity(rows.length() as usize);
+
+    for i in 0..rows.length() {
+        let row = rows.get_index(scope, i).unwrap();
+        let Ok(row) = v8::Local::<v8::Array>::try_from(row) else {
+            throw_type_error(scope, "Every record must be an array.");
+            return;
+        };
+
+        let mut record = Vec::with_capacity(row.length() as usize);
+        for j in 0..row.length() {
+            let field = row.get_index(scope, j).unwrap();
+            record.push(field.to_rust_string_lossy(scope));
+        }
+
+        records.push(record);
+    }
+
+    match write_records(&records, delimiter, quote) {
+        Ok(text) => rv.set(v8::String::new(scope, &text).unwrap().into()),
+        Err(e) => throw_type_error(scope, &e.to_string()),
+    }
+}
+
+/// Reads the (single byte) delimiter and quote characters from the arguments.
+fn get_dialect(
+    scope: &mut v8::HandleScope,
+    args: &v8::FunctionCallbackArguments,
+    index: i32,
+) -> Option<(u8, u8)> {
+    let delimiter = args.get(index).to_rust_string_lossy(scope);
+    let quote = args.get(index + 1).to_rust_string_lossy(scope);
+
+    match (delimiter.as_bytes(), quote.as_bytes()) {
+        ([delimiter], [quote]) if delimiter != quote => Some((*delimiter, *quote)),
+        _ => {
+            throw_type_error(
+                scope,
+                "The delimiter and quote must be different single-byte characters.",
+            );
+            None
+        }
+    }
+}
+
+/// Creates a JavaScript array (of string arrays) from the parsed records.
+fn to_records_array<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    records: Vec<Vec<String>>,
+) -> v8::Local<'s, v8::Array> {
+    let records: Vec<v8::Local<v8::Value>> = records
+        .iter()
+        .map(|record| {
+            let fields: Vec<v8::Local<v8::Value>> = record
+                .iter()
+                .map(|field| v8::String::new(scope, field).unwrap().into())
+                .collect();
+
+            v8::Array::new_with_elements(scope, &fields).into()
+        })
+        .collect();
+
+    v8::Array::new_with_elements(scope, &records)
+}
+
+/// A CSV parser that can be fed with arbitrary chunks of input.
+///
+/// Note: Records can span multiple chunks (e.g. quoted fields with embedded
+/// newlines), so partially read records are kept until they are complete.
+struct Parser {
+    reader: csv_core::Reader,
+    output: Vec<u8>,
+    ends: Vec<usize>,
+    output_len: usize,
+    ends_len: usize,
+}
+
+impl Parser {
+    fn new(delimiter: u8, quote: u8) -> Self {
+        let reader = csv_core::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .quote(quote)
+            .build();
+
+        Parser {
+            reader,
+            output: vec![0; 1024],
+            ends: vec![0; 32],
+            output_len: 0,
+            ends_len: 0,
+        }
+    }
+
+    /// Parses a chunk of input, returning the records completed by it.
+    fn feed(&mut self, chunk: &[u8]) -> Vec<Vec<String>> {
+        // Note: An empty input signals the end of data to the reader.
+        match chunk.is_empty() {
+            true => vec![],
+            false => self.read(chunk),
+        }
+    }
+
+    /// Signals the end of input, returning the last (unterminated) record.
+    fn finish(&mut self) -> Vec<Vec<String>> {
+        self.read(&[])
+    }
+
+    fn read(&mut self, mut input: &[u8]) -> Vec<Vec<String>> {
+        let mut records = vec![];
+        loop {
+            let (result, read, written, ends) = self.reader.read_record(
+                input,
+                &mut self.output[self.output_len..],
+                &mut self.ends[self.ends_len..],
+            );
+
+            input = &input[read..];
+            self.output_len += written;
+            self.ends_len += ends;
+
+            match result {
+                ReadRecordResult::InputEmpty => break,
+                ReadRecordResult::OutputFull => self.output.resize(self.output.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len() * 2, 0),
+                ReadRecordResult::Record => records.push(self.take_record()),
+                ReadRecordResult::End => break,
+            }
+        }
+        records
+    }
+
+    /// Splits the buffered output into the fields of the current record.
+    fn take_record(&mut self) -> Vec<String> {
+        let mut start = 0;
+        let record = self.ends[..self.ends_len]
+            .iter()
+            .map(|&end| {
+                let field = String::from_utf8_lossy(&self.output[start..end]).into_owned();
+                start = end;
+                field
+            })
+            .collect();
+
+        self.output_len = 0;
+        self.ends_len = 0;
+        record
+    }
+}
+
+/// Writes the records as CSV text (quoting fields only when needed).
+fn write_records(records: &[Vec<String>], delimiter: u8, quote: u8) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .quote(quote)
+        .flexible(true)
+        .from_writer(vec![]);
+
+    for record in records {
+        writer.write_record(record)?;
+    }
+
+    let bytes = writer.into_inner()?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_chunks(chunks: &[&str]) -> Vec<Vec<String>> {
+        let mut parser = Parser::new(b',', b'"');
+        let mut records = vec![];
+        for chunk in chunks {
+            records.extend(parser.feed(chunk.as_bytes()));
+        }
+        records.extend(parser.finish());
+        records
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let records = parse_chunks(&["name,age\nalice,30\n", "bob,41"]);
+        assert_eq!(
+            records,
+            vec![vec!["name", "age"], vec!["alice", "30"], vec!["bob", "41"],]
+        );
+    }
+
+    #[test]
+    fn test_parse_quoted_fields_across_chunks() {
+        let records = parse_chunks(&["id,note\n1,\"multi\nli", "ne, \"\"quoted\"\"\"\n2,x\n"]);
+        assert_eq!(
+            records,
+            vec![
+                vec!["id", "note"],
+                vec!["1", "multi\nline, \"quoted\""],
+                vec!["2", "x"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_records() {
+        let records = vec![
+            vec!["a".to_string(), "b,c".to_string()],
+            vec!["line\nbreak".to_string(), "say \"hi\"".to_string()],
+        ];
+        assert_eq!(
+            write_records(&records, b',', b'"').unwrap(),
+            "a,\"b,c\"\n\"line\nbreak\",\"say \"\"hi\"\"\"\n"
+        );
+    }
+}
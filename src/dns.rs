@@ -1,6 +1,9 @@
+use crate::bindings::decode_result;
+use crate::bindings::external_references;
 use crate::bindings::set_exception_code;
 use crate::bindings::set_function_to;
 use crate::bindings::set_property_to;
+use crate::limiter::TaskClass;
 use crate::runtime::JsFuture;
 use crate::runtime::JsRuntime;
 use anyhow::Result;
@@ -19,6 +22,11 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![dns_lookup]
+}
+
 /// Describes what will run after the async dns_lookup completes.
 struct DnsLookupFuture {
     promise: v8::Global<v8::PromiseResolver>,
@@ -27,8 +35,9 @@ struct DnsLookupFuture {
 
 impl JsFuture for DnsLookupFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
-        // Extract the result.
+        // Extract the result and deserialize it.
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<Vec<(String, String)>>);
 
         // Handle when something goes wrong on the DNS lookup.
         if let Err(e) = result {
@@ -40,9 +49,8 @@ impl JsFuture for DnsLookupFuture {
             return;
         }
 
-        // Otherwise, get the result and deserialize it.
+        // Otherwise, get the deserialized result.
         let result = result.unwrap();
-        let result: Vec<(String, String)> = bincode::deserialize(&result).unwrap();
 
         let ips: Vec<v8::Local<v8::Value>> = result
             .iter()
@@ -106,7 +114,9 @@ fn dns_lookup(
         }
     };
 
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Dns, task, Some(task_cb));
 
     rv.set(promise.into());
 }
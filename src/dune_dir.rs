@@ -0,0 +1,53 @@
+// Dune Directory
+//
+// Resolves the directory where dune keeps its persistent files (cached URL
+// imports, the REPL history, etc). It defaults to `~/.dune` and can be moved
+// with the `--cache-dir` flag or the `DUNE_DIR` env variable.
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The env variable that relocates the dune directory.
+pub const DUNE_DIR_ENV: &str = "DUNE_DIR";
+
+/// The directory set through the `--cache-dir` CLI flag.
+static ROOT_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Relocates the dune directory (takes precedence over `DUNE_DIR`).
+pub fn set_root(path: PathBuf) {
+    // Note: Relative paths are resolved once, so changing the current
+    // directory later on doesn't move the cache.
+    let path = env::current_dir().unwrap_or_default().join(path);
+    let _ = ROOT_OVERRIDE.set(path);
+}
+
+/// Returns the user-defined location of the dune directory (if any).
+fn root_override() -> Option<PathBuf> {
+    ROOT_OVERRIDE.get().cloned().or_else(|| {
+        env::var_os(DUNE_DIR_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .map(|path| env::current_dir().unwrap_or_default().join(path))
+    })
+}
+
+/// Returns the root of the dune directory.
+pub fn root() -> PathBuf {
+    root_override().unwrap_or_else(|| dirs::home_dir().unwrap().join(".dune"))
+}
+
+/// Returns the directory where URL imports are cached.
+pub fn cache_dir() -> PathBuf {
+    match root_override() {
+        Some(root) => root.join("cache"),
+        // Use local cache directory in development.
+        None if cfg!(debug_assertions) => PathBuf::from(".cache"),
+        None => root().join("cache"),
+    }
+}
+
+/// Returns the location of the REPL's history file.
+pub fn history_file() -> PathBuf {
+    root().join(".dune_history")
+}
@@ -1,10 +1,49 @@
+use crate::runtime::JsRuntimeState;
+use crate::stdio::stderr_columns;
 use anyhow::Error;
+use clap::ValueEnum;
 use colored::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+use serde_json::Value;
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::fs;
 pub use std::io::Error as IoError;
 use std::io::ErrorKind;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// How uncaught errors are reported (see the `--error-format` flag).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Colored output fitted to the terminal.
+    #[default]
+    Human,
+    /// A single line of JSON (for editors and other tools).
+    Json,
+}
+
+/// The error format selected through the CLI.
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Sets how uncaught errors are reported for the rest of the process.
+pub fn set_error_format(format: ErrorFormat) {
+    let _ = ERROR_FORMAT.set(format);
+}
+
+fn error_format() -> ErrorFormat {
+    ERROR_FORMAT.get().copied().unwrap_or_default()
+}
+
+lazy_static! {
+    // Matches a stack-trace frame (e.g. `at foo (/app/index.js:10:5)`).
+    static ref STACK_FRAME_REGEX: Regex =
+        Regex::new(r"^\s*at (?:(.+?) \()?(.+):(\d+):(\d+)\)?$").unwrap();
+}
 
 /// A simple error type that lets the creator specify both the error message and
 /// the error class name.
@@ -37,6 +76,19 @@ pub fn generic_error(message: impl Into<Cow<'static, str>>) -> Error {
     CustomError::new("Error", message)
 }
 
+/// The error that stops the runtime once the program asks to exit the process
+/// (e.g. calling `process.exit()`), carrying the requested exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitRequest(pub i32);
+
+impl std::error::Error for ExitRequest {}
+
+impl Display for ExitRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The process requested to exit with code {}.", self.0)
+    }
+}
+
 /// Represents an exception coming from V8.
 #[derive(Eq, PartialEq, Clone, Default)]
 pub struct JsError {
@@ -47,8 +99,16 @@ pub struct JsError {
     pub start_column: Option<i64>,
     pub end_column: Option<i64>,
     pub stack: Option<String>,
+    /// The exception's `.code` property (e.g. `ERR_INVALID_ARG_TYPE`).
+    pub code: Option<String>,
+    /// Lines surrounding the source-line, as (line-number, line) pairs.
+    pub source_context: Vec<(i64, String)>,
 }
 
+/// How many lines are shown before and after the line that threw.
+const CONTEXT_LINES_BEFORE: usize = 2;
+const CONTEXT_LINES_AFTER: usize = 1;
+
 impl JsError {
     // https://github.com/denoland/rusty_v8/blob/0d093a02f658781d52e6d70d138768fc19a79d54/examples/shell.rs#L158
     pub fn from_v8_exception<'a>(
@@ -101,6 +161,20 @@ impl JsError {
             .map(|stack| stack.unwrap_or_default())
             .ok();
 
+        let code = exception.ok().and_then(|exception| {
+            let code = v8::String::new(scope, "code").unwrap();
+            let code = exception.get(scope, code.into())?;
+            code.is_string().then(|| code.to_rust_string_lossy(scope))
+        });
+
+        // Read the lines surrounding the error (if the source is available).
+        let source_context = match (source_line.as_ref(), line_number) {
+            (Some(source_line), Some(line_number)) => read_source(scope, &resource_name)
+                .map(|source| extract_context(&source, line_number, source_line))
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+
         JsError {
             message: message_value,
             resource_name,
@@ -109,8 +183,79 @@ impl JsError {
             start_column,
             end_column,
             stack,
+            code,
+            source_context,
         }
     }
+
+    /// Returns a JSON representation of the error (line and column numbers
+    /// are 1-based).
+    pub fn to_json(&self) -> Value {
+        let frames: Vec<Value> = self
+            .stack
+            .iter()
+            .flat_map(|stack| stack.lines())
+            .filter_map(|line| STACK_FRAME_REGEX.captures(line))
+            .map(|frame| {
+                json!({
+                    "function": frame.get(1).map(|name| name.as_str()),
+                    "file": &frame[2],
+                    "line": frame[3].parse::<i64>().ok(),
+                    "column": frame[4].parse::<i64>().ok(),
+                })
+            })
+            .collect();
+
+        json!({
+            "message": self.message,
+            "code": self.code,
+            "file": self.resource_name,
+            "line": self.line_number,
+            "column": self.start_column.map(|column| column + 1),
+            "stack": frames,
+        })
+    }
+}
+
+/// Gets the source of a script from the module map (or the file-system).
+///
+/// Note: The module map holds the compiled source (e.g. for TypeScript files),
+/// so its line numbers match the ones reported by v8.
+fn read_source(isolate: &v8::Isolate, resource_name: &str) -> Option<String> {
+    let source = isolate
+        .get_slot::<Rc<RefCell<JsRuntimeState>>>()
+        .and_then(|state| {
+            // The state may already be borrowed (e.g. while compiling modules).
+            let state = state.try_borrow().ok()?;
+            state.module_map.get_source(resource_name).map(String::from)
+        });
+
+    source.or_else(|| fs::read_to_string(resource_name).ok())
+}
+
+/// Extracts the lines around the (1-based) line number from a source.
+fn extract_context(source: &str, line_number: i64, source_line: &str) -> Vec<(i64, String)> {
+    let index = (line_number - 1).max(0) as usize;
+    let first = index.saturating_sub(CONTEXT_LINES_BEFORE);
+    let count = index - first + CONTEXT_LINES_AFTER + 1;
+
+    let lines: Vec<(i64, String)> = source
+        .lines()
+        .enumerate()
+        .skip(first)
+        .take(count)
+        .map(|(index, line)| (index as i64 + 1, line.trim_end().to_owned()))
+        .collect();
+
+    // Note: A mismatch means the source changed since it was loaded.
+    let matches = |(number, line): &(i64, String)| {
+        *number == line_number && line.as_str() == source_line.trim_end()
+    };
+
+    match lines.iter().any(matches) {
+        true => lines,
+        false => vec![],
+    }
 }
 
 impl std::error::Error for JsError {}
@@ -136,31 +281,58 @@ impl Display for JsError {
 impl Debug for JsError {
     /// Displays a full version of the error with stack-trace.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Note: The output is fitted to the terminal's width, but it's left as
+        // it is when the width can't be determined (e.g. piped to a file).
+        let width = stderr_columns().filter(|width| *width >= MIN_WRAP_WIDTH);
+        let fit = |text: &str| match width {
+            Some(width) => wrap_text(text, width),
+            None => text.to_owned(),
+        };
+
         // Output exception information.
-        write!(f, "{} {}", "Uncaught".red().bold(), self.message)?;
+        let message = fit(&format!("Uncaught {}", self.message));
+        let message = message.strip_prefix("Uncaught").unwrap_or(&message);
+        write!(f, "{}{}", "Uncaught".red().bold(), message)?;
 
         // Output source-line if exists.
         match self.source_line.as_ref() {
             Some(source_line) if !source_line.is_empty() => {
-                // Log the source-line.
-                writeln!(f, "\n{source_line}")?;
+                let start_column = self.start_column.unwrap_or_default() as usize;
+                let end_column = self.end_column.unwrap_or_default() as usize;
 
-                // Indicate the position where the error was thrown.
-                let start_column = self.start_column.unwrap_or_default();
-                let end_column = self.end_column.unwrap_or_default();
+                // The surrounding lines are shown (with line numbers) when the
+                // source is available and the line fits in the terminal.
+                let gutter = self.source_context.last().map(|(n, _)| n.to_string().len());
+                let fits = |gutter: usize| {
+                    let length = source_line.chars().count() + gutter + 5;
+                    width.is_none_or(|width| length <= width)
+                };
 
-                for _ in 0..start_column {
-                    write!(f, " ")?;
-                }
+                match gutter.filter(|gutter| fits(*gutter)) {
+                    Some(gutter) => {
+                        self.fmt_source_context(f, gutter, start_column, end_column, width)?
+                    }
+                    None => {
+                        // Long source lines (e.g. minified code) are clipped around the error.
+                        let (source_line, start_column, end_column) = match width {
+                            Some(width) => {
+                                clip_source_line(source_line, start_column, end_column, width)
+                            }
+                            None => (source_line.to_owned(), start_column, end_column),
+                        };
 
-                for _ in start_column..end_column {
-                    let mark = "^".red();
-                    write!(f, "{mark}")?;
+                        // Log the source-line.
+                        writeln!(f, "\n{source_line}")?;
+
+                        // Indicate the position where the error was thrown.
+                        let marks = "^".repeat(end_column.saturating_sub(start_column));
+                        write!(f, "{}{}", " ".repeat(start_column), marks.red())?;
+                    }
                 }
 
                 // Print stacktrace if available.
                 if let Some(stack) = self.stack.as_ref() {
-                    write!(f, "\n{}", stack.dimmed())?;
+                    write!(f, "\n{}", fit(stack).dimmed())?;
                 }
             }
             _ => {}
@@ -170,23 +342,244 @@ impl Debug for JsError {
     }
 }
 
+impl JsError {
+    /// Displays the lines around the error (with line numbers), marking the
+    /// position where the error was thrown.
+    fn fmt_source_context(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        gutter: usize,
+        start_column: usize,
+        end_column: usize,
+        width: Option<usize>,
+    ) -> std::fmt::Result {
+        for (number, line) in self.source_context.iter() {
+            let is_error_line = Some(*number) == self.line_number;
+            let marker = if is_error_line { ">" } else { " " };
+            let prefix = format!("{marker} {number:>gutter$} | ");
+
+            // Note: Only the surrounding lines can be truncated.
+            let line = match width {
+                Some(width) if !is_error_line => truncate_line(line, width - prefix.len()),
+                _ => line.to_owned(),
+            };
+
+            match is_error_line {
+                true => write!(f, "\n{}{line}", prefix.bold())?,
+                false => write!(f, "\n{}{line}", prefix.dimmed())?,
+            };
+
+            // Indicate the position where the error was thrown.
+            if is_error_line {
+                let prefix = format!("  {:gutter$} | ", "");
+                let marks = "^".repeat(end_column.saturating_sub(start_column));
+                let padding = " ".repeat(start_column);
+                write!(f, "\n{}{padding}{}", prefix.dimmed(), marks.red())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Cuts a line that doesn't fit the width, marking the cut with an ellipsis.
+fn truncate_line(line: &str, width: usize) -> String {
+    match line.chars().count() > width {
+        true => {
+            let line: String = line.chars().take(width.saturating_sub(3)).collect();
+            format!("{line}...")
+        }
+        false => line.to_owned(),
+    }
+}
+
+/// Terminals narrower than this are not worth wrapping the output for.
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Wraps text (word by word) so that no line is wider than the given width.
+///
+/// Note: Continuation lines are indented a bit further than the line they
+/// belong to, so wrapped stack frames can still be told apart.
+fn wrap_text(text: &str, width: usize) -> String {
+    let mut lines = vec![];
+
+    for line in text.lines() {
+        if line.chars().count() <= width {
+            lines.push(line.to_owned());
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let continuation = " ".repeat((indent + 2).min(width / 2));
+        let mut current = line[..indent].to_owned();
+        let mut has_words = false;
+
+        for word in line.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+
+            while !word.is_empty() {
+                let length = current.chars().count();
+                let separator = usize::from(has_words);
+
+                if length + separator + word.len() <= width {
+                    if has_words {
+                        current.push(' ');
+                    }
+                    current.extend(word.drain(..));
+                    has_words = true;
+                    break;
+                }
+
+                // Move the word to a new line (if it fits there).
+                if has_words {
+                    lines.push(std::mem::replace(&mut current, continuation.clone()));
+                    has_words = false;
+                    continue;
+                }
+
+                // Words longer than a whole line are split.
+                let take = width.saturating_sub(length).clamp(1, word.len());
+                current.extend(word.drain(..take));
+                lines.push(std::mem::replace(&mut current, continuation.clone()));
+            }
+        }
+
+        if has_words {
+            lines.push(current);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Cuts a long source line down to a window (around the error) that fits the width.
+///
+/// Returns the clipped line along with the adjusted start and end columns.
+fn clip_source_line(line: &str, start: usize, end: usize, width: usize) -> (String, usize, usize) {
+    let chars: Vec<char> = line.chars().collect();
+
+    // Note: Very narrow terminals are not worth the trouble.
+    if chars.len() <= width || width < MIN_WRAP_WIDTH {
+        return (line.to_owned(), start, end);
+    }
+
+    // Leave some room for the ellipses on both sides.
+    let available = width - 6;
+    let start = start.min(chars.len());
+    let end = end.clamp(start, chars.len());
+    let marked = (end - start).min(available);
+
+    // Center the marked range inside the window.
+    let offset = start
+        .saturating_sub((available - marked) / 2)
+        .min(chars.len() - available);
+
+    let window_end = offset + available;
+    let mut clipped = String::new();
+
+    if offset > 0 {
+        clipped.push_str("...");
+    }
+
+    clipped.extend(&chars[offset..window_end]);
+
+    if window_end < chars.len() {
+        clipped.push_str("...");
+    }
+
+    let shift = if offset > 0 { 3 } else { 0 };
+    let start = start - offset + shift;
+
+    (clipped, start, start + marked)
+}
+
 pub fn unwrap_or_exit<T>(result: Result<T, Error>) -> T {
     match result {
         Ok(value) => value,
-        Err(e) => {
-            eprintln!("{e:?}");
-            std::process::exit(1);
-        }
+        Err(e) => exit_with_error(&e),
     }
 }
 
-pub fn report_and_exit(error: JsError) {
-    eprint!("{error:?}");
+/// Exits the process because of an error that stopped the runtime.
+///
+/// Note: Exit requests (e.g. `process.exit()`) are not reported, the process
+/// just exits with the code that was asked for.
+pub fn exit_with_error(error: &Error) -> ! {
+    if let Some(ExitRequest(code)) = error.downcast_ref::<ExitRequest>() {
+        std::process::exit(*code);
+    }
+    report_error(error);
     std::process::exit(1);
 }
 
+/// Reports an error to stderr using the selected error format.
+pub fn report_error(error: &Error) {
+    // Note: Asking to exit is not an error that needs reporting.
+    if error.is::<ExitRequest>() {
+        return;
+    }
+    if error_format() == ErrorFormat::Human {
+        // Note: JS exceptions are shown in full (with the stack-trace).
+        match error.downcast_ref::<JsError>() {
+            Some(error) => eprint!("{error:?}"),
+            None => eprintln!("{error:?}"),
+        };
+        return;
+    }
+
+    eprintln!("{}", error_to_json(error));
+}
+
+/// Describes an error as JSON (see `--error-format=json`).
+fn error_to_json(error: &Error) -> Value {
+    match (
+        error.downcast_ref::<JsError>(),
+        error.downcast_ref::<CustomError>(),
+    ) {
+        (Some(error), _) => error.to_json(),
+        (_, Some(error)) => json!({ "message": format!("{}: {}", error.class, error.message) }),
+        _ => json!({ "message": error.to_string() }),
+    }
+}
+
+// OS error numbers of reaching the open files limit, per process and
+// system-wide (there's no `ErrorKind` for those).
+#[cfg(target_family = "unix")]
+const OPEN_FILES_ERRORS: [(i32, &str); 2] =
+    [(24, "TOO_MANY_OPEN_FILES"), (23, "FILE_TABLE_OVERFLOW")];
+
+#[cfg(target_family = "windows")]
+const OPEN_FILES_ERRORS: [(i32, &str); 1] = [(4, "TOO_MANY_OPEN_FILES")];
+
+/// Returns the error's code if it was caused by reaching the open files limit.
+fn open_files_error_code(err: &IoError) -> Option<&'static str> {
+    let errno = err.raw_os_error()?;
+    OPEN_FILES_ERRORS
+        .iter()
+        .find(|(value, _)| *value == errno)
+        .map(|(_, code)| *code)
+}
+
+/// Returns actionable guidance for IO errors caused by reaching the open files limit.
+pub fn resource_limit_hint(err: &IoError) -> Option<&'static str> {
+    open_files_error_code(err)?;
+
+    #[cfg(target_family = "unix")]
+    let hint = "Too many open files, consider raising the limit (e.g. `ulimit -n 10240` \
+or `process.setrlimit('nofile', { soft })`) or opening fewer files at once.";
+
+    #[cfg(target_family = "windows")]
+    let hint = "Too many open files, consider opening fewer files at once.";
+
+    Some(hint)
+}
+
 /// Returns a string representation of the IO error's code.
 pub fn extract_error_code(err: &IoError) -> Option<&'static str> {
+    if let Some(code) = open_files_error_code(err) {
+        return Some(code);
+    }
+
     match err.kind() {
         ErrorKind::AddrInUse => Some("ADDR_IN_USE"),
         ErrorKind::AddrNotAvailable => Some("ADDR_NOT_AVAILABLE"),
@@ -207,3 +600,126 @@ pub fn extract_error_code(err: &IoError) -> Option<&'static str> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the characters of the line between the given columns.
+    fn columns(line: &str, start: usize, end: usize) -> String {
+        line.chars().skip(start).take(end - start).collect()
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        // Lines that fit are left alone.
+        assert_eq!(wrap_text("short\n  line", 20), "short\n  line");
+
+        // Continuation lines are indented further than the line they belong to.
+        let wrapped = wrap_text("    at foo (/app/a.js:1:5)", 24);
+        assert_eq!(wrapped, "    at foo\n      (/app/a.js:1:5)");
+    }
+
+    #[test]
+    fn test_wrap_text_long_words() {
+        let word = "x".repeat(30);
+        let wrapped = wrap_text(&format!("a {word}"), 10);
+        let lines: Vec<&str> = wrapped.lines().collect();
+
+        assert_eq!(lines[0], "a");
+        assert!(lines.iter().all(|line| line.chars().count() <= 10));
+        assert_eq!(lines[1..].concat().replace(' ', ""), word);
+    }
+
+    #[test]
+    fn test_wrap_text_multibyte() {
+        assert_eq!(wrap_text("αβγ δεζ ηθι", 8), "αβγ δεζ\n  ηθι");
+
+        let wrapped = wrap_text(&"ü".repeat(25), 10);
+        let expected = format!(
+            "{}\n  {}\n  {}",
+            "ü".repeat(10),
+            "ü".repeat(8),
+            "ü".repeat(7)
+        );
+        assert_eq!(wrapped, expected);
+    }
+
+    #[test]
+    fn test_clip_source_line() {
+        // Lines that fit (or very narrow terminals) are left alone.
+        assert_eq!(clip_source_line("throw", 0, 5, 30), ("throw".into(), 0, 5));
+
+        let line = "a".repeat(100);
+        assert_eq!(clip_source_line(&line, 50, 55, 10), (line.clone(), 50, 55));
+
+        // The error is centered inside the window.
+        let line = format!("{}throw{}", "a".repeat(50), "a".repeat(45));
+        let (clipped, start, end) = clip_source_line(&line, 50, 55, 30);
+
+        assert_eq!(clipped.chars().count(), 30);
+        assert!(clipped.starts_with("...") && clipped.ends_with("..."));
+        assert_eq!(columns(&clipped, start, end), "throw");
+    }
+
+    #[test]
+    fn test_clip_source_line_edges() {
+        // An error at the start of the line only cuts the end.
+        let line = format!("throw{}", "a".repeat(95));
+        let (clipped, start, end) = clip_source_line(&line, 0, 5, 30);
+
+        assert_eq!((start, end), (0, 5));
+        assert!(!clipped.starts_with("...") && clipped.ends_with("..."));
+        assert_eq!(columns(&clipped, start, end), "throw");
+
+        // An error at the end of the line only cuts the start.
+        let line = format!("{}throw", "a".repeat(95));
+        let (clipped, start, end) = clip_source_line(&line, 95, 100, 30);
+
+        assert!(clipped.starts_with("...") && !clipped.ends_with("..."));
+        assert_eq!(columns(&clipped, start, end), "throw");
+        assert_eq!(end, clipped.chars().count());
+    }
+
+    #[test]
+    fn test_clip_source_line_multibyte() {
+        // Note: Columns count characters, not bytes.
+        let line = format!("{}throw{}", "é".repeat(60), "é".repeat(35));
+        let (clipped, start, end) = clip_source_line(&line, 60, 65, 30);
+
+        assert_eq!(clipped.chars().count(), 30);
+        assert_eq!(columns(&clipped, start, end), "throw");
+    }
+    #[test]
+    fn test_error_to_json() {
+        let error = JsError {
+            message: "Uncaught TypeError: oops".into(),
+            resource_name: "/app/index.js".into(),
+            line_number: Some(3),
+            start_column: Some(4),
+            code: Some("ERR_INVALID_ARG_TYPE".into()),
+            stack: Some(
+                "TypeError: oops\n    at foo (/app/index.js:3:5)\n    at /app/main.js:1:1".into(),
+            ),
+            ..Default::default()
+        };
+
+        let expected = json!({
+            "message": "Uncaught TypeError: oops",
+            "code": "ERR_INVALID_ARG_TYPE",
+            "file": "/app/index.js",
+            "line": 3,
+            "column": 5,
+            "stack": [
+                { "function": "foo", "file": "/app/index.js", "line": 3, "column": 5 },
+                { "function": null, "file": "/app/main.js", "line": 1, "column": 1 },
+            ],
+        });
+
+        assert_eq!(error_to_json(&Error::new(error)), expected);
+
+        // Other errors only have a message.
+        let value = error_to_json(&generic_error("Bad import."));
+        assert_eq!(value, json!({ "message": "Error: Bad import." }));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bindings::external_references;
 use crate::bindings::set_function_to;
 use crate::runtime::JsRuntime;
 
@@ -88,6 +89,14 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        set_uncaught_exception_callback,
+        set_unhandled_rejection_callback
+    ]
+}
+
 /// Setting the `uncaught_exception_callback` from JavaScript.
 fn set_uncaught_exception_callback(
     scope: &mut v8::HandleScope,
@@ -1,3 +1,5 @@
+use crate::bindings::decode_result;
+use crate::bindings::external_references;
 use crate::bindings::get_internal_ref;
 use crate::bindings::set_constant_to;
 use crate::bindings::set_exception_code;
@@ -5,8 +7,12 @@ use crate::bindings::set_function_to;
 use crate::bindings::set_internal_ref;
 use crate::bindings::set_property_to;
 use crate::bindings::throw_exception;
+use crate::limiter::CancelToken;
+use crate::limiter::TaskClass;
 use crate::runtime::JsFuture;
 use crate::runtime::JsRuntime;
+use crate::runtime::JsRuntimeState;
+use crate::stat_cache;
 use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
@@ -14,16 +20,22 @@ use dune_event_loop::FsEvent;
 use dune_event_loop::FsEventKind;
 use dune_event_loop::LoopHandle;
 use dune_event_loop::TaskResult;
+use filetime::FileTime;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fs;
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io;
 use std::io::prelude::*;
 use std::io::SeekFrom;
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 use std::time::UNIX_EPOCH;
 
@@ -33,6 +45,46 @@ use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 #[cfg(target_family = "windows")]
 use std::os::windows::io::{AsRawHandle, FromRawHandle, RawHandle};
 
+/// Makes `copyFile` fail if the destination already exists.
+const COPYFILE_EXCL: u32 = 1;
+
+/// The `access` checks (the path exists, is readable, writable or executable).
+const F_OK: u32 = 0;
+const R_OK: u32 = 4;
+const W_OK: u32 = 2;
+const X_OK: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+/// Options controlling a (recursive) copy.
+struct CopyOptions {
+    recursive: bool,
+    force: bool,
+    error_on_exist: bool,
+}
+
+/// An open directory, shared with the thread-pool tasks reading from it.
+type DirHandle = Arc<Mutex<Option<fs::ReadDir>>>;
+
+#[derive(Debug, Serialize, Deserialize)]
+/// Struct that describes a directory entry (without an extra stat call).
+struct DirEntryInfo {
+    name: String,
+    is_directory: bool,
+    is_file: bool,
+    is_symbolic_link: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// The type of a directory entry (as reported by `readdir`).
+struct FileTypeFlags {
+    is_file: bool,
+    is_directory: bool,
+    is_symbolic_link: bool,
+}
+
+/// The directory entries with their types (when requested).
+type DirectoryEntries = Vec<(OsString, Option<FileTypeFlags>)>;
+
 #[derive(Default, Debug, Serialize, Deserialize)]
 /// Struct that provides information about a file.
 struct FileStatistics {
@@ -65,29 +117,154 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     set_function_to(scope, target, "openSync", open_sync);
     set_function_to(scope, target, "read", read);
     set_function_to(scope, target, "readSync", read_sync);
+    set_function_to(scope, target, "readFile", read_file);
+    set_function_to(scope, target, "readFileSync", read_file_sync);
     set_function_to(scope, target, "write", write);
     set_function_to(scope, target, "writeSync", write_sync);
+    set_function_to(scope, target, "writeAt", write_at);
+    set_function_to(scope, target, "writeAtSync", write_at_sync);
+    set_function_to(scope, target, "appendFile", append_file);
+    set_function_to(scope, target, "appendFileSync", append_file_sync);
+    set_function_to(scope, target, "truncate", truncate);
+    set_function_to(scope, target, "truncateSync", truncate_sync);
+    set_function_to(scope, target, "ftruncate", ftruncate);
+    set_function_to(scope, target, "ftruncateSync", ftruncate_sync);
     set_function_to(scope, target, "stat", stat);
     set_function_to(scope, target, "statSync", stat_sync);
+    set_function_to(scope, target, "access", access);
+    set_function_to(scope, target, "accessSync", access_sync);
+    set_function_to(scope, target, "lstat", lstat);
+    set_function_to(scope, target, "lstatSync", lstat_sync);
     set_function_to(scope, target, "mkdir", mkdir);
     set_function_to(scope, target, "mkdirSync", mkdir_sync);
+    set_function_to(scope, target, "mkdtemp", mkdtemp);
+    set_function_to(scope, target, "mkdtempSync", mkdtemp_sync);
+    set_function_to(scope, target, "chmod", chmod);
+    set_function_to(scope, target, "chmodSync", chmod_sync);
+    set_function_to(scope, target, "chown", chown);
+    set_function_to(scope, target, "chownSync", chown_sync);
+    set_function_to(scope, target, "utimes", utimes);
+    set_function_to(scope, target, "utimesSync", utimes_sync);
     set_function_to(scope, target, "rmdir", rmdir);
     set_function_to(scope, target, "rmdirSync", rmdir_sync);
     set_function_to(scope, target, "readdir", readdir);
     set_function_to(scope, target, "readdirSync", readdir_sync);
+    set_function_to(scope, target, "opendir", opendir);
+    set_function_to(scope, target, "opendirSync", opendir_sync);
+    set_function_to(scope, target, "readDirEntry", read_dir_entry);
+    set_function_to(scope, target, "readDirEntrySync", read_dir_entry_sync);
+    set_function_to(scope, target, "closedir", closedir);
     set_function_to(scope, target, "rm", rm);
     set_function_to(scope, target, "rmSync", rm_sync);
     set_function_to(scope, target, "close", close);
     set_function_to(scope, target, "closeSync", close_sync);
     set_function_to(scope, target, "rename", rename);
     set_function_to(scope, target, "renameSync", rename_sync);
+    set_function_to(scope, target, "copyFile", copy_file);
+    set_function_to(scope, target, "copyFileSync", copy_file_sync);
+    set_function_to(scope, target, "cp", cp);
+    set_function_to(scope, target, "cpSync", cp_sync);
+    set_function_to(scope, target, "symlink", symlink);
+    set_function_to(scope, target, "symlinkSync", symlink_sync);
+    set_function_to(scope, target, "readlink", readlink);
+    set_function_to(scope, target, "readlinkSync", readlink_sync);
+    set_function_to(scope, target, "realpath", realpath);
+    set_function_to(scope, target, "realpathSync", realpath_sync);
+    set_function_to(scope, target, "clearStatCache", clear_stat_cache);
     set_function_to(scope, target, "watch", watch);
     set_function_to(scope, target, "unwatch", unwatch);
+    set_function_to(scope, target, "createCancelToken", create_cancel_token);
+    set_function_to(scope, target, "cancel", cancel);
+    set_function_to(scope, target, "sendFd", send_fd);
+    set_function_to(scope, target, "recvFd", recv_fd);
+
+    let constants = [
+        ("COPYFILE_EXCL", COPYFILE_EXCL),
+        ("F_OK", F_OK),
+        ("R_OK", R_OK),
+        ("W_OK", W_OK),
+        ("X_OK", X_OK),
+    ];
+
+    for (name, value) in constants {
+        let value = v8::Integer::new_from_unsigned(scope, value);
+        set_constant_to(scope, target, name, value.into());
+    }
 
     // Return v8 global handle.
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        open,
+        open_sync,
+        read,
+        read_sync,
+        read_file,
+        read_file_sync,
+        write,
+        write_sync,
+        write_at,
+        write_at_sync,
+        append_file,
+        append_file_sync,
+        truncate,
+        truncate_sync,
+        ftruncate,
+        ftruncate_sync,
+        stat,
+        stat_sync,
+        access,
+        access_sync,
+        lstat,
+        lstat_sync,
+        mkdir,
+        mkdir_sync,
+        mkdtemp,
+        mkdtemp_sync,
+        chmod,
+        chmod_sync,
+        chown,
+        chown_sync,
+        utimes,
+        utimes_sync,
+        rmdir,
+        rmdir_sync,
+        readdir,
+        readdir_sync,
+        opendir,
+        opendir_sync,
+        read_dir_entry,
+        read_dir_entry_sync,
+        closedir,
+        rm,
+        rm_sync,
+        close,
+        close_sync,
+        rename,
+        rename_sync,
+        copy_file,
+        copy_file_sync,
+        cp,
+        cp_sync,
+        symlink,
+        symlink_sync,
+        readlink,
+        readlink_sync,
+        realpath,
+        realpath_sync,
+        clear_stat_cache,
+        watch,
+        unwatch,
+        create_cancel_token,
+        cancel,
+        send_fd,
+        recv_fd
+    ]
+}
+
 /// Describes what will run after the async open_file_op completes.
 struct FsOpenFuture {
     promise: v8::Global<v8::PromiseResolver>,
@@ -97,6 +274,7 @@ struct FsOpenFuture {
 impl JsFuture for FsOpenFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<usize>);
 
         // Handle when something goes wrong with opening the file.
         if let Err(e) = result {
@@ -107,11 +285,8 @@ impl JsFuture for FsOpenFuture {
             return;
         }
 
-        // Otherwise, get the result and deserialize it.
-        let result = result.unwrap();
-
-        // Deserialize bytes into a file-descriptor.
-        let file_ptr: usize = bincode::deserialize(&result).unwrap();
+        // Otherwise, get the file-descriptor.
+        let file_ptr = result.unwrap();
         let file = get_file_reference(file_ptr);
 
         let file_wrapper = v8::ObjectTemplate::new(scope);
@@ -169,7 +344,9 @@ fn open(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
@@ -218,6 +395,7 @@ struct FsReadFuture {
 impl JsFuture for FsReadFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<(usize, Vec<u8>)>);
 
         // Handle when something goes wrong with reading.
         if let Err(e) = result {
@@ -229,10 +407,7 @@ impl JsFuture for FsReadFuture {
         }
 
         // Otherwise, resolve the promise passing the result.
-        let result = result.unwrap();
-
-        // Deserialize bincode binary into actual rust types.
-        let (n, data): (usize, Vec<u8>) = bincode::deserialize(&result).unwrap();
+        let (n, data) = result.unwrap();
 
         // Copy the slice's bytes into v8's typed-array backing store.
         for (i, value) in data.iter().enumerate() {
@@ -302,7 +477,9 @@ fn read(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
@@ -348,6 +525,126 @@ fn read_sync(
     }
 }
 
+/// Describes what will run after the async read_whole_file_op completes.
+struct FsReadFileFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+    utf8: bool,
+}
+
+impl JsFuture for FsReadFileFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<Vec<u8>>);
+
+        // Hand the contents over as an ArrayBuffer (or a string).
+        let contents = result.and_then(|data| create_file_contents(scope, data, self.utf8));
+
+        // Something went wrong while reading the file.
+        if let Err(e) = contents {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        self.promise
+            .open(scope)
+            .resolve(scope, contents.unwrap())
+            .unwrap();
+    }
+}
+
+/// Reads asynchronously the entire contents of a file.
+fn read_file(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get file path and whether to decode the contents as utf-8.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let utf8 = args.get(1).boolean_value(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match read_whole_file_op(path) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsReadFileFuture {
+                promise,
+                maybe_result,
+                utf8,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Reads synchronously the entire contents of a file.
+fn read_file_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get file path and whether to decode the contents as utf-8.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let utf8 = args.get(1).boolean_value(scope);
+
+    let contents =
+        read_whole_file_op(path).and_then(|data| create_file_contents(scope, data, utf8));
+
+    match contents {
+        Ok(contents) => rv.set(contents),
+        Err(e) => throw_exception(scope, &e),
+    }
+}
+
+/// Hands the file's contents to JavaScript, as an ArrayBuffer or a (utf-8) string.
+fn create_file_contents<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    data: Vec<u8>,
+    utf8: bool,
+) -> Result<v8::Local<'a, v8::Value>> {
+    if utf8 {
+        // Note: Invalid sequences are replaced (like TextDecoder does).
+        let text = String::from_utf8_lossy(&data);
+        return match v8::String::new(scope, &text) {
+            Some(text) => Ok(text.into()),
+            None => bail!("The file is too large to be read as a string."),
+        };
+    }
+
+    // Note: The bytes are handed over to v8 without copying.
+    let store = data.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+
+    Ok(buffer.into())
+}
+
 /// Describes what will run after the async write_file_op completes.
 struct FsWriteFuture {
     promise: v8::Global<v8::PromiseResolver>,
@@ -443,7 +740,9 @@ fn write(
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
@@ -477,18 +776,18 @@ fn write_sync(
     }
 }
 
-/// Describes what will run after the async stats_op completes.
-struct FsStatFuture {
+/// Describes what will run after the async write_at_op completes.
+struct FsWriteAtFuture {
     promise: v8::Global<v8::PromiseResolver>,
     maybe_result: TaskResult,
 }
 
-impl JsFuture for FsStatFuture {
+impl JsFuture for FsWriteAtFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
-        // Unwrap the result.
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<usize>);
 
-        // Something went wrong while getting the file's stats.
+        // Something went wrong while writing to the file.
         if let Err(e) = result {
             let message = v8::String::new(scope, &e.to_string()).unwrap();
             let exception = v8::Exception::error(scope, message);
@@ -497,44 +796,64 @@ impl JsFuture for FsStatFuture {
             return;
         }
 
-        // Otherwise, resolve the promise passing the result.
-        let result = result.unwrap();
-
-        // Deserialize bincode binary into actual rust types.
-        let stats: FileStatistics = bincode::deserialize(&result).unwrap();
-        let stats = create_v8_stats_object(scope, stats);
-
+        // Otherwise, resolve with the amount of bytes written.
+        let bytes = v8::Number::new(scope, result.unwrap() as f64);
         self.promise
             .open(scope)
-            .resolve(scope, stats.into())
+            .resolve(scope, bytes.into())
             .unwrap();
     }
 }
 
-/// Get's asynchronously file statistics.
-fn stat(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
-    // Get the path.
-    let path = args.get(0).to_rust_string_lossy(scope);
+// Writes asynchronously contents at a position of the file.
+fn write_at(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the file_wrap object.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Get data as ArrayBuffer and the position to write at.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+    let offset = get_length(scope, args.get(2));
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
     let promise = promise_resolver.get_promise(scope);
 
+    // Check if the file is already closed, otherwise create a file reference.
+    let file = match get_internal_ref::<Option<File>>(scope, file_wrap, 0) {
+        Some(file) => file.try_clone().unwrap(),
+        None => {
+            let message = v8::String::new(scope, "File is closed.").unwrap();
+            let exception = v8::Exception::error(scope, message);
+            promise_resolver.reject(scope, exception);
+            rv.set(promise.into());
+            return;
+        }
+    };
+
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    let task = move || match stats_op(path) {
+    // The actual async task.
+    let task = move || match write_at_op(&file, &buffer, offset) {
         Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
         Err(e) => Some(Result::Err(e)),
     };
 
+    // The callback that will run after the above task completes.
     let task_cb = {
         let promise = v8::Global::new(scope, promise_resolver);
         let state_rc = state_rc.clone();
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = FsStatFuture {
+            let future = FsWriteAtFuture {
                 promise,
                 maybe_result,
             };
@@ -543,90 +862,157 @@ fn stat(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
 
-/// Get's file statistics.
-fn stat_sync(
+/// Writes contents at a position of the file.
+fn write_at_sync(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get the path.
-    let path = args.get(0).to_rust_string_lossy(scope);
+    // Get the file_wrap object.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
 
-    match stats_op(path) {
-        Ok(stats) => rv.set(create_v8_stats_object(scope, stats).into()),
-        Err(e) => throw_exception(scope, &e),
+    // Check if the file is already closed, otherwise create a file reference.
+    let file = match get_internal_ref::<Option<File>>(scope, file_wrap, 0) {
+        Some(file) => file.try_clone().unwrap(),
+        None => {
+            throw_exception(scope, &anyhow!("File is closed."));
+            return;
+        }
     };
-}
 
-/// Describes what will run after the async mkdir_op completes.
-struct FsMkdirFuture {
-    promise: v8::Global<v8::PromiseResolver>,
-    maybe_result: TaskResult,
+    // Get data as ArrayBuffer and the position to write at.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+    let offset = get_length(scope, args.get(2));
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    match write_at_op(&file, &buffer, offset) {
+        Ok(bytes) => rv.set(v8::Number::new(scope, bytes as f64).into()),
+        Err(e) => throw_exception(scope, &e),
+    }
 }
 
-impl JsFuture for FsMkdirFuture {
-    fn run(&mut self, scope: &mut v8::HandleScope) {
-        // If the result is None then mkdir worked.
-        if self.maybe_result.is_none() {
-            let undefined = v8::undefined(scope);
-            self.promise
-                .open(scope)
-                .resolve(scope, undefined.into())
-                .unwrap();
+/// Appends contents to a file (creating it if needed) asynchronously.
+fn append_file(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get file path.
+    let path = args.get(0).to_rust_string_lossy(scope);
 
-            return;
-        }
+    // Get data as ArrayBuffer.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
 
-        // Something went wrong.
-        let result = self.maybe_result.take().unwrap();
+    // Note: `copy_contents` copies only the view's window (it starts from the
+    // view's byte_offset), not the whole backing buffer.
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
 
-        // Something went wrong while getting the file's stats.
-        if let Err(e) = result {
-            let message = v8::String::new(scope, &e.to_string()).unwrap();
-            let exception = v8::Exception::error(scope, message);
-            set_exception_code(scope, exception, &e);
-            self.promise.open(scope).reject(scope, exception);
-            return;
-        }
+    let promise = spawn_fs_op(scope, move || append_file_op(path, &buffer));
+    rv.set(promise.into());
+}
 
-        unreachable!();
+/// Appends contents to a file (creating it if needed).
+fn append_file_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get file path.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    // Get data as ArrayBuffer.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    if let Err(e) = append_file_op(path, &buffer) {
+        throw_exception(scope, &e);
     }
 }
 
-/// Creates a directory asynchronously.
-fn mkdir(
+/// Truncates (or extends) a file to the given length asynchronously.
+fn truncate(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get desired folder location.
+    // Get file path and the new length.
     let path = args.get(0).to_rust_string_lossy(scope);
-    let recursive = args.get(1).to_rust_string_lossy(scope) == "true";
+    let len = get_length(scope, args.get(1));
+
+    let promise = spawn_fs_op(scope, move || truncate_op(path, len));
+    rv.set(promise.into());
+}
+
+/// Truncates (or extends) a file to the given length.
+fn truncate_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get file path and the new length.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let len = get_length(scope, args.get(1));
+
+    if let Err(e) = truncate_op(path, len) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Truncates (or extends) an open file to the given length asynchronously.
+fn ftruncate(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the file_wrap object and the new length.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
+    let len = get_length(scope, args.get(1));
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
     let promise = promise_resolver.get_promise(scope);
 
+    // Check if the file is already closed, otherwise create a file reference.
+    let file = match get_internal_ref::<Option<File>>(scope, file_wrap, 0) {
+        Some(file) => file.try_clone().unwrap(),
+        None => {
+            let message = v8::String::new(scope, "File is closed.").unwrap();
+            let exception = v8::Exception::error(scope, message);
+            promise_resolver.reject(scope, exception);
+            rv.set(promise.into());
+            return;
+        }
+    };
+
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    let task = move || match mkdir_op(path, recursive) {
+    // The actual async task.
+    let task = move || match file.set_len(len) {
         Ok(_) => None,
-        Err(e) => Some(Result::Err(e)),
+        Err(e) => Some(Result::Err(anyhow!(e))),
     };
 
+    // The callback that will run after the above task completes.
     let task_cb = {
         let promise = v8::Global::new(scope, promise_resolver);
         let state_rc = state_rc.clone();
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = FsMkdirFuture {
+            let future = FsWriteFuture {
                 promise,
                 maybe_result,
             };
@@ -635,47 +1021,54 @@ fn mkdir(
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
 
-/// Creates a directory synchronously.
-fn mkdir_sync(
+/// Truncates (or extends) an open file to the given length.
+fn ftruncate_sync(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     _: v8::ReturnValue,
 ) {
-    // Get desired folder location.
-    let path = args.get(0).to_rust_string_lossy(scope);
-    let recursive = args.get(1).to_rust_string_lossy(scope) == "true";
-
-    if let Err(e) = mkdir_op(path, recursive) {
-        throw_exception(scope, &e);
-    }
-}
+    // Get the file_wrap object and the new length.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
+    let len = get_length(scope, args.get(1));
 
-/// Describes what will run after the async rmdir_op completes.
-struct FsRmdirFuture {
+    // Check if the file is already closed, otherwise create a file reference.
+    let file = match get_internal_ref::<Option<File>>(scope, file_wrap, 0) {
+        Some(file) => file.try_clone().unwrap(),
+        None => {
+            throw_exception(scope, &anyhow!("File is closed."));
+            return;
+        }
+    };
+
+    if let Err(e) = file.set_len(len) {
+        throw_exception(scope, &anyhow!(e));
+    }
+}
+
+/// Returns the (non-negative) length passed from JavaScript.
+fn get_length(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> u64 {
+    value.integer_value(scope).unwrap_or_default().max(0) as u64
+}
+
+/// Describes what will run after the async stats_op completes.
+struct FsStatFuture {
     promise: v8::Global<v8::PromiseResolver>,
     maybe_result: TaskResult,
+    bigint: bool,
 }
 
-impl JsFuture for FsRmdirFuture {
+impl JsFuture for FsStatFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
-        // If the result is None then mkdir worked.
-        if self.maybe_result.is_none() {
-            let undefined = v8::undefined(scope);
-            self.promise
-                .open(scope)
-                .resolve(scope, undefined.into())
-                .unwrap();
-
-            return;
-        }
-
-        // Something went wrong.
+        // Unwrap the result.
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<FileStatistics>);
 
         // Something went wrong while getting the file's stats.
         if let Err(e) = result {
@@ -686,18 +1079,113 @@ impl JsFuture for FsRmdirFuture {
             return;
         }
 
-        unreachable!();
+        // Otherwise, resolve the promise passing the result.
+        let stats = create_v8_stats_object(scope, result.unwrap(), self.bigint);
+
+        self.promise
+            .open(scope)
+            .resolve(scope, stats.into())
+            .unwrap();
     }
 }
 
-/// Removes empty directories asynchronously.
-fn rmdir(
+/// Get's asynchronously file statistics.
+fn stat(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    // Get the path, the caching and the bigint options.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let cache = args.get(1).boolean_value(scope);
+    let bigint = args.get(2).boolean_value(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    let task = move || match stats_op(path, cache) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsStatFuture {
+                promise,
+                maybe_result,
+                bigint,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Get's file statistics.
+fn stat_sync(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get to be removed folder location.
+    // Get the path, the caching and the bigint options.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let cache = args.get(1).boolean_value(scope);
+    let bigint = args.get(2).boolean_value(scope);
+
+    match stats_op(path, cache) {
+        Ok(stats) => rv.set(create_v8_stats_object(scope, stats, bigint).into()),
+        Err(e) => throw_exception(scope, &e),
+    };
+}
+
+/// Checks asynchronously whether the path exists (and can be accessed).
+fn access(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path and the checks to run.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let mode = args.get(1).uint32_value(scope).unwrap_or(F_OK);
+
+    let promise = spawn_fs_op(scope, move || access_op(path, mode));
+    rv.set(promise.into());
+}
+
+/// Checks whether the path exists (and can be accessed).
+fn access_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the path and the checks to run.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let mode = args.get(1).uint32_value(scope).unwrap_or(F_OK);
+
+    if let Err(e) = access_op(path, mode) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Get's asynchronously file statistics (describing the link itself for symlinks).
+fn lstat(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path and the bigint option.
     let path = args.get(0).to_rust_string_lossy(scope);
+    let bigint = args.get(1).boolean_value(scope);
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
@@ -706,8 +1194,8 @@ fn rmdir(
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    let task = move || match rmdir_op(path) {
-        Ok(_) => None,
+    let task = move || match lstats_op(path) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
         Err(e) => Some(Result::Err(e)),
     };
 
@@ -717,46 +1205,52 @@ fn rmdir(
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = FsRmdirFuture {
+            let future = FsStatFuture {
                 promise,
                 maybe_result,
+                bigint,
             };
             state.pending_futures.push(Box::new(future));
         }
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
 
-/// Removes empty directories.
-fn rmdir_sync(
+/// Get's file statistics (describing the link itself for symlinks).
+fn lstat_sync(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
-    _: v8::ReturnValue,
+    mut rv: v8::ReturnValue,
 ) {
-    // Get to be removed folder location.
+    // Get the path and the bigint option.
     let path = args.get(0).to_rust_string_lossy(scope);
+    let bigint = args.get(1).boolean_value(scope);
 
-    if let Err(e) = rmdir_op(path) {
-        throw_exception(scope, &e);
-    }
+    match lstats_op(path) {
+        Ok(stats) => rv.set(create_v8_stats_object(scope, stats, bigint).into()),
+        Err(e) => throw_exception(scope, &e),
+    };
 }
 
-/// Describes what will run after the async read_dir_op completes.
-struct ReadDirFuture {
+/// Describes what will run after the async realpath_op completes.
+struct FsRealpathFuture {
     promise: v8::Global<v8::PromiseResolver>,
     maybe_result: TaskResult,
 }
 
-impl JsFuture for ReadDirFuture {
+impl JsFuture for FsRealpathFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
         // Unwrap the result.
         let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<String>);
 
-        // Check if something went wrong on directory read.
+        // Something went wrong while resolving the path.
         if let Err(e) = result {
             let message = v8::String::new(scope, &e.to_string()).unwrap();
             let exception = v8::Exception::error(scope, message);
@@ -766,34 +1260,24 @@ impl JsFuture for ReadDirFuture {
         }
 
         // Otherwise, resolve the promise passing the result.
-        let result = result.unwrap();
-
-        // Deserialize bincode binary into an actual rust type.
-        let directory: Vec<OsString> = bincode::deserialize(&result).unwrap();
-        let directory: Vec<v8::Local<v8::Value>> = directory
-            .iter()
-            .map(|entry| entry.to_str().unwrap())
-            .map(|entry| v8::String::new(scope, entry).unwrap())
-            .map(|entry_value| entry_value.into())
-            .collect();
-
-        let directory_value = v8::Array::new_with_elements(scope, &directory);
+        let path = v8::String::new(scope, &result.unwrap()).unwrap();
 
         self.promise
             .open(scope)
-            .resolve(scope, directory_value.into())
+            .resolve(scope, path.into())
             .unwrap();
     }
 }
 
-/// Reads the contents of a directory asynchronously.
-fn readdir(
+/// Resolves asynchronously a path to its canonical form.
+fn realpath(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get desired folder location.
+    // Get the path and the caching option.
     let path = args.get(0).to_rust_string_lossy(scope);
+    let cache = args.get(1).boolean_value(scope);
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
@@ -802,7 +1286,7 @@ fn readdir(
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    let task = move || match readdir_op(path) {
+    let task = move || match realpath_op(path, cache) {
         Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
         Err(e) => Some(Result::Err(e)),
     };
@@ -813,7 +1297,7 @@ fn readdir(
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = ReadDirFuture {
+            let future = FsRealpathFuture {
                 promise,
                 maybe_result,
             };
@@ -821,47 +1305,51 @@ fn readdir(
         }
     };
 
-    state.handle.spawn(task, Some(task_cb));
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
 
-/// Reads the contents of a directory synchronously.
-fn readdir_sync(
+/// Resolves synchronously a path to its canonical form.
+fn realpath_sync(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get the path.
+    // Get the path and the caching option.
     let path = args.get(0).to_rust_string_lossy(scope);
+    let cache = args.get(1).boolean_value(scope);
 
-    match readdir_op(path) {
-        Ok(directory) => {
-            // Cast OsString values to v8::Locals.
-            let directory: Vec<v8::Local<v8::Value>> = directory
-                .iter()
-                .map(|entry| entry.to_str().unwrap())
-                .map(|entry| v8::String::new(scope, entry).unwrap())
-                .map(|entry_value| entry_value.into())
-                .collect();
-
-            let directory_value = v8::Array::new_with_elements(scope, &directory);
-
-            rv.set(directory_value.into());
-        }
+    match realpath_op(path, cache) {
+        Ok(path) => rv.set(v8::String::new(scope, &path).unwrap().into()),
         Err(e) => throw_exception(scope, &e),
+    };
+}
+
+/// Drops the cached lookups of a path (or all of them when not specified).
+fn clear_stat_cache(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    match args.get(0).is_undefined() {
+        true => stat_cache::clear(),
+        false => stat_cache::invalidate(args.get(0).to_rust_string_lossy(scope)),
     }
 }
 
-/// Describes what will run after the async rm_op completes.
-struct FsRmFuture {
+/// Describes what will run after an async fs op (that returns nothing) completes.
+struct FsVoidFuture {
     promise: v8::Global<v8::PromiseResolver>,
     maybe_result: TaskResult,
 }
 
-impl JsFuture for FsRmFuture {
+impl JsFuture for FsVoidFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
-        // If the result is None then mkdir worked.
+        // If the result is None then the op worked.
         if self.maybe_result.is_none() {
             let undefined = v8::undefined(scope);
             self.promise
@@ -888,11 +1376,12 @@ impl JsFuture for FsRmFuture {
     }
 }
 
-/// Removes files and directories asynchronously.
-fn rm(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
-    // Get to be removed folder location.
-    let path = args.get(0).to_rust_string_lossy(scope);
-
+/// Runs an fs op (that returns nothing) on the thread-pool, returning the
+/// promise that settles once it completes.
+fn spawn_fs_op<'s, F>(scope: &mut v8::HandleScope<'s>, op: F) -> v8::Local<'s, v8::Promise>
+where
+    F: FnOnce() -> Result<()> + Send + 'static,
+{
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
     let promise = promise_resolver.get_promise(scope);
@@ -900,18 +1389,20 @@ fn rm(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv:
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    let task = move || match rm_op(path) {
+    // The actual async task.
+    let task = move || match op() {
         Ok(_) => None,
         Err(e) => Some(Result::Err(e)),
     };
 
+    // The callback that will run after the above task completes.
     let task_cb = {
         let promise = v8::Global::new(scope, promise_resolver);
         let state_rc = state_rc.clone();
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = FsRmFuture {
+            let future = FsVoidFuture {
                 promise,
                 maybe_result,
             };
@@ -920,80 +1411,22 @@ fn rm(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv:
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
-
-    rv.set(promise.into());
-}
-
-/// Removes files and directories.
-fn rm_sync(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
-    // Get to be removed folder location.
-    let path = args.get(0).to_rust_string_lossy(scope);
-
-    if let Err(e) = rm_op(path) {
-        throw_exception(scope, &e);
-    }
-}
-
-/// Closes a file asynchronously.
-fn close(
-    scope: &mut v8::HandleScope,
-    args: v8::FunctionCallbackArguments,
-    mut rv: v8::ReturnValue,
-) {
-    // Get the file_wrap object.
-    let file_wrap = args.get(0).to_object(scope).unwrap();
-
-    // Create a promise resolver and extract the actual promise.
-    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
-    let promise = promise_resolver.get_promise(scope);
-
-    if let Some(file) = get_internal_ref::<Option<File>>(scope, file_wrap, 0).take() {
-        // Note: By taking the file reference out of the option and immediately dropping
-        // it will make rust to close the file.
-        drop(file);
-
-        let success = v8::Boolean::new(scope, true);
-        promise_resolver.resolve(scope, success.into());
-        rv.set(promise.into());
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
-        return;
-    }
-
-    let message = v8::String::new(scope, "File is closed.").unwrap();
-    let exception = v8::Exception::error(scope, message);
-
-    promise_resolver.reject(scope, exception);
-    rv.set(promise.into());
-}
-
-/// Closes a file synchronously.
-fn close_sync(
-    scope: &mut v8::HandleScope,
-    args: v8::FunctionCallbackArguments,
-    _: v8::ReturnValue,
-) {
-    // Get the file_wrap object.
-    let file_wrap = args.get(0).to_object(scope).unwrap();
-
-    if let Some(file) = get_internal_ref::<Option<File>>(scope, file_wrap, 0).take() {
-        // Note: By taking the file reference out of the option and immediately dropping
-        // it will make rust to close the file.
-        return drop(file);
-    }
-
-    throw_exception(scope, &anyhow!("File is closed."));
+    promise
 }
 
-/// Describes what will run after the async rename_op completes.
-struct FsRenameFuture {
+/// Describes what will run after the async mkdir_op completes.
+struct FsMkdirFuture {
     promise: v8::Global<v8::PromiseResolver>,
     maybe_result: TaskResult,
 }
 
-impl JsFuture for FsRenameFuture {
+impl JsFuture for FsMkdirFuture {
     fn run(&mut self, scope: &mut v8::HandleScope) {
-        // If the result is None then renaming worked.
+        // If the result is None then mkdir worked.
         if self.maybe_result.is_none() {
             let undefined = v8::undefined(scope);
             self.promise
@@ -1007,7 +1440,7 @@ impl JsFuture for FsRenameFuture {
         // Something went wrong.
         let result = self.maybe_result.take().unwrap();
 
-        // Something went wrong while renaming the file.
+        // Something went wrong while getting the file's stats.
         if let Err(e) = result {
             let message = v8::String::new(scope, &e.to_string()).unwrap();
             let exception = v8::Exception::error(scope, message);
@@ -1020,15 +1453,15 @@ impl JsFuture for FsRenameFuture {
     }
 }
 
-/// Renames a file asynchronously.
-fn rename(
+/// Creates a directory asynchronously.
+fn mkdir(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
     mut rv: v8::ReturnValue,
 ) {
-    // Get `from` and `to` values.
-    let from = args.get(0).to_rust_string_lossy(scope);
-    let to = args.get(1).to_rust_string_lossy(scope);
+    // Get desired folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let recursive = args.get(1).to_rust_string_lossy(scope) == "true";
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
@@ -1037,20 +1470,18 @@ fn rename(
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
-    // The actual async task.
-    let task = move || match rename_op(from, to) {
+    let task = move || match mkdir_op(path, recursive) {
         Ok(_) => None,
         Err(e) => Some(Result::Err(e)),
     };
 
-    // The callback that will run after the above task completes.
     let task_cb = {
         let promise = v8::Global::new(scope, promise_resolver);
         let state_rc = state_rc.clone();
 
         move |_: LoopHandle, maybe_result: TaskResult| {
             let mut state = state_rc.borrow_mut();
-            let future = FsRenameFuture {
+            let future = FsMkdirFuture {
                 promise,
                 maybe_result,
             };
@@ -1059,315 +1490,2117 @@ fn rename(
     };
 
     // Spawn the async task using the event-loop.
-    state.handle.spawn(task, Some(task_cb));
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
 
     rv.set(promise.into());
 }
 
-/// Renames a file synchronously.
-fn rename_sync(
+/// Creates a unique temporary directory asynchronously.
+fn mkdtemp(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
-    _: v8::ReturnValue,
+    mut rv: v8::ReturnValue,
 ) {
-    // Get `from` and `to` values.
-    let from = args.get(0).to_rust_string_lossy(scope);
-    let to = args.get(1).to_rust_string_lossy(scope);
-
-    if let Err(e) = rename_op(from, to) {
-        throw_exception(scope, &e);
-    }
-}
+    // Get the directory's prefix.
+    let prefix = args.get(0).to_rust_string_lossy(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    let task = move || match mkdtemp_op(prefix) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsRealpathFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Creates a unique temporary directory synchronously.
+fn mkdtemp_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the directory's prefix.
+    let prefix = args.get(0).to_rust_string_lossy(scope);
+
+    match mkdtemp_op(prefix) {
+        Ok(path) => rv.set(v8::String::new(scope, &path).unwrap().into()),
+        Err(e) => throw_exception(scope, &e),
+    };
+}
+
+/// Changes the permissions of a file asynchronously.
+fn chmod(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path and the new mode.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let mode = args.get(1).uint32_value(scope).unwrap_or_default();
+
+    let promise = spawn_fs_op(scope, move || chmod_op(path, mode));
+    rv.set(promise.into());
+}
+
+/// Changes the permissions of a file synchronously.
+fn chmod_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the path and the new mode.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let mode = args.get(1).uint32_value(scope).unwrap_or_default();
+
+    if let Err(e) = chmod_op(path, mode) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Changes the owner and group of a file asynchronously.
+fn chown(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path, the user and the group ids.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let uid = args.get(1).uint32_value(scope).unwrap_or_default();
+    let gid = args.get(2).uint32_value(scope).unwrap_or_default();
+
+    let promise = spawn_fs_op(scope, move || chown_op(path, uid, gid));
+    rv.set(promise.into());
+}
+
+/// Changes the owner and group of a file synchronously.
+fn chown_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the path, the user and the group ids.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let uid = args.get(1).uint32_value(scope).unwrap_or_default();
+    let gid = args.get(2).uint32_value(scope).unwrap_or_default();
+
+    if let Err(e) = chown_op(path, uid, gid) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Changes the access and modification times of a file asynchronously.
+fn utimes(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path and the new times (in milliseconds).
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let atime = args.get(1).number_value(scope).unwrap_or_default();
+    let mtime = args.get(2).number_value(scope).unwrap_or_default();
+
+    let promise = spawn_fs_op(scope, move || utimes_op(path, atime, mtime));
+    rv.set(promise.into());
+}
+
+/// Changes the access and modification times of a file synchronously.
+fn utimes_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the path and the new times (in milliseconds).
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let atime = args.get(1).number_value(scope).unwrap_or_default();
+    let mtime = args.get(2).number_value(scope).unwrap_or_default();
+
+    if let Err(e) = utimes_op(path, atime, mtime) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Creates a directory synchronously.
+fn mkdir_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get desired folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let recursive = args.get(1).to_rust_string_lossy(scope) == "true";
+
+    if let Err(e) = mkdir_op(path, recursive) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Describes what will run after the async rmdir_op completes.
+struct FsRmdirFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for FsRmdirFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // If the result is None then mkdir worked.
+        if self.maybe_result.is_none() {
+            let undefined = v8::undefined(scope);
+            self.promise
+                .open(scope)
+                .resolve(scope, undefined.into())
+                .unwrap();
+
+            return;
+        }
+
+        // Something went wrong.
+        let result = self.maybe_result.take().unwrap();
+
+        // Something went wrong while getting the file's stats.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        unreachable!();
+    }
+}
+
+/// Removes empty directories asynchronously.
+fn rmdir(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get to be removed folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    let task = move || match rmdir_op(path) {
+        Ok(_) => None,
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsRmdirFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Removes empty directories.
+fn rmdir_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get to be removed folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    if let Err(e) = rmdir_op(path) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Describes what will run after the async read_dir_op completes.
+struct ReadDirFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for ReadDirFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // Unwrap the result.
+        let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<DirectoryEntries>);
+
+        // Check if something went wrong on directory read.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        // Otherwise, resolve the promise passing the result.
+        let directory_value = create_v8_directory(scope, result.unwrap());
+
+        self.promise
+            .open(scope)
+            .resolve(scope, directory_value.into())
+            .unwrap();
+    }
+}
+
+/// Reads the contents of a directory asynchronously.
+fn readdir(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get desired folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let token_id = get_token_id(scope, args.get(1));
+    let with_file_types = args.get(2).boolean_value(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+    let token = state.limiter.token(token_id);
+
+    let task = move || match readdir_op(path, &token, with_file_types) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = ReadDirFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Reads the contents of a directory synchronously.
+fn readdir_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path and whether to include the entries' types.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let with_file_types = args.get(1).boolean_value(scope);
+
+    match readdir_op(path, &CancelToken::default(), with_file_types) {
+        Ok(directory) => rv.set(create_v8_directory(scope, directory).into()),
+        Err(e) => throw_exception(scope, &e),
+    }
+}
+
+/// Creates a JavaScript array of the entries' names (or of entry objects).
+fn create_v8_directory<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    directory: DirectoryEntries,
+) -> v8::Local<'a, v8::Array> {
+    let directory: Vec<v8::Local<v8::Value>> = directory
+        .into_iter()
+        .map(|(name, file_type)| {
+            let name = v8::String::new(scope, &name.to_string_lossy()).unwrap();
+            let Some(file_type) = file_type else {
+                return name.into();
+            };
+
+            let target = v8::Object::new(scope);
+            let is_file = v8::Boolean::new(scope, file_type.is_file);
+            let is_directory = v8::Boolean::new(scope, file_type.is_directory);
+            let is_symbolic_link = v8::Boolean::new(scope, file_type.is_symbolic_link);
+
+            set_property_to(scope, target, "name", name.into());
+            set_property_to(scope, target, "isFile", is_file.into());
+            set_property_to(scope, target, "isDirectory", is_directory.into());
+            set_property_to(scope, target, "isSymbolicLink", is_symbolic_link.into());
+
+            target.into()
+        })
+        .collect();
+
+    v8::Array::new_with_elements(scope, &directory)
+}
+
+/// Describes what will run after the async opendir completes.
+struct FsOpendirFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+    handle: DirHandle,
+}
+
+impl JsFuture for FsOpendirFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // Something went wrong while opening the directory.
+        if let Some(Err(e)) = self.maybe_result.take() {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        // Otherwise, resolve with the directory's wrapper.
+        let dir_wrap = create_dir_wrap(scope, self.handle.clone());
+        self.promise
+            .open(scope)
+            .resolve(scope, dir_wrap.into())
+            .unwrap();
+    }
+}
+
+/// Opens a directory (for reading its entries one by one) asynchronously.
+fn opendir(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get directory path.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // Note: The task fills the shared handle that the future wraps later on.
+    let handle = DirHandle::default();
+
+    // The actual async task.
+    let task = {
+        let handle = handle.clone();
+        move || match fs::read_dir(path) {
+            Ok(entries) => {
+                *handle.lock().unwrap() = Some(entries);
+                None
+            }
+            Err(e) => Some(Result::Err(anyhow!(e))),
+        }
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsOpendirFuture {
+                promise,
+                maybe_result,
+                handle,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Opens a directory (for reading its entries one by one) synchronously.
+fn opendir_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get directory path.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            let handle = Arc::new(Mutex::new(Some(entries)));
+            rv.set(create_dir_wrap(scope, handle).into());
+        }
+        Err(e) => throw_exception(scope, &anyhow!(e)),
+    }
+}
+
+/// Wraps an open directory into a JavaScript object.
+fn create_dir_wrap<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    handle: DirHandle,
+) -> v8::Local<'a, v8::Object> {
+    let dir_wrap = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    dir_wrap.set_internal_field_count(1);
+
+    let dir_wrap = dir_wrap.new_instance(scope).unwrap();
+    set_internal_ref(scope, dir_wrap, 0, handle);
+
+    dir_wrap
+}
+
+/// Describes what will run after the async read_dir_entry_op completes.
+struct FsReadDirEntryFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for FsReadDirEntryFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<Option<DirEntryInfo>>);
+
+        // Something went wrong while reading the directory.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        // Otherwise, resolve with the entry (or null at the end).
+        let entry = create_v8_dir_entry(scope, result.unwrap());
+        self.promise.open(scope).resolve(scope, entry).unwrap();
+    }
+}
+
+/// Reads asynchronously the next entry of an open directory.
+fn read_dir_entry(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the dir_wrap object.
+    let dir_wrap = args.get(0).to_object(scope).unwrap();
+    let handle = get_internal_ref::<DirHandle>(scope, dir_wrap, 0).clone();
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match read_dir_entry_op(&handle) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsReadDirEntryFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Reads synchronously the next entry of an open directory.
+fn read_dir_entry_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the dir_wrap object.
+    let dir_wrap = args.get(0).to_object(scope).unwrap();
+    let handle = get_internal_ref::<DirHandle>(scope, dir_wrap, 0).clone();
+
+    match read_dir_entry_op(&handle) {
+        Ok(entry) => rv.set(create_v8_dir_entry(scope, entry)),
+        Err(e) => throw_exception(scope, &e),
+    }
+}
+
+/// Closes an open directory (dropping the underlying `ReadDir`).
+fn closedir(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get the dir_wrap object.
+    let dir_wrap = args.get(0).to_object(scope).unwrap();
+    let handle = get_internal_ref::<DirHandle>(scope, dir_wrap, 0);
+
+    if handle.lock().unwrap().take().is_none() {
+        throw_exception(scope, &anyhow!("Directory is closed."));
+    }
+}
+
+/// Creates a JavaScript directory entry object (or null at the end).
+fn create_v8_dir_entry<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    entry: Option<DirEntryInfo>,
+) -> v8::Local<'a, v8::Value> {
+    let Some(entry) = entry else {
+        return v8::null(scope).into();
+    };
+
+    let target = v8::Object::new(scope);
+    let name = v8::String::new(scope, &entry.name).unwrap();
+    let is_directory = v8::Boolean::new(scope, entry.is_directory);
+    let is_file = v8::Boolean::new(scope, entry.is_file);
+    let is_symbolic_link = v8::Boolean::new(scope, entry.is_symbolic_link);
+
+    set_property_to(scope, target, "name", name.into());
+    set_property_to(scope, target, "isDirectory", is_directory.into());
+    set_property_to(scope, target, "isFile", is_file.into());
+    set_property_to(scope, target, "isSymbolicLink", is_symbolic_link.into());
+
+    target.into()
+}
+
+/// Describes what will run after the async rm_op completes.
+struct FsRmFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for FsRmFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // If the result is None then mkdir worked.
+        if self.maybe_result.is_none() {
+            let undefined = v8::undefined(scope);
+            self.promise
+                .open(scope)
+                .resolve(scope, undefined.into())
+                .unwrap();
+
+            return;
+        }
+
+        // Something went wrong.
+        let result = self.maybe_result.take().unwrap();
+
+        // Something went wrong while getting the file's stats.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        unreachable!();
+    }
+}
+
+/// Removes files and directories asynchronously.
+fn rm(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    // Get to be removed folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+    let token_id = get_token_id(scope, args.get(1));
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+    let token = token_id.map(|id| state.limiter.token(Some(id)));
+
+    let task = move || match rm_op(path, token.as_ref()) {
+        Ok(_) => None,
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsRmFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Removes files and directories.
+fn rm_sync(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get to be removed folder location.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    if let Err(e) = rm_op(path, None) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Closes a file asynchronously.
+fn close(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the file_wrap object.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    if let Some(file) = get_internal_ref::<Option<File>>(scope, file_wrap, 0).take() {
+        // Note: By taking the file reference out of the option and immediately dropping
+        // it will make rust to close the file.
+        drop(file);
+
+        let success = v8::Boolean::new(scope, true);
+        promise_resolver.resolve(scope, success.into());
+        rv.set(promise.into());
+
+        return;
+    }
+
+    let message = v8::String::new(scope, "File is closed.").unwrap();
+    let exception = v8::Exception::error(scope, message);
+
+    promise_resolver.reject(scope, exception);
+    rv.set(promise.into());
+}
+
+/// Closes a file synchronously.
+fn close_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the file_wrap object.
+    let file_wrap = args.get(0).to_object(scope).unwrap();
+
+    if let Some(file) = get_internal_ref::<Option<File>>(scope, file_wrap, 0).take() {
+        // Note: By taking the file reference out of the option and immediately dropping
+        // it will make rust to close the file.
+        return drop(file);
+    }
+
+    throw_exception(scope, &anyhow!("File is closed."));
+}
+
+/// Describes what will run after the async rename_op completes.
+struct FsRenameFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for FsRenameFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // If the result is None then renaming worked.
+        if self.maybe_result.is_none() {
+            let undefined = v8::undefined(scope);
+            self.promise
+                .open(scope)
+                .resolve(scope, undefined.into())
+                .unwrap();
+
+            return;
+        }
+
+        // Something went wrong.
+        let result = self.maybe_result.take().unwrap();
+
+        // Something went wrong while renaming the file.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        unreachable!();
+    }
+}
+
+/// Renames a file asynchronously.
+fn rename(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get `from` and `to` values.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match rename_op(from, to) {
+        Ok(_) => None,
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsRenameFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Renames a file synchronously.
+fn rename_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get `from` and `to` values.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+
+    if let Err(e) = rename_op(from, to) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Describes what will run after the async copy_file_op completes.
+struct FsCopyFileFuture {
+    promise: v8::Global<v8::PromiseResolver>,
+    maybe_result: TaskResult,
+}
+
+impl JsFuture for FsCopyFileFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        let result = self.maybe_result.take().unwrap();
+        let result = result.and_then(decode_result::<u64>);
+
+        // Something went wrong while copying the file.
+        if let Err(e) = result {
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            self.promise.open(scope).reject(scope, exception);
+            return;
+        }
+
+        // Otherwise, resolve with the amount of bytes copied.
+        let bytes = v8::Number::new(scope, result.unwrap() as f64);
+        self.promise
+            .open(scope)
+            .resolve(scope, bytes.into())
+            .unwrap();
+    }
+}
+
+/// Copies a file asynchronously.
+fn copy_file(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get `from`, `to` and the copy flags.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+    let flags = args.get(2).uint32_value(scope).unwrap_or_default();
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match copy_file_op(from, to, flags) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsCopyFileFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Copies a file synchronously.
+fn copy_file_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get `from`, `to` and the copy flags.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+    let flags = args.get(2).uint32_value(scope).unwrap_or_default();
+
+    match copy_file_op(from, to, flags) {
+        Ok(bytes) => rv.set(v8::Number::new(scope, bytes as f64).into()),
+        Err(e) => throw_exception(scope, &e),
+    }
+}
+
+/// Reads the `recursive`, `force` and `errorOnExist` copy options.
+fn get_copy_options(
+    scope: &mut v8::HandleScope,
+    args: &v8::FunctionCallbackArguments,
+) -> CopyOptions {
+    CopyOptions {
+        recursive: args.get(2).boolean_value(scope),
+        force: args.get(3).boolean_value(scope),
+        error_on_exist: args.get(4).boolean_value(scope),
+    }
+}
+
+/// Copies files and directories asynchronously.
+fn cp(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    // Get `from`, `to` and the copy options.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+    let options = get_copy_options(scope, &args);
+
+    let promise = spawn_fs_op(scope, move || cp_op(from, to, options));
+    rv.set(promise.into());
+}
+
+/// Copies files and directories synchronously.
+fn cp_sync(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get `from`, `to` and the copy options.
+    let from = args.get(0).to_rust_string_lossy(scope);
+    let to = args.get(1).to_rust_string_lossy(scope);
+    let options = get_copy_options(scope, &args);
+
+    if let Err(e) = cp_op(from, to, options) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Creates a symbolic link asynchronously.
+fn symlink(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the link's `target`, `path` and type (only used on windows).
+    let target = args.get(0).to_rust_string_lossy(scope);
+    let path = args.get(1).to_rust_string_lossy(scope);
+    let is_dir = args.get(2).to_rust_string_lossy(scope) == "dir";
+
+    let promise = spawn_fs_op(scope, move || symlink_op(target, path, is_dir));
+    rv.set(promise.into());
+}
+
+/// Creates a symbolic link synchronously.
+fn symlink_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the link's `target`, `path` and type (only used on windows).
+    let target = args.get(0).to_rust_string_lossy(scope);
+    let path = args.get(1).to_rust_string_lossy(scope);
+    let is_dir = args.get(2).to_rust_string_lossy(scope) == "dir";
+
+    if let Err(e) = symlink_op(target, path, is_dir) {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Reads the target of a symbolic link asynchronously.
+fn readlink(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the link's path.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match readlink_op(path) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsRealpathFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Reads the target of a symbolic link synchronously.
+fn readlink_sync(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the link's path.
+    let path = args.get(0).to_rust_string_lossy(scope);
+
+    match readlink_op(path) {
+        Ok(target) => rv.set(v8::String::new(scope, &target).unwrap().into()),
+        Err(e) => throw_exception(scope, &e),
+    };
+}
 
 struct WatchFuture {
     event: FsEvent,
+    root: Rc<str>,
     on_event_cb: Rc<v8::Global<v8::Function>>,
 }
 
-impl JsFuture for WatchFuture {
-    fn run(&mut self, scope: &mut v8::HandleScope) {
-        // Create a v8 array.
-        let paths: Vec<v8::Local<v8::Value>> = self
-            .event
-            .paths
-            .iter()
-            .map(|path| path.to_str().unwrap())
-            .map(|path| v8::String::new(scope, path).unwrap())
-            .map(|path_value| path_value.into())
+impl JsFuture for WatchFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // Create a v8 array.
+        let paths: Vec<v8::Local<v8::Value>> = self
+            .event
+            .paths
+            .iter()
+            .map(|path| path.to_str().unwrap())
+            .map(|path| v8::String::new(scope, path).unwrap())
+            .map(|path_value| path_value.into())
+            .collect();
+
+        let paths_value = v8::Array::new_with_elements(scope, &paths);
+
+        // Format the event type.
+        let kind = match self.event.kind {
+            FsEventKind::Any => v8::String::new(scope, "any"),
+            FsEventKind::Access(_) => v8::String::new(scope, "access"),
+            FsEventKind::Create(_) => v8::String::new(scope, "create"),
+            FsEventKind::Modify(_) => v8::String::new(scope, "modify"),
+            FsEventKind::Remove(_) => v8::String::new(scope, "remove"),
+            FsEventKind::Other => v8::String::new(scope, "other"),
+        };
+
+        let root = v8::String::new(scope, &self.root).unwrap();
+
+        let event = v8::Object::new(scope);
+        set_constant_to(scope, event, "kind", kind.unwrap().into());
+        set_constant_to(scope, event, "paths", paths_value.into());
+        set_constant_to(scope, event, "root", root.into());
+
+        // Get access to the on_read callback.
+        let on_event = v8::Local::new(scope, (*self.on_event_cb).clone());
+        let undefined = v8::undefined(scope).into();
+
+        on_event.call(scope, undefined, &[event.into()]);
+    }
+}
+
+#[derive(Default)]
+/// Events held back until a watched root has been quiet for the debounce period.
+struct PendingFsEvents {
+    events: Vec<FsEvent>,
+    timer: Option<u32>,
+}
+
+impl PendingFsEvents {
+    /// Drops the held back events, cancelling their delivery.
+    fn cancel(&mut self, handle: &LoopHandle) {
+        self.events.clear();
+        if let Some(timer) = self.timer.take() {
+            handle.remove_timer(&timer);
+        }
+    }
+}
+
+/// The event-loop watchers started by a single `watch` call.
+#[derive(Default)]
+struct WatchGroup {
+    indexes: Vec<u32>,
+    pending: Vec<Rc<RefCell<PendingFsEvents>>>,
+}
+
+thread_local! {
+    /// The watch groups of the runtime (keyed by the id `watch` returned).
+    static WATCH_GROUPS: RefCell<HashMap<u32, WatchGroup>> = RefCell::new(HashMap::new());
+}
+
+/// Starts a watcher for the requested path(s).
+fn watch(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the path(s) to watch (a single string or an array of them).
+    let paths: Vec<String> = match v8::Local::<v8::Array>::try_from(args.get(0)) {
+        Ok(paths) => (0..paths.length())
+            .map(|i| paths.get_index(scope, i).unwrap())
+            .map(|path| path.to_rust_string_lossy(scope))
+            .collect(),
+        Err(_) => vec![args.get(0).to_rust_string_lossy(scope)],
+    };
+
+    // Get recursive and debounce options.
+    let recursive = args.get(1).boolean_value(scope);
+    let debounce_ms = args.get(3).integer_value(scope).unwrap_or(0).max(0) as u64;
+
+    // Get the on_event callback.
+    let on_event_cb = v8::Local::<v8::Function>::try_from(args.get(2)).unwrap();
+    let on_event_cb = Rc::new(v8::Global::new(scope, on_event_cb));
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+    let mut group = WatchGroup::default();
+
+    // Note: Every path gets its own event-loop watcher, all of them sharing the JS callback.
+    for path in paths {
+        let root: Rc<str> = Rc::from(path.as_str());
+        let pending = Rc::new(RefCell::new(PendingFsEvents::default()));
+        let on_event = watch_callback(&state_rc, &on_event_cb, root, &pending, debounce_ms);
+
+        match state.handle.fs_event_start(path, recursive, on_event) {
+            Ok(index) => {
+                group.indexes.push(index);
+                group.pending.push(pending);
+            }
+            Err(e) => {
+                // Don't leave the already started watchers behind.
+                for index in &group.indexes {
+                    state.handle.fs_event_stop(index);
+                }
+                throw_exception(scope, &e);
+                return;
+            }
+        }
+    }
+
+    // The first watcher's index identifies the whole group.
+    let Some(&id) = group.indexes.first() else {
+        let e = anyhow!("At least one path must be watched.");
+        throw_exception(scope, &e);
+        return;
+    };
+
+    WATCH_GROUPS.with(|groups| groups.borrow_mut().insert(id, group));
+
+    rv.set(v8::Integer::new(scope, id as i32).into());
+}
+
+/// Returns the event-loop callback of a watched root (coalescing events if debounced).
+fn watch_callback(
+    state_rc: &Rc<RefCell<JsRuntimeState>>,
+    on_event_cb: &Rc<v8::Global<v8::Function>>,
+    root: Rc<str>,
+    pending: &Rc<RefCell<PendingFsEvents>>,
+    debounce_ms: u64,
+) -> impl FnMut(LoopHandle, FsEvent) + 'static {
+    let state_rc = state_rc.clone();
+    let on_event_cb = Rc::clone(on_event_cb);
+    let pending = Rc::clone(pending);
+
+    move |handle: LoopHandle, event: FsEvent| {
+        // Cached lookups of the changed paths are no longer trustworthy.
+        event.paths.iter().for_each(stat_cache::invalidate);
+
+        if debounce_ms == 0 {
+            let mut state = state_rc.borrow_mut();
+            let future = WatchFuture {
+                event,
+                root: Rc::clone(&root),
+                on_event_cb: Rc::clone(&on_event_cb),
+            };
+            state.pending_futures.push(Box::new(future));
+            return;
+        }
+
+        // Note: A later event for the same paths replaces the earlier one, so
+        // a burst (e.g. write-then-rename) ends up as a single callback.
+        let mut pending_events = pending.borrow_mut();
+        let events = &mut pending_events.events;
+        match events.iter().position(|e| e.paths == event.paths) {
+            Some(index) => events[index] = event,
+            None => events.push(event),
+        }
+
+        // Note: Every event restarts the window, so the events are only delivered
+        // once the root has been quiet for the whole debounce period.
+        if let Some(timer) = pending_events.timer.take() {
+            handle.remove_timer(&timer);
+        }
+
+        let flush = {
+            let state_rc = state_rc.clone();
+            let pending = Rc::clone(&pending);
+            let root = Rc::clone(&root);
+            let on_event_cb = Rc::clone(&on_event_cb);
+            move |_: LoopHandle| {
+                let events = {
+                    let mut pending = pending.borrow_mut();
+                    pending.timer = None;
+                    std::mem::take(&mut pending.events)
+                };
+
+                let mut state = state_rc.borrow_mut();
+                for event in events {
+                    let future = WatchFuture {
+                        event,
+                        root: Rc::clone(&root),
+                        on_event_cb: Rc::clone(&on_event_cb),
+                    };
+                    state.pending_futures.push(Box::new(future));
+                }
+
+                // Note: Same as timers, wake the event-loop so it doesn't idle
+                // in the poll phase while the JS futures are ready.
+                if !state.wake_event_queued {
+                    state.interrupt_handle.interrupt();
+                    state.wake_event_queued = true;
+                }
+            }
+        };
+
+        pending_events.timer = Some(handle.timer(debounce_ms, false, flush));
+    }
+}
+
+/// Stops a running watcher (and every path it was watching).
+fn unwatch(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get the rid of the watcher.
+    let index = args.get(0).int32_value(scope).unwrap() as u32;
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    let group = WATCH_GROUPS.with(|groups| groups.borrow_mut().remove(&index));
+    let group = group.unwrap_or_else(|| WatchGroup {
+        indexes: vec![index],
+        ..Default::default()
+    });
+
+    for index in &group.indexes {
+        state.handle.fs_event_stop(index);
+    }
+
+    // Events still waiting for their debounce window are dropped as well.
+    for pending in &group.pending {
+        pending.borrow_mut().cancel(&state.handle);
+    }
+}
+
+/// Creates a token that cancels the fs tasks it's passed to.
+fn create_cancel_token(
+    scope: &mut v8::HandleScope,
+    _: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let state_rc = JsRuntime::state(scope);
+    let id = state_rc.borrow().limiter.create_token();
+    rv.set(v8::Number::new(scope, id as f64).into());
+}
+
+/// Cancels the fs tasks holding the token (and releases it).
+fn cancel(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    if let Some(id) = get_token_id(scope, args.get(0)) {
+        let state_rc = JsRuntime::state(scope);
+        state_rc.borrow().limiter.cancel(id);
+    }
+}
+
+/// Sends a file descriptor over a (connected) unix socket asynchronously.
+fn send_fd(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the socket and the file descriptor to pass.
+    let socket = args.get(0).integer_value(scope).unwrap() as i32;
+    let fd = args.get(1).integer_value(scope).unwrap() as i32;
+
+    let promise = spawn_fs_op(scope, move || send_fd_op(socket, fd));
+    rv.set(promise.into());
+}
+
+/// Receives a file descriptor from a (connected) unix socket asynchronously.
+fn recv_fd(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the socket to receive from.
+    let socket = args.get(0).integer_value(scope).unwrap() as i32;
+
+    // Create a promise resolver and extract the actual promise.
+    let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+    let promise = promise_resolver.get_promise(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    // The actual async task.
+    let task = move || match recv_fd_op(socket) {
+        Ok(result) => Some(Ok(bincode::serialize(&result).unwrap())),
+        Err(e) => Some(Result::Err(e)),
+    };
+
+    // The callback that will run after the above task completes.
+    // Note: The received descriptor is wrapped the same way an opened file is.
+    let task_cb = {
+        let promise = v8::Global::new(scope, promise_resolver);
+        let state_rc = state_rc.clone();
+
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = FsOpenFuture {
+                promise,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    };
+
+    // Spawn the async task using the event-loop.
+    state
+        .limiter
+        .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+    rv.set(promise.into());
+}
+
+/// Returns the cancel token id passed from JavaScript (if any).
+fn get_token_id(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> Option<usize> {
+    match value.is_number() {
+        true => value.integer_value(scope).map(|id| id as usize),
+        false => None,
+    }
+}
+
+#[cfg(target_family = "unix")]
+fn get_file_reference(fd: usize) -> File {
+    unsafe { fs::File::from_raw_fd(fd as RawFd) }
+}
+
+#[cfg(target_family = "windows")]
+fn get_file_reference(handle: usize) -> File {
+    unsafe { fs::File::from_raw_handle(handle as RawHandle) }
+}
+
+/// Pure rust implementation of opening a file.
+fn open_file_op<P: AsRef<Path>>(path: P, flags: String) -> Result<usize> {
+    // Options and flags which can be used to configure how a file is opened.
+    let read = flags == "r" || flags == "r+" || flags == "w+" || flags == "a+";
+    let write = flags == "r+" || flags == "w" || flags == "w+";
+    let create = flags == "w" || flags == "w+" || flags == "a" || flags == "a+";
+    let truncate = flags == "w+";
+    let append = flags == "a" || flags == "a+";
+
+    let result = OpenOptions::new()
+        .read(read)
+        .write(write)
+        .create(create)
+        .append(append)
+        .truncate(truncate)
+        .open(&path);
+
+    if create {
+        stat_cache::invalidate(&path);
+    }
+
+    // Note: The reason we leak the wrapped file handle is to prevent rust
+    // from dropping the handle (a.k.a close the file) when current scope ends.
+    match result {
+        #[cfg(target_family = "unix")]
+        Ok(file) => Ok(Box::leak(Box::new(file)).as_raw_fd() as usize),
+        #[cfg(target_family = "windows")]
+        Ok(file) => Ok(Box::leak(Box::new(file)).as_raw_handle() as usize),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Pure rust implementation of reading a chunk from a file.
+fn read_file_op(file: &mut File, size: i64, offset: i64) -> Result<(usize, Vec<u8>)> {
+    // Move file cursor to requested position.
+    if let Err(e) = file.seek(SeekFrom::Start(offset as u64)) {
+        bail!(e);
+    }
+
+    let mut buffer = vec![0; size as usize];
+
+    // Read at most `size` bytes from the file.
+    match file.take(size as u64).read(&mut buffer) {
+        Ok(n) => Ok((n, buffer)),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Pure rust implementation of reading the entire contents of a file.
+fn read_whole_file_op<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|e| anyhow!(e))
+}
+
+/// Pure rust implementation of writing bytes to a file.
+fn write_file_op(file: &mut File, buffer: &[u8]) -> Result<()> {
+    // Write buffer to file.
+    if let Err(e) = file.write_all(buffer) {
+        bail!(e);
+    }
+    Ok(())
+}
+
+/// Pure rust implementation of writing bytes at a position of the file.
+///
+/// Note: Positioned writes (pwrite) don't go through the shared cursor, so
+/// concurrent calls on clones of the same descriptor can't interfere and
+/// the cursor stays where it was.
+#[cfg(target_family = "unix")]
+fn write_at_op(file: &File, buffer: &[u8], offset: u64) -> Result<usize> {
+    use std::os::unix::fs::FileExt;
+    file.write_all_at(buffer, offset)?;
+    Ok(buffer.len())
+}
+
+/// Pure rust implementation of writing bytes at a position of the file.
+#[cfg(target_family = "windows")]
+fn write_at_op(file: &File, buffer: &[u8], offset: u64) -> Result<usize> {
+    use std::os::windows::fs::FileExt;
+
+    // Note: Unlike unix, `seek_write` moves the cursor so we put it back.
+    let mut file = file;
+    let cursor = file.stream_position()?;
+    let mut written = 0;
+
+    while written < buffer.len() {
+        let position = offset + written as u64;
+        written += file.seek_write(&buffer[written..], position)?;
+    }
+
+    file.seek(SeekFrom::Start(cursor))?;
+    Ok(written)
+}
+
+/// Pure rust implementation of creating a symbolic link.
+#[cfg(target_family = "unix")]
+fn symlink_op<P: AsRef<Path>>(target: P, path: P, _is_dir: bool) -> Result<()> {
+    let result = std::os::unix::fs::symlink(target, &path).map_err(|e| anyhow!(e));
+    invalidate_stat_cache(&[path.as_ref()], result)
+}
+
+/// Pure rust implementation of creating a symbolic link.
+#[cfg(target_family = "windows")]
+fn symlink_op<P: AsRef<Path>>(target: P, path: P, is_dir: bool) -> Result<()> {
+    // Note: Windows needs to know up front what the link points to.
+    let result = match is_dir {
+        true => std::os::windows::fs::symlink_dir(target, &path),
+        false => std::os::windows::fs::symlink_file(target, &path),
+    };
+    invalidate_stat_cache(&[path.as_ref()], result.map_err(|e| anyhow!(e)))
+}
+
+/// Pure rust implementation of reading a symbolic link's target.
+fn readlink_op<P: AsRef<Path>>(path: P) -> Result<String> {
+    match fs::read_link(path) {
+        Ok(target) => Ok(target.to_string_lossy().into_owned()),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Pure rust implementation of appending to a file.
+fn append_file_op<P: AsRef<Path>>(path: P, buffer: &[u8]) -> Result<()> {
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| anyhow!(e))
+        .and_then(|mut file| write_file_op(&mut file, buffer));
+
+    invalidate_stat_cache(&[path.as_ref()], result)
+}
+
+/// Pure rust implementation of truncating (or zero-extending) a file.
+fn truncate_op<P: AsRef<Path>>(path: P, len: u64) -> Result<()> {
+    let result = OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_len(len))
+        .map_err(|e| anyhow!(e));
+
+    invalidate_stat_cache(&[path.as_ref()], result)
+}
+
+/// Pure rust implementation of getting file statistics.
+fn stats_op<P: AsRef<Path>>(path: P, cache: bool) -> Result<FileStatistics> {
+    // Try get file's metadata information.
+    let metadata = match cache {
+        true => stat_cache::metadata(path),
+        false => fs::metadata(path),
+    };
+
+    match metadata {
+        Ok(metadata) => Ok(file_statistics(metadata)),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Pure rust implementation of getting file statistics (without following links).
+fn lstats_op<P: AsRef<Path>>(path: P) -> Result<FileStatistics> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) => Ok(file_statistics(metadata)),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Collects the file statistics out of the file's metadata.
+fn file_statistics(metadata: fs::Metadata) -> FileStatistics {
+    // Returns the size of the file, in bytes, this metadata is for.
+    let size = metadata.len();
+
+    // Returns the last access time of this metadata.
+    let access_time = metadata
+        .accessed()
+        .ok()
+        .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
+
+    // Returns the last modification time listed in this metadata.
+    let modified_time = metadata
+        .modified()
+        .ok()
+        .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
+
+    // Returns the creation time listed in this metadata.
+    let birth_time = metadata
+        .created()
+        .ok()
+        .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
+
+    let is_directory = metadata.is_dir();
+    let is_file = metadata.is_file();
+    let is_symbolic_link = metadata.is_symlink();
+
+    #[allow(unused_mut)]
+    let mut stats = FileStatistics {
+        size,
+        access_time,
+        modified_time,
+        birth_time,
+        is_directory,
+        is_file,
+        is_symbolic_link,
+        ..Default::default()
+    };
+
+    // In UNIX systems we can get some extra info.
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::fs::MetadataExt;
+
+        stats.is_socket = Some(metadata.file_type().is_socket());
+        stats.is_fifo = Some(metadata.file_type().is_fifo());
+        stats.is_block_device = Some(metadata.file_type().is_block_device());
+        stats.is_character_device = Some(metadata.file_type().is_char_device());
+        stats.blocks = Some(metadata.blocks());
+        stats.block_size = Some(metadata.blksize());
+        stats.mode = Some(metadata.mode());
+        stats.device = Some(metadata.dev());
+        stats.group_id = Some(metadata.gid());
+        stats.inode = Some(metadata.ino());
+        stats.hard_links = Some(metadata.nlink());
+        stats.rdev = Some(metadata.rdev());
+    }
+
+    stats
+}
+
+/// Pure rust implementation of checking the access to a path.
+fn access_op<P: AsRef<Path>>(path: P, mode: u32) -> Result<()> {
+    let path = path.as_ref();
+    let metadata = fs::metadata(path)?;
+
+    let granted = access_granted(&metadata);
+    let wanted = mode & (R_OK | W_OK | X_OK);
+
+    if granted & wanted != wanted {
+        let message = format!("EACCES: permission denied, access '{}'", path.display());
+        bail!(io::Error::new(io::ErrorKind::PermissionDenied, message));
+    }
+
+    Ok(())
+}
+
+/// Returns the access (R_OK | W_OK | X_OK) the effective user has.
+#[cfg(target_family = "unix")]
+fn access_granted(metadata: &fs::Metadata) -> u32 {
+    use nix::unistd::{getegid, geteuid};
+    use std::os::unix::fs::MetadataExt;
+
+    let mode = metadata.mode();
+
+    // Note: Root bypasses the permission bits, except that it can only execute
+    // files that are executable by someone.
+    if geteuid().is_root() {
+        let execute = if mode & 0o111 != 0 { X_OK } else { 0 };
+        return R_OK | W_OK | execute;
+    }
+
+    // Note: Supplementary groups aren't taken into account.
+    let bits = match (metadata.uid(), metadata.gid()) {
+        (uid, _) if uid == geteuid().as_raw() => mode >> 6,
+        (_, gid) if gid == getegid().as_raw() => mode >> 3,
+        _ => mode,
+    };
+
+    bits & 0o7
+}
+
+/// Returns the access (R_OK | W_OK | X_OK) the current user has.
+#[cfg(target_family = "windows")]
+fn access_granted(metadata: &fs::Metadata) -> u32 {
+    // Note: Windows only tells us whether the file is read-only.
+    match metadata.permissions().readonly() {
+        true => R_OK | X_OK,
+        false => R_OK | W_OK | X_OK,
+    }
+}
+
+/// Pure rust implementation of resolving a path to its canonical form.
+fn realpath_op<P: AsRef<Path>>(path: P, cache: bool) -> Result<String> {
+    let path = match cache {
+        true => stat_cache::canonicalize(path),
+        false => fs::canonicalize(path),
+    };
+
+    match path {
+        Ok(path) => Ok(strip_verbatim_prefix(path.to_string_lossy().into_owned())),
+        Err(e) => bail!(e),
+    }
+}
+
+/// Turns windows' verbatim paths (that `canonicalize` returns) into regular
+/// ones, since most programs (and APIs) don't understand them.
+fn strip_verbatim_prefix(path: String) -> String {
+    if let Some(path) = path.strip_prefix(r"\\?\UNC\") {
+        return format!(r"\\{path}");
+    }
+    if let Some(path) = path.strip_prefix(r"\\?\") {
+        return path.to_string();
+    }
+    path
+}
+
+/// Pure rust implementation of changing a file's access and modification times.
+fn utimes_op<P: AsRef<Path>>(path: P, atime: f64, mtime: f64) -> Result<()> {
+    // Converts a (possibly negative) millisecond timestamp.
+    let to_filetime = |millis: f64| {
+        let secs = (millis / 1000.0).floor();
+        let nanos = ((millis - secs * 1000.0) * 1_000_000.0) as u32;
+        FileTime::from_unix_time(secs as i64, nanos)
+    };
+
+    filetime::set_file_times(path, to_filetime(atime), to_filetime(mtime))?;
+    Ok(())
+}
+
+/// Pure rust implementation of creating a unique (temporary) directory.
+fn mkdtemp_op(prefix: String) -> Result<String> {
+    use rand::distributions::Alphanumeric;
+    use rand::Rng;
+
+    const ATTEMPTS: usize = 100;
+
+    // Note: `create_dir` fails when the directory exists, so two calls can
+    // never end up with the same directory (we just try another name).
+    for _ in 0..ATTEMPTS {
+        let suffix: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(6)
+            .map(char::from)
             .collect();
 
-        let paths_value = v8::Array::new_with_elements(scope, &paths);
+        let path = format!("{prefix}{suffix}");
 
-        // Format the event type.
-        let kind = match self.event.kind {
-            FsEventKind::Any => v8::String::new(scope, "any"),
-            FsEventKind::Access(_) => v8::String::new(scope, "access"),
-            FsEventKind::Create(_) => v8::String::new(scope, "create"),
-            FsEventKind::Modify(_) => v8::String::new(scope, "modify"),
-            FsEventKind::Remove(_) => v8::String::new(scope, "remove"),
-            FsEventKind::Other => v8::String::new(scope, "other"),
-        };
+        match fs::create_dir(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => bail!(e),
+        }
+    }
+
+    bail!("Failed to create a unique directory with prefix \"{prefix}\".")
+}
+
+/// Pure rust implementation of changing a file's permissions.
+#[cfg(target_family = "unix")]
+fn chmod_op<P: AsRef<Path>>(path: P, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|e| anyhow!(e))
+}
+
+/// Pure rust implementation of changing a file's permissions.
+#[cfg(target_family = "windows")]
+fn chmod_op<P: AsRef<Path>>(path: P, mode: u32) -> Result<()> {
+    // Note: Windows only has a read-only flag, which is set when the mode
+    // doesn't grant write access to the owner.
+    let mut permissions = fs::metadata(&path)?.permissions();
+    permissions.set_readonly(mode & 0o200 == 0);
+    fs::set_permissions(path, permissions).map_err(|e| anyhow!(e))
+}
+
+/// Pure rust implementation of changing a file's owner and group.
+#[cfg(target_family = "unix")]
+fn chown_op<P: AsRef<Path>>(path: P, uid: u32, gid: u32) -> Result<()> {
+    std::os::unix::fs::chown(path, Some(uid), Some(gid)).map_err(|e| anyhow!(e))
+}
+
+/// Pure rust implementation of changing a file's owner and group.
+#[cfg(target_family = "windows")]
+fn chown_op<P: AsRef<Path>>(_: P, _: u32, _: u32) -> Result<()> {
+    bail!("Operation not supported")
+}
+
+/// Pure rust implementation of creating directories.
+fn mkdir_op<P: AsRef<Path>>(path: P, recursive: bool) -> Result<()> {
+    let result = match recursive {
+        true => fs::create_dir_all(&path),
+        false => fs::create_dir(&path),
+    };
+    invalidate_stat_cache(&[path.as_ref()], result.map_err(|e| anyhow!(e)))
+}
 
-        let event = v8::Object::new(scope);
-        set_constant_to(scope, event, "kind", kind.unwrap().into());
-        set_constant_to(scope, event, "paths", paths_value.into());
+/// Pure rust implementation of deleting (empty) directories.
+fn rmdir_op<P: AsRef<Path>>(path: P) -> Result<()> {
+    let result = fs::remove_dir(&path).map_err(|e| anyhow!(e));
+    invalidate_stat_cache(&[path.as_ref()], result)
+}
 
-        // Get access to the on_read callback.
-        let on_event = v8::Local::new(scope, (*self.on_event_cb).clone());
-        let undefined = v8::undefined(scope).into();
+/// Pure rust implementation of reading a directory.
+fn readdir_op<P: AsRef<Path>>(
+    path: P,
+    token: &CancelToken,
+    with_file_types: bool,
+) -> Result<DirectoryEntries> {
+    let mut entries = vec![];
+    for entry in fs::read_dir(path)? {
+        token.check()?;
+        let entry = entry?;
+
+        // Note: On most platforms the type comes along with the entry (no stat).
+        let file_type = match with_file_types {
+            true => {
+                let file_type = entry.file_type()?;
+                Some(FileTypeFlags {
+                    is_file: file_type.is_file(),
+                    is_directory: file_type.is_dir(),
+                    is_symbolic_link: file_type.is_symlink(),
+                })
+            }
+            false => None,
+        };
 
-        on_event.call(scope, undefined, &[event.into()]);
+        entries.push((entry.file_name(), file_type));
     }
+    Ok(entries)
 }
 
-/// Starts a watcher for a requested path.
-fn watch(
-    scope: &mut v8::HandleScope,
-    args: v8::FunctionCallbackArguments,
-    mut rv: v8::ReturnValue,
-) {
-    // Get path and recursive option.
-    let path = args.get(0).to_rust_string_lossy(scope);
-    let recursive = args.get(1).boolean_value(scope);
+/// Pure rust implementation of reading the next entry of an open directory.
+fn read_dir_entry_op(handle: &DirHandle) -> Result<Option<DirEntryInfo>> {
+    let mut handle = handle.lock().unwrap();
+    let Some(entries) = handle.as_mut() else {
+        bail!("Directory is closed.");
+    };
 
-    // Get the on_event callback.
-    let on_event_cb = v8::Local::<v8::Function>::try_from(args.get(2)).unwrap();
-    let on_event_cb = Rc::new(v8::Global::new(scope, on_event_cb));
+    let entry = match entries.next() {
+        Some(entry) => entry?,
+        None => return Ok(None),
+    };
 
-    let state_rc = JsRuntime::state(scope);
-    let state = state_rc.borrow();
+    // Note: On most platforms the file type comes along with the entry.
+    let file_type = entry.file_type()?;
 
-    // A Rust wrapper around the JS on_event callback.
-    let on_event = {
-        let state_rc = state_rc.clone();
-        move |_: LoopHandle, event: FsEvent| {
-            let mut state = state_rc.borrow_mut();
-            let future = WatchFuture {
-                event,
-                on_event_cb: Rc::clone(&on_event_cb),
-            };
-            state.pending_futures.push(Box::new(future));
-        }
+    Ok(Some(DirEntryInfo {
+        name: entry.file_name().to_string_lossy().into_owned(),
+        is_directory: file_type.is_dir(),
+        is_file: file_type.is_file(),
+        is_symbolic_link: file_type.is_symlink(),
+    }))
+}
+
+/// Pure rust implementation of deleting files and directories.
+///
+/// Note: Without a cancel token directories are removed by `fs::remove_dir_all`,
+/// which is hardened against symlink races (CVE-2022-21658). The tree is only
+/// walked by hand when the removal has to be cancellable.
+fn rm_op<P: AsRef<Path>>(path: P, token: Option<&CancelToken>) -> Result<()> {
+    if let Some(token) = token {
+        token.check()?;
+    }
+    let path = path.as_ref();
+    let result = match (stats_op(path, false)?.is_directory, token) {
+        (true, Some(token)) => remove_dir_all_op(path, token),
+        (true, None) => fs::remove_dir_all(path).map_err(|e| anyhow!(e)),
+        (false, _) => fs::remove_file(path).map_err(|e| anyhow!(e)),
     };
+    invalidate_stat_cache(&[path], result)
+}
 
-    // Start the watcher.
-    let index = match state.handle.fs_event_start(path, recursive, on_event) {
-        Ok(index) => v8::Integer::new(scope, index as i32),
-        Err(e) => {
-            throw_exception(scope, &e);
-            return;
+/// Removes a directory and its contents, stopping early if cancelled.
+///
+/// Note: Symbolic links are removed, not followed.
+fn remove_dir_all_op(path: &Path, token: &CancelToken) -> Result<()> {
+    for entry in fs::read_dir(path)? {
+        token.check()?;
+        let entry = entry?;
+        match entry.file_type()?.is_dir() {
+            true => remove_dir_all_op(&entry.path(), token)?,
+            false => remove_entry_op(&entry.path())?,
         }
-    };
+    }
+    fs::remove_dir(path).map_err(|e| anyhow!(e))
+}
 
-    rv.set(index.into());
+/// Removes a file (or a symbolic link).
+fn remove_entry_op(path: &Path) -> Result<()> {
+    // Note: On Windows, links to directories have to be removed as directories.
+    match fs::remove_file(path) {
+        Err(_) if cfg!(windows) && path.is_dir() => fs::remove_dir(path)?,
+        result => result?,
+    };
+    Ok(())
 }
 
-/// Stops a running watcher.
-fn unwatch(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
-    // Get the rid of the watcher.
-    let index = args.get(0).int32_value(scope).unwrap() as u32;
-    let state_rc = JsRuntime::state(scope);
-    let state = state_rc.borrow();
+/// Pure rust implementation of renaming a file/directory.
+fn rename_op<P: AsRef<Path>>(from: P, to: P) -> Result<()> {
+    let result = fs::rename(&from, &to).map_err(|e| anyhow!(e));
+    invalidate_stat_cache(&[from.as_ref(), to.as_ref()], result)
+}
 
-    state.handle.fs_event_stop(&index);
+/// Pure rust implementation of copying a file (returns the bytes copied).
+fn copy_file_op<P: AsRef<Path>>(from: P, to: P, flags: u32) -> Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let result = match flags & COPYFILE_EXCL {
+        0 => fs::copy(from, to).map_err(|e| anyhow!(e)),
+        _ => copy_file_excl_op(from, to),
+    };
+    invalidate_stat_cache(&[to], result)
 }
 
-#[cfg(target_family = "unix")]
-fn get_file_reference(fd: usize) -> File {
-    unsafe { fs::File::from_raw_fd(fd as RawFd) }
+/// Copies a file, failing if the destination already exists.
+fn copy_file_excl_op(from: &Path, to: &Path) -> Result<u64> {
+    // Note: Creating the destination up front makes the existence check atomic, but
+    // the source is checked first so a bad source doesn't leave an empty file behind.
+    fs::metadata(from)?;
+    OpenOptions::new().write(true).create_new(true).open(to)?;
+
+    fs::copy(from, to).map_err(|e| {
+        let _ = fs::remove_file(to);
+        anyhow!(e)
+    })
 }
 
-#[cfg(target_family = "windows")]
-fn get_file_reference(handle: usize) -> File {
-    unsafe { fs::File::from_raw_handle(handle as RawHandle) }
+/// Pure rust implementation of copying files and directories.
+fn cp_op<P: AsRef<Path>>(from: P, to: P, options: CopyOptions) -> Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let result = cp_path_op(from, to, options);
+    invalidate_stat_cache(&[to], result)
 }
 
-/// Pure rust implementation of opening a file.
-fn open_file_op<P: AsRef<Path>>(path: P, flags: String) -> Result<usize> {
-    // Options and flags which can be used to configure how a file is opened.
-    let read = flags == "r" || flags == "r+" || flags == "w+" || flags == "a+";
-    let write = flags == "r+" || flags == "w" || flags == "w+";
-    let create = flags == "w" || flags == "w+" || flags == "a" || flags == "a+";
-    let truncate = flags == "w+";
-    let append = flags == "a" || flags == "a+";
+/// Copies a file or (recursively) a directory.
+fn cp_path_op(from: &Path, to: &Path, options: CopyOptions) -> Result<()> {
+    let metadata = fs::metadata(from)?;
 
-    // Note: The reason we leak the wrapped file handle is to prevent rust
-    // from dropping the handle (a.k.a close the file) when current scope ends.
-    match OpenOptions::new()
-        .read(read)
-        .write(write)
-        .create(create)
-        .append(append)
-        .truncate(truncate)
-        .open(path)
-    {
-        #[cfg(target_family = "unix")]
-        Ok(file) => Ok(Box::leak(Box::new(file)).as_raw_fd() as usize),
-        #[cfg(target_family = "windows")]
-        Ok(file) => Ok(Box::leak(Box::new(file)).as_raw_handle() as usize),
-        Err(e) => bail!(e),
+    if !metadata.is_dir() {
+        return cp_entry_op(from, to, options);
     }
-}
 
-/// Pure rust implementation of reading a chunk from a file.
-fn read_file_op(file: &mut File, size: i64, offset: i64) -> Result<(usize, Vec<u8>)> {
-    // Move file cursor to requested position.
-    if let Err(e) = file.seek(SeekFrom::Start(offset as u64)) {
-        bail!(e);
+    if !options.recursive {
+        let message = format!("EISDIR: is a directory, cp '{}'", from.display());
+        bail!(io::Error::new(io::ErrorKind::IsADirectory, message));
     }
 
-    let mut buffer = vec![0; size as usize];
-
-    // Read at most `size` bytes from the file.
-    match file.take(size as u64).read(&mut buffer) {
-        Ok(n) => Ok((n, buffer)),
-        Err(e) => bail!(e),
+    // Note: Copying a directory into itself would never run out of entries.
+    if std::path::absolute(to)?.starts_with(std::path::absolute(from)?) {
+        let message = format!("cannot copy '{}' into itself", from.display());
+        bail!(io::Error::new(io::ErrorKind::InvalidInput, message));
     }
+
+    cp_dir_op(from, to, &metadata, options)
 }
 
-/// Pure rust implementation of writing bytes to a file.
-fn write_file_op(file: &mut File, buffer: &[u8]) -> Result<()> {
-    // Write buffer to file.
-    if let Err(e) = file.write_all(buffer) {
-        bail!(e);
+/// Recreates a directory (and everything under it) at the destination.
+fn cp_dir_op(from: &Path, to: &Path, metadata: &fs::Metadata, options: CopyOptions) -> Result<()> {
+    // Note: Existing directories are merged into, not replaced.
+    if !to.is_dir() {
+        fs::create_dir(to)?;
     }
-    Ok(())
-}
 
-/// Pure rust implementation of getting file statistics.
-fn stats_op<P: AsRef<Path>>(path: P) -> Result<FileStatistics> {
-    // Try get file's metadata information.
-    match fs::metadata(path) {
-        Ok(metadata) => {
-            // Returns the size of the file, in bytes, this metadata is for.
-            let size = metadata.len();
-
-            // Returns the last access time of this metadata.
-            let access_time = metadata
-                .accessed()
-                .ok()
-                .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
-
-            // Returns the last modification time listed in this metadata.
-            let modified_time = metadata
-                .modified()
-                .ok()
-                .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
-
-            // Returns the creation time listed in this metadata.
-            let birth_time = metadata
-                .created()
-                .ok()
-                .map(|time| time.duration_since(UNIX_EPOCH).unwrap());
-
-            let is_directory = metadata.is_dir();
-            let is_file = metadata.is_file();
-            let is_symbolic_link = metadata.is_symlink();
-
-            #[allow(unused_mut)]
-            let mut stats = FileStatistics {
-                size,
-                access_time,
-                modified_time,
-                birth_time,
-                is_directory,
-                is_file,
-                is_symbolic_link,
-                ..Default::default()
-            };
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        match entry.file_type()?.is_dir() {
+            true => cp_dir_op(&entry.path(), &destination, &entry.metadata()?, options)?,
+            false => cp_entry_op(&entry.path(), &destination, options)?,
+        }
+    }
 
-            // In UNIX systems we can get some extra info.
-            #[cfg(target_family = "unix")]
-            {
-                use std::os::unix::fs::FileTypeExt;
-                use std::os::unix::fs::MetadataExt;
-
-                stats.is_socket = Some(metadata.file_type().is_socket());
-                stats.is_fifo = Some(metadata.file_type().is_fifo());
-                stats.is_block_device = Some(metadata.file_type().is_block_device());
-                stats.is_character_device = Some(metadata.file_type().is_char_device());
-                stats.blocks = Some(metadata.blocks());
-                stats.block_size = Some(metadata.blksize());
-                stats.mode = Some(metadata.mode());
-                stats.device = Some(metadata.dev());
-                stats.group_id = Some(metadata.gid());
-                stats.inode = Some(metadata.ino());
-                stats.hard_links = Some(metadata.nlink());
-                stats.rdev = Some(metadata.rdev());
-            }
+    // Note: The mode is applied last so read-only directories can be filled.
+    copy_permissions(metadata, to)
+}
 
-            Ok(stats)
+/// Copies a file (or recreates a symbolic link) at the destination.
+fn cp_entry_op(from: &Path, to: &Path, options: CopyOptions) -> Result<()> {
+    if fs::symlink_metadata(to).is_ok() {
+        if options.error_on_exist && !options.force {
+            let message = format!("EEXIST: file already exists, cp '{}'", to.display());
+            bail!(io::Error::new(io::ErrorKind::AlreadyExists, message));
         }
-        Err(e) => bail!(e),
+        if !options.force {
+            return Ok(());
+        }
+        remove_entry_op(to)?;
     }
-}
 
-/// Pure rust implementation of creating directories.
-fn mkdir_op<P: AsRef<Path>>(path: P, recursive: bool) -> Result<()> {
-    if recursive {
-        fs::create_dir_all(path).map_err(|e| anyhow!(e))?;
-        return Ok(());
+    let metadata = fs::symlink_metadata(from)?;
+
+    if metadata.is_symlink() {
+        return symlink_op(fs::read_link(from)?.as_path(), to, from.is_dir());
     }
-    fs::create_dir(path).map_err(|e| anyhow!(e))
+
+    fs::copy(from, to)?;
+    copy_permissions(&metadata, to)
 }
 
-/// Pure rust implementation of deleting (empty) directories.
-fn rmdir_op<P: AsRef<Path>>(path: P) -> Result<()> {
-    fs::remove_dir(path).map_err(|e| anyhow!(e))
+/// Applies the source's permissions (the unix mode bits) to the destination.
+#[cfg(target_family = "unix")]
+fn copy_permissions(metadata: &fs::Metadata, to: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    fs::set_permissions(to, fs::Permissions::from_mode(mode)).map_err(|e| anyhow!(e))
 }
 
-/// Pure rust implementation of reading a directory.
-fn readdir_op<P: AsRef<Path>>(path: P) -> Result<Vec<OsString>> {
-    fs::read_dir(path)
-        .map(|directory| directory.map(|entry| entry.unwrap().file_name()).collect())
-        .map_err(|e| anyhow!(e))
+/// Applies the source's permissions (only the readonly flag) to the destination.
+#[cfg(target_family = "windows")]
+fn copy_permissions(metadata: &fs::Metadata, to: &Path) -> Result<()> {
+    fs::set_permissions(to, metadata.permissions()).map_err(|e| anyhow!(e))
 }
 
-/// Pure rust implementation of deleting files and directories.
-fn rm_op<P: AsRef<Path>>(path: P) -> Result<()> {
-    if stats_op(&path)?.is_directory {
-        fs::remove_dir_all(&path).map_err(|e| anyhow!(e))?;
-        return Ok(());
+/// Drops the cached lookups (see `stat_cache`) of the paths an operation changed.
+fn invalidate_stat_cache<T>(paths: &[&Path], result: Result<T>) -> Result<T> {
+    paths.iter().for_each(stat_cache::invalidate);
+    result
+}
+
+/// Passes a file descriptor as SCM_RIGHTS ancillary data.
+#[cfg(target_family = "unix")]
+fn send_fd_op(socket: RawFd, fd: RawFd) -> Result<()> {
+    use nix::sys::socket::{sendmsg, ControlMessage, MsgFlags};
+    use std::io::IoSlice;
+
+    // Note: Ancillary data can't be sent on its own, so a single (dummy)
+    // byte of regular data goes along with it.
+    let iov = [IoSlice::new(&[0])];
+    let fds = [fd];
+    let cmsgs = [ControlMessage::ScmRights(&fds)];
+
+    sendmsg::<()>(socket, &iov, &cmsgs, MsgFlags::empty(), None)?;
+    Ok(())
+}
+
+/// Receives a file descriptor passed as SCM_RIGHTS ancillary data.
+#[cfg(target_family = "unix")]
+fn recv_fd_op(socket: RawFd) -> Result<usize> {
+    use nix::sys::socket::{recvmsg, ControlMessageOwned, MsgFlags};
+    use std::io::IoSliceMut;
+
+    let mut buf = [0u8; 1];
+    let mut iov = [IoSliceMut::new(&mut buf)];
+    let mut cmsg_space = nix::cmsg_space!([RawFd; 1]);
+
+    let msg = recvmsg::<()>(socket, &mut iov, Some(&mut cmsg_space), MsgFlags::empty())?;
+
+    if msg.bytes == 0 {
+        bail!("The socket was closed before a file descriptor was received.");
+    }
+
+    let mut received = vec![];
+    for cmsg in msg.cmsgs()? {
+        if let ControlMessageOwned::ScmRights(fds) = cmsg {
+            received.extend(fds);
+        }
+    }
+
+    // We only hand over the first descriptor, so the rest get closed.
+    let Some((fd, rest)) = received.split_first() else {
+        bail!("No file descriptor was received.");
+    };
+
+    for fd in rest {
+        drop(unsafe { File::from_raw_fd(*fd) });
     }
-    fs::remove_file(&path).map_err(|e| anyhow!(e))
+
+    Ok(*fd as usize)
 }
 
-/// Pure rust implementation of renaming a file/directory.
-fn rename_op<P: AsRef<Path>>(from: P, to: P) -> Result<()> {
-    fs::rename(from, to).map_err(|e| anyhow!(e))
+#[cfg(target_family = "windows")]
+fn send_fd_op(_: i32, _: i32) -> Result<()> {
+    bail!("Passing file descriptors is only supported on unix platforms.")
+}
+
+#[cfg(target_family = "windows")]
+fn recv_fd_op(_: i32) -> Result<usize> {
+    bail!("Passing file descriptors is only supported on unix platforms.")
 }
 
 /// Creates a JavaScript file stats object.
 fn create_v8_stats_object<'a>(
     scope: &mut v8::HandleScope<'a>,
     stats: FileStatistics,
+    bigint: bool,
 ) -> v8::Local<'a, v8::Object> {
     // This will be out stats object.
     let target = v8::Object::new(scope);
     let undefined = v8::undefined(scope);
 
+    // Note: Numbers lose precision above 2^53 (e.g. large inodes), so these
+    // can be emitted as BigInts instead.
+    let integer = |scope: &mut v8::HandleScope<'a>, value: u64| -> v8::Local<'a, v8::Value> {
+        match bigint {
+            true => v8::BigInt::new_from_u64(scope, value).into(),
+            false => v8::Number::new(scope, value as f64).into(),
+        }
+    };
+
     // The size of the file in bytes.
-    let size = v8::Number::new(scope, stats.size as f64);
+    let size = integer(scope, stats.size);
 
     // The timestamp indicating the last time this file was accessed.
     let access_time: v8::Local<v8::Value> = match stats.access_time {
-        Some(value) => v8::Number::new(scope, value.as_millis() as f64).into(),
+        Some(value) => integer(scope, value.as_millis() as u64),
         None => undefined.into(),
     };
 
     // The timestamp indicating the last time this file was modified.
     let modified_time: v8::Local<v8::Value> = match stats.modified_time {
-        Some(value) => v8::Number::new(scope, value.as_millis() as f64).into(),
+        Some(value) => integer(scope, value.as_millis() as u64),
         None => undefined.into(),
     };
 
     // The timestamp indicating the creation time of this file.
     let birth_time: v8::Local<v8::Value> = match stats.birth_time {
-        Some(value) => v8::Number::new(scope, value.as_millis() as f64).into(),
+        Some(value) => integer(scope, value.as_millis() as u64),
         None => undefined.into(),
     };
 
-    set_property_to(scope, target, "size", size.into());
+    set_property_to(scope, target, "size", size);
     set_property_to(scope, target, "atimeMs", access_time);
     set_property_to(scope, target, "mtimeMs", modified_time);
     set_property_to(scope, target, "birthtimeMs", birth_time);
@@ -1403,7 +3636,7 @@ fn create_v8_stats_object<'a>(
     };
 
     let blocks: v8::Local<v8::Value> = match stats.blocks {
-        Some(value) => v8::Number::new(scope, value as f64).into(),
+        Some(value) => integer(scope, value),
         None => undefined.into(),
     };
 
@@ -1418,7 +3651,7 @@ fn create_v8_stats_object<'a>(
     };
 
     let device: v8::Local<v8::Value> = match stats.device {
-        Some(value) => v8::Number::new(scope, value as f64).into(),
+        Some(value) => integer(scope, value),
         None => undefined.into(),
     };
 
@@ -1428,7 +3661,7 @@ fn create_v8_stats_object<'a>(
     };
 
     let inode: v8::Local<v8::Value> = match stats.inode {
-        Some(value) => v8::Number::new(scope, value as f64).into(),
+        Some(value) => integer(scope, value),
         None => undefined.into(),
     };
 
@@ -1457,3 +3690,359 @@ fn create_v8_stats_object<'a>(
 
     target
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_append_file() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let fresh = temp_dir.child("fresh.txt");
+        let existing = temp_dir.child("existing.txt");
+        existing.write_str("hello").unwrap();
+
+        append_file_op(fresh.path(), b"first").unwrap();
+        append_file_op(fresh.path(), b", second").unwrap();
+        append_file_op(existing.path(), b" world").unwrap();
+
+        fresh.assert("first, second");
+        existing.assert("hello world");
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_symlink() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let target = temp_dir.child("target.txt");
+        let link = temp_dir.child("link.txt");
+        target.touch().unwrap();
+
+        symlink_op(target.path(), link.path(), false).unwrap();
+
+        let resolved = readlink_op(link.path()).unwrap();
+        assert_eq!(resolved, target.path().to_string_lossy());
+
+        // Reading a regular file as a link should fail.
+        assert!(readlink_op(target.path()).is_err());
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_lstat() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let target = temp_dir.child("target.txt");
+        let link = temp_dir.child("link.txt");
+        target.touch().unwrap();
+        link.symlink_to_file(target.path()).unwrap();
+
+        let stats = stats_op(link.path(), false).unwrap();
+        let lstats = lstats_op(link.path()).unwrap();
+
+        assert!(stats.is_file && !stats.is_symbolic_link);
+        assert!(!lstats.is_file && lstats.is_symbolic_link);
+        assert_ne!(stats.inode, lstats.inode);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("truncate.txt");
+        temp_file.write_str("hello world").unwrap();
+
+        truncate_op(temp_file.path(), 5).unwrap();
+        temp_file.assert("hello");
+
+        // Growing the file fills the new space with zeros.
+        truncate_op(temp_file.path(), 7).unwrap();
+        temp_file.assert("hello\0\0");
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_chmod() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("chmod.txt");
+        temp_file.touch().unwrap();
+
+        chmod_op(temp_file.path(), 0o600).unwrap();
+
+        let stats = stats_op(temp_file.path(), false).unwrap();
+        assert_eq!(stats.mode.unwrap() & 0o777, 0o600);
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_access() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("access.txt");
+        temp_file.touch().unwrap();
+        chmod_op(temp_file.path(), 0o644).unwrap();
+
+        assert!(access_op(temp_file.path(), F_OK).is_ok());
+        assert!(access_op(temp_file.path(), R_OK | W_OK).is_ok());
+        assert!(access_op(temp_dir.child("missing").path(), F_OK).is_err());
+
+        // Nobody can execute the file (not even root).
+        assert!(access_op(temp_file.path(), X_OK).is_err());
+    }
+
+    #[test]
+    fn test_write_at() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("write_at.txt");
+        temp_file.write_str("hello  ").unwrap();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp_file.path())
+            .unwrap();
+
+        file.seek(SeekFrom::Start(2)).unwrap();
+
+        let bytes = write_at_op(&file, b"world", 6).unwrap();
+        assert_eq!(bytes, 5);
+        assert_eq!(file.stream_position().unwrap(), 2);
+
+        temp_file.assert("hello world");
+    }
+
+    #[test]
+    fn test_mkdtemp() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let prefix = temp_dir
+            .path()
+            .join("build-")
+            .to_string_lossy()
+            .into_owned();
+
+        let first = mkdtemp_op(prefix.clone()).unwrap();
+        let second = mkdtemp_op(prefix.clone()).unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.starts_with(&prefix) && Path::new(&first).is_dir());
+        assert!(second.starts_with(&prefix) && Path::new(&second).is_dir());
+    }
+
+    #[test]
+    fn test_realpath() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let nested = temp_dir.child("a/b");
+        nested.create_dir_all().unwrap();
+
+        let path = nested.path().join("../../a/./b/..");
+        let expected = fs::canonicalize(temp_dir.child("a").path()).unwrap();
+        let expected = strip_verbatim_prefix(expected.to_string_lossy().into_owned());
+
+        assert_eq!(realpath_op(&path, false).unwrap(), expected);
+        assert!(realpath_op(temp_dir.child("missing").path(), false).is_err());
+    }
+
+    #[test]
+    fn test_strip_verbatim_prefix() {
+        let tests = vec![
+            (r"\\?\C:\Users\dune", r"C:\Users\dune"),
+            (r"\\?\UNC\server\share", r"\\server\share"),
+            ("/home/dune", "/home/dune"),
+        ];
+
+        for (path, expected) in tests {
+            assert_eq!(strip_verbatim_prefix(path.into()), expected);
+        }
+    }
+
+    #[test]
+    fn test_utimes() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("utimes.txt");
+        temp_file.touch().unwrap();
+
+        utimes_op(temp_file.path(), 1_000_000_000_500.0, 1_000_000_000_000.0).unwrap();
+
+        let stats = stats_op(temp_file.path(), false).unwrap();
+        let mtime = stats.modified_time.unwrap();
+        assert_eq!(mtime.as_millis(), 1_000_000_000_000);
+    }
+
+    #[test]
+    fn test_read_dir_entry() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        temp_dir.child("file.txt").touch().unwrap();
+        temp_dir.child("folder").create_dir_all().unwrap();
+
+        let entries = fs::read_dir(temp_dir.path()).unwrap();
+        let handle = Arc::new(Mutex::new(Some(entries)));
+
+        let mut names = vec![];
+        while let Some(entry) = read_dir_entry_op(&handle).unwrap() {
+            assert_eq!(entry.is_directory, entry.name == "folder");
+            assert_eq!(entry.is_file, entry.name == "file.txt");
+            names.push(entry.name);
+        }
+
+        names.sort();
+        assert_eq!(names, vec!["file.txt", "folder"]);
+
+        // Reading from a closed directory should fail.
+        handle.lock().unwrap().take();
+        assert!(read_dir_entry_op(&handle).is_err());
+    }
+
+    #[test]
+    fn test_readdir_with_file_types() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        temp_dir.child("file.txt").touch().unwrap();
+        temp_dir.child("folder").create_dir_all().unwrap();
+
+        let token = CancelToken::default();
+        let mut entries = readdir_op(temp_dir.path(), &token, true).unwrap();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (name, file_type) = &entries[0];
+        assert_eq!(name, "file.txt");
+        assert!(file_type.unwrap().is_file);
+
+        let (name, file_type) = &entries[1];
+        assert_eq!(name, "folder");
+        assert!(file_type.unwrap().is_directory);
+
+        // Plain names by default.
+        let entries = readdir_op(temp_dir.path(), &token, false).unwrap();
+        assert!(entries.iter().all(|(_, file_type)| file_type.is_none()));
+    }
+
+    #[test]
+    fn test_rm_directory() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        for name in ["plain", "cancellable", "cancelled"] {
+            temp_dir.child(name).child("a/b/file.txt").touch().unwrap();
+        }
+
+        // Without a token the directory is removed by `fs::remove_dir_all`.
+        rm_op(temp_dir.child("plain").path(), None).unwrap();
+        assert!(!temp_dir.child("plain").path().exists());
+
+        let token = CancelToken::default();
+        rm_op(temp_dir.child("cancellable").path(), Some(&token)).unwrap();
+        assert!(!temp_dir.child("cancellable").path().exists());
+
+        // A cancelled removal leaves the directory alone.
+        token.cancel();
+        assert!(rm_op(temp_dir.child("cancelled").path(), Some(&token)).is_err());
+        assert!(temp_dir.child("cancelled").path().exists());
+    }
+
+    #[test]
+    fn test_copy_file_excl() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let source = temp_dir.child("source.txt");
+        let destination = temp_dir.child("destination.txt");
+        source.write_str("copied").unwrap();
+
+        let bytes = copy_file_op(source.path(), destination.path(), 0).unwrap();
+        assert_eq!(bytes, 6);
+        destination.assert("copied");
+
+        // The destination exists now, so an exclusive copy must fail.
+        let result = copy_file_op(source.path(), destination.path(), COPYFILE_EXCL);
+        assert!(result.is_err());
+
+        // A missing source must not leave an (empty) destination behind.
+        let missing = temp_dir.child("missing.txt");
+        let other = temp_dir.child("other.txt");
+        let result = copy_file_op(missing.path(), other.path(), COPYFILE_EXCL);
+        assert!(result.is_err());
+        assert!(!other.path().exists());
+    }
+
+    #[test]
+    fn test_fs_writes_invalidate_stat_cache() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let source = temp_dir.child("module.js");
+        let destination = temp_dir.child("renamed.js");
+        source.write_str("export default 42;").unwrap();
+
+        assert!(stat_cache::is_file(source.path()));
+
+        rename_op(source.path(), destination.path()).unwrap();
+        assert!(!stat_cache::is_file(source.path()));
+        assert!(stat_cache::is_file(destination.path()));
+
+        rm_op(destination.path(), None).unwrap();
+        assert!(!stat_cache::is_file(destination.path()));
+    }
+
+    #[test]
+    fn test_cp_recursive() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let source = temp_dir.child("source");
+        source.child("a.txt").write_str("a").unwrap();
+        source.child("nested/b.txt").write_str("b").unwrap();
+
+        let destination = temp_dir.child("destination");
+        let options = CopyOptions {
+            recursive: false,
+            force: true,
+            error_on_exist: false,
+        };
+
+        // Directories can only be copied recursively.
+        assert!(cp_op(source.path(), destination.path(), options).is_err());
+
+        let options = CopyOptions {
+            recursive: true,
+            ..options
+        };
+
+        cp_op(source.path(), destination.path(), options).unwrap();
+        destination.child("a.txt").assert("a");
+        destination.child("nested/b.txt").assert("b");
+
+        // Without `force`, existing files are left as they are.
+        source.child("a.txt").write_str("changed").unwrap();
+        let options = CopyOptions {
+            force: false,
+            ..options
+        };
+
+        cp_op(source.path(), destination.path(), options).unwrap();
+        destination.child("a.txt").assert("a");
+
+        // ..unless `errorOnExist` is set.
+        let options = CopyOptions {
+            error_on_exist: true,
+            ..options
+        };
+
+        assert!(cp_op(source.path(), destination.path(), options).is_err());
+
+        // A directory can't be copied into itself.
+        let inner = source.child("nested/copy");
+        assert!(cp_op(source.path(), inner.path(), options).is_err());
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_pass_fd() {
+        use std::os::unix::net::UnixStream;
+
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let temp_file = temp_dir.child("passed.txt");
+        temp_file.write_str("passed").unwrap();
+
+        let file = File::open(temp_file.path()).unwrap();
+        let (left, right) = UnixStream::pair().unwrap();
+
+        send_fd_op(left.as_raw_fd(), file.as_raw_fd()).unwrap();
+        let fd = recv_fd_op(right.as_raw_fd()).unwrap();
+
+        let mut received = get_file_reference(fd);
+        let mut content = String::new();
+        received.read_to_string(&mut content).unwrap();
+
+        assert_ne!(fd as RawFd, file.as_raw_fd());
+        assert_eq!(content, "passed");
+    }
+}
@@ -1,7 +1,11 @@
+use crate::bindings::external_references;
 use crate::bindings::set_exception_code;
+use crate::bindings::throw_exception;
 use crate::bindings::throw_type_error;
-use crate::errors::unwrap_or_exit;
-use crate::modules::load_import;
+use crate::limiter::catch_panics;
+use crate::loaders::Reload;
+use crate::modules::load_import_in_order;
+use crate::modules::load_import_task;
 use crate::modules::resolve_import;
 use crate::modules::EsModuleFuture;
 use crate::modules::ModuleGraph;
@@ -31,12 +35,13 @@ pub fn module_resolve_cb<'a>(
     let dependant = state.module_map.get_path(referrer);
 
     let specifier = specifier.to_rust_string_lossy(scope);
-    let specifier = unwrap_or_exit(resolve_import(
-        dependant.as_deref(),
-        &specifier,
-        false,
-        import_map,
-    ));
+    let specifier = match resolve_import(dependant.as_deref(), &specifier, false, import_map) {
+        Ok(specifier) => specifier,
+        Err(e) => {
+            throw_exception(scope, &e);
+            return None;
+        }
+    };
 
     // This call should always give us back the module.
     let module = state.module_map.get(&specifier).unwrap();
@@ -83,6 +88,11 @@ pub extern "C" fn host_initialize_import_meta_object_cb(
     meta.set(scope, key.into(), value.into());
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![import_meta_resolve]
+}
+
 fn import_meta_resolve(
     scope: &mut v8::HandleScope,
     args: v8::FunctionCallbackArguments,
@@ -164,7 +174,7 @@ pub fn host_import_module_dynamically_cb<'s>(
 
     let import_map = state.options.import_map.clone();
 
-    let specifier = match resolve_import(Some(&base), &specifier, false, import_map) {
+    let specifier = match resolve_import(Some(&base), &specifier, false, import_map.clone()) {
         Ok(specifier) => specifier,
         Err(e) => {
             drop(state);
@@ -219,14 +229,30 @@ pub fn host_import_module_dynamically_cb<'s>(
     state.module_map.pending.push(Rc::clone(&graph_rc));
     state.module_map.seen.insert(specifier.clone(), status);
 
+    // In frozen mode, load the module in the exact order it was requested.
+    if state.options.frozen {
+        let module = Rc::clone(&graph_rc.borrow().root_rc);
+        let integrity = import_map.and_then(|map| map.integrity(&specifier));
+        load_import_in_order(
+            &state_rc,
+            &mut state,
+            specifier,
+            module,
+            &Reload::All,
+            integrity,
+        );
+        return Some(promise);
+    }
+
     /*  Use the event-loop to asynchronously load the requested module. */
 
     let task = {
         let specifier = specifier.clone();
-        move || match load_import(&specifier, true) {
-            anyhow::Result::Ok(source) => Some(Ok(bincode::serialize(&source).unwrap())),
-            Err(e) => Some(Result::Err(e)),
-        }
+        let trace = state.options.trace_imports;
+        let integrity = import_map.and_then(|map| map.integrity(&specifier));
+        catch_panics(move || {
+            load_import_task(&specifier, &Reload::All, integrity.as_deref(), trace)
+        })
     };
 
     let task_cb = {
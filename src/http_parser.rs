@@ -1,8 +1,17 @@
+use crate::bindings::external_references;
+use crate::bindings::get_internal_ref;
 use crate::bindings::set_constant_to;
 use crate::bindings::set_exception_code;
 use crate::bindings::set_function_to;
+use crate::bindings::set_internal_ref_with_finalizer;
+use crate::bindings::throw_exception;
+use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
+use std::fmt;
+
+/// Default limit for the size of the HTTP headers (16 KiB).
+const DEFAULT_MAX_HEADER_SIZE: usize = 16 * 1024;
 
 pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     // Create local JS object.
@@ -11,11 +20,28 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     set_function_to(scope, target, "parseRequest", parse_incoming_request);
     set_function_to(scope, target, "parseResponse", parse_incoming_response);
     set_function_to(scope, target, "parseChunks", parse_body_chunks);
+    set_function_to(scope, target, "createConnection", create_connection);
+    set_function_to(scope, target, "feedConnection", feed_connection);
+    set_function_to(scope, target, "pollConnection", poll_connection);
+    set_function_to(scope, target, "closeConnection", close_connection);
 
     // Return v8 global handle.
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        parse_incoming_request,
+        parse_incoming_response,
+        parse_body_chunks,
+        create_connection,
+        feed_connection,
+        poll_connection,
+        close_connection
+    ]
+}
+
 /// Parses an HTTP request received from a client.
 fn parse_incoming_request(
     scope: &mut v8::HandleScope,
@@ -28,6 +54,8 @@ fn parse_incoming_request(
 
     http_request.copy_contents(&mut data);
 
+    let max_header_size = get_max_header_size(scope, args.get(1));
+
     // Try parse the HTTP request bytes.
     let mut request_headers = [httparse::EMPTY_HEADER; 32];
     let mut request = httparse::Request::new(&mut request_headers);
@@ -45,10 +73,20 @@ fn parse_incoming_request(
 
     // Check if the HTTP request is still incomplete.
     if status.is_partial() {
+        // Note: Headers are incomplete, so every byte received so far counts.
+        if data.len() > max_header_size {
+            throw_headers_too_large(scope, max_header_size);
+            return;
+        }
         rv.set(v8::null(scope).into());
         return;
     }
 
+    if status.unwrap() > max_header_size {
+        throw_headers_too_large(scope, max_header_size);
+        return;
+    }
+
     let method = request.method.unwrap_or_default().to_ascii_uppercase();
     let method = v8::String::new(scope, &method).unwrap();
 
@@ -100,6 +138,8 @@ fn parse_incoming_response(
 
     http_response.copy_contents(&mut data);
 
+    let max_header_size = get_max_header_size(scope, args.get(1));
+
     // Try parse the HTTP response bytes.
     let mut response_headers = [httparse::EMPTY_HEADER; 32];
     let mut response = httparse::Response::new(&mut response_headers);
@@ -117,10 +157,20 @@ fn parse_incoming_response(
 
     // Check if the HTTP response is still incomplete.
     if status.is_partial() {
+        // Note: Headers are incomplete, so every byte received so far counts.
+        if data.len() > max_header_size {
+            throw_headers_too_large(scope, max_header_size);
+            return;
+        }
         rv.set(v8::null(scope).into());
         return;
     }
 
+    if status.unwrap() > max_header_size {
+        throw_headers_too_large(scope, max_header_size);
+        return;
+    }
+
     let status_code = response.code.unwrap_or_default();
     let status_code = v8::Integer::new(scope, status_code as i32);
 
@@ -152,6 +202,25 @@ fn parse_incoming_response(
     rv.set(target.into());
 }
 
+/// Reads the (optional) max header size argument.
+fn get_max_header_size(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> usize {
+    match value.is_number() {
+        true => value.integer_value(scope).unwrap_or_default().max(0) as usize,
+        false => DEFAULT_MAX_HEADER_SIZE,
+    }
+}
+
+/// Throws an exception for headers exceeding the size limit.
+fn throw_headers_too_large(scope: &mut v8::HandleScope, limit: usize) {
+    let message = format!("HTTP headers exceed the maximum size of {limit} bytes.");
+    let message = v8::String::new(scope, &message).unwrap();
+    let exception = v8::Exception::error(scope, message);
+    let exception_object = exception.to_object(scope).unwrap();
+    let code = v8::String::new(scope, "ERR_HEADERS_TOO_LARGE").unwrap();
+    set_constant_to(scope, exception_object, "code", code.into());
+    scope.throw_exception(exception);
+}
+
 /// Gets available chunks from a streaming HTTP message.
 fn parse_body_chunks(
     scope: &mut v8::HandleScope,
@@ -206,6 +275,348 @@ fn parse_body_chunks(
     rv.set(target.into());
 }
 
+/// Creates the parser of an incoming (server-side) HTTP connection.
+fn create_connection(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let max_header_size = get_max_header_size(scope, args.get(0));
+    let connection_wrapper = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    connection_wrapper.set_internal_field_count(1);
+
+    let connection_wrapper = connection_wrapper.new_instance(scope).unwrap();
+    let connection = ServerConnection::new(max_header_size);
+
+    set_internal_ref_with_finalizer(scope, connection_wrapper, 0, Some(connection));
+
+    rv.set(connection_wrapper.into());
+}
+
+/// Appends bytes received from the socket to the connection's buffer.
+fn feed_connection(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the connection_wrap object.
+    let connection_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Get data as ArrayBuffer.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+    let mut bytes = vec![0; data.byte_length()];
+    data.copy_contents(&mut bytes);
+
+    match get_internal_ref::<Option<ServerConnection>>(scope, connection_wrap, 0) {
+        Some(connection) => connection.feed(&bytes),
+        None => throw_exception(scope, &anyhow!("The connection has been closed.")),
+    }
+}
+
+/// Gets the next event of the connection (or null if more bytes are needed).
+fn poll_connection(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the connection_wrap object.
+    let connection_wrap = args.get(0).to_object(scope).unwrap();
+    let connection = match get_internal_ref::<Option<ServerConnection>>(scope, connection_wrap, 0) {
+        Some(connection) => connection,
+        None => {
+            throw_exception(scope, &anyhow!("The connection has been closed."));
+            return;
+        }
+    };
+
+    let event = match connection.poll() {
+        Ok(Some(event)) => event,
+        Ok(None) => {
+            rv.set(v8::null(scope).into());
+            return;
+        }
+        Err(e) => {
+            match e.downcast_ref::<HeadersTooLarge>() {
+                Some(HeadersTooLarge(limit)) => throw_headers_too_large(scope, *limit),
+                None => {
+                    let message = v8::String::new(scope, &e.to_string()).unwrap();
+                    let exception = v8::Exception::error(scope, message);
+                    set_exception_code(scope, exception, &e);
+                    scope.throw_exception(exception);
+                }
+            }
+            return;
+        }
+    };
+
+    // Build the v8 result object.
+    let target = v8::Object::new(scope);
+
+    let (event_type, keep_alive) = match event {
+        ConnectionEvent::Request(head) => {
+            let method = v8::String::new(scope, &head.method).unwrap();
+            let path = v8::String::new(scope, &head.path).unwrap();
+            let version = v8::Integer::new(scope, head.version as i32);
+            let headers = head
+                .headers
+                .iter()
+                .fold(v8::Object::new(scope), |acc, (name, value)| {
+                    let value = v8::String::new(scope, value).unwrap();
+                    set_constant_to(scope, acc, name, value.into());
+                    acc
+                });
+
+            set_constant_to(scope, target, "method", method.into());
+            set_constant_to(scope, target, "path", path.into());
+            set_constant_to(scope, target, "version", version.into());
+            set_constant_to(scope, target, "headers", headers.into());
+
+            ("request", head.keep_alive)
+        }
+        ConnectionEvent::Body(chunks) => {
+            // Create a v8 typed-array for each chunk.
+            let chunks: Vec<v8::Local<v8::Value>> = chunks
+                .into_iter()
+                .map(|chunk| {
+                    let length = chunk.len();
+                    let store = chunk.into_boxed_slice();
+                    let store =
+                        v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+                    let buffer = v8::ArrayBuffer::with_backing_store(scope, &store);
+                    v8::Uint8Array::new(scope, buffer, 0, length)
+                        .unwrap()
+                        .into()
+                })
+                .collect();
+
+            let chunks = v8::Array::new_with_elements(scope, &chunks);
+            set_constant_to(scope, target, "chunks", chunks.into());
+
+            ("body", connection.keep_alive)
+        }
+        ConnectionEvent::End => ("end", connection.keep_alive),
+    };
+
+    let event_type = v8::String::new(scope, event_type).unwrap();
+    let keep_alive = v8::Boolean::new(scope, keep_alive);
+
+    set_constant_to(scope, target, "type", event_type.into());
+    set_constant_to(scope, target, "keepAlive", keep_alive.into());
+
+    rv.set(target.into());
+}
+
+/// Frees the parser of a connection (once its socket has been closed).
+fn close_connection(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get the connection_wrap object.
+    let connection_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Note: Dropping the parser also frees its (possibly large) buffer, without
+    // waiting for the wrapper to be garbage collected.
+    get_internal_ref::<Option<ServerConnection>>(scope, connection_wrap, 0).take();
+}
+
+/// Error returned when the request headers exceed the size limit.
+#[derive(Debug)]
+struct HeadersTooLarge(usize);
+
+impl fmt::Display for HeadersTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HTTP headers exceed the maximum size of {} bytes.",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for HeadersTooLarge {}
+
+/// The parsed head (request line and headers) of an HTTP request.
+#[derive(Debug, PartialEq)]
+struct RequestHead {
+    method: String,
+    path: String,
+    version: u8,
+    headers: Vec<(String, String)>,
+    keep_alive: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum ConnectionEvent {
+    /// A new request's head has been received.
+    Request(RequestHead),
+    /// Part of the current request's body.
+    Body(Vec<RawChunk>),
+    /// The current request (including its body) has been fully received.
+    End,
+}
+
+#[derive(Debug, PartialEq)]
+enum ConnectionState {
+    /// Waiting for the head of the next request.
+    Head,
+    /// Reading a `Content-Length` body (holding the bytes left).
+    SizedBody(usize),
+    /// Reading a `Transfer-Encoding: chunked` body.
+    ChunkedBody,
+    /// The request has been fully received.
+    Done,
+    /// No more requests should be read from the connection.
+    Closed,
+}
+
+/// A state machine reading the HTTP requests of a server-side connection.
+///
+/// After each request (and its body) has been consumed the parser moves on to
+/// the next one, so pipelined requests sharing the same buffer are handled
+/// without the JS layer having to track any buffer boundaries.
+struct ServerConnection {
+    state: ConnectionState,
+    buffer: Vec<u8>,
+    keep_alive: bool,
+    max_header_size: usize,
+}
+
+impl ServerConnection {
+    /// Creates a connection parser that is waiting for the first request.
+    pub fn new(max_header_size: usize) -> Self {
+        Self {
+            state: ConnectionState::Head,
+            buffer: Vec::new(),
+            keep_alive: true,
+            max_header_size,
+        }
+    }
+
+    /// Appends newly received bytes to the internal buffer.
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Returns the next available event, or `None` if more bytes are needed.
+    pub fn poll(&mut self) -> Result<Option<ConnectionEvent>> {
+        match self.state {
+            ConnectionState::Head => self.poll_head(),
+            ConnectionState::SizedBody(0) => {
+                self.state = ConnectionState::Done;
+                self.poll()
+            }
+            ConnectionState::SizedBody(remaining) => {
+                // Wait until more bytes are available.
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                let length = remaining.min(self.buffer.len());
+                let chunk = self.buffer.drain(..length).collect();
+                self.state = ConnectionState::SizedBody(remaining - length);
+                Ok(Some(ConnectionEvent::Body(vec![chunk])))
+            }
+            ConnectionState::ChunkedBody => {
+                let (chunks, _, received_last_chunk) = get_available_chunks(&mut self.buffer)?;
+                if received_last_chunk {
+                    self.state = ConnectionState::Done;
+                }
+                match chunks.is_empty() {
+                    true if received_last_chunk => self.poll(),
+                    true => Ok(None),
+                    false => Ok(Some(ConnectionEvent::Body(chunks))),
+                }
+            }
+            ConnectionState::Done => {
+                self.state = match self.keep_alive {
+                    true => ConnectionState::Head,
+                    false => ConnectionState::Closed,
+                };
+                Ok(Some(ConnectionEvent::End))
+            }
+            ConnectionState::Closed => Ok(None),
+        }
+    }
+
+    /// Tries to parse the head of the next request.
+    fn poll_head(&mut self) -> Result<Option<ConnectionEvent>> {
+        let mut request_headers = [httparse::EMPTY_HEADER; 32];
+        let mut request = httparse::Request::new(&mut request_headers);
+
+        let status = request.parse(&self.buffer)?;
+
+        // Note: Headers are incomplete, so every byte received so far counts.
+        if status.is_partial() {
+            if self.buffer.len() > self.max_header_size {
+                bail!(HeadersTooLarge(self.max_header_size));
+            }
+            return Ok(None);
+        }
+
+        let marker = status.unwrap();
+        if marker > self.max_header_size {
+            bail!(HeadersTooLarge(self.max_header_size));
+        }
+
+        let version = request.version.unwrap_or_default();
+        let headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .map(|h| {
+                let name = h.name.to_lowercase();
+                let value = String::from_utf8_lossy(h.value).to_string();
+                (name, value)
+            })
+            .collect();
+
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_lowercase())
+        };
+
+        // HTTP/1.1 connections are persistent by default, while HTTP/1.0 ones
+        // have to explicitly ask for it.
+        let keep_alive = match header("connection") {
+            Some(value) if value.contains("close") => false,
+            Some(value) if value.contains("keep-alive") => true,
+            _ => version == 1,
+        };
+
+        let is_chunked = header("transfer-encoding").is_some_and(|v| v.contains("chunked"));
+        let content_length = match header("content-length") {
+            Some(value) => match value.trim().parse::<usize>() {
+                Ok(length) => length,
+                Err(_) => bail!("Invalid HTTP Content-Length header."),
+            },
+            None => 0,
+        };
+
+        let head = RequestHead {
+            method: request.method.unwrap_or_default().to_ascii_uppercase(),
+            path: request.path.unwrap_or("/").to_owned(),
+            version,
+            headers,
+            keep_alive,
+        };
+
+        // Content-Length and Transfer-Encoding are mutual exclusive, but if
+        // both are present the latter takes precedence (RFC7230 3.3.3).
+        self.state = match is_chunked {
+            true => ConnectionState::ChunkedBody,
+            false => ConnectionState::SizedBody(content_length),
+        };
+
+        self.keep_alive = keep_alive;
+        self.buffer.drain(..marker);
+
+        Ok(Some(ConnectionEvent::Request(head)))
+    }
+}
+
 type RawChunk = Vec<u8>;
 
 /// Extracts available chunks from a buffer.
@@ -377,3 +788,128 @@ impl ChunkParser {
         Some(Chunk::new(size, body))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain_events(connection: &mut ServerConnection) -> Vec<ConnectionEvent> {
+        let mut events = vec![];
+        while let Some(event) = connection.poll().unwrap() {
+            events.push(event);
+        }
+        events
+    }
+
+    fn request_path(event: &ConnectionEvent) -> &str {
+        match event {
+            ConnectionEvent::Request(head) => &head.path,
+            _ => panic!("Expected a request event, got {event:?}"),
+        }
+    }
+
+    #[test]
+    fn test_pipelined_requests() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n");
+
+        let events = drain_events(&mut connection);
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(request_path(&events[0]), "/a");
+        assert_eq!(events[1], ConnectionEvent::End);
+        assert_eq!(request_path(&events[2]), "/b");
+        assert_eq!(events[3], ConnectionEvent::End);
+    }
+
+    #[test]
+    fn test_body_filling_the_buffer() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello");
+
+        let events = drain_events(&mut connection);
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1], ConnectionEvent::Body(vec![b"hello".to_vec()]));
+        assert_eq!(events[2], ConnectionEvent::End);
+        assert_eq!(connection.state, ConnectionState::Head);
+        assert!(connection.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_pipelined_requests_with_bodies() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"POST /a HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc");
+        connection.feed(b"POST /b HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n");
+        connection.feed(b"2\r\nde\r\n0\r\n\r\n");
+        connection.feed(b"GET /c HTTP/1.1\r\n\r\n");
+
+        let events = drain_events(&mut connection);
+
+        assert_eq!(events.len(), 8);
+        assert_eq!(request_path(&events[0]), "/a");
+        assert_eq!(events[1], ConnectionEvent::Body(vec![b"abc".to_vec()]));
+        assert_eq!(events[2], ConnectionEvent::End);
+        assert_eq!(request_path(&events[3]), "/b");
+        assert_eq!(events[4], ConnectionEvent::Body(vec![b"de".to_vec()]));
+        assert_eq!(events[5], ConnectionEvent::End);
+        assert_eq!(request_path(&events[6]), "/c");
+        assert_eq!(events[7], ConnectionEvent::End);
+    }
+
+    #[test]
+    fn test_body_split_across_reads() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"POST / HTTP/1.1\r\nContent-Length: 6\r\n\r\nfoo");
+
+        let events = drain_events(&mut connection);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1], ConnectionEvent::Body(vec![b"foo".to_vec()]));
+
+        connection.feed(b"bar");
+
+        let events = drain_events(&mut connection);
+        assert_eq!(events[0], ConnectionEvent::Body(vec![b"bar".to_vec()]));
+        assert_eq!(events[1], ConnectionEvent::End);
+    }
+
+    #[test]
+    fn test_connection_close() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"GET /a HTTP/1.1\r\nConnection: close\r\n\r\nGET /b HTTP/1.1\r\n\r\n");
+
+        let events = drain_events(&mut connection);
+
+        // The second request should be ignored.
+        assert_eq!(events.len(), 2);
+        assert_eq!(connection.state, ConnectionState::Closed);
+    }
+
+    #[test]
+    fn test_keep_alive_defaults() {
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"GET / HTTP/1.0\r\n\r\n");
+
+        match connection.poll().unwrap() {
+            Some(ConnectionEvent::Request(head)) => assert!(!head.keep_alive),
+            event => panic!("Expected a request event, got {event:?}"),
+        }
+
+        let mut connection = ServerConnection::new(DEFAULT_MAX_HEADER_SIZE);
+        connection.feed(b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n");
+
+        match connection.poll().unwrap() {
+            Some(ConnectionEvent::Request(head)) => assert!(head.keep_alive),
+            event => panic!("Expected a request event, got {event:?}"),
+        }
+    }
+
+    #[test]
+    fn test_headers_too_large() {
+        let mut connection = ServerConnection::new(16);
+        connection.feed(b"GET / HTTP/1.1\r\nHost: localhost");
+
+        let error = connection.poll().unwrap_err();
+        assert!(error.downcast_ref::<HeadersTooLarge>().is_some());
+    }
+}
@@ -3,8 +3,7 @@
 // http://hyperandroid.com/2020/02/12/v8-inspector-from-an-embedder-standpoint/
 // https://github.com/ahmadov/v8_inspector_example/tree/master/
 
-use crate::errors::generic_error;
-use crate::errors::unwrap_or_exit;
+use crate::verbosity::info;
 use axum::extract::ws::Message;
 use axum::extract::ws::WebSocket;
 use axum::extract::ws::WebSocketUpgrade;
@@ -16,8 +15,14 @@ use axum::Router;
 use dune_event_loop::LoopInterruptHandle;
 use futures::sink::SinkExt;
 use futures::stream::StreamExt;
+use serde::Deserialize;
 use serde::Serialize;
+use serde_json::json;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::env;
+use std::io;
 use std::mem::MaybeUninit;
 use std::net::SocketAddrV4;
 use std::rc::Rc;
@@ -27,11 +32,22 @@ use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::runtime::Builder;
 use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::error::TryRecvError;
 use uuid::Uuid;
 
 // Dune supports only a single context in `JsRuntime`.
 const CONTEXT_GROUP_ID: i32 = 1;
 
+/// The env variable that fixes the inspector's target id (set by the watcher, so
+/// devtools can reconnect to the same target after a restart).
+pub const INSPECTOR_ID_ENV: &str = "DUNE_INSPECTOR_ID";
+
+// How long we keep trying to bind an address that's still in use (e.g. by the
+// process the watcher just killed).
+const BIND_RETRIES: u32 = 20;
+const BIND_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 #[derive(Serialize)]
 struct Details {
     description: String,
@@ -59,6 +75,13 @@ struct Versions {
 // Messages sent by the connected frontend devtools.
 type InspectorMessage = String;
 
+// The CDP message id used to stop the internal profiler.
+const PROFILER_STOP_ID: u32 = 4;
+
+// How many messages can be queued for the frontend (e.g. `Debugger.scriptParsed`
+// events are sent for every loaded script at once).
+const OUTBOUND_CAPACITY: usize = 1024;
+
 #[derive(Debug)]
 enum FrontendMessage {
     /// A new debugger session has been successfully connected.
@@ -74,6 +97,8 @@ enum FrontendMessage {
 pub struct JsRuntimeInspector {
     v8_inspector_client: v8::inspector::V8InspectorClientBase,
     v8_inspector: Rc<RefCell<v8::UniquePtr<v8::inspector::V8Inspector>>>,
+    isolate_ptr: *mut v8::Isolate,
+    context: v8::Global<v8::Context>,
     session: Option<Box<InspectorSession>>,
     inbound_rx: mpsc::Receiver<FrontendMessage>,
     inbound_tx: mpsc::Sender<FrontendMessage>,
@@ -85,6 +110,7 @@ pub struct JsRuntimeInspector {
     break_on_start: bool,
     waiting_for_session: bool,
     root: Option<String>,
+    profiler: Option<(Box<InspectorSession>, broadcast::Receiver<InspectorMessage>)>,
 }
 
 impl JsRuntimeInspector {
@@ -99,11 +125,13 @@ impl JsRuntimeInspector {
         let v8_inspector_client = v8::inspector::V8InspectorClientBase::new::<Self>();
         let (inbound_tx, inbound_rx) = mpsc::channel::<FrontendMessage>();
         let (handshake_tx, handshake_rx) = mpsc::channel::<()>();
-        let (outbound_tx, _outbound_rx) = broadcast::channel::<InspectorMessage>(64);
+        let (outbound_tx, _outbound_rx) = broadcast::channel::<InspectorMessage>(OUTBOUND_CAPACITY);
 
         let inspector = Rc::new(RefCell::new(Self {
             v8_inspector_client,
             v8_inspector: Default::default(),
+            isolate_ptr: isolate as *mut v8::Isolate,
+            context: context.clone(),
             session: None,
             inbound_tx,
             inbound_rx,
@@ -115,6 +143,7 @@ impl JsRuntimeInspector {
             waiting_for_session: should_wait,
             break_on_start: should_wait,
             root,
+            profiler: None,
         }));
 
         let scope = &mut v8::HandleScope::new(isolate);
@@ -135,10 +164,10 @@ impl JsRuntimeInspector {
     }
 
     // Starts listening for ws connections.
-    pub fn start_agent(&mut self, address: SocketAddrV4) {
+    pub fn start_agent(&mut self, address: SocketAddrV4) -> io::Result<()> {
         // Build the shared state for axum.
         let state = AppState {
-            id: Uuid::new_v4(),
+            id: target_id(),
             address,
             outbound_tx: self.outbound_tx.clone(),
             inbound_tx: self.inbound_tx.clone(),
@@ -152,15 +181,22 @@ impl JsRuntimeInspector {
             .thread_name("dune-inspector-thread")
             .worker_threads(2)
             .enable_io()
+            .enable_time()
             .build()
             .unwrap();
 
+        // Note: The address is bound before the thread is spawned, so a bind
+        // failure is returned to the caller instead of exiting the process.
+        let listener = executor.block_on(bind(address))?;
+
         // Spawn the web-socket server thread.
-        thread::spawn(move || executor.block_on(serve(state)));
+        thread::spawn(move || executor.block_on(serve(listener, state)));
 
         if self.break_on_start {
             self.wait_for_session_and_break_on_next_statement();
         }
+
+        Ok(())
     }
 
     // Notify the inspector about the newly created context.
@@ -228,6 +264,52 @@ impl JsRuntimeInspector {
         }
     }
 
+    /// Starts sampling the V8 stack through an internal session (no frontend is needed).
+    pub fn start_profiling(&mut self, interval: u64) {
+        // Responses are collected locally instead of being sent to a websocket.
+        let (outbound_tx, outbound_rx) = broadcast::channel::<InspectorMessage>(64);
+        let mut session = InspectorSession::new(self.v8_inspector.clone(), outbound_tx);
+
+        let commands = [
+            json!({ "id": 1, "method": "Profiler.enable" }),
+            json!({
+                "id": 2,
+                "method": "Profiler.setSamplingInterval",
+                "params": { "interval": interval },
+            }),
+            json!({ "id": 3, "method": "Profiler.start" }),
+        ];
+
+        for command in commands {
+            session.dispatch_message(command.to_string());
+        }
+
+        self.profiler = Some((session, outbound_rx));
+    }
+
+    /// Stops the internal profiler and returns the samples as folded stacks.
+    pub fn stop_profiling(&mut self) -> Option<String> {
+        let (mut session, mut outbound_rx) = self.profiler.take()?;
+        let command = json!({ "id": PROFILER_STOP_ID, "method": "Profiler.stop" });
+
+        session.dispatch_message(command.to_string());
+
+        // Note: V8 responds to protocol messages synchronously, so the recorded
+        // profile is already waiting in the channel.
+        loop {
+            match outbound_rx.try_recv() {
+                Ok(message) => match serde_json::from_str::<ProfilerResponse>(&message) {
+                    Ok(response) if response.id == Some(PROFILER_STOP_ID) => {
+                        return Some(response.result.profile.to_folded_stacks());
+                    }
+                    _ => continue,
+                },
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+
     /// Processes the received messages, such as establishing or disconnecting a session
     /// and dispatching commands to the active session.
     fn process_incoming_message(&mut self, message: FrontendMessage) {
@@ -294,6 +376,19 @@ impl v8::inspector::V8InspectorClientImpl for JsRuntimeInspector {
         assert_eq!(context_group_id, CONTEXT_GROUP_ID);
         self.waiting_for_session = false;
     }
+
+    // Note: V8 asks for the default context when evaluating without a context id
+    // (e.g. `Runtime.evaluate` from the devtools console).
+    fn ensure_default_context_in_group(
+        &mut self,
+        context_group_id: i32,
+    ) -> Option<v8::Local<v8::Context>> {
+        assert_eq!(context_group_id, CONTEXT_GROUP_ID);
+        // SAFETY: The isolate outlives the inspector (see `JsRuntime`).
+        let isolate = unsafe { &mut *self.isolate_ptr };
+        let scope = &mut unsafe { v8::CallbackScope::new(isolate) };
+        Some(v8::Local::new(scope, &self.context))
+    }
 }
 
 /// An inspector session that proxies messages to concrete "transport layer",
@@ -342,7 +437,8 @@ impl InspectorSession {
     // Dispatch message to outbound channel.
     fn send_message(&self, msg: v8::UniquePtr<v8::inspector::StringBuffer>) {
         let message = msg.unwrap().string().to_string();
-        self.outbound_tx.send(message).unwrap();
+        // Note: Sending fails only if the frontend is gone (nobody to notify).
+        let _ = self.outbound_tx.send(message);
     }
 
     // Schedule a v8 break on next statement.
@@ -385,6 +481,95 @@ impl v8::inspector::ChannelImpl for InspectorSession {
     fn flush_protocol_notifications(&mut self) {}
 }
 
+#[derive(Deserialize)]
+struct ProfilerResponse {
+    id: Option<u32>,
+    result: ProfilerResult,
+}
+
+#[derive(Deserialize)]
+struct ProfilerResult {
+    profile: CpuProfile,
+}
+
+/// A CPU profile as reported by the CDP `Profiler.stop` command.
+#[derive(Deserialize)]
+struct CpuProfile {
+    nodes: Vec<ProfileNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileNode {
+    id: u64,
+    call_frame: CallFrame,
+    #[serde(default)]
+    hit_count: u64,
+    #[serde(default)]
+    children: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallFrame {
+    function_name: String,
+    url: String,
+    line_number: i64,
+}
+
+impl CallFrame {
+    // Returns the frame's label (the synthetic root frame is skipped).
+    fn label(&self) -> Option<String> {
+        let name = match self.function_name.as_str() {
+            "(root)" => return None,
+            "" => "(anonymous)",
+            name => name,
+        };
+        match self.url.is_empty() {
+            true => Some(name.into()),
+            false => Some(format!("{} {}:{}", name, self.url, self.line_number + 1)),
+        }
+    }
+}
+
+impl CpuProfile {
+    /// Converts the profile into the folded-stacks format consumed by `inferno`
+    /// and `flamegraph.pl` (one `frame;frame;frame count` line per stack).
+    fn to_folded_stacks(&self) -> String {
+        let nodes: HashMap<u64, &ProfileNode> = self.nodes.iter().map(|n| (n.id, n)).collect();
+        let parents: HashMap<u64, u64> = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.children.iter().map(|child| (*child, node.id)))
+            .collect();
+
+        let mut stacks = BTreeMap::<String, u64>::new();
+
+        for node in self.nodes.iter().filter(|node| node.hit_count > 0) {
+            // Walk up to the root collecting the frame labels.
+            let mut frames = vec![];
+            let mut current = Some(node.id);
+
+            while let Some(id) = current {
+                frames.extend(nodes[&id].call_frame.label());
+                current = parents.get(&id).copied();
+            }
+
+            if frames.is_empty() {
+                continue;
+            }
+
+            frames.reverse();
+            *stacks.entry(frames.join(";")).or_default() += node.hit_count;
+        }
+
+        stacks
+            .iter()
+            .map(|(stack, count)| format!("{stack} {count}\n"))
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 struct AppState {
     pub id: Uuid,
@@ -411,13 +596,31 @@ impl AppState {
     }
 }
 
-async fn serve(state: AppState) {
-    // Bind to specified address, handle errors gracefully.
-    let listener = TcpListener::bind(state.address).await;
-    let listener = unwrap_or_exit(listener.map_err(|e| generic_error(e.to_string())));
+/// Returns the id of the debugging target (stable across restarts in watch mode).
+fn target_id() -> Uuid {
+    env::var(INSPECTOR_ID_ENV)
+        .ok()
+        .and_then(|id| Uuid::parse_str(&id).ok())
+        .unwrap_or_else(Uuid::new_v4)
+}
 
-    println!("Debugger listening on ws://{}/{}", state.address, state.id);
-    println!("Visit chrome://inspect to connect to the debugger.");
+/// Binds the address, waiting a bit if it's still held by a previous process.
+async fn bind(address: SocketAddrV4) -> io::Result<TcpListener> {
+    let mut attempts = 0;
+    loop {
+        match TcpListener::bind(address).await {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse && attempts < BIND_RETRIES => {
+                attempts += 1;
+                tokio::time::sleep(BIND_RETRY_DELAY).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn serve(listener: TcpListener, state: AppState) {
+    info!("Debugger listening on ws://{}/{}", state.address, state.id);
+    info!("Visit chrome://inspect to connect to the debugger.");
 
     // Build our application with some routes.
     let app = Router::new()
@@ -489,7 +692,12 @@ async fn websocket(socket: WebSocket, state: AppState) {
 
     // Spawn the task that sends messages to devtools frontend.
     let mut send_task = tokio::spawn(async move {
-        while let Ok(message) = outbound_tx.recv().await {
+        loop {
+            let message = match outbound_tx.recv().await {
+                Ok(message) => message,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
             // In any websocket error, break loop.
             if sender.send(Message::Text(message.into())).await.is_err() {
                 break;
@@ -517,3 +725,146 @@ fn new_box_with<T>(new_fn: impl FnOnce(*mut T) -> T) -> Box<T> {
         Box::from_raw(p)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::JsRuntime;
+    use crate::runtime::JsRuntimeOptions;
+    use serde_json::Value;
+    use std::net::Ipv4Addr;
+    use std::time::Instant;
+
+    #[test]
+    fn test_evaluate_on_call_frame() {
+        let options = JsRuntimeOptions {
+            inspect: Some((SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0), false)),
+            ..Default::default()
+        };
+
+        let mut runtime = JsRuntime::with_options(options).unwrap();
+        let inspector = runtime.inspector().unwrap();
+
+        // Act as the devtools frontend (without the websocket in between).
+        let inbound_tx = inspector.borrow().inbound_tx.clone();
+        let mut outbound_rx = inspector.borrow().outbound_tx.subscribe();
+        let send = move |message: Value| {
+            let message = FrontendMessage::Command(message.to_string());
+            inbound_tx.send(message).unwrap();
+        };
+
+        inspector
+            .borrow()
+            .inbound_tx
+            .send(FrontendMessage::Connected)
+            .unwrap();
+        send(json!({ "id": 1, "method": "Debugger.enable" }));
+        send(json!({
+            "id": 2,
+            "method": "Debugger.setBreakpointByUrl",
+            "params": { "urlRegex": "breakpoint\\.js$", "lineNumber": 2 },
+        }));
+
+        runtime.poll_inspect_session();
+
+        let frontend = thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while Instant::now() < deadline {
+                let message = match outbound_rx.try_recv() {
+                    Ok(message) => serde_json::from_str::<Value>(&message).unwrap(),
+                    Err(TryRecvError::Empty) | Err(TryRecvError::Lagged(_)) => {
+                        thread::sleep(Duration::from_millis(10));
+                        continue;
+                    }
+                    Err(_) => break,
+                };
+
+                // Evaluate a local variable in the paused frame.
+                if message["method"] == "Debugger.paused" {
+                    let frame_id = &message["params"]["callFrames"][0]["callFrameId"];
+                    send(json!({
+                        "id": 3,
+                        "method": "Debugger.evaluateOnCallFrame",
+                        "params": { "callFrameId": frame_id, "expression": "local * 2" },
+                    }));
+                }
+
+                if message["id"] == 3 {
+                    send(json!({ "id": 4, "method": "Debugger.resume" }));
+                    return Some(message["result"]["result"]["value"].clone());
+                }
+            }
+            None
+        });
+
+        let source = "function run() {\n  const local = 21;\n  return local;\n}\nrun();\n";
+        runtime
+            .execute_module("/breakpoint.js", Some(source))
+            .unwrap();
+        runtime.run_event_loop().unwrap();
+
+        assert_eq!(frontend.join().unwrap(), Some(json!(42)));
+    }
+
+    #[test]
+    fn test_target_id_across_restarts() {
+        // The watcher passes the same id to every restarted process.
+        let id = Uuid::new_v4();
+        env::set_var(INSPECTOR_ID_ENV, id.to_string());
+        assert_eq!(target_id(), id);
+        assert_eq!(target_id(), id);
+
+        // Invalid ids are replaced by random ones.
+        env::set_var(INSPECTOR_ID_ENV, "not-a-uuid");
+        assert_ne!(target_id(), id);
+        env::remove_var(INSPECTOR_ID_ENV);
+    }
+
+    #[test]
+    fn test_bind_waits_for_the_address() {
+        // The previous process (killed by the watcher) still holds the address.
+        let previous = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = previous.local_addr().unwrap().port();
+        let address = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
+
+        let release = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            drop(previous);
+        });
+
+        let executor = Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        let listener = executor.block_on(bind(address));
+        release.join().unwrap();
+
+        assert_eq!(listener.unwrap().local_addr().unwrap().port(), port);
+    }
+
+    #[test]
+    fn test_to_folded_stacks() {
+        let profile = r#"{
+            "nodes": [
+                { "id": 1, "callFrame": { "functionName": "(root)", "url": "", "lineNumber": -1 }, "children": [2, 4] },
+                { "id": 2, "callFrame": { "functionName": "main", "url": "file:///a.js", "lineNumber": 0 }, "hitCount": 1, "children": [3] },
+                { "id": 3, "callFrame": { "functionName": "", "url": "file:///a.js", "lineNumber": 4 }, "hitCount": 3 },
+                { "id": 4, "callFrame": { "functionName": "(idle)", "url": "", "lineNumber": -1 }, "hitCount": 2 }
+            ]
+        }"#;
+
+        let profile: CpuProfile = serde_json::from_str(profile).unwrap();
+        let folded = profile.to_folded_stacks();
+
+        assert_eq!(
+            folded,
+            concat!(
+                "(idle) 2\n",
+                "main file:///a.js:1 1\n",
+                "main file:///a.js:1;(anonymous) file:///a.js:5 3\n",
+            )
+        );
+    }
+}
@@ -5,12 +5,16 @@
 //
 // https://developer.mozilla.org/en-US/docs/Web/API/AbortController
 
-import { EventEmitter } from 'events';
+import { Event, EventTarget } from '@web/events';
+
+const timers = process.binding('timers');
+
+const TIMEOUT_MAX = Math.pow(2, 31) - 1;
 
 /**
  * Error type referring to an operation being aborted.
  */
-class AbortError extends Error {
+export class AbortError extends Error {
   constructor(message) {
     super();
     this.name = 'AbortError';
@@ -18,21 +22,58 @@ class AbortError extends Error {
   }
 }
 
+/**
+ * Error type referring to an operation that timed out.
+ */
+export class TimeoutError extends Error {
+  constructor(message) {
+    super();
+    this.name = 'TimeoutError';
+    this.message = message;
+  }
+}
+
+/**
+ * Creates the error an operation rejects with once its signal is aborted.
+ * @ignore
+ */
+export function abortErrorOf(signal) {
+  const error = new AbortError('The operation was aborted.');
+  error.cause = signal.reason;
+  return error;
+}
+
+/**
+ * Marks the signal as aborted and notifies its listeners.
+ * @ignore
+ */
+function abortSignal(signal, reason) {
+  // If it's already aborted, don't do anything.
+  if (signal.aborted) return;
+
+  signal.aborted = true;
+  signal.reason = reason;
+  signal.dispatchEvent(new Event('abort'));
+}
+
 /**
  * The `AbortSignal` interface represents a signal object that allows you
  * to communicate with a request and abort it.
  */
-export class AbortSignal {
+export class AbortSignal extends EventTarget {
   /**
    * Creates a new abort-signal instance.
    *
    * @returns {AbortSignal}
    */
   constructor() {
-    this.eventEmitter = new EventEmitter();
+    super();
     this.onabort = null;
     this.aborted = false;
     this.reason = undefined;
+
+    // Invoke the `onabort` event handler (if any) like any other listener.
+    this.addEventListener('abort', (event) => this.onabort?.(event));
   }
 
   /**
@@ -54,30 +95,53 @@ export class AbortSignal {
    * @param {Number} milliseconds
    * @returns {AbortSignal}
    */
-  // eslint-disable-next-line no-unused-vars
   static timeout(milliseconds) {
-    // Note: Implementing the static `timeout` method adds a lot of complexity, plus
-    // the `http.request` method supports natively the concept of timeouts.
-    //
-    // https://github.com/mo/abortcontroller-polyfill/issues/73#issuecomment-1660420796
+    // Check the delay argument.
+    if (!Number.isFinite(milliseconds) || milliseconds < 0) {
+      throw new TypeError(
+        `The "milliseconds" argument must be a positive number.`
+      );
+    }
 
-    throw new Error('Not implemented!');
-  }
+    const signal = new AbortSignal();
+    const reason = new TimeoutError('The operation timed out.');
 
-  addEventListener(name, handler) {
-    this.eventEmitter.on(name, handler);
-  }
+    // Note: The timer doesn't keep the process alive (same as in browsers), so a
+    // settled operation doesn't hold the exit until the timeout expires.
+    const delay = Math.min(milliseconds, TIMEOUT_MAX);
+    timers.createUnrefTimeout(() => abortSignal(signal, reason), delay);
 
-  removeEventListener(name, handler) {
-    this.eventEmitter.removeListener(name, handler);
+    return signal;
   }
 
-  dispatchEvent(type) {
-    const event = { type, target: this };
-    const handlerName = `on${type}`;
+  /**
+   * Returns an abort-signal instance that aborts when any of the given signals aborts.
+   * https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal/any_static
+   *
+   * @param {Iterable<AbortSignal>} signals
+   * @returns {AbortSignal}
+   */
+  static any(signals) {
+    const signal = new AbortSignal();
+    const sources = Array.from(signals);
+
+    // Check if some signal is already aborted.
+    const aborted = sources.find((source) => source.aborted);
+    if (aborted) {
+      abortSignal(signal, aborted.reason);
+      return signal;
+    }
+
+    // Note: The listeners are removed from every source on abort, so the
+    // remaining (long-lived) signals don't hold on to the combined one.
+    const onAbort = (event) => {
+      sources.forEach((source) => source.removeEventListener('abort', onAbort));
+      abortSignal(signal, event.target.reason);
+    };
+
+    sources.forEach((source) => source.addEventListener('abort', onAbort));
 
-    if (typeof this[handlerName] === 'function') this[handlerName](event);
-    this.eventEmitter.emit(type, event);
+    return signal;
   }
 
   /**
@@ -105,15 +169,14 @@ export class AbortController {
   /**
    * Aborts a request before it has completed.
    *
-   * @param {String} reason
+   * @param {(String|Error)} reason - A message, or an error used as the reason as is.
    */
   abort(reason = 'The operation was aborted.') {
     // If it's already aborted, don't do anything.
     if (this.signal.aborted) return;
 
-    this.signal.aborted = true;
-    this.signal.reason = new AbortError(reason);
-    this.signal.dispatchEvent('abort');
+    const isError = reason instanceof Error;
+    abortSignal(this.signal, isError ? reason : new AbortError(reason));
   }
 }
 
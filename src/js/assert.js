@@ -645,48 +645,181 @@ class Assert {
    * Asserts that a function throws an error.
    *
    * @param {function} callback - The function expected to throw an error when invoked.
-   * @param {object} [expectedError] - An Error object representing the expected error.
+   * @param {(Error|function|RegExp)} [expectedError] - An Error object, an Error class, a RegExp matched against the message or a predicate.
+   * @param {string} [message] - A custom error message to be used if the assertion fails.
    */
-  static throws(callback, expectedError = new Error()) {
+  static throws(callback, expectedError = new Error(), message = '') {
     this.isFunction(callback);
 
     try {
       callback();
     } catch (error) {
-      if (
-        typeof error === 'object' &&
-        error instanceof Error &&
-        typeof expectedError === 'object' &&
-        expectedError instanceof Error
-      ) {
-        if (expectedError.message.length) {
-          this.equal(
-            error.message,
-            expectedError.message,
-            `Expected exception message "${error.message}" to be equals "${expectedError.message}" but it's not.`
-          );
-        }
-
-        return;
-      }
+      this.errorMatches(error, expectedError, message);
+      return;
+    }
 
-      this.equal(
-        error,
-        expectedError,
-        `Expected error of type ${ValueConverter.toString(
-          error
-        )} to be equals ${ValueConverter.toString(expectedError)} but it's not.`
-      );
+    throw InvalidValueException.expected(
+      describeError(expectedError),
+      null,
+      message || 'Expected from callback to throw an Error "${expected}" but it didn\'t.'
+    );
+  }
 
+  /**
+   * Asserts that a promise (or the promise returned by a function) rejects.
+   *
+   * @param {(Promise|function)} promiseOrFn - The promise, or async function, expected to reject.
+   * @param {(Error|function|RegExp)} [expectedError] - An Error object, an Error class, a RegExp matched against the message or a predicate.
+   * @param {string} [message] - A custom error message to be used if the assertion fails.
+   * @returns {Promise<void>}
+   */
+  static async rejects(promiseOrFn, expectedError = new Error(), message = '') {
+    try {
+      await (typeof promiseOrFn === 'function' ? promiseOrFn() : promiseOrFn);
+    } catch (error) {
+      this.errorMatches(error, expectedError, message);
       return;
     }
 
     throw InvalidValueException.expected(
-      ValueConverter.toString(expectedError),
+      describeError(expectedError),
       null,
-      'Expected from callback to throw an Error "${expected}" but it didn\'t.'
+      message || 'Expected promise to reject with "${expected}" but it didn\'t.'
     );
   }
+
+  /**
+   * Asserts that a function doesn't throw an error.
+   *
+   * @param {function} callback - The function expected not to throw when invoked.
+   * @param {string} [message] - A custom error message to be used if the assertion fails.
+   */
+  static doesNotThrow(callback, message = '') {
+    this.isFunction(callback);
+
+    try {
+      callback();
+    } catch (error) {
+      throw new Error(
+        message ||
+          `Expected from callback not to throw but it threw "${errorMessageOf(
+            error
+          )}".`
+      );
+    }
+  }
+
+  /**
+   * Asserts that a promise (or the promise returned by a function) doesn't reject.
+   *
+   * @param {(Promise|function)} promiseOrFn - The promise, or async function, expected to resolve.
+   * @param {string} [message] - A custom error message to be used if the assertion fails.
+   * @returns {Promise<void>}
+   */
+  static async doesNotReject(promiseOrFn, message = '') {
+    try {
+      await (typeof promiseOrFn === 'function' ? promiseOrFn() : promiseOrFn);
+    } catch (error) {
+      throw new Error(
+        message ||
+          `Expected promise not to reject but it rejected with "${errorMessageOf(
+            error
+          )}".`
+      );
+    }
+  }
+
+  /**
+   * Asserts that a thrown error matches the expected error.
+   *
+   * @param {*} error - The error that was thrown (or the rejection reason).
+   * @param {(Error|function|RegExp)} expectedError - An Error object, an Error class, a RegExp matched against the message or a predicate.
+   * @param {string} [message] - A custom error message to be used if the assertion fails.
+   */
+  static errorMatches(error, expectedError, message = '') {
+    // Error classes are checked using the `instanceof` operator.
+    if (isErrorClass(expectedError)) {
+      if (!(error instanceof expectedError)) {
+        throw new Error(
+          message ||
+            `Expected error of type ${expectedError.name} but got "${errorMessageOf(
+              error
+            )}".`
+        );
+      }
+      return;
+    }
+
+    // Regular expressions are matched against the error's message.
+    if (expectedError instanceof RegExp) {
+      if (!expectedError.test(errorMessageOf(error))) {
+        throw new Error(
+          message ||
+            `Expected exception message "${errorMessageOf(
+              error
+            )}" to match ${expectedError} but it doesn't.`
+        );
+      }
+      return;
+    }
+
+    // Predicates have to return `true` for the error to match.
+    if (typeof expectedError === 'function') {
+      if (expectedError(error) !== true) {
+        throw new Error(
+          message ||
+            `Expected exception "${errorMessageOf(
+              error
+            )}" to pass the validation function but it didn't.`
+        );
+      }
+      return;
+    }
+
+    if (
+      typeof error === 'object' &&
+      error instanceof Error &&
+      typeof expectedError === 'object' &&
+      expectedError instanceof Error
+    ) {
+      if (expectedError.message.length) {
+        this.equal(
+          error.message,
+          expectedError.message,
+          message ||
+            `Expected exception message "${error.message}" to be equals "${expectedError.message}" but it's not.`
+        );
+      }
+
+      return;
+    }
+
+    this.equal(
+      error,
+      expectedError,
+      message ||
+        `Expected error of type ${ValueConverter.toString(
+          error
+        )} to be equals ${ValueConverter.toString(expectedError)} but it's not.`
+    );
+  }
+}
+
+function isErrorClass(value) {
+  return (
+    typeof value === 'function' &&
+    (value === Error || value.prototype instanceof Error)
+  );
+}
+
+function describeError(expectedError) {
+  return isErrorClass(expectedError)
+    ? expectedError.name
+    : ValueConverter.toString(expectedError);
+}
+
+function errorMessageOf(error) {
+  return error instanceof Error ? error.message : String(error);
 }
 
 export default Assert;
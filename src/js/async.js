@@ -0,0 +1,411 @@
+/**
+ * Async Utilities
+ *
+ * Conveniences for controlling when (and how often) functions run, that
+ * most projects end up re-implementing. These are NOT web standards.
+ *
+ * Every helper is a standalone named export, so bundlers can drop the
+ * unused ones.
+ *
+ * @module Async
+ */
+
+import timers from 'timers';
+import { setTimeout as sleep } from 'timers/promises';
+import { AbortController, TimeoutError } from '@web/abort';
+
+// Note: `clearTimeout` only accepts timer IDs (not null).
+function clearTimer(id) {
+  if (id !== null) timers.clearTimeout(id);
+}
+
+function validateFunction(fn, name) {
+  if (typeof fn !== 'function') {
+    throw new TypeError(`The "${name}" argument must be of type function.`);
+  }
+}
+
+/**
+ * Delays calling a function until `wait` milliseconds have passed since the
+ * last time the debounced function was called.
+ *
+ * @param {Function} fn - The function to debounce.
+ * @param {Number} [wait] - The milliseconds to wait (default: 0).
+ * @param {Object} [options]
+ * @param {Boolean} [options.leading] - Calls the function at the start of a burst (default: false).
+ * @param {Boolean} [options.trailing] - Calls the function at the end of a burst (default: true).
+ * @returns {Function} The debounced function (with `cancel` and `flush` methods).
+ */
+export function debounce(fn, wait = 0, options = {}) {
+  validateFunction(fn, 'fn');
+  const { leading = false, trailing = true } = options;
+
+  let timer = null;
+  let pendingArgs = null;
+  let pendingThis;
+  let result;
+
+  const invoke = () => {
+    const args = pendingArgs;
+    pendingArgs = null;
+    result = fn.apply(pendingThis, args);
+  };
+
+  const onTimeout = () => {
+    timer = null;
+    if (trailing && pendingArgs) invoke();
+    pendingArgs = null;
+  };
+
+  function debounced(...args) {
+    pendingThis = this;
+    pendingArgs = args;
+
+    // Note: A leading call consumes the arguments, so the trailing call only
+    // happens if the function was called again during the wait.
+    if (timer === null && leading) invoke();
+
+    clearTimer(timer);
+    timer = timers.setTimeout(onTimeout, wait);
+
+    return result;
+  }
+
+  debounced.cancel = () => {
+    clearTimer(timer);
+    timer = null;
+    pendingArgs = null;
+  };
+
+  debounced.flush = () => {
+    clearTimer(timer);
+    onTimeout();
+    return result;
+  };
+
+  return debounced;
+}
+
+/**
+ * Calls a function at most once every `wait` milliseconds.
+ *
+ * @param {Function} fn - The function to throttle.
+ * @param {Number} [wait] - The milliseconds between calls (default: 0).
+ * @param {Object} [options]
+ * @param {Boolean} [options.leading] - Calls the function at the start of a window (default: true).
+ * @param {Boolean} [options.trailing] - Calls the function (with the latest arguments) at the end of a window (default: true).
+ * @returns {Function} The throttled function (with a `cancel` method).
+ */
+export function throttle(fn, wait = 0, options = {}) {
+  validateFunction(fn, 'fn');
+  const { leading = true, trailing = true } = options;
+
+  let timer = null;
+  let pendingArgs = null;
+  let pendingThis;
+  let result;
+
+  const invoke = () => {
+    const args = pendingArgs;
+    pendingArgs = null;
+    result = fn.apply(pendingThis, args);
+  };
+
+  const onTimeout = () => {
+    // A trailing call opens a new window, so calls stay spaced out.
+    if (trailing && pendingArgs) {
+      invoke();
+      timer = timers.setTimeout(onTimeout, wait);
+      return;
+    }
+    timer = null;
+    pendingArgs = null;
+  };
+
+  function throttled(...args) {
+    pendingThis = this;
+    pendingArgs = args;
+
+    if (timer === null) {
+      if (leading) invoke();
+      timer = timers.setTimeout(onTimeout, wait);
+    }
+
+    return result;
+  }
+
+  throttled.cancel = () => {
+    clearTimer(timer);
+    timer = null;
+    pendingArgs = null;
+  };
+
+  return throttled;
+}
+
+/**
+ * Calls an async function until it succeeds, waiting (exponentially longer)
+ * between attempts.
+ *
+ * @param {Function} fn - The function to call (receives the attempt number).
+ * @param {Object} [options]
+ * @param {Number} [options.retries] - The max number of retries (default: 3).
+ * @param {Number} [options.minTimeout] - The milliseconds before the first retry (default: 100).
+ * @param {Number} [options.maxTimeout] - The max milliseconds between retries (default: Infinity).
+ * @param {Number} [options.factor] - The exponential factor (default: 2).
+ * @param {Function} [options.onRetry] - Called with the error (and attempt number) before retrying.
+ * @param {AbortSignal} [options.signal] - Cancels any pending retry.
+ * @returns {Promise<*>} The result of the first successful attempt.
+ */
+export async function retry(fn, options = {}) {
+  validateFunction(fn, 'fn');
+  const {
+    retries = 3,
+    minTimeout = 100,
+    maxTimeout = Infinity,
+    factor = 2,
+    onRetry,
+    signal,
+  } = options;
+
+  for (let attempt = 1; ; attempt++) {
+    signal?.throwIfAborted();
+    try {
+      return await fn(attempt);
+    } catch (err) {
+      if (attempt > retries || signal?.aborted) throw err;
+      onRetry?.(err, attempt);
+    }
+
+    const delay = Math.min(minTimeout * factor ** (attempt - 1), maxTimeout);
+    await sleep(delay, undefined, { signal });
+  }
+}
+
+/**
+ * Rejects with a `TimeoutError` if a promise doesn't settle in time.
+ *
+ * Passing a function (instead of a promise) makes the operation cancellable,
+ * since it's called with an `AbortSignal` that aborts when time runs out.
+ *
+ * @example
+ * const res = await timeout((signal) => fetch(url, { signal }), 5000);
+ *
+ * @param {(Promise|Function)} task - The promise, or a function returning one.
+ * @param {Number} ms - The milliseconds to wait for the promise.
+ * @param {Object} [options]
+ * @param {String} [options.message] - The message of the `TimeoutError`.
+ * @returns {Promise<*>}
+ */
+export async function timeout(task, ms, options = {}) {
+  const controller = new AbortController();
+  const timer = new AbortController();
+  const promise =
+    typeof task === 'function'
+      ? task(controller.signal)
+      : Promise.resolve(task);
+
+  const expired = sleep(ms, undefined, { signal: timer.signal }).then(() => {
+    const message = options.message ?? `The operation timed out after ${ms}ms.`;
+    const error = new TimeoutError(message);
+    controller.abort(error);
+    throw error;
+  });
+
+  try {
+    return await Promise.race([promise, expired]);
+  } finally {
+    // Stop the timer, so it doesn't keep the event-loop alive.
+    timer.abort();
+    expired.catch(() => {});
+  }
+}
+
+/**
+ * Creates a limiter that runs at most `concurrency` async functions at once,
+ * queueing the rest.
+ *
+ * @example
+ * const limit = pLimit(2);
+ * await Promise.all(urls.map((url) => limit(fetch, url)));
+ *
+ * @param {Number} concurrency - The max number of functions running at once.
+ * @returns {Function} The limiter, which is called as `limit(fn, ...args)`.
+ */
+export function pLimit(concurrency) {
+  if (
+    !(Number.isInteger(concurrency) || concurrency === Infinity) ||
+    concurrency < 1
+  ) {
+    const message = 'The "concurrency" argument must be a positive integer.';
+    throw new RangeError(message);
+  }
+
+  const queue = [];
+  let active = 0;
+
+  const next = () => {
+    if (active >= concurrency || queue.length === 0) return;
+    const { fn, args, resolve, reject } = queue.shift();
+    active++;
+
+    // Note: Running the function inside a promise turns sync throws into
+    // rejections.
+    new Promise((run) => run(fn(...args)))
+      .then(resolve, reject)
+      .finally(() => {
+        active--;
+        next();
+      });
+  };
+
+  const limit = (fn, ...args) => {
+    validateFunction(fn, 'fn');
+    return new Promise((resolve, reject) => {
+      queue.push({ fn, args, resolve, reject });
+      next();
+    });
+  };
+
+  Object.defineProperties(limit, {
+    activeCount: { get: () => active },
+    pendingCount: { get: () => queue.length },
+  });
+
+  limit.clearQueue = () => {
+    queue.length = 0;
+  };
+
+  return limit;
+}
+
+/**
+ * A group of concurrent tasks that succeed or fail together (structured
+ * concurrency): The first task to fail cancels the rest, through the group's
+ * abort signal.
+ *
+ * @example
+ * const group = new TaskGroup();
+ * group.spawn((signal) => fetch(first, { signal }));
+ * group.spawn((signal) => fetch(second, { signal }));
+ * const [a, b] = await group.wait();
+ */
+export class TaskGroup {
+  #controller = new AbortController();
+  #tasks = [];
+  #error = null;
+  #failed = false;
+
+  /**
+   * Creates a new task group.
+   *
+   * @param {Object} [options]
+   * @param {AbortSignal} [options.signal] - Cancels the whole group.
+   */
+  constructor(options = {}) {
+    const { signal } = options;
+    if (signal?.aborted) {
+      this.cancel(signal.reason);
+    } else {
+      const onAbort = () => this.cancel(signal.reason);
+      signal?.addEventListener('abort', onAbort, { once: true });
+    }
+  }
+
+  /**
+   * The signal that aborts when a task fails (or the group is cancelled).
+   *
+   * @returns {AbortSignal}
+   */
+  get signal() {
+    return this.#controller.signal;
+  }
+
+  /**
+   * Starts a new task in the group.
+   *
+   * @param {Function} fn - The task, called with the group's signal (and the arguments).
+   * @param {...*} args - The arguments passed to the task.
+   * @returns {Promise<*>} The task's result.
+   */
+  spawn(fn, ...args) {
+    validateFunction(fn, 'fn');
+    this.signal.throwIfAborted();
+
+    const task = new Promise((run) => run(fn(this.signal, ...args)));
+    this.#tasks.push(task);
+
+    // Note: Failures are reported by `wait()`, so the task's promise doesn't
+    // have to be handled by the caller.
+    task.catch((err) => {
+      if (this.#failed || this.signal.aborted) return;
+      this.#failed = true;
+      this.#error = err;
+      this.#controller.abort(err instanceof Error ? err : String(err));
+    });
+
+    return task;
+  }
+
+  /**
+   * Cancels every task in the group.
+   *
+   * @param {(String|Error)} [reason]
+   */
+  cancel(reason) {
+    this.#controller.abort(reason);
+  }
+
+  /**
+   * Waits for every task (including the ones spawned while waiting) to settle.
+   *
+   * @returns {Promise<Array>} The results of the tasks, in the order they were spawned.
+   * @throws The error of the first task that failed (or the cancellation reason).
+   */
+  async wait() {
+    let settled = 0;
+    while (settled < this.#tasks.length) {
+      const pending = this.#tasks.slice(settled);
+      settled = this.#tasks.length;
+      await Promise.allSettled(pending);
+    }
+
+    if (this.#failed) throw this.#error;
+    this.signal.throwIfAborted();
+
+    return Promise.all(this.#tasks);
+  }
+}
+
+/**
+ * Runs a function with a new task group, waiting for all of its tasks.
+ *
+ * @example
+ * await withTaskGroup(async (group) => {
+ *   for (const url of urls) group.spawn((signal) => fetch(url, { signal }));
+ * });
+ *
+ * @param {Function} fn - Called with the group (its failure cancels the group as well).
+ * @param {Object} [options] - The options of the `TaskGroup`.
+ * @returns {Promise<*>} The result of the function.
+ */
+export async function withTaskGroup(fn, options) {
+  validateFunction(fn, 'fn');
+  const group = new TaskGroup(options);
+  const result = group.spawn(() => fn(group));
+  await group.wait();
+  return result;
+}
+
+export { TimeoutError };
+
+export default {
+  debounce,
+  throttle,
+  retry,
+  timeout,
+  pLimit,
+  TaskGroup,
+  withTaskGroup,
+  TimeoutError,
+};
@@ -0,0 +1,242 @@
+/**
+ * Benchmarking APIs
+ *
+ * The bench module measures how long pieces of JavaScript code take to run,
+ * drawing inspiration from Deno's built-in benchmark runner.
+ *
+ * @see {@link https://docs.deno.com/runtime/reference/cli/bench/}
+ *
+ * @module Bench
+ */
+
+import { performance } from 'perf_hooks';
+import { findFiles } from 'test';
+import { bg_red, bold, bright_black, red } from 'colors';
+
+const { remapStackTrace } = process.binding('modules');
+
+// Output labels.
+const FAIL = bg_red(bold(' FAIL '));
+
+// Regex to match benchmark files.
+const BENCH_FILE = /\.bench\.(js|ts)$/;
+
+// The minimum duration (in ms) of a single sample, so the clock's resolution
+// doesn't skew the results of very fast functions.
+const MIN_SAMPLE_TIME = 1;
+
+// Upper bound of samples collected for a single benchmark.
+const MAX_SAMPLES = 10_000;
+
+let sink;
+
+/**
+ * Consumes a value so the optimizer can't eliminate the code computing it.
+ *
+ * @param {*} value - The value to consume.
+ * @returns {*} The same value.
+ */
+export function doNotOptimize(value) {
+  sink = value;
+  return sink;
+}
+
+// Runs a function `size` times and returns the elapsed time (in ms).
+async function runBatch(fn, size) {
+  const start = performance.now();
+  for (let i = 0; i < size; i++) {
+    const result = fn();
+    doNotOptimize(result instanceof Promise ? await result : result);
+  }
+  return performance.now() - start;
+}
+
+// Returns the value at the given percentile of a sorted list.
+function percentile(sorted, p) {
+  const index = Math.ceil((p / 100) * sorted.length) - 1;
+  return sorted[Math.min(Math.max(index, 0), sorted.length - 1)];
+}
+
+// Formats a duration (in nanoseconds) using a readable unit.
+function formatTime(ns) {
+  if (ns < 1e3) return `${ns.toFixed(2)} ns`;
+  if (ns < 1e6) return `${(ns / 1e3).toFixed(2)} µs`;
+  if (ns < 1e9) return `${(ns / 1e6).toFixed(2)} ms`;
+  return `${(ns / 1e9).toFixed(2)} s`;
+}
+
+/**
+ * Measures a function, returning statistics of its time per iteration.
+ *
+ * @param {Function} fn - The function to measure (may return a promise).
+ * @param {Object} options - The warmup and measurement times (in ms).
+ * @returns {Promise<Object>}
+ */
+async function measure(fn, options) {
+  let batch = 1;
+
+  // Warm up the function (letting the JIT optimize it) and find a batch
+  // size that takes at least `MIN_SAMPLE_TIME` to run.
+  const warmupEnd = performance.now() + options.warmup;
+  do {
+    const elapsed = await runBatch(fn, batch);
+    if (elapsed < MIN_SAMPLE_TIME) batch *= 2;
+  } while (performance.now() < warmupEnd);
+
+  const samples = [];
+  const measureEnd = performance.now() + options.time;
+
+  // Collect samples of the average time per iteration (in ns).
+  while (samples.length < MAX_SAMPLES) {
+    const elapsed = await runBatch(fn, batch);
+    samples.push((elapsed * 1e6) / batch);
+    if (performance.now() >= measureEnd && samples.length >= 10) break;
+  }
+
+  const sorted = samples.sort((a, b) => a - b);
+  const mean = sorted.reduce((sum, value) => sum + value, 0) / sorted.length;
+
+  return {
+    iterations: sorted.length * batch,
+    mean,
+    median: percentile(sorted, 50),
+    p99: percentile(sorted, 99),
+    min: sorted[0],
+    max: sorted[sorted.length - 1],
+  };
+}
+
+/**
+ *  BenchRunner is the main executor to run JavaScript benchmarks.
+ */
+export class BenchRunner {
+  // Initializes the bench runner.
+  constructor() {
+    this.benches = new Map();
+    this.benchFiles = [];
+    this.filter = undefined;
+    this.json = false;
+  }
+
+  /**
+   * Registers a new benchmark to the runner.
+   *
+   * @param {String} name - The name of the benchmark.
+   * @param {Function} benchFn - The function being measured.
+   */
+  bench(name, benchFn) {
+    // We don't allow benchmarks with similar names.
+    if (this.benches.has(name)) {
+      throw new Error("Benchmarks can't share the same name.");
+    }
+
+    this.benches.set(name, benchFn);
+  }
+
+  /**
+   * Loads benchmarks from files to the runner recursively.
+   *
+   * @param {String} [entryPoint] - The path to start loading benchmarks from.
+   */
+  async importBenches(entryPoint = process.cwd()) {
+    this.benchFiles.push(...findFiles(entryPoint, BENCH_FILE));
+    await Promise.all(this.benchFiles.map((filename) => import(filename)));
+  }
+
+  #printRow(columns, style = (value) => value) {
+    const widths = [40, 16, 14, 14, 0];
+    const cells = columns.map((value, i) => String(value).padEnd(widths[i]));
+    console.log(cells.map(style).join(''));
+  }
+
+  /**
+   * Runs all the registered benchmarks and reports their results.
+   */
+  async run() {
+    const results = [];
+    let failed = 0;
+
+    if (!this.json) {
+      const header = ['benchmark', 'time (avg)', 'median', 'p99', 'iter/s'];
+      this.#printRow(header, bold);
+    }
+
+    for (const [name, benchFn] of this.benches) {
+      // Filter benchmarks based on provided regex.
+      if (this.filter && !this.filter.test(name)) continue;
+      if (benchFn.ignore) continue;
+
+      try {
+        const { warmup, time } = benchFn;
+        const stats = await measure(benchFn, { warmup, time });
+        results.push({ name, ...stats });
+
+        if (!this.json) {
+          const { mean, median, p99 } = stats;
+          const perSecond = Math.round(1e9 / mean).toLocaleString();
+          const times = [mean, median, p99].map(formatTime);
+          this.#printRow([name, ...times, perSecond]);
+        }
+      } catch (err) {
+        failed++;
+        const stack = err?.stack ? remapStackTrace(err.stack) : String(err);
+        console.error(`${FAIL} ${red(name)}\n ${red(stack)}`);
+      }
+    }
+
+    if (this.json) {
+      console.log(JSON.stringify({ benches: results }, null, 2));
+    } else {
+      console.log(bright_black('\nTimes are per iteration.'));
+    }
+
+    // Exit with non-zero code if a benchmark failed.
+    process.exit(failed > 0 ? 1 : 0);
+  }
+}
+
+export const mainRunner = new BenchRunner();
+
+function parseOptionsArgs(args) {
+  // Check if enough arguments are specified.
+  if (args.length < 2) {
+    throw new Error(`Not enough arguments specified.`);
+  }
+  // Use param overloading.
+  const defaultOptions = { ignore: false, warmup: 100, time: 500 };
+  if (typeof args[1] === 'object') {
+    args[1] = { ...defaultOptions, ...args[1] };
+    return [args[0], args[2], args[1]];
+  }
+  return [...args, defaultOptions];
+}
+
+/**
+ * Specifies a benchmark to be registered with the default bench runner.
+ *
+ * @param {string} name - The name of the benchmark.
+ * @param {Object} [options] - Additional configuration options.
+ * @param {boolean} [options.ignore] - The benchmark will not be executed.
+ * @param {number} [options.warmup] - The warmup time in ms (default: 100).
+ * @param {number} [options.time] - The measurement time in ms (default: 500).
+ * @param {Function} benchFn - The function being measured.
+ */
+function bench(...params) {
+  // Parse variadic parameters.
+  const [name, benchFn, options] = parseOptionsArgs(params);
+
+  if (typeof name !== 'string') {
+    throw new TypeError(`The "name" argument must be of type string.`);
+  }
+
+  if (typeof benchFn !== 'function') {
+    throw new TypeError(`The "benchFn" argument must be of type function.`);
+  }
+
+  // Hack: attach options to the bench function.
+  Object.assign(benchFn, options);
+
+  mainRunner.bench(name, benchFn);
+}
+
+export default bench;
@@ -0,0 +1,201 @@
+// Blob API
+//
+// The Blob interface represents a blob, which is a file-like object of immutable,
+// raw data; they can be read as text or binary data.
+//
+// https://developer.mozilla.org/en-US/docs/Web/API/Blob
+
+import { ReadableStream } from '@web/streams';
+
+const binding = process.binding('blob');
+
+// The size of each chunk emitted by `blob.stream()`.
+const STREAM_CHUNK_SIZE = 64 * 1024;
+
+// Note: Per spec, types with characters outside U+0020 to U+007E are ignored.
+function normalizeType(type = '') {
+  const value = String(type);
+  return /^[\x20-\x7E]*$/.test(value) ? value.toLowerCase() : '';
+}
+
+function relativeIndex(value, size, fallback) {
+  if (value === undefined) return fallback;
+  const index = Math.trunc(Number(value)) || 0;
+  return index < 0 ? Math.max(size + index, 0) : Math.min(index, size);
+}
+
+/**
+ * A file-like object of immutable, raw data.
+ */
+export class Blob {
+  #buffer;
+  #offset;
+  #size;
+  #type;
+
+  /**
+   * Creates a new Blob object.
+   *
+   * @param {Array<(string|ArrayBuffer|ArrayBufferView|Blob)>} [parts]
+   * @param {Object} [options]
+   * @param {string} [options.type] - The MIME type of the data.
+   * @returns {Blob}
+   */
+  constructor(parts = [], options = {}) {
+    if (typeof parts?.[Symbol.iterator] !== 'function') {
+      throw new TypeError(`The "parts" argument must be an iterable object.`);
+    }
+
+    const views = Array.from(parts, (part) => Blob.#toView(part));
+
+    this.#buffer = views.length ? binding.concat(views) : new ArrayBuffer(0);
+    this.#offset = 0;
+    this.#size = this.#buffer.byteLength;
+    this.#type = normalizeType(options?.type);
+  }
+
+  static #toView(part) {
+    if (part instanceof Blob) return part.#view();
+    if (part instanceof ArrayBuffer) return new Uint8Array(part);
+    if (ArrayBuffer.isView(part)) {
+      return new Uint8Array(part.buffer, part.byteOffset, part.byteLength);
+    }
+    return new TextEncoder().encode(String(part));
+  }
+
+  #view() {
+    return new Uint8Array(this.#buffer, this.#offset, this.#size);
+  }
+
+  /**
+   * The size, in bytes, of the data contained in the Blob object.
+   */
+  get size() {
+    return this.#size;
+  }
+
+  /**
+   * A string indicating the MIME type of the data contained in the Blob.
+   */
+  get type() {
+    return this.#type;
+  }
+
+  /**
+   * Returns a new Blob containing the data in the specified range (no copying).
+   *
+   * @param {number} [start]
+   * @param {number} [end]
+   * @param {string} [contentType]
+   * @returns {Blob}
+   */
+  slice(start, end, contentType = '') {
+    const from = relativeIndex(start, this.#size, 0);
+    const to = relativeIndex(end, this.#size, this.#size);
+
+    const blob = new Blob();
+    blob.#buffer = this.#buffer;
+    blob.#offset = this.#offset + from;
+    blob.#size = Math.max(to - from, 0);
+    blob.#type = normalizeType(contentType);
+
+    return blob;
+  }
+
+  /**
+   * Resolves with the contents of the blob as an ArrayBuffer.
+   *
+   * @returns {Promise<ArrayBuffer>}
+   */
+  async arrayBuffer() {
+    return this.#buffer.slice(this.#offset, this.#offset + this.#size);
+  }
+
+  /**
+   * Resolves with the contents of the blob as a Uint8Array.
+   *
+   * @returns {Promise<Uint8Array>}
+   */
+  async bytes() {
+    return new Uint8Array(await this.arrayBuffer());
+  }
+
+  /**
+   * Resolves with the contents of the blob as a UTF-8 string.
+   *
+   * @returns {Promise<string>}
+   */
+  async text() {
+    return new TextDecoder().decode(this.#view());
+  }
+
+  /**
+   * Returns a readable byte stream of the contents of the blob.
+   *
+   * @returns {ReadableStream}
+   */
+  stream() {
+    const view = this.#view();
+    let position = 0;
+    return new ReadableStream({
+      type: 'bytes',
+      pull(controller) {
+        if (position >= view.length) return controller.close();
+        controller.enqueue(view.slice(position, position + STREAM_CHUNK_SIZE));
+        position += STREAM_CHUNK_SIZE;
+      },
+    });
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'Blob';
+  }
+}
+
+/**
+ * A Blob with a name and a last modification date.
+ */
+export class File extends Blob {
+  #name;
+  #lastModified;
+
+  /**
+   * Creates a new File object.
+   *
+   * @param {Array<(string|ArrayBuffer|ArrayBufferView|Blob)>} parts
+   * @param {string} name - The name of the file.
+   * @param {Object} [options]
+   * @param {string} [options.type] - The MIME type of the data.
+   * @param {number} [options.lastModified] - Milliseconds since the UNIX epoch.
+   * @returns {File}
+   */
+  constructor(parts, name, options = {}) {
+    if (arguments.length < 2) {
+      throw new TypeError(`The "parts" and "name" arguments are required.`);
+    }
+
+    super(parts, options);
+    this.#name = String(name);
+    this.#lastModified = Number(options?.lastModified ?? Date.now());
+  }
+
+  /**
+   * The name of the file.
+   */
+  get name() {
+    return this.#name;
+  }
+
+  /**
+   * The last modified date of the file (milliseconds since the UNIX epoch).
+   */
+  get lastModified() {
+    return this.#lastModified;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'File';
+  }
+}
+
+export default { Blob, File };
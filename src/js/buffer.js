@@ -0,0 +1,694 @@
+/**
+ * Buffer
+ *
+ * A Node.js compatible Buffer: A `Uint8Array` with extra methods for working
+ * with encoded strings and (endian aware) binary numbers.
+ *
+ * @see {@link https://nodejs.org/api/buffer.html}
+ *
+ * @module Buffer
+ */
+
+const binding = process.binding('buffer');
+
+// Supported encodings (and their aliases).
+const ENCODINGS = {
+  utf8: 'utf8',
+  'utf-8': 'utf8',
+  hex: 'hex',
+  base64: 'base64',
+  base64url: 'base64url',
+  latin1: 'latin1',
+  binary: 'latin1',
+  ascii: 'ascii',
+  ucs2: 'utf16le',
+  'ucs-2': 'utf16le',
+  utf16le: 'utf16le',
+  'utf-16le': 'utf16le',
+};
+
+// The max number of arguments passed at once to `String.fromCharCode`.
+const CHAR_CODES_CHUNK_SIZE = 0x1000;
+
+function normalizeEncoding(encoding) {
+  if (encoding === undefined || encoding === null) return 'utf8';
+  const normalized = ENCODINGS[String(encoding).toLowerCase()];
+  if (!normalized) {
+    throw new TypeError(`Unknown encoding: ${encoding}`);
+  }
+  return normalized;
+}
+
+// Converts a string to bytes, using a (normalized) encoding.
+function encode(string, encoding) {
+  switch (encoding) {
+    case 'latin1':
+    case 'ascii': {
+      const bytes = new Uint8Array(string.length);
+      for (let i = 0; i < string.length; i++) {
+        bytes[i] = string.charCodeAt(i) & 0xff;
+      }
+      return bytes;
+    }
+    case 'utf16le': {
+      const bytes = new Uint8Array(string.length * 2);
+      for (let i = 0; i < string.length; i++) {
+        const code = string.charCodeAt(i);
+        bytes[i * 2] = code & 0xff;
+        bytes[i * 2 + 1] = code >> 8;
+      }
+      return bytes;
+    }
+    default:
+      // Note: The encoding-heavy paths (utf8, hex and base64) are done in Rust.
+      return new Uint8Array(binding.encode(string, encoding));
+  }
+}
+
+function fromCharCodes(codes) {
+  let output = '';
+  for (let i = 0; i < codes.length; i += CHAR_CODES_CHUNK_SIZE) {
+    const chunk = codes.subarray(i, i + CHAR_CODES_CHUNK_SIZE);
+    output += String.fromCharCode.apply(null, chunk);
+  }
+  return output;
+}
+
+// Converts bytes to a string, using a (normalized) encoding.
+function decode(bytes, encoding) {
+  switch (encoding) {
+    case 'latin1':
+      return fromCharCodes(bytes);
+    case 'ascii':
+      return fromCharCodes(bytes.map((byte) => byte & 0x7f));
+    case 'utf16le': {
+      const length = bytes.length >> 1;
+      const codes = new Uint16Array(length);
+      for (let i = 0; i < length; i++) {
+        codes[i] = bytes[i * 2] | (bytes[i * 2 + 1] << 8);
+      }
+      return fromCharCodes(codes);
+    }
+    default:
+      return binding.decode(bytes, encoding);
+  }
+}
+
+function clampIndex(value, length, fallback) {
+  if (value === undefined) return fallback;
+  const index = Math.trunc(Number(value)) || 0;
+  return Math.min(Math.max(index, 0), length);
+}
+
+function toBytes(value, encoding) {
+  if (typeof value === 'string') {
+    return encode(value, normalizeEncoding(encoding));
+  }
+  if (value instanceof Uint8Array) return value;
+  if (ArrayBuffer.isView(value)) {
+    return new Uint8Array(value.buffer, value.byteOffset, value.byteLength);
+  }
+  throw new TypeError(
+    'The "value" argument must be of type string, Buffer or Uint8Array.'
+  );
+}
+
+function checkOffset(buffer, offset, size) {
+  const max = buffer.length - size;
+  if (!Number.isInteger(offset) || offset < 0 || offset > max) {
+    throw new RangeError(
+      `The value of "offset" is out of range. It must be >= 0 and <= ${max}. Received ${offset}`
+    );
+  }
+}
+
+function checkValue(value, min, max) {
+  if (typeof value !== typeof min) {
+    throw new TypeError(`The "value" argument must be of type ${typeof min}.`);
+  }
+  if (value < min || value > max) {
+    throw new RangeError(
+      `The value of "value" is out of range. It must be >= ${min} and <= ${max}. Received ${value}`
+    );
+  }
+}
+
+function checkByteLength(byteLength) {
+  if (!Number.isInteger(byteLength) || byteLength < 1 || byteLength > 6) {
+    throw new RangeError(
+      `The value of "byteLength" is out of range. It must be >= 1 and <= 6. Received ${byteLength}`
+    );
+  }
+}
+
+function dataView(buffer) {
+  return new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+}
+
+/**
+ * A fixed-length sequence of bytes (a subclass of `Uint8Array`).
+ */
+export class Buffer extends Uint8Array {
+  /**
+   * Creates a new Buffer from a string, an array(-like) of bytes, another
+   * Buffer, or an ArrayBuffer (sharing its memory).
+   *
+   * @param {(string|Array|ArrayBuffer|ArrayBufferView|Object)} value
+   * @param {(string|number)} [encodingOrOffset] - The string's encoding, or the ArrayBuffer's byte offset.
+   * @param {number} [length] - The number of bytes to use from the ArrayBuffer.
+   * @returns {Buffer}
+   */
+  static from(value, encodingOrOffset, length) {
+    if (typeof value === 'string') {
+      const encoding = normalizeEncoding(encodingOrOffset);
+      const bytes = encode(value, encoding);
+      return new Buffer(bytes.buffer, bytes.byteOffset, bytes.length);
+    }
+
+    // Buffers created from an ArrayBuffer share the same memory.
+    if (
+      value instanceof ArrayBuffer ||
+      (globalThis.SharedArrayBuffer && value instanceof SharedArrayBuffer)
+    ) {
+      const offset = clampIndex(encodingOrOffset, value.byteLength, 0);
+      const size = length ?? value.byteLength - offset;
+      const available = value.byteLength - offset;
+      return new Buffer(value, offset, Math.min(size, available));
+    }
+
+    if (value instanceof Uint8Array || value instanceof DataView) {
+      const buffer = new Buffer(value.byteLength);
+      buffer.set(toBytes(value));
+      return buffer;
+    }
+
+    // The output of `buffer.toJSON()`.
+    if (value?.type === 'Buffer' && Array.isArray(value.data)) {
+      return Buffer.from(value.data);
+    }
+
+    if (Array.isArray(value) || ArrayBuffer.isView(value)) {
+      const buffer = new Buffer(value.length);
+      buffer.set(value);
+      return buffer;
+    }
+
+    const primitive =
+      value?.[Symbol.toPrimitive]?.('string') ?? value?.valueOf?.();
+    if (primitive != null && primitive !== value) {
+      return Buffer.from(primitive, encodingOrOffset, length);
+    }
+
+    if (typeof value?.length === 'number') {
+      return Buffer.from(Array.from(value));
+    }
+
+    throw new TypeError(
+      'The first argument must be of type string, Buffer, ArrayBuffer, Array, or Array-like Object.'
+    );
+  }
+
+  /**
+   * Allocates a new zero-filled Buffer (unless a fill value is provided).
+   *
+   * @param {number} size - The length of the new Buffer.
+   * @param {(string|number|Uint8Array)} [fill] - The value to fill the Buffer with.
+   * @param {string} [encoding] - The encoding of a string fill value.
+   * @returns {Buffer}
+   */
+  static alloc(size, fill, encoding) {
+    if (!Number.isInteger(size) || size < 0) {
+      const message = 'The "size" argument must be a non-negative integer.';
+      throw new RangeError(message);
+    }
+    const buffer = new Buffer(size);
+    if (fill !== undefined && fill !== 0) buffer.fill(fill, encoding);
+    return buffer;
+  }
+
+  /**
+   * Allocates a new Buffer (which is zero-filled as well in Dune).
+   *
+   * @param {number} size - The length of the new Buffer.
+   * @returns {Buffer}
+   */
+  static allocUnsafe(size) {
+    return Buffer.alloc(size);
+  }
+
+  /**
+   * Returns the number of bytes of a string (when encoded), or a binary value.
+   *
+   * @param {(string|ArrayBuffer|ArrayBufferView)} value
+   * @param {string} [encoding]
+   * @returns {number}
+   */
+  static byteLength(value, encoding) {
+    if (typeof value === 'string') {
+      return encode(value, normalizeEncoding(encoding)).length;
+    }
+    if (value?.byteLength !== undefined) return value.byteLength;
+
+    throw new TypeError(
+      'The "string" argument must be of type string, Buffer, or ArrayBuffer.'
+    );
+  }
+
+  /**
+   * Joins a list of Buffers (or Uint8Arrays) into a new Buffer.
+   *
+   * @param {Uint8Array[]} list
+   * @param {number} [totalLength] - The length of the result (truncating or zero-filling).
+   * @returns {Buffer}
+   */
+  static concat(list, totalLength) {
+    if (!Array.isArray(list)) {
+      throw new TypeError('The "list" argument must be an instance of Array.');
+    }
+
+    const size =
+      totalLength ?? list.reduce((sum, item) => sum + item.length, 0);
+    const buffer = Buffer.alloc(size);
+    let offset = 0;
+
+    for (const item of list) {
+      if (!(item instanceof Uint8Array)) {
+        throw new TypeError('The "list" items must be Buffers or Uint8Arrays.');
+      }
+      if (offset >= size) break;
+      const bytes = item.subarray(0, size - offset);
+      buffer.set(bytes, offset);
+      offset += bytes.length;
+    }
+
+    return buffer;
+  }
+
+  /**
+   * Checks if the value is a Buffer.
+   *
+   * @param {*} value
+   * @returns {boolean}
+   */
+  static isBuffer(value) {
+    return value instanceof Buffer;
+  }
+
+  /**
+   * Checks if the encoding is supported.
+   *
+   * @param {string} encoding
+   * @returns {boolean}
+   */
+  static isEncoding(encoding) {
+    return typeof encoding === 'string' && encoding.toLowerCase() in ENCODINGS;
+  }
+
+  /**
+   * Compares two Buffers (useful for sorting).
+   *
+   * @param {Uint8Array} a
+   * @param {Uint8Array} b
+   * @returns {number} -1, 0 or 1.
+   */
+  static compare(a, b) {
+    const length = Math.min(a.length, b.length);
+    for (let i = 0; i < length; i++) {
+      if (a[i] !== b[i]) return a[i] < b[i] ? -1 : 1;
+    }
+    return Math.sign(a.length - b.length);
+  }
+
+  /**
+   * Decodes the Buffer (or a part of it) into a string.
+   *
+   * @param {string} [encoding] - The encoding to use (default: utf8).
+   * @param {number} [start] - The byte offset to start decoding at.
+   * @param {number} [end] - The byte offset to stop decoding at (not inclusive).
+   * @returns {string}
+   */
+  toString(encoding, start, end) {
+    start = clampIndex(start, this.length, 0);
+    end = clampIndex(end, this.length, this.length);
+    const length = Math.max(end - start, 0);
+    const bytes = new Uint8Array(this.buffer, this.byteOffset + start, length);
+    return decode(bytes, normalizeEncoding(encoding));
+  }
+
+  /**
+   * Writes an encoded string into the Buffer.
+   *
+   * Additional signatures:
+   * - write(string: string, encoding?: string)
+   * - write(string: string, offset: number, encoding?: string)
+   *
+   * @param {string} string
+   * @param {number} [offset] - The byte offset to start writing at.
+   * @param {number} [length] - The max number of bytes to write.
+   * @param {string} [encoding] - The encoding of the string (default: utf8).
+   * @returns {number} The number of bytes written.
+   */
+  write(string, offset, length, encoding) {
+    if (typeof offset === 'string') [offset, encoding] = [0, offset];
+    if (typeof length === 'string') [length, encoding] = [undefined, length];
+
+    offset = offset ?? 0;
+    if (!Number.isInteger(offset) || offset < 0 || offset > this.length) {
+      throw new RangeError(`The value of "offset" is out of range.`);
+    }
+
+    const bytes = encode(String(string), normalizeEncoding(encoding));
+    const available = this.length - offset;
+    const size = Math.min(bytes.length, length ?? Infinity, available);
+    this.set(bytes.subarray(0, size), offset);
+
+    return size;
+  }
+
+  /**
+   * Checks if both Buffers have the same bytes.
+   *
+   * @param {Uint8Array} other
+   * @returns {boolean}
+   */
+  equals(other) {
+    if (!(other instanceof Uint8Array)) {
+      const message = 'The "other" argument must be a Buffer or Uint8Array.';
+      throw new TypeError(message);
+    }
+    return Buffer.compare(this, other) === 0;
+  }
+
+  /**
+   * Compares the Buffer (or a part of it) with another one.
+   *
+   * @param {Uint8Array} target
+   * @param {number} [targetStart]
+   * @param {number} [targetEnd]
+   * @param {number} [sourceStart]
+   * @param {number} [sourceEnd]
+   * @returns {number} -1, 0 or 1.
+   */
+  compare(target, targetStart, targetEnd, sourceStart, sourceEnd) {
+    const source = this.subarray(sourceStart ?? 0, sourceEnd ?? this.length);
+    return Buffer.compare(
+      source,
+      target.subarray(targetStart ?? 0, targetEnd ?? target.length)
+    );
+  }
+
+  /**
+   * Copies bytes from the Buffer into a target Buffer (or Uint8Array).
+   *
+   * @param {Uint8Array} target
+   * @param {number} [targetStart]
+   * @param {number} [sourceStart]
+   * @param {number} [sourceEnd]
+   * @returns {number} The number of bytes copied.
+   */
+  copy(target, targetStart = 0, sourceStart = 0, sourceEnd = this.length) {
+    const available = Math.max(target.length - targetStart, 0);
+    const end = Math.min(sourceEnd, sourceStart + available, this.length);
+    const bytes = this.subarray(sourceStart, Math.max(end, sourceStart));
+    target.set(bytes, targetStart);
+    return bytes.length;
+  }
+
+  /**
+   * Returns a Buffer referencing the same memory (unlike `Uint8Array#slice`).
+   *
+   * @param {number} [start]
+   * @param {number} [end]
+   * @returns {Buffer}
+   */
+  slice(start, end) {
+    return this.subarray(start, end);
+  }
+
+  /**
+   * Fills the Buffer (or a part of it) with a value.
+   *
+   * Additional signatures:
+   * - fill(value: any, encoding: string)
+   * - fill(value: any, offset: number, encoding: string)
+   *
+   * @param {(string|number|Uint8Array)} value
+   * @param {number} [offset]
+   * @param {number} [end]
+   * @param {string} [encoding]
+   * @returns {Buffer}
+   */
+  fill(value, offset, end, encoding) {
+    if (typeof offset === 'string') [offset, encoding] = [undefined, offset];
+    if (typeof end === 'string') [end, encoding] = [undefined, end];
+
+    offset = clampIndex(offset, this.length, 0);
+    end = clampIndex(end, this.length, this.length);
+
+    if (typeof value === 'number' || typeof value === 'boolean') {
+      return super.fill(Number(value) & 0xff, offset, end);
+    }
+
+    const bytes = toBytes(value, encoding);
+    if (bytes.length === 0) {
+      throw new TypeError(`The argument "value" is invalid. Received ${value}`);
+    }
+
+    for (let i = offset; i < end; i += bytes.length) {
+      this.set(bytes.subarray(0, end - i), i);
+    }
+
+    return this;
+  }
+
+  /**
+   * Returns the index of the first occurrence of a value (or -1).
+   *
+   * @param {(string|number|Uint8Array)} value
+   * @param {number} [byteOffset]
+   * @param {string} [encoding]
+   * @returns {number}
+   */
+  indexOf(value, byteOffset = 0, encoding) {
+    if (typeof byteOffset === 'string') {
+      [byteOffset, encoding] = [0, byteOffset];
+    }
+    if (byteOffset < 0) byteOffset = Math.max(this.length + byteOffset, 0);
+
+    if (typeof value === 'number') {
+      return super.indexOf(value & 0xff, byteOffset);
+    }
+
+    const bytes = toBytes(value, encoding);
+    const last = this.length - bytes.length;
+
+    for (let i = byteOffset; i <= last; i++) {
+      let j = 0;
+      while (j < bytes.length && this[i + j] === bytes[j]) j++;
+      if (j === bytes.length) return i;
+    }
+
+    return -1;
+  }
+
+  /**
+   * Checks if the Buffer contains a value.
+   *
+   * @param {(string|number|Uint8Array)} value
+   * @param {number} [byteOffset]
+   * @param {string} [encoding]
+   * @returns {boolean}
+   */
+  includes(value, byteOffset, encoding) {
+    return this.indexOf(value, byteOffset, encoding) !== -1;
+  }
+
+  /**
+   * Returns a JSON representation of the Buffer.
+   *
+   * @returns {Object}
+   */
+  toJSON() {
+    return { type: 'Buffer', data: Array.from(this) };
+  }
+
+  /**
+   * Reads an unsigned, little-endian integer of up to 6 bytes.
+   *
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number}
+   */
+  readUIntLE(offset, byteLength) {
+    checkByteLength(byteLength);
+    checkOffset(this, offset, byteLength);
+    let value = 0;
+    for (let i = byteLength - 1; i >= 0; i--) {
+      value = value * 256 + this[offset + i];
+    }
+    return value;
+  }
+
+  /**
+   * Reads an unsigned, big-endian integer of up to 6 bytes.
+   *
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number}
+   */
+  readUIntBE(offset, byteLength) {
+    checkByteLength(byteLength);
+    checkOffset(this, offset, byteLength);
+    let value = 0;
+    for (let i = 0; i < byteLength; i++) {
+      value = value * 256 + this[offset + i];
+    }
+    return value;
+  }
+
+  /**
+   * Reads a signed, little-endian integer of up to 6 bytes.
+   *
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number}
+   */
+  readIntLE(offset, byteLength) {
+    const value = this.readUIntLE(offset, byteLength);
+    const limit = 2 ** (8 * byteLength - 1);
+    return value >= limit ? value - limit * 2 : value;
+  }
+
+  /**
+   * Reads a signed, big-endian integer of up to 6 bytes.
+   *
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number}
+   */
+  readIntBE(offset, byteLength) {
+    const value = this.readUIntBE(offset, byteLength);
+    const limit = 2 ** (8 * byteLength - 1);
+    return value >= limit ? value - limit * 2 : value;
+  }
+
+  /**
+   * Writes an unsigned, little-endian integer of up to 6 bytes.
+   *
+   * @param {number} value
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number} The offset plus the number of bytes written.
+   */
+  writeUIntLE(value, offset, byteLength) {
+    checkByteLength(byteLength);
+    checkOffset(this, offset, byteLength);
+    checkValue(value, 0, 2 ** (8 * byteLength) - 1);
+    for (let i = 0; i < byteLength; i++) {
+      this[offset + i] = value % 256;
+      value = Math.floor(value / 256);
+    }
+    return offset + byteLength;
+  }
+
+  /**
+   * Writes an unsigned, big-endian integer of up to 6 bytes.
+   *
+   * @param {number} value
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number} The offset plus the number of bytes written.
+   */
+  writeUIntBE(value, offset, byteLength) {
+    checkByteLength(byteLength);
+    checkOffset(this, offset, byteLength);
+    checkValue(value, 0, 2 ** (8 * byteLength) - 1);
+    for (let i = byteLength - 1; i >= 0; i--) {
+      this[offset + i] = value % 256;
+      value = Math.floor(value / 256);
+    }
+    return offset + byteLength;
+  }
+
+  /**
+   * Writes a signed, little-endian integer of up to 6 bytes.
+   *
+   * @param {number} value
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number} The offset plus the number of bytes written.
+   */
+  writeIntLE(value, offset, byteLength) {
+    checkByteLength(byteLength);
+    const limit = 2 ** (8 * byteLength - 1);
+    checkValue(value, -limit, limit - 1);
+    const unsigned = value < 0 ? value + limit * 2 : value;
+    return this.writeUIntLE(unsigned, offset, byteLength);
+  }
+
+  /**
+   * Writes a signed, big-endian integer of up to 6 bytes.
+   *
+   * @param {number} value
+   * @param {number} offset
+   * @param {number} byteLength
+   * @returns {number} The offset plus the number of bytes written.
+   */
+  writeIntBE(value, offset, byteLength) {
+    checkByteLength(byteLength);
+    const limit = 2 ** (8 * byteLength - 1);
+    checkValue(value, -limit, limit - 1);
+    const unsigned = value < 0 ? value + limit * 2 : value;
+    return this.writeUIntBE(unsigned, offset, byteLength);
+  }
+}
+
+// The fixed-size numbers that can be read and written: [method name suffix,
+// DataView accessor, byte size, value range (for integers)].
+const NUMBER_TYPES = [
+  ['Int8', 'Int8', 1, [-(2 ** 7), 2 ** 7 - 1]],
+  ['UInt8', 'Uint8', 1, [0, 2 ** 8 - 1]],
+  ['Int16', 'Int16', 2, [-(2 ** 15), 2 ** 15 - 1]],
+  ['UInt16', 'Uint16', 2, [0, 2 ** 16 - 1]],
+  ['Int32', 'Int32', 4, [-(2 ** 31), 2 ** 31 - 1]],
+  ['UInt32', 'Uint32', 4, [0, 2 ** 32 - 1]],
+  ['Float', 'Float32', 4],
+  ['Double', 'Float64', 8],
+  ['BigInt64', 'BigInt64', 8, [-(2n ** 63n), 2n ** 63n - 1n]],
+  ['BigUInt64', 'BigUint64', 8, [0n, 2n ** 64n - 1n]],
+];
+
+function defineMethod(name, method) {
+  const descriptor = { value: method, writable: true, configurable: true };
+  Object.defineProperty(Buffer.prototype, name, descriptor);
+
+  // Node.js also provides `Uint` aliases (e.g. `readUint8`).
+  if (name.includes('UInt')) {
+    const alias = name.replace('UInt', 'Uint');
+    Object.defineProperty(Buffer.prototype, alias, descriptor);
+  }
+}
+
+for (const [name, type, size, range] of NUMBER_TYPES) {
+  const endians = size === 1 ? [['', false]] : [['LE', true], ['BE', false]];
+
+  for (const [endian, littleEndian] of endians) {
+    defineMethod(`read${name}${endian}`, function (offset = 0) {
+      checkOffset(this, offset, size);
+      return dataView(this)[`get${type}`](offset, littleEndian);
+    });
+
+    defineMethod(`write${name}${endian}`, function (value, offset = 0) {
+      checkOffset(this, offset, size);
+      if (range) checkValue(value, ...range);
+      dataView(this)[`set${type}`](offset, value, littleEndian);
+      return offset + size;
+    });
+  }
+}
+
+for (const name of ['readUIntLE', 'readUIntBE', 'writeUIntLE', 'writeUIntBE']) {
+  defineMethod(name.replace('UInt', 'Uint'), Buffer.prototype[name]);
+}
+
+export default { Buffer };
@@ -43,54 +43,67 @@ const BG_BRIGHT_MAGENTA = ESC + '[105m';
 const BG_BRIGHT_CYAN = ESC + '[106m';
 const BG_BRIGHT_WHITE = ESC + '[107m';
 
+// Note: Colors are disabled when the output is not a terminal, when the
+// `NO_COLOR` env variable is set, or with the `--no-color` flag. It's checked
+// on first use, since this module may be evaluated in the startup snapshot.
+let enabled;
+
+const isEnabled = () => {
+  enabled ??= process.binding('stdio').isColorEnabled();
+  return enabled;
+};
+
+const style = (code) => (value) =>
+  isEnabled() ? code + value + CLEAR : `${value}`;
+
 /**
  * Text styling utilities.
  */
 
-export const bold = (value) => BOLD + value + CLEAR;
-export const underline = (value) => UNDERLINE + value + CLEAR;
+export const bold = style(BOLD);
+export const underline = style(UNDERLINE);
 
 /**
  * ANSI supported colors.
  */
 
-export const black = (value) => BLACK + value + CLEAR;
-export const red = (value) => RED + value + CLEAR;
-export const green = (value) => GREEN + value + CLEAR;
-export const yellow = (value) => YELLOW + value + CLEAR;
-export const blue = (value) => BLUE + value + CLEAR;
-export const magenta = (value) => MAGENTA + value + CLEAR;
-export const cyan = (value) => CYAN + value + CLEAR;
-export const white = (value) => WHITE + value + CLEAR;
-export const bright_black = (value) => BRIGHT_BLACK + value + CLEAR;
-export const bright_red = (value) => BRIGHT_RED + value + CLEAR;
-export const bright_green = (value) => BRIGHT_GREEN + value + CLEAR;
-export const bright_yellow = (value) => BRIGHT_YELLOW + value + CLEAR;
-export const bright_blue = (value) => BRIGHT_BLUE + value + CLEAR;
-export const bright_magenta = (value) => BRIGHT_MAGENTA + value + CLEAR;
-export const bright_cyan = (value) => BRIGHT_CYAN + value + CLEAR;
-export const bright_white = (value) => BRIGHT_WHITE + value + CLEAR;
+export const black = style(BLACK);
+export const red = style(RED);
+export const green = style(GREEN);
+export const yellow = style(YELLOW);
+export const blue = style(BLUE);
+export const magenta = style(MAGENTA);
+export const cyan = style(CYAN);
+export const white = style(WHITE);
+export const bright_black = style(BRIGHT_BLACK);
+export const bright_red = style(BRIGHT_RED);
+export const bright_green = style(BRIGHT_GREEN);
+export const bright_yellow = style(BRIGHT_YELLOW);
+export const bright_blue = style(BRIGHT_BLUE);
+export const bright_magenta = style(BRIGHT_MAGENTA);
+export const bright_cyan = style(BRIGHT_CYAN);
+export const bright_white = style(BRIGHT_WHITE);
 
 /**
  * ANSI supported colors for the background.
  */
 
-export const bg_black = (value) => BG_BLACK + value + CLEAR;
-export const bg_red = (value) => BG_RED + value + CLEAR;
-export const bg_green = (value) => BG_GREEN + value + CLEAR;
-export const bg_yellow = (value) => BG_YELLOW + value + CLEAR;
-export const bg_blue = (value) => BG_BLUE + value + CLEAR;
-export const bg_magenta = (value) => BG_MAGENTA + value + CLEAR;
-export const bg_cyan = (value) => BG_CYAN + value + CLEAR;
-export const bg_white = (value) => BG_WHITE + value + CLEAR;
-export const bg_bright_black = (value) => BG_BRIGHT_BLACK + value + CLEAR;
-export const bg_bright_red = (value) => BG_BRIGHT_RED + value + CLEAR;
-export const bg_bright_green = (value) => BG_BRIGHT_GREEN + value + CLEAR;
-export const bg_bright_yellow = (value) => BG_BRIGHT_YELLOW + value + CLEAR;
-export const bg_bright_blue = (value) => BG_BRIGHT_BLUE + value + CLEAR;
-export const bg_bright_magenta = (value) => BG_BRIGHT_MAGENTA + value + CLEAR;
-export const bg_bright_cyan = (value) => BG_BRIGHT_CYAN + value + CLEAR;
-export const bg_bright_white = (value) => BG_BRIGHT_WHITE + value + CLEAR;
+export const bg_black = style(BG_BLACK);
+export const bg_red = style(BG_RED);
+export const bg_green = style(BG_GREEN);
+export const bg_yellow = style(BG_YELLOW);
+export const bg_blue = style(BG_BLUE);
+export const bg_magenta = style(BG_MAGENTA);
+export const bg_cyan = style(BG_CYAN);
+export const bg_white = style(BG_WHITE);
+export const bg_bright_black = style(BG_BRIGHT_BLACK);
+export const bg_bright_red = style(BG_BRIGHT_RED);
+export const bg_bright_green = style(BG_BRIGHT_GREEN);
+export const bg_bright_yellow = style(BG_BRIGHT_YELLOW);
+export const bg_bright_blue = style(BG_BRIGHT_BLUE);
+export const bg_bright_magenta = style(BG_BRIGHT_MAGENTA);
+export const bg_bright_cyan = style(BG_BRIGHT_CYAN);
+export const bg_bright_white = style(BG_BRIGHT_WHITE);
 
 export default {
   bold,
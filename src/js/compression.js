@@ -0,0 +1,156 @@
+// Compression Streams API
+//
+// The CompressionStream and DecompressionStream classes compress (or
+// decompress) a stream of data using the gzip, deflate, deflate-raw or brotli
+// formats.
+//
+// https://compression.spec.whatwg.org/
+
+import { TransformStream } from '@web/streams';
+
+const binding = process.binding('compression');
+
+function toUint8Array(chunk) {
+  if (chunk instanceof Uint8Array) return chunk;
+  if (chunk instanceof ArrayBuffer) return new Uint8Array(chunk);
+  if (ArrayBuffer.isView(chunk)) {
+    return new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+  }
+  throw new TypeError('The chunk must be an ArrayBuffer or ArrayBufferView.');
+}
+
+/**
+ * Creates a transform stream backed by a native (streaming) codec.
+ */
+function createCodecStream(codec) {
+  return new TransformStream({
+    transform(chunk, controller) {
+      try {
+        const output = binding.write(codec, toUint8Array(chunk));
+        if (output.byteLength > 0) controller.enqueue(output);
+      } catch (err) {
+        binding.close(codec);
+        throw err;
+      }
+    },
+    flush(controller) {
+      const output = binding.finish(codec);
+      if (output.byteLength > 0) controller.enqueue(output);
+    },
+    // Note: A cancelled (or aborted) stream is never flushed, so the codec
+    // is dropped right away instead.
+    cancel() {
+      binding.close(codec);
+    },
+  });
+}
+
+/**
+ * Compresses a stream of data.
+ * https://developer.mozilla.org/en-US/docs/Web/API/CompressionStream
+ */
+export class CompressionStream {
+  #stream;
+
+  /**
+   * Creates a new CompressionStream object.
+   *
+   * @param {String} format - One of `gzip`, `deflate`, `deflate-raw` or `br`.
+   * @param {Object} [options]
+   * @param {Number} [options.level] - The compression level (0-9, or 0-11 for brotli).
+   * @returns {CompressionStream}
+   */
+  constructor(format, options = {}) {
+    const codec = binding.createCompressor(String(format), options.level);
+    this.#stream = createCodecStream(codec);
+  }
+
+  /**
+   * The readable side (compressed data) of the stream.
+   */
+  get readable() {
+    return this.#stream.readable;
+  }
+
+  /**
+   * The writable side (uncompressed data) of the stream.
+   */
+  get writable() {
+    return this.#stream.writable;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'CompressionStream';
+  }
+}
+
+/**
+ * Decompresses a stream of data.
+ * https://developer.mozilla.org/en-US/docs/Web/API/DecompressionStream
+ */
+export class DecompressionStream {
+  #stream;
+
+  /**
+   * Creates a new DecompressionStream object.
+   *
+   * @param {String} format - One of `gzip`, `deflate`, `deflate-raw` or `br`.
+   * @returns {DecompressionStream}
+   */
+  constructor(format) {
+    const codec = binding.createDecompressor(String(format));
+    this.#stream = createCodecStream(codec);
+  }
+
+  /**
+   * The readable side (decompressed data) of the stream.
+   */
+  get readable() {
+    return this.#stream.readable;
+  }
+
+  /**
+   * The writable side (compressed data) of the stream.
+   */
+  get writable() {
+    return this.#stream.writable;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'DecompressionStream';
+  }
+}
+
+/**
+ * Compresses a whole buffer at once.
+ *
+ * @param {Uint8Array} data - The data to be compressed.
+ * @param {String} format - One of `gzip`, `deflate`, `deflate-raw` or `br`.
+ * @param {Number} [level] - The compression level.
+ * @returns {Uint8Array}
+ */
+export function compress(data, format, level) {
+  const codec = binding.createCompressor(String(format), level);
+  const head = binding.write(codec, toUint8Array(data));
+  const tail = binding.finish(codec);
+
+  const output = new Uint8Array(head.byteLength + tail.byteLength);
+  output.set(head, 0);
+  output.set(tail, head.byteLength);
+  return output;
+}
+
+/**
+ * Compresses a whole buffer at once, on the thread-pool.
+ *
+ * @ignore
+ * @param {Uint8Array} data - The data to be compressed.
+ * @param {String} format - One of `gzip`, `deflate`, `deflate-raw` or `br`.
+ * @param {Number} [level] - The compression level.
+ * @returns {Promise<Uint8Array>}
+ */
+export function compressAsync(data, format, level) {
+  return binding.compress(toUint8Array(data), String(format), level);
+}
+
+export default { CompressionStream, DecompressionStream, compress };
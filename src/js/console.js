@@ -21,6 +21,13 @@ function pre(amount) {
   return ' '.repeat(amount);
 }
 
+// Returns the max length of a value printed on a single line.
+// Note: Values are split into multiple lines sooner in narrow terminals.
+function breakLength(limit) {
+  const columns = process.stdout.columns;
+  return columns ? Math.max(Math.min(limit, columns - 20), 16) : limit;
+}
+
 // Small util for objects that might not have a `.toString` method.
 function objectToString(value) {
   return Object.prototype.toString.call(value);
@@ -83,7 +90,7 @@ function stringifyArray(arr, seen, depth) {
   const uncoloredEntries = entries.join('').replace(/\u001b\[[0-9;]*m/g, '');
 
   // Multiline formatting.
-  if (uncoloredEntries.length > 60) {
+  if (uncoloredEntries.length > breakLength(60)) {
     const start = '[\n';
     const end = `\n${pre((depth - 1) * 2)}]`;
     const entriesPretty = prettifyArray(entries, depth, hasOnlyNumbers);
@@ -246,12 +253,13 @@ function stringifyPromise(value) {
   }
 
   const output = stringify(promiseValue, undefined, 1);
-  const end = `${output.length > 50 ? '\n' : ' '}}`;
+  const multiline = output.length > breakLength(50);
+  const end = `${multiline ? '\n' : ' '}}`;
 
   const prefix =
     state === 'FULFILLED'
-      ? `${output.length > 50 ? '\n  ' : ''}`
-      : `${output.length > 50 ? '\n  ' : ''}${red('<rejected>')} `;
+      ? `${multiline ? '\n  ' : ''}`
+      : `${multiline ? '\n  ' : ''}${red('<rejected>')} `;
 
   return 'Promise { ' + prefix + output + end;
 }
@@ -327,7 +335,7 @@ function stringifyObject(value, seen = new WeakSet(), depth) {
   const prefix = !className || className === 'Object' ? '' : className + ' ';
 
   // Apply multi-line formatting on long properties.
-  if (entries.map((v) => v.trim()).join('').length > 50) {
+  if (entries.map((v) => v.trim()).join('').length > breakLength(50)) {
     const start = `${prefix}{\n`;
     const end = `\n${pre((depth - 1) * 2)}}`;
     return `${start}${entries.join(',\n')}${end}`;
@@ -470,6 +478,31 @@ export class Console {
   }
 }
 
+/**
+ * Formats the given values the same way `console.log` does.
+ *
+ * @ignore
+ * @param  {...*} args - The values to format.
+ * @returns {String}
+ */
+export function format(...args) {
+  return args.map((arg) => stringify(arg)).join(' ');
+}
+
+// The V8 (inspector) console, available after `wrapConsole` is called.
+let inspectorConsole;
+
+/**
+ * Notifies the attached debugger (if any) about a console call, without printing.
+ *
+ * @ignore
+ * @param {String} method - The console method, e.g. "info" or "error".
+ * @param  {...*} args - The console call arguments.
+ */
+export function forwardToInspector(method, ...args) {
+  inspectorConsole?.[method]?.(...args);
+}
+
 // This wrapper forwards console messages to V8's internal console implementation,
 // triggering the `Runtime.consoleAPICalled` event. This ensures that the
 // attached debugger (if exists) is notified about the console call.
@@ -478,6 +511,9 @@ export class Console {
 // https://chromedevtools.github.io/devtools-protocol/tot/Runtime/#event-consoleAPICalled
 //
 export function wrapConsole(console, consoleFromV8) {
+  // Keep a reference for modules that print on their own (e.g. the logger).
+  inspectorConsole = consoleFromV8;
+
   // Get the property names of the console prototype.
   const prototype = Object.getPrototypeOf(console);
   const propertyNames = Object.getOwnPropertyNames(prototype);
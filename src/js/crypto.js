@@ -0,0 +1,76 @@
+// Crypto
+//
+// A subset of the Web Crypto API (random values only). The values come from the
+// operating system's CSPRNG, unless the `--seed-entropy` flag is used in which
+// case they are derived from the `--seed` number (reproducible, but insecure).
+//
+// https://developer.mozilla.org/en-US/docs/Web/API/Crypto
+
+const binding = process.binding('crypto');
+
+const INTEGER_ARRAYS = [
+  Int8Array,
+  Uint8Array,
+  Uint8ClampedArray,
+  Int16Array,
+  Uint16Array,
+  Int32Array,
+  Uint32Array,
+  BigInt64Array,
+  BigUint64Array,
+];
+
+/**
+ * Fills the given integer typed array with random values.
+ *
+ * @param {TypedArray} array - An integer-based typed array (max 65536 bytes).
+ * @returns {TypedArray}
+ */
+export function getRandomValues(array) {
+  if (!INTEGER_ARRAYS.some((type) => array instanceof type)) {
+    throw new TypeError('The array must be an integer-based TypedArray.');
+  }
+
+  const bytes = binding.randomBytes(array.byteLength);
+  new Uint8Array(array.buffer, array.byteOffset, array.byteLength).set(bytes);
+
+  return array;
+}
+
+/**
+ * Returns a new Uint8Array filled with random bytes.
+ *
+ * @param {Number} size - The number of bytes (max 65536).
+ * @returns {Uint8Array}
+ */
+export function randomBytes(size) {
+  if (!Number.isInteger(size) || size < 0) {
+    throw new TypeError('The size must be a non-negative integer.');
+  }
+  return binding.randomBytes(size);
+}
+
+/**
+ * Generates a random (version 4) UUID.
+ *
+ * @returns {String}
+ */
+export function randomUUID() {
+  const bytes = binding.randomBytes(16);
+
+  // Set the version (4) and variant (RFC 4122) bits.
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+  const hex = Array.from(bytes, (b) => b.toString(16).padStart(2, '0'));
+
+  return [
+    hex.slice(0, 4).join(''),
+    hex.slice(4, 6).join(''),
+    hex.slice(6, 8).join(''),
+    hex.slice(8, 10).join(''),
+    hex.slice(10, 16).join(''),
+  ].join('-');
+}
+
+export default { getRandomValues, randomBytes, randomUUID };
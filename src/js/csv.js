@@ -0,0 +1,128 @@
+/**
+ * CSV API
+ *
+ * Parses CSV text into rows (or objects keyed by the header row) and
+ * serializes rows back to CSV. Large inputs can be parsed as a stream, one
+ * chunk at a time.
+ *
+ * @module CSV
+ */
+
+const binding = process.binding('csv');
+
+function getDialect(options) {
+  const { delimiter = ',', quote = '"' } = options;
+  return [delimiter, quote];
+}
+
+/**
+ * Converts parsed records into rows (using the header row when requested).
+ */
+class RowBuilder {
+  constructor(headers) {
+    this.headers = Array.isArray(headers) ? headers.map(String) : null;
+    this.useHeaderRow = headers === true;
+  }
+
+  build(records) {
+    const rows = [];
+    for (const record of records) {
+      // The first record names the columns.
+      if (this.useHeaderRow && !this.headers) {
+        this.headers = record;
+        continue;
+      }
+      rows.push(this.headers ? this.toObject(record) : record);
+    }
+    return rows;
+  }
+
+  toObject(record) {
+    const row = {};
+    this.headers.forEach((name, i) => (row[name] = record[i]));
+    return row;
+  }
+}
+
+/**
+ * Parses CSV text (quoted fields may contain delimiters and newlines).
+ *
+ * @param {(String|Uint8Array)} input - The CSV text (or its UTF-8 bytes).
+ * @param {Object} [options]
+ * @param {String} [options.delimiter] - The field delimiter (default: `,`).
+ * @param {String} [options.quote] - The quote character (default: `"`).
+ * @param {(Boolean|String[])} [options.headers] - Return objects keyed by the
+ * first row (`true`) or by the given column names.
+ * @returns {Array<(String[]|Object)>}
+ */
+export function parse(input, options = {}) {
+  const parser = binding.createParser(...getDialect(options));
+  const builder = new RowBuilder(options.headers);
+
+  const records = binding.parse(parser, input);
+  records.push(...binding.finish(parser));
+
+  return builder.build(records);
+}
+
+/**
+ * Parses a stream of CSV chunks, yielding rows as soon as they're complete.
+ *
+ * @param {AsyncIterable<(String|Uint8Array)>} source - A stream of chunks
+ * (e.g. a `File`, a `ReadableStream` or `fs.createReadStream()`).
+ * @param {Object} [options] - The same options as `parse`.
+ * @returns {AsyncGenerator<(String[]|Object)>}
+ */
+export async function* parseStream(source, options = {}) {
+  const parser = binding.createParser(...getDialect(options));
+  const builder = new RowBuilder(options.headers);
+
+  // Note: Readable stream generators (see `fs.createReadStream`) have to be
+  // called before being iterated.
+  const chunks = typeof source === 'function' ? source() : source;
+
+  for await (const chunk of chunks) {
+    yield* builder.build(binding.parse(parser, chunk));
+  }
+
+  yield* builder.build(binding.finish(parser));
+}
+
+/**
+ * Serializes rows to CSV text, quoting fields only when needed.
+ *
+ * @param {Array<(Array|Object)>} rows - Arrays of fields or objects.
+ * @param {Object} [options]
+ * @param {String} [options.delimiter] - The field delimiter (default: `,`).
+ * @param {String} [options.quote] - The quote character (default: `"`).
+ * @param {(Boolean|String[])} [options.headers] - The column names (objects
+ * default to the keys of the first row), `false` omits the header row.
+ * @returns {String}
+ */
+export function stringify(rows, options = {}) {
+  if (!Array.isArray(rows)) {
+    throw new TypeError('The rows must be an array.');
+  }
+
+  const toField = (value) => (value == null ? '' : String(value));
+  const isObjectRow = rows.length > 0 && !Array.isArray(rows[0]);
+
+  let headers = Array.isArray(options.headers) ? options.headers : null;
+
+  if (isObjectRow && !headers) {
+    headers = Object.keys(rows[0]);
+  }
+
+  const records = rows.map((row) => {
+    const fields = Array.isArray(row) ? row : headers.map((name) => row[name]);
+    return fields.map(toField);
+  });
+
+  if (headers && options.headers !== false) {
+    records.unshift(headers.map(toField));
+  }
+
+  return binding.stringify(records, ...getDialect(options));
+}
+
+export default { parse, parseStream, stringify };
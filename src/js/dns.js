@@ -32,13 +32,91 @@ export const IP_ADDRESS_V6 = new RegExp(
  * @property {string} family - Denoting the family of the address (`IPv4` or `IPv6`).
  */
 
+// Note: The OS resolver doesn't report the record TTLs, so resolutions are
+// cached for a configurable amount of time instead.
+const cacheOptions = {
+  ttl: 30_000,
+  negativeTtl: 1_000,
+  maxEntries: 1_000,
+};
+
+// Cached (or in-flight) resolutions keyed by hostname, in LRU order.
+const cache = new Map();
+
+// Returns a fresh cached resolution (marking it as recently used).
+function getCached(hostname) {
+  const entry = cache.get(hostname);
+  if (!entry) return;
+
+  if (entry.expires <= Date.now()) {
+    cache.delete(hostname);
+    return;
+  }
+
+  cache.delete(hostname);
+  cache.set(hostname, entry);
+
+  return entry.promise;
+}
+
+// Caches a pending resolution, evicting the least recently used ones.
+function setCached(hostname, promise) {
+  const entry = { promise, expires: Infinity };
+
+  while (cache.size >= cacheOptions.maxEntries) {
+    cache.delete(cache.keys().next().value);
+  }
+
+  cache.set(hostname, entry);
+
+  // Failed resolutions (e.g. NXDOMAIN) are only cached briefly.
+  promise.then(
+    () => (entry.expires = Date.now() + cacheOptions.ttl),
+    () => (entry.expires = Date.now() + cacheOptions.negativeTtl)
+  );
+}
+
+/**
+ * Configures the DNS cache.
+ *
+ * @param {Object} options - Configuration options for the cache.
+ * @param {Number} [options.ttl] - How long (in ms) resolutions are cached (default: 30000).
+ * @param {Number} [options.negativeTtl] - How long (in ms) failed resolutions are cached (default: 1000).
+ * @param {Number} [options.maxEntries] - The maximum number of cached hostnames (default: 1000).
+ */
+export function setCacheOptions(options = {}) {
+  for (const key of Object.keys(cacheOptions)) {
+    const value = options[key];
+    if (value === undefined) continue;
+    if (typeof value !== 'number' || value < 0) {
+      throw new TypeError(`The "${key}" option must be a non-negative number.`);
+    }
+    cacheOptions[key] = value;
+  }
+}
+
+/**
+ * Drops the cached resolutions of a hostname (or all of them).
+ *
+ * @param {String} [hostname] - The hostname to be flushed (default: every hostname).
+ */
+export function flushCache(hostname) {
+  if (hostname === undefined) {
+    cache.clear();
+    return;
+  }
+  cache.delete(hostname);
+}
+
 /**
  * Resolves a host name into the first found A (IPv4) or AAAA (IPv6) record.
  *
  * @param {String} hostname - Host name to resolve.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {Boolean} [options.cache] - Use (and update) the DNS cache (default: true).
  * @returns {Promise<Resolution[]>} An array of resolved hostnames.
  */
-export async function lookup(hostname) {
+export async function lookup(hostname, options = {}) {
   // Check the data argument type.
   if (!hostname || typeof hostname !== 'string') {
     throw new TypeError(`The "hostname" argument must be of type string.`);
@@ -54,9 +132,24 @@ export async function lookup(hostname) {
     return [{ family: 'IPv6', address: hostname }];
   }
 
-  return binding.lookup(hostname);
+  // Bypass the cache when asked to.
+  if (options.cache === false || cacheOptions.maxEntries === 0) {
+    return binding.lookup(hostname);
+  }
+
+  let promise = getCached(hostname);
+  if (!promise) {
+    promise = binding.lookup(hostname);
+    setCached(hostname, promise);
+  }
+
+  // Give each caller its own copy of the (shared) resolution.
+  const addresses = await promise;
+  return addresses.map((address) => ({ ...address }));
 }
 
 export default {
   lookup,
+  flushCache,
+  setCacheOptions,
 };
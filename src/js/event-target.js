@@ -0,0 +1,338 @@
+// Event Target API
+//
+// The EventTarget interface is implemented by objects that can receive events
+// and may have listeners for them (the web counterpart of the EventEmitter).
+//
+// https://developer.mozilla.org/en-US/docs/Web/API/EventTarget
+
+import { performance } from 'perf_hooks';
+
+// Note: The dispatch state of an event (and the listeners of a target) are kept
+// outside of the objects, so they're not exposed to user code.
+const eventState = new WeakMap();
+const targetListeners = new WeakMap();
+
+const NONE = 0;
+const CAPTURING_PHASE = 1;
+const AT_TARGET = 2;
+const BUBBLING_PHASE = 3;
+
+/**
+ * An event which takes place on an EventTarget.
+ */
+export class Event {
+  static NONE = NONE;
+  static CAPTURING_PHASE = CAPTURING_PHASE;
+  static AT_TARGET = AT_TARGET;
+  static BUBBLING_PHASE = BUBBLING_PHASE;
+
+  /**
+   * Creates a new Event object.
+   *
+   * @param {String} type - The name of the event.
+   * @param {Object} [options]
+   * @param {Boolean} [options.bubbles] - Indicates if the event bubbles.
+   * @param {Boolean} [options.cancelable] - Indicates if the event can be canceled.
+   * @param {Boolean} [options.composed] - Indicates if the event propagates across shadow roots.
+   * @returns {Event}
+   */
+  constructor(type, options = {}) {
+    if (arguments.length === 0) {
+      throw new TypeError(`The "type" argument must be specified.`);
+    }
+
+    eventState.set(this, {
+      type: String(type),
+      bubbles: Boolean(options?.bubbles),
+      cancelable: Boolean(options?.cancelable),
+      composed: Boolean(options?.composed),
+      timeStamp: performance.now(),
+      target: null,
+      currentTarget: null,
+      eventPhase: NONE,
+      canceled: false,
+      dispatching: false,
+      inPassiveListener: false,
+      stopPropagation: false,
+      stopImmediatePropagation: false,
+    });
+  }
+
+  get type() {
+    return eventState.get(this).type;
+  }
+
+  get bubbles() {
+    return eventState.get(this).bubbles;
+  }
+
+  get cancelable() {
+    return eventState.get(this).cancelable;
+  }
+
+  get composed() {
+    return eventState.get(this).composed;
+  }
+
+  get timeStamp() {
+    return eventState.get(this).timeStamp;
+  }
+
+  get target() {
+    return eventState.get(this).target;
+  }
+
+  get srcElement() {
+    return this.target;
+  }
+
+  get currentTarget() {
+    return eventState.get(this).currentTarget;
+  }
+
+  get eventPhase() {
+    return eventState.get(this).eventPhase;
+  }
+
+  get isTrusted() {
+    return false;
+  }
+
+  get defaultPrevented() {
+    return eventState.get(this).canceled;
+  }
+
+  get returnValue() {
+    return !this.defaultPrevented;
+  }
+
+  get cancelBubble() {
+    return eventState.get(this).stopPropagation;
+  }
+
+  set cancelBubble(value) {
+    if (value) this.stopPropagation();
+  }
+
+  /**
+   * Cancels the event (if it is cancelable and not in a passive listener).
+   */
+  preventDefault() {
+    const state = eventState.get(this);
+    if (state.cancelable && !state.inPassiveListener) {
+      state.canceled = true;
+    }
+  }
+
+  /**
+   * Prevents further propagation of the current event.
+   */
+  stopPropagation() {
+    eventState.get(this).stopPropagation = true;
+  }
+
+  /**
+   * Prevents other listeners of the same event from being called.
+   */
+  stopImmediatePropagation() {
+    const state = eventState.get(this);
+    state.stopPropagation = true;
+    state.stopImmediatePropagation = true;
+  }
+
+  /**
+   * Returns the event's path (the objects on which listeners will be invoked).
+   *
+   * @returns {Array<EventTarget>}
+   */
+  composedPath() {
+    const { currentTarget } = eventState.get(this);
+    return currentTarget ? [currentTarget] : [];
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'Event';
+  }
+}
+
+/**
+ * An event carrying custom data (via the `detail` property).
+ */
+export class CustomEvent extends Event {
+  #detail;
+
+  /**
+   * Creates a new CustomEvent object.
+   *
+   * @param {String} type - The name of the event.
+   * @param {Object} [options] - Same as Event's options.
+   * @param {*} [options.detail] - Event-dependent value associated with the event.
+   * @returns {CustomEvent}
+   */
+  constructor(type, options = {}) {
+    super(type, options);
+    this.#detail = options?.detail ?? null;
+  }
+
+  get detail() {
+    return this.#detail;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'CustomEvent';
+  }
+}
+
+function normalizeOptions(options) {
+  if (typeof options === 'boolean') return { capture: options };
+  return options ?? {};
+}
+
+/**
+ * An object that can receive events and may have listeners for them.
+ */
+export class EventTarget {
+  /**
+   * Creates a new EventTarget object.
+   *
+   * @returns {EventTarget}
+   */
+  constructor() {
+    targetListeners.set(this, new Map());
+  }
+
+  /**
+   * Registers an event listener for the given event type.
+   *
+   * @param {String} type - The name of the event.
+   * @param {(Function|Object)} callback - A function, or an object with a `handleEvent` method.
+   * @param {(Object|Boolean)} [options] - The listener options (or the capture flag).
+   * @param {Boolean} [options.capture] - Invokes the listener in the capturing phase.
+   * @param {Boolean} [options.once] - Removes the listener after its first invocation.
+   * @param {Boolean} [options.passive] - Indicates the listener never cancels the event.
+   * @param {AbortSignal} [options.signal] - Removes the listener when aborted.
+   */
+  addEventListener(type, callback, options = {}) {
+    const { capture, once, passive, signal } = normalizeOptions(options);
+
+    if (callback === null || callback === undefined) return;
+    if (signal?.aborted) return;
+
+    const listeners = targetListeners.get(this);
+    const list = listeners.get(String(type)) ?? [];
+    const isCapture = Boolean(capture);
+
+    // The same listener can't be registered twice (for the same phase).
+    if (list.some((l) => l.callback === callback && l.capture === isCapture)) {
+      return;
+    }
+
+    const onAbort = () => {
+      this.removeEventListener(type, callback, { capture: isCapture });
+    };
+
+    list.push({
+      callback,
+      capture: isCapture,
+      once: Boolean(once),
+      passive: Boolean(passive),
+      removed: false,
+      signal,
+      onAbort,
+    });
+
+    listeners.set(String(type), list);
+    signal?.addEventListener('abort', onAbort);
+  }
+
+  /**
+   * Removes an event listener previously registered with addEventListener().
+   *
+   * @param {String} type - The name of the event.
+   * @param {(Function|Object)} callback - The listener to be removed.
+   * @param {(Object|Boolean)} [options] - The listener options (or the capture flag).
+   */
+  removeEventListener(type, callback, options = {}) {
+    const capture = Boolean(normalizeOptions(options).capture);
+    const listeners = targetListeners.get(this);
+    const list = listeners.get(String(type)) ?? [];
+
+    const index = list.findIndex(
+      (l) => l.callback === callback && l.capture === capture
+    );
+
+    if (index === -1) return;
+
+    // Note: Marking the listener as removed makes sure it won't be called
+    // by a dispatch that is already in progress.
+    const [listener] = list.splice(index, 1);
+    listener.removed = true;
+    listener.signal?.removeEventListener('abort', listener.onAbort);
+  }
+
+  /**
+   * Dispatches an event to this target, invoking the affected listeners.
+   *
+   * @param {Event} event - The event to be dispatched.
+   * @returns {Boolean} False if the event was canceled, true otherwise.
+   */
+  dispatchEvent(event) {
+    if (!(event instanceof Event)) {
+      throw new TypeError(`The "event" argument must be an instance of Event.`);
+    }
+
+    const state = eventState.get(event);
+
+    if (state.dispatching) {
+      throw new Error('The event is already being dispatched.');
+    }
+
+    const list = targetListeners.get(this).get(state.type) ?? [];
+
+    state.dispatching = true;
+    state.target = this;
+    state.currentTarget = this;
+    state.eventPhase = AT_TARGET;
+
+    // Capturing listeners are invoked first (there is no tree to propagate).
+    const snapshot = [
+      ...list.filter((listener) => listener.capture),
+      ...list.filter((listener) => !listener.capture),
+    ];
+
+    for (const listener of snapshot) {
+      if (listener.removed) continue;
+      if (listener.once) {
+        this.removeEventListener(state.type, listener.callback, listener);
+      }
+
+      state.inPassiveListener = listener.passive;
+
+      try {
+        const { callback } = listener;
+        if (typeof callback === 'function') {
+          callback.call(this, event);
+        } else if (typeof callback?.handleEvent === 'function') {
+          callback.handleEvent(event);
+        }
+      } catch (err) {
+        reportError(err);
+      }
+
+      state.inPassiveListener = false;
+      if (state.stopImmediatePropagation) break;
+    }
+
+    state.dispatching = false;
+    state.currentTarget = null;
+    state.eventPhase = NONE;
+
+    return !state.canceled;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'EventTarget';
+  }
+}
+
+export default { Event, CustomEvent, EventTarget };
@@ -0,0 +1,518 @@
+// Fetch API Primitives
+//
+// The Headers, Request and Response interfaces of the Fetch API. They are used
+// by the global fetch() method, and by HTTP servers handling requests with the
+// same web standard primitives.
+//
+// https://fetch.spec.whatwg.org/
+
+import { Blob } from '@web/blob';
+import { ReadableStream } from '@web/streams';
+
+const TOKEN = /^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$/;
+const NULL_BODY_STATUSES = [101, 103, 204, 205, 304];
+const REDIRECT_STATUSES = [301, 302, 303, 307, 308];
+
+function normalizeName(name) {
+  const value = String(name);
+  if (!TOKEN.test(value)) {
+    throw new TypeError(`Invalid header name: "${value}"`);
+  }
+  return value.toLowerCase();
+}
+
+function normalizeValue(value) {
+  // Note: Leading and trailing HTTP whitespace is removed.
+  const normalized = String(value).replace(/^[\t\n\r ]+|[\t\n\r ]+$/g, '');
+  if (/[\0\r\n]/.test(normalized)) {
+    throw new TypeError(`Invalid header value: "${normalized}"`);
+  }
+  return normalized;
+}
+
+/**
+ * A collection of HTTP headers (with case-insensitive names).
+ * https://developer.mozilla.org/en-US/docs/Web/API/Headers
+ */
+export class Headers {
+  #map;
+
+  /**
+   * Creates a new Headers object.
+   *
+   * @param {(Headers|Array|Object)} [init] - Headers to initialize the object with.
+   * @returns {Headers}
+   */
+  constructor(init) {
+    this.#map = new Map();
+
+    if (init === undefined || init === null) return;
+
+    if (typeof init !== 'object') {
+      throw new TypeError(`The "init" argument must be of type object.`);
+    }
+
+    // Note: Iterating a Headers object also yields [name, value] pairs.
+    if (typeof init[Symbol.iterator] === 'function') {
+      for (const pair of init) {
+        if (pair?.length !== 2) {
+          throw new TypeError('Header pairs must be [name, value] tuples.');
+        }
+        this.append(pair[0], pair[1]);
+      }
+      return;
+    }
+
+    // Note: Multi-value headers (e.g., `set-cookie`) may be given as arrays.
+    for (const [name, value] of Object.entries(init)) {
+      [value].flat().forEach((item) => this.append(name, item));
+    }
+  }
+
+  /**
+   * Appends a new value to an existing header (or adds the header).
+   *
+   * @param {String} name
+   * @param {String} value
+   */
+  append(name, value) {
+    const key = normalizeName(name);
+    const values = this.#map.get(key) ?? [];
+    values.push(normalizeValue(value));
+    this.#map.set(key, values);
+  }
+
+  /**
+   * Deletes a header.
+   *
+   * @param {String} name
+   */
+  delete(name) {
+    this.#map.delete(normalizeName(name));
+  }
+
+  /**
+   * Returns all the values of a header (joined), or null if it doesn't exist.
+   *
+   * @param {String} name
+   * @returns {(String|null)}
+   */
+  get(name) {
+    return this.#map.get(normalizeName(name))?.join(', ') ?? null;
+  }
+
+  /**
+   * Returns the values of all the `Set-Cookie` headers.
+   *
+   * @returns {Array<String>}
+   */
+  getSetCookie() {
+    return [...(this.#map.get('set-cookie') ?? [])];
+  }
+
+  /**
+   * Checks if a header exists.
+   *
+   * @param {String} name
+   * @returns {Boolean}
+   */
+  has(name) {
+    return this.#map.has(normalizeName(name));
+  }
+
+  /**
+   * Sets a new value for a header (replacing any existing values).
+   *
+   * @param {String} name
+   * @param {String} value
+   */
+  set(name, value) {
+    this.#map.set(normalizeName(name), [normalizeValue(value)]);
+  }
+
+  /**
+   * Executes a callback for each header.
+   *
+   * @param {Function} callback
+   * @param {*} [thisArg]
+   */
+  forEach(callback, thisArg) {
+    for (const [name, value] of this) {
+      callback.call(thisArg, value, name, this);
+    }
+  }
+
+  /**
+   * Returns an iterator of the [name, value] pairs (sorted by name).
+   *
+   * @returns {Iterator}
+   */
+  *entries() {
+    const names = [...this.#map.keys()].sort();
+    for (const name of names) {
+      // Note: Set-Cookie headers can't be combined, so they're kept separate.
+      if (name === 'set-cookie') {
+        for (const value of this.#map.get(name)) yield [name, value];
+        continue;
+      }
+      yield [name, this.#map.get(name).join(', ')];
+    }
+  }
+
+  *keys() {
+    for (const [name] of this) yield name;
+  }
+
+  *values() {
+    for (const [, value] of this) yield value;
+  }
+
+  [Symbol.iterator]() {
+    return this.entries();
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'Headers';
+  }
+}
+
+function toBytes(chunk) {
+  if (chunk instanceof Uint8Array) return chunk;
+  if (chunk instanceof ArrayBuffer) return new Uint8Array(chunk);
+  if (ArrayBuffer.isView(chunk)) {
+    return new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+  }
+  if (typeof chunk === 'string') return new TextEncoder().encode(chunk);
+  throw new TypeError('Body chunks must be of type string or Uint8Array.');
+}
+
+async function* bytesOf(iterable) {
+  for await (const chunk of iterable) yield toBytes(chunk);
+}
+
+/**
+ * Extracts the (iterable) source and the content type of a body.
+ */
+function extractBody(body) {
+  if (body === undefined || body === null) return { source: null, type: null };
+
+  if (typeof body === 'string') {
+    const type = 'text/plain;charset=UTF-8';
+    return { source: [new TextEncoder().encode(body)], type };
+  }
+
+  if (body instanceof ArrayBuffer || ArrayBuffer.isView(body)) {
+    return { source: [toBytes(body)], type: null };
+  }
+
+  if (body instanceof Blob) {
+    return { source: body.stream(), type: body.type || null };
+  }
+
+  // Streams (or any other iterable of chunks).
+  if (
+    typeof body[Symbol.asyncIterator] === 'function' ||
+    typeof body[Symbol.iterator] === 'function'
+  ) {
+    return { source: body, type: null };
+  }
+
+  return { source: [toBytes(String(body))], type: 'text/plain;charset=UTF-8' };
+}
+
+/**
+ * The body mixin shared by requests and responses.
+ */
+class Body {
+  #source;
+  #stream;
+  #bodyUsed;
+
+  constructor(source) {
+    this.#source = source;
+    this.#bodyUsed = false;
+  }
+
+  /**
+   * A ReadableStream of the body contents.
+   */
+  get body() {
+    if (this.#source === null) return null;
+    this.#stream ??= ReadableStream.from(bytesOf(this.#source));
+    return this.#stream;
+  }
+
+  /**
+   * Stores a boolean value that declares whether the body has been used yet.
+   */
+  get bodyUsed() {
+    return this.#bodyUsed;
+  }
+
+  async #consume() {
+    if (this.#bodyUsed) {
+      throw new TypeError('The body has already been consumed.');
+    }
+
+    this.#bodyUsed = true;
+
+    const chunks = [];
+    let length = 0;
+
+    for await (const chunk of this.body ?? []) {
+      chunks.push(chunk);
+      length += chunk.byteLength;
+    }
+
+    const content = new Uint8Array(length);
+    let offset = 0;
+
+    for (const chunk of chunks) {
+      content.set(chunk, offset);
+      offset += chunk.byteLength;
+    }
+
+    return content;
+  }
+
+  /**
+   * Resolves with an ArrayBuffer representation of the body.
+   *
+   * @returns Promise<ArrayBuffer>
+   */
+  async arrayBuffer() {
+    const content = await this.#consume();
+    return content.buffer;
+  }
+
+  /**
+   * Resolves with a Uint8Array representation of the body.
+   *
+   * @returns Promise<Uint8Array>
+   */
+  async bytes() {
+    return this.#consume();
+  }
+
+  /**
+   * Resolves with a text representation of the body.
+   *
+   * @returns Promise<String>
+   */
+  async text() {
+    const content = await this.#consume();
+    return new TextDecoder().decode(content);
+  }
+
+  /**
+   * Resolves with the result of parsing the body text as JSON.
+   *
+   * @returns Promise<Object>
+   */
+  async json() {
+    const content = await this.text();
+    return JSON.parse(content);
+  }
+
+  /**
+   * Resolves with a Blob representation of the body.
+   *
+   * @returns Promise<Blob>
+   */
+  async blob() {
+    const content = await this.#consume();
+    const type = this.headers.get('content-type') ?? '';
+    return new Blob([content], { type });
+  }
+}
+
+/**
+ * The Request interface of the Fetch API represents a resource request.
+ * https://developer.mozilla.org/en-US/docs/Web/API/Request
+ */
+export class Request extends Body {
+  #url;
+  #method;
+  #headers;
+  #signal;
+
+  /**
+   * Creates a new Request object.
+   *
+   * @param {(String|Request)} input - The URL of the resource (or a Request to copy).
+   * @param {Object} [init]
+   * @param {String} [init.method] - The request method (default: `GET`).
+   * @param {(Headers|Object)} [init.headers] - The headers of the request.
+   * @param {*} [init.body] - The body of the request (string, buffer, Blob or stream).
+   * @param {AbortSignal} [init.signal] - A signal to abort the request.
+   * @returns {Request}
+   */
+  constructor(input, init = {}) {
+    const isRequest = input instanceof Request;
+    const method = String(init.method ?? (isRequest ? input.method : 'GET'));
+
+    if (!TOKEN.test(method)) {
+      throw new TypeError(`Invalid HTTP method: "${method}"`);
+    }
+
+    const upperMethod = method.toUpperCase();
+    const body = init.body ?? (isRequest ? input.body : null);
+
+    if (body !== null && (upperMethod === 'GET' || upperMethod === 'HEAD')) {
+      throw new TypeError('Requests with GET/HEAD method cannot have a body.');
+    }
+
+    const { source, type } = extractBody(body);
+    super(source);
+
+    this.#url = isRequest ? input.url : String(input);
+    this.#method = upperMethod;
+    this.#headers = new Headers(
+      init.headers ?? (isRequest ? input.headers : {})
+    );
+    this.#signal = init.signal ?? (isRequest ? input.signal : null);
+
+    if (type && !this.#headers.has('content-type')) {
+      this.#headers.set('content-type', type);
+    }
+  }
+
+  /**
+   * The URL of the request.
+   */
+  get url() {
+    return this.#url;
+  }
+
+  /**
+   * The method of the request (e.g., `GET`, `POST`).
+   */
+  get method() {
+    return this.#method;
+  }
+
+  /**
+   * The Headers object associated with the request.
+   */
+  get headers() {
+    return this.#headers;
+  }
+
+  /**
+   * The AbortSignal associated with the request (if any).
+   */
+  get signal() {
+    return this.#signal;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'Request';
+  }
+}
+
+/**
+ * The Response interface of the Fetch API represents the response to a request.
+ * https://developer.mozilla.org/en-US/docs/Web/API/Response
+ */
+export class Response extends Body {
+  #status;
+  #statusText;
+  #headers;
+
+  /**
+   * Creates a new Response object.
+   *
+   * @param {*} [body] - The body of the response (string, buffer, Blob or stream).
+   * @param {Object} [init]
+   * @param {Number} [init.status] - The status code of the response (default: 200).
+   * @param {String} [init.statusText] - The status message of the response.
+   * @param {(Headers|Object)} [init.headers] - The headers of the response.
+   * @returns {Response}
+   */
+  constructor(body = null, init = {}) {
+    const status = init.status ?? 200;
+
+    if (!Number.isInteger(status) || status < 200 || status > 599) {
+      throw new RangeError(`The status "${status}" is not in range 200-599.`);
+    }
+
+    if (body !== null && NULL_BODY_STATUSES.includes(status)) {
+      throw new TypeError(`A ${status} response cannot have a body.`);
+    }
+
+    const { source, type } = extractBody(body);
+    super(source);
+
+    this.#status = status;
+    this.#statusText = String(init.statusText ?? '');
+    this.#headers = new Headers(init.headers);
+
+    if (type && !this.#headers.has('content-type')) {
+      this.#headers.set('content-type', type);
+    }
+  }
+
+  /**
+   * Creates a response with a JSON body.
+   *
+   * @param {*} data - The data to be serialized as JSON.
+   * @param {Object} [init] - Same as the Response's options.
+   * @returns {Response}
+   */
+  static json(data, init = {}) {
+    const headers = new Headers(init.headers);
+    if (!headers.has('content-type')) {
+      headers.set('content-type', 'application/json');
+    }
+    return new Response(JSON.stringify(data), { ...init, headers });
+  }
+
+  /**
+   * Creates a redirect response to the given URL.
+   *
+   * @param {String} url - The URL the client should be redirected to.
+   * @param {Number} [status] - The redirect status code (default: 302).
+   * @returns {Response}
+   */
+  static redirect(url, status = 302) {
+    if (!REDIRECT_STATUSES.includes(status)) {
+      throw new RangeError(`Invalid redirect status code: "${status}"`);
+    }
+    return new Response(null, { status, headers: { location: String(url) } });
+  }
+
+  /**
+   * A boolean indicating whether the response was successful.
+   */
+  get ok() {
+    // Should be in the range (200 – 299).
+    return this.#status >= 200 && this.#status <= 299;
+  }
+
+  /**
+   * The status code of the response. (This will be 200 for a success).
+   */
+  get status() {
+    return this.#status;
+  }
+
+  /**
+   * The status message corresponding to the status code. (e.g., OK for 200).
+   */
+  get statusText() {
+    return this.#statusText;
+  }
+
+  /**
+   * The Headers object associated with the response.
+   */
+  get headers() {
+    return this.#headers;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'Response';
+  }
+}
+
+export default { Headers, Request, Response };
@@ -6,130 +6,282 @@
 // https://developer.mozilla.org/en-US/docs/Web/API/fetch
 
 import http from 'http';
+import { Blob } from '@web/blob';
+import { ReadableStream } from '@web/streams';
+import { DecompressionStream } from '@web/compression';
+import { Headers, Request, Response } from '@web/fetch_types';
 
-// Utility function that combines uint8arrays.
-function concatUint8Arrays(...arrays) {
-  return arrays.reduce(
-    (acc, array) => new Uint8Array([...acc, ...array]),
-    new Uint8Array(0)
-  );
+// Responses with these status codes never include a body.
+const NULL_BODY_STATUSES = [101, 103, 204, 205, 304];
+
+// Content encodings (and their stream formats) decompressed automatically.
+const CONTENT_ENCODINGS = {
+  gzip: 'gzip',
+  'x-gzip': 'gzip',
+  deflate: 'deflate',
+  br: 'br',
+};
+
+// The options that are part of a `Request` (the rest are dune specific).
+const REQUEST_INIT = ['method', 'headers', 'body', 'signal'];
+
+// Registered interceptors, in the order they run.
+const interceptors = [];
+
+function hasHeader(headers, name) {
+  return Object.keys(headers).some((key) => key.toLowerCase() === name);
 }
 
 /**
- * The Response interface of the Fetch API represents the response to a request.
- * https://developer.mozilla.org/en-US/docs/Web/API/Response
+ * Sends a request over the network (after the interceptors have run).
+ *
+ * @ignore
+ * @param {(String|Request)} input
+ * @param {Object} options
+ * @returns Promise<Response>
  */
-class Response {
-  #statusCode;
-  #headers;
-  #body;
-  #bodyUsed;
-
-  /**
-   * Creates a new Response object.
-   *
-   * @returns {Response}
-   */
-  constructor({ statusCode, headers, body }) {
-    this.#statusCode = statusCode;
-    this.#headers = headers;
-    this.#body = body;
-    this.#bodyUsed = false;
-  }
+async function sendRequest(input, options = {}) {
+  let url = input;
 
-  /**
-   * Resolves with a text representation of the response body.
-   *
-   * @returns Promise<String>
-   */
-  async text() {
-    const content = await this.#body.text();
-    this.#bodyUsed = true;
-    return content;
+  // Requests are unwrapped into the options of `http.request`.
+  if (input instanceof Request) {
+    url = input.url;
+    const headers = Object.fromEntries(input.headers);
+    const body = input.body && new Blob([await input.arrayBuffer()]);
+    const { method, signal } = input;
+    options = { method, headers, body, signal, ...options };
   }
 
-  /**
-   * Resolves with the result of parsing the response body text as JSON.
-   *
-   * @returns Promise<Object>
-   */
-  async json() {
-    const content = await this.#body.json();
-    this.#bodyUsed = true;
-    return content;
+  if (options.headers instanceof Headers) {
+    options = { ...options, headers: Object.fromEntries(options.headers) };
   }
 
-  /**
-   * Resolves with an ArrayBuffer representation of the response body.
-   *
-   * @returns Promise<ArrayBuffer>
-   */
-  async arrayBuffer() {
-    const chunks = [];
-    for await (const data of this.#body) {
-      const chunk = new Uint8Array(data);
-      chunks.push(chunk);
-    }
-    this.#bodyUsed = true;
-    const content = concatUint8Arrays(...chunks);
-    return content.buffer;
-  }
+  // Blob bodies are sent as raw bytes using the blob's MIME type.
+  if (options.body instanceof Blob) {
+    const { body } = options;
+    const headers = { ...options.headers };
+    const hasContentType = hasHeader(headers, 'content-type');
 
-  /**
-   * A ReadableStream of the body contents.
-   */
-  get body() {
-    return this.#body;
-  }
+    if (body.type && !hasContentType) headers['content-type'] = body.type;
 
-  /**
-   * Stores a boolean value that declares whether the body has been used in a response yet.
-   */
-  get bodyUsed() {
-    return this.#bodyUsed;
+    options = { ...options, headers, body: await body.bytes() };
   }
 
-  /**
-   * The Headers object associated with the response.
-   */
-  get headers() {
-    return this.#headers;
-  }
+  const { decompress = true, retry, ...requestOptions } = options;
 
-  /**
-   * A boolean indicating whether the response was successful.
-   */
-  get ok() {
-    // Should be in the range (200 – 299).
-    return this.#statusCode >= 200 && this.#statusCode <= 299;
+  // Advertise the encodings that can be decompressed (unless set already).
+  const headers = { ...requestOptions.headers };
+  if (decompress && !hasHeader(headers, 'accept-encoding')) {
+    headers['accept-encoding'] = 'gzip, deflate, br';
+    requestOptions.headers = headers;
   }
 
-  /**
-   * The status code of the response. (This will be 200 for a success).
-   */
-  get status() {
-    return this.#statusCode;
+  const send = () => sendOnce(url, requestOptions, decompress);
+
+  return retry ? withRetries(send, retry, requestOptions) : send();
+}
+
+// Status codes that are retried by default.
+const RETRY_STATUSES = [408, 429, 500, 502, 503, 504];
+
+// Error codes of (likely) transient network failures.
+const RETRY_ERROR_CODES = [
+  'ERR_CONNECTION_RESET',
+  'ERR_CONNECTION_REFUSED',
+  'ERR_CONNECTION_ABORTED',
+  'ERR_BROKEN_PIPE',
+  'ERR_TIMED_OUT',
+  'ERR_NOT_CONNECTED',
+];
+
+function isRetryableError(err) {
+  return RETRY_ERROR_CODES.includes(err?.code) || err?.name === 'TimeoutError';
+}
+
+// Returns the delay (in ms) requested by a `Retry-After` header (if any).
+function retryAfter(response) {
+  const value = response.headers.get('retry-after');
+  if (!value) return;
+
+  const seconds = Number(value);
+  if (Number.isFinite(seconds)) return Math.max(seconds * 1000, 0);
+
+  const date = Date.parse(value);
+  if (!Number.isNaN(date)) return Math.max(date - Date.now(), 0);
+}
+
+// Waits for the given time, unless the signal is aborted first.
+function sleep(ms, signal) {
+  return new Promise((resolve, reject) => {
+    if (signal?.aborted) return reject(signal.reason);
+
+    const onAbort = () => {
+      clearTimeout(timer);
+      reject(signal.reason);
+    };
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve();
+    }, ms);
+
+    signal?.addEventListener('abort', onAbort);
+  });
+}
+
+/**
+ * Sends a request again on transient failures, with exponential backoff.
+ *
+ * @ignore
+ * @param {Function} send - Sends the request (once).
+ * @param {(Boolean|Object)} retry - The retry options (`true` for the defaults).
+ * @param {Object} options - The options of the request.
+ * @returns Promise<Response>
+ */
+async function withRetries(send, retry, options) {
+  const {
+    attempts = 3,
+    backoffMs = 100,
+    on = RETRY_STATUSES,
+  } = retry === true ? {} : retry;
+
+  // Note: Streamed bodies are consumed by the first attempt, so they can't
+  // be sent again.
+  const { body, signal } = options;
+  const isReplayable =
+    body == null || typeof body === 'string' || body instanceof Uint8Array;
+
+  const maxAttempts = isReplayable ? Math.max(attempts, 1) : 1;
+
+  for (let attempt = 1; ; attempt++) {
+    const isLast = attempt >= maxAttempts;
+
+    // Exponential backoff with "equal" jitter (half fixed, half random).
+    const backoff = backoffMs * 2 ** (attempt - 1);
+    let delay = backoff / 2 + Math.random() * (backoff / 2);
+
+    try {
+      const response = await send();
+      if (isLast || !on.includes(response.status)) return response;
+
+      // Servers can tell when to try again (e.g. when rate limiting).
+      if (response.status === 429 || response.status === 503) {
+        delay = retryAfter(response) ?? delay;
+      }
+
+      // Discard the body (closing the connection) before trying again.
+      await response.body?.cancel();
+    } catch (err) {
+      if (isLast || signal?.aborted || !isRetryableError(err)) throw err;
+    }
+
+    await sleep(delay, signal);
   }
+}
+
+/**
+ * Sends a request over the network (once).
+ *
+ * @ignore
+ * @param {String} url
+ * @param {Object} requestOptions - The options of `http.request`.
+ * @param {Boolean} decompress - Decompresses encoded response bodies.
+ * @returns Promise<Response>
+ */
+async function sendOnce(url, requestOptions, decompress) {
+  // Fetch is a wrapper around `http.request`.
+  const res = await http.request(url, requestOptions);
+  const status = res.statusCode;
+
+  const encoding = res.headers['content-encoding']?.trim().toLowerCase();
+  const format = CONTENT_ENCODINGS[encoding];
+  const isHead = requestOptions.method?.toUpperCase() === 'HEAD';
+  const isEmpty = res.headers['content-length'] === '0';
+
+  let body = NULL_BODY_STATUSES.includes(status) ? null : res.body;
 
-  /**
-   * The status message corresponding to the status code. (e.g., OK for 200).
-   */
-  get statusText() {
-    return http.STATUS_CODES[this.#statusCode];
+  // Note: Encoded bodies are decompressed while being read.
+  if (body && decompress && format && !isHead && !isEmpty) {
+    body = ReadableStream.from(body).pipeThrough(
+      new DecompressionStream(format)
+    );
   }
+
+  return new Response(body, {
+    status,
+    statusText: http.STATUS_CODES[status] ?? '',
+    headers: res.headers,
+  });
 }
 
 /**
  * Starts the process of fetching a resource from the network.
  *
- * @param {String} url
+ * @param {(String|Request)} input
  * @param {Object} options
+ * @param {Boolean} [options.decompress] - Decompresses encoded response bodies (default: true).
+ * @param {(Boolean|Object)} [options.retry] - Retries transient failures (default: false).
+ * @param {Number} [options.retry.attempts] - The maximum number of attempts (default: 3).
+ * @param {Number} [options.retry.backoffMs] - The initial backoff, doubled after every attempt (default: 100).
+ * @param {Number[]} [options.retry.on] - The response status codes to retry (default: 408, 429, 500, 502, 503, 504).
  *
  * @returns Promise<Response>
  */
-async function fetch(url, options = {}) {
-  // Fetch is a wrapper around `http.request`.
-  return new Response(await http.request(url, options));
+async function fetch(input, options = {}) {
+  if (interceptors.length === 0) return sendRequest(input, options);
+
+  // Interceptors work on `Request` objects, while the dune specific options
+  // are passed along to the network layer untouched.
+  const init = {};
+  const extraOptions = {};
+  for (const [key, value] of Object.entries(options)) {
+    (REQUEST_INIT.includes(key) ? init : extraOptions)[key] = value;
+  }
+
+  const chain = interceptors.slice();
+  const dispatch = async (index, request) => {
+    if (!(request instanceof Request)) {
+      throw new TypeError('Interceptors must pass a Request to next().');
+    }
+    if (index === chain.length) return sendRequest(request, extraOptions);
+
+    const next = (nextRequest = request) => dispatch(index + 1, nextRequest);
+    const response = await chain[index](request, next);
+
+    if (!(response instanceof Response)) {
+      const message = 'Interceptors must return (or resolve to) a Response.';
+      throw new TypeError(message);
+    }
+    return response;
+  };
+
+  return dispatch(0, new Request(input, init));
+}
+
+/**
+ * Registers an interceptor that runs around every `fetch` call.
+ *
+ * The interceptor is called as `interceptor(request, next)` and must return
+ * (or resolve to) a Response, either the one of `next(request)` (which runs
+ * the rest of the interceptors and sends the request) or a synthetic one.
+ *
+ * @param {Function} interceptor - The interceptor to register.
+ * @returns {Function} A function that unregisters the interceptor.
+ */
+function intercept(interceptor) {
+  if (typeof interceptor !== 'function') {
+    throw new TypeError('The "interceptor" argument must be of type function.');
+  }
+
+  interceptors.push(interceptor);
+
+  return () => {
+    const index = interceptors.indexOf(interceptor);
+    if (index !== -1) interceptors.splice(index, 1);
+  };
 }
 
+fetch.intercept = intercept;
+
+export { Headers, Request, Response };
+
 export default fetch;
@@ -9,14 +9,65 @@
  * @module File-System
  */
 
+import { ReadableStream, WritableStream } from '@web/streams';
+import { AbortSignal, abortErrorOf } from '@web/abort';
+
 const binding = process.binding('fs');
 
 const BUFFER_SIZE = 40 * 1024; // 40KB bytes buffer when reading.
 
+export const constants = {
+  // Makes `copyFile` fail if the destination already exists.
+  COPYFILE_EXCL: binding.COPYFILE_EXCL,
+  // The checks `access` runs (they can be combined, e.g. `R_OK | W_OK`).
+  F_OK: binding.F_OK,
+  R_OK: binding.R_OK,
+  W_OK: binding.W_OK,
+  X_OK: binding.X_OK,
+};
+
+/**
+ * Runs a cancellable fs operation, rejecting (and cancelling the native task)
+ * as soon as the signal is aborted.
+ *
+ * Note: The native task stops at its next check (e.g. the next directory
+ * entry), so the work done until then isn't undone.
+ */
+function withSignal(signal, operation) {
+  if (signal === undefined) return operation();
+
+  if (!(signal instanceof AbortSignal)) {
+    throw new TypeError(`The "options.signal" must be an AbortSignal.`);
+  }
+
+  if (signal.aborted) return Promise.reject(abortErrorOf(signal));
+
+  const token = binding.createCancelToken();
+
+  return new Promise((resolve, reject) => {
+    const onAbort = () => {
+      binding.cancel(token);
+      reject(abortErrorOf(signal));
+    };
+
+    signal.addEventListener('abort', onAbort);
+
+    operation(token)
+      .then(resolve, reject)
+      .finally(() => {
+        signal.removeEventListener('abort', onAbort);
+        binding.cancel(token);
+      });
+  });
+}
+
 /**
  * A File object is an object wrapper for a numeric file descriptor.
  */
 export class File {
+  #readable;
+  #writable;
+
   /**
    * Creates a new File instance given a file path.
    *
@@ -148,6 +199,64 @@ export class File {
     binding.writeSync(this._handle, data);
   }
 
+  /**
+   * Writes asynchronously a binary buffer at a position of the file (without moving the cursor).
+   *
+   * Note: Files opened in append mode ignore the position on some platforms (e.g. Linux).
+   *
+   * @param {Uint8Array} data - The binary data to be written to the file.
+   * @param {Number} offset - The position in the file at which to begin writing.
+   * @returns {Promise<Number>} - The amount of bytes written.
+   */
+  async writeAt(data, offset) {
+    // Check the data argument type.
+    if (!(data instanceof Uint8Array)) {
+      throw new TypeError(`The "data" argument must be of type Uint8Array.`);
+    }
+
+    // Check the offset argument type.
+    if (!Number.isInteger(offset) || offset < 0) {
+      throw new TypeError(
+        `The "offset" argument must be a non-negative integer.`
+      );
+    }
+
+    // Check if the file is open.
+    if (!this._handle) {
+      throw new Error('The file is not open.');
+    }
+
+    return binding.writeAt(this._handle, data, offset);
+  }
+
+  /**
+   * Writes synchronously a binary buffer at a position of the file (without moving the cursor).
+   *
+   * @param {Uint8Array} data - The binary data to be written to the file.
+   * @param {Number} offset - The position in the file at which to begin writing.
+   * @returns {Number} - The amount of bytes written.
+   */
+  writeAtSync(data, offset) {
+    // Check the data argument type.
+    if (!(data instanceof Uint8Array)) {
+      throw new TypeError(`The "data" argument must be of type Uint8Array.`);
+    }
+
+    // Check the offset argument type.
+    if (!Number.isInteger(offset) || offset < 0) {
+      throw new TypeError(
+        `The "offset" argument must be a non-negative integer.`
+      );
+    }
+
+    // Check if the file is open.
+    if (!this._handle) {
+      throw new Error('The file is not open.');
+    }
+
+    return binding.writeAtSync(this._handle, data, offset);
+  }
+
   /**
    * Information about a specific `File` object.
    *
@@ -233,6 +342,50 @@ export class File {
     this.fd = null;
   }
 
+  /**
+   * A readable byte stream of the file contents (closes the file when done or cancelled).
+   *
+   * @returns {ReadableStream}
+   */
+  get readable() {
+    // Note: The same stream is returned every time, so it can be locked.
+    if (this.#readable) return this.#readable;
+
+    let offset = 0;
+    this.#readable = new ReadableStream({
+      type: 'bytes',
+      pull: async (controller) => {
+        // Note: Reads happen only on demand, so a slow consumer applies
+        // backpressure all the way down to the file.
+        const buffer = new Uint8Array(BUFFER_SIZE);
+        const bytesRead = await this.read(buffer, offset);
+        if (!bytesRead) {
+          await this.close();
+          return controller.close();
+        }
+        offset += bytesRead;
+        controller.enqueue(buffer.subarray(0, bytesRead));
+      },
+      cancel: () => this.close(),
+    });
+
+    return this.#readable;
+  }
+
+  /**
+   * A writable byte stream into the file (closes the file when closed or aborted).
+   *
+   * @returns {WritableStream}
+   */
+  get writable() {
+    this.#writable ??= new WritableStream({
+      write: (chunk) => this.write(chunk),
+      close: () => this.close(),
+      abort: () => this.close(),
+    });
+    return this.#writable;
+  }
+
   /**
    * The `File` instances are asynchronously iterable objects.
    * @ignore
@@ -289,15 +442,19 @@ class FsWatcher {
   /**
    * Creates a new FsWatcher instance.
    *
-   * @param {String} path - The path to be monitored for changes.
+   * @param {String|String[]} path - The path(s) to be monitored for changes.
    * @param {Boolean} recursive - The watcher will monitor changes in the directory and its subdirectories.
+   * @param {Number} debounceMs - How long (in ms) a path must be quiet before its coalesced events are emitted.
    * @returns {FsWatcher} An instance to monitor file or directory changes.
    */
-  constructor(path, recursive = false) {
+  constructor(path, recursive = false, debounceMs = 0) {
     this.#pushQueue = [];
     this.#pullQueue = [];
-    this.#id = binding.watch(path, recursive, (event) =>
-      this._asyncDispatch(event)
+    this.#id = binding.watch(
+      path,
+      recursive,
+      (event) => this.#id !== undefined && this._asyncDispatch(event),
+      debounceMs
     );
   }
 
@@ -366,6 +523,74 @@ class FsWatcher {
   }
 }
 
+// Default polling interval (in ms) of `watchFile`.
+const WATCH_FILE_INTERVAL = 5007;
+
+// Stats reported for paths that don't exist (yet).
+const EMPTY_STATS = Object.freeze({
+  size: 0,
+  atimeMs: 0,
+  mtimeMs: 0,
+  birthtimeMs: 0,
+  isFile: false,
+  isDirectory: false,
+  isSymbolicLink: false,
+});
+
+// Fields compared between polls to detect a change.
+const STAT_FIELDS = ['size', 'mtimeMs', 'birthtimeMs', 'inode', 'mode'];
+
+/**
+ * Polls the statistics of a path, notifying listeners when they change.
+ */
+class StatWatcher {
+  #timer;
+  #previous;
+  #polling;
+
+  /**
+   * Creates a new StatWatcher instance.
+   *
+   * @param {String} path - The path to be polled.
+   * @param {Number} interval - How often (in ms) the path is polled.
+   */
+  constructor(path, interval) {
+    this.path = path;
+    this.listeners = new Set();
+    this.#polling = false;
+    this.#timer = setInterval(() => this.#poll(), interval);
+  }
+
+  async #poll() {
+    // Skip this tick if the previous stat is still in flight.
+    if (this.#polling) return;
+    this.#polling = true;
+
+    const current = await binding.stat(this.path).catch(() => EMPTY_STATS);
+    const previous = this.#previous ?? current;
+    this.#previous = current;
+    this.#polling = false;
+
+    // Note: The first poll only records the initial statistics.
+    if (!this.#timer || previous === current) return;
+
+    const changed = STAT_FIELDS.some((key) => previous[key] !== current[key]);
+    if (!changed) return;
+
+    this.listeners.forEach((listener) => listener(current, previous));
+  }
+
+  /**
+   * Stops polling the path.
+   */
+  close() {
+    clearInterval(this.#timer);
+    this.#timer = undefined;
+  }
+}
+
+const statWatchers = new Map();
+
 /**
  * Asynchronously opens a file.
  *
@@ -415,33 +640,18 @@ export function openSync(path, mode = 'r') {
  * @returns {Promise<(String|Uint8Array)>} - The contents of the file.
  */
 export async function readFile(path, options = {}) {
-  // Create a new file instance.
-  const file = new File(path, 'r');
-  await file.open();
-
-  // Allocate a buffer to store all the bytes from the file.
-  const stat = await file.stat();
-  const data = new Uint8Array(stat.size);
-
-  let bytesRead = 0;
-
-  // Note: Since the file object is async iterable will read the entire content
-  // of the file using the for-await loop.
-  for await (let chunk of file) {
-    data.set(chunk, bytesRead);
-    bytesRead += chunk.length;
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
   }
 
-  await file.close();
-
-  // Decode given an encoder.
   const encoding = typeof options === 'string' ? options : options.encoding;
+  const utf8 = isUtf8(encoding);
 
-  if (encoding) {
-    return new TextDecoder(encoding).decode(data);
-  }
+  // Note: The whole file is read (and decoded when utf-8) on the thread-pool.
+  const data = await binding.readFile(path, utf8);
 
-  return data;
+  return utf8 ? data : decodeContents(new Uint8Array(data), encoding);
 }
 
 /**
@@ -453,28 +663,24 @@ export async function readFile(path, options = {}) {
  * @returns {(String|Uint8Array)} - The contents of the file.
  */
 export function readFileSync(path, options = {}) {
-  // Create a new file instance.
-  const file = new File(path, 'r');
-  file.openSync();
-
-  // Allocate a buffer to store all the bytes from the file.
-  const stat = file.statSync();
-  const data = new Uint8Array(stat.size);
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
 
-  let bytesRead = 0;
+  const encoding = typeof options === 'string' ? options : options.encoding;
+  const utf8 = isUtf8(encoding);
+  const data = binding.readFileSync(path, utf8);
 
-  // Note: Since the file object is iterable will read the entire content
-  // of the file using the for-of loop.
-  for (let chunk of file) {
-    data.set(chunk, bytesRead);
-    bytesRead += chunk.length;
-  }
+  return utf8 ? data : decodeContents(new Uint8Array(data), encoding);
+}
 
-  file.closeSync();
+function isUtf8(encoding) {
+  return /^utf-?8$/i.test(encoding || '');
+}
 
+function decodeContents(data, encoding) {
   // Decode given an encoder.
-  const encoding = typeof options === 'string' ? options : options.encoding;
-
   if (encoding) {
     return new TextDecoder(encoding).decode(data);
   }
@@ -556,181 +762,656 @@ export function writeFileSync(path, data, options = {}) {
 }
 
 /**
- * Copies asynchronously a file from the source path to destination path.
+ * Appends asynchronously contents to a file, creating the file if it doesn't exist.
  *
- * @param {String} source - The path of the source file to be copied.
- * @param {String} destination - The path where the source file will be copied to.
+ * @param {String} path - The path of the file where the data is to be appended.
+ * @param {(String|Uint8Array)} data - The data to append to the file.
+ * @param {(String|Object)} [options] - The options to control the file append operation.
+ * @param {String} [options.encoding] - The encoding to be used for writing the file.
  * @returns {Promise}
  */
-export async function copyFile(source, destination) {
-  // Check the source argument type.
-  if (typeof source !== 'string') {
-    throw new TypeError(`The "source" argument must be of type string.`);
+export async function appendFile(path, data, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
   }
 
-  // Check the destination argument type.
-  if (typeof destination !== 'string') {
-    throw new TypeError(`The "destination" argument must be of type string.`);
+  // Check the data argument type.
+  if (!(data instanceof Uint8Array) && typeof data !== 'string') {
+    throw new TypeError(
+      `The "data" argument must be of type string or Uint8Array.`
+    );
   }
 
-  return writeFile(destination, await readFile(source));
+  const encoding = typeof options === 'string' ? options : options.encoding;
+
+  return binding.appendFile(path, toUint8Array(data, encoding || 'utf-8'));
 }
 
 /**
- * Copies synchronously a file from the source path to destination path.
+ * Appends synchronously contents to a file, creating the file if it doesn't exist.
  *
- * @param {String} source - The path of the source file to be copied.
- * @param {String} destination - The path where the source file will be copied to.
+ * @param {String} path - The path of the file where the data is to be appended.
+ * @param {(String|Uint8Array)} data - The data to append to the file.
+ * @param {(String|Object)} [options] - The options to control the file append operation.
+ * @param {String} [options.encoding] - The encoding to be used for writing the file.
  */
-export function copyFileSync(source, destination) {
-  // Check the source argument type.
-  if (typeof source !== 'string') {
-    throw new TypeError(`The "source" argument must be of type string.`);
+export function appendFileSync(path, data, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
   }
 
-  // Check the destination argument type.
-  if (typeof destination !== 'string') {
-    throw new TypeError(`The "destination" argument must be of type string.`);
+  // Check the data argument type.
+  if (!(data instanceof Uint8Array) && typeof data !== 'string') {
+    throw new TypeError(
+      `The "data" argument must be of type string or Uint8Array.`
+    );
   }
 
-  return writeFileSync(destination, readFileSync(source));
+  const encoding = typeof options === 'string' ? options : options.encoding;
+
+  binding.appendFileSync(path, toUint8Array(data, encoding || 'utf-8'));
+}
+
+function validateLength(len) {
+  if (!Number.isInteger(len) || len < 0) {
+    throw new TypeError(`The "len" argument must be a non-negative integer.`);
+  }
 }
 
 /**
- * Retrieves asynchronously statistics for the file.
+ * Truncates (or zero-extends) asynchronously a file to the given length.
  *
- * @param {String} path - The path of the file for which statistics are to be retrieved.
- * @returns {Promise<FileStats>} An object containing the statistics of the file.
+ * @param {String} path - The path of the file to be truncated.
+ * @param {Number} [len] - The new length of the file in bytes.
+ * @returns {Promise}
  */
-export async function stat(path) {
+export async function truncate(path, len = 0) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  // Get path statistics.
-  const stats = await binding.stat(path);
+  validateLength(len);
 
-  return stats;
+  return binding.truncate(path, len);
 }
 
 /**
- * Retrieves synchronously statistics for the file.
+ * Truncates (or zero-extends) synchronously a file to the given length.
  *
- * @param {String} path - The path of the file for which statistics are to be retrieved.
- * @returns {Object} An object containing the statistics of the file.
+ * @param {String} path - The path of the file to be truncated.
+ * @param {Number} [len] - The new length of the file in bytes.
  */
-export function statSync(path) {
+export function truncateSync(path, len = 0) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  // Get path statistics.
-  const stats = binding.statSync(path);
+  validateLength(len);
 
-  return stats;
+  binding.truncateSync(path, len);
 }
 
 /**
- * Creates directories asynchronously.
+ * Truncates (or zero-extends) asynchronously an open file to the given length.
  *
- * @param {String} path - The path where the new directory will be created.
- * @param {Object} [options] - Configuration options for directory creation.
- * @param {boolean}  [options.recursive] - Will create all directories necessary to reach the specified path.
+ * @param {File} file - The (open) file to be truncated.
+ * @param {Number} [len] - The new length of the file in bytes.
  * @returns {Promise}
  */
-export async function mkdir(path, options = {}) {
-  // Check the path argument type.
-  if (typeof path !== 'string') {
-    throw new TypeError('The "path" argument must be of type string.');
+export async function ftruncate(file, len = 0) {
+  // Check if the file is open.
+  if (!file?._handle) {
+    throw new Error('The file is not open.');
   }
 
-  await binding.mkdir(path, options?.recursive || false);
+  validateLength(len);
+
+  return binding.ftruncate(file._handle, len);
 }
 
 /**
- * Creates directories synchronously.
+ * Truncates (or zero-extends) synchronously an open file to the given length.
  *
- * @param {String} path - The path where the new directory will be created.
- * @param {Object} [options] - Configuration options for directory creation.
- * @param {boolean}  [options.recursive] - Will create all directories necessary to reach the specified path.
+ * @param {File} file - The (open) file to be truncated.
+ * @param {Number} [len] - The new length of the file in bytes.
  */
-export function mkdirSync(path, options = {}) {
-  // Check the path argument type.
-  if (typeof path !== 'string') {
-    throw new TypeError('The "path" argument must be of type string.');
+export function ftruncateSync(file, len = 0) {
+  // Check if the file is open.
+  if (!file?._handle) {
+    throw new Error('The file is not open.');
   }
 
-  binding.mkdirSync(path, options?.recursive || false);
+  validateLength(len);
+
+  binding.ftruncateSync(file._handle, len);
+}
+
+function validateIds(uid, gid) {
+  if (!Number.isInteger(uid) || uid < 0) {
+    throw new TypeError(`The "uid" argument must be a non-negative integer.`);
+  }
+  if (!Number.isInteger(gid) || gid < 0) {
+    throw new TypeError(`The "gid" argument must be a non-negative integer.`);
+  }
+}
+
+// Accepts the mode as a number (e.g. 0o644) or as an octal string (e.g. "644").
+function parseMode(mode) {
+  const value = typeof mode === 'string' ? parseInt(mode, 8) : mode;
+  if (!Number.isInteger(value) || value < 0 || value > 0o7777) {
+    throw new TypeError(`The "mode" argument must be a valid file mode.`);
+  }
+  return value;
+}
+
+// Accepts the times as milliseconds since the epoch or as Date objects.
+function toMillis(time, name) {
+  const value = time instanceof Date ? time.getTime() : time;
+  if (typeof value !== 'number' || !Number.isFinite(value)) {
+    throw new TypeError(`The "${name}" argument must be a number or a Date.`);
+  }
+  return value;
 }
 
 /**
- * Removes empty directories asynchronously.
+ * Changes asynchronously the access and modification times of a file.
  *
- * @param {String} path - The path of the directory to be removed.
- * @param {Object} [options] - Configuration options for directory removal.
- * @param {number} [options.maxRetries=0] - The maximum number of times to retry the removal in case of failure.
- * @param {number} [options.retryDelay=100] - The delay in milliseconds between retries.
+ * @param {String} path - The path of the file.
+ * @param {(Number|Date)} atime - The new access time (milliseconds since the epoch).
+ * @param {(Number|Date)} mtime - The new modification time (milliseconds since the epoch).
  * @returns {Promise}
  */
-export async function rmdir(path, options = {}, __retries = 0) {
+export async function utimes(path, atime, mtime) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  const maxRetries = options?.maxRetries || 0;
-  const retryDelay = options?.retryDelay || 100;
-
-  try {
-    // Try removing the empty directory.
-    await binding.rmdir(path);
-  } catch (err) {
-    // If we maxed out the retries accept failure.
-    if (__retries >= maxRetries) throw err;
-
-    // Note: Wrapping the setTimeout into a promise is necessary otherwise the
-    // outer rmdir call won't wait for all the inner ones.
-    await new Promise((success, failure) => {
-      // Back-off and retry later.
-      setTimeout(
-        () =>
-          rmdir(path, options, __retries + 1)
-            .then(success)
-            .catch(failure),
-        retryDelay
-      );
-    });
-  }
+  return binding.utimes(
+    path,
+    toMillis(atime, 'atime'),
+    toMillis(mtime, 'mtime')
+  );
 }
 
 /**
- * Removes empty directories synchronously.
+ * Changes synchronously the access and modification times of a file.
  *
- * @param {String} path - The path of the directory to be removed.
- * @param {Object} [options] - Configuration options for directory removal.
- * @param {number} [options.maxRetries=0] - The maximum number of times to retry the removal in case of failure.
- * @param {number} [options.retryDelay=100] - The delay in milliseconds between retries.
+ * @param {String} path - The path of the file.
+ * @param {(Number|Date)} atime - The new access time (milliseconds since the epoch).
+ * @param {(Number|Date)} mtime - The new modification time (milliseconds since the epoch).
  */
-export function rmdirSync(path, options = {}, __retries = 0) {
+export function utimesSync(path, atime, mtime) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  const maxRetries = options?.maxRetries || 0;
-  const retryDelay = options?.retryDelay || 100;
-
-  try {
-    // Try removing the empty directory.
-    binding.rmdirSync(path);
-  } catch (err) {
-    // If we maxed out the retries accept failure.
-    if (__retries >= maxRetries) throw err;
+  binding.utimesSync(path, toMillis(atime, 'atime'), toMillis(mtime, 'mtime'));
+}
 
-    // Back-off and retry later.
-    setTimeout(() => {
+/**
+ * Creates asynchronously a unique temporary directory.
+ *
+ * @param {String} prefix - The path prefix (random characters are appended to it).
+ * @returns {Promise<String>} The path of the created directory.
+ */
+export async function mkdtemp(prefix) {
+  // Check the prefix argument type.
+  if (typeof prefix !== 'string') {
+    throw new TypeError('The "prefix" argument must be of type string.');
+  }
+
+  return binding.mkdtemp(prefix);
+}
+
+/**
+ * Creates synchronously a unique temporary directory.
+ *
+ * @param {String} prefix - The path prefix (random characters are appended to it).
+ * @returns {String} The path of the created directory.
+ */
+export function mkdtempSync(prefix) {
+  // Check the prefix argument type.
+  if (typeof prefix !== 'string') {
+    throw new TypeError('The "prefix" argument must be of type string.');
+  }
+
+  return binding.mkdtempSync(prefix);
+}
+
+/**
+ * Changes asynchronously the permissions of a file.
+ *
+ * Note: On Windows only the read-only flag (the owner's write bit) is honored.
+ *
+ * @param {String} path - The path of the file.
+ * @param {(Number|String)} mode - The new permissions (e.g. `0o644` or `"644"`).
+ * @returns {Promise}
+ */
+export async function chmod(path, mode) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.chmod(path, parseMode(mode));
+}
+
+/**
+ * Changes synchronously the permissions of a file.
+ *
+ * Note: On Windows only the read-only flag (the owner's write bit) is honored.
+ *
+ * @param {String} path - The path of the file.
+ * @param {(Number|String)} mode - The new permissions (e.g. `0o644` or `"644"`).
+ */
+export function chmodSync(path, mode) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  binding.chmodSync(path, parseMode(mode));
+}
+
+/**
+ * Changes asynchronously the owner and group of a file (not supported on Windows).
+ *
+ * @param {String} path - The path of the file.
+ * @param {Number} uid - The id of the new owner.
+ * @param {Number} gid - The id of the new group.
+ * @returns {Promise}
+ */
+export async function chown(path, uid, gid) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  validateIds(uid, gid);
+
+  return binding.chown(path, uid, gid);
+}
+
+/**
+ * Changes synchronously the owner and group of a file (not supported on Windows).
+ *
+ * @param {String} path - The path of the file.
+ * @param {Number} uid - The id of the new owner.
+ * @param {Number} gid - The id of the new group.
+ */
+export function chownSync(path, uid, gid) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  validateIds(uid, gid);
+
+  binding.chownSync(path, uid, gid);
+}
+
+/**
+ * Copies asynchronously a file from the source path to destination path.
+ *
+ * @param {String} source - The path of the source file to be copied.
+ * @param {String} destination - The path where the source file will be copied to.
+ * @param {Number} [mode] - Modifiers for the copy (e.g. `constants.COPYFILE_EXCL`).
+ * @returns {Promise<Number>} The amount of bytes copied.
+ */
+export async function copyFile(source, destination, mode = 0) {
+  // Check the source argument type.
+  if (typeof source !== 'string') {
+    throw new TypeError(`The "source" argument must be of type string.`);
+  }
+
+  // Check the destination argument type.
+  if (typeof destination !== 'string') {
+    throw new TypeError(`The "destination" argument must be of type string.`);
+  }
+
+  return binding.copyFile(source, destination, mode);
+}
+
+/**
+ * Copies synchronously a file from the source path to destination path.
+ *
+ * @param {String} source - The path of the source file to be copied.
+ * @param {String} destination - The path where the source file will be copied to.
+ * @param {Number} [mode] - Modifiers for the copy (e.g. `constants.COPYFILE_EXCL`).
+ * @returns {Number} The amount of bytes copied.
+ */
+export function copyFileSync(source, destination, mode = 0) {
+  // Check the source argument type.
+  if (typeof source !== 'string') {
+    throw new TypeError(`The "source" argument must be of type string.`);
+  }
+
+  // Check the destination argument type.
+  if (typeof destination !== 'string') {
+    throw new TypeError(`The "destination" argument must be of type string.`);
+  }
+
+  return binding.copyFileSync(source, destination, mode);
+}
+
+// Validates the cp arguments and returns the options as booleans.
+function cpOptions(source, destination, options) {
+  // Check the source argument type.
+  if (typeof source !== 'string') {
+    throw new TypeError(`The "source" argument must be of type string.`);
+  }
+
+  // Check the destination argument type.
+  if (typeof destination !== 'string') {
+    throw new TypeError(`The "destination" argument must be of type string.`);
+  }
+
+  return [
+    Boolean(options?.recursive),
+    options?.force ?? true,
+    Boolean(options?.errorOnExist),
+  ];
+}
+
+/**
+ * Copies asynchronously a file or (recursively) a directory.
+ *
+ * @param {String} source - The path of the file or directory to be copied.
+ * @param {String} destination - The path where the source will be copied to.
+ * @param {Object} [options]
+ * @param {boolean} [options.recursive] - Copies directories and their contents.
+ * @param {boolean} [options.force] - Overwrites existing files (defaults to true).
+ * @param {boolean} [options.errorOnExist] - Rejects on existing files when `force` is false.
+ * @returns {Promise<void>}
+ */
+export async function cp(source, destination, options = {}) {
+  const flags = cpOptions(source, destination, options);
+  return binding.cp(source, destination, ...flags);
+}
+
+/**
+ * Copies synchronously a file or (recursively) a directory.
+ *
+ * @param {String} source - The path of the file or directory to be copied.
+ * @param {String} destination - The path where the source will be copied to.
+ * @param {Object} [options]
+ * @param {boolean} [options.recursive] - Copies directories and their contents.
+ * @param {boolean} [options.force] - Overwrites existing files (defaults to true).
+ * @param {boolean} [options.errorOnExist] - Throws on existing files when `force` is false.
+ */
+export function cpSync(source, destination, options = {}) {
+  const flags = cpOptions(source, destination, options);
+  binding.cpSync(source, destination, ...flags);
+}
+
+/**
+ * Retrieves asynchronously statistics for the file.
+ *
+ * @param {String} path - The path of the file for which statistics are to be retrieved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.cache] - Allows a recently cached result to be returned (see `clearStatCache`).
+ * @param {boolean} [options.bigint] - Returns the sizes, times and identifiers (ino, dev, blocks) as BigInts.
+ * @returns {Promise<FileStats>} An object containing the statistics of the file.
+ */
+export async function stat(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  // Get path statistics.
+  const stats = await binding.stat(
+    path,
+    Boolean(options.cache),
+    Boolean(options.bigint)
+  );
+
+  return stats;
+}
+
+/**
+ * Retrieves synchronously statistics for the file.
+ *
+ * @param {String} path - The path of the file for which statistics are to be retrieved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.cache] - Allows a recently cached result to be returned (see `clearStatCache`).
+ * @param {boolean} [options.bigint] - Returns the sizes, times and identifiers (ino, dev, blocks) as BigInts.
+ * @returns {Object} An object containing the statistics of the file.
+ */
+export function statSync(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  // Get path statistics.
+  const stats = binding.statSync(
+    path,
+    Boolean(options.cache),
+    Boolean(options.bigint)
+  );
+
+  return stats;
+}
+
+/**
+ * Checks asynchronously that the path exists and that the current user can access it.
+ *
+ * @param {String} path - The path to be checked.
+ * @param {Number} [mode] - The checks to run (`constants.F_OK` by default, or a mask of `R_OK`, `W_OK` and `X_OK`).
+ * @returns {Promise} Rejects when the path doesn't exist or the access is denied.
+ */
+export async function access(path, mode = constants.F_OK) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.access(path, mode);
+}
+
+/**
+ * Checks synchronously that the path exists and that the current user can access it.
+ *
+ * @param {String} path - The path to be checked.
+ * @param {Number} [mode] - The checks to run (`constants.F_OK` by default, or a mask of `R_OK`, `W_OK` and `X_OK`).
+ */
+export function accessSync(path, mode = constants.F_OK) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  binding.accessSync(path, mode);
+}
+
+/**
+ * Retrieves asynchronously statistics for the path, without following symbolic links.
+ *
+ * @param {String} path - The path for which statistics are to be retrieved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.bigint] - Returns the sizes, times and identifiers (ino, dev, blocks) as BigInts.
+ * @returns {Promise<FileStats>} An object containing the statistics of the path (or of the link itself).
+ */
+export async function lstat(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.lstat(path, Boolean(options.bigint));
+}
+
+/**
+ * Retrieves synchronously statistics for the path, without following symbolic links.
+ *
+ * @param {String} path - The path for which statistics are to be retrieved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.bigint] - Returns the sizes, times and identifiers (ino, dev, blocks) as BigInts.
+ * @returns {FileStats} An object containing the statistics of the path (or of the link itself).
+ */
+export function lstatSync(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.lstatSync(path, Boolean(options.bigint));
+}
+
+/**
+ * Resolves asynchronously a path to its canonical (absolute) form, following symbolic links.
+ *
+ * @param {String} path - The path to be resolved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.cache] - Allows a recently cached result to be returned (see `clearStatCache`).
+ * @returns {Promise<String>} The resolved path.
+ */
+export async function realpath(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.realpath(path, Boolean(options.cache));
+}
+
+/**
+ * Resolves synchronously a path to its canonical (absolute) form, following symbolic links.
+ *
+ * @param {String} path - The path to be resolved.
+ * @param {Object} [options] - Configuration options for the lookup.
+ * @param {boolean} [options.cache] - Allows a recently cached result to be returned (see `clearStatCache`).
+ * @returns {String} The resolved path.
+ */
+export function realpathSync(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.realpathSync(path, Boolean(options.cache));
+}
+
+/**
+ * Drops the cached `stat`/`realpath` results of a path and everything under it.
+ *
+ * @param {String} [path] - The path to be invalidated (default: every path).
+ */
+export function clearStatCache(path) {
+  // Check the path argument type.
+  if (path !== undefined && typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  binding.clearStatCache(path);
+}
+
+/**
+ * Creates directories asynchronously.
+ *
+ * @param {String} path - The path where the new directory will be created.
+ * @param {Object} [options] - Configuration options for directory creation.
+ * @param {boolean}  [options.recursive] - Will create all directories necessary to reach the specified path.
+ * @returns {Promise}
+ */
+export async function mkdir(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  await binding.mkdir(path, options?.recursive || false);
+}
+
+/**
+ * Creates directories synchronously.
+ *
+ * @param {String} path - The path where the new directory will be created.
+ * @param {Object} [options] - Configuration options for directory creation.
+ * @param {boolean}  [options.recursive] - Will create all directories necessary to reach the specified path.
+ */
+export function mkdirSync(path, options = {}) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  binding.mkdirSync(path, options?.recursive || false);
+}
+
+/**
+ * Removes empty directories asynchronously.
+ *
+ * @param {String} path - The path of the directory to be removed.
+ * @param {Object} [options] - Configuration options for directory removal.
+ * @param {number} [options.maxRetries=0] - The maximum number of times to retry the removal in case of failure.
+ * @param {number} [options.retryDelay=100] - The delay in milliseconds between retries.
+ * @returns {Promise}
+ */
+export async function rmdir(path, options = {}, __retries = 0) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  const maxRetries = options?.maxRetries || 0;
+  const retryDelay = options?.retryDelay || 100;
+
+  try {
+    // Try removing the empty directory.
+    await binding.rmdir(path);
+  } catch (err) {
+    // If we maxed out the retries accept failure.
+    if (__retries >= maxRetries) throw err;
+
+    // Note: Wrapping the setTimeout into a promise is necessary otherwise the
+    // outer rmdir call won't wait for all the inner ones.
+    await new Promise((success, failure) => {
+      // Back-off and retry later.
+      setTimeout(
+        () =>
+          rmdir(path, options, __retries + 1)
+            .then(success)
+            .catch(failure),
+        retryDelay
+      );
+    });
+  }
+}
+
+/**
+ * Removes empty directories synchronously.
+ *
+ * @param {String} path - The path of the directory to be removed.
+ * @param {Object} [options] - Configuration options for directory removal.
+ * @param {number} [options.maxRetries=0] - The maximum number of times to retry the removal in case of failure.
+ * @param {number} [options.retryDelay=100] - The delay in milliseconds between retries.
+ */
+export function rmdirSync(path, options = {}, __retries = 0) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  const maxRetries = options?.maxRetries || 0;
+  const retryDelay = options?.retryDelay || 100;
+
+  try {
+    // Try removing the empty directory.
+    binding.rmdirSync(path);
+  } catch (err) {
+    // If we maxed out the retries accept failure.
+    if (__retries >= maxRetries) throw err;
+
+    // Back-off and retry later.
+    setTimeout(() => {
       rmdirSync(path, options, __retries + 1);
     }, retryDelay);
   }
@@ -740,30 +1421,39 @@ export function rmdirSync(path, options = {}, __retries = 0) {
  * Reads asynchronously the contents of a directory.
  *
  * @param {String} path - The path of the directory whose contents are to be read.
- * @returns {Promise<String[]>} An array of strings, where each string is the name of a file or directory.
+ * @param {Object} [options]
+ * @param {AbortSignal} [options.signal] - Cancels the read (and rejects).
+ * @param {boolean} [options.withFileTypes] - Returns entry objects instead of names.
+ * @returns {Promise<String[]|Object[]>} An array of strings, where each string is the name of a file or directory.
  */
-export async function readdir(path) {
+export async function readdir(path, options = {}) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  return binding.readdir(path);
+  const withFileTypes = Boolean(options?.withFileTypes);
+
+  return withSignal(options?.signal, (token) =>
+    binding.readdir(path, token, withFileTypes)
+  );
 }
 
 /**
  * Reads the contents of a directory.
  *
  * @param {String} path - The path of the directory whose contents are to be read.
- * @returns {String[]} An array of strings, where each string is the name of a file or directory.
+ * @param {Object} [options]
+ * @param {boolean} [options.withFileTypes] - Returns entry objects instead of names.
+ * @returns {String[]|Object[]} An array of strings, where each string is the name of a file or directory.
  */
-export function readdirSync(path) {
+export function readdirSync(path, options = {}) {
   // Check the path argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  return binding.readdirSync(path);
+  return binding.readdirSync(path, Boolean(options?.withFileTypes));
 }
 
 /**
@@ -774,6 +1464,7 @@ export function readdirSync(path) {
  * @param {boolean} [options.recursive=false] - The method will remove the directory and all its contents recursively.
  * @param {number} [options.maxRetries=0] - The maximum number of times to retry the removal in case of failure.
  * @param {number} [options.retryDelay=100] - The delay in milliseconds between retries.
+ * @param {AbortSignal} [options.signal] - Stops the removal (and rejects), leaving the rest in place.
  * @returns {Promise}
  */
 export async function rm(path, options = {}, __retries = 0) {
@@ -786,6 +1477,9 @@ export async function rm(path, options = {}, __retries = 0) {
   const recursive = options?.recursive || false;
   const maxRetries = options?.maxRetries || 0;
   const retryDelay = options?.retryDelay || 100;
+  const signal = options?.signal;
+
+  signal?.throwIfAborted();
 
   // Get path's statistics.
   const pathStat = await stat(path);
@@ -797,10 +1491,10 @@ export async function rm(path, options = {}, __retries = 0) {
 
   try {
     // Try removing file or directory.
-    await binding.rm(path);
+    await withSignal(signal, (token) => binding.rm(path, token));
   } catch (err) {
     // If we maxed out the retries accept failure.
-    if (__retries >= maxRetries) throw err;
+    if (__retries >= maxRetries || signal?.aborted) throw err;
 
     // Note: Wrapping the setTimeout into a promise is necessary otherwise the
     // outer rm call won't wait for all the inner ones.
@@ -900,21 +1594,340 @@ export function renameSync(from, to) {
   binding.renameSync(from, to);
 }
 
+// Accepts a numeric file descriptor or anything exposing one (e.g. a File).
+function toFd(value, name) {
+  const fd = typeof value === 'number' ? value : value?.fd;
+  if (!Number.isInteger(fd) || fd < 0) {
+    throw new TypeError(`The "${name}" argument must be a file descriptor.`);
+  }
+  return fd;
+}
+
 /**
- * Returns an async iterator that watches for changes over a path.
+ * Passes an open file's descriptor to the other end of a unix socket.
  *
- * @param {String} path - The path to be monitored for changes.
+ * Note: The socket is given by its numeric descriptor (e.g. a socket passed
+ * down by systemd's socket activation or inherited from a parent process).
+ *
+ * @param {(Number|Object)} socket - The descriptor of a connected unix socket.
+ * @param {(File|Number)} file - The (open) file whose descriptor will be sent.
+ * @returns {Promise}
+ */
+export async function sendFd(socket, file) {
+  return binding.sendFd(toFd(socket, 'socket'), toFd(file, 'file'));
+}
+
+/**
+ * Receives a file descriptor sent over a unix socket.
+ *
+ * @param {(Number|Object)} socket - The descriptor of a connected unix socket.
+ * @returns {Promise<File>} A File wrapping the received descriptor.
+ */
+export async function recvFd(socket) {
+  const handle = await binding.recvFd(toFd(socket, 'socket'));
+  const file = new File(`fd:${handle.fd}`);
+
+  file._handle = handle;
+  file.fd = handle.fd;
+
+  return file;
+}
+
+function validateSymlinkType(type) {
+  if (type !== 'file' && type !== 'dir') {
+    throw new TypeError(`The "type" argument must be either "file" or "dir".`);
+  }
+}
+
+/**
+ * Creates asynchronously a symbolic link at `path` pointing to `target`.
+ *
+ * @param {String} target - The path the link will point to.
+ * @param {String} path - The path of the link to be created.
+ * @param {String} [type] - Either "file" or "dir" (only used on windows).
+ * @returns {Promise}
+ */
+export async function symlink(target, path, type = 'file') {
+  // Check the `target` argument type.
+  if (typeof target !== 'string') {
+    throw new TypeError('The "target" argument must be of type string.');
+  }
+
+  // Check the `path` argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  validateSymlinkType(type);
+
+  return binding.symlink(target, path, type);
+}
+
+/**
+ * Creates synchronously a symbolic link at `path` pointing to `target`.
+ *
+ * @param {String} target - The path the link will point to.
+ * @param {String} path - The path of the link to be created.
+ * @param {String} [type] - Either "file" or "dir" (only used on windows).
+ */
+export function symlinkSync(target, path, type = 'file') {
+  // Check the `target` argument type.
+  if (typeof target !== 'string') {
+    throw new TypeError('The "target" argument must be of type string.');
+  }
+
+  // Check the `path` argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  validateSymlinkType(type);
+
+  binding.symlinkSync(target, path, type);
+}
+
+/**
+ * Reads asynchronously the target of a symbolic link.
+ *
+ * @param {String} path - The path of the symbolic link.
+ * @returns {Promise<String>} The path the link points to.
+ */
+export async function readlink(path) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.readlink(path);
+}
+
+/**
+ * Reads synchronously the target of a symbolic link.
+ *
+ * @param {String} path - The path of the symbolic link.
+ * @returns {String} The path the link points to.
+ */
+export function readlinkSync(path) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return binding.readlinkSync(path);
+}
+
+/**
+ * A Dir object reads the entries of a directory lazily (one at a time).
+ */
+export class Dir {
+  /**
+   * Information about a directory entry.
+   *
+   * @typedef {Object} DirEntry
+   * @property {String} name - The name of the entry.
+   * @property {boolean} isDirectory - Returns `true` if the entry is a directory.
+   * @property {boolean} isFile - Returns `true` if the entry is a regular file.
+   * @property {boolean} isSymbolicLink - Returns `true` if the entry is a symbolic link.
+   */
+
+  constructor(path, handle) {
+    this.path = path;
+    this._handle = handle;
+  }
+
+  /**
+   * Reads asynchronously the next entry of the directory.
+   *
+   * @returns {Promise<DirEntry|null>} The next entry (or null when there are no more).
+   */
+  async read() {
+    // Check if the directory is open.
+    if (!this._handle) {
+      throw new Error('The directory is not open.');
+    }
+
+    return binding.readDirEntry(this._handle);
+  }
+
+  /**
+   * Reads synchronously the next entry of the directory.
+   *
+   * @returns {DirEntry|null} The next entry (or null when there are no more).
+   */
+  readSync() {
+    // Check if the directory is open.
+    if (!this._handle) {
+      throw new Error('The directory is not open.');
+    }
+
+    return binding.readDirEntrySync(this._handle);
+  }
+
+  /**
+   * Closes the directory asynchronously.
+   */
+  async close() {
+    this.closeSync();
+  }
+
+  /**
+   * Closes the directory synchronously.
+   */
+  closeSync() {
+    // Check if the directory is open.
+    if (!this._handle) {
+      throw new Error('The directory is not open.');
+    }
+
+    binding.closedir(this._handle);
+    this._handle = null;
+  }
+
+  /**
+   * Iterates over the entries, closing the directory at the end.
+   */
+  async *[Symbol.asyncIterator]() {
+    try {
+      let entry;
+      while ((entry = await this.read()) !== null) yield entry;
+    } finally {
+      if (this._handle) this.closeSync();
+    }
+  }
+
+  /**
+   * Iterates synchronously over the entries, closing the directory at the end.
+   */
+  *[Symbol.iterator]() {
+    try {
+      let entry;
+      while ((entry = this.readSync()) !== null) yield entry;
+    } finally {
+      if (this._handle) this.closeSync();
+    }
+  }
+}
+
+/**
+ * Opens asynchronously a directory for reading its entries one at a time.
+ *
+ * @param {String} path - The path of the directory to be opened.
+ * @returns {Promise<Dir>} An instance of the `Dir` class.
+ */
+export async function opendir(path) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return new Dir(path, await binding.opendir(path));
+}
+
+/**
+ * Opens synchronously a directory for reading its entries one at a time.
+ *
+ * @param {String} path - The path of the directory to be opened.
+ * @returns {Dir} An instance of the `Dir` class.
+ */
+export function opendirSync(path) {
+  // Check the path argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  return new Dir(path, binding.opendirSync(path));
+}
+
+/**
+ * Returns an async iterator that watches for changes over a path (or several).
+ *
+ * @param {String|String[]} path - The path(s) to be monitored for changes (events carry the watched `root`).
  * @param {Object} [options] - Configuration options for the file watcher.
  *  @param {boolean} [options.recursive] - Will monitor the specified directory and its subdirectories for changes.
+ *  @param {Number} [options.debounceMs] - Coalesces the events of a path until it's quiet for this long (default: 0).
  * @returns {FsWatcher} An instance of the `FsWatcher` class.
  */
 export function watch(path, options = {}) {
+  // Check the `path` argument type.
+  const paths = Array.isArray(path) ? path : [path];
+
+  if (paths.length === 0 || paths.some((p) => typeof p !== 'string')) {
+    throw new TypeError(
+      'The "path" argument must be of type string or an array of strings.'
+    );
+  }
+
+  const debounceMs = options.debounceMs ?? 0;
+
+  if (!Number.isFinite(debounceMs) || debounceMs < 0) {
+    throw new RangeError(
+      'The "debounceMs" option must be a non-negative number.'
+    );
+  }
+
+  return new FsWatcher(path, options.recursive, debounceMs);
+}
+
+/**
+ * Watches for changes on a path by polling its statistics.
+ *
+ * @param {String} path - The path to be monitored for changes.
+ * @param {Object} [options] - Configuration options for the watcher.
+ * @param {Number} [options.intervalMs] - How often (in ms) the path is polled (default: 5007).
+ * @param {Function} listener - Called with the current and previous statistics on change.
+ */
+export function watchFile(path, options, listener) {
+  // Use param overloading.
+  if (typeof options === 'function') {
+    listener = options;
+    options = {};
+  }
+
   // Check the `path` argument type.
   if (typeof path !== 'string') {
     throw new TypeError('The "path" argument must be of type string.');
   }
 
-  return new FsWatcher(path, options.recursive);
+  // Check the `listener` argument type.
+  if (typeof listener !== 'function') {
+    throw new TypeError('The "listener" argument must be of type function.');
+  }
+
+  const interval = options?.intervalMs ?? WATCH_FILE_INTERVAL;
+
+  if (!Number.isFinite(interval) || interval <= 0) {
+    throw new RangeError('The "intervalMs" option must be a positive number.');
+  }
+
+  // Listeners of the same path share a single poller.
+  if (!statWatchers.has(path)) {
+    statWatchers.set(path, new StatWatcher(path, interval));
+  }
+
+  statWatchers.get(path).listeners.add(listener);
+}
+
+/**
+ * Stops watching for changes on a path (started by `watchFile`).
+ *
+ * @param {String} path - The path being monitored.
+ * @param {Function} [listener] - The listener to remove (default: all of them).
+ */
+export function unwatchFile(path, listener) {
+  const watcher = statWatchers.get(path);
+  if (!watcher) return;
+
+  if (listener) {
+    watcher.listeners.delete(listener);
+  } else {
+    watcher.listeners.clear();
+  }
+
+  // Stop polling when nobody is listening anymore.
+  if (watcher.listeners.size === 0) {
+    watcher.close();
+    statWatchers.delete(path);
+  }
 }
 
 /**
@@ -943,6 +1956,49 @@ export function createReadStream(path, options = {}) {
   };
 }
 
+/**
+ * Returns an async iterator yielding the lines of a file (without reading
+ * the whole file into memory).
+ *
+ * @param {String} path - The path of the file to be read.
+ * @param {(String|Object)} [options] - Configuration options for the reader.
+ * @param {String} [options.encoding] - The encoding of the file (default: UTF-8).
+ * @returns {AsyncGenerator<String>} The lines, without their `\n` or `\r\n` endings.
+ */
+export async function* readLines(path, options = {}) {
+  // Check the `path` argument type.
+  if (typeof path !== 'string') {
+    throw new TypeError('The "path" argument must be of type string.');
+  }
+
+  const encoding = typeof options === 'string' ? options : options.encoding;
+  const textDecoder = new TextDecoder(encoding || 'utf-8');
+  const file = await open(path);
+
+  // Note: A line may span multiple chunks, so the incomplete tail of every
+  // chunk is carried over to the next one.
+  let pending = '';
+
+  try {
+    for await (const chunk of file) {
+      pending += textDecoder.decode(chunk, { stream: true });
+      const lines = pending.split('\n');
+      pending = lines.pop();
+      for (const line of lines) {
+        yield line.endsWith('\r') ? line.slice(0, -1) : line;
+      }
+    }
+
+    // Yield the final line (when the file doesn't end with a newline).
+    pending += textDecoder.decode();
+    if (pending) {
+      yield pending.endsWith('\r') ? pending.slice(0, -1) : pending;
+    }
+  } finally {
+    await file.close();
+  }
+}
+
 /**
  * Returns a new writable IO stream.
  *
@@ -972,27 +2028,63 @@ export function createWriteStream(path, options = {}) {
 
 export default {
   File,
+  Dir,
+  constants,
   open,
   openSync,
   readFile,
   readFileSync,
   writeFile,
   writeFileSync,
+  appendFile,
+  appendFileSync,
+  truncate,
+  truncateSync,
+  ftruncate,
+  ftruncateSync,
+  chmod,
+  chmodSync,
+  chown,
+  chownSync,
+  utimes,
+  utimesSync,
   copyFile,
   copyFileSync,
+  cp,
+  cpSync,
   stat,
   statSync,
+  access,
+  accessSync,
+  lstat,
+  lstatSync,
+  realpath,
+  realpathSync,
+  clearStatCache,
   mkdir,
   mkdirSync,
+  mkdtemp,
+  mkdtempSync,
   rmdir,
   rmdirSync,
   readdir,
   readdirSync,
+  opendir,
+  opendirSync,
   rm,
   rmSync,
   rename,
   renameSync,
+  symlink,
+  symlinkSync,
+  readlink,
+  readlinkSync,
+  sendFd,
+  recvFd,
   watch,
+  watchFile,
+  unwatchFile,
   createReadStream,
   createWriteStream,
+  readLines,
 };
@@ -10,11 +10,19 @@
  * @module HTTP
  */
 
+import fs from 'fs';
 import net from 'net';
+import path from 'path';
 import assert from 'assert';
 import { EventEmitter } from 'events';
+import { compressAsync } from '@web/compression';
+import {
+  Request as FetchRequest,
+  Response as FetchResponse,
+} from '@web/fetch_types';
 
 const binding = process.binding('http_parser');
+const cookies = process.binding('cookies');
 
 /**
  * A list of the HTTP methods that are supported by the parser.
@@ -192,6 +200,31 @@ function formatHeaders(headers) {
   return kHeaders;
 }
 
+function toHeaderValue(value) {
+  return Array.isArray(value) ? value.map(String) : String(value);
+}
+
+function checkMaxBodySize(value) {
+  if (value === Infinity) return value;
+  assert.integer(value, 'The "maxBodySize" option must be an integer.');
+  assert.greaterThanOrEqual(0, value);
+  return value;
+}
+
+function checkMaxHeaderSize(value = 16384) {
+  assert.integer(value, 'The "maxHeaderSize" option must be an integer.');
+  assert.greaterThan(0, value);
+  return value;
+}
+
+function bodyTooLargeError(limit) {
+  const message = `HTTP body exceeds the maximum size of ${limit} bytes.`;
+  const error = new Error(message);
+  error.code = 'ERR_BODY_TOO_LARGE';
+  error.status = 413;
+  return error;
+}
+
 async function* wrapIterable(iterable) {
   let result;
   let iterator = iterable[Symbol.asyncIterator]();
@@ -203,6 +236,144 @@ async function* wrapIterable(iterable) {
 
 const urlRegex = new RegExp('^(.*:)//([A-Za-z0-9-.]+)(:[0-9]+)?(.*)$');
 
+// Responses with these status codes never include a body.
+const NO_BODY_STATUSES = [101, 103, 204, 205, 304];
+
+/**
+ * An HTTP agent pooling idle (keep-alive) connections per origin, so later
+ * requests to the same host can skip opening a new connection.
+ */
+export class Agent {
+  #idle;
+  #active;
+  #waiting;
+
+  /**
+   * Creates a new Agent instance.
+   *
+   * @param {Object} [options] - Configuration options for the agent.
+   * @param {Boolean} [options.keepAlive] - Keeps connections around to be reused (default: true).
+   * @param {Number} [options.maxSockets] - The maximum number of concurrent connections per origin (default: Infinity).
+   * @param {Number} [options.idleTimeout] - How long (in ms) idle connections are kept open (default: 1000).
+   */
+  constructor(options = {}) {
+    this.keepAlive = options.keepAlive ?? true;
+    this.maxSockets = options.maxSockets ?? Infinity;
+    this.idleTimeout = options.idleTimeout ?? 1000;
+    this.#idle = new Map();
+    this.#active = new Map();
+    this.#waiting = new Map();
+
+    if (!(this.maxSockets > 0)) {
+      const message = 'The "maxSockets" option must be a positive number.';
+      throw new RangeError(message);
+    }
+  }
+
+  /**
+   * Returns a connection to the origin, reusing an idle one if possible.
+   *
+   * @ignore
+   * @param {String} hostname - The hostname of the origin.
+   * @param {Number} port - The port of the origin.
+   * @param {Boolean} [fresh] - Always opens a new connection.
+   * @returns {Promise<Object>} The socket and whether it was reused.
+   */
+  async acquire(hostname, port, fresh = false) {
+    const origin = `${hostname}:${port}`;
+
+    // Wait for a slot when the origin is at its connections limit.
+    while ((this.#active.get(origin) ?? 0) >= this.maxSockets) {
+      const waiting = this.#waiting.get(origin) ?? [];
+      this.#waiting.set(origin, waiting);
+      await new Promise((resolve) => waiting.push(resolve));
+    }
+
+    this.#active.set(origin, (this.#active.get(origin) ?? 0) + 1);
+
+    // Note: The most recently used connection is the least likely to have
+    // been closed by the server in the meantime.
+    const entry = fresh ? undefined : this.#idle.get(origin)?.pop();
+    if (entry) {
+      clearTimeout(entry.timer);
+      entry.socket.off('close', entry.onClose);
+      return { socket: entry.socket, reused: true };
+    }
+
+    try {
+      const socket = new net.Socket();
+      await socket.connect(port, hostname);
+      return { socket, reused: false };
+    } catch (err) {
+      this.#free(origin);
+      throw err;
+    }
+  }
+
+  /**
+   * Gives a connection back to the agent, to be pooled or closed.
+   *
+   * @ignore
+   * @param {String} hostname - The hostname of the origin.
+   * @param {Number} port - The port of the origin.
+   * @param {net.Socket} socket - The connection.
+   * @param {Boolean} reusable - The connection can be used for another request.
+   */
+  release(hostname, port, socket, reusable) {
+    const origin = `${hostname}:${port}`;
+    this.#free(origin);
+
+    if (!reusable || !this.keepAlive) {
+      socket.destroy();
+      return;
+    }
+
+    // Note: Idle connections shouldn't time out from read inactivity.
+    socket.setTimeout(0);
+
+    const entry = { socket };
+    const idle = this.#idle.get(origin) ?? [];
+    this.#idle.set(origin, idle);
+
+    const remove = () => {
+      const index = idle.indexOf(entry);
+      if (index !== -1) idle.splice(index, 1);
+    };
+
+    // Drop connections closed by the server or idle for too long.
+    entry.onClose = remove;
+    entry.timer = setTimeout(() => {
+      remove();
+      socket.destroy();
+    }, this.idleTimeout);
+
+    socket.on('close', entry.onClose);
+    idle.push(entry);
+  }
+
+  /**
+   * Closes all the idle connections of the agent.
+   */
+  destroy() {
+    for (const idle of this.#idle.values()) {
+      for (const { socket, timer } of idle.splice(0)) {
+        clearTimeout(timer);
+        socket.destroy();
+      }
+    }
+  }
+
+  #free(origin) {
+    this.#active.set(origin, this.#active.get(origin) - 1);
+    this.#waiting.get(origin)?.shift()?.();
+  }
+}
+
+/**
+ * The agent used by requests that don't specify one.
+ */
+export const globalAgent = new Agent();
+
 /**
  * An outgoing HTTP request to a remote host.
  * @ignore
@@ -220,6 +391,10 @@ class Request {
   #headers;
   #isChunkedEncoding;
   #signal;
+  #maxBodySize;
+  #maxHeaderSize;
+  #agent;
+  #responseStarted;
 
   constructor(url, options) {
     // Include protocol in URL.
@@ -244,6 +419,8 @@ class Request {
 
     this.#timeout = options.timeout;
     this.#throwOnError = options.throwOnError;
+    this.#maxBodySize = checkMaxBodySize(options.maxBodySize);
+    this.#maxHeaderSize = checkMaxHeaderSize(options.maxHeaderSize);
 
     this.#body = options.body;
     this.#bodyLength = this.#body?.length || 0;
@@ -262,6 +439,12 @@ class Request {
     this.#headers.set('connection', 'close');
     this.#headers.set('content-length', this.#bodyLength);
 
+    // Pool the connection unless disabled (`agent: false`) for this request.
+    this.#agent = options.agent === false ? null : options.agent || globalAgent;
+    if (this.#agent?.keepAlive) {
+      this.#headers.set('connection', 'keep-alive');
+    }
+
     // Check if encoding should be chunked.
     if (
       isIterable(this.#body) &&
@@ -277,11 +460,64 @@ class Request {
     for (const [name, value] of Object.entries(options.headers)) {
       this.#headers.set(name.toLowerCase(), value);
     }
+  }
 
-    this.#socket = new net.Socket();
+  async #acquireSocket(fresh = false) {
+    if (!this.#agent) {
+      const socket = new net.Socket();
+      await socket.connect(this.#port, this.#hostname);
+      return { socket, reused: false };
+    }
+    return this.#agent.acquire(this.#hostname, this.#port, fresh);
+  }
+
+  #releaseSocket(reusable, destroy = false) {
+    const socket = this.#socket;
+    if (!socket) return;
+    this.#socket = undefined;
+
+    // Dedicated connections are closed gracefully, unless something failed.
+    if (!this.#agent) {
+      destroy ? socket.destroy() : socket.end();
+      return;
+    }
+    this.#agent.release(this.#hostname, this.#port, socket, reusable);
   }
 
   async send() {
+    const { socket, reused } = await this.#acquireSocket();
+    this.#socket = socket;
+
+    // Subscribe to the abort-controller if provided.
+    if (this.#signal) {
+      this.#signal.addEventListener('abort', () =>
+        this.#releaseSocket(false, true)
+      );
+    }
+
+    try {
+      return await this.#exchange();
+    } catch (err) {
+      this.#releaseSocket(false, true);
+
+      // Note: Pooled connections might have been closed by the server while
+      // idle, so the request is retried once on a new connection (unless the
+      // body is a stream that can't be sent twice).
+      const canRetry = !this.#responseStarted && !this.#isChunkedEncoding;
+      if (!reused || !canRetry || this.#signal?.aborted) throw err;
+
+      this.#socket = (await this.#acquireSocket(true)).socket;
+
+      try {
+        return await this.#exchange();
+      } catch (err) {
+        this.#releaseSocket(false, true);
+        throw err;
+      }
+    }
+  }
+
+  async #exchange() {
     // Start building the HTTP message.
     const encoder = new TextEncoder();
     const reqHeaders = [`${this.#method} ${this.#path} HTTP/1.1`];
@@ -296,17 +532,11 @@ class Request {
     const reqHeadersBytes = encoder.encode(`${reqHeadersString}\r\n\r\n`);
 
     // Write headers to the socket.
-    await this.#socket.connect(this.#port, this.#hostname);
     await this.#socket.write(reqHeadersBytes);
 
-    // Subscribe to the abort-controller if provided.
-    if (this.#signal) {
-      this.#signal.addEventListener('abort', () => this.#socket.destroy());
-    }
-
     // Write body to the socket (sized).
     if (this.#body && !this.#isChunkedEncoding) {
-      this.#socket.write(this.#body);
+      await this.#socket.write(this.#body);
     }
 
     // Write body to the socket (chunked).
@@ -328,8 +558,9 @@ class Request {
 
     for await (const data of wrapIterable(this.#socket)) {
       // Concatenate existing buffer with new data.
+      this.#responseStarted = true;
       buffer = concatUint8Arrays(buffer, data);
-      const metadata = binding.parseResponse(buffer);
+      const metadata = binding.parseResponse(buffer, this.#maxHeaderSize);
 
       // Response headers are still incomplete.
       if (!metadata) continue;
@@ -340,11 +571,40 @@ class Request {
         throw new Error(`HTTP request failed with error: "${message}"`);
       }
 
+      // Refuse to read bodies that are known to be over the limit.
+      const contentLength = Number.parseInt(metadata.headers['content-length']);
+      if (contentLength > this.#maxBodySize) {
+        throw bodyTooLargeError(this.#maxBodySize);
+      }
+
       // Remove headers data from buffer.
       buffer = buffer.subarray(metadata.marker);
 
-      return new IncomingResponse(metadata, buffer, this.#socket);
+      // Note: The connection can only be reused if both sides agree to keep it
+      // alive, and once the response body has been read to its end.
+      const keepAlive =
+        this.#headers.get('connection') === 'keep-alive' &&
+        metadata.headers['connection']?.toLowerCase() !== 'close';
+
+      // Responses to HEAD requests (and a few status codes) have no body.
+      const hasBody = !(
+        this.#method === 'HEAD' ||
+        NO_BODY_STATUSES.includes(metadata.statusCode)
+      );
+
+      const release = (reusable) => this.#releaseSocket(keepAlive && reusable);
+
+      return new IncomingResponse(
+        metadata,
+        hasBody ? buffer : new Uint8Array(),
+        this.#socket,
+        release,
+        this.#maxBodySize,
+        hasBody
+      );
     }
+
+    throw new Error('Socket closed before receiving an HTTP response.');
   }
 }
 
@@ -356,10 +616,16 @@ class IncomingResponse {
   #headers;
   #body;
 
-  constructor(metadata, buffer, socket) {
+  constructor(metadata, buffer, socket, release, maxBodySize, hasBody) {
     this.#statusCode = metadata.statusCode;
     this.#headers = metadata.headers;
-    this.#body = new Body(metadata, buffer, socket, false);
+    this.#body = new Body(
+      hasBody ? metadata : { headers: {} },
+      buffer,
+      socket,
+      release,
+      maxBodySize
+    );
   }
 
   /**
@@ -399,22 +665,33 @@ class Body {
   #bodyLength;
   #isChunked;
   #isComplete;
-  #keepAlive;
+  #release;
+  #maxBodySize;
+  #received;
 
-  constructor({ headers }, buffer, socket, keepAlive = true) {
+  constructor({ headers }, buffer, socket, release, maxBodySize = Infinity) {
     this.#body = buffer;
     this.#bodyLength = Number.parseInt(headers['content-length']) || 0;
     this.#isChunked = headers['transfer-encoding']?.includes('chunked');
-    this.#isComplete = this.#body?.length === this.#bodyLength;
-    this.#keepAlive = keepAlive;
+    this.#isComplete =
+      !this.#isChunked && this.#body?.length === this.#bodyLength;
+    this.#release = release;
     this.#socket = socket;
+    this.#maxBodySize = maxBodySize;
+    this.#received = 0;
 
-    if (this.#isComplete && !this.#isChunked && !keepAlive) {
-      this.#socket.end();
-      this.#socket = undefined;
+    if (this.#isComplete && !this.#isChunked) {
+      this.#releaseSocket(true);
     }
   }
 
+  // Hands the socket back (to be reused or closed) once it's no longer needed.
+  #releaseSocket(reusable) {
+    if (!this.#socket) return;
+    this.#socket = undefined;
+    this.#release(reusable);
+  }
+
   /**
    * Formats the body to a UTF-8 string.
    *
@@ -446,7 +723,15 @@ class Body {
    */
   async *[Symbol.asyncIterator](signal) {
     // Close socket on stream pipeline errors.
-    if (signal) signal.on('uncaughtStreamException', () => this.#socket.end());
+    if (signal) {
+      signal.on('uncaughtStreamException', () => this.#releaseSocket(false));
+    }
+
+    // Content-Length bodies can be checked before reading them.
+    if (!this.#isChunked && this.#bodyLength > this.#maxBodySize) {
+      this.#releaseSocket(false);
+      throw bodyTooLargeError(this.#maxBodySize);
+    }
 
     if (this.#isComplete && !this.#isChunked) {
       const remainingContent = this.#body.subarray(0, this.#bodyLength);
@@ -459,6 +744,20 @@ class Body {
     // Node.js for example combines the first chunk with the HTTP headers when
     // sending responses with chunked encoding.
 
+    try {
+      yield* this.#readFromSocket();
+    } finally {
+      // Note: The socket is only reusable if the body was read to its end
+      // (it's closed when the consumer stops reading early, e.g. a cancelled
+      // stream, or the connection ended before the whole body arrived).
+      const reusable = this.#isComplete && this.#body.length === 0;
+      this.#releaseSocket(reusable);
+    }
+  }
+
+  async *#readFromSocket() {
+    if (!this.#socket) return;
+
     for await (const newData of wrapIterable(this.#socket)) {
       // Mix current body with new data.
       this.#body = concatUint8Arrays(this.#body, newData);
@@ -469,6 +768,8 @@ class Body {
         // No results means not enough bytes to extract the next chunk.
         if (result) {
           this.#body = this.#body.subarray(result.position);
+          this.#checkBodySize(result.chunks);
+          this.#isComplete = result.done;
           yield* result.chunks;
           if (result.done) break;
         }
@@ -477,15 +778,163 @@ class Body {
         // length is already known from the `Content-Length` header
         // but, it comes to us in multiple TCP packets.
         if (this.#body.length >= this.#bodyLength) {
-          yield this.#body.subarray(0, this.#bodyLength);
+          const content = this.#body.subarray(0, this.#bodyLength);
           this.#body = this.#body.subarray(this.#bodyLength);
+          this.#isComplete = true;
+          yield content;
           break;
         }
       }
     }
+  }
+
+  #checkBodySize(chunks) {
+    // Note: A single chunk is capped by the parser, but the total size of the
+    // body has to be tracked as chunks keep coming in.
+    for (const chunk of chunks) this.#received += chunk.byteLength;
+    if (this.#received > this.#maxBodySize) {
+      throw bodyTooLargeError(this.#maxBodySize);
+    }
+  }
+}
+
+/**
+ * Reads the HTTP requests (one after the other) of a server-side connection.
+ * @ignore
+ */
+class IncomingConnection {
+  #reader;
+  #parser;
+
+  constructor(socket, maxHeaderSize) {
+    this.#reader = socket[Symbol.asyncIterator]();
+    this.#parser = binding.createConnection(maxHeaderSize);
+  }
+
+  /**
+   * Returns the next parser event, reading from the socket when more bytes
+   * are needed (null means the socket has been closed).
+   */
+  async next(onData) {
+    let event;
+    while (!(event = binding.pollConnection(this.#parser))) {
+      const { value, done } = await this.#reader.next();
+      if (done) return null;
+      onData?.();
+      binding.feedConnection(this.#parser, value);
+    }
+    return event;
+  }
 
-    // Close TCP socket on not keep-alive connections.
-    if (!this.#keepAlive) this.#socket.end();
+  /**
+   * Frees the native parser (and its buffer) once the socket is done.
+   */
+  close() {
+    binding.closeConnection(this.#parser);
+  }
+}
+
+/**
+ * The body of an HTTP request received by the server.
+ * @ignore
+ */
+class ServerBody {
+  #connection;
+  #maxBodySize;
+  #received;
+  #done;
+  #reusable;
+
+  constructor(connection, maxBodySize) {
+    this.#connection = connection;
+    this.#maxBodySize = maxBodySize;
+    this.#received = 0;
+    this.#done = false;
+    this.#reusable = true;
+  }
+
+  /**
+   * Formats the body to a UTF-8 string.
+   *
+   * @returns {Promise<string>} The complete body as a UTF-8 string.
+   */
+  async text() {
+    const string = [];
+    const decoder = new TextDecoder();
+    for await (const chunk of this) {
+      string.push(decoder.decode(chunk, { stream: true }));
+    }
+    string.push(decoder.decode());
+    return string.join('');
+  }
+
+  /**
+   * Formats the body to an actual JSON object.
+   *
+   * @returns {Promise<Object>} The complete body as a JavaScript object.
+   */
+  async json() {
+    const data = await this.text();
+    return JSON.parse(data);
+  }
+
+  /**
+   * Reads the rest of the body, so the next request can be parsed.
+   *
+   * @returns {Promise<boolean>} False if the connection can't be reused.
+   */
+  async discard() {
+    try {
+      while (!this.#done) await this.#read();
+    } catch (_) {
+      this.#reusable = false;
+    }
+    return this.#reusable;
+  }
+
+  async #read() {
+    let event;
+    try {
+      event = await this.#connection.next();
+    } catch (err) {
+      this.#done = true;
+      this.#reusable = false;
+      throw err;
+    }
+
+    // The client closed the connection (or the request has been received).
+    if (!event || event.type === 'end') {
+      this.#done = true;
+      this.#reusable = Boolean(event?.keepAlive);
+      return [];
+    }
+
+    for (const chunk of event.chunks) this.#received += chunk.byteLength;
+
+    // Note: The rest of an oversized body is still on the wire, so the
+    // connection can't be reused for another request.
+    if (this.#received > this.#maxBodySize) {
+      this.#done = true;
+      this.#reusable = false;
+      throw bodyTooLargeError(this.#maxBodySize);
+    }
+
+    return event.chunks;
+  }
+
+  /**
+   * The HTTP body should be async iterable.
+   * @ignore
+   */
+  async *[Symbol.asyncIterator](signal) {
+    // Don't reuse the connection on stream pipeline errors.
+    if (signal) {
+      signal.on('uncaughtStreamException', () => (this.#reusable = false));
+    }
+
+    while (!this.#done) {
+      yield* await this.#read();
+    }
   }
 }
 
@@ -502,16 +951,29 @@ export class Server extends EventEmitter {
   #tcp;
   #pushQueue;
   #pullQueue;
+  #maxBodySize;
+  #maxHeaderSize;
+  #headersTimeout;
 
   /**
    * Creates a new Server instance.
    *
+   * @param {Object} [options] - Configuration options for the HTTP server.
+   * @param {Number} [options.maxBodySize] - The maximum size (in bytes) of a request body.
+   * @param {Number} [options.maxHeaderSize] - The maximum size (in bytes) of the request headers.
+   * @param {Number} [options.headersTimeout] - Time (in milliseconds) to receive the complete request headers.
    * @returns {Server} An instance of the HTTP `Server` class.
    */
-  constructor() {
+  constructor(options = {}) {
     super();
     this.#pushQueue = [];
     this.#pullQueue = [];
+    this.#maxBodySize = checkMaxBodySize(options.maxBodySize ?? Infinity);
+    this.#maxHeaderSize = checkMaxHeaderSize(options.maxHeaderSize);
+    this.#headersTimeout = options.headersTimeout ?? 60000;
+
+    const message = 'The "headersTimeout" option must be an integer.';
+    assert.integer(this.#headersTimeout, message);
 
     // Setting up the underling TCP server.
     this.#tcp = net.createServer(this.#handleConnectionSafely.bind(this));
@@ -560,47 +1022,93 @@ export class Server extends EventEmitter {
     // Set-up client event dispatcher.
     socket.on('error', (err) => this.emit('clientError', err));
 
-    // Set up a buffer to hold the incoming data.
-    let buffer = new Uint8Array();
+    const connection = new IncomingConnection(socket, this.#maxHeaderSize);
+    let headersTimer;
 
-    for await (const data of socket) {
-      // Concatenate existing buffer with new data.
-      buffer = concatUint8Arrays(buffer, data);
+    // Stalled connections (that never complete the request headers) are
+    // closed after the headers timeout.
+    const startHeadersTimer = () => {
+      if (headersTimer || !this.#headersTimeout) return;
+      headersTimer = setTimeout(async () => {
+        const message = 'HTTP/1.1 408 Request Timeout\r\nConnection: close';
+        await socket.write(`${message}\r\n\r\n`).catch(() => {});
+        socket.destroy();
+      }, this.#headersTimeout);
+    };
 
-      // Try parsing the HTTP headers.
-      let metadata;
-      try {
-        metadata = binding.parseRequest(buffer);
-      } catch (_) {
-        const message = 'HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n';
-        await socket.write(message);
-        break;
+    const clearHeadersTimer = () => {
+      clearTimeout(headersTimer);
+      headersTimer = undefined;
+    };
+
+    startHeadersTimer();
+
+    try {
+      while (true) {
+        // Note: The parser moves on to the next request by itself, so
+        // pipelined requests (sharing the same TCP packet) are also handled.
+        let metadata;
+        try {
+          metadata = await connection.next(startHeadersTimer);
+        } catch (err) {
+          const status =
+            err.code === 'ERR_HEADERS_TOO_LARGE'
+              ? 'HTTP/1.1 431 Request Header Fields Too Large'
+              : 'HTTP/1.1 400 Bad Request';
+          await socket.write(`${status}\r\nConnection: close\r\n\r\n`);
+          break;
+        }
+
+        // The client closed the connection.
+        if (!metadata) return;
+
+        clearHeadersTimer();
+
+        const keepAlive = await this.#handleRequest(
+          metadata,
+          connection,
+          socket
+        );
+        if (!keepAlive) break;
       }
+      await socket.end();
+    } finally {
+      clearHeadersTimer();
+      connection.close();
+    }
+  }
 
-      // Request headers are still incomplete.
-      if (!metadata) continue;
+  async #handleRequest(metadata, connection, socket) {
+    // Reject bodies that are known to be over the limit right away.
+    const contentLength = Number.parseInt(metadata.headers['content-length']);
+    if (contentLength > this.#maxBodySize) {
+      const status = 'HTTP/1.1 413 Payload Too Large';
+      await socket.write(`${status}\r\nConnection: close\r\n\r\n`);
+      return false;
+    }
 
-      buffer = buffer.subarray(metadata.marker);
+    // Create the request and response streams.
+    const body = new ServerBody(connection, this.#maxBodySize);
+    const request = new ServerRequest(metadata, body);
+    const response = new ServerResponse(metadata, socket);
 
-      // Create the request and response streams.
-      const request = new ServerRequest(metadata, buffer, socket);
-      const response = new ServerResponse(metadata, socket);
+    // Check if a request handler is specified; if so, emit the 'request' event.
+    const hasRequestHandler = this.listenerCount('request') > 0;
 
-      // Check if a request handler is specified; if so, emit the 'request' event.
-      const hasRequestHandler = this.listenerCount('request') > 0;
+    hasRequestHandler
+      ? this.emit('request', request, response)
+      : this.#asyncDispatch({ request, response });
 
-      hasRequestHandler
-        ? this.emit('request', request, response)
-        : this.#asyncDispatch({ request, response });
+    // Hack: To support persistent connections, we employ this technique to delay
+    // accepting a new request from the same socket until the current
+    // request-response cycle is complete.
+    await new Promise((resolve) => response.once('finish', resolve));
 
-      // Hack: To support persistent connections, we employ this technique to delay
-      // accepting a new request from the same socket until the current
-      // request-response cycle is complete.
-      await new Promise((resolve) => response.once('finish', resolve));
+    // Connection should close based on headers.
+    if (response.getHeader('connection') === 'close') return false;
 
-      // Connection should close based on headers.
-      if (response.getHeader('connection') === 'close') break;
-    }
+    // Skip any unread part of the body before reading the next request.
+    return body.discard();
   }
 
   #asyncDispatch(socket) {
@@ -668,12 +1176,12 @@ export class Server extends EventEmitter {
 export class ServerRequest {
   #body;
 
-  constructor(metadata, buffer, socket) {
+  constructor(metadata, body) {
     this.httpVersion = `1.${metadata.version}`;
     this.method = metadata.method;
     this.url = metadata.path;
     this.headers = metadata.headers;
-    this.#body = new Body(metadata, buffer, socket);
+    this.#body = body;
   }
 
   /**
@@ -715,7 +1223,7 @@ class ServerResponse extends EventEmitter {
   #writtenOnce;
   #version;
 
-  constructor({ version, headers }, socket) {
+  constructor({ version, keepAlive }, socket) {
     super();
     this.#socket = socket;
     this.#headersSent = false;
@@ -724,8 +1232,6 @@ class ServerResponse extends EventEmitter {
     this.#writtenOnce = false;
     this.#version = version;
 
-    const keepAlive = headers?.connection !== 'close';
-
     // Set default headers.
     this.#headers = new Map();
     this.#headers.set('date', new Date().toGMTString());
@@ -827,7 +1333,7 @@ class ServerResponse extends EventEmitter {
     // Override headers with user-defined ones.
     for (const [name, value] of Object.entries(headers)) {
       assert.string(name);
-      this.#headers.set(name.toLowerCase(), String(value));
+      this.#headers.set(name.toLowerCase(), toHeaderValue(value));
     }
 
     await this.#sendHeaders();
@@ -891,7 +1397,10 @@ class ServerResponse extends EventEmitter {
     // Format and append HTTP headers to message.
     const headers = formatHeaders(this.#headers);
     for (const [name, value] of Object.entries(headers)) {
-      resHeaders.push(`${name.trim()}: ${value}`);
+      // Note: Multi-value headers are sent as separate header lines.
+      for (const item of [value].flat()) {
+        resHeaders.push(`${name.trim()}: ${item}`);
+      }
     }
 
     const resHeadersString = resHeaders.join('\r\n');
@@ -906,7 +1415,7 @@ class ServerResponse extends EventEmitter {
    * Sets a single header value for implicit headers.
    *
    * @param {String} name - The name of the header.
-   * @param {String} value - The value to be set for the header.
+   * @param {(String|Array<String>)} value - The value(s) to be set for the header.
    */
   setHeader(name, value = '') {
     // Check for correct types on provided params.
@@ -918,7 +1427,7 @@ class ServerResponse extends EventEmitter {
       throw new Error('Cannot set headers after they are sent.');
     }
 
-    this.#headers.set(name.toLowerCase(), String(value));
+    this.#headers.set(name.toLowerCase(), toHeaderValue(value));
   }
 
   /**
@@ -1027,6 +1536,9 @@ const defaultOptions = {
   timeout: 30000,
   throwOnError: false,
   signal: null,
+  maxBodySize: Infinity,
+  maxHeaderSize: 16384,
+  agent: undefined,
 };
 
 /**
@@ -1040,6 +1552,9 @@ const defaultOptions = {
  * @param {Number} [options.timeout] - A timeout in milliseconds for the request.
  * @param {boolean} [options.throwOnError] - Will throw an error for non-2xx response codes.
  * @param {AbortSignal} [options.signal] - An AbortSignal to cancel the request.
+ * @param {Number} [options.maxBodySize] - The maximum size (in bytes) of the response body.
+ * @param {Number} [options.maxHeaderSize] - The maximum size (in bytes) of the response headers.
+ * @param {(Agent|false)} [options.agent] - The agent pooling the connection (`false` opens a dedicated one).
  * @returns {Promise<IncomingResponse>} Containing the HTTP response.
  */
 export function request(url, options = {}) {
@@ -1051,7 +1566,7 @@ export function request(url, options = {}) {
   // Check if the operation has been already aborted.
   options?.signal?.throwIfAborted();
 
-  const configuration = Object.assign(defaultOptions, options);
+  const configuration = Object.assign({}, defaultOptions, options);
   const request = new Request(url, configuration);
   const { signal } = configuration;
 
@@ -1065,12 +1580,22 @@ export function request(url, options = {}) {
 /**
  * Creates a new HTTP server.
  *
+ * @param {Object} [options] - Configuration options for the HTTP server.
+ * @param {Number} [options.maxBodySize] - The maximum size (in bytes) of a request body.
+ * @param {Number} [options.maxHeaderSize] - The maximum size (in bytes) of the request headers.
+ * @param {Number} [options.headersTimeout] - Time (in milliseconds) to receive the complete request headers.
  * @param {Function} [onRequest] - A function that is called whenever the server receives a HTTP request.
  * @returns {Server} Representing the newly created HTTP server.
  */
-export function createServer(onRequest) {
+export function createServer(options, onRequest) {
+  // The options argument can be omitted.
+  if (typeof options === 'function') {
+    onRequest = options;
+    options = {};
+  }
+
   // Instantiate a new HTTP server.
-  const server = new Server();
+  const server = new Server(options);
   if (onRequest) {
     assert.isFunction(onRequest);
     server.on('request', onRequest);
@@ -1078,4 +1603,480 @@ export function createServer(onRequest) {
   return server;
 }
 
-export default { METHODS, STATUS_CODES, Server, createServer, request };
+/**
+ * Parses a `Cookie` header into an object of cookie names and values.
+ *
+ * @param {string} header - The value of the `Cookie` header.
+ * @returns {Object} An object mapping each cookie name to its (decoded) value.
+ */
+export function parseCookies(header) {
+  if (typeof header !== 'string') {
+    throw new TypeError('The "header" argument must be of type string.');
+  }
+  return cookies.parse(header);
+}
+
+/**
+ * Serializes a cookie into a `Set-Cookie` header value.
+ *
+ * @param {string} name - The name of the cookie.
+ * @param {string} value - The value of the cookie (it will be URI encoded).
+ * @param {Object} [options] - The attributes of the cookie.
+ * @param {Number} [options.maxAge] - Seconds until the cookie expires.
+ * @param {Date} [options.expires] - The date the cookie expires.
+ * @param {string} [options.domain] - The host(s) the cookie will be sent to.
+ * @param {string} [options.path] - The path that must exist in the requested URL.
+ * @param {boolean} [options.secure] - Only send the cookie over HTTPS.
+ * @param {boolean} [options.httpOnly] - Forbids JavaScript from accessing the cookie.
+ * @param {(string|boolean)} [options.sameSite] - `Strict`, `Lax` or `None`.
+ * @returns {string} The value of the `Set-Cookie` header.
+ */
+export function serializeCookie(name, value, options = {}) {
+  // Check the argument types.
+  assert.string(name, 'The "name" argument must be of type string.');
+  assert.string(value, 'The "value" argument must be of type string.');
+
+  const { maxAge, expires, sameSite } = options;
+
+  if (maxAge !== undefined && !Number.isInteger(maxAge)) {
+    throw new TypeError('The "maxAge" option must be an integer.');
+  }
+
+  if (expires !== undefined && !(expires instanceof Date)) {
+    throw new TypeError('The "expires" option must be a Date.');
+  }
+
+  return cookies.serialize(name, value, {
+    ...options,
+    expires: expires?.toUTCString(),
+    sameSite: sameSite === true ? 'Strict' : sameSite || undefined,
+  });
+}
+
+// Content types of common file extensions (used when serving static files).
+const MIME_TYPES = {
+  '.html': 'text/html; charset=utf-8',
+  '.htm': 'text/html; charset=utf-8',
+  '.css': 'text/css; charset=utf-8',
+  '.js': 'text/javascript; charset=utf-8',
+  '.mjs': 'text/javascript; charset=utf-8',
+  '.json': 'application/json; charset=utf-8',
+  '.map': 'application/json; charset=utf-8',
+  '.txt': 'text/plain; charset=utf-8',
+  '.md': 'text/markdown; charset=utf-8',
+  '.csv': 'text/csv; charset=utf-8',
+  '.xml': 'application/xml; charset=utf-8',
+  '.svg': 'image/svg+xml',
+  '.png': 'image/png',
+  '.jpg': 'image/jpeg',
+  '.jpeg': 'image/jpeg',
+  '.gif': 'image/gif',
+  '.webp': 'image/webp',
+  '.avif': 'image/avif',
+  '.ico': 'image/x-icon',
+  '.woff': 'font/woff',
+  '.woff2': 'font/woff2',
+  '.ttf': 'font/ttf',
+  '.otf': 'font/otf',
+  '.mp3': 'audio/mpeg',
+  '.wav': 'audio/wav',
+  '.mp4': 'video/mp4',
+  '.webm': 'video/webm',
+  '.wasm': 'application/wasm',
+  '.pdf': 'application/pdf',
+  '.zip': 'application/zip',
+  '.gz': 'application/gzip',
+};
+
+const STATIC_CHUNK_SIZE = 64 * 1024;
+
+// Files outside these sizes are always served uncompressed.
+const MIN_COMPRESS_SIZE = 1024;
+const MAX_COMPRESS_SIZE = 8 * 1024 * 1024;
+
+// How many compressed files a static server keeps in memory.
+const COMPRESSED_CACHE_SIZE = 128;
+
+/**
+ * Checks if a content type benefits from compression (e.g. text files).
+ * @ignore
+ */
+function isCompressible(type) {
+  const essence = type.split(';')[0].trim();
+  return (
+    essence.startsWith('text/') ||
+    essence.endsWith('+json') ||
+    essence.endsWith('+xml') ||
+    ['application/json', 'application/xml', 'application/wasm'].includes(
+      essence
+    )
+  );
+}
+
+/**
+ * Checks if the client accepts gzip encoded responses.
+ * @ignore
+ */
+function acceptsGzip(header = '') {
+  return header.split(',').some((value) => {
+    const [coding, ...params] = value.split(';').map((part) => part.trim());
+    const quality = params.find((param) => param.startsWith('q='));
+    const weight = quality ? Number(quality.slice(2)) : 1;
+    return (coding === 'gzip' || coding === '*') && weight > 0;
+  });
+}
+
+/**
+ * Maps a request URL to a file path under the root directory.
+ * @ignore
+ */
+function resolveStaticPath(root, url, dotfiles) {
+  // Drop the query string and the fragment (if any).
+  const pathname = url.split(/[?#]/)[0];
+
+  let segments;
+  try {
+    segments = pathname.split('/').map(decodeURIComponent);
+  } catch (_) {
+    return { status: 400 };
+  }
+
+  // Note: Segments are checked after decoding, so encoded separators (%2F) or
+  // parent references (%2E%2E) can't be used to escape the root directory.
+  for (const segment of segments) {
+    if (/[\\/\0]/.test(segment) || segment === '..') return { status: 403 };
+    if (process.platform === 'windows' && segment.includes(':')) {
+      return { status: 403 };
+    }
+    if (!dotfiles && segment.startsWith('.') && segment !== '.') {
+      return { status: 404 };
+    }
+  }
+
+  const filePath = path.join(root, ...segments.filter(Boolean));
+  const prefix = root.endsWith(path.sep) ? root : root + path.sep;
+
+  // Extra guard, the path should never point outside the root directory.
+  if (filePath !== root && !filePath.startsWith(prefix)) {
+    return { status: 403 };
+  }
+
+  return { filePath };
+}
+
+/**
+ * Gets the stats of the file to serve (using the index file for directories).
+ * @ignore
+ */
+async function statStaticFile(filePath, index) {
+  try {
+    const stats = await fs.stat(filePath);
+    if (stats.isFile) return { filePath, stats };
+    if (!stats.isDirectory || !index) return null;
+    return statStaticFile(path.join(filePath, index), null);
+  } catch (_) {
+    return null;
+  }
+}
+
+/**
+ * Checks if the client's cached copy is still fresh (conditional requests).
+ * @ignore
+ */
+function isFresh(headers, etag, mtimeMs) {
+  const noneMatch = headers['if-none-match'];
+  if (noneMatch) {
+    const tags = noneMatch.split(',').map((tag) => tag.trim());
+    return tags.some((tag) => tag === '*' || tag.replace(/^W\//, '') === etag);
+  }
+
+  const modifiedSince = Date.parse(headers['if-modified-since']);
+  if (Number.isNaN(modifiedSince) || mtimeMs === undefined) return false;
+
+  // Note: HTTP dates have a precision of seconds.
+  return Math.floor(mtimeMs / 1000) * 1000 <= modifiedSince;
+}
+
+/**
+ * Parses a (single) byte range of a `Range` header.
+ * @ignore
+ */
+function parseRange(header, size) {
+  // Multiple or malformed ranges are ignored (the full content is sent).
+  const match = /^bytes=(\d*)-(\d*)$/.exec(header.trim());
+  if (!match || (!match[1] && !match[2])) return null;
+
+  const [, first, last] = match;
+
+  // A suffix range selects the last N bytes of the file.
+  if (!first) {
+    const suffix = Number(last);
+    if (suffix === 0 || size === 0) return { unsatisfiable: true };
+    return { start: Math.max(size - suffix, 0), end: size - 1 };
+  }
+
+  const start = Number(first);
+  const end = last ? Math.min(Number(last), size - 1) : size - 1;
+
+  if (last && Number(last) < start) return null;
+  if (start >= size) return { unsatisfiable: true };
+
+  return { start, end };
+}
+
+/**
+ * Responds with a status code (and its message as body).
+ * @ignore
+ */
+async function sendStatus(response, code, headers = {}) {
+  const message = STATUS_CODES[code];
+  await response.writeHead(code, {
+    ...headers,
+    'content-type': 'text/plain; charset=utf-8',
+    'content-length': message.length,
+  });
+  await response.end(message);
+}
+
+/**
+ * Creates a request handler serving the files of a directory.
+ *
+ * @param {string} root - The directory to serve files from.
+ * @param {Object} [options] - Configuration options for the static file server.
+ * @param {(string|boolean)} [options.index] - The file served for directories (default: `index.html`).
+ * @param {boolean} [options.dotfiles] - Allows serving files and directories starting with a dot.
+ * @param {boolean} [options.gzip] - Compresses text-like files for clients accepting gzip (default: `true`).
+ * @returns {Function} A request handler that can be passed to `createServer`.
+ */
+export function serveDir(root, options = {}) {
+  // Check the root argument type.
+  assert.string(root, 'The "root" argument must be of type string.');
+
+  const rootDir = path.resolve(root);
+  const index = options.index ?? 'index.html';
+  const dotfiles = Boolean(options.dotfiles);
+  const gzip = options.gzip ?? true;
+
+  // Note: Compressed files are cached (by path and ETag) so they're not
+  // recompressed on every request, evicting the oldest entries first.
+  const compressed = new Map();
+
+  async function compressFile(filePath, etag) {
+    const key = `${filePath}:${etag}`;
+    if (compressed.has(key)) return compressed.get(key);
+
+    // Note: Files can be up to a few MiBs, so they're compressed on the
+    // thread-pool instead of blocking the event-loop.
+    const data = await compressAsync(await fs.readFile(filePath), 'gzip');
+
+    compressed.set(key, data);
+    if (compressed.size > COMPRESSED_CACHE_SIZE) {
+      compressed.delete(compressed.keys().next().value);
+    }
+
+    return data;
+  }
+
+  return async function serveStatic(request, response) {
+    // Files can only be read.
+    if (request.method !== 'GET' && request.method !== 'HEAD') {
+      return sendStatus(response, 405, { allow: 'GET, HEAD' });
+    }
+
+    const { status, filePath } = resolveStaticPath(
+      rootDir,
+      request.url,
+      dotfiles
+    );
+
+    if (status) return sendStatus(response, status);
+
+    const file = await statStaticFile(filePath, index);
+    if (!file) return sendStatus(response, 404);
+
+    const { size, mtimeMs } = file.stats;
+    const mtime = Math.floor(mtimeMs ?? 0);
+    const etag = `"${size.toString(16)}-${mtime.toString(16)}"`;
+    const lastModified = mtimeMs && new Date(mtimeMs).toUTCString();
+    const extension = path.extname(file.filePath).toLowerCase();
+
+    const headers = {
+      'accept-ranges': 'bytes',
+      'content-type': MIME_TYPES[extension] || 'application/octet-stream',
+      etag,
+    };
+
+    if (lastModified) headers['last-modified'] = lastModified;
+
+    const compressible =
+      gzip &&
+      size >= MIN_COMPRESS_SIZE &&
+      size <= MAX_COMPRESS_SIZE &&
+      isCompressible(headers['content-type']);
+
+    // Note: Range requests are always served from the uncompressed file.
+    const useGzip =
+      compressible &&
+      !request.headers.range &&
+      acceptsGzip(request.headers['accept-encoding']);
+
+    if (compressible) headers.vary = 'accept-encoding';
+
+    // Each encoding of the file needs a different (strong) ETag.
+    if (useGzip) headers.etag = `${etag.slice(0, -1)}-gzip"`;
+
+    // The client already has the latest version of the file.
+    if (isFresh(request.headers, headers.etag, mtimeMs)) {
+      response.removeHeader('transfer-encoding');
+      await response.writeHead(304, headers);
+      return response.end();
+    }
+
+    if (useGzip) {
+      const data = await compressFile(file.filePath, etag);
+
+      headers['content-encoding'] = 'gzip';
+      headers['content-length'] = data.byteLength;
+      await response.writeHead(200, headers);
+
+      if (request.method === 'HEAD') return response.end();
+      return response.end(data);
+    }
+
+    let start = 0;
+    let end = size - 1;
+    let code = 200;
+
+    // Ranges only apply if the file didn't change (when `If-Range` is set).
+    const ifRange = request.headers['if-range'];
+    const range =
+      request.headers.range &&
+      (!ifRange || ifRange === etag || ifRange === lastModified)
+        ? parseRange(request.headers.range, size)
+        : null;
+
+    if (range?.unsatisfiable) {
+      return sendStatus(response, 416, { 'content-range': `bytes */${size}` });
+    }
+
+    if (range) {
+      ({ start, end } = range);
+      code = 206;
+      headers['content-range'] = `bytes ${start}-${end}/${size}`;
+    }
+
+    headers['content-length'] = end - start + 1;
+    await response.writeHead(code, headers);
+
+    if (request.method === 'HEAD') return response.end();
+
+    // Stream the requested bytes of the file.
+    const handle = await fs.open(file.filePath, 'r');
+    try {
+      let position = start;
+      while (position <= end) {
+        const length = Math.min(STATIC_CHUNK_SIZE, end - position + 1);
+        const chunk = new Uint8Array(length);
+        const bytesRead = await handle.read(chunk, position);
+        if (bytesRead === 0) break;
+        await response.write(chunk.subarray(0, bytesRead));
+        position += bytesRead;
+      }
+    } finally {
+      await handle.close();
+    }
+
+    await response.end();
+  };
+}
+
+/**
+ * Writes a fetch-style response to a server response.
+ * @ignore
+ */
+async function sendResponse(response, { status, statusText, headers, body }) {
+  const head = {};
+
+  // Note: Set-Cookie headers can't be combined into a single line.
+  for (const [name, value] of headers) {
+    head[name] = name === 'set-cookie' ? [...(head[name] ?? []), value] : value;
+  }
+
+  // A 304 response has no body (so no chunked encoding either).
+  if (status === 304) response.removeHeader('transfer-encoding');
+
+  await response.writeHead(status, statusText || STATUS_CODES[status], head);
+
+  for await (const chunk of body ?? []) {
+    // Note: An empty chunk would terminate a chunked response.
+    if (chunk.byteLength > 0) await response.write(chunk);
+  }
+
+  await response.end();
+}
+
+/**
+ * Creates an HTTP server that handles requests with fetch-style primitives.
+ *
+ * @param {Function} handler - A function receiving a Request and returning a Response.
+ * @param {Object} [options] - Same as the `createServer` options.
+ * @param {Function} [options.onError] - A function returning a Response when the handler throws.
+ * @returns {Server} Representing the newly created HTTP server.
+ */
+export function serve(handler, options = {}) {
+  // Check the handler argument type.
+  assert.isFunction(handler);
+
+  const { onError = defaultOnError, ...serverOptions } = options;
+  const server = new Server(serverOptions);
+
+  server.on('request', async (req, res) => {
+    const host = req.headers.host ?? 'localhost';
+    const hasBody = req.method !== 'GET' && req.method !== 'HEAD';
+    const request = new FetchRequest(`http://${host}${req.url}`, {
+      method: req.method,
+      headers: req.headers,
+      body: hasBody ? req : null,
+    });
+
+    let response;
+
+    try {
+      response = await handler(request);
+      if (!(response instanceof FetchResponse)) {
+        throw new TypeError('The handler must return a Response object.');
+      }
+    } catch (err) {
+      response = await onError(err);
+    }
+
+    // Responses to HEAD requests never include a body.
+    if (req.method === 'HEAD') {
+      const { status, statusText, headers } = response;
+      return sendResponse(res, { status, statusText, headers, body: null });
+    }
+
+    await sendResponse(res, response);
+  });
+
+  return server;
+}
+
+function defaultOnError(err) {
+  console.error(err);
+  return new FetchResponse('Internal Server Error', { status: 500 });
+}
+
+export default {
+  METHODS,
+  STATUS_CODES,
+  Agent,
+  globalAgent,
+  Server,
+  createServer,
+  request,
+  parseCookies,
+  serializeCookie,
+  serveDir,
+  serve,
+};
@@ -0,0 +1,240 @@
+/**
+ * Logging API
+ *
+ * A leveled logger that prints human readable lines when attached to a terminal
+ * and JSON records (one per line) for production environments.
+ *
+ * The minimum level is read from the `LOG_LEVEL` env variable and the output
+ * format from `LOG_FORMAT` (set it to "json" for JSON records).
+ *
+ * @module Log
+ */
+
+import { performance } from 'perf_hooks';
+import { format, forwardToInspector } from 'console';
+import { bright_black, blue, green, yellow, red, magenta } from 'colors';
+
+const binding = process.binding('log');
+const stdio = process.binding('stdio');
+
+const LEVELS = {
+  trace: 10,
+  debug: 20,
+  info: 30,
+  warn: 40,
+  error: 50,
+  silent: Infinity,
+};
+
+// The console method each level is forwarded to (for the inspector).
+const CONSOLE_METHODS = {
+  trace: 'debug',
+  debug: 'debug',
+  info: 'info',
+  warn: 'warn',
+  error: 'error',
+};
+
+const LEVEL_COLORS = {
+  trace: bright_black,
+  debug: blue,
+  info: green,
+  warn: yellow,
+  error: red,
+};
+
+function parseLevel(level) {
+  const name = String(level).toLowerCase();
+  if (!Object.hasOwn(LEVELS, name)) {
+    throw new TypeError(`Unknown log level: "${level}".`);
+  }
+  return name;
+}
+
+// Note: A bad `LOG_LEVEL` value shouldn't break every module importing the
+// logger, so it falls back to the default level (with a warning).
+let warnedLevel = false;
+
+function defaultLevel() {
+  const level = process.env.LOG_LEVEL ?? 'info';
+  try {
+    return parseLevel(level);
+  } catch {
+    if (!warnedLevel) {
+      console.warn(`Unknown LOG_LEVEL "${level}", falling back to "info".`);
+      warnedLevel = true;
+    }
+    return 'info';
+  }
+}
+
+function isPlainObject(value) {
+  if (value === null || typeof value !== 'object') return false;
+  const prototype = Object.getPrototypeOf(value);
+  return prototype === Object.prototype || prototype === null;
+}
+
+// Note: The wall clock time is derived from the monotonic clock, so records
+// are always ordered even if the system time changes while running.
+function timestamp() {
+  return new Date(performance.timeOrigin + performance.now()).toISOString();
+}
+
+/**
+ * A leveled logger.
+ */
+export class Logger {
+  #level;
+  #json;
+  #colors;
+  #fields;
+
+  /**
+   * Creates a new Logger instance.
+   *
+   * @param {Object} [options]
+   * @param {String} [options.level] - The minimum level that will be printed.
+   * @param {Boolean} [options.json] - Print records as JSON lines.
+   * @param {Object} [options.fields] - Fields included in every record.
+   * @returns {Logger}
+   */
+  constructor(options = {}) {
+    this.#level = options.level ? parseLevel(options.level) : defaultLevel();
+    this.#json = options.json ?? process.env.LOG_FORMAT === 'json';
+    this.#colors = !this.#json && stdio.isColorEnabled();
+    this.#fields = { ...options.fields };
+  }
+
+  /**
+   * The minimum level that will be printed.
+   */
+  get level() {
+    return this.#level;
+  }
+
+  set level(level) {
+    this.#level = parseLevel(level);
+  }
+
+  /**
+   * Checks if messages of the given level will be printed.
+   *
+   * @param {String} level
+   * @returns {Boolean}
+   */
+  isLevelEnabled(level) {
+    return LEVELS[parseLevel(level)] >= LEVELS[this.#level];
+  }
+
+  /**
+   * Creates a logger that includes the given fields in every record.
+   *
+   * @param {Object} fields
+   * @returns {Logger}
+   */
+  child(fields) {
+    return new Logger({
+      level: this.#level,
+      json: this.#json,
+      fields: { ...this.#fields, ...fields },
+    });
+  }
+
+  /**
+   * Logs a message at the "trace" level.
+   *
+   * @param  {...*} args - An optional fields object followed by the message.
+   */
+  trace(...args) {
+    this.#write('trace', args);
+  }
+
+  /**
+   * Logs a message at the "debug" level.
+   *
+   * @param  {...*} args - An optional fields object followed by the message.
+   */
+  debug(...args) {
+    this.#write('debug', args);
+  }
+
+  /**
+   * Logs a message at the "info" level.
+   *
+   * @param  {...*} args - An optional fields object followed by the message.
+   */
+  info(...args) {
+    this.#write('info', args);
+  }
+
+  /**
+   * Logs a message at the "warn" level.
+   *
+   * @param  {...*} args - An optional fields object followed by the message.
+   */
+  warn(...args) {
+    this.#write('warn', args);
+  }
+
+  /**
+   * Logs a message at the "error" level.
+   *
+   * @param  {...*} args - An optional fields object followed by the message.
+   */
+  error(...args) {
+    this.#write('error', args);
+  }
+
+  #write(level, args) {
+    if (!this.isLevelEnabled(level)) return;
+
+    // A leading plain object holds the record's structured fields.
+    let fields = this.#fields;
+    if (isPlainObject(args[0]) && args.length > 1) {
+      fields = { ...fields, ...args.shift() };
+    }
+
+    const time = timestamp();
+    const message = format(...args);
+    const output = this.#json
+      ? binding.serialize(level, time, message, fields)
+      : this.#pretty(level, time, message, fields);
+
+    // Warnings and errors go to stderr.
+    const isError = LEVELS[level] >= LEVELS.warn;
+    const stream = isError ? process.stderr : process.stdout;
+    stream.write(`${output}\n`);
+
+    forwardToInspector(CONSOLE_METHODS[level], output);
+  }
+
+  #pretty(level, time, message, fields) {
+    const paint = (color, value) => (this.#colors ? color(value) : value);
+    const label = paint(LEVEL_COLORS[level], level.toUpperCase().padEnd(5));
+    const context = Object.entries(fields).map(
+      ([key, value]) => `${paint(magenta, key)}=${format(value)}`
+    );
+
+    return [paint(bright_black, time), label, message, ...context].join(' ');
+  }
+}
+
+/**
+ * Creates a new Logger instance.
+ *
+ * @param {Object} [options] - See the `Logger` constructor.
+ * @returns {Logger}
+ */
+export function createLogger(options) {
+  return new Logger(options);
+}
+
+const logger = new Logger();
+
+export const trace = logger.trace.bind(logger);
+export const debug = logger.debug.bind(logger);
+export const info = logger.info.bind(logger);
+export const warn = logger.warn.bind(logger);
+export const error = logger.error.bind(logger);
+
+export default logger;
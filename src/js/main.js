@@ -1,10 +1,6 @@
 import process from 'process';
 import timers from 'timers';
-import fetch from '@web/fetch';
-import structuredClone from '@web/clone';
 import { Console, prompt, wrapConsole } from 'console';
-import { AbortController, AbortSignal } from '@web/abort';
-import { TextEncoder, TextDecoder } from '@web/text_encoding';
 
 globalThis.global = globalThis;
 
@@ -15,6 +11,34 @@ function makeGlobal(name, value) {
   globalThis[name] = value;
 }
 
+const { requireCore } = process.binding('modules');
+
+// Note: Globals backed by bigger modules (e.g. fetch) are defined as accessors
+// that load the module on first access, so scripts not using them don't pay
+// for it at startup. Modules are shared with the ones loaded through imports.
+function makeLazyGlobal(name, load) {
+  Object.defineProperty(globalThis, name, {
+    get() {
+      // The accessor is removed first, so the module may check the global too.
+      delete globalThis[name];
+      makeGlobal(name, load());
+      return globalThis[name];
+    },
+    set(value) {
+      delete globalThis[name];
+      makeGlobal(name, value);
+    },
+    enumerable: true,
+    configurable: true,
+  });
+}
+
+function makeLazyGlobals(specifier, names) {
+  for (const name of names) {
+    makeLazyGlobal(name, () => requireCore(specifier)[name]);
+  }
+}
+
 const { $$queueMicro, reportError } = globalThis;
 
 // Note: We wrap `queueMicrotask` and manually emit the exception because
@@ -54,9 +78,33 @@ makeGlobal('clearTimeout', timers.clearTimeout);
 makeGlobal('clearInterval', timers.clearInterval);
 makeGlobal('clearImmediate', timers.clearImmediate);
 
-makeGlobal('TextEncoder', TextEncoder);
-makeGlobal('TextDecoder', TextDecoder);
-makeGlobal('structuredClone', structuredClone);
-makeGlobal('AbortController', AbortController);
-makeGlobal('AbortSignal', AbortSignal);
-makeGlobal('fetch', fetch);
+makeLazyGlobals('@web/text_encoding', ['TextEncoder', 'TextDecoder']);
+makeLazyGlobal('structuredClone', () => requireCore('@web/clone').default);
+makeLazyGlobals('@web/events', ['Event', 'CustomEvent', 'EventTarget']);
+makeLazyGlobals('@web/abort', ['AbortController', 'AbortSignal']);
+makeLazyGlobal('fetch', () => requireCore('@web/fetch').default);
+makeLazyGlobals('@web/fetch', ['Headers', 'Request', 'Response']);
+makeLazyGlobals('@web/blob', ['Blob', 'File']);
+makeLazyGlobals('buffer', ['Buffer']);
+makeLazyGlobals('@web/compression', ['CompressionStream', 'DecompressionStream']);
+
+makeLazyGlobal('crypto', () => {
+  const { getRandomValues, randomUUID } = requireCore('crypto');
+  return { getRandomValues, randomUUID };
+});
+
+// Expose the WHATWG streams classes.
+makeLazyGlobals('@web/streams', [
+  'ReadableStream',
+  'ReadableStreamDefaultReader',
+  'ReadableStreamBYOBReader',
+  'ReadableStreamDefaultController',
+  'ReadableByteStreamController',
+  'WritableStream',
+  'WritableStreamDefaultWriter',
+  'WritableStreamDefaultController',
+  'TransformStream',
+  'TransformStreamDefaultController',
+  'ByteLengthQueuingStrategy',
+  'CountQueuingStrategy',
+]);
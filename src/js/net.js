@@ -12,8 +12,10 @@
 import dns from 'dns';
 import assert from 'assert';
 import { EventEmitter } from 'events';
+import { ReadableStream, WritableStream } from '@web/streams';
 
 const binding = process.binding('net');
+const pollBinding = process.binding('poll');
 
 function parseOptionsArgs(args) {
   // Use options overloading.
@@ -43,6 +45,106 @@ function makeDeferredPromise() {
 
 const TIMEOUT_MAX = Math.pow(2, 31) - 1;
 
+// The delay (in ms) before racing the next address (RFC 8305, section 5).
+const CONNECTION_ATTEMPT_DELAY = 250;
+
+// Orders the addresses alternating between families, starting with IPv6
+// (RFC 8305, section 4).
+function interleaveAddresses(addresses) {
+  const ipv6 = addresses.filter((addr) => addr.family === 'IPv6');
+  const ipv4 = addresses.filter((addr) => addr.family !== 'IPv6');
+
+  const ordered = [];
+  for (let i = 0; i < Math.max(ipv6.length, ipv4.length); i++) {
+    if (ipv6[i]) ordered.push(ipv6[i]);
+    if (ipv4[i]) ordered.push(ipv4[i]);
+  }
+
+  return ordered;
+}
+
+/**
+ * Connects to the first reachable address, starting a new attempt every
+ * `CONNECTION_ATTEMPT_DELAY` (or as soon as the previous one fails) and
+ * closing the rest once one connects (Happy Eyeballs, RFC 8305).
+ *
+ * @ignore
+ * @param {Resolution[]} addresses - The resolved addresses of the host.
+ * @param {Number} port - The port number on the remote host.
+ * @param {Number} [time] - Milliseconds before every attempt is given up (0 means never).
+ * @returns {Promise<Object>} The connected socket's information.
+ */
+function connectToAny(addresses, port, time = 0) {
+  const { promise, promiseExt } = makeDeferredPromise();
+  const ordered = interleaveAddresses(addresses);
+  const attempts = [];
+
+  let next = 0;
+  let failures = 0;
+  let settled = false;
+  let timer;
+  let deadline;
+
+  const cancel = (attempt) => {
+    if (attempt.closed || attempt.id === undefined) return;
+    attempt.closed = true;
+    binding.close(attempt.id);
+  };
+
+  const settle = () => {
+    settled = true;
+    clearTimeout(timer);
+    clearTimeout(deadline);
+  };
+
+  const startAttempt = () => {
+    if (settled || next >= ordered.length) return;
+
+    const { id, promise } = binding.connect(ordered[next++].address, port);
+    const attempt = { id, closed: false };
+    attempts.push(attempt);
+
+    clearTimeout(timer);
+    timer = setTimeout(startAttempt, CONNECTION_ATTEMPT_DELAY);
+
+    promise.then(
+      (socket) => {
+        // Another attempt won the race, so this one is no longer needed.
+        if (settled) return cancel(attempt);
+        settle();
+        attempts.filter((other) => other !== attempt).forEach(cancel);
+        promiseExt.resolve(socket);
+      },
+      (err) => {
+        // Note: Failed connections are already cleaned up by the runtime.
+        attempt.closed = true;
+        if (settled) return;
+        if (++failures === ordered.length) {
+          settle();
+          promiseExt.reject(err);
+          return;
+        }
+        startAttempt();
+      }
+    );
+  };
+
+  if (time > 0) {
+    // Close the attempts that are still in flight, so they don't keep the
+    // process alive (or connect) after the caller gave up on them.
+    deadline = setTimeout(() => {
+      if (settled) return;
+      settle();
+      attempts.forEach(cancel);
+      promiseExt.reject(new TimeoutError('The connection attempt timed out.'));
+    }, time);
+  }
+
+  startAttempt();
+
+  return promise;
+}
+
 // Error type referring to socket connection timeout.
 export class TimeoutError extends Error {
   constructor(message) {
@@ -204,6 +306,10 @@ export class Socket extends EventEmitter {
   #pushQueue;
   #pullQueue;
   #timeoutHandle;
+  #onRead;
+  #paused;
+  #readableStream;
+  #writableStream;
 
   /**
    * Creates a new Socket instance.
@@ -216,6 +322,7 @@ export class Socket extends EventEmitter {
     this.#pullQueue = [];
     this.#connecting = false;
     this.#timeoutHandle = undefined;
+    this.#paused = false;
     this.bytesRead = 0;
     this.bytesWritten = 0;
     this.remotePort = undefined;
@@ -259,18 +366,23 @@ export class Socket extends EventEmitter {
 
     this.#connecting = true;
 
-    // Use DNS lookup to resolve the hostname.
-    const addresses = await dns.lookup(hostname);
+    let connection;
+    try {
+      // Use DNS lookup to resolve the hostname.
+      const addresses = await dns.lookup(hostname);
 
-    // Prefer IPv4 address.
-    const remoteHost = addresses.some((addr) => addr.family === 'IPv4')
-      ? addresses.filter((addr) => addr.family === 'IPv4')[0].address
-      : addresses[0].address;
+      // Race the resolved addresses (dual-stack hosts have both families).
+      connection = await connectToAny(
+        addresses,
+        Number.parseInt(port),
+        this.timeout
+      );
+    } catch (err) {
+      this.#connecting = false;
+      throw err;
+    }
 
-    const { id, host, remote } = await binding.connect(
-      remoteHost,
-      Number.parseInt(port)
-    );
+    const { id, host, remote } = connection;
 
     this.#id = id;
     this.#connecting = false;
@@ -286,6 +398,7 @@ export class Socket extends EventEmitter {
     );
 
     this.#timeoutHandle = signal;
+    this.#onRead = onAvailableSocketData;
     this.emit('connect', { host, remote });
 
     binding.readStart(this.#id, onAvailableSocketData);
@@ -328,6 +441,44 @@ export class Socket extends EventEmitter {
     this.timeout = timeout;
   }
 
+  /**
+   * Enables (or disables) Nagle's algorithm being turned off (TCP_NODELAY).
+   *
+   * Note: The option only applies while the socket is connected.
+   *
+   * @param {Boolean} [noDelay] - Sends data as soon as it's written (default: true).
+   */
+  setNoDelay(noDelay = true) {
+    // Check if the socket is indeed connected.
+    if (!this.#id) {
+      throw new Error('Socket is not connected to a remote host.');
+    }
+    binding.setNoDelay(this.#id, Boolean(noDelay));
+  }
+
+  /**
+   * Enables (or disables) the TCP keepalive probes (SO_KEEPALIVE).
+   *
+   * Note: The option only applies while the socket is connected.
+   *
+   * @param {Boolean} [enable] - Sends probes on idle connections (default: false).
+   * @param {Number} [delaySecs] - The idle time before the first probe (default: the OS one).
+   */
+  setKeepAlive(enable = false, delaySecs = 0) {
+    // Check if the socket is indeed connected.
+    if (!this.#id) {
+      throw new Error('Socket is not connected to a remote host.');
+    }
+
+    if (!Number.isInteger(delaySecs) || delaySecs < 0) {
+      throw new RangeError(
+        'The "delaySecs" argument must be a non-negative integer.'
+      );
+    }
+
+    binding.setKeepAlive(this.#id, Boolean(enable), delaySecs);
+  }
+
   /**
    * Returns a promise which is fulfilled when the TCP stream can return a chunk.
    *
@@ -445,6 +596,10 @@ export class Socket extends EventEmitter {
     this.#pullQueue = [];
     this.#connecting = false;
     this.#timeoutHandle = undefined;
+    this.#onRead = undefined;
+    this.#paused = false;
+    this.#readableStream = undefined;
+    this.#writableStream = undefined;
     this.bytesRead = 0;
     this.bytesWritten = 0;
     this.remotePort = undefined;
@@ -452,6 +607,26 @@ export class Socket extends EventEmitter {
     this.timeout = 0;
   }
 
+  /**
+   * Stops reading from the OS, so the peer is slowed down by TCP itself.
+   * @ignore
+   */
+  #pauseReading() {
+    if (!this.#id || this.#paused) return;
+    this.#paused = true;
+    binding.readStop(this.#id);
+  }
+
+  /**
+   * Starts reading from the OS again (after being paused).
+   * @ignore
+   */
+  #resumeReading() {
+    if (!this.#id || !this.#paused) return;
+    this.#paused = false;
+    binding.readStart(this.#id, this.#onRead);
+  }
+
   #asyncDispatch(value) {
     if (this.#pullQueue.length === 0) {
       this.#pushQueue.push(value);
@@ -512,6 +687,7 @@ export class Socket extends EventEmitter {
     );
 
     this.#timeoutHandle = signal;
+    this.#onRead = onAvailableSocketData;
     binding.readStart(this.#id, onAvailableSocketData);
   }
 
@@ -526,6 +702,41 @@ export class Socket extends EventEmitter {
     }
   }
 
+  /**
+   * A readable stream of the incoming data (cancelling it ends the socket).
+   *
+   * @returns {ReadableStream}
+   */
+  get readable() {
+    this.#readableStream ??= new ReadableStream({
+      pull: async (controller) => {
+        this.#resumeReading();
+        const data = await this.read();
+        if (!data) return controller.close();
+        controller.enqueue(data);
+        // Note: Reading stops while the queue is full, so a slow consumer
+        // applies backpressure all the way down to the socket.
+        if (controller.desiredSize <= 0) this.#pauseReading();
+      },
+      cancel: () => this.end(),
+    });
+    return this.#readableStream;
+  }
+
+  /**
+   * A writable stream into the socket (aborting it destroys the socket).
+   *
+   * @returns {WritableStream}
+   */
+  get writable() {
+    this.#writableStream ??= new WritableStream({
+      write: (chunk) => this.write(chunk),
+      close: () => this.end(),
+      abort: () => this.destroy(),
+    });
+    return this.#writableStream;
+  }
+
   /**
    * The socket should be async iterable.
    * @ignore
@@ -709,6 +920,82 @@ export class Server extends EventEmitter {
   }
 }
 
+/**
+ * Readiness of a watched file descriptor.
+ *
+ * @typedef Readiness
+ * @property {boolean} readable - The descriptor can be read without blocking.
+ * @property {boolean} writable - The descriptor can be written without blocking.
+ * @property {boolean} hangup - The other end (or both ends) has been closed.
+ * @property {boolean} error - The descriptor is in an error state.
+ */
+
+function validateInterest(interest) {
+  if (!interest?.readable && !interest?.writable) {
+    throw new TypeError('At least one of readable or writable must be set.');
+  }
+}
+
+/**
+ * Watches a raw file descriptor (a socket handle on Windows) for readiness.
+ *
+ * The watcher never owns the descriptor: It must be non-blocking, stay open
+ * while being watched, and the watcher must be closed before the descriptor.
+ */
+export class FdWatcher {
+  #id;
+
+  /**
+   * Starts watching the file descriptor.
+   *
+   * @param {number} fd - The raw file descriptor (or socket handle).
+   * @param {Object} interest - The readiness events to watch.
+   * @param {boolean} [interest.readable] - Watches for readability.
+   * @param {boolean} [interest.writable] - Watches for writability.
+   * @param {Function} listener - Called with a `Readiness` object.
+   */
+  constructor(fd, interest, listener) {
+    if (!Number.isInteger(fd) || fd < 0) {
+      throw new TypeError('The "fd" argument must be a non-negative integer.');
+    }
+    validateInterest(interest);
+    assert.isFunction(listener);
+    this.#id = pollBinding.watch(fd, interest, listener);
+  }
+
+  /**
+   * Changes the readiness events being watched.
+   *
+   * @param {Object} interest - The readiness events to watch.
+   */
+  update(interest) {
+    if (this.#id === null) throw new Error('The watcher is closed.');
+    validateInterest(interest);
+    pollBinding.update(this.#id, interest);
+  }
+
+  /**
+   * Stops watching the file descriptor (without closing it).
+   */
+  close() {
+    if (this.#id === null) return;
+    pollBinding.unwatch(this.#id);
+    this.#id = null;
+  }
+}
+
+/**
+ * Watches a raw file descriptor for readiness (see `FdWatcher`).
+ *
+ * @param {number} fd - The raw file descriptor (or socket handle).
+ * @param {Object} interest - The readiness events to watch.
+ * @param {Function} listener - Called with a `Readiness` object.
+ * @returns {FdWatcher} An instance of the `FdWatcher` class.
+ */
+export function watchFd(fd, interest, listener) {
+  return new FdWatcher(fd, interest, listener);
+}
+
 export default {
   TimeoutError,
   Socket,
@@ -716,4 +1003,6 @@ export default {
   createConnection,
   Server,
   createServer,
+  FdWatcher,
+  watchFd,
 };
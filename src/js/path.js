@@ -0,0 +1,318 @@
+/**
+ * Path API
+ *
+ * Utilities for working with file and directory paths. The default export
+ * follows the conventions of the current platform, while the `posix` and
+ * `win32` namespaces can be used for cross-platform code.
+ *
+ * @module Path
+ */
+
+function validateString(value, name) {
+  if (typeof value !== 'string') {
+    throw new TypeError(`The "${name}" argument must be of type string.`);
+  }
+  return value;
+}
+
+// Matches (optional) UNC or drive devices followed by any leading separators.
+const WIN32_ROOT = /^(?:([\\/]{2}[^\\/]+[\\/]+[^\\/]+)|([a-zA-Z]:))?([\\/]+)?/;
+
+const posixFlavor = {
+  sep: '/',
+  delimiter: ':',
+  separators: /\/+/,
+  isSeparator: (char) => char === '/',
+  equals: (a, b) => a === b,
+  splitRoot(path) {
+    const [slashes] = /^\/*/.exec(path);
+    const absolute = slashes.length > 0;
+    return { device: '', absolute, rest: path.slice(slashes.length) };
+  },
+};
+
+const win32Flavor = {
+  sep: '\\',
+  delimiter: ';',
+  separators: /[\\/]+/,
+  isSeparator: (char) => char === '\\' || char === '/',
+  equals: (a, b) => a.toLowerCase() === b.toLowerCase(),
+  splitRoot(path) {
+    const [match, unc, drive, slashes] = WIN32_ROOT.exec(path);
+    const device = unc ? unc.replace(/\//g, '\\') : drive || '';
+    const absolute = Boolean(unc || slashes);
+    return { device, absolute, rest: path.slice(match.length) };
+  },
+};
+
+/**
+ * Creates a path namespace using the given flavor's conventions.
+ */
+function createPath(flavor) {
+  const { sep, delimiter, separators, isSeparator, equals, splitRoot } = flavor;
+
+  // Splits a path into segments, resolving the `.` and `..` ones.
+  const segmentsOf = (path, allowAboveRoot) => {
+    const segments = [];
+    for (const segment of path.split(separators)) {
+      if (!segment || segment === '.') continue;
+      if (segment !== '..') {
+        segments.push(segment);
+      } else if (segments.length && segments.at(-1) !== '..') {
+        segments.pop();
+      } else if (allowAboveRoot) {
+        segments.push('..');
+      }
+    }
+    return segments;
+  };
+
+  // Returns the current directory (or the device's root if it's different).
+  const currentDirectory = (device) => {
+    const cwd = process.cwd();
+    if (device && !equals(splitRoot(cwd).device, device)) return device + sep;
+    return cwd;
+  };
+
+  // Splits a path into its root, its directory part and its last portion.
+  const split = (path) => {
+    const { device, absolute, rest } = splitRoot(path);
+    let end = rest.length;
+    while (end > 0 && isSeparator(rest[end - 1])) end--;
+    const trimmed = rest.slice(0, end);
+    let index = trimmed.length - 1;
+    while (index >= 0 && !isSeparator(trimmed[index])) index--;
+    const base = trimmed.slice(index + 1);
+    let dir = index >= 0 ? trimmed.slice(0, index) : '';
+    while (dir && isSeparator(dir.at(-1))) dir = dir.slice(0, -1);
+    return { root: device + (absolute ? sep : ''), dir, base };
+  };
+
+  /**
+   * Resolves a sequence of paths into an absolute path.
+   *
+   * @param {...string} paths
+   * @returns {string}
+   */
+  function resolve(...paths) {
+    let device = '';
+    let tail = '';
+    let absolute = false;
+
+    for (let i = paths.length - 1; i >= -1; i--) {
+      const path =
+        i >= 0 ? validateString(paths[i], 'paths') : currentDirectory(device);
+
+      if (!path) continue;
+
+      const parsed = splitRoot(path);
+      if (parsed.device && device && !equals(parsed.device, device)) continue;
+
+      device ||= parsed.device;
+      if (!absolute) {
+        tail = `${parsed.rest}${sep}${tail}`;
+        absolute = parsed.absolute;
+      }
+
+      // Note: On windows we also need a device to call a path fully resolved.
+      if (absolute && (device || flavor === posixFlavor)) break;
+    }
+
+    const root = device + (absolute ? sep : '');
+    return root + segmentsOf(tail, !absolute).join(sep) || '.';
+  }
+
+  /**
+   * Normalizes a path, resolving the `..` and `.` segments.
+   *
+   * @param {string} path
+   * @returns {string}
+   */
+  function normalize(path) {
+    validateString(path, 'path');
+    if (!path) return '.';
+
+    const { device, absolute, rest } = splitRoot(path);
+    let tail = segmentsOf(rest, !absolute).join(sep);
+
+    if (!tail && !absolute) tail = '.';
+    if (tail && isSeparator(path.at(-1))) tail += sep;
+
+    return device + (absolute ? sep : '') + tail;
+  }
+
+  /**
+   * Checks if a path is absolute.
+   *
+   * @param {string} path
+   * @returns {boolean}
+   */
+  function isAbsolute(path) {
+    return splitRoot(validateString(path, 'path')).absolute;
+  }
+
+  /**
+   * Joins all the given path segments together and normalizes the result.
+   *
+   * @param {...string} paths
+   * @returns {string}
+   */
+  function join(...paths) {
+    paths.forEach((path) => validateString(path, 'paths'));
+    const joined = paths.filter(Boolean).join(sep);
+    return joined ? normalize(joined) : '.';
+  }
+
+  /**
+   * Returns the relative path from `from` to `to`.
+   *
+   * @param {string} from
+   * @param {string} to
+   * @returns {string}
+   */
+  function relative(from, to) {
+    validateString(from, 'from');
+    validateString(to, 'to');
+
+    from = resolve(from);
+    to = resolve(to);
+
+    if (equals(from, to)) return '';
+
+    const source = splitRoot(from);
+    const target = splitRoot(to);
+
+    // Paths on different devices can't be relative to each other.
+    if (!equals(source.device, target.device)) return to;
+
+    const fromSegments = segmentsOf(source.rest, false);
+    const toSegments = segmentsOf(target.rest, false);
+
+    let common = 0;
+    while (
+      common < fromSegments.length &&
+      common < toSegments.length &&
+      equals(fromSegments[common], toSegments[common])
+    ) {
+      common++;
+    }
+
+    const up = Array(fromSegments.length - common).fill('..');
+    return [...up, ...toSegments.slice(common)].join(sep);
+  }
+
+  /**
+   * Returns the directory name of a path.
+   *
+   * @param {string} path
+   * @returns {string}
+   */
+  function dirname(path) {
+    const { root, dir } = split(validateString(path, 'path'));
+    return root + dir || '.';
+  }
+
+  /**
+   * Returns the last portion of a path (optionally without the suffix).
+   *
+   * @param {string} path
+   * @param {string} [suffix]
+   * @returns {string}
+   */
+  function basename(path, suffix) {
+    const { base } = split(validateString(path, 'path'));
+    if (suffix === undefined) return base;
+    validateString(suffix, 'suffix');
+    if (base !== suffix && base.endsWith(suffix)) {
+      return base.slice(0, base.length - suffix.length);
+    }
+    return base;
+  }
+
+  /**
+   * Returns the extension of a path (from the last `.` of the last portion).
+   *
+   * @param {string} path
+   * @returns {string}
+   */
+  function extname(path) {
+    const base = basename(path);
+    const index = base.lastIndexOf('.');
+    return index > 0 && base !== '..' ? base.slice(index) : '';
+  }
+
+  /**
+   * Returns an object whose properties represent significant elements of
+   * the path.
+   *
+   * @param {string} path
+   * @returns {Object} - The `root`, `dir`, `base`, `ext` and `name` elements.
+   */
+  function parse(path) {
+    const { root, dir, base } = split(validateString(path, 'path'));
+    const ext = extname(base);
+    return {
+      root,
+      dir: root + dir,
+      base,
+      ext,
+      name: base.slice(0, base.length - ext.length),
+    };
+  }
+
+  /**
+   * Returns a path string from an object (the opposite of `parse`).
+   *
+   * @param {Object} object
+   * @returns {string}
+   */
+  function format(object) {
+    if (object === null || typeof object !== 'object') {
+      throw new TypeError(`The "object" argument must be of type object.`);
+    }
+    const dir = object.dir || object.root || '';
+    const base = object.base || `${object.name || ''}${object.ext || ''}`;
+    if (!dir) return base;
+    return dir === object.root ? dir + base : dir + sep + base;
+  }
+
+  return {
+    sep,
+    delimiter,
+    resolve,
+    normalize,
+    isAbsolute,
+    join,
+    relative,
+    dirname,
+    basename,
+    extname,
+    parse,
+    format,
+  };
+}
+
+export const posix = createPath(posixFlavor);
+export const win32 = createPath(win32Flavor);
+
+posix.posix = win32.posix = posix;
+posix.win32 = win32.win32 = win32;
+
+const path = process.platform === 'windows' ? win32 : posix;
+
+export const {
+  sep,
+  delimiter,
+  resolve,
+  normalize,
+  isAbsolute,
+  join,
+  relative,
+  dirname,
+  basename,
+  extname,
+  parse,
+  format,
+} = path;
+
+export default path;
@@ -6,6 +6,7 @@ const cache = new Map();
 const internalBinding = clone(process.binding);
 const kill = clone(process.kill);
 const nextTick = clone(process.nextTick);
+const exit = clone(process.exit);
 
 // Note: Integrating a caching layer into process.binding enables us
 // to avoid traversing the JavaScript - Rust bridge for native method
@@ -29,12 +30,20 @@ process.kill = (pid, signal = 'SIGKILL') => {
   kill(pid, signal);
 };
 
+// Note: Without an explicit code, the program exits with `process.exitCode`.
+process.exit = (code = process.exitCode) => {
+  process.exitCode = code ?? 0;
+  exit(process.exitCode);
+};
+
+process.exitCode = undefined;
+
 process.nextTick = (callback, ...args) => {
   // Check if callback is a valid function.
   if (typeof callback !== 'function') {
     throw new TypeError(`The "callback" argument must be of type function.`);
   }
-  nextTick(() => callback(...args));
+  nextTick(callback, ...args);
 };
 
 function defineStream(name, getter) {
@@ -46,21 +55,101 @@ function defineStream(name, getter) {
 }
 
 const io = process.binding('stdio');
+const os = process.binding('signals');
 
-defineStream('stdout', () => ({
-  write: io.write,
-  end() {},
-}));
+const outputStreams = [];
+let resizeSignal;
 
-defineStream('stdin', () => ({
-  read: io.read,
-}));
+// Note: Terminal resizes are reported through the `SIGWINCH` signal, which
+// doesn't exist on Windows (the 'resize' event is never emitted there).
+function watchResizeIfListening() {
+  const listening = outputStreams.some((s) => s.listenerCount('resize') > 0);
 
-defineStream('stderr', () => ({
-  write: io.writeError,
-}));
+  if (listening && !resizeSignal && os.signals.includes('SIGWINCH')) {
+    const emitResize = () => outputStreams.forEach((s) => s.emit('resize'));
+    resizeSignal = os.startSignal('SIGWINCH', emitResize);
+  }
 
-const os = process.binding('signals');
+  if (!listening && resizeSignal) {
+    os.cancelSignal(resizeSignal);
+    resizeSignal = undefined;
+  }
+}
+
+/**
+ * An output stream (stdout or stderr) emitting a 'resize' event when the
+ * terminal it's attached to changes size.
+ */
+class OutputStream extends EventEmitter {
+  constructor(fd, write) {
+    super();
+    this.fd = fd;
+    this.write = write;
+    outputStreams.push(this);
+  }
+
+  end() {}
+
+  get isTTY() {
+    return io.isTerminal(this.fd);
+  }
+
+  // Note: The `columns` and `rows` properties are only defined (numbers) for
+  // terminals, and they're read on access so they follow terminal resizes.
+  get columns() {
+    return io.columns(this.fd);
+  }
+
+  get rows() {
+    return io.rows(this.fd);
+  }
+
+  on(event, ...args) {
+    super.on(event, ...args);
+    watchResizeIfListening();
+    return this;
+  }
+
+  once(event, ...args) {
+    super.once(event, ...args);
+    watchResizeIfListening();
+    return this;
+  }
+
+  removeListener(event, ...args) {
+    super.removeListener(event, ...args);
+    watchResizeIfListening();
+    return this;
+  }
+
+  removeAllListeners(event) {
+    super.removeAllListeners(event);
+    watchResizeIfListening();
+    return this;
+  }
+
+  addListener(event, ...args) {
+    return this.on(event, ...args);
+  }
+
+  off(event, ...args) {
+    return this.removeListener(event, ...args);
+  }
+}
+
+const stdout = new OutputStream(1, io.write);
+const stderr = new OutputStream(2, io.writeError);
+
+const stdin = {
+  read: io.read,
+  get isTTY() {
+    return io.isTerminal(0);
+  },
+};
+
+defineStream('stdout', () => stdout);
+defineStream('stdin', () => stdin);
+defineStream('stderr', () => stderr);
 
 // Note: To transform the process object, initialized in Rust, into
 // an event emitter, we must manually instantiate the object fields
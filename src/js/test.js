@@ -13,6 +13,8 @@ import fs from 'fs';
 import { performance } from 'perf_hooks';
 import { bg_green, bg_red, red, green, bold } from 'colors';
 
+const { remapStackTrace } = process.binding('modules');
+
 // Output labels.
 const OK = bg_green(bold(' OK '));
 const FAIL = bg_red(bold(' FAIL '));
@@ -54,6 +56,42 @@ function joinPaths(...parts) {
   return parts.join(separator).replace(replace, separator);
 }
 
+/**
+ * Finds the files matching a pattern, starting from a file or a directory
+ * (which is traversed recursively).
+ *
+ * @param {String} entryPoint - The file or directory to start from.
+ * @param {RegExp} pattern - The pattern that file paths should match.
+ * @returns {String[]}
+ */
+export function findFiles(entryPoint, pattern) {
+  const stat = fs.statSync(entryPoint);
+
+  if (stat.isFile) return [entryPoint];
+
+  const files = [];
+  const walkDirs = (path) => {
+    // Read all files/folders from current path.
+    for (const filename of fs.readdirSync(path)) {
+      const filePath = joinPaths(path, filename);
+      const stat = fs.statSync(filePath);
+
+      // A matching file has been found.
+      if (stat.isFile && filePath.match(pattern)) {
+        files.push(filePath);
+        continue;
+      }
+
+      // Continue traversing the sub-directories.
+      if (stat.isDirectory) walkDirs(filePath);
+    }
+  };
+
+  if (stat.isDirectory) walkDirs(entryPoint);
+
+  return files;
+}
+
 /**
  *  TestRunner is the main executor to run JavaScript tests.
  */
@@ -86,44 +124,13 @@ export class TestRunner {
     this.tests.set(description, testFn);
   }
 
-  #walkDirs(path, files = []) {
-    // Read all files/folders from current path.
-    const entities = fs.readdirSync(path);
-
-    for (const filename of entities) {
-      const filePath = joinPaths(path, filename);
-      const stat = fs.statSync(filePath);
-
-      // Test file has been found.
-      if (stat.isFile && filePath.match(TEST_FILE)) {
-        files.push(filePath);
-        continue;
-      }
-
-      // Continue traversing the sub-directories.
-      if (stat.isDirectory) {
-        this.#walkDirs(filePath, files);
-      }
-    }
-  }
-
   /**
    * Loads tests from files to the runner recursively.
    *
    * @param {String} [entryPoint] - The path that serves as the starting point for loading tests.
    */
   async importTests(entryPoint = process.cwd()) {
-    // Find if the `entryPoint` is file or directory.
-    const stat = fs.statSync(entryPoint);
-
-    if (stat.isDirectory) {
-      this.#walkDirs(entryPoint, this.testFiles);
-    }
-
-    if (stat.isFile) {
-      this.testFiles.push(entryPoint);
-    }
-
+    this.testFiles.push(...findFiles(entryPoint, TEST_FILE));
     await Promise.all(this.testFiles.map((filename) => import(filename)));
   }
 
@@ -153,7 +160,10 @@ export class TestRunner {
         console.log(`${OK} ${green(description)}`);
       } catch (err) {
         this.counters.failed++;
-        console.log(`${FAIL} ${red(description)}\n ${red(err.stack)}`);
+        // Note: Point failures of transpiled tests (e.g. TypeScript) to the
+        // original source instead of the generated JavaScript.
+        const stack = err?.stack ? remapStackTrace(err.stack) : String(err);
+        console.log(`${FAIL} ${red(description)}\n ${red(stack)}`);
 
         // Stop running test suite.
         if (this.failFast) {
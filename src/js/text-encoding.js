@@ -16,14 +16,9 @@
  */
 
 /**
- * @fileoverview Polyfill for TextEncoder and TextDecoder.
- *
- * You probably want `text.min.js`, and not this file directly.
+ * @fileoverview Polyfill for TextEncoder (the TextDecoder is backed by Rust).
  */
 
-// used for FastTextDecoder
-const validUtfLabels = ['utf-8', 'utf8', 'unicode-1-1-utf-8'];
-
 /**
  * @constructor
  */
@@ -112,167 +107,133 @@ FastTextEncoder.prototype['encode'] = function (
 };
 
 /**
- * @constructor
- * @param {string=} utfLabel
- * @param {{fatal: boolean}=} options
+ * @param {string} string
+ * @param {!Uint8Array} dest
+ * @return {{read: number, written: number}}
  */
-function FastTextDecoder(utfLabel = 'utf-8', options = { fatal: false }) {
-  if (validUtfLabels.indexOf(utfLabel.toLowerCase()) === -1) {
-    throw new RangeError(
-      `Failed to construct 'TextDecoder': The encoding label provided ('${utfLabel}') is invalid.`
-    );
-  }
-  if (options.fatal) {
-    throw new Error(
-      `Failed to construct 'TextDecoder': the 'fatal' option is unsupported.`
+FastTextEncoder.prototype['encodeInto'] = function (string, dest) {
+  if (!(dest instanceof Uint8Array)) {
+    throw new TypeError(
+      `The "dest" argument must be an instance of Uint8Array.`
     );
   }
-}
 
-Object.defineProperty(FastTextDecoder.prototype, 'encoding', {
-  value: 'utf-8',
-});
+  string = String(string);
 
-Object.defineProperty(FastTextDecoder.prototype, 'fatal', { value: false });
+  let read = 0;
+  let written = 0;
 
-Object.defineProperty(FastTextDecoder.prototype, 'ignoreBOM', { value: false });
+  while (read < string.length) {
+    let value = string.codePointAt(read);
+    const units = value > 0xffff ? 2 : 1;
+
+    // Lone surrogates are replaced with U+FFFD (per spec).
+    if (value >= 0xd800 && value <= 0xdfff) {
+      value = 0xfffd;
+    }
+
+    let size = 4;
+    if (value < 0x80) size = 1;
+    else if (value < 0x800) size = 2;
+    else if (value < 0x10000) size = 3;
+
+    // Only write complete characters.
+    if (written + size > dest.length) break;
+
+    if (size === 1) {
+      dest[written++] = value;
+    } else if (size === 2) {
+      dest[written++] = ((value >>> 6) & 0x1f) | 0xc0;
+      dest[written++] = (value & 0x3f) | 0x80;
+    } else if (size === 3) {
+      dest[written++] = ((value >>> 12) & 0x0f) | 0xe0;
+      dest[written++] = ((value >>> 6) & 0x3f) | 0x80;
+      dest[written++] = (value & 0x3f) | 0x80;
+    } else {
+      dest[written++] = ((value >>> 18) & 0x07) | 0xf0;
+      dest[written++] = ((value >>> 12) & 0x3f) | 0x80;
+      dest[written++] = ((value >>> 6) & 0x3f) | 0x80;
+      dest[written++] = (value & 0x3f) | 0x80;
+    }
+
+    read += units;
+  }
+
+  return { read, written };
+};
+
+const binding = process.binding('text_encoding');
 
 /**
- * @param {!Uint8Array} bytes
- * @return {string}
+ * Converts a BufferSource into a Uint8Array view (no copying).
+ *
+ * @param {(!ArrayBuffer|!ArrayBufferView)} input
+ * @return {!Uint8Array}
  */
-function decodeBuffer(bytes) {
-  return Buffer.from(bytes.buffer, bytes.byteOffset, bytes.byteLength).toString(
-    'utf-8'
+function toUint8Array(input) {
+  if (input instanceof Uint8Array) return input;
+  if (input instanceof ArrayBuffer) return new Uint8Array(input);
+  if (ArrayBuffer.isView(input)) {
+    return new Uint8Array(input.buffer, input.byteOffset, input.byteLength);
+  }
+  throw new TypeError(
+    `The "input" argument must be an instance of ArrayBuffer or ArrayBufferView.`
   );
 }
 
 /**
- * @param {!Uint8Array} bytes
- * @return {string}
+ * A WHATWG TextDecoder backed by native decoders.
+ *
+ * Supports every encoding of the Encoding Standard (e.g. utf-8, utf-16le,
+ * iso-8859-2, windows-1252) along with streaming decoding.
  */
-function decodeFallback(bytes) {
-  let inputIndex = 0;
-
-  // Create a working buffer for UTF-16 code points, but don't generate one
-  // which is too large for small input sizes. UTF-8 to UCS-16 conversion is
-  // going to be at most 1:1, if all code points are ASCII. The other extreme
-  // is 4-byte UTF-8, which results in two UCS-16 points, but this is still 50%
-  // fewer entries in the output.
-  const pendingSize = Math.min(256 * 256, bytes.length + 1);
-  const pending = new Uint16Array(pendingSize);
-  const chunks = [];
-  let pendingIndex = 0;
-
-  for (;;) {
-    const more = inputIndex < bytes.length;
-
-    // If there's no more data or there'd be no room for two UTF-16 values,
-    // create a chunk. This isn't done at the end by simply slicing the data
-    // into equal sized chunks as we might hit a surrogate pair.
-    if (!more || pendingIndex >= pendingSize - 1) {
-      // nb. .apply and friends are *really slow*. Low-hanging fruit is to
-      // expand this to literally pass pending[0], pending[1], ... etc, but
-      // the output code expands pretty fast in this case.
-      chunks.push(
-        String.fromCharCode.apply(null, pending.subarray(0, pendingIndex))
+class TextDecoder {
+  #encoding;
+  #fatal;
+  #ignoreBOM;
+  #decoder;
+
+  /**
+   * @param {string=} label
+   * @param {{fatal: boolean, ignoreBOM: boolean}=} options
+   */
+  constructor(label = 'utf-8', options = {}) {
+    const encoding = binding.normalizeEncoding(String(label).trim());
+
+    if (!encoding || encoding === 'replacement') {
+      throw new RangeError(
+        `Failed to construct 'TextDecoder': The encoding label provided ('${label}') is invalid.`
       );
-
-      if (!more) {
-        return chunks.join('');
-      }
-
-      // Move the buffer forward and create another chunk.
-      bytes = bytes.subarray(inputIndex);
-      inputIndex = 0;
-      pendingIndex = 0;
     }
 
-    // The native TextDecoder will generate "REPLACEMENT CHARACTER" where the
-    // input data is invalid. Here, we blindly parse the data even if it's
-    // wrong: e.g., if a 3-byte sequence doesn't have two valid continuations.
-
-    const byte1 = bytes[inputIndex++];
-    if ((byte1 & 0x80) === 0) {
-      // 1-byte or null
-      pending[pendingIndex++] = byte1;
-    } else if ((byte1 & 0xe0) === 0xc0) {
-      // 2-byte
-      const byte2 = bytes[inputIndex++] & 0x3f;
-      pending[pendingIndex++] = ((byte1 & 0x1f) << 6) | byte2;
-    } else if ((byte1 & 0xf0) === 0xe0) {
-      // 3-byte
-      const byte2 = bytes[inputIndex++] & 0x3f;
-      const byte3 = bytes[inputIndex++] & 0x3f;
-      pending[pendingIndex++] = ((byte1 & 0x1f) << 12) | (byte2 << 6) | byte3;
-    } else if ((byte1 & 0xf8) === 0xf0) {
-      // 4-byte
-      const byte2 = bytes[inputIndex++] & 0x3f;
-      const byte3 = bytes[inputIndex++] & 0x3f;
-      const byte4 = bytes[inputIndex++] & 0x3f;
-
-      // this can be > 0xffff, so possibly generate surrogates
-      let codepoint =
-        ((byte1 & 0x07) << 0x12) | (byte2 << 0x0c) | (byte3 << 0x06) | byte4;
-      if (codepoint > 0xffff) {
-        // codepoint &= ~0x10000;
-        codepoint -= 0x10000;
-        pending[pendingIndex++] = ((codepoint >>> 10) & 0x3ff) | 0xd800;
-        codepoint = 0xdc00 | (codepoint & 0x3ff);
-      }
-      pending[pendingIndex++] = codepoint;
-    } else {
-      // invalid initial byte
-    }
+    this.#encoding = encoding;
+    this.#fatal = Boolean(options?.fatal);
+    this.#ignoreBOM = Boolean(options?.ignoreBOM);
+    this.#decoder = binding.createDecoder(encoding, this.#ignoreBOM);
   }
-}
 
-// Decoding a string is pretty slow, but use alternative options where possible.
-let decodeImpl = decodeFallback;
-if (typeof Buffer === 'function' && Buffer.from) {
-  // Buffer.from was added in Node v5.10.0 (2015-11-17).
-  decodeImpl = decodeBuffer;
-} else if (
-  typeof Blob === 'function' &&
-  typeof URL === 'function' &&
-  typeof URL.createObjectURL === 'function'
-) {
-  // Blob and URL.createObjectURL are available from IE10, Safari 6, Chrome 19
-  // (all released in 2012), Firefox 19 (2013), ...
-  decodeImpl = decodeSyncXHR;
-}
+  get encoding() {
+    return this.#encoding;
+  }
 
-/**
- * @param {(!ArrayBuffer|!ArrayBufferView)} buffer
- * @param {{stream: boolean}=} options
- * @return {string}
- */
-FastTextDecoder.prototype['decode'] = function (
-  buffer,
-  options = { stream: false }
-) {
-  if (options['stream']) {
-    throw new Error(`Failed to decode: the 'stream' option is unsupported.`);
+  get fatal() {
+    return this.#fatal;
   }
 
-  let bytes;
-
-  if (buffer instanceof Uint8Array) {
-    // Accept Uint8Array instances as-is.
-    bytes = buffer;
-  } else if (buffer.buffer instanceof ArrayBuffer) {
-    // Look for ArrayBufferView, which isn't a real type, but basically
-    // represents all the valid TypedArray types plus DataView. They all have
-    // ".buffer" as an instance of ArrayBuffer.
-    bytes = new Uint8Array(buffer.buffer);
-  } else {
-    // The only other valid argument here is that "buffer" is an ArrayBuffer.
-    // We also try to convert anything else passed to a Uint8Array, as this
-    // catches anything that's array-like. Native code would throw here.
-    bytes = new Uint8Array(buffer);
+  get ignoreBOM() {
+    return this.#ignoreBOM;
   }
 
-  return decodeImpl(/** @type {!Uint8Array} */ (bytes));
-};
+  /**
+   * @param {(!ArrayBuffer|!ArrayBufferView)=} input
+   * @param {{stream: boolean}=} options
+   * @return {string}
+   */
+  decode(input = new Uint8Array(), options = {}) {
+    const bytes = toUint8Array(input);
+    const stream = Boolean(options?.stream);
+    return binding.decode(this.#decoder, bytes, stream, this.#fatal);
+  }
+}
 
-export { FastTextEncoder as TextEncoder, FastTextDecoder as TextDecoder };
+export { FastTextEncoder as TextEncoder, TextDecoder };
@@ -0,0 +1,149 @@
+/**
+ * Timers Promises API
+ *
+ * Promise based versions of the DOM style timers, so a delay can simply be
+ * awaited (e.g. `await setTimeout(1000)`) and cancelled using abort signals.
+ *
+ * @see {@link https://nodejs.org/api/timers.html#timers-promises-api}
+ *
+ * @module TimersPromises
+ */
+
+import timers from 'timers';
+import { AbortSignal, abortErrorOf } from '@web/abort';
+
+function validateSignal(signal) {
+  if (signal !== undefined && !(signal instanceof AbortSignal)) {
+    throw new TypeError(`The "options.signal" must be an AbortSignal.`);
+  }
+}
+
+/**
+ * Returns a promise that resolves with `value` after `delay` milliseconds.
+ *
+ * @param {Number} [delay] - The milliseconds to wait before resolving.
+ * @param {*} [value] - The value the promise resolves with.
+ * @param {Object} [options]
+ * @param {AbortSignal} [options.signal] - Cancels the timer (and rejects).
+ * @returns {Promise<*>}
+ */
+export function setTimeout(delay, value, options = {}) {
+  const { signal } = options;
+  validateSignal(signal);
+
+  if (signal?.aborted) return Promise.reject(abortErrorOf(signal));
+
+  return new Promise((resolve, reject) => {
+    const onAbort = () => {
+      timers.clearTimeout(id);
+      reject(abortErrorOf(signal));
+    };
+
+    const id = timers.setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve(value);
+    }, delay);
+
+    signal?.addEventListener('abort', onAbort);
+  });
+}
+
+/**
+ * Returns a promise that resolves with `value` after the I/O phase.
+ *
+ * @param {*} [value] - The value the promise resolves with.
+ * @param {Object} [options]
+ * @param {AbortSignal} [options.signal] - Cancels the immediate (and rejects).
+ * @returns {Promise<*>}
+ */
+export function setImmediate(value, options = {}) {
+  const { signal } = options;
+  validateSignal(signal);
+
+  if (signal?.aborted) return Promise.reject(abortErrorOf(signal));
+
+  return new Promise((resolve, reject) => {
+    const onAbort = () => {
+      timers.clearImmediate(id);
+      reject(abortErrorOf(signal));
+    };
+
+    const id = timers.setImmediate(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve(value);
+    });
+
+    signal?.addEventListener('abort', onAbort);
+  });
+}
+
+/**
+ * Returns an async iterator that yields `value` every `delay` milliseconds.
+ *
+ * @param {Number} [delay] - The milliseconds between each iteration.
+ * @param {*} [value] - The value yielded on every iteration.
+ * @param {Object} [options]
+ * @param {AbortSignal} [options.signal] - Cancels the interval (and throws).
+ * @returns {AsyncGenerator<*>}
+ */
+export async function* setInterval(delay, value, options = {}) {
+  const { signal } = options;
+  validateSignal(signal);
+
+  if (signal?.aborted) throw abortErrorOf(signal);
+
+  // Note: Ticks that happen while the consumer is busy are not dropped, they
+  // are counted and yielded right after.
+  let ticks = 0;
+  let notify = null;
+
+  const wakeUp = () => {
+    notify?.();
+    notify = null;
+  };
+
+  const id = timers.setInterval(() => {
+    ticks++;
+    wakeUp();
+  }, delay);
+
+  signal?.addEventListener('abort', wakeUp);
+
+  try {
+    while (true) {
+      if (!ticks && !signal?.aborted) {
+        await new Promise((resolve) => (notify = resolve));
+      }
+      if (signal?.aborted) throw abortErrorOf(signal);
+      ticks--;
+      yield value;
+    }
+  } finally {
+    timers.clearInterval(id);
+    signal?.removeEventListener('abort', wakeUp);
+  }
+}
+
+/**
+ * A scheduling API on top of the promise based timers.
+ */
+export const scheduler = {
+  /**
+   * Waits for `delay` milliseconds before resolving.
+   *
+   * @param {Number} delay
+   * @param {Object} [options]
+   * @param {AbortSignal} [options.signal]
+   * @returns {Promise<void>}
+   */
+  wait: (delay, options) => setTimeout(delay, undefined, options),
+
+  /**
+   * Yields control back to the event-loop (until after the I/O phase).
+   *
+   * @returns {Promise<void>}
+   */
+  yield: () => setImmediate(),
+};
+
+export default { setTimeout, setImmediate, setInterval, scheduler };
@@ -0,0 +1,215 @@
+/**
+ * UDP Datagram APIs
+ *
+ * The UDP APIs provide an asynchronous network API for sending and receiving
+ * datagrams (e.g. DNS queries or telemetry), without any connection state.
+ *
+ * @see {@link https://nodejs.org/dist/latest-v18.x/docs/api/dgram.html}
+ *
+ * @module UDP
+ */
+
+import dns from 'dns';
+import { EventEmitter } from 'events';
+
+const binding = process.binding('udp');
+const pollBinding = process.binding('poll');
+
+function toUint8Array(data, encoding) {
+  if (!(data instanceof Uint8Array)) {
+    return new TextEncoder(encoding).encode(data);
+  }
+  return data;
+}
+
+// Resolves the host name to an IP address (preferring IPv4).
+async function resolveHost(hostname) {
+  const addresses = await dns.lookup(hostname);
+  const ipv4 = addresses.find((addr) => addr.family === 'IPv4');
+  return (ipv4 ?? addresses[0]).address;
+}
+
+/**
+ * Information about a socket's (local or remote) address.
+ *
+ * @typedef SocketAddress
+ * @property {Number} port - The port number.
+ * @property {String} family - The IP family (`IPv4` or `IPv6`).
+ * @property {String} address - The IP address.
+ */
+
+/**
+ * A UDP socket, bound to a local address.
+ */
+export class Socket extends EventEmitter {
+  #wrap;
+  #fd;
+  #host;
+  #readWatcher;
+  #writeWaiters;
+
+  /**
+   * Creates a new Socket instance.
+   *
+   * @ignore
+   * @param {Object} socketInfo - The bound socket's information.
+   * @returns {Socket}
+   */
+  constructor(socketInfo) {
+    super();
+    this.#wrap = socketInfo.wrap;
+    this.#fd = socketInfo.fd;
+    this.#host = socketInfo.host;
+    this.#readWatcher = null;
+    this.#writeWaiters = new Map();
+  }
+
+  /**
+   * Sends a datagram to a remote address.
+   *
+   * @param {String|Uint8Array} data - The contents of the datagram.
+   * @param {Number} port - The port number on the remote host.
+   * @param {String} [host] - The remote host (default: `127.0.0.1`).
+   * @param {String} [encoding] - The encoding of the data, if it's a string.
+   * @returns {Promise<Number>} The amount of bytes sent.
+   */
+  async send(data, port, host = '127.0.0.1', encoding = 'utf-8') {
+    // Check if the socket is closed.
+    if (!this.#wrap) {
+      throw new Error('Socket is closed.');
+    }
+
+    if (Number.isNaN(Number.parseInt(port))) {
+      throw new TypeError(`The "port" argument must be castable to number.`);
+    }
+
+    const bytes = toUint8Array(data, encoding);
+    const address = await resolveHost(host);
+
+    for (;;) {
+      try {
+        return binding.send(this.#wrap, bytes, address, port);
+      } catch (err) {
+        if (err.code !== 'ERR_WOULD_BLOCK') throw err;
+      }
+      // The send buffer is full, wait until it drains.
+      await this.#writable();
+    }
+  }
+
+  // Resolves once the socket can be written without blocking.
+  #writable() {
+    return new Promise((resolve, reject) => {
+      const interest = { writable: true };
+      const id = pollBinding.watch(this.#fd, interest, () => {
+        pollBinding.unwatch(id);
+        this.#writeWaiters.delete(id);
+        resolve();
+      });
+      this.#writeWaiters.set(id, reject);
+    });
+  }
+
+  /**
+   * Starts receiving datagrams, emitted as `message` events.
+   *
+   * @param {Function} [callback] - Also called with `(data, remote)` for every datagram.
+   */
+  recvStart(callback) {
+    // Check if the socket is closed.
+    if (!this.#wrap) {
+      throw new Error('Socket is closed.');
+    }
+
+    if (callback) this.on('message', callback);
+    if (this.#readWatcher !== null) return;
+
+    // HACK: The following is used to handle uncaught errors thrown
+    // from the event-emitter when no one is subscribed to the `error` event.
+    if (this.listenerCount('error') === 0) this.on('error', () => {});
+
+    const interest = { readable: true };
+    this.#readWatcher = pollBinding.watch(this.#fd, interest, () =>
+      this.#drain()
+    );
+  }
+
+  // Reads every available datagram (until the socket would block).
+  #drain() {
+    try {
+      let datagram;
+      while (this.#wrap && (datagram = binding.recv(this.#wrap))) {
+        const data = new Uint8Array(datagram.data);
+        this.emit('message', data, datagram.remote);
+      }
+    } catch (err) {
+      this.emit('error', err);
+    }
+  }
+
+  /**
+   * Stops receiving datagrams.
+   */
+  recvStop() {
+    if (this.#readWatcher === null) return;
+    pollBinding.unwatch(this.#readWatcher);
+    this.#readWatcher = null;
+  }
+
+  /**
+   * Returns the bound address, the address family name and port of the socket.
+   *
+   * @returns {SocketAddress} The local address of the socket.
+   */
+  address() {
+    return this.#host;
+  }
+
+  /**
+   * Closes the socket.
+   */
+  close() {
+    // Check if the socket is already closed.
+    if (!this.#wrap) {
+      throw new Error('Socket is already closed.');
+    }
+
+    // Note: The socket must stop being watched before it's closed.
+    this.recvStop();
+    for (const [id, reject] of this.#writeWaiters) {
+      pollBinding.unwatch(id);
+      reject(new Error('Socket is closed.'));
+    }
+    this.#writeWaiters.clear();
+    binding.close(this.#wrap);
+
+    this.#wrap = null;
+    this.emit('close');
+  }
+}
+
+/**
+ * Binds a new UDP socket to a local port and host.
+ *
+ * @param {Number} [port] - The local port (default: a random one).
+ * @param {String} [host] - The local host (default: `127.0.0.1`).
+ * @returns {Promise<Socket>} An instance of the `Socket` class.
+ */
+export async function bind(port = 0, host = '127.0.0.1') {
+  if (Number.isNaN(Number.parseInt(port))) {
+    throw new TypeError(`The "port" argument must be castable to number.`);
+  }
+
+  if (typeof host !== 'string') {
+    throw new TypeError(`The "host" argument must be of type string.`);
+  }
+
+  const address = await resolveHost(host);
+
+  return new Socket(binding.bind(address, port));
+}
+
+export default {
+  Socket,
+  bind,
+};
@@ -12,3 +12,179 @@ export function cloneFunction(fn) {
   }
   return temp;
 }
+
+/**
+ * Creates a parse-args error (with a Node.js compatible code).
+ */
+function parseArgsError(code, message) {
+  const error = new TypeError(message);
+  error.code = code;
+  return error;
+}
+
+function validateOptionsConfig(options) {
+  for (const [name, config] of Object.entries(options)) {
+    if (config.type !== 'string' && config.type !== 'boolean') {
+      throw new TypeError(
+        `The "options.${name}.type" property must be "string" or "boolean".`
+      );
+    }
+    if (config.short !== undefined && String(config.short).length !== 1) {
+      throw new TypeError(
+        `The "options.${name}.short" property must be a single character.`
+      );
+    }
+  }
+}
+
+/**
+ * Parses command-line arguments (a subset of Node's `util.parseArgs`).
+ *
+ * @param {Object} [config]
+ * @param {String[]} [config.args] - The arguments (default: the script's args).
+ * @param {Object} [config.options] - The known options, keyed by their long
+ * name, e.g. `{ port: { type: 'string', short: 'p', default: '80' } }`.
+ * @param {Boolean} [config.strict] - Throw on unknown options (default: true).
+ * @param {Boolean} [config.allowPositionals] - Accept positional arguments
+ * (default: the opposite of `strict`).
+ * @returns {{ values: Object, positionals: String[] }}
+ */
+export function parseArgs(config = {}) {
+  const {
+    args = process.argv.slice(2),
+    options = {},
+    strict = true,
+    allowPositionals = !strict,
+  } = config;
+
+  validateOptionsConfig(options);
+
+  const values = Object.create(null);
+  const positionals = [];
+
+  // Maps short aliases (e.g. `-p`) to their long option names.
+  const shorts = new Map();
+  for (const [name, { short }] of Object.entries(options)) {
+    if (short !== undefined) shorts.set(String(short), name);
+  }
+
+  const store = (name, value) => {
+    const option = options[name];
+    if (option?.multiple) {
+      values[name] = [...(values[name] ?? []), value];
+      return;
+    }
+    values[name] = value;
+  };
+
+  const storeOption = (name, display, inlineValue, nextArg) => {
+    const option = options[name];
+
+    // Unknown options are only allowed in non-strict mode.
+    if (!option) {
+      if (strict) {
+        throw parseArgsError(
+          'ERR_PARSE_ARGS_UNKNOWN_OPTION',
+          `Unknown option '${display}'.`
+        );
+      }
+      store(name, inlineValue ?? true);
+      return false;
+    }
+
+    if (option.type === 'boolean') {
+      if (inlineValue !== undefined && strict) {
+        throw parseArgsError(
+          'ERR_PARSE_ARGS_INVALID_OPTION_VALUE',
+          `Option '${display}' does not take an argument.`
+        );
+      }
+      store(name, true);
+      return false;
+    }
+
+    // String options take the inline value or consume the next argument.
+    if (inlineValue !== undefined) {
+      store(name, inlineValue);
+      return false;
+    }
+
+    if (strict && nextArg === undefined) {
+      throw parseArgsError(
+        'ERR_PARSE_ARGS_INVALID_OPTION_VALUE',
+        `Option '${display} <value>' argument missing.`
+      );
+    }
+
+    // Note: Values starting with a dash are most likely a forgotten argument,
+    // they have to be passed inline instead (e.g. `--offset=-1`).
+    if (strict && String(nextArg).startsWith('-')) {
+      throw parseArgsError(
+        'ERR_PARSE_ARGS_INVALID_OPTION_VALUE',
+        `Option '${display}' argument is ambiguous, use '${display}=-XYZ'.`
+      );
+    }
+
+    store(name, nextArg === undefined ? true : String(nextArg));
+    return nextArg !== undefined;
+  };
+
+  const addPositional = (arg) => {
+    if (!allowPositionals) {
+      throw parseArgsError(
+        'ERR_PARSE_ARGS_UNEXPECTED_POSITIONAL',
+        `Unexpected argument '${arg}'. This command does not take positional arguments.`
+      );
+    }
+    positionals.push(arg);
+  };
+
+  for (let i = 0; i < args.length; i++) {
+    const arg = String(args[i]);
+    const nextArg = args[i + 1];
+
+    // Everything after the `--` terminator is positional.
+    if (arg === '--') {
+      args.slice(i + 1).map(String).forEach(addPositional);
+      break;
+    }
+
+    if (arg.startsWith('--')) {
+      const [name, ...rest] = arg.slice(2).split('=');
+      const inlineValue = rest.length > 0 ? rest.join('=') : undefined;
+      if (storeOption(name, `--${name}`, inlineValue, nextArg)) i++;
+      continue;
+    }
+
+    if (arg.startsWith('-') && arg !== '-') {
+      // Short options can be combined (e.g. `-abc` or `-p8080`).
+      for (let j = 1; j < arg.length; j++) {
+        const short = arg[j];
+        const name = shorts.get(short) ?? short;
+        const rest = arg.slice(j + 1);
+
+        if (options[name]?.type === 'string') {
+          const inlineValue = rest.length > 0 ? rest : undefined;
+          if (storeOption(name, `-${short}`, inlineValue, nextArg)) i++;
+          break;
+        }
+
+        storeOption(name, `-${short}`, undefined, undefined);
+      }
+      continue;
+    }
+
+    addPositional(arg);
+  }
+
+  // Fill in the default values of the options that weren't given.
+  for (const [name, option] of Object.entries(options)) {
+    if (values[name] === undefined && option.default !== undefined) {
+      values[name] = option.default;
+    }
+  }
+
+  return { values, positionals };
+}
+
+export default { cloneFunction, parseArgs };
@@ -0,0 +1,1205 @@
+// Streams API
+//
+// The Streams API allows JavaScript to programmatically access streams of data
+// (received over the network, read from disk, etc.) and process them chunk by
+// chunk, with backpressure signaled through the queuing strategies.
+//
+// https://developer.mozilla.org/en-US/docs/Web/API/Streams_API
+
+// Internal state of every stream object, kept out of the public surface.
+const internals = new WeakMap();
+
+function createDeferred() {
+  let resolve;
+  let reject;
+  const promise = new Promise((res, rej) => {
+    resolve = res;
+    reject = rej;
+  });
+  return { promise, resolve, reject };
+}
+
+function settled(value) {
+  const deferred = createDeferred();
+  deferred.resolve(value);
+  return deferred;
+}
+
+function extractHighWaterMark(strategy, fallback) {
+  const value = strategy?.highWaterMark ?? fallback;
+  const highWaterMark = Number(value);
+  if (Number.isNaN(highWaterMark) || highWaterMark < 0) {
+    throw new RangeError(`The "highWaterMark" must be a non-negative number.`);
+  }
+  return highWaterMark;
+}
+
+function extractSizeAlgorithm(strategy) {
+  const size = strategy?.size;
+  return typeof size === 'function' ? (chunk) => Number(size(chunk)) : () => 1;
+}
+
+function toBytes(chunk) {
+  if (chunk instanceof ArrayBuffer) return new Uint8Array(chunk);
+  if (ArrayBuffer.isView(chunk)) {
+    return new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+  }
+  throw new TypeError(
+    `The chunk must be an ArrayBuffer or an ArrayBufferView.`
+  );
+}
+
+/**
+ * The shared state machine behind a ReadableStream and its controller.
+ * @ignore
+ */
+class ReadableState {
+  constructor(source, strategy) {
+    this.source = source;
+    this.isBytes = source.type === 'bytes';
+    this.state = 'readable';
+    this.storedError = undefined;
+    this.queue = [];
+    this.queueTotalSize = 0;
+    this.readRequests = [];
+    this.reader = undefined;
+    this.started = false;
+    this.pulling = false;
+    this.pullAgain = false;
+    this.closeRequested = false;
+    this.closed = createDeferred();
+    this.closed.promise.catch(() => {});
+    this.highWaterMark = extractHighWaterMark(strategy, this.isBytes ? 0 : 1);
+    this.sizeAlgorithm = this.isBytes
+      ? (chunk) => chunk.byteLength
+      : extractSizeAlgorithm(strategy);
+  }
+
+  get desiredSize() {
+    if (this.state === 'errored') return null;
+    if (this.state === 'closed') return 0;
+    return this.highWaterMark - this.queueTotalSize;
+  }
+
+  start(controller) {
+    this.controller = controller;
+    const startResult = this.source.start?.(controller);
+
+    Promise.resolve(startResult).then(
+      () => {
+        this.started = true;
+        this.callPull();
+      },
+      (err) => this.error(err)
+    );
+  }
+
+  shouldPull() {
+    if (this.state !== 'readable' || this.closeRequested) return false;
+    if (!this.started) return false;
+    if (this.readRequests.length > 0) return true;
+    return this.desiredSize > 0;
+  }
+
+  callPull() {
+    if (!this.shouldPull() || !this.source.pull) return;
+
+    // Coalesce pull requests issued while a pull is in progress.
+    if (this.pulling) {
+      this.pullAgain = true;
+      return;
+    }
+
+    this.pulling = true;
+
+    Promise.resolve()
+      .then(() => this.source.pull(this.controller))
+      .then(
+        () => {
+          this.pulling = false;
+          if (this.pullAgain) {
+            this.pullAgain = false;
+            this.callPull();
+          }
+        },
+        (err) => this.error(err)
+      );
+  }
+
+  enqueue(chunk) {
+    if (this.state !== 'readable' || this.closeRequested) {
+      throw new TypeError('The stream is not in a readable state.');
+    }
+
+    if (this.isBytes) chunk = toBytes(chunk);
+
+    const size = this.sizeAlgorithm(chunk);
+    this.queue.push({ chunk, size });
+    this.queueTotalSize += size;
+
+    this.fulfillReadRequests();
+    this.callPull();
+  }
+
+  close() {
+    if (this.state !== 'readable' || this.closeRequested) {
+      throw new TypeError('The stream is not in a readable state.');
+    }
+
+    this.closeRequested = true;
+    if (this.queue.length === 0) this.finishClose();
+  }
+
+  error(err) {
+    if (this.state !== 'readable') return;
+
+    this.state = 'errored';
+    this.storedError = err;
+    this.queue = [];
+    this.queueTotalSize = 0;
+
+    this.readRequests.forEach((request) => request.reject(err));
+    this.readRequests = [];
+    this.closed.reject(err);
+  }
+
+  finishClose() {
+    this.state = 'closed';
+    this.readRequests.forEach((request) => request.resolve(this.done(request)));
+    this.readRequests = [];
+    this.closed.resolve();
+  }
+
+  done(request) {
+    const value = request.view ? request.view.subarray(0, 0) : undefined;
+    return { value, done: true };
+  }
+
+  fulfillReadRequests() {
+    while (this.readRequests.length > 0 && this.queue.length > 0) {
+      const request = this.readRequests.shift();
+      request.resolve(this.dequeue(request.view));
+    }
+    if (this.closeRequested && this.queue.length === 0) this.finishClose();
+  }
+
+  dequeue(view) {
+    // Default reads hand over whole chunks.
+    if (!view) {
+      const { chunk, size } = this.queue.shift();
+      this.queueTotalSize -= size;
+      return { value: chunk, done: false };
+    }
+
+    // BYOB reads copy as many queued bytes as the view can fit.
+    const target = toBytes(view);
+    const elementSize = view.BYTES_PER_ELEMENT || 1;
+    let filled = 0;
+
+    while (this.queue.length > 0 && filled < target.byteLength) {
+      const entry = this.queue[0];
+      const available = target.byteLength - filled;
+      const count = Math.min(entry.chunk.byteLength, available);
+
+      target.set(entry.chunk.subarray(0, count), filled);
+      filled += count;
+
+      if (count === entry.chunk.byteLength) {
+        this.queue.shift();
+      } else {
+        entry.chunk = entry.chunk.subarray(count);
+      }
+      this.queueTotalSize -= count;
+    }
+
+    const length = Math.floor(filled / elementSize);
+    const value = new view.constructor(view.buffer, view.byteOffset, length);
+
+    return { value, done: false };
+  }
+
+  read(view) {
+    if (this.state === 'errored') return Promise.reject(this.storedError);
+    if (this.state === 'closed') return Promise.resolve(this.done({ view }));
+
+    const request = createDeferred();
+    request.view = view;
+    this.readRequests.push(request);
+
+    this.fulfillReadRequests();
+    this.callPull();
+
+    return request.promise;
+  }
+
+  async cancel(reason) {
+    if (this.state === 'closed') return;
+    if (this.state === 'errored') throw this.storedError;
+
+    this.queue = [];
+    this.queueTotalSize = 0;
+    this.finishClose();
+
+    await this.source.cancel?.(reason);
+  }
+
+  releaseReader() {
+    const err = new TypeError('The reader lock was released.');
+    this.readRequests.forEach((request) => request.reject(err));
+    this.readRequests = [];
+    this.reader = undefined;
+  }
+}
+
+/**
+ * Allows control of a ReadableStream's state and internal queue.
+ */
+export class ReadableStreamDefaultController {
+  #state;
+
+  constructor(state) {
+    this.#state = state;
+  }
+
+  /**
+   * The desired size required to fill the stream's internal queue.
+   */
+  get desiredSize() {
+    return this.#state.desiredSize;
+  }
+
+  /**
+   * Enqueues a given chunk in the associated stream.
+   *
+   * @param {*} chunk
+   */
+  enqueue(chunk) {
+    this.#state.enqueue(chunk);
+  }
+
+  /**
+   * Closes the associated stream.
+   */
+  close() {
+    this.#state.close();
+  }
+
+  /**
+   * Causes any future interactions with the associated stream to error.
+   *
+   * @param {*} err
+   */
+  error(err) {
+    this.#state.error(err);
+  }
+}
+
+/**
+ * The controller of a readable byte stream.
+ */
+export class ReadableByteStreamController extends ReadableStreamDefaultController {
+  /**
+   * Bytes are always enqueued by the source, so there is no pending view.
+   */
+  get byobRequest() {
+    return null;
+  }
+}
+
+/**
+ * A readable stream of data.
+ */
+export class ReadableStream {
+  /**
+   * Creates a new ReadableStream object.
+   *
+   * @param {Object} [source] - Defines how the constructed stream instance will behave.
+   * @param {Object} [strategy] - The queuing strategy of the stream.
+   * @returns {ReadableStream}
+   */
+  constructor(source = {}, strategy = {}) {
+    const state = new ReadableState(source ?? {}, strategy);
+    const controller = state.isBytes
+      ? new ReadableByteStreamController(state)
+      : new ReadableStreamDefaultController(state);
+
+    internals.set(this, state);
+    state.start(controller);
+  }
+
+  /**
+   * Creates a ReadableStream that pulls its chunks from an (async) iterable.
+   *
+   * @param {(AsyncIterable|Iterable)} iterable
+   * @returns {ReadableStream}
+   */
+  static from(iterable) {
+    const iterator = iterable?.[Symbol.asyncIterator]
+      ? iterable[Symbol.asyncIterator]()
+      : iterable?.[Symbol.iterator]?.();
+
+    if (!iterator) {
+      throw new TypeError(
+        `The "iterable" argument must be an iterable object.`
+      );
+    }
+
+    const source = {
+      async pull(controller) {
+        const { value, done } = await iterator.next();
+        if (done) return controller.close();
+        controller.enqueue(value);
+      },
+      // Note: Returning the iterator releases the underlying resource (e.g.
+      // it closes the socket or the file the chunks are coming from).
+      async cancel(reason) {
+        await iterator.return?.(reason);
+      },
+    };
+
+    return new ReadableStream(source, { highWaterMark: 0 });
+  }
+
+  /**
+   * Returns whether or not the readable stream is locked to a reader.
+   */
+  get locked() {
+    return internals.get(this).reader !== undefined;
+  }
+
+  /**
+   * Cancels the stream, signaling a loss of interest in it by the consumer.
+   *
+   * @param {*} [reason]
+   * @returns {Promise<void>}
+   */
+  async cancel(reason) {
+    if (this.locked) {
+      throw new TypeError('Cannot cancel a locked stream.');
+    }
+    return internals.get(this).cancel(reason);
+  }
+
+  /**
+   * Creates a reader and locks the stream to it.
+   *
+   * @param {Object} [options]
+   * @param {string} [options.mode] - Set to "byob" to get a BYOB reader.
+   * @returns {(ReadableStreamDefaultReader|ReadableStreamBYOBReader)}
+   */
+  getReader(options = {}) {
+    if (options?.mode === undefined) {
+      return new ReadableStreamDefaultReader(this);
+    }
+    if (options.mode === 'byob') return new ReadableStreamBYOBReader(this);
+
+    throw new TypeError(`Invalid reader mode: "${options.mode}".`);
+  }
+
+  /**
+   * Pipes the current stream to a given writable stream.
+   *
+   * @param {WritableStream} destination
+   * @param {Object} [options]
+   * @returns {Promise<void>}
+   */
+  async pipeTo(destination, options = {}) {
+    const { preventClose, preventAbort, preventCancel, signal } = options;
+
+    const reader = this.getReader();
+    const writer = destination.getWriter();
+
+    const aborted = new Promise((_, reject) => {
+      if (signal?.aborted) reject(signal.reason);
+      signal?.addEventListener('abort', () => reject(signal.reason));
+    });
+    aborted.catch(() => {});
+
+    try {
+      while (true) {
+        await Promise.race([writer.ready, aborted]);
+        const { value, done } = await Promise.race([reader.read(), aborted]);
+        if (done) break;
+        await Promise.race([writer.write(value), aborted]);
+      }
+      if (!preventClose) await writer.close();
+    } catch (err) {
+      if (!preventCancel) await reader.cancel(err).catch(() => {});
+      if (!preventAbort) await writer.abort(err).catch(() => {});
+      throw err;
+    } finally {
+      reader.releaseLock();
+      writer.releaseLock();
+    }
+  }
+
+  /**
+   * Pipes the current stream through a transform stream.
+   *
+   * @param {Object} transform - A { writable, readable } pair.
+   * @param {Object} [options] - The same options as `pipeTo`.
+   * @returns {ReadableStream}
+   */
+  pipeThrough({ writable, readable }, options = {}) {
+    this.pipeTo(writable, options).catch(() => {});
+    return readable;
+  }
+
+  /**
+   * Tees the stream, returning a two-element array of branches.
+   *
+   * @returns {Array<ReadableStream>}
+   */
+  tee() {
+    const reader = this.getReader();
+    const controllers = [];
+    const canceled = [false, false];
+    const reasons = [];
+    let reading = null;
+
+    const pull = () => {
+      reading ??= reader.read().then(
+        ({ value, done }) => {
+          reading = null;
+          controllers.forEach((controller, i) => {
+            if (canceled[i]) return;
+            done ? controller.close() : controller.enqueue(value);
+          });
+        },
+        (err) => controllers.forEach((controller) => controller.error(err))
+      );
+      return reading;
+    };
+
+    const branch = (i) =>
+      new ReadableStream(
+        {
+          start: (controller) => (controllers[i] = controller),
+          pull,
+          cancel: (reason) => {
+            canceled[i] = true;
+            reasons[i] = reason;
+            if (canceled[0] && canceled[1]) return reader.cancel(reasons);
+          },
+        },
+        { highWaterMark: 0 }
+      );
+
+    return [branch(0), branch(1)];
+  }
+
+  /**
+   * Returns an async iterator over the chunks of the stream.
+   *
+   * @param {Object} [options]
+   * @param {boolean} [options.preventCancel] - Keep the stream open on early exit.
+   * @returns {AsyncGenerator}
+   */
+  async *values(options = {}) {
+    const reader = this.getReader();
+    let finished = false;
+
+    try {
+      while (true) {
+        const { value, done } = await reader.read();
+        if (done) break;
+        yield value;
+      }
+      finished = true;
+    } finally {
+      if (!finished && !options?.preventCancel) await reader.cancel();
+      reader.releaseLock();
+    }
+  }
+
+  /**
+   * The readable stream should be async iterable.
+   * @ignore
+   */
+  [Symbol.asyncIterator](options) {
+    return this.values(options);
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'ReadableStream';
+  }
+}
+
+/**
+ * Locks a stream so that no other reader can acquire it.
+ * @ignore
+ */
+function acquireReader(reader, stream) {
+  const state = internals.get(stream);
+
+  if (!state) {
+    throw new TypeError(`The "stream" argument must be a ReadableStream.`);
+  }
+  if (state.reader) {
+    throw new TypeError('The stream is already locked to a reader.');
+  }
+
+  state.reader = reader;
+  return state;
+}
+
+/**
+ * A default reader that can be used to read chunks from a ReadableStream.
+ */
+export class ReadableStreamDefaultReader {
+  #state;
+
+  /**
+   * Creates a new reader and locks the given stream to it.
+   *
+   * @param {ReadableStream} stream
+   * @returns {ReadableStreamDefaultReader}
+   */
+  constructor(stream) {
+    this.#state = acquireReader(this, stream);
+  }
+
+  #assertLocked() {
+    if (this.#state?.reader !== this) {
+      throw new TypeError('The reader has been released.');
+    }
+  }
+
+  /**
+   * A promise that fulfills when the stream closes.
+   */
+  get closed() {
+    return this.#state.closed.promise;
+  }
+
+  /**
+   * Returns a promise providing access to the next chunk in the queue.
+   *
+   * @returns {Promise<{ value: *, done: boolean }>}
+   */
+  async read() {
+    this.#assertLocked();
+    return this.#state.read();
+  }
+
+  /**
+   * Cancels the stream the reader is locked to.
+   *
+   * @param {*} [reason]
+   * @returns {Promise<void>}
+   */
+  async cancel(reason) {
+    this.#assertLocked();
+    return this.#state.cancel(reason);
+  }
+
+  /**
+   * Releases the reader's lock on the stream.
+   */
+  releaseLock() {
+    if (this.#state?.reader === this) this.#state.releaseReader();
+  }
+}
+
+/**
+ * A reader that reads bytes directly into a provided buffer (bring your own buffer).
+ */
+export class ReadableStreamBYOBReader {
+  #state;
+
+  /**
+   * Creates a new BYOB reader and locks the given byte stream to it.
+   *
+   * @param {ReadableStream} stream
+   * @returns {ReadableStreamBYOBReader}
+   */
+  constructor(stream) {
+    if (!internals.get(stream)?.isBytes) {
+      throw new TypeError('BYOB readers require a readable byte stream.');
+    }
+    this.#state = acquireReader(this, stream);
+  }
+
+  #assertLocked() {
+    if (this.#state?.reader !== this) {
+      throw new TypeError('The reader has been released.');
+    }
+  }
+
+  /**
+   * A promise that fulfills when the stream closes.
+   */
+  get closed() {
+    return this.#state.closed.promise;
+  }
+
+  /**
+   * Reads bytes from the stream into the given view.
+   *
+   * @param {ArrayBufferView} view - The buffer the bytes will be written to.
+   * @returns {Promise<{ value: ArrayBufferView, done: boolean }>}
+   */
+  async read(view) {
+    this.#assertLocked();
+
+    if (!ArrayBuffer.isView(view) || view.byteLength === 0) {
+      throw new TypeError(
+        `The "view" argument must be a non-empty ArrayBufferView.`
+      );
+    }
+    return this.#state.read(view);
+  }
+
+  /**
+   * Cancels the stream the reader is locked to.
+   *
+   * @param {*} [reason]
+   * @returns {Promise<void>}
+   */
+  async cancel(reason) {
+    this.#assertLocked();
+    return this.#state.cancel(reason);
+  }
+
+  /**
+   * Releases the reader's lock on the stream.
+   */
+  releaseLock() {
+    if (this.#state?.reader === this) this.#state.releaseReader();
+  }
+}
+
+/**
+ * The shared state machine behind a WritableStream and its controller.
+ * @ignore
+ */
+class WritableState {
+  constructor(sink, strategy) {
+    this.sink = sink;
+    this.state = 'writable';
+    this.storedError = undefined;
+    this.queue = [];
+    this.queueTotalSize = 0;
+    this.inFlight = false;
+    this.started = false;
+    this.closeRequest = undefined;
+    this.writer = undefined;
+    this.ready = settled();
+    this.closed = createDeferred();
+    this.closed.promise.catch(() => {});
+    this.highWaterMark = extractHighWaterMark(strategy, 1);
+    this.sizeAlgorithm = extractSizeAlgorithm(strategy);
+  }
+
+  get desiredSize() {
+    if (this.state === 'errored') return null;
+    if (this.state === 'closed') return 0;
+    return this.highWaterMark - this.queueTotalSize;
+  }
+
+  start(controller) {
+    this.controller = controller;
+    const startResult = this.sink.start?.(controller);
+
+    Promise.resolve(startResult).then(
+      () => {
+        this.started = true;
+        this.advance();
+      },
+      (err) => this.error(err)
+    );
+  }
+
+  updateBackpressure() {
+    const backpressure = this.desiredSize <= 0;
+    const waiting = this.ready.pending;
+
+    if (backpressure && !waiting) {
+      this.ready = createDeferred();
+      this.ready.pending = true;
+      this.ready.promise.catch(() => {});
+    }
+    if (!backpressure && waiting) {
+      this.ready.pending = false;
+      this.ready.resolve();
+    }
+  }
+
+  write(chunk) {
+    if (this.state === 'errored') return Promise.reject(this.storedError);
+    if (this.state !== 'writable' || this.closeRequest) {
+      return Promise.reject(new TypeError('The stream is closing or closed.'));
+    }
+
+    const request = createDeferred();
+    const size = this.sizeAlgorithm(chunk);
+
+    this.queue.push({ chunk, size, request });
+    this.queueTotalSize += size;
+
+    this.updateBackpressure();
+    this.advance();
+
+    return request.promise;
+  }
+
+  close() {
+    if (this.state === 'errored') return Promise.reject(this.storedError);
+    if (this.state !== 'writable' || this.closeRequest) {
+      return Promise.reject(new TypeError('The stream is closing or closed.'));
+    }
+
+    this.closeRequest = createDeferred();
+
+    // A closing stream no longer applies backpressure.
+    if (this.ready.pending) {
+      this.ready.pending = false;
+      this.ready.resolve();
+    }
+
+    this.advance();
+    return this.closeRequest.promise;
+  }
+
+  advance() {
+    if (!this.started || this.inFlight || this.state !== 'writable') return;
+
+    // Flush queued chunks one at a time, in order.
+    if (this.queue.length > 0) {
+      const { chunk, size, request } = this.queue[0];
+      this.inFlight = true;
+
+      Promise.resolve()
+        .then(() => this.sink.write?.(chunk, this.controller))
+        .then(
+          () => {
+            this.inFlight = false;
+            this.queue.shift();
+            this.queueTotalSize -= size;
+            request.resolve();
+            if (!this.closeRequest) this.updateBackpressure();
+            this.advance();
+          },
+          (err) => this.error(err)
+        );
+      return;
+    }
+
+    if (this.closeRequest) {
+      this.inFlight = true;
+
+      Promise.resolve()
+        .then(() => this.sink.close?.())
+        .then(
+          () => {
+            this.inFlight = false;
+            this.state = 'closed';
+            this.closeRequest.resolve();
+            this.closed.resolve();
+          },
+          (err) => this.error(err)
+        );
+    }
+  }
+
+  error(err) {
+    if (this.state === 'errored' || this.state === 'closed') return;
+
+    this.state = 'errored';
+    this.storedError = err;
+    this.inFlight = false;
+
+    this.queue.forEach(({ request }) => request.reject(err));
+    this.queue = [];
+    this.queueTotalSize = 0;
+
+    this.closeRequest?.reject(err);
+    this.closed.reject(err);
+
+    if (this.ready.pending) {
+      this.ready.pending = false;
+      this.ready.reject(err);
+    } else {
+      this.ready = createDeferred();
+      this.ready.promise.catch(() => {});
+      this.ready.reject(err);
+    }
+  }
+
+  async abort(reason) {
+    if (this.state === 'closed' || this.state === 'errored') return;
+
+    this.error(reason);
+    await this.sink.abort?.(reason);
+  }
+}
+
+/**
+ * Allows control of a WritableStream's state.
+ */
+export class WritableStreamDefaultController {
+  #state;
+
+  constructor(state) {
+    this.#state = state;
+  }
+
+  /**
+   * Causes any future interactions with the associated stream to error.
+   *
+   * @param {*} err
+   */
+  error(err) {
+    this.#state.error(err);
+  }
+}
+
+/**
+ * A destination for streaming data.
+ */
+export class WritableStream {
+  /**
+   * Creates a new WritableStream object.
+   *
+   * @param {Object} [sink] - Defines how the constructed stream instance will behave.
+   * @param {Object} [strategy] - The queuing strategy of the stream.
+   * @returns {WritableStream}
+   */
+  constructor(sink = {}, strategy = {}) {
+    const state = new WritableState(sink ?? {}, strategy);
+    const controller = new WritableStreamDefaultController(state);
+
+    internals.set(this, state);
+    state.start(controller);
+  }
+
+  /**
+   * Returns whether or not the writable stream is locked to a writer.
+   */
+  get locked() {
+    return internals.get(this).writer !== undefined;
+  }
+
+  /**
+   * Aborts the stream, signaling that the producer can no longer write to it.
+   *
+   * @param {*} [reason]
+   * @returns {Promise<void>}
+   */
+  async abort(reason) {
+    if (this.locked) {
+      throw new TypeError('Cannot abort a locked stream.');
+    }
+    return internals.get(this).abort(reason);
+  }
+
+  /**
+   * Closes the stream.
+   *
+   * @returns {Promise<void>}
+   */
+  async close() {
+    if (this.locked) {
+      throw new TypeError('Cannot close a locked stream.');
+    }
+    return internals.get(this).close();
+  }
+
+  /**
+   * Creates a writer and locks the stream to it.
+   *
+   * @returns {WritableStreamDefaultWriter}
+   */
+  getWriter() {
+    return new WritableStreamDefaultWriter(this);
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'WritableStream';
+  }
+}
+
+/**
+ * A writer that can be used to write chunks to a WritableStream.
+ */
+export class WritableStreamDefaultWriter {
+  #state;
+
+  /**
+   * Creates a new writer and locks the given stream to it.
+   *
+   * @param {WritableStream} stream
+   * @returns {WritableStreamDefaultWriter}
+   */
+  constructor(stream) {
+    const state = internals.get(stream);
+
+    if (!(state instanceof WritableState)) {
+      throw new TypeError(`The "stream" argument must be a WritableStream.`);
+    }
+    if (state.writer) {
+      throw new TypeError('The stream is already locked to a writer.');
+    }
+
+    state.writer = this;
+    this.#state = state;
+  }
+
+  #assertLocked() {
+    if (this.#state?.writer !== this) {
+      throw new TypeError('The writer has been released.');
+    }
+  }
+
+  /**
+   * A promise that fulfills when the stream closes.
+   */
+  get closed() {
+    return this.#state.closed.promise;
+  }
+
+  /**
+   * A promise that fulfills when the desired size of the queue becomes positive.
+   */
+  get ready() {
+    return this.#state.ready.promise;
+  }
+
+  /**
+   * The desired size required to fill the stream's internal queue.
+   */
+  get desiredSize() {
+    this.#assertLocked();
+    return this.#state.desiredSize;
+  }
+
+  /**
+   * Writes a chunk of data to the stream.
+   *
+   * @param {*} chunk
+   * @returns {Promise<void>}
+   */
+  async write(chunk) {
+    this.#assertLocked();
+    return this.#state.write(chunk);
+  }
+
+  /**
+   * Closes the stream once all queued chunks have been written.
+   *
+   * @returns {Promise<void>}
+   */
+  async close() {
+    this.#assertLocked();
+    return this.#state.close();
+  }
+
+  /**
+   * Aborts the stream.
+   *
+   * @param {*} [reason]
+   * @returns {Promise<void>}
+   */
+  async abort(reason) {
+    this.#assertLocked();
+    return this.#state.abort(reason);
+  }
+
+  /**
+   * Releases the writer's lock on the stream.
+   */
+  releaseLock() {
+    if (this.#state?.writer === this) this.#state.writer = undefined;
+  }
+}
+
+/**
+ * Allows control of the readable side of a TransformStream.
+ */
+export class TransformStreamDefaultController {
+  #link;
+
+  constructor(link) {
+    this.#link = link;
+  }
+
+  /**
+   * The desired size required to fill the readable side's internal queue.
+   */
+  get desiredSize() {
+    return this.#link.readable.desiredSize;
+  }
+
+  /**
+   * Enqueues a chunk to the readable side of the stream.
+   *
+   * @param {*} chunk
+   */
+  enqueue(chunk) {
+    const { readable } = this.#link;
+    readable.enqueue(chunk);
+    if (readable.desiredSize <= 0) this.#link.applyBackpressure();
+  }
+
+  /**
+   * Errors both sides of the stream.
+   *
+   * @param {*} err
+   */
+  error(err) {
+    this.#link.readable.error(err);
+    this.#link.writable.error(err);
+  }
+
+  /**
+   * Closes the readable side and errors the writable side of the stream.
+   */
+  terminate() {
+    const { readable, writable } = this.#link;
+    if (readable.state === 'readable' && !readable.closeRequested) {
+      readable.close();
+    }
+    writable.error(new TypeError('The transform stream has been terminated.'));
+  }
+}
+
+/**
+ * A writable/readable pair that transforms chunks written to it.
+ */
+export class TransformStream {
+  #readable;
+  #writable;
+
+  /**
+   * Creates a new TransformStream object.
+   *
+   * @param {Object} [transformer] - Defines how chunks are transformed.
+   * @param {Object} [writableStrategy] - The queuing strategy of the writable side.
+   * @param {Object} [readableStrategy] - The queuing strategy of the readable side.
+   * @returns {TransformStream}
+   */
+  constructor(transformer = {}, writableStrategy = {}, readableStrategy = {}) {
+    transformer ??= {};
+
+    const link = {};
+    const controller = new TransformStreamDefaultController(link);
+
+    link.applyBackpressure = () => {
+      if (link.backpressure?.pending) return;
+      link.backpressure = createDeferred();
+      link.backpressure.pending = true;
+    };
+
+    link.releaseBackpressure = () => {
+      link.backpressure.pending = false;
+      link.backpressure.resolve();
+    };
+
+    this.#readable = new ReadableStream(
+      {
+        // Note: A pull from the consumer releases the backpressure applied
+        // to the writable side of the stream.
+        pull: () => link.releaseBackpressure(),
+        cancel: async (reason) => {
+          link.writable.error(reason);
+          await transformer.cancel?.(reason);
+        },
+      },
+      { highWaterMark: 0, ...readableStrategy }
+    );
+
+    link.readable = internals.get(this.#readable);
+    if (link.readable.desiredSize <= 0) link.applyBackpressure();
+
+    this.#writable = new WritableStream(
+      {
+        start: async () => {
+          try {
+            await transformer.start?.(controller);
+          } catch (err) {
+            link.readable.error(err);
+            throw err;
+          }
+        },
+        write: async (chunk) => {
+          await link.backpressure?.promise;
+          if (!transformer.transform) return controller.enqueue(chunk);
+          await transformer.transform(chunk, controller);
+        },
+        close: async () => {
+          await transformer.flush?.(controller);
+          const { readable } = link;
+          if (readable.state === 'readable' && !readable.closeRequested) {
+            readable.close();
+          }
+        },
+        abort: async (reason) => {
+          link.readable.error(reason);
+          await transformer.cancel?.(reason);
+        },
+      },
+      writableStrategy
+    );
+
+    link.writable = internals.get(this.#writable);
+  }
+
+  /**
+   * The readable side of the transform stream.
+   */
+  get readable() {
+    return this.#readable;
+  }
+
+  /**
+   * The writable side of the transform stream.
+   */
+  get writable() {
+    return this.#writable;
+  }
+
+  get [Symbol.toStringTag]() {
+    return 'TransformStream';
+  }
+}
+
+/**
+ * A queuing strategy that counts the number of bytes in each chunk.
+ */
+export class ByteLengthQueuingStrategy {
+  constructor({ highWaterMark }) {
+    this.highWaterMark = highWaterMark;
+  }
+
+  size(chunk) {
+    return chunk.byteLength;
+  }
+}
+
+/**
+ * A queuing strategy that counts the number of chunks.
+ */
+export class CountQueuingStrategy {
+  constructor({ highWaterMark }) {
+    this.highWaterMark = highWaterMark;
+  }
+
+  size() {
+    return 1;
+  }
+}
+
+export default {
+  ReadableStream,
+  ReadableStreamDefaultReader,
+  ReadableStreamBYOBReader,
+  ReadableStreamDefaultController,
+  ReadableByteStreamController,
+  WritableStream,
+  WritableStreamDefaultWriter,
+  WritableStreamDefaultController,
+  TransformStream,
+  TransformStreamDefaultController,
+  ByteLengthQueuingStrategy,
+  CountQueuingStrategy,
+};
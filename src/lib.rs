@@ -0,0 +1,63 @@
+//! Dune is an open-source, cross-platform, shell around the V8 engine, written in
+//! Rust and capable of running JavaScript (dah) and TypeScript code out of the box.
+//!
+//! Besides the `dune` binary, the runtime can be embedded in other Rust programs
+//! using `JsRuntime` (configured through `JsRuntimeOptions`), exposing native
+//! functions to JavaScript through an `Extension`.
+
+// Note: The hidden modules are only public for the `dune` binary.
+mod bindings;
+mod blob;
+mod buffer;
+#[doc(hidden)]
+pub mod cli;
+mod compression;
+mod cookies;
+mod crypto;
+mod csv;
+mod dns;
+mod dotenv;
+mod dune_dir;
+#[doc(hidden)]
+pub mod errors;
+mod exceptions;
+mod file;
+mod hooks;
+mod http_parser;
+mod inspector;
+#[doc(hidden)]
+pub mod limiter;
+mod loaders;
+mod log;
+mod modules;
+mod net;
+mod perf_hooks;
+mod poll;
+mod process;
+mod promise;
+mod repl;
+mod runtime;
+mod signals;
+mod snapshot;
+mod stat_cache;
+mod stdio;
+mod text_encoding;
+mod timers;
+#[doc(hidden)]
+pub mod tools;
+mod transpilers;
+mod udp;
+mod verbosity;
+mod watcher;
+
+pub use bindings::Extension;
+pub use bindings::NativeFunction;
+pub use errors::ExitRequest;
+pub use runtime::JsRuntime;
+pub use runtime::JsRuntimeOptions;
+use tools::bundle;
+use tools::cache;
+use tools::compile;
+use tools::info;
+use tools::upgrade;
+use tools::vendor;
@@ -0,0 +1,355 @@
+// Thread-pool Task Limiter
+//
+// Caps how many tasks of the same class (e.g. fs or dns) run on the thread-pool
+// at the same time, so a burst of work (like a `Promise.all` of thousands of file
+// reads) doesn't overwhelm the pool and delay the rest. Extra tasks are queued
+// and spawned in FIFO order as running ones complete. Panicking tasks are
+// reported back to their callbacks as errors, so their slots are freed too.
+//
+// Tasks can also be cancelled cooperatively: a task holding a `CancelToken`
+// checks it between steps (e.g. for every entry of a directory walk) and bails
+// out early once it's set. Tasks that don't check it (like a single syscall)
+// still run to completion.
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use dune_event_loop::LoopHandle;
+use dune_event_loop::TaskResult;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// The kinds of thread-pool tasks that can be limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskClass {
+    Fs,
+    Dns,
+    Compression,
+}
+
+/// A queued task, waiting for a slot to be spawned.
+type Job = Box<dyn FnOnce(&LoopHandle)>;
+
+#[derive(Default)]
+struct Bucket {
+    running: usize,
+    queue: VecDeque<Job>,
+}
+
+/// A flag that cooperative tasks check to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Asks the task to stop (at its next check).
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns an error if the task has been cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.0.load(Ordering::SeqCst) {
+            bail!("The operation was aborted.");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    limit: Option<usize>,
+    buckets: HashMap<TaskClass, Bucket>,
+    // Tasks spawned (or queued) whose callbacks haven't run yet.
+    in_flight: usize,
+    // Cancel tokens handed out to JavaScript (by id).
+    tokens: HashMap<usize, CancelToken>,
+    next_token_id: usize,
+}
+
+#[derive(Clone, Default)]
+pub struct TaskLimiter {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl TaskLimiter {
+    /// Creates a limiter allowing at most `limit` tasks per class (if any).
+    pub fn new(limit: Option<usize>) -> Self {
+        let inner = Inner {
+            limit: limit.map(|limit| limit.max(1)),
+            buckets: HashMap::new(),
+            in_flight: 0,
+            tokens: HashMap::new(),
+            next_token_id: 0,
+        };
+        Self {
+            inner: Rc::new(RefCell::new(inner)),
+        }
+    }
+
+    /// Spawns a task on the thread-pool, or queues it if the class is at its limit.
+    pub fn spawn<F, U>(&self, handle: &LoopHandle, class: TaskClass, task: F, task_cb: Option<U>)
+    where
+        F: FnOnce() -> TaskResult + Send + 'static,
+        U: FnOnce(LoopHandle, TaskResult) + 'static,
+    {
+        let task = catch_panics(task);
+
+        self.inner.borrow_mut().in_flight += 1;
+
+        // No limit means no slots to manage, spawn the task right away.
+        if self.inner.borrow().limit.is_none() {
+            let limiter = self.clone();
+            let task_cb = move |handle: LoopHandle, result: TaskResult| {
+                limiter.inner.borrow_mut().in_flight -= 1;
+                if let Some(task_cb) = task_cb {
+                    task_cb(handle, result);
+                }
+            };
+            handle.spawn(task, Some(task_cb));
+            return;
+        }
+
+        let job: Job = {
+            let limiter = self.clone();
+            Box::new(move |handle: &LoopHandle| {
+                let task_cb = move |handle: LoopHandle, result: TaskResult| {
+                    // Free up the slot (starting the next queued task) before
+                    // running the actual callback.
+                    if let Some(next) = limiter.release(class) {
+                        next(&handle);
+                    }
+                    limiter.inner.borrow_mut().in_flight -= 1;
+                    if let Some(task_cb) = task_cb {
+                        task_cb(handle, result);
+                    }
+                };
+                handle.spawn(task, Some(task_cb));
+            })
+        };
+
+        let mut inner = self.inner.borrow_mut();
+        let limit = inner.limit.unwrap();
+        let bucket = inner.buckets.entry(class).or_default();
+
+        if bucket.running >= limit {
+            bucket.queue.push_back(job);
+            return;
+        }
+
+        bucket.running += 1;
+        drop(inner);
+        job(handle);
+    }
+
+    /// Releases a slot of the given class, returning the next queued task (if any).
+    fn release(&self, class: TaskClass) -> Option<Job> {
+        let mut inner = self.inner.borrow_mut();
+        let bucket = inner.buckets.get_mut(&class).unwrap();
+
+        // Note: The slot is handed over to the next task, so the running
+        // counter only goes down when the queue is empty.
+        match bucket.queue.pop_front() {
+            Some(job) => Some(job),
+            None => {
+                bucket.running -= 1;
+                None
+            }
+        }
+    }
+
+    /// Creates a cancel token, returning the id it can be looked up with.
+    pub fn create_token(&self) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_token_id;
+        inner.next_token_id += 1;
+        inner.tokens.insert(id, CancelToken::default());
+        id
+    }
+
+    /// Returns the token with the given id (or a fresh one if there's none).
+    pub fn token(&self, id: Option<usize>) -> CancelToken {
+        let inner = self.inner.borrow();
+        id.and_then(|id| inner.tokens.get(&id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Cancels the tasks holding the token, and forgets about it.
+    ///
+    /// Note: It's fine to call this after the tasks completed (to free the token).
+    pub fn cancel(&self, id: usize) {
+        if let Some(token) = self.inner.borrow_mut().tokens.remove(&id) {
+            token.cancel();
+        }
+    }
+
+    /// Returns the number of tasks (of any class) whose callbacks haven't run yet.
+    pub fn in_flight(&self) -> usize {
+        self.inner.borrow().in_flight
+    }
+
+    /// Returns the number of running and queued tasks of the given class.
+    #[cfg(test)]
+    pub fn stats(&self, class: TaskClass) -> (usize, usize) {
+        let inner = self.inner.borrow();
+        match inner.buckets.get(&class) {
+            Some(bucket) => (bucket.running, bucket.queue.len()),
+            None => (0, 0),
+        }
+    }
+}
+
+thread_local! {
+    // Set while the current thread runs a task wrapped by `catch_panics`.
+    static CATCHING_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns true if a panic on the current thread will be caught by `catch_panics`.
+///
+/// Note: The process' panic hook runs before unwinding, so it uses this to avoid
+/// exiting on panics that will end up as rejected promises.
+pub fn is_catching_panics() -> bool {
+    CATCHING_PANICS.get()
+}
+
+/// Wraps a thread-pool task so a panic is handed to its callback as an error
+/// (rejecting the JS promise) instead of the task never completing.
+pub fn catch_panics<F>(task: F) -> impl FnOnce() -> TaskResult + Send + 'static
+where
+    F: FnOnce() -> TaskResult + Send + 'static,
+{
+    move || {
+        let outer = CATCHING_PANICS.replace(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(task));
+        CATCHING_PANICS.set(outer);
+
+        match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = match payload.downcast_ref::<&str>() {
+                    Some(message) => message.to_string(),
+                    None => match payload.downcast_ref::<String>() {
+                        Some(message) => message.clone(),
+                        None => "Unknown reason".into(),
+                    },
+                };
+                Some(Err(anyhow!("Thread-pool task panicked: {message}")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dune_event_loop::EventLoop;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cancel_token() {
+        let limiter = TaskLimiter::new(None);
+        let id = limiter.create_token();
+        let token = limiter.token(Some(id));
+
+        assert!(token.check().is_ok());
+        limiter.cancel(id);
+        assert!(token.check().is_err());
+
+        // Cancelled tokens are forgotten.
+        assert!(limiter.token(Some(id)).check().is_ok());
+    }
+
+    #[test]
+    fn test_limiter_caps_concurrent_tasks() {
+        let mut event_loop = EventLoop::new(8);
+        let handle = event_loop.handle();
+        let limiter = TaskLimiter::new(Some(2));
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let completed = Rc::new(RefCell::new(0));
+
+        for _ in 0..10 {
+            let task = {
+                let running = running.clone();
+                let max_running = max_running.clone();
+                move || {
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(current, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    None
+                }
+            };
+            let task_cb = {
+                let completed = completed.clone();
+                move |_: LoopHandle, _: TaskResult| *completed.borrow_mut() += 1
+            };
+            limiter.spawn(&handle, TaskClass::Fs, task, Some(task_cb));
+        }
+
+        assert_eq!(limiter.stats(TaskClass::Fs), (2, 8));
+        assert_eq!(limiter.in_flight(), 10);
+
+        while event_loop.has_pending_events() {
+            event_loop.tick();
+        }
+
+        assert_eq!(*completed.borrow(), 10);
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.stats(TaskClass::Fs), (0, 0));
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_task_panics_are_delivered_as_errors() {
+        let mut event_loop = EventLoop::new(1);
+        let handle = event_loop.handle();
+        let limiter = TaskLimiter::new(Some(1));
+        let errors = Rc::new(RefCell::new(vec![]));
+
+        for _ in 0..2 {
+            let task = || -> TaskResult { panic!("Invalid bytes") };
+            let task_cb = {
+                let errors = errors.clone();
+                move |_: LoopHandle, result: TaskResult| {
+                    let error = result.unwrap().unwrap_err();
+                    errors.borrow_mut().push(error.to_string());
+                }
+            };
+            limiter.spawn(&handle, TaskClass::Dns, task, Some(task_cb));
+        }
+
+        while event_loop.has_pending_events() {
+            event_loop.tick();
+        }
+
+        // The queued task also runs, so the slot was freed after the panic.
+        let expected = "Thread-pool task panicked: Invalid bytes";
+        assert_eq!(*errors.borrow(), vec![expected, expected]);
+    }
+
+    #[test]
+    fn test_catching_panics_flag() {
+        let task = catch_panics(|| -> TaskResult {
+            assert!(is_catching_panics());
+            panic!("Invalid bytes")
+        });
+
+        assert!(!is_catching_panics());
+        assert!(task().unwrap().is_err());
+        // The flag is reset once the panic has been caught.
+        assert!(!is_catching_panics());
+    }
+}
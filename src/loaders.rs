@@ -1,12 +1,18 @@
+use crate::dune_dir;
 use crate::errors::generic_error;
 use crate::modules::ModulePath;
 use crate::modules::ModuleSource;
 use crate::modules::CORE_MODULES;
+use crate::stat_cache;
 use crate::transpilers::Jsx;
 use crate::transpilers::TypeScript;
 use crate::transpilers::Wasm;
+use crate::verbosity::info;
+use crate::verbosity::trace;
 use anyhow::bail;
 use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use colored::*;
 use lazy_static::lazy_static;
 use path_absolutize::*;
@@ -14,6 +20,10 @@ use regex::Regex;
 use sha::sha1::Sha1;
 use sha::utils::Digest;
 use sha::utils::DigestExt;
+use sha2::Digest as _;
+use sha2::Sha256;
+use sha2::Sha384;
+use sha2::Sha512;
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -64,7 +74,7 @@ impl FsModuleLoader {
     /// Loads import as file.
     fn load_as_file(&self, path: &Path) -> Result<ModuleSource> {
         // 1. Check if path is already a valid file.
-        if path.is_file() {
+        if stat_cache::is_file(path) {
             return self.load_source(path);
         }
 
@@ -72,7 +82,7 @@ impl FsModuleLoader {
         if path.extension().is_none() {
             for ext in EXTENSIONS {
                 let path = &path.with_extension(ext);
-                if path.is_file() {
+                if stat_cache::is_file(path) {
                     return self.load_source(path);
                 }
             }
@@ -86,7 +96,7 @@ impl FsModuleLoader {
     fn load_as_directory(&self, path: &Path) -> Result<ModuleSource> {
         for ext in EXTENSIONS {
             let path = &path.join(format!("index.{ext}"));
-            if path.is_file() {
+            if stat_cache::is_file(path) {
                 return self.load_source(path);
             }
         }
@@ -151,20 +161,45 @@ impl ModuleLoader for FsModuleLoader {
     }
 }
 
-lazy_static! {
-    // Use local cache directory in development.
-    pub static ref CACHE_DIR: PathBuf = if cfg!(debug_assertions) {
-        PathBuf::from(".cache")
-    } else {
-        dirs::home_dir().unwrap().join(".dune/cache")
-    };
+/// Decides which URL imports ignore the cache (and are downloaded again).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum Reload {
+    /// URL imports are loaded from the cache (when available).
+    #[default]
+    None,
+    /// Every URL import is downloaded again.
+    All,
+    /// Only URL imports starting with one of the prefixes are downloaded again.
+    Prefixes(Vec<String>),
+}
+
+impl Reload {
+    /// Checks if the given specifier has to skip the cache.
+    pub fn matches(&self, specifier: &str) -> bool {
+        match self {
+            Reload::None => false,
+            Reload::All => true,
+            Reload::Prefixes(prefixes) => prefixes.iter().any(|p| specifier.starts_with(p)),
+        }
+    }
 }
 
 #[derive(Default)]
 /// Loader supporting URL imports.
 pub struct UrlModuleLoader {
-    // Ignores the cache and re-downloads the dependency.
-    pub skip_cache: bool,
+    // Decides if the cache is ignored and the dependency re-downloaded.
+    pub reload: Reload,
+    // The pinned subresource integrity (e.g. `sha384-...`) of the dependency.
+    pub integrity: Option<String>,
+}
+
+impl UrlModuleLoader {
+    /// Returns the location of a URL import in the cache directory.
+    pub fn cache_path(specifier: &str) -> PathBuf {
+        // Hash URL using sha1.
+        let hash = Sha1::default().digest(specifier.as_bytes()).to_hex();
+        dune_dir::cache_dir().join(hash)
+    }
 }
 
 impl ModuleLoader for UrlModuleLoader {
@@ -191,23 +226,31 @@ impl ModuleLoader for UrlModuleLoader {
 
     fn load(&self, specifier: &str) -> Result<ModuleSource> {
         // Create the cache directory.
-        if fs::create_dir_all(CACHE_DIR.as_path()).is_err() {
+        if fs::create_dir_all(dune_dir::cache_dir()).is_err() {
             bail!("Failed to create module caching directory");
         }
 
-        // Hash URL using sha1.
-        let hash = Sha1::default().digest(specifier.as_bytes()).to_hex();
-        let module_path = CACHE_DIR.join(hash);
+        let module_path = Self::cache_path(specifier);
+        let integrity_path = module_path.with_extension("integrity");
+        let url_path = module_path.with_extension("url");
+
+        // Note: Modules with a pinned integrity are only loaded from the cache
+        // if they were verified against the same value when downloaded.
+        let is_verified = match self.integrity.as_ref() {
+            Some(integrity) => fs::read_to_string(&integrity_path).is_ok_and(|v| &v == integrity),
+            None => true,
+        };
 
-        if !self.skip_cache {
+        if !self.reload.matches(specifier) && is_verified {
             // Check cache, and load file.
             if module_path.is_file() {
+                trace!("Cache hit {specifier}");
                 let source = fs::read_to_string(&module_path).unwrap();
                 return Ok(source);
             }
         }
 
-        println!("{} {}", "Downloading".green(), specifier);
+        info!("{} {}", "Downloading".green(), specifier);
 
         // Download file and, save it to cache.
         let source = match ureq::get(specifier).call()?.into_string() {
@@ -215,6 +258,11 @@ impl ModuleLoader for UrlModuleLoader {
             Err(_) => bail!(format!("Module not found \"{specifier}\"")),
         };
 
+        // Check the downloaded source against the pinned integrity.
+        if let Some(integrity) = self.integrity.as_ref() {
+            verify_integrity(specifier, source.as_bytes(), integrity)?;
+        }
+
         // Use a preprocessor if necessary.
         let source = match (
             specifier.ends_with(".wasm"),
@@ -231,11 +279,68 @@ impl ModuleLoader for UrlModuleLoader {
         };
 
         fs::write(&module_path, &source)?;
+        fs::write(&url_path, specifier)?;
+
+        if let Some(integrity) = self.integrity.as_ref() {
+            fs::write(&integrity_path, integrity)?;
+        }
 
         Ok(source)
     }
 }
 
+/// The hash algorithms supported in integrity values (weakest first).
+const INTEGRITY_ALGORITHMS: [&str; 3] = ["sha256", "sha384", "sha512"];
+
+/// Computes the base64 encoded digest of the content.
+fn integrity_digest(algorithm: &str, content: &[u8]) -> String {
+    let digest = match algorithm {
+        "sha256" => Sha256::digest(content).to_vec(),
+        "sha384" => Sha384::digest(content).to_vec(),
+        _ => Sha512::digest(content).to_vec(),
+    };
+    BASE64.encode(digest)
+}
+
+/// Verifies the content against a subresource integrity value.
+/// https://www.w3.org/TR/SRI/#does-response-match-metadatalist
+pub fn verify_integrity(specifier: &str, content: &[u8], integrity: &str) -> Result<()> {
+    // Parse the (space separated) `<algorithm>-<base64 digest>` entries.
+    let entries: Vec<(usize, &str)> = integrity
+        .split_whitespace()
+        .filter_map(|entry| entry.split_once('-'))
+        .filter_map(|(algorithm, digest)| {
+            let strength = INTEGRITY_ALGORITHMS.iter().position(|a| *a == algorithm)?;
+            Some((strength, digest))
+        })
+        .collect();
+
+    // Note: Only the strongest algorithm is taken into account.
+    let Some(strength) = entries.iter().map(|(strength, _)| *strength).max() else {
+        let supported = INTEGRITY_ALGORITHMS.join(", ");
+        bail!("Invalid integrity \"{integrity}\" for \"{specifier}\" (supported: {supported})");
+    };
+
+    let algorithm = INTEGRITY_ALGORITHMS[strength];
+    let actual = integrity_digest(algorithm, content);
+    let expected: Vec<&str> = entries
+        .iter()
+        .filter(|(s, _)| *s == strength)
+        .map(|(_, digest)| *digest)
+        .collect();
+
+    if !expected.contains(&actual.as_str()) {
+        let expected = expected.join(" ");
+        bail!(
+            "Integrity check failed for \"{specifier}\"\n  \
+             expected: {algorithm}-{expected}\n  \
+             actual:   {algorithm}-{actual}"
+        );
+    }
+
+    Ok(())
+}
+
 #[derive(Default)]
 pub struct CoreModuleLoader;
 
@@ -377,4 +482,61 @@ mod tests {
             assert_eq!(url, expected);
         }
     }
+
+    #[test]
+    fn test_reload_matches() {
+        let reload = Reload::Prefixes(vec!["https://esm.sh/preact".into()]);
+
+        assert!(reload.matches("https://esm.sh/preact@10.11.3"));
+        assert!(!reload.matches("https://esm.sh/react@18.2.0"));
+        assert!(Reload::All.matches("https://esm.sh/react@18.2.0"));
+        assert!(!Reload::None.matches("https://esm.sh/react@18.2.0"));
+    }
+
+    #[test]
+    fn test_scoped_reload_uses_cache() {
+        // Note: The host doesn't exist, so the module can only come from the cache.
+        let specifier = "https://dune.invalid/cached/module.js";
+        let path = UrlModuleLoader::cache_path(specifier);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "export default 42;").unwrap();
+
+        let loader = UrlModuleLoader {
+            reload: Reload::Prefixes(vec!["https://dune.invalid/other/".into()]),
+            integrity: None,
+        };
+
+        let source = loader.load(specifier);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(source.unwrap(), "export default 42;");
+    }
+
+    #[test]
+    fn test_verify_integrity() {
+        let specifier = "https://dune.invalid/module.js";
+        let content = b"export default 42;";
+
+        // Matching digests (the strongest algorithm wins).
+        let sha256 = "sha256-yadUG5jpd7v2xNhRzPf1bJC/55YzlJw+4xmF8O1krBM=";
+        let sha384 = "sha384-J1XHFv53L+ww3YMaWzDHN989aQ33N6C5ixpUXZFb5R6MIekTJdgPziWCHXksIRoo";
+
+        assert!(verify_integrity(specifier, content, sha256).is_ok());
+        assert!(verify_integrity(specifier, content, &format!("{sha256} {sha384}")).is_ok());
+        assert!(verify_integrity(specifier, content, &format!("md5-abc {sha384}")).is_ok());
+        assert!(verify_integrity(specifier, content, &format!("{sha384} sha256-abc")).is_ok());
+
+        // Mismatching digests report the expected and actual values.
+        let error = verify_integrity(specifier, content, "sha256-abc").unwrap_err();
+        let message = error.to_string();
+
+        assert!(message.contains("Integrity check failed"));
+        assert!(message.contains("expected: sha256-abc"));
+        assert!(message.contains(&format!("actual:   {sha256}")));
+
+        // Unsupported algorithms only.
+        assert!(verify_integrity(specifier, content, "md5-abc").is_err());
+        assert!(verify_integrity(specifier, content, "").is_err());
+    }
 }
@@ -0,0 +1,157 @@
+// Logging APIs
+//
+// This module provides the native helpers behind the leveled `log` module,
+// namely the JSON record serializer.
+
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+// Placeholder for values whose conversion throws (e.g. getters or proxies).
+const THROWS: &str = "[Throws]";
+
+// Nested values deeper than this are replaced with a placeholder (this also
+// protects the serializer from circular references).
+const MAX_DEPTH: usize = 10;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "serialize", serialize);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![serialize]
+}
+
+/// Serializes a log record into a single JSON line.
+fn serialize(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the record's standard properties.
+    let level = args.get(0).to_rust_string_lossy(scope);
+    let time = args.get(1).to_rust_string_lossy(scope);
+    let message = args.get(2).to_rust_string_lossy(scope);
+
+    let mut record = Map::new();
+
+    record.insert("level".into(), Value::String(level));
+    record.insert("time".into(), Value::String(time));
+    record.insert("msg".into(), Value::String(message));
+
+    // Merge the structured fields into the record.
+    if let Value::Object(fields) = to_json_value(scope, args.get(3), 0) {
+        record.extend(fields);
+    }
+
+    let output = Value::Object(record).to_string();
+    rv.set(v8::String::new(scope, &output).unwrap().into());
+}
+
+/// Converts a JavaScript value into a JSON value.
+fn to_json_value(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>, depth: usize) -> Value {
+    // Note: Logging must never throw, so values that throw while being
+    // converted are replaced with a placeholder instead.
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    try_to_json_value(tc_scope, value, depth).unwrap_or_else(|| Value::String(THROWS.into()))
+}
+
+/// Converts a JavaScript value into a JSON value (None if a JS exception is thrown).
+fn try_to_json_value(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+    depth: usize,
+) -> Option<Value> {
+    if value.is_null_or_undefined() || value.is_function() || value.is_symbol() {
+        return Some(Value::Null);
+    }
+
+    if value.is_boolean() {
+        return Some(Value::Bool(value.boolean_value(scope)));
+    }
+
+    if value.is_number() {
+        let number = value.number_value(scope).unwrap_or(f64::NAN);
+        return Some(Number::from_f64(number).map_or(Value::Null, Value::Number));
+    }
+
+    if value.is_string() || value.is_big_int() || !value.is_object() {
+        return Some(Value::String(value.to_rust_string_lossy(scope)));
+    }
+
+    if depth >= MAX_DEPTH {
+        return Some(Value::String("[Object]".into()));
+    }
+
+    let object = value.to_object(scope)?;
+
+    // Errors don't have enumerable properties, so pick the useful ones.
+    if value.is_native_error() {
+        let mut error = Map::new();
+        for name in ["name", "message", "stack"] {
+            let key = v8::String::new(scope, name).unwrap();
+            let value = property_to_json_value(scope, object, key.into(), depth);
+            error.insert(name.into(), value);
+        }
+        return Some(Value::Object(error));
+    }
+
+    // Respect custom `toJSON` methods (e.g. dates).
+    let to_json = v8::String::new(scope, "toJSON").unwrap();
+    let to_json = object.get(scope, to_json.into())?;
+
+    if let Ok(to_json) = v8::Local::<v8::Function>::try_from(to_json) {
+        let value = to_json.call(scope, object.into(), &[])?;
+        return Some(to_json_value(scope, value, depth + 1));
+    }
+
+    if let Ok(array) = v8::Local::<v8::Array>::try_from(value) {
+        let mut values = Vec::with_capacity(array.length() as usize);
+        for i in 0..array.length() {
+            let value = array.get_index(scope, i)?;
+            values.push(to_json_value(scope, value, depth + 1));
+        }
+        return Some(Value::Array(values));
+    }
+
+    // Collect the object's own enumerable (string) properties.
+    let names_args = v8::GetPropertyNamesArgs {
+        key_conversion: v8::KeyConversionMode::ConvertToString,
+        ..Default::default()
+    };
+
+    let names = object.get_own_property_names(scope, names_args)?;
+    let mut map = Map::new();
+
+    for i in 0..names.length() {
+        let key = names.get_index(scope, i)?;
+        let value = property_to_json_value(scope, object, key, depth);
+        let key = key.to_rust_string_lossy(scope);
+        map.insert(key, value);
+    }
+
+    Some(Value::Object(map))
+}
+
+/// Converts an object's property into a JSON value.
+fn property_to_json_value(
+    scope: &mut v8::HandleScope,
+    object: v8::Local<v8::Object>,
+    key: v8::Local<v8::Value>,
+    depth: usize,
+) -> Value {
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    match object.get(tc_scope, key) {
+        Some(value) => to_json_value(tc_scope, value, depth + 1),
+        None => Value::String(THROWS.into()),
+    }
+}
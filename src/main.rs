@@ -1,53 +1,78 @@
-mod bindings;
-mod cli;
-mod dns;
-mod dotenv;
-mod errors;
-mod exceptions;
-mod file;
-mod hooks;
-mod http_parser;
-mod inspector;
-mod loaders;
-mod modules;
-mod net;
-mod perf_hooks;
-mod process;
-mod promise;
-mod repl;
-mod runtime;
-mod signals;
-mod stdio;
-mod timers;
-mod tools;
-mod transpilers;
-mod watcher;
-
-use crate::cli::process_cli_arguments;
-use crate::errors::generic_error;
-use runtime::JsRuntime;
+use dune::cli::process_cli_arguments;
+use dune::errors::generic_error;
+use dune::errors::report_error;
+use dune::errors::unwrap_or_exit;
+use dune::limiter;
+use dune::tools::compile;
+use dune::JsRuntime;
+use dune::JsRuntimeOptions;
 use std::env;
-use tools::bundle;
-use tools::compile;
-use tools::upgrade;
 
 fn run_standalone(source: String) {
     // Create a new JS runtime.
     let tag = "dune:standalone/main";
-    let mut runtime = JsRuntime::new();
+
+    // Note: The executable is the script, every argument is passed to it.
+    let exec_path = env::current_exe()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let options = JsRuntimeOptions {
+        argv: std::iter::once(exec_path)
+            .chain(env::args().skip(1))
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut runtime = unwrap_or_exit(JsRuntime::with_options(options));
     let mod_result = runtime.execute_module(tag, Some(&source));
 
     match mod_result {
-        Ok(_) => runtime.run_event_loop(),
-        Err(e) => eprintln!("{e:?}"),
+        Ok(_) => unwrap_or_exit(runtime.run_event_loop()),
+        Err(e) => report_error(&e),
     };
-    std::process::exit(0);
+    std::process::exit(runtime.exit_code());
+}
+
+/// Env variable that replaces the custom panic hook, for debugging Dune itself.
+const DISABLE_PANIC_HOOK_ENV: &str = "DUNE_DISABLE_PANIC_HOOK";
+
+/// How panics are handled (based on the `DUNE_DISABLE_PANIC_HOOK` variable).
+enum PanicMode {
+    /// Prints the friendly bug report message and exits.
+    Report,
+    /// Keeps Rust's default hook (honoring `RUST_BACKTRACE`).
+    Native,
+    /// Keeps Rust's default hook, then aborts (producing a core dump).
+    Abort,
+}
+
+fn panic_mode() -> PanicMode {
+    match env::var(DISABLE_PANIC_HOOK_ENV) {
+        Ok(value) if value.eq_ignore_ascii_case("abort") => PanicMode::Abort,
+        Ok(value) if !matches!(value.as_str(), "" | "0" | "false") => PanicMode::Native,
+        _ => PanicMode::Report,
+    }
+}
+
+/// Aborts the process after a panic, so debuggers and core dumps can catch it.
+fn setup_abort_panic_hook() {
+    let orig_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        orig_hook(panic_info);
+        std::process::abort();
+    }));
 }
 
 /// Custom hook on panics (copied from Deno).
 fn setup_panic_hook() {
     let orig_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
+        // Note: Panics of thread-pool tasks are caught and reported to their
+        // callbacks (rejecting the JS promise), so the process keeps running.
+        if limiter::is_catching_panics() {
+            return;
+        }
         eprintln!("\n============================================================");
         eprintln!("Dune has panicked. This is a bug in Dune. Please report this");
         eprintln!("at https://github.com/aalykiot/dune/issues");
@@ -65,9 +90,11 @@ fn setup_panic_hook() {
 }
 
 fn main() {
-    // Set custom panic hook on release builds.
-    if !cfg!(debug_assertions) {
-        setup_panic_hook();
+    // Set custom panic hook on release builds (unless disabled).
+    match panic_mode() {
+        PanicMode::Report if !cfg!(debug_assertions) => setup_panic_hook(),
+        PanicMode::Abort => setup_abort_panic_hook(),
+        _ => {}
     }
 
     #[cfg(target_family = "windows")]
@@ -1,27 +1,44 @@
+use crate::bindings::decode_result;
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+use crate::bindings::set_property_to;
+use crate::bindings::throw_exception;
+use crate::bindings::throw_type_error;
 use crate::errors::generic_error;
-use crate::errors::unwrap_or_exit;
 use crate::errors::JsError;
+use crate::hooks::module_resolve_cb;
+use crate::limiter::catch_panics;
 use crate::loaders::CoreModuleLoader;
 use crate::loaders::FsModuleLoader;
 use crate::loaders::ModuleLoader;
+use crate::loaders::Reload;
 use crate::loaders::UrlModuleLoader;
 use crate::runtime::JsFuture;
 use crate::runtime::JsRuntime;
+use crate::runtime::JsRuntimeState;
+use crate::verbosity::trace;
 use anyhow::anyhow;
 use anyhow::Error;
 use anyhow::Result;
+use base64::prelude::*;
 use dune_event_loop::LoopHandle;
 use dune_event_loop::TaskResult;
 use lazy_static::lazy_static;
+use regex::Captures;
 use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
 use serde_json::Value;
+use sourcemap::SourceMap;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::LinkedList;
+use std::collections::VecDeque;
 use std::env;
-use std::fs;
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
 use url::Url;
 
 lazy_static! {
@@ -31,6 +48,7 @@ lazy_static! {
             ("events", include_str!("./js/events.js")),
             ("process", include_str!("./js/process.js")),
             ("timers", include_str!("./js/timers.js")),
+            ("timers/promises", include_str!("./js/timers-promises.js")),
             ("assert", include_str!("./js/assert.js")),
             ("util", include_str!("./js/util.js")),
             ("fs", include_str!("./js/fs.js")),
@@ -38,13 +56,26 @@ lazy_static! {
             ("colors", include_str!("./js/colors.js")),
             ("dns", include_str!("./js/dns.js")),
             ("net", include_str!("./js/net.js")),
+            ("udp", include_str!("./js/udp.js")),
             ("test", include_str!("./js/test.js")),
+            ("bench", include_str!("./js/bench.js")),
             ("stream", include_str!("./js/stream.js")),
             ("http", include_str!("./js/http.js")),
+            ("log", include_str!("./js/log.js")),
+            ("path", include_str!("./js/path.js")),
+            ("crypto", include_str!("./js/crypto.js")),
+            ("csv", include_str!("./js/csv.js")),
+            ("buffer", include_str!("./js/buffer.js")),
+            ("async", include_str!("./js/async.js")),
+            ("@web/events", include_str!("./js/event-target.js")),
             ("@web/abort", include_str!("./js/abort-controller.js")),
             ("@web/text_encoding", include_str!("./js/text-encoding.js")),
             ("@web/clone", include_str!("./js/structured-clone.js")),
             ("@web/fetch", include_str!("./js/fetch.js")),
+            ("@web/fetch_types", include_str!("./js/fetch-types.js")),
+            ("@web/blob", include_str!("./js/blob.js")),
+            ("@web/streams", include_str!("./js/web-streams.js")),
+            ("@web/compression", include_str!("./js/compression.js")),
         ];
         HashMap::from_iter(modules.into_iter())
     };
@@ -80,8 +111,15 @@ pub type ModuleSource = String;
 pub struct ModuleMap {
     pub main: Option<ModulePath>,
     pub index: HashMap<ModulePath, v8::Global<v8::Module>>,
+    pub sources: HashMap<ModulePath, ModuleSource>,
     pub seen: HashMap<ModulePath, ModuleStatus>,
     pub pending: Vec<Rc<RefCell<ModuleGraph>>>,
+    pub traces: Vec<ImportTrace>,
+    // Parsed (inline) source-maps of transpiled modules.
+    source_maps: HashMap<ModulePath, Option<SourceMap>>,
+    // Imports waiting for their turn to be loaded (in frozen mode).
+    frozen_queue: VecDeque<QueuedImport>,
+    frozen_loading: bool,
 }
 
 impl ModuleMap {
@@ -90,18 +128,46 @@ impl ModuleMap {
         Self {
             main: None,
             index: HashMap::new(),
+            sources: HashMap::new(),
             seen: HashMap::new(),
             pending: vec![],
+            traces: vec![],
+            source_maps: HashMap::new(),
+            frozen_queue: VecDeque::new(),
+            frozen_loading: false,
         }
     }
 
-    // Inserts a compiled ES module to the map.
-    pub fn insert(&mut self, path: &str, module: v8::Global<v8::Module>) {
-        // No main module has been set, so let's update the value.
-        if self.main.is_none() && (fs::metadata(path).is_ok() || path.starts_with("http")) {
+    // Inserts a compiled ES module (and the source it was compiled from) to the map.
+    pub fn insert(&mut self, path: &str, module: v8::Global<v8::Module>, source: &str) {
+        self.index.insert(path.into(), module);
+        self.sources.insert(path.into(), source.into());
+    }
+
+    // Returns the (compiled) source code of a module.
+    pub fn get_source(&self, key: &str) -> Option<&str> {
+        self.sources.get(key).map(|source| source.as_str())
+    }
+
+    // Maps a (1-based) location of a transpiled module back to its original source.
+    pub fn original_location(&mut self, key: &str, line: u32, column: u32) -> Option<(u32, u32)> {
+        // Note: Source-maps are parsed the first time they're needed.
+        if !self.source_maps.contains_key(key) {
+            let source_map = self.get_source(key).and_then(parse_inline_source_map);
+            self.source_maps.insert(key.into(), source_map);
+        }
+
+        let source_map = self.source_maps.get(key)?.as_ref()?;
+        let token = source_map.lookup_token(line.checked_sub(1)?, column.saturating_sub(1))?;
+
+        Some((token.get_src_line() + 1, token.get_src_col() + 1))
+    }
+
+    // Marks the given path as the main entry point (first call wins).
+    pub fn set_main(&mut self, path: &str) {
+        if self.main.is_none() {
             self.main = Some(path.into());
         }
-        self.index.insert(path.into(), module);
     }
 
     // Returns if there are still pending imports to be loaded.
@@ -248,6 +314,41 @@ impl ModuleGraph {
     }
 }
 
+/// The source of a loaded module, as handed over to the `EsModuleFuture`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadedModule {
+    pub source: ModuleSource,
+    // How the module was fetched (only captured when tracing imports).
+    pub fetch: Option<FetchInfo>,
+}
+
+impl LoadedModule {
+    // Wraps an already available source into a task result.
+    pub fn from_source(source: &str) -> TaskResult {
+        let module = LoadedModule {
+            source: source.into(),
+            fetch: None,
+        };
+        Some(Ok(bincode::serialize(&module).unwrap()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FetchInfo {
+    pub cache_hit: Option<bool>,
+    pub duration: Duration,
+}
+
+/// Timing and cache status of a single module load (see `--trace-imports`).
+#[derive(Debug, Serialize)]
+pub struct ImportTrace {
+    pub path: ModulePath,
+    pub loader: &'static str,
+    pub cache_hit: Option<bool>,
+    pub fetch_ms: f64,
+    pub compile_ms: f64,
+}
+
 pub struct EsModuleFuture {
     pub path: ModulePath,
     pub module: Rc<RefCell<EsModule>>,
@@ -256,16 +357,15 @@ pub struct EsModuleFuture {
 
 impl EsModuleFuture {
     // Handles an error based on the import type.
-    fn handle_failure(&mut self, e: anyhow::Error) {
+    fn handle_failure(&mut self, state: &mut JsRuntimeState, e: anyhow::Error) {
         let module = self.module.borrow();
         // In dynamic imports we reject the promise(s).
         if module.is_dynamic_import {
             module.exception.borrow_mut().replace(e.to_string());
             return;
         }
-        // In static imports we exit the process.
-        eprintln!("{}", generic_error(e.to_string()));
-        std::process::exit(1);
+        // In static imports we stop the runtime.
+        state.set_fatal_error(generic_error(e.to_string()));
     }
 }
 
@@ -281,24 +381,33 @@ impl JsFuture for EsModuleFuture {
             return;
         }
 
+        // Note: Core modules may be loaded synchronously (see `require_core`) while
+        // the import is in flight, in that case the loaded instance is shared.
+        if state.module_map.seen.get(&self.path) == Some(&ModuleStatus::Ready) {
+            self.module.borrow_mut().status = ModuleStatus::Ready;
+            return;
+        }
+
         // Extract module's source code.
-        let source = self.maybe_result.take().unwrap();
-        let source = match source {
-            Ok(source) => bincode::deserialize::<String>(&source).unwrap(),
+        let loaded = self.maybe_result.take().unwrap();
+        let LoadedModule { source, fetch } = match loaded.and_then(decode_result) {
+            Ok(loaded) => loaded,
             Err(e) => {
-                self.handle_failure(Error::msg(e.to_string()));
+                self.handle_failure(&mut state, Error::msg(e.to_string()));
                 return;
             }
         };
 
+        let compile_start = fetch.as_ref().map(|_| Instant::now());
+
         let tc_scope = &mut v8::TryCatch::new(scope);
         let origin = create_origin(tc_scope, &self.path, true);
 
         // Compile source and get it's dependencies.
-        let source = v8::String::new(tc_scope, &source).unwrap();
-        let mut source = v8::script_compiler::Source::new(source, Some(&origin));
+        let code = v8::String::new(tc_scope, &source).unwrap();
+        let mut code = v8::script_compiler::Source::new(code, Some(&origin));
 
-        let module = match v8::script_compiler::compile_module(tc_scope, &mut source) {
+        let module = match v8::script_compiler::compile_module(tc_scope, &mut code) {
             Some(module) => module,
             None => {
                 assert!(tc_scope.has_caught());
@@ -306,25 +415,40 @@ impl JsFuture for EsModuleFuture {
                 let exception = JsError::from_v8_exception(tc_scope, exception, None);
                 let exception = format!("{} ({})", exception.message, exception.resource_name);
 
-                self.handle_failure(Error::msg(exception));
+                self.handle_failure(&mut state, Error::msg(exception));
                 return;
             }
         };
 
+        // Record how long it took to fetch and compile the module.
+        if let (Some(fetch), Some(start)) = (fetch, compile_start) {
+            state.module_map.traces.push(ImportTrace {
+                path: self.path.clone(),
+                loader: loader_kind(&self.path),
+                cache_hit: fetch.cache_hit,
+                fetch_ms: fetch.duration.as_secs_f64() * 1000.0,
+                compile_ms: start.elapsed().as_secs_f64() * 1000.0,
+            });
+        }
+
         let new_status = ModuleStatus::Resolving;
         let module_ref = v8::Global::new(tc_scope, module);
 
-        state.module_map.insert(self.path.as_str(), module_ref);
+        state
+            .module_map
+            .insert(self.path.as_str(), module_ref, &source);
         state.module_map.seen.insert(self.path.clone(), new_status);
 
         let import_map = state.options.import_map.clone();
 
-        let skip_cache = match self.module.borrow().is_dynamic_import {
-            true => !state.options.test_mode || state.options.reload,
-            false => state.options.reload,
+        let reload = match self.module.borrow().is_dynamic_import {
+            true if !state.options.test_mode => Reload::All,
+            _ => state.options.reload.clone(),
         };
+        let trace = state.options.trace_imports;
 
         let mut dependencies = vec![];
+        let mut newly_seen = vec![];
 
         let requests = module.get_module_requests();
         let base = self.path.clone();
@@ -340,7 +464,7 @@ impl JsFuture for EsModuleFuture {
             let specifier = match resolve_import(base, &specifier, false, import_map.clone()) {
                 Ok(specifier) => specifier,
                 Err(e) => {
-                    self.handle_failure(Error::msg(e.to_string()));
+                    self.handle_failure(&mut state, Error::msg(e.to_string()));
                     return;
                 }
             };
@@ -364,19 +488,39 @@ impl JsFuture for EsModuleFuture {
 
             dependencies.push(Rc::clone(&module));
 
-            // If the module is newly seen, use the event-loop to load
-            // the requested module.
+            // Keep track of newly seen modules, these need to be loaded.
             if seen_module.is_none() {
+                state.module_map.seen.insert(specifier.clone(), status);
+                newly_seen.push((specifier, module));
+            }
+        }
+
+        // Note: In frozen mode, sibling imports are loaded one by one (sorted by
+        // their specifier), trading some concurrency for a deterministic
+        // resolution order across runs.
+        if state.options.frozen {
+            newly_seen.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (specifier, module) in newly_seen {
+                let integrity = import_map
+                    .as_ref()
+                    .and_then(|map| map.integrity(&specifier));
+                load_import_in_order(&state_rc, &mut state, specifier, module, &reload, integrity);
+            }
+        } else {
+            for (specifier, module) in newly_seen {
+                // Use the event-loop to asynchronously load the requested module.
                 let task = {
                     let specifier = specifier.clone();
-                    move || match load_import(&specifier, skip_cache) {
-                        Ok(source) => Some(Ok(bincode::serialize(&source).unwrap())),
-                        Err(e) => Some(Result::Err(e)),
-                    }
+                    let reload = reload.clone();
+                    let integrity = import_map
+                        .as_ref()
+                        .and_then(|map| map.integrity(&specifier));
+                    catch_panics(move || {
+                        load_import_task(&specifier, &reload, integrity.as_deref(), trace)
+                    })
                 };
 
                 let task_cb = {
-                    let specifier = specifier.clone();
                     let state_rc = state_rc.clone();
                     move |_: LoopHandle, maybe_result: TaskResult| {
                         let mut state = state_rc.borrow_mut();
@@ -389,7 +533,6 @@ impl JsFuture for EsModuleFuture {
                     }
                 };
 
-                state.module_map.seen.insert(specifier, status);
                 state.handle.spawn(task, Some(task_cb));
             }
         }
@@ -399,11 +542,111 @@ impl JsFuture for EsModuleFuture {
     }
 }
 
+/// An import waiting for its turn to be loaded (in frozen mode).
+struct QueuedImport {
+    specifier: String,
+    module: Rc<RefCell<EsModule>>,
+    reload: Reload,
+    integrity: Option<String>,
+}
+
+/// Queues a module to be loaded once the previously requested ones are, so
+/// modules are resolved in the exact order they were requested.
+pub fn load_import_in_order(
+    state_rc: &Rc<RefCell<JsRuntimeState>>,
+    state: &mut JsRuntimeState,
+    specifier: String,
+    module: Rc<RefCell<EsModule>>,
+    reload: &Reload,
+    integrity: Option<String>,
+) {
+    state.module_map.frozen_queue.push_back(QueuedImport {
+        specifier,
+        module,
+        reload: reload.clone(),
+        integrity,
+    });
+
+    if !state.module_map.frozen_loading {
+        load_next_queued_import(state_rc, state);
+    }
+}
+
+/// Loads the next queued import (in frozen mode) using the thread-pool.
+///
+/// Note: The loading (and integrity checking) still happens off the main thread,
+/// only a single import is in flight at a time.
+fn load_next_queued_import(state_rc: &Rc<RefCell<JsRuntimeState>>, state: &mut JsRuntimeState) {
+    let Some(import) = state.module_map.frozen_queue.pop_front() else {
+        state.module_map.frozen_loading = false;
+        return;
+    };
+
+    state.module_map.frozen_loading = true;
+
+    let QueuedImport {
+        specifier,
+        module,
+        reload,
+        integrity,
+    } = import;
+
+    let task = {
+        let specifier = specifier.clone();
+        let trace = state.options.trace_imports;
+        catch_panics(move || load_import_task(&specifier, &reload, integrity.as_deref(), trace))
+    };
+
+    let task_cb = {
+        let state_rc = state_rc.clone();
+        move |_: LoopHandle, maybe_result: TaskResult| {
+            let mut state = state_rc.borrow_mut();
+            let future = EsModuleFuture {
+                path: specifier,
+                module,
+                maybe_result,
+            };
+            state.pending_futures.push(Box::new(future));
+            load_next_queued_import(&state_rc, &mut state);
+        }
+    };
+
+    state.handle.spawn(task, Some(task_cb));
+}
+
 lazy_static! {
     // Windows absolute path regex validator.
     static ref WINDOWS_REGEX: Regex = Regex::new(r"^[a-zA-Z]:\\").unwrap();
     // URL regex validator (string begins with http:// or https://).
     static ref URL_REGEX: Regex = Regex::new(r"^(http|https)://").unwrap();
+    // Stack-trace location matcher (e.g. `/app/index.ts:10:5`).
+    static ref LOCATION_REGEX: Regex = Regex::new(r"([^\s()]+):(\d+):(\d+)").unwrap();
+}
+
+/// The prefix of inline source-maps added by the transpilers.
+const INLINE_SOURCE_MAP: &str = "//# sourceMappingURL=data:application/json;base64,";
+
+/// Parses the inline source-map (if any) at the end of a module's source.
+fn parse_inline_source_map(source: &str) -> Option<SourceMap> {
+    let (_, encoded) = source.rsplit_once(INLINE_SOURCE_MAP)?;
+    let decoded = BASE64_STANDARD.decode(encoded.trim()).ok()?;
+    SourceMap::from_slice(&decoded).ok()
+}
+
+/// Rewrites the locations of a stack-trace to point to the original sources.
+pub fn remap_stack_trace(module_map: &mut ModuleMap, stack: &str) -> String {
+    let remap = |captures: &Captures| {
+        let path = &captures[1];
+        let line = captures[2].parse().unwrap_or_default();
+        let column = captures[3].parse().unwrap_or_default();
+
+        match module_map.original_location(path, line, column) {
+            Some((line, column)) => format!("{path}:{line}:{column}"),
+            None => captures[0].to_string(),
+        }
+    };
+
+    LOCATION_REGEX.replace_all(stack, remap).into_owned()
 }
 
 /// Resolves an import using the appropriate loader.
@@ -419,6 +662,17 @@ pub fn resolve_import(
         None => specifier.into(),
     };
 
+    // Map Node.js built-in imports (e.g. `node:fs`) to Dune's core modules.
+    let specifier = match specifier.strip_prefix("node:") {
+        Some(name) if CORE_MODULES.contains_key(name) && !name.starts_with('@') => name.into(),
+        Some(_) => {
+            return Err(anyhow!(
+                "Node.js built-in module \"{specifier}\" is not supported"
+            ))
+        }
+        None => specifier,
+    };
+
     // Look the params and choose a loader.
     let loader: Box<dyn ModuleLoader> = {
         let is_core_module_import = CORE_MODULES.contains_key(specifier.as_str());
@@ -440,7 +694,11 @@ pub fn resolve_import(
 }
 
 /// Loads an import using the appropriate loader.
-pub fn load_import(specifier: &str, skip_cache: bool) -> Result<ModuleSource> {
+pub fn load_import(
+    specifier: &str,
+    reload: &Reload,
+    integrity: Option<&str>,
+) -> Result<ModuleSource> {
     // Look the params and choose a loader.
     let loader: Box<dyn ModuleLoader> = match (
         CORE_MODULES.contains_key(specifier),
@@ -449,14 +707,49 @@ pub fn load_import(specifier: &str, skip_cache: bool) -> Result<ModuleSource> {
     ) {
         (true, _, _) => Box::new(CoreModuleLoader),
         (_, true, _) => Box::new(FsModuleLoader),
-        (_, _, true) => Box::new(UrlModuleLoader { skip_cache }),
+        (_, _, true) => Box::new(UrlModuleLoader {
+            reload: reload.clone(),
+            integrity: integrity.map(String::from),
+        }),
         _ => Box::new(FsModuleLoader),
     };
 
+    trace!("Loading {specifier}");
+
     // Load module.
     loader.load(specifier)
 }
 
+/// Loads an import and wraps it as a task result for an `EsModuleFuture`.
+pub fn load_import_task(
+    specifier: &str,
+    reload: &Reload,
+    integrity: Option<&str>,
+    trace: bool,
+) -> TaskResult {
+    // Note: Timings and cache lookups are only captured when tracing imports,
+    // to keep the common path free of any extra work.
+    let start = trace.then(Instant::now);
+    let cache_hit = match trace && loader_kind(specifier) == "url" {
+        true => {
+            Some(!reload.matches(specifier) && UrlModuleLoader::cache_path(specifier).is_file())
+        }
+        false => None,
+    };
+
+    match load_import(specifier, reload, integrity) {
+        Ok(source) => {
+            let fetch = start.map(|start| FetchInfo {
+                cache_hit,
+                duration: start.elapsed(),
+            });
+            let module = LoadedModule { source, fetch };
+            Some(Ok(bincode::serialize(&module).unwrap()))
+        }
+        Err(e) => Some(Result::Err(e)),
+    }
+}
+
 /// A single import mapping (specifier, target).
 type ImportMapEntry = (String, String);
 
@@ -464,6 +757,8 @@ type ImportMapEntry = (String, String);
 #[derive(Debug, Clone)]
 pub struct ImportMap {
     map: Vec<ImportMapEntry>,
+    /// Pinned subresource integrity values (URL -> `sha384-...`).
+    integrity: HashMap<String, String>,
 }
 
 impl ImportMap {
@@ -487,7 +782,19 @@ impl ImportMap {
 
         map.sort_by(|a, b| b.0.cmp(&a.0));
 
-        Ok(ImportMap { map })
+        // https://html.spec.whatwg.org/multipage/webappapis.html#import-map-integrity
+        let integrity = match json["integrity"].to_owned() {
+            Value::Null => HashMap::new(),
+            Value::Object(integrity) => serde_json::from_value(Value::Object(integrity))?,
+            _ => return Err(anyhow!("Import map's 'integrity' must be an object")),
+        };
+
+        Ok(ImportMap { map, integrity })
+    }
+
+    /// Returns the pinned integrity (if any) of a module's URL.
+    pub fn integrity(&self, url: &str) -> Option<String> {
+        self.integrity.get(url).cloned()
     }
 
     /// Tries to match a specifier against an import-map entry.
@@ -504,6 +811,11 @@ impl ImportMap {
             target = target.replacen('.', &cwd, 1);
         }
 
+        // Exact entries (e.g. URLs with a query string) map as they are.
+        if specifier == base {
+            return Some(target);
+        }
+
         // Note: The reason we need this additional check below with the specifier's
         // extension (if exists) is to be able to support extension-less imports.
         //
@@ -533,19 +845,38 @@ pub fn fetch_module_tree<'a>(
     // Find appropriate loader if source is empty.
     let source = match source {
         Some(source) => source.into(),
-        None => unwrap_or_exit(load_import(filename, true)),
+        None => {
+            let import_map = state.borrow().options.import_map.clone();
+            let integrity = import_map.and_then(|map| map.integrity(filename));
+            match load_import(filename, &Reload::All, integrity.as_deref()) {
+                Ok(source) => source,
+                Err(e) => {
+                    throw_exception(scope, &e);
+                    return None;
+                }
+            }
+        }
     };
-    let source = v8::String::new(scope, &source).unwrap();
-    let mut source = v8::script_compiler::Source::new(source, Some(&origin));
+    let code = v8::String::new(scope, &source).unwrap();
+    let mut code = v8::script_compiler::Source::new(code, Some(&origin));
 
-    let module = match v8::script_compiler::compile_module(scope, &mut source) {
+    let module = match v8::script_compiler::compile_module(scope, &mut code) {
         Some(module) => module,
         None => return None,
     };
 
     // Subscribe module to the module-map.
     let module_ref = v8::Global::new(scope, module);
-    state.borrow_mut().module_map.insert(filename, module_ref);
+    // Note: Modules loaded synchronously are evaluated before any import can
+    // reach them, so they're marked as ready (later imports share them).
+    let mut state_ref = state.borrow_mut();
+    state_ref.module_map.insert(filename, module_ref, &source);
+    state_ref
+        .module_map
+        .seen
+        .insert(filename.into(), ModuleStatus::Ready);
+
+    drop(state_ref);
 
     let requests = module.get_module_requests();
 
@@ -556,7 +887,13 @@ pub fn fetch_module_tree<'a>(
 
         // Transform v8's ModuleRequest into Rust string.
         let specifier = request.get_specifier().to_rust_string_lossy(scope);
-        let specifier = unwrap_or_exit(resolve_import(Some(filename), &specifier, false, None));
+        let specifier = match resolve_import(Some(filename), &specifier, false, None) {
+            Ok(specifier) => specifier,
+            Err(e) => {
+                throw_exception(scope, &e);
+                return None;
+            }
+        };
 
         // Resolve subtree of modules.
         if !state.borrow().module_map.index.contains_key(&specifier) {
@@ -566,3 +903,163 @@ pub fn fetch_module_tree<'a>(
 
     Some(module)
 }
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "snapshot", snapshot);
+    set_function_to(scope, target, "remapStackTrace", remap_stack_trace_binding);
+    set_function_to(scope, target, "requireCore", require_core);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![snapshot, remap_stack_trace_binding, require_core]
+}
+
+/// Returns the loader kind (fs, url or core) used for a module path.
+fn loader_kind(path: &str) -> &'static str {
+    match (CORE_MODULES.contains_key(path), URL_REGEX.is_match(path)) {
+        (true, _) => "core",
+        (_, true) => "url",
+        _ => "fs",
+    }
+}
+
+/// Loads (and evaluates) a core module synchronously, returning its namespace.
+fn require_core(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let name = args.get(0).to_rust_string_lossy(scope);
+
+    if !CORE_MODULES.contains_key(name.as_str()) {
+        throw_type_error(scope, &format!("Unknown core module \"{name}\"."));
+        return;
+    }
+
+    // Check if the module is already loaded (by an import, or a previous call).
+    let state_rc = JsRuntime::state(scope);
+    let maybe_module = state_rc.borrow().module_map.get(&name);
+
+    let is_new = maybe_module.is_none();
+    let module = match maybe_module {
+        Some(module) => v8::Local::new(scope, module),
+        None => match fetch_module_tree(scope, &name, None) {
+            Some(module) => module,
+            None => return,
+        },
+    };
+
+    let status = module.get_status();
+
+    // Note: Modules imported right now (asynchronously) may still be loading.
+    if status == v8::ModuleStatus::Uninstantiated && !is_new {
+        let e = anyhow!("The \"{name}\" module is still loading.");
+        throw_exception(scope, &e);
+        return;
+    }
+
+    if status == v8::ModuleStatus::Uninstantiated
+        && module
+            .instantiate_module(scope, module_resolve_cb)
+            .is_none()
+    {
+        return;
+    }
+
+    if module.get_status() == v8::ModuleStatus::Instantiated {
+        let _ = module.evaluate(scope);
+    }
+
+    match module.get_status() {
+        v8::ModuleStatus::Errored => {
+            let exception = module.get_exception();
+            scope.throw_exception(exception);
+        }
+        _ => rv.set(module.get_module_namespace()),
+    }
+}
+
+/// Returns a read-only snapshot of the loaded modules and their dependencies.
+fn snapshot(
+    scope: &mut v8::HandleScope,
+    _: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get a reference to runtime's state.
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    let import_map = state.options.import_map.clone();
+    let main = state.module_map.main();
+
+    // Note: Internal entries (e.g. `dune:environment/main`) are runtime
+    // implementation details, so we're keeping them out of the snapshot.
+    let mut entries: Vec<(ModulePath, v8::Global<v8::Module>)> = state
+        .module_map
+        .index
+        .iter()
+        .filter(|(path, _)| !path.starts_with("dune:"))
+        .map(|(path, module)| (path.clone(), module.clone()))
+        .collect();
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    drop(state);
+
+    let modules = v8::Array::new(scope, entries.len() as i32);
+
+    for (i, (path, module)) in entries.iter().enumerate() {
+        let module = v8::Local::new(scope, module);
+        let requests = module.get_module_requests();
+        let dependencies = v8::Array::new(scope, requests.length() as i32);
+
+        for j in 0..requests.length() {
+            // Get import request from the `module_requests` array.
+            let request = requests.get(scope, j).unwrap();
+            let request = v8::Local::<v8::ModuleRequest>::try_from(request).unwrap();
+
+            // Resolve the specifier the same way the module loader did.
+            let specifier = request.get_specifier().to_rust_string_lossy(scope);
+            let specifier = resolve_import(Some(path), &specifier, false, import_map.clone())
+                .unwrap_or(specifier);
+
+            let specifier = v8::String::new(scope, &specifier).unwrap();
+            dependencies.set_index(scope, j as u32, specifier.into());
+        }
+
+        let specifier = v8::String::new(scope, path).unwrap();
+        let loader = v8::String::new(scope, loader_kind(path)).unwrap();
+        let is_main = v8::Boolean::new(scope, main.as_deref() == Some(path.as_str()));
+
+        let entry = v8::Object::new(scope);
+
+        set_property_to(scope, entry, "specifier", specifier.into());
+        set_property_to(scope, entry, "loader", loader.into());
+        set_property_to(scope, entry, "main", is_main.into());
+        set_property_to(scope, entry, "dependencies", dependencies.into());
+
+        modules.set_index(scope, i as u32, entry.into());
+    }
+
+    rv.set(modules.into());
+}
+
+/// Maps the locations of a stack-trace (string) back to the original sources.
+fn remap_stack_trace_binding(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let stack = args.get(0).to_rust_string_lossy(scope);
+
+    let state_rc = JsRuntime::state(scope);
+    let stack = remap_stack_trace(&mut state_rc.borrow_mut().module_map, &stack);
+
+    rv.set(v8::String::new(scope, &stack).unwrap().into());
+}
@@ -1,14 +1,36 @@
+use crate::bindings::external_references;
 use crate::bindings::set_exception_code;
 use crate::bindings::set_function_to;
 use crate::bindings::set_property_to;
+use crate::bindings::throw_exception;
 use crate::runtime::JsFuture;
 use crate::runtime::JsRuntime;
+use anyhow::anyhow;
 use anyhow::Result;
 use dune_event_loop::Index;
 use dune_event_loop::LoopHandle;
 use dune_event_loop::TcpSocketInfo;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::net::IpAddr;
+use std::net::SocketAddr;
 use std::rc::Rc;
+use std::time::Duration;
+
+thread_local! {
+    /// The local and remote address of every open TCP stream (keyed by its ID).
+    ///
+    /// Note: The streams are owned by the event-loop, so setting a socket option
+    /// means finding the descriptor connecting these two addresses.
+    static SOCKET_ADDRESSES: RefCell<HashMap<Index, (SocketAddr, SocketAddr)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Remembers the addresses of a newly opened TCP stream.
+fn track_socket(sock: &TcpSocketInfo) {
+    let addresses = (sock.host, sock.remote);
+    SOCKET_ADDRESSES.with(|sockets| sockets.borrow_mut().insert(sock.id, addresses));
+}
 
 pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     // Create local JS object.
@@ -16,15 +38,33 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
 
     set_function_to(scope, target, "connect", connect);
     set_function_to(scope, target, "readStart", read_start);
+    set_function_to(scope, target, "readStop", read_stop);
     set_function_to(scope, target, "write", write);
     set_function_to(scope, target, "listen", listen);
     set_function_to(scope, target, "shutdown", shutdown);
     set_function_to(scope, target, "close", close);
+    set_function_to(scope, target, "setNoDelay", set_no_delay);
+    set_function_to(scope, target, "setKeepAlive", set_keep_alive);
 
     // Return v8 global handle.
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        connect,
+        read_start,
+        read_stop,
+        write,
+        listen,
+        shutdown,
+        close,
+        set_no_delay,
+        set_keep_alive
+    ]
+}
+
 struct TcpConnectFuture {
     sock: Result<TcpSocketInfo>,
     promise: v8::Global<v8::PromiseResolver>,
@@ -99,7 +139,10 @@ fn connect(
     // Get IP and PORT from arguments.
     let ip = args.get(0).to_rust_string_lossy(scope);
     let port = args.get(1).to_rust_string_lossy(scope);
-    let address = format!("{ip}:{port}");
+    let address = match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{ip}]:{port}"),
+        _ => format!("{ip}:{port}"),
+    };
 
     // Create a promise resolver and extract the actual promise.
     let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
@@ -114,8 +157,9 @@ fn connect(
         move |_: LoopHandle, index: Index, sock: Result<TcpSocketInfo>| {
             let mut state = state_rc.borrow_mut();
             // If connection did't happen, remove the resource.
-            if sock.is_err() {
-                state.handle.tcp_close(index, |_: LoopHandle| {});
+            match sock.as_ref() {
+                Ok(sock) => track_socket(sock),
+                Err(_) => state.handle.tcp_close(index, |_: LoopHandle| {}),
             }
             // Create a new JsFuture.
             let future = TcpConnectFuture { sock, promise };
@@ -126,19 +170,29 @@ fn connect(
     // Try open a TCP stream with the remote host.
     let connect = state.handle.tcp_connect(&address, on_connection);
 
-    // Check if the tcp_connect failed early.
-    if let Err(e) = connect {
-        // Drop state to avoid panics.
-        drop(state);
-        // Create the JavaScript error.
-        let message = v8::String::new(scope, &e.to_string()).unwrap();
-        let exception = v8::Exception::error(scope, message);
-        set_exception_code(scope, exception, &e);
-        promise_resolver.reject(scope, exception).unwrap();
-        return;
+    // Note: The pending connection's ID is returned along with the promise, so
+    // the connection attempt can be cancelled (closed) before it completes.
+    let pending = v8::Object::new(scope);
+    set_property_to(scope, pending, "promise", promise.into());
+
+    match connect {
+        Ok(index) => {
+            let index = v8::Integer::new(scope, index as i32);
+            set_property_to(scope, pending, "id", index.into());
+        }
+        // Check if the tcp_connect failed early.
+        Err(e) => {
+            // Drop state to avoid panics.
+            drop(state);
+            // Create the JavaScript error.
+            let message = v8::String::new(scope, &e.to_string()).unwrap();
+            let exception = v8::Exception::error(scope, message);
+            set_exception_code(scope, exception, &e);
+            promise_resolver.reject(scope, exception).unwrap();
+        }
     }
 
-    rv.set(promise.into());
+    rv.set(pending.into());
 }
 
 struct ReadStartFuture {
@@ -212,6 +266,19 @@ fn read_start(
     });
 }
 
+/// Stops reading from the socket (until `readStart` is called again).
+///
+/// Note: The data stays in the OS buffers meanwhile, so once they fill up the
+/// peer is slowed down by TCP's flow control.
+fn read_stop(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get socket's ID.
+    let index = args.get(0).int32_value(scope).unwrap() as u32;
+    let state_rc = JsRuntime::state(scope);
+    let state = state_rc.borrow();
+
+    state.handle.tcp_read_stop(index);
+}
+
 struct TcpWriteFuture {
     result: Result<usize>,
     promise: v8::Global<v8::PromiseResolver>,
@@ -352,6 +419,11 @@ fn listen(
     let server_id = state.handle.tcp_listen(&address, {
         let state_rc = state_rc.clone();
         move |_: LoopHandle, _: Index, socket: Result<TcpSocketInfo>| {
+            // Note: Accepted streams accept socket options too.
+            if let Ok(sock) = socket.as_ref() {
+                track_socket(sock);
+            }
+
             let mut state = state_rc.borrow_mut();
             let future = TcpListenFuture {
                 socket,
@@ -467,6 +539,8 @@ fn close(
     let state_rc = JsRuntime::state(scope);
     let state = state_rc.borrow();
 
+    SOCKET_ADDRESSES.with(|sockets| sockets.borrow_mut().remove(&index));
+
     let on_close = {
         let state_rc = state_rc.clone();
         let promise = v8::Global::new(scope, promise_resolver);
@@ -480,3 +554,135 @@ fn close(
     state.handle.tcp_close(index, on_close);
     rv.set(promise.into());
 }
+
+/// Returns the addresses of an open TCP stream.
+fn socket_addresses(index: Index) -> Result<(SocketAddr, SocketAddr)> {
+    SOCKET_ADDRESSES
+        .with(|sockets| sockets.borrow().get(&index).copied())
+        .ok_or_else(|| anyhow!("The socket is not connected."))
+}
+
+/// Enables (or disables) the TCP_NODELAY option of a TCP stream.
+fn set_no_delay(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get socket's ID and the option's value.
+    let index = args.get(0).int32_value(scope).unwrap() as u32;
+    let enable = args.get(1).boolean_value(scope);
+
+    let result = socket_addresses(index)
+        .and_then(|(local, remote)| with_socket(local, remote, |s| s.set_nodelay(enable)));
+
+    if let Err(e) = result {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Enables (or disables) the SO_KEEPALIVE option of a TCP stream.
+fn set_keep_alive(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    _: v8::ReturnValue,
+) {
+    // Get socket's ID, the option's value and the idle time before probing.
+    let index = args.get(0).int32_value(scope).unwrap() as u32;
+    let enable = args.get(1).boolean_value(scope);
+    let delay = args.get(2).uint32_value(scope).unwrap_or_default();
+
+    let result = socket_addresses(index)
+        .and_then(|(local, remote)| set_keep_alive_op(local, remote, enable, delay));
+
+    if let Err(e) = result {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Pure rust implementation of setting the keepalive option (and its delay).
+fn set_keep_alive_op(
+    local: SocketAddr,
+    remote: SocketAddr,
+    enable: bool,
+    delay: u32,
+) -> Result<()> {
+    with_socket(local, remote, |socket| match (enable, delay) {
+        (false, _) => socket.set_keepalive(false),
+        (true, 0) => socket.set_keepalive(true),
+        (true, secs) => {
+            let keepalive =
+                socket2::TcpKeepalive::new().with_time(Duration::from_secs(secs as u64));
+            socket.set_tcp_keepalive(&keepalive)
+        }
+    })
+}
+
+/// Runs `f` with the (borrowed) socket connecting the two addresses.
+#[cfg(target_family = "unix")]
+fn with_socket<F>(local: SocketAddr, remote: SocketAddr, f: F) -> Result<()>
+where
+    F: FnOnce(socket2::SockRef) -> std::io::Result<()>,
+{
+    use std::os::fd::BorrowedFd;
+    use std::os::fd::RawFd;
+
+    // Note: Within a process, a TCP stream is the only socket with this pair of
+    // addresses, so the match is exact. Non-socket descriptors fail the lookup.
+    for entry in std::fs::read_dir("/dev/fd")? {
+        let Ok(fd) = entry?.file_name().to_string_lossy().parse::<RawFd>() else {
+            continue;
+        };
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+        let socket = socket2::SockRef::from(&fd);
+        let local_addr = socket.local_addr().ok().and_then(|addr| addr.as_socket());
+        let peer_addr = socket.peer_addr().ok().and_then(|addr| addr.as_socket());
+
+        if local_addr == Some(local) && peer_addr == Some(remote) {
+            return f(socket).map_err(|e| anyhow!(e));
+        }
+    }
+
+    Err(anyhow!("The socket is not connected."))
+}
+
+/// Runs `f` with the (borrowed) socket connecting the two addresses.
+#[cfg(target_family = "windows")]
+fn with_socket<F>(_: SocketAddr, _: SocketAddr, _: F) -> Result<()>
+where
+    F: FnOnce(socket2::SockRef) -> std::io::Result<()>,
+{
+    Err(anyhow!("Socket options are not supported on Windows."))
+}
+
+#[cfg(test)]
+#[cfg(target_family = "unix")]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::net::TcpStream;
+
+    #[test]
+    fn test_tcp_socket_options() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        let local = client.local_addr().unwrap();
+        let remote = client.peer_addr().unwrap();
+
+        with_socket(local, remote, |s| s.set_nodelay(true)).unwrap();
+        assert!(client.nodelay().unwrap());
+        assert!(!server.nodelay().unwrap());
+
+        with_socket(local, remote, |s| s.set_nodelay(false)).unwrap();
+        assert!(!client.nodelay().unwrap());
+
+        // The accepted end is found through the reversed pair of addresses.
+        set_keep_alive_op(remote, local, true, 30).unwrap();
+        assert!(socket2::SockRef::from(&server).keepalive().unwrap());
+
+        // Unknown pairs of addresses are rejected.
+        let unknown = "127.0.0.1:1".parse().unwrap();
+        assert!(with_socket(unknown, remote, |s| s.set_nodelay(true)).is_err());
+    }
+}
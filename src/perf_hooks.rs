@@ -4,6 +4,7 @@
 // https://nodejs.org/api/perf_hooks.html#performance-measurement-apis
 
 use crate::bindings::create_object_under;
+use crate::bindings::external_references;
 use crate::bindings::set_function_to;
 use crate::bindings::set_property_to;
 use crate::JsRuntime;
@@ -28,6 +29,11 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![now]
+}
+
 fn now(scope: &mut v8::HandleScope, _args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
     // Get a reference to runtime's state.
     let state_rc = JsRuntime::state(scope);
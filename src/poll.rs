@@ -0,0 +1,422 @@
+// Readiness Polling
+//
+// A low-level, poll(2)-style API that notifies JavaScript when a raw file
+// descriptor (or a socket handle on Windows) becomes readable or writable.
+//
+// Safety contract: Dune never takes ownership of a watched descriptor. The
+// caller keeps it open (and non-blocking) for as long as it's being watched,
+// and must stop watching it *before* closing it. Otherwise, a new descriptor
+// reusing the same number may end up reporting events to the old watcher.
+
+use crate::bindings::external_references;
+use crate::bindings::set_function_to;
+use crate::bindings::set_property_to;
+use crate::bindings::throw_exception;
+use crate::bindings::throw_type_error;
+use crate::runtime::JsFuture;
+use crate::runtime::JsRuntime;
+use crate::runtime::JsRuntimeState;
+use anyhow::anyhow;
+use anyhow::Result;
+use dune_event_loop::LoopInterruptHandle;
+use mio::event::Source;
+#[cfg(unix)]
+use mio::unix::SourceFd;
+use mio::Events;
+use mio::Interest;
+use mio::Poll;
+use mio::Registry;
+use mio::Token;
+use std::collections::HashMap;
+use std::io;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+/// A raw file descriptor, watched without taking ownership of it.
+#[cfg(unix)]
+struct RawSource(std::os::fd::RawFd);
+
+/// A raw socket handle, watched without taking ownership of it.
+///
+/// Note: Only socket handles can be polled on Windows. The wrapper is never
+/// dropped, so the socket is not closed when we stop watching it.
+#[cfg(windows)]
+struct RawSource(std::mem::ManuallyDrop<mio::net::TcpStream>);
+
+impl RawSource {
+    #[cfg(unix)]
+    fn new(handle: i64) -> Self {
+        Self(handle as std::os::fd::RawFd)
+    }
+
+    #[cfg(windows)]
+    fn new(handle: i64) -> Self {
+        use std::os::windows::io::FromRawSocket;
+        let socket = unsafe { mio::net::TcpStream::from_raw_socket(handle as u64) };
+        Self(std::mem::ManuallyDrop::new(socket))
+    }
+}
+
+#[cfg(unix)]
+impl Source for RawSource {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.0).register(registry, token, interest)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.0).reregister(registry, token, interest)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.0).deregister(registry)
+    }
+}
+
+#[cfg(windows)]
+impl Source for RawSource {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        self.0.register(registry, token, interest)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        self.0.reregister(registry, token, interest)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.0.deregister(registry)
+    }
+}
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "watch", watch);
+    set_function_to(scope, target, "update", update);
+    set_function_to(scope, target, "unwatch", unwatch);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![watch, update, unwatch]
+}
+
+/// The readiness of a watched handle, as reported by the OS.
+#[derive(Debug, Clone, Copy)]
+struct Readiness {
+    id: usize,
+    readable: bool,
+    writable: bool,
+    hangup: bool,
+    error: bool,
+}
+
+/// A raw handle registered to the poller (without owning it).
+struct Watcher {
+    source: RawSource,
+    interest: Interest,
+    callback: Rc<v8::Global<v8::Function>>,
+}
+
+/// The (lazily started) thread blocking on the OS readiness queue.
+struct Backend {
+    registry: Registry,
+    events: mpsc::Receiver<Readiness>,
+}
+
+/// Keeps track of the watched raw handles of a runtime.
+#[derive(Default)]
+pub struct Poller {
+    backend: Option<Backend>,
+    watchers: HashMap<usize, Watcher>,
+    next_id: usize,
+}
+
+impl Poller {
+    /// Returns if there are any watched handles (keeping the event-loop alive).
+    pub fn is_active(&self) -> bool {
+        !self.watchers.is_empty()
+    }
+
+    fn backend(&mut self, interrupt: &LoopInterruptHandle) -> Result<&Backend> {
+        if self.backend.is_none() {
+            self.backend = Some(spawn_backend(interrupt.clone())?);
+        }
+        Ok(self.backend.as_ref().unwrap())
+    }
+
+    fn register(
+        &mut self,
+        interrupt: &LoopInterruptHandle,
+        mut source: RawSource,
+        interest: Interest,
+        callback: v8::Global<v8::Function>,
+    ) -> Result<usize> {
+        let id = self.next_id;
+        let registry = &self.backend(interrupt)?.registry;
+
+        registry.register(&mut source, Token(id), interest)?;
+
+        let callback = Rc::new(callback);
+        let watcher = Watcher {
+            source,
+            interest,
+            callback,
+        };
+
+        self.watchers.insert(id, watcher);
+        self.next_id += 1;
+
+        Ok(id)
+    }
+
+    /// Re-registers a handle, which also re-arms its (edge-triggered) events.
+    fn reregister(&mut self, id: usize, interest: Option<Interest>) -> Result<()> {
+        let (Some(backend), Some(watcher)) = (&self.backend, self.watchers.get_mut(&id)) else {
+            return Ok(());
+        };
+
+        let interest = interest.unwrap_or(watcher.interest);
+        backend
+            .registry
+            .reregister(&mut watcher.source, Token(id), interest)?;
+
+        watcher.interest = interest;
+        Ok(())
+    }
+
+    fn deregister(&mut self, id: usize) {
+        let (Some(backend), Some(mut watcher)) = (&self.backend, self.watchers.remove(&id)) else {
+            return;
+        };
+
+        // Note: The handle might be closed already (against the contract), in
+        // which case the OS has dropped the registration on its own.
+        let _ = backend.registry.deregister(&mut watcher.source);
+    }
+}
+
+/// Starts the thread that waits for readiness events and forwards them.
+fn spawn_backend(interrupt: LoopInterruptHandle) -> Result<Backend> {
+    let mut poll = Poll::new()?;
+    let registry = poll.registry().try_clone()?;
+    let (sender, receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .name("dune-poll".into())
+        .spawn(move || {
+            let mut events = Events::with_capacity(256);
+            loop {
+                if let Err(e) = poll.poll(&mut events, None) {
+                    if e.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    break;
+                }
+
+                for event in events.iter() {
+                    let readiness = Readiness {
+                        id: event.token().0,
+                        readable: event.is_readable(),
+                        writable: event.is_writable(),
+                        hangup: event.is_read_closed() || event.is_write_closed(),
+                        error: event.is_error(),
+                    };
+                    // The runtime is gone, so there's nobody to notify.
+                    if sender.send(readiness).is_err() {
+                        return;
+                    }
+                }
+
+                // Wake up the event-loop (if it's blocked on its own poll-phase).
+                interrupt.interrupt();
+            }
+        })?;
+
+    Ok(Backend {
+        registry,
+        events: receiver,
+    })
+}
+
+/// Moves the readiness events (received so far) to the pending futures.
+pub fn queue_ready_events(state: &mut JsRuntimeState) {
+    let poller = &state.poller;
+    let Some(backend) = poller.backend.as_ref() else {
+        return;
+    };
+
+    for readiness in backend.events.try_iter() {
+        // Events of handles that stopped being watched are dropped.
+        if let Some(watcher) = poller.watchers.get(&readiness.id) {
+            let callback = Rc::clone(&watcher.callback);
+            let future = PollFuture {
+                readiness,
+                callback,
+            };
+            state.pending_futures.push(Box::new(future));
+        }
+    }
+}
+
+struct PollFuture {
+    readiness: Readiness,
+    callback: Rc<v8::Global<v8::Function>>,
+}
+
+impl JsFuture for PollFuture {
+    fn run(&mut self, scope: &mut v8::HandleScope) {
+        // Create the event object passed to the callback.
+        let event = v8::Object::new(scope);
+        let fields = [
+            ("readable", self.readiness.readable),
+            ("writable", self.readiness.writable),
+            ("hangup", self.readiness.hangup),
+            ("error", self.readiness.error),
+        ];
+
+        for (name, value) in fields {
+            let value = v8::Boolean::new(scope, value);
+            set_property_to(scope, event, name, value.into());
+        }
+
+        let undefined = v8::undefined(scope).into();
+        let callback = v8::Local::new(scope, (*self.callback).clone());
+        let tc_scope = &mut v8::TryCatch::new(scope);
+
+        callback.call(tc_scope, undefined, &[event.into()]);
+
+        // On exception, report it and exit.
+        if tc_scope.has_caught() {
+            let exception = tc_scope.exception().unwrap();
+            let exception = v8::Global::new(tc_scope, exception);
+            let state = JsRuntime::state(tc_scope);
+            state.borrow_mut().exceptions.capture_exception(exception);
+            return;
+        }
+
+        // Note: Re-arming the handle after every callback gives us level-triggered
+        // semantics, meaning that the callback will run again if the handle is
+        // still ready (e.g. not all the available data have been read).
+        let state_rc = JsRuntime::state(tc_scope);
+        let mut state = state_rc.borrow_mut();
+
+        if let Err(e) = state.poller.reregister(self.readiness.id, None) {
+            state.poller.deregister(self.readiness.id);
+            drop(state);
+
+            // Report the failure as an uncaught exception.
+            let message = format!("Failed to watch the file descriptor: {e}");
+            let message = v8::String::new(tc_scope, &message).unwrap();
+            let exception = v8::Exception::error(tc_scope, message);
+            let exception = v8::Global::new(tc_scope, exception);
+            let state = JsRuntime::state(tc_scope);
+            state.borrow_mut().exceptions.capture_exception(exception);
+        }
+    }
+}
+
+/// Extracts the readiness interest from a JS object.
+fn interest_from(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> Option<Interest> {
+    let object = value.to_object(scope)?;
+    let mut flag = |name: &str| {
+        let key = v8::String::new(scope, name).unwrap();
+        object
+            .get(scope, key.into())
+            .is_some_and(|value| value.boolean_value(scope))
+    };
+
+    match (flag("readable"), flag("writable")) {
+        (true, true) => Some(Interest::READABLE | Interest::WRITABLE),
+        (true, false) => Some(Interest::READABLE),
+        (false, true) => Some(Interest::WRITABLE),
+        (false, false) => None,
+    }
+}
+
+/// Starts watching a raw handle for readiness events.
+fn watch(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the raw handle and the interest from javascript.
+    let handle = args.get(0).integer_value(scope).unwrap_or(-1);
+    let Some(interest) = interest_from(scope, args.get(1)) else {
+        throw_type_error(scope, "At least one of readable or writable must be set.");
+        return;
+    };
+
+    if handle < 0 {
+        throw_type_error(scope, "The file descriptor must be a non-negative integer.");
+        return;
+    }
+
+    let callback = v8::Local::<v8::Function>::try_from(args.get(2)).unwrap();
+    let callback = v8::Global::new(scope, callback);
+
+    let state_rc = JsRuntime::state(scope);
+    let mut state = state_rc.borrow_mut();
+    let interrupt = state.interrupt_handle.clone();
+    let result = state
+        .poller
+        .register(&interrupt, RawSource::new(handle), interest, callback);
+
+    drop(state);
+
+    match result {
+        Ok(id) => rv.set(v8::Number::new(scope, id as f64).into()),
+        Err(e) => {
+            let e = anyhow!("Failed to watch the file descriptor: {e}");
+            throw_exception(scope, &e);
+        }
+    }
+}
+
+/// Changes the readiness interest of a watched handle.
+fn update(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    let id = args.get(0).int32_value(scope).unwrap() as usize;
+    let Some(interest) = interest_from(scope, args.get(1)) else {
+        throw_type_error(scope, "At least one of readable or writable must be set.");
+        return;
+    };
+
+    let state_rc = JsRuntime::state(scope);
+    let result = state_rc.borrow_mut().poller.reregister(id, Some(interest));
+
+    if let Err(e) = result {
+        throw_exception(scope, &e);
+    }
+}
+
+/// Stops watching a raw handle.
+fn unwatch(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    let id = args.get(0).int32_value(scope).unwrap() as usize;
+    let state_rc = JsRuntime::state(scope);
+
+    state_rc.borrow_mut().poller.deregister(id);
+}
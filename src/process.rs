@@ -4,11 +4,15 @@
 // https://nodejs.org/dist/latest-v17.x/docs/api/process.html
 
 use crate::bindings::create_object_under;
+use crate::bindings::external_references;
 use crate::bindings::set_constant_to;
 use crate::bindings::set_function_to;
 use crate::bindings::set_property_to;
 use crate::bindings::throw_exception;
+use crate::bindings::throw_type_error;
 use crate::bindings::BINDINGS;
+use crate::errors::ExitRequest;
+use crate::errors::IoError;
 use crate::JsRuntime;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
@@ -38,10 +42,27 @@ pub fn initialize<'s>(
     set_function_to(scope, process, "uptime", uptime);
     set_function_to(scope, process, "kill", kill);
     set_function_to(scope, process, "binding", bind);
+    set_function_to(scope, process, "getrlimit", getrlimit);
+    set_function_to(scope, process, "setrlimit", setrlimit);
 
     process
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        cwd,
+        exit,
+        memory_usage,
+        next_tick,
+        uptime,
+        kill,
+        bind,
+        getrlimit,
+        setrlimit
+    ]
+}
+
 /// Refreshes the static values of the process object.
 pub fn refresh(scope: &mut v8::HandleScope) {
     // Get access to the process object.
@@ -51,9 +72,25 @@ pub fn refresh(scope: &mut v8::HandleScope) {
     let process = global.get(scope, key.into()).unwrap();
     let process = v8::Local::<v8::Object>::try_from(process).unwrap();
 
-    // `process.argv` - an array containing the command-line arguments passed
-    //  when the dune process was launched.
-    let arguments: Vec<String> = env::args().collect();
+    // `process.execPath` - the absolute path of the dune executable.
+    let argv0 = env::args().next().unwrap_or_default();
+    let exec_path = env::current_exe()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| argv0.clone());
+
+    let value = v8::String::new(scope, &exec_path).unwrap();
+    set_property_to(scope, process, "execPath", value.into());
+
+    // `process.argv0` - the original value of argv[0] (as typed by the user).
+    let value = v8::String::new(scope, &argv0).unwrap();
+    set_property_to(scope, process, "argv0", value.into());
+
+    // `process.argv` - an array containing the dune executable, the script's path
+    //  and the command-line arguments passed to the script.
+    let state_rc = JsRuntime::state(scope);
+    let script_argv = state_rc.borrow().options.argv.clone();
+
+    let arguments: Vec<String> = std::iter::once(exec_path).chain(script_argv).collect();
     let argv = v8::Array::new(scope, arguments.len() as i32);
 
     arguments.iter().enumerate().for_each(|(i, arg)| {
@@ -80,11 +117,21 @@ pub fn refresh(scope: &mut v8::HandleScope) {
 
     set_property_to(scope, process, "pid", id.into());
 
+    // `process.ppid` - PID of the parent of the current process.
+    let id = v8::Number::new(scope, parent_id() as f64);
+
+    set_property_to(scope, process, "ppid", id.into());
+
     // `process.platform` - a string identifying the operating system platform.
     let platform = v8::String::new(scope, env::consts::OS).unwrap();
 
     set_property_to(scope, process, "platform", platform.into());
 
+    // `process.arch` - the CPU architecture dune was compiled for.
+    let arch = v8::String::new(scope, env::consts::ARCH).unwrap();
+
+    set_property_to(scope, process, "arch", arch.into());
+
     // `process.version` - the dune version.
     let version = format!("v{}", VERSIONS.get("dune").unwrap());
     let version = v8::String::new(scope, version.as_str()).unwrap();
@@ -102,6 +149,48 @@ pub fn refresh(scope: &mut v8::HandleScope) {
     set_property_to(scope, process, "versions", versions.into());
 }
 
+/// Returns the PID of the parent process.
+#[cfg(target_family = "unix")]
+fn parent_id() -> u32 {
+    std::os::unix::process::parent_id()
+}
+
+/// Returns the PID of the parent process (or 0 when it can't be found).
+#[cfg(target_family = "windows")]
+fn parent_id() -> u32 {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::*;
+
+    let pid = std::process::id();
+    let mut parent = 0;
+
+    // Note: Windows doesn't track the parent of a process directly, so we walk
+    // a snapshot of the running processes until we find the current one.
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return parent;
+        }
+
+        let mut entry: PROCESSENTRY32 = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32>() as u32;
+
+        let mut found = Process32First(snapshot, &mut entry);
+        while found != 0 {
+            if entry.th32ProcessID == pid {
+                parent = entry.th32ParentProcessID;
+                break;
+            }
+            found = Process32Next(snapshot, &mut entry);
+        }
+
+        CloseHandle(snapshot);
+    }
+
+    parent
+}
+
 /// Current working directory.
 fn cwd(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
     match env::current_dir() {
@@ -121,10 +210,21 @@ fn cwd(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, mut rv: v8
 /// Exits the program with the given code.
 fn exit(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
     // Exit the program when value is not valid i32.
-    match args.get(0).to_int32(scope) {
-        Some(code) => std::process::exit(code.value()),
-        None => std::process::exit(0),
-    }
+    let code = match args.get(0).to_int32(scope) {
+        Some(code) => code.value(),
+        None => 0,
+    };
+    request_exit(scope, code);
+}
+
+/// Stops the running JS and makes the runtime return an exit request.
+///
+/// Note: The process is not exited right away, the embedder (e.g. the CLI)
+/// decides what to do once `run_event_loop` returns the request.
+pub fn request_exit(scope: &mut v8::HandleScope, code: i32) {
+    let state_rc = JsRuntime::state(scope);
+    state_rc.borrow_mut().set_fatal_error(ExitRequest(code));
+    scope.terminate_execution();
 }
 
 /// Returns an object describing the memory usage.
@@ -156,17 +256,10 @@ fn next_tick(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _
     let callback = v8::Local::<v8::Function>::try_from(args.get(0)).unwrap();
     let callback = v8::Global::new(scope, callback);
 
-    // Convert params argument (Array<Local<Value>>) to Rust vector.
-    let params = match v8::Local::<v8::Array>::try_from(args.get(3)) {
-        Ok(params) => {
-            (0..params.length()).fold(Vec::<v8::Global<v8::Value>>::new(), |mut acc, i| {
-                let param = params.get_index(scope, i).unwrap();
-                acc.push(v8::Global::new(scope, param));
-                acc
-            })
-        }
-        Err(_) => vec![],
-    };
+    // The rest of the arguments are passed through to the callback.
+    let params = (1..args.length())
+        .map(|i| v8::Global::new(scope, args.get(i)))
+        .collect();
 
     let state_rc = JsRuntime::state(scope);
 
@@ -225,6 +318,130 @@ fn kill(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8:
     }
 }
 
+/// Maps a resource name (as used by `ulimit`) to the OS resource.
+#[cfg(target_family = "unix")]
+fn parse_resource(name: &str) -> Option<nix::sys::resource::Resource> {
+    use nix::sys::resource::Resource;
+
+    match name {
+        "core" => Some(Resource::RLIMIT_CORE),
+        "cpu" => Some(Resource::RLIMIT_CPU),
+        "data" => Some(Resource::RLIMIT_DATA),
+        "fsize" => Some(Resource::RLIMIT_FSIZE),
+        "nofile" => Some(Resource::RLIMIT_NOFILE),
+        "stack" => Some(Resource::RLIMIT_STACK),
+        _ => None,
+    }
+}
+
+/// Returns the soft and hard limits of a system resource.
+#[cfg(target_family = "unix")]
+fn getrlimit(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    use nix::sys::resource::rlim_t;
+    use nix::sys::resource::RLIM_INFINITY;
+
+    let name = args.get(0).to_rust_string_lossy(scope);
+    let Some(resource) = parse_resource(&name) else {
+        throw_type_error(scope, &format!("Unknown resource: \"{name}\""));
+        return;
+    };
+
+    let (soft, hard) = match nix::sys::resource::getrlimit(resource) {
+        Ok(limits) => limits,
+        Err(e) => {
+            throw_exception(scope, &IoError::from(e).into());
+            return;
+        }
+    };
+
+    // Note: Unlimited resources are represented as `Infinity`.
+    let to_number = |value: rlim_t| match value {
+        RLIM_INFINITY => f64::INFINITY,
+        value => value as f64,
+    };
+
+    let limits = v8::Object::new(scope);
+    let soft = v8::Number::new(scope, to_number(soft));
+    let hard = v8::Number::new(scope, to_number(hard));
+
+    set_property_to(scope, limits, "soft", soft.into());
+    set_property_to(scope, limits, "hard", hard.into());
+
+    rv.set(limits.into());
+}
+
+/// Sets the soft and hard limits of a system resource.
+#[cfg(target_family = "unix")]
+fn setrlimit(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    use nix::sys::resource::rlim_t;
+    use nix::sys::resource::RLIM_INFINITY;
+
+    let name = args.get(0).to_rust_string_lossy(scope);
+    let Some(resource) = parse_resource(&name) else {
+        throw_type_error(scope, &format!("Unknown resource: \"{name}\""));
+        return;
+    };
+
+    let Ok(limits) = v8::Local::<v8::Object>::try_from(args.get(1)) else {
+        throw_type_error(scope, "The \"limits\" argument must be of type object.");
+        return;
+    };
+
+    // Omitted limits keep their current values.
+    let (current_soft, current_hard) = match nix::sys::resource::getrlimit(resource) {
+        Ok(limits) => limits,
+        Err(e) => {
+            throw_exception(scope, &IoError::from(e).into());
+            return;
+        }
+    };
+
+    let mut read_limit = |name: &str, current: rlim_t| -> Option<rlim_t> {
+        let key = v8::String::new(scope, name).unwrap();
+        let value = limits.get(scope, key.into()).unwrap();
+        if value.is_undefined() {
+            return Some(current);
+        }
+        match value.number_value(scope) {
+            Some(value) if value == f64::INFINITY => Some(RLIM_INFINITY),
+            Some(value) if value >= 0.0 && value.fract() == 0.0 => Some(value as rlim_t),
+            _ => None,
+        }
+    };
+
+    let (Some(soft), Some(hard)) = (
+        read_limit("soft", current_soft),
+        read_limit("hard", current_hard),
+    ) else {
+        throw_type_error(scope, "Limits must be non-negative integers or Infinity.");
+        return;
+    };
+
+    if let Err(e) = nix::sys::resource::setrlimit(resource, soft, hard) {
+        throw_exception(scope, &IoError::from(e).into());
+    }
+}
+
+#[cfg(target_family = "windows")]
+fn getrlimit(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    throw_exception(
+        scope,
+        &anyhow::anyhow!("process.getrlimit is unsupported on Windows."),
+    );
+}
+
+#[cfg(target_family = "windows")]
+fn setrlimit(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    throw_exception(
+        scope,
+        &anyhow::anyhow!("process.setrlimit is unsupported on Windows."),
+    );
+}
+
 /// Exposes native modules to JavaScript.
 fn bind(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
     // Get requested native binding.
@@ -1,3 +1,6 @@
+use crate::dune_dir;
+use crate::errors::exit_with_error;
+use crate::errors::ExitRequest;
 use crate::runtime::check_exceptions;
 use crate::runtime::JsRuntime;
 use colored::*;
@@ -191,10 +194,6 @@ enum ReplMessage {
     Terminate,
 }
 
-/// CLI configuration for REPL.
-static CLI_ROOT: &str = ".dune";
-static CLI_HISTORY: &str = ".dune_history";
-
 /// Starts the REPL server.
 pub fn start(mut runtime: JsRuntime) {
     // Create a channel for thread communication.
@@ -214,7 +213,7 @@ pub fn start(mut runtime: JsRuntime) {
     // Spawn the REPL thread.
     thread::spawn(move || {
         let mut editor = Editor::new().unwrap();
-        let history_file_path = &dirs::home_dir().unwrap().join(CLI_ROOT).join(CLI_HISTORY);
+        let history_file_path = &dune_dir::history_file();
 
         editor.set_helper(Some(RLHelper::new()));
         editor.load_history(history_file_path).unwrap_or_default();
@@ -269,6 +268,10 @@ pub fn start(mut runtime: JsRuntime) {
         if maybe_message.is_err() {
             // Tick the event loop and report exceptions.
             runtime.tick_event_loop();
+            // Errors that stop the runtime also end the REPL session.
+            if let Some(error) = runtime.take_fatal_error() {
+                exit_with_error(&error);
+            }
             // Check for exceptions.
             let scope = &mut runtime.handle_scope();
             if let Some(error) = check_exceptions(scope) {
@@ -297,10 +300,15 @@ pub fn start(mut runtime: JsRuntime) {
                         log.call(scope, global.into(), &[value]);
                     }
                     Ok(None) => {}
+                    Err(e) if e.is::<ExitRequest>() => exit_with_error(&e),
                     Err(e) => eprintln!("{e}"),
                 };
             }
-            ReplMessage::Terminate => break,
+            ReplMessage::Terminate => {
+                // Let in-flight work (e.g. a file write) finish before exiting.
+                runtime.drain();
+                break;
+            }
         }
     }
 }
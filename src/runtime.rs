@@ -1,6 +1,8 @@
 use crate::bindings;
-use crate::errors::report_and_exit;
-use crate::errors::unwrap_or_exit;
+use crate::bindings::Extension;
+use crate::bindings::EXTERNAL_REFERENCES;
+use crate::crypto::Entropy;
+use crate::errors::generic_error;
 use crate::errors::JsError;
 use crate::exceptions::ExceptionState;
 use crate::exceptions::PromiseRejectionEntry;
@@ -9,33 +11,71 @@ use crate::hooks::host_initialize_import_meta_object_cb;
 use crate::hooks::module_resolve_cb;
 use crate::hooks::promise_reject_cb;
 use crate::inspector::JsRuntimeInspector;
+use crate::limiter::catch_panics;
+use crate::limiter::TaskLimiter;
+use crate::loaders::Reload;
 use crate::modules::create_origin;
 use crate::modules::fetch_module_tree;
-use crate::modules::load_import;
+use crate::modules::load_import_task;
 use crate::modules::resolve_import;
 use crate::modules::EsModuleFuture;
 use crate::modules::ImportKind;
 use crate::modules::ImportMap;
+use crate::modules::ImportTrace;
+use crate::modules::LoadedModule;
 use crate::modules::ModuleGraph;
 use crate::modules::ModuleMap;
 use crate::modules::ModuleStatus;
+use crate::modules::CORE_MODULES;
+use crate::poll;
+use crate::poll::Poller;
 use crate::process;
+use crate::snapshot;
+use crate::timers;
+use crate::timers::UnrefTimers;
+use crate::verbosity::trace;
 use anyhow::bail;
 use anyhow::Error;
 use anyhow::Ok;
+use colored::*;
 use dune_event_loop::EventLoop;
 use dune_event_loop::LoopHandle;
 use dune_event_loop::LoopInterruptHandle;
 use dune_event_loop::TaskResult;
 use std::cell::RefCell;
 use std::cmp;
+use std::env;
+use std::fs;
 use std::net::SocketAddrV4;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::Once;
+use std::thread;
+use std::time::Duration;
 use std::time::Instant;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
+/// The path and source of the module setting up the core environment.
+const MAIN_ENVIRONMENT: &str = "dune:environment/main";
+const MAIN_ENVIRONMENT_SOURCE: &str = include_str!("./js/main.js");
+
+/// The env variable with extra (space separated) flags passed to V8.
+pub const V8_FLAGS_ENV: &str = "DUNE_V8_FLAGS";
+
+/// How the core environment is set up when a runtime is created.
+#[derive(Clone, Copy)]
+enum StartupMode {
+    /// Evaluates `main.js` (and the core modules) from scratch.
+    Fresh,
+    /// Deserializes the environment from the startup snapshot.
+    Snapshot(&'static [u8]),
+    /// Evaluates `main.js`, so the heap can be saved as a startup snapshot.
+    Creator,
+}
+
 /// A vector with JS callbacks and parameters.
 type NextTickQueue = Vec<(v8::Global<v8::Function>, Vec<v8::Global<v8::Value>>)>;
 
@@ -69,17 +109,39 @@ pub struct JsRuntimeState {
     pub options: JsRuntimeOptions,
     /// Tracks wake event for current loop iteration.
     pub wake_event_queued: bool,
+    /// Caps the number of concurrent thread-pool tasks per class.
+    pub limiter: TaskLimiter,
+    /// The source of the values returned by `crypto.getRandomValues()`.
+    pub entropy: Entropy,
+    /// Raw handles watched for readiness events.
+    pub poller: Poller,
+    /// Timeouts that don't keep the event-loop alive.
+    pub unref_timers: UnrefTimers,
+    /// An error that stopped the runtime (returned by `run_event_loop`).
+    pub fatal_error: Option<Error>,
+}
+
+impl JsRuntimeState {
+    /// Records an error that stops the runtime (only the first one is kept).
+    pub fn set_fatal_error(&mut self, error: impl Into<Error>) {
+        if self.fatal_error.is_none() {
+            self.fatal_error = Some(error.into());
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
-#[allow(dead_code)]
 pub struct JsRuntimeOptions {
     // The seed used in Math.random() method.
     pub seed: Option<i64>,
-    // Reloads every URL import.
-    pub reload: bool,
+    // Seeds the crypto random values as well (only meant for tests).
+    pub seed_entropy: bool,
+    // Decides which URL imports are downloaded again (ignoring the cache).
+    pub reload: Reload,
     // The main entry point for the program.
     pub root: Option<String>,
+    // The script's path followed by the arguments passed to it.
+    pub argv: Vec<String>,
     // Holds user defined import maps for module loading.
     pub import_map: Option<ImportMap>,
     // The numbers of threads used by the thread-pool.
@@ -90,6 +152,28 @@ pub struct JsRuntimeOptions {
     pub inspect: Option<(SocketAddrV4, bool)>,
     // Exposes v8's garbage collector.
     pub expose_gc: bool,
+    // Writes a CPU profile (folded stacks) to the given file on exit.
+    pub prof: Option<PathBuf>,
+    // The sampling interval of the CPU profiler in microseconds.
+    pub prof_interval: Option<u64>,
+    // Loads modules in a deterministic order.
+    pub frozen: bool,
+    // Records the loader, cache status and timings of every module load.
+    pub trace_imports: bool,
+    // Writes the recorded module loads as JSON (instead of a summary).
+    pub trace_imports_file: Option<PathBuf>,
+    // The max number of fs (or dns) tasks running at once on the thread-pool.
+    pub task_limit: Option<usize>,
+    // Native functions provided by the embedder (see `bindings::Extension`).
+    pub extensions: Vec<Extension>,
+    // Extra flags passed to V8 (appended to the ones from `DUNE_V8_FLAGS`).
+    pub v8_flags: Vec<String>,
+    // Disables V8's optimizing compilers (the code is only interpreted).
+    pub jitless: bool,
+    // Makes `eval` and `new Function` throw (like a CSP without 'unsafe-eval').
+    pub disallow_code_generation: bool,
+    // How long (in milliseconds) `drain` waits for in-flight work on shutdown.
+    pub drain_timeout: Option<u64>,
 }
 
 pub struct JsRuntime {
@@ -107,12 +191,32 @@ pub struct JsRuntime {
 
 impl JsRuntime {
     /// Creates a new JsRuntime.
-    pub fn new() -> JsRuntime {
+    pub fn new() -> Result<JsRuntime, Error> {
         Self::with_options(JsRuntimeOptions::default())
     }
 
     /// Creates a new JsRuntime based on provided options.
-    pub fn with_options(options: JsRuntimeOptions) -> JsRuntime {
+    pub fn with_options(options: JsRuntimeOptions) -> Result<JsRuntime, Error> {
+        // Note: The snapshot is only valid for the V8 flags it was created with.
+        let has_default_flags = options.seed.is_none()
+            && !options.expose_gc
+            && !options.jitless
+            && !options.disallow_code_generation
+            && user_v8_flags(&options).is_empty();
+
+        let mode = match snapshot::startup_snapshot() {
+            Some(blob) if has_default_flags => StartupMode::Snapshot(blob),
+            _ => StartupMode::Fresh,
+        };
+        Self::create(options, mode)
+    }
+
+    /// Creates a JsRuntime whose heap can be saved as a startup snapshot.
+    pub fn for_snapshot() -> Result<JsRuntime, Error> {
+        Self::create(JsRuntimeOptions::default(), StartupMode::Creator)
+    }
+
+    fn create(options: JsRuntimeOptions, mode: StartupMode) -> Result<JsRuntime, Error> {
         // Configuration flags for V8.
         let mut flags = String::from(concat!(
             " --no-validate-asm",
@@ -129,8 +233,21 @@ impl JsRuntime {
             flags.push_str(" --expose-gc")
         }
 
+        if options.jitless {
+            flags.push_str(" --jitless")
+        }
+
+        if options.disallow_code_generation {
+            flags.push_str(" --disallow-code-generation-from-strings")
+        }
+
         v8::V8::set_flags_from_string(&flags);
 
+        // Note: User flags are left out of snapshots (see `with_options`).
+        if !matches!(mode, StartupMode::Creator) {
+            set_user_v8_flags(user_v8_flags(&options));
+        }
+
         // Fire up the v8 engine.
         static V8_INIT: Once = Once::new();
         V8_INIT.call_once(move || {
@@ -139,7 +256,16 @@ impl JsRuntime {
             v8::V8::initialize();
         });
 
-        let mut isolate = v8::Isolate::new(v8::CreateParams::default());
+        let references = &*EXTERNAL_REFERENCES;
+        let mut isolate = match mode {
+            StartupMode::Fresh => v8::Isolate::new(v8::CreateParams::default()),
+            StartupMode::Snapshot(blob) => v8::Isolate::new(
+                v8::CreateParams::default()
+                    .snapshot_blob(blob)
+                    .external_references(&**references),
+            ),
+            StartupMode::Creator => v8::Isolate::snapshot_creator(Some(references), None),
+        };
 
         isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
         isolate.set_capture_stack_trace_for_uncaught_exceptions(true, 10);
@@ -151,7 +277,10 @@ impl JsRuntime {
 
         let context = {
             let scope = &mut v8::HandleScope::new(&mut *isolate);
-            let context = bindings::create_new_context(scope);
+            let context = match mode {
+                StartupMode::Snapshot(_) => bindings::restore_context(scope, &options.extensions),
+                _ => bindings::create_new_context(scope, &options.extensions),
+            };
             v8::Global::new(scope, context)
         };
 
@@ -167,9 +296,10 @@ impl JsRuntime {
             .unwrap()
             .as_millis();
 
-        // Initialize the v8 inspector.
+        // Initialize the v8 inspector (the CPU profiler is driven through it).
         let address = options.inspect.map(|(address, _)| (address));
-        let inspector = options.inspect.map(|(_, waiting_for_session)| {
+        let waiting_for_session = options.inspect.is_some_and(|(_, wait)| wait);
+        let inspector = (options.inspect.is_some() || options.prof.is_some()).then(|| {
             JsRuntimeInspector::new(
                 &mut isolate,
                 context.clone(),
@@ -179,6 +309,13 @@ impl JsRuntime {
             )
         });
 
+        const DEFAULT_PROF_INTERVAL: u64 = 1000;
+
+        let prof_interval = options
+            .prof
+            .as_ref()
+            .map(|_| options.prof_interval.unwrap_or(DEFAULT_PROF_INTERVAL));
+
         // Store state inside the v8 isolate slot.
         // https://v8docs.nodesource.com/node-4.8/d5/dda/classv8_1_1_isolate.html#a7acadfe7965997e9c386a05f098fbe36
         let state = Rc::new(RefCell::new(JsRuntimeState {
@@ -191,6 +328,11 @@ impl JsRuntime {
             time_origin,
             next_tick_queue: Vec::new(),
             exceptions: ExceptionState::new(),
+            limiter: TaskLimiter::new(options.task_limit),
+            entropy: Entropy::new(options.seed.filter(|_| options.seed_entropy)),
+            poller: Poller::default(),
+            unref_timers: UnrefTimers::default(),
+            fatal_error: None,
             options,
             wake_event_queued: false,
         }));
@@ -204,21 +346,29 @@ impl JsRuntime {
             inspector,
         };
 
-        runtime.load_main_environment();
+        match mode {
+            StartupMode::Snapshot(_) => runtime.restore_main_environment()?,
+            _ => runtime.load_main_environment()?,
+        };
 
         // Start inspector agent is requested.
-        if let Some(inspector) = runtime.inspector().as_mut() {
-            let address = address.unwrap();
-            inspector.borrow_mut().start_agent(address);
+        if let (Some(inspector), Some(address)) = (runtime.inspector(), address) {
+            let result = inspector.borrow_mut().start_agent(address);
+            result.map_err(|e| generic_error(e.to_string()))?;
+        }
+
+        // Start sampling the stack if a CPU profile is requested.
+        if let (Some(inspector), Some(interval)) = (runtime.inspector(), prof_interval) {
+            inspector.borrow_mut().start_profiling(interval);
         }
 
-        runtime
+        Ok(runtime)
     }
 
     /// Initializes synchronously the core environment (see lib/main.js).
-    fn load_main_environment(&mut self) {
-        let name = "dune:environment/main";
-        let source = include_str!("./js/main.js");
+    fn load_main_environment(&mut self) -> Result<(), Error> {
+        let name = MAIN_ENVIRONMENT;
+        let source = MAIN_ENVIRONMENT_SOURCE;
 
         let scope = &mut self.handle_scope();
         let tc_scope = &mut v8::TryCatch::new(scope);
@@ -229,8 +379,7 @@ impl JsRuntime {
                 assert!(tc_scope.has_caught());
                 let exception = tc_scope.exception().unwrap();
                 let exception = JsError::from_v8_exception(tc_scope, exception, None);
-                eprintln!("{exception:?}");
-                std::process::exit(1);
+                bail!(exception);
             }
         };
 
@@ -241,7 +390,7 @@ impl JsRuntime {
             assert!(tc_scope.has_caught());
             let exception = tc_scope.exception().unwrap();
             let exception = JsError::from_v8_exception(tc_scope, exception, None);
-            report_and_exit(exception);
+            bail!(exception);
         }
 
         let _ = module.evaluate(tc_scope);
@@ -249,11 +398,104 @@ impl JsRuntime {
         if module.get_status() == v8::ModuleStatus::Errored {
             let exception = module.get_exception();
             let exception = JsError::from_v8_exception(tc_scope, exception, None);
-            report_and_exit(exception);
+            bail!(exception);
         }
 
         // Initialize process static values.
         process::refresh(tc_scope);
+
+        Ok(())
+    }
+
+    /// Restores the core environment from the startup snapshot.
+    fn restore_main_environment(&mut self) -> Result<(), Error> {
+        let scope = &mut self.handle_scope();
+        let state_rc = JsRuntime::state(scope);
+
+        // Note: The modules of the environment are stored next to their paths
+        // (see `create_snapshot`), since the module-map is not part of the heap.
+        let paths = scope
+            .get_context_data_from_snapshot_once::<v8::Array>(0)
+            .ok_or_else(|| generic_error("The startup snapshot is missing the module-map."))?;
+
+        for index in 0..paths.length() {
+            let path = paths.get_index(scope, index).unwrap();
+            let path = path.to_rust_string_lossy(scope);
+            let module = scope
+                .get_context_data_from_snapshot_once::<v8::Module>(index as usize + 1)
+                .unwrap();
+
+            let source = match path.as_str() {
+                MAIN_ENVIRONMENT => MAIN_ENVIRONMENT_SOURCE,
+                path => CORE_MODULES.get(path).copied().unwrap_or_default(),
+            };
+
+            let module = v8::Global::new(scope, module);
+            let mut state = state_rc.borrow_mut();
+            state.module_map.insert(&path, module, source);
+            state.module_map.seen.insert(path, ModuleStatus::Ready);
+        }
+
+        // Initialize process static values.
+        process::refresh(scope);
+
+        Ok(())
+    }
+
+    /// Serializes the heap of a runtime created with `for_snapshot`.
+    pub fn create_snapshot(self) -> Result<Vec<u8>, Error> {
+        let mut runtime = std::mem::ManuallyDrop::new(self);
+        let context = runtime.context();
+        let state_rc = runtime.get_state();
+
+        {
+            let scope = &mut v8::HandleScope::new(&mut *runtime.isolate);
+            let context = v8::Local::new(scope, context);
+
+            // Store the module-map's modules (in a known order) with the context.
+            let state = state_rc.borrow();
+            let mut modules: Vec<_> = state.module_map.index.iter().collect();
+            modules.sort_by(|a, b| a.0.cmp(b.0));
+
+            let paths = v8::Array::new(scope, modules.len() as i32);
+            for (index, (path, _)) in modules.iter().enumerate() {
+                let path = v8::String::new(scope, path).unwrap();
+                paths.set_index(scope, index as u32, path.into());
+            }
+
+            scope.add_context_data(context, paths);
+            for (_, module) in modules {
+                let module = v8::Local::new(scope, module);
+                scope.add_context_data(context, module);
+            }
+
+            scope.set_default_context(context);
+        }
+
+        // SAFETY: The runtime is never used (or dropped) again, its fields are
+        // moved out of it one by one. The state has to go before the isolate
+        // since V8 can't serialize a heap with live global handles.
+        let (mut isolate, event_loop, inspector) = unsafe {
+            drop(std::ptr::read(&runtime.state));
+            (
+                std::ptr::read(&runtime.isolate),
+                std::ptr::read(&runtime.event_loop),
+                std::ptr::read(&runtime.inspector),
+            )
+        };
+
+        let state = isolate.remove_slot::<Rc<RefCell<JsRuntimeState>>>();
+
+        drop(state_rc);
+        drop(state);
+        drop(inspector);
+        drop(event_loop);
+
+        let blob = isolate
+            .create_blob(v8::FunctionCodeHandling::Keep)
+            .ok_or_else(|| generic_error("Failed to create the startup snapshot."))?;
+
+        Ok(blob.to_vec())
     }
 
     /// Executes traditional JavaScript code (traditional = not ES modules).
@@ -297,12 +539,148 @@ impl JsRuntime {
 
         match script.run(tc_scope) {
             Some(value) => Ok(Some(v8::Global::new(tc_scope, value))),
+            // Note: The execution is terminated when the runtime is stopped by
+            // an error (e.g. calling `process.exit()`).
+            None if tc_scope.has_terminated() => match state_rc.borrow_mut().fatal_error.take() {
+                Some(error) => {
+                    tc_scope.cancel_terminate_execution();
+                    Err(error)
+                }
+                None => Ok(None),
+            },
             None => handle_exception(tc_scope),
         }
     }
 
+    /// Evaluates a script and converts its completion value into JSON.
+    ///
+    /// Promises are awaited by running the event-loop until they settle, while
+    /// values that have no JSON representation (e.g. functions) are an error.
+    pub fn eval_to_json(&mut self, source: &str) -> Result<serde_json::Value, Error> {
+        let value = match self.execute_script("<eval>", source)? {
+            Some(value) => value,
+            None => return Ok(serde_json::Value::Null),
+        };
+
+        let value = self.resolve_value(value)?;
+        let scope = &mut self.handle_scope();
+        let value = v8::Local::new(scope, value);
+
+        // Note: `JSON.stringify` silently drops these, so we have to check them first.
+        if value.is_undefined() {
+            return Ok(serde_json::Value::Null);
+        }
+        if value.is_function() || value.is_symbol() {
+            let kind = value.type_of(scope).to_rust_string_lossy(scope);
+            bail!(generic_error(format!("Cannot convert a {kind} to JSON.")));
+        }
+
+        let tc_scope = &mut v8::TryCatch::new(scope);
+
+        // Values like BigInts and circular objects make `JSON.stringify` throw.
+        let json = match v8::json::stringify(tc_scope, value) {
+            Some(json) => json.to_rust_string_lossy(tc_scope),
+            None => {
+                let exception = tc_scope.exception().unwrap();
+                let exception = JsError::from_v8_exception(tc_scope, exception, None);
+                bail!(exception);
+            }
+        };
+
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Runs the event-loop until the given value settles (if it's a promise).
+    fn resolve_value(
+        &mut self,
+        value: v8::Global<v8::Value>,
+    ) -> Result<v8::Global<v8::Value>, Error> {
+        let promise = {
+            let scope = &mut self.handle_scope();
+            let local = v8::Local::new(scope, &value);
+            let promise = match v8::Local::<v8::Promise>::try_from(local) {
+                Ok(promise) => promise,
+                Err(_) => return Ok(value),
+            };
+            // Note: The rejection is handled below, so it must not be reported
+            // as an unhandled one in the meantime.
+            let noop =
+                |_: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, _: v8::ReturnValue| {};
+            let noop = v8::Function::new(scope, noop).unwrap();
+            promise.catch(scope, noop);
+            v8::Global::new(scope, promise)
+        };
+
+        let state = |runtime: &mut JsRuntime| {
+            let scope = &mut runtime.handle_scope();
+            promise.open(scope).state()
+        };
+
+        // Settle promises that only wait on micro-tasks.
+        run_next_tick_callbacks(&mut self.handle_scope());
+        self.check_fatal_error()?;
+
+        while state(self) == v8::PromiseState::Pending {
+            if !self.has_pending_work() {
+                bail!(generic_error("The promise never settled."));
+            }
+            self.tick_event_loop();
+            self.check_fatal_error()?;
+
+            if let Some(error) = check_exceptions(&mut self.handle_scope()) {
+                bail!(error);
+            }
+        }
+
+        let scope = &mut self.handle_scope();
+        let promise = v8::Local::new(scope, &promise);
+        let result = promise.result(scope);
+
+        if promise.state() == v8::PromiseState::Rejected {
+            bail!(JsError::from_v8_exception(scope, result, None));
+        }
+
+        Ok(v8::Global::new(scope, result))
+    }
+
     /// Executes JavaScript code as ES module.
     pub fn execute_module(&mut self, filename: &str, source: Option<&str>) -> Result<(), Error> {
+        self.load_module(filename, source, true).map(|_| ())
+    }
+
+    /// Executes a module before the main one (e.g. to patch globals).
+    ///
+    /// Note: The event-loop is ticked until the module is evaluated, so modules
+    /// are preloaded in order and before the main module starts loading.
+    pub fn preload_module(&mut self, filename: &str) -> Result<(), Error> {
+        let graph_rc = self.load_module(filename, None, false)?;
+        let is_pending = |runtime: &mut JsRuntime| {
+            let state_rc = runtime.get_state();
+            let state = state_rc.borrow();
+            let pending = &state.module_map.pending;
+            pending.iter().any(|graph| Rc::ptr_eq(graph, &graph_rc))
+        };
+
+        while is_pending(self) {
+            self.tick_event_loop();
+            self.check_fatal_error()?;
+        }
+
+        // Report any unhandled promise rejections.
+        if let Some(error) = check_exceptions(&mut self.handle_scope()) {
+            bail!(error);
+        }
+
+        Ok(())
+    }
+
+    /// Starts loading a module (and its static imports) using the event-loop.
+    fn load_module(
+        &mut self,
+        filename: &str,
+        source: Option<&str>,
+        is_main: bool,
+    ) -> Result<Rc<RefCell<ModuleGraph>>, Error> {
         // Get a reference to v8's scope.
         let scope = &mut self.handle_scope();
         let state_rc = JsRuntime::state(scope);
@@ -312,7 +690,7 @@ impl JsRuntime {
         // location passed as parameter as an ES module.
         let path = match source.is_some() {
             true => filename.to_string(),
-            false => unwrap_or_exit(resolve_import(None, filename, false, None)),
+            false => resolve_import(None, filename, false, None)?,
         };
 
         // Create static import module graph.
@@ -320,6 +698,10 @@ impl JsRuntime {
         let graph_rc = Rc::new(RefCell::new(graph));
         let status = ModuleStatus::Fetching;
 
+        if is_main {
+            state.module_map.set_main(&path);
+        }
+
         state.module_map.pending.push(Rc::clone(&graph_rc));
         state.module_map.seen.insert(path.clone(), status);
 
@@ -328,23 +710,26 @@ impl JsRuntime {
             state.pending_futures.push(Box::new(EsModuleFuture {
                 path,
                 module: Rc::clone(&graph_rc.borrow().root_rc),
-                maybe_result: Some(Ok(bincode::serialize(&source).unwrap())),
+                maybe_result: LoadedModule::from_source(source),
             }));
-            return Ok(());
+            return Ok(graph_rc);
         }
 
         /*  Use the event-loop to asynchronously load the requested module. */
 
         let task = {
             let specifier = path.clone();
-            move || match load_import(&specifier, true) {
-                anyhow::Result::Ok(source) => Some(Ok(bincode::serialize(&source).unwrap())),
-                Err(e) => Some(Result::Err(e)),
-            }
+            let trace = state.options.trace_imports;
+            let import_map = state.options.import_map.as_ref();
+            let integrity = import_map.and_then(|map| map.integrity(&specifier));
+            catch_panics(move || {
+                load_import_task(&specifier, &Reload::All, integrity.as_deref(), trace)
+            })
         };
 
         let task_cb = {
             let state_rc = state_rc.clone();
+            let graph_rc = Rc::clone(&graph_rc);
             move |_: LoopHandle, maybe_result: TaskResult| {
                 let mut state = state_rc.borrow_mut();
                 let future = EsModuleFuture {
@@ -358,14 +743,25 @@ impl JsRuntime {
 
         state.handle.spawn(task, Some(task_cb));
 
-        Ok(())
+        Ok(graph_rc)
     }
 
-    /// Runs a single tick of the event-loop.
+    /// Runs a single tick of the event-loop (stopping early on a fatal error).
     pub fn tick_event_loop(&mut self) {
+        if cfg!(debug_assertions) {
+            trace!("Event-loop tick");
+        }
         run_next_tick_callbacks(&mut self.handle_scope());
+        if self.has_fatal_error() {
+            return;
+        }
         self.fast_forward_imports();
+        if self.has_fatal_error() {
+            return;
+        }
         self.event_loop.tick();
+        poll::queue_ready_events(&mut self.get_state().borrow_mut());
+        timers::queue_expired_timers(&mut self.get_state().borrow_mut());
         self.run_pending_futures();
     }
 
@@ -377,35 +773,159 @@ impl JsRuntime {
     }
 
     /// Runs the event-loop until no more pending events exists.
-    pub fn run_event_loop(&mut self) {
+    ///
+    /// Returns the first uncaught exception (or unhandled rejection), leaving it
+    /// up to the caller to report it and decide whether to exit the process.
+    pub fn run_event_loop(&mut self) -> Result<(), Error> {
+        let result = self.run_event_loop_until_idle();
+
+        // Note: The reports are written before the context goes away, even when
+        // the program failed (or asked to exit).
+        self.write_reports();
+        result?;
+
+        // We can now notify debugger that the program has finished running
+        // and we're ready to exit the process.
+        if let Some(inspector) = self.inspector() {
+            let context = self.context();
+            let scope = &mut self.handle_scope();
+            inspector.borrow_mut().context_destroyed(scope, context);
+        }
+
+        Ok(())
+    }
+
+    /// Ticks the event-loop until no more pending events exists (or an error
+    /// stops the runtime).
+    fn run_event_loop_until_idle(&mut self) -> Result<(), Error> {
         // Check for pending devtools messages.
         self.poll_inspect_session();
         // Run callbacks/promises from next-tick and micro-task queues.
         run_next_tick_callbacks(&mut self.handle_scope());
+        self.check_fatal_error()?;
+
+        loop {
+            while self.has_pending_work() {
+                // Check for pending devtools messages.
+                self.poll_inspect_session();
+                // Tick the event-loop one cycle.
+                self.tick_event_loop();
+                self.check_fatal_error()?;
+
+                // Report any unhandled promise rejections.
+                if let Some(error) = check_exceptions(&mut self.handle_scope()) {
+                    bail!(error);
+                }
+            }
 
-        while self.event_loop.has_pending_events()
-            || self.has_promise_rejections()
-            || self.isolate.has_pending_background_tasks()
-            || self.has_pending_imports()
-            || self.has_next_tick_callbacks()
-        {
-            // Check for pending devtools messages.
-            self.poll_inspect_session();
-            // Tick the event-loop one cycle.
-            self.tick_event_loop();
+            // Note: The `beforeExit` listeners may schedule more work, which
+            // keeps the event-loop alive (and the event will fire again).
+            emit_before_exit(&mut self.handle_scope());
+            run_next_tick_callbacks(&mut self.handle_scope());
+            self.check_fatal_error()?;
 
-            // Report any unhandled promise rejections.
-            if let Some(error) = check_exceptions(&mut self.handle_scope()) {
-                report_and_exit(error);
+            if !self.has_pending_work() {
+                return Ok(());
             }
         }
+    }
 
-        // We can now notify debugger that the program has finished running
-        // and we're ready to exit the process.
-        if let Some(inspector) = self.inspector() {
-            let context = self.context();
-            let scope = &mut self.handle_scope();
-            inspector.borrow_mut().context_destroyed(scope, context);
+    /// Returns if an error has stopped the runtime.
+    pub fn has_fatal_error(&self) -> bool {
+        self.state.borrow().fatal_error.is_some()
+    }
+
+    /// Takes the error that stopped the runtime (if any), so it can be used again.
+    pub fn take_fatal_error(&mut self) -> Option<Error> {
+        let error = self.state.borrow_mut().fatal_error.take()?;
+        // Note: The execution is terminated when JS reports a fatal error.
+        self.isolate.cancel_terminate_execution();
+        Some(error)
+    }
+
+    /// Returns the error that stopped the runtime as a `Result`.
+    fn check_fatal_error(&mut self) -> Result<(), Error> {
+        match self.take_fatal_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the requested CPU profile and import traces (only the first time
+    /// it's called, since the profiler is stopped).
+    ///
+    /// Note: `run_event_loop` calls it on every exit path, so embedders only
+    /// need it when the event-loop never runs (e.g. the main module failed).
+    pub fn write_reports(&mut self) {
+        self.write_cpu_profile();
+        self.report_import_traces();
+    }
+
+    /// Stops the CPU profiler (if running) and writes the folded stacks to disk.
+    fn write_cpu_profile(&mut self) {
+        let path = match self.get_state().borrow_mut().options.prof.take() {
+            Some(path) => path,
+            None => return,
+        };
+
+        let inspector = self.inspector().unwrap();
+        let profile = inspector.borrow_mut().stop_profiling();
+
+        if let Err(e) = fs::write(&path, profile.unwrap_or_default()) {
+            let message = format!("Failed to write CPU profile: {e}");
+            eprintln!("{}", generic_error(message));
+        }
+    }
+
+    /// Prints a summary (or writes a JSON file) of the traced module loads.
+    fn report_import_traces(&mut self) {
+        let state_rc = self.get_state();
+        let mut state = state_rc.borrow_mut();
+
+        if !std::mem::take(&mut state.options.trace_imports) {
+            return;
+        }
+
+        let mut traces: Vec<&ImportTrace> = state.module_map.traces.iter().collect();
+
+        // Write the traces as JSON if a file was specified.
+        if let Some(path) = state.options.trace_imports_file.as_ref() {
+            let json = serde_json::to_string_pretty(&traces).unwrap();
+            if let Err(e) = fs::write(path, json) {
+                let message = format!("Failed to write import traces: {e}");
+                eprintln!("{}", generic_error(message));
+            }
+            return;
+        }
+
+        // Show the slowest module loads first.
+        traces.sort_by(|a, b| (b.fetch_ms + b.compile_ms).total_cmp(&(a.fetch_ms + a.compile_ms)));
+
+        let fetch_ms: f64 = traces.iter().map(|trace| trace.fetch_ms).sum();
+        let compile_ms: f64 = traces.iter().map(|trace| trace.compile_ms).sum();
+
+        eprintln!(
+            "{} {} modules (fetch: {:.1} ms, compile: {:.1} ms)",
+            "Imports".bold(),
+            traces.len(),
+            fetch_ms,
+            compile_ms
+        );
+
+        for trace in traces {
+            let cache = match trace.cache_hit {
+                Some(true) => "hit",
+                Some(false) => "miss",
+                None => "-",
+            };
+            eprintln!(
+                "  {:<4} {:<4} {:>9.1} ms {:>9.1} ms  {}",
+                trace.loader,
+                cache,
+                trace.fetch_ms,
+                trace.compile_ms,
+                trace.path.dimmed()
+            );
         }
     }
 
@@ -428,7 +948,11 @@ impl JsRuntime {
         for mut fut in futures {
             fut.run(scope);
             if let Some(error) = check_exceptions(scope) {
-                report_and_exit(error);
+                state_rc.borrow_mut().set_fatal_error(error);
+            }
+            // Note: The remaining futures are dropped since the runtime is stopped.
+            if state_rc.borrow().fatal_error.is_some() {
+                break;
             }
             run_next_tick_callbacks(scope);
         }
@@ -507,7 +1031,8 @@ impl JsRuntime {
                 assert!(tc_scope.has_caught());
                 let exception = tc_scope.exception().unwrap();
                 let exception = JsError::from_v8_exception(tc_scope, exception, None);
-                report_and_exit(exception);
+                state_rc.borrow_mut().set_fatal_error(exception);
+                return;
             }
 
             let _ = module.evaluate(tc_scope);
@@ -527,7 +1052,8 @@ impl JsRuntime {
                 drop(state);
 
                 if let Some(error) = check_exceptions(tc_scope) {
-                    report_and_exit(error);
+                    state_rc.borrow_mut().set_fatal_error(error);
+                    return;
                 }
             }
 
@@ -563,6 +1089,73 @@ impl JsRuntime {
     pub fn has_next_tick_callbacks(&mut self) -> bool {
         !self.get_state().borrow().next_tick_queue.is_empty()
     }
+
+    /// Checks if there is any work left that keeps the event-loop alive.
+    pub fn has_pending_work(&mut self) -> bool {
+        self.event_loop.has_pending_events()
+            || self.has_promise_rejections()
+            || self.isolate.has_pending_background_tasks()
+            || self.has_pending_imports()
+            || self.has_next_tick_callbacks()
+            || self.get_state().borrow().poller.is_active()
+    }
+
+    /// Checks if there is work in-flight (thread-pool tasks, their callbacks, or
+    /// next-tick callbacks), ignoring long-lived handles like timers and servers.
+    pub fn has_inflight_work(&mut self) -> bool {
+        let state_rc = self.get_state();
+        let state = state_rc.borrow();
+        state.limiter.in_flight() > 0
+            || !state.pending_futures.is_empty()
+            || !state.next_tick_queue.is_empty()
+            || state.module_map.has_pending_imports()
+    }
+
+    /// Ticks the event-loop until the in-flight work is flushed (so resources
+    /// can close cleanly on shutdown), or until the drain timeout is reached.
+    ///
+    /// Returns `false` if the timeout was reached (or the runtime stopped).
+    pub fn drain(&mut self) -> bool {
+        const DEFAULT_DRAIN_TIMEOUT: u64 = 5000;
+
+        let timeout = self.get_state().borrow().options.drain_timeout;
+        let timeout = Duration::from_millis(timeout.unwrap_or(DEFAULT_DRAIN_TIMEOUT));
+        let deadline = Instant::now() + timeout;
+
+        // Note: A stuck task would leave the event-loop waiting for I/O, so it's
+        // woken up at the deadline (unless draining finishes first).
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let handle = self.event_loop.interrupt_handle();
+        thread::spawn(move || {
+            if done_rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                handle.interrupt();
+            }
+        });
+
+        run_next_tick_callbacks(&mut self.handle_scope());
+
+        while self.has_inflight_work() && !self.has_fatal_error() {
+            if Instant::now() >= deadline {
+                break;
+            }
+            self.tick_event_loop();
+        }
+
+        drop(done_tx);
+        !self.has_inflight_work() && !self.has_fatal_error()
+    }
+
+    /// Returns the exit code requested by the program (via `process.exitCode`).
+    pub fn exit_code(&mut self) -> i32 {
+        let scope = &mut self.handle_scope();
+        let process = get_process_object(scope);
+        let key = v8::String::new(scope, "exitCode").unwrap();
+
+        process
+            .get(scope, key.into())
+            .and_then(|code| code.int32_value(scope))
+            .unwrap_or_default()
+    }
 }
 
 impl std::ops::Drop for JsRuntime {
@@ -613,41 +1206,142 @@ impl JsRuntime {
     }
 }
 
+/// Returns the V8 flags given by the user (from `DUNE_V8_FLAGS` and the options).
+fn user_v8_flags(options: &JsRuntimeOptions) -> Vec<String> {
+    let env_flags = env::var(V8_FLAGS_ENV).unwrap_or_default();
+    let env_flags = env_flags.split_whitespace().map(String::from);
+    let option_flags = options.v8_flags.iter().map(|flag| flag.trim().to_string());
+
+    env_flags
+        .chain(option_flags)
+        .filter(|flag| !flag.is_empty())
+        .collect()
+}
+
+/// Passes the user's flags to V8, warning about the ones it doesn't accept.
+fn set_user_v8_flags(flags: Vec<String>) {
+    let warn = |message: String| eprintln!("{}: {message}", "Warning".yellow().bold());
+    let (flags, invalid): (Vec<String>, Vec<String>) = flags
+        .into_iter()
+        .partition(|flag| flag.starts_with('-') && !flag.contains(char::is_whitespace));
+
+    for flag in invalid {
+        warn(format!("Ignoring invalid V8 flag \"{flag}\""));
+    }
+
+    if flags.is_empty() {
+        return;
+    }
+
+    // Note: V8 expects the first argument to be the program's name, and
+    // returns it along with the unrecognized flags.
+    let args = std::iter::once("dune".to_string()).chain(flags).collect();
+    let unrecognized = v8::V8::set_flags_from_command_line(args);
+
+    for flag in unrecognized.into_iter().skip(1) {
+        warn(format!("Ignoring unknown V8 flag \"{flag}\""));
+    }
+}
+
+/// Returns the global `process` object.
+fn get_process_object<'s>(scope: &mut v8::HandleScope<'s>) -> v8::Local<'s, v8::Object> {
+    let context = scope.get_current_context();
+    let global = context.global(scope);
+    let key = v8::String::new(scope, "process").unwrap();
+    let process = global.get(scope, key.into()).unwrap();
+
+    v8::Local::<v8::Object>::try_from(process).unwrap()
+}
+
+/// Emits the `beforeExit` event on the process object (passing the exit code).
+fn emit_before_exit(scope: &mut v8::HandleScope) {
+    let process = get_process_object(scope);
+    let key = v8::String::new(scope, "emit").unwrap();
+    let emit = process.get(scope, key.into()).unwrap();
+    let emit = v8::Local::<v8::Function>::try_from(emit).unwrap();
+
+    let key = v8::String::new(scope, "exitCode").unwrap();
+    let code = process.get(scope, key.into()).unwrap();
+    let code = v8::Integer::new(scope, code.int32_value(scope).unwrap_or_default());
+    let event = v8::String::new(scope, "beforeExit").unwrap();
+
+    let tc_scope = &mut v8::TryCatch::new(scope);
+
+    emit.call(tc_scope, process.into(), &[event.into(), code.into()]);
+
+    // On exception, report it the same way as any other uncaught error.
+    if tc_scope.has_caught() {
+        let exception = tc_scope.exception().unwrap();
+        let exception = v8::Global::new(tc_scope, exception);
+        let state_rc = JsRuntime::state(tc_scope);
+        let mut state = state_rc.borrow_mut();
+        state.exceptions.capture_exception(exception);
+
+        drop(state);
+
+        if let Some(error) = check_exceptions(tc_scope) {
+            state_rc.borrow_mut().set_fatal_error(error);
+        }
+    }
+}
+
 /// Runs callbacks stored in the next-tick queue.
 fn run_next_tick_callbacks(scope: &mut v8::HandleScope) {
     let state_rc = JsRuntime::state(scope);
-    let callbacks: NextTickQueue = state_rc.borrow_mut().next_tick_queue.drain(..).collect();
-
     let undefined = v8::undefined(scope);
     let tc_scope = &mut v8::TryCatch::new(scope);
 
-    for (cb, params) in callbacks {
-        // Create a local handle for the callback and its parameters.
-        let cb = v8::Local::new(tc_scope, cb);
-        let args: Vec<v8::Local<v8::Value>> = params
-            .iter()
-            .map(|arg| v8::Local::new(tc_scope, arg))
-            .collect();
+    loop {
+        // Stop running callbacks once the runtime is stopped.
+        if state_rc.borrow().fatal_error.is_some() {
+            return;
+        }
+
+        let callbacks: NextTickQueue = state_rc.borrow_mut().next_tick_queue.drain(..).collect();
 
-        cb.call(tc_scope, undefined.into(), &args);
+        // Note: Following Node.js, the next-tick queue is drained completely (including
+        // callbacks scheduled from other next-tick callbacks) before any promise job
+        // runs. Promise jobs may schedule new next-tick callbacks, so repeat until both
+        // queues are empty.
+        if callbacks.is_empty() {
+            tc_scope.perform_microtask_checkpoint();
+            if state_rc.borrow().next_tick_queue.is_empty() {
+                break;
+            }
+            continue;
+        }
 
-        // On exception, report it and handle the error.
-        if tc_scope.has_caught() {
-            let exception = tc_scope.exception().unwrap();
-            let exception = v8::Global::new(tc_scope, exception);
-            let mut state = state_rc.borrow_mut();
-            state.exceptions.capture_exception(exception);
+        for (cb, params) in callbacks {
+            // Create a local handle for the callback and its parameters.
+            let cb = v8::Local::new(tc_scope, cb);
+            let args: Vec<v8::Local<v8::Value>> = params
+                .iter()
+                .map(|arg| v8::Local::new(tc_scope, arg))
+                .collect();
 
-            drop(state);
+            cb.call(tc_scope, undefined.into(), &args);
 
-            // Check for uncaught errors (capture callbacks might be in place).
-            if let Some(error) = check_exceptions(tc_scope) {
-                report_and_exit(error);
+            if state_rc.borrow().fatal_error.is_some() {
+                return;
+            }
+
+            // On exception, report it and handle the error.
+            if tc_scope.has_caught() {
+                let exception = tc_scope.exception().unwrap();
+                let exception = v8::Global::new(tc_scope, exception);
+                let mut state = state_rc.borrow_mut();
+                state.exceptions.capture_exception(exception);
+
+                drop(state);
+
+                // Check for uncaught errors (capture callbacks might be in place).
+                if let Some(error) = check_exceptions(tc_scope) {
+                    state_rc.borrow_mut().set_fatal_error(error);
+                    return;
+                }
             }
         }
     }
-
-    tc_scope.perform_microtask_checkpoint();
 }
 
 // Returns an error if an uncaught exception or unhandled rejection has been captured.
@@ -748,3 +1442,506 @@ pub fn check_exceptions(scope: &mut v8::HandleScope) -> Option<JsError> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::set_internal_ref_with_finalizer;
+    use crate::errors::ExitRequest;
+    use crate::limiter::TaskClass;
+    use assert_fs::prelude::*;
+    use serde_json::json;
+    use std::cell::Cell;
+
+    /// Example host function, returning the seconds since the Unix epoch.
+    fn host_now(
+        scope: &mut v8::HandleScope,
+        _: v8::FunctionCallbackArguments,
+        mut rv: v8::ReturnValue,
+    ) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        rv.set(v8::Number::new(scope, now.as_secs_f64()).into());
+    }
+
+    #[test]
+    fn test_extensions() {
+        let options = JsRuntimeOptions {
+            extensions: vec![Extension {
+                name: "host",
+                functions: vec![("now", host_now)],
+            }],
+            ..Default::default()
+        };
+
+        let mut runtime = JsRuntime::with_options(options).unwrap();
+        let value = runtime.eval_to_json("typeof host.now() === 'number' && host.now() > 0");
+        assert_eq!(value.unwrap(), json!(true));
+    }
+
+    /// Resolves (or rejects) a promise with the result of a thread-pool task.
+    struct TaskFuture {
+        promise: v8::Global<v8::PromiseResolver>,
+        maybe_result: TaskResult,
+    }
+
+    impl JsFuture for TaskFuture {
+        fn run(&mut self, scope: &mut v8::HandleScope) {
+            let promise = self.promise.open(scope);
+            match self.maybe_result.take().unwrap() {
+                Result::Ok(_) => promise.resolve(scope, v8::undefined(scope).into()),
+                Result::Err(e) => {
+                    let message = v8::String::new(scope, &e.to_string()).unwrap();
+                    let exception = v8::Exception::error(scope, message);
+                    promise.reject(scope, exception)
+                }
+            };
+        }
+    }
+
+    /// Example host function, spawning a thread-pool task that panics.
+    fn host_panic(
+        scope: &mut v8::HandleScope,
+        _: v8::FunctionCallbackArguments,
+        mut rv: v8::ReturnValue,
+    ) {
+        let promise_resolver = v8::PromiseResolver::new(scope).unwrap();
+        let promise = promise_resolver.get_promise(scope);
+
+        let state_rc = JsRuntime::state(scope);
+        let state = state_rc.borrow();
+
+        let task = || -> TaskResult { panic!("Invalid bytes") };
+        let task_cb = {
+            let promise = v8::Global::new(scope, promise_resolver);
+            let state_rc = state_rc.clone();
+            move |_: LoopHandle, maybe_result: TaskResult| {
+                let future = TaskFuture {
+                    promise,
+                    maybe_result,
+                };
+                state_rc.borrow_mut().pending_futures.push(Box::new(future));
+            }
+        };
+
+        state
+            .limiter
+            .spawn(&state.handle, TaskClass::Fs, task, Some(task_cb));
+
+        rv.set(promise.into());
+    }
+
+    #[test]
+    fn test_task_panic_rejects_promise() {
+        let options = JsRuntimeOptions {
+            extensions: vec![Extension {
+                name: "host",
+                functions: vec![("panic", host_panic)],
+            }],
+            ..Default::default()
+        };
+
+        let mut runtime = JsRuntime::with_options(options).unwrap();
+        let source = "host.panic().then(() => 'resolved', (e) => e.message)";
+        let value = runtime.eval_to_json(source);
+
+        let expected = "Thread-pool task panicked: Invalid bytes";
+        assert_eq!(value.unwrap(), json!(expected));
+    }
+
+    #[test]
+    fn test_internal_ref_finalizer() {
+        /// Counts how many times it has been dropped.
+        struct Tracked(Rc<Cell<usize>>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let mut runtime = JsRuntime::new().unwrap();
+        let dropped = Rc::new(Cell::new(0));
+
+        {
+            let scope = &mut runtime.handle_scope();
+            for _ in 0..10 {
+                let template = v8::ObjectTemplate::new(scope);
+                template.set_internal_field_count(1);
+                let wrapper = template.new_instance(scope).unwrap();
+                set_internal_ref_with_finalizer(scope, wrapper, 0, Tracked(dropped.clone()));
+            }
+        }
+
+        // Note: A forced (full) GC runs the finalizers synchronously.
+        runtime.isolate.low_memory_notification();
+        assert_eq!(dropped.get(), 10);
+    }
+
+    #[test]
+    fn test_eval_to_json() {
+        let mut runtime = JsRuntime::new().unwrap();
+
+        let value = runtime.eval_to_json("({ a: [1, 'b', null], c: 1 + 1 })");
+        assert_eq!(value.unwrap(), json!({ "a": [1, "b", null], "c": 2 }));
+
+        let value = runtime.eval_to_json("undefined");
+        assert_eq!(value.unwrap(), json!(null));
+
+        let value = runtime.eval_to_json("new Promise((res) => setTimeout(() => res(42), 1))");
+        assert_eq!(value.unwrap(), json!(42));
+    }
+
+    #[test]
+    fn test_eval_to_json_errors() {
+        let mut runtime = JsRuntime::new().unwrap();
+
+        let error = runtime.eval_to_json("() => {}").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("Cannot convert a function to JSON."));
+
+        let error = runtime.eval_to_json("Promise.reject(new Error('Oops'))");
+        assert!(error.unwrap_err().to_string().contains("Oops"));
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut runtime = JsRuntime::new().unwrap();
+        let source =
+            "import('fs').then((fs) => fs.readFile('Cargo.toml')).then(() => (done = true))";
+
+        runtime.execute_script("<anonymous>", source).unwrap();
+
+        assert!(runtime.has_inflight_work());
+        assert!(runtime.drain());
+        assert!(!runtime.has_inflight_work());
+
+        let value = runtime.eval_to_json("globalThis.done");
+        assert_eq!(value.unwrap(), json!(true));
+    }
+
+    #[test]
+    fn test_abort_signal_timeout_is_unref() {
+        let mut runtime = JsRuntime::new().unwrap();
+        let source = "globalThis.signal = AbortSignal.timeout(30000);";
+        let start = Instant::now();
+
+        runtime.execute_script("<anonymous>", source).unwrap();
+        runtime.run_event_loop().unwrap();
+
+        // The pending timeout didn't hold the event-loop alive.
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let value = runtime.eval_to_json("globalThis.signal.aborted");
+        assert_eq!(value.unwrap(), json!(false));
+    }
+
+    #[test]
+    fn test_unref_timeout_fires_while_alive() {
+        let mut runtime = JsRuntime::new().unwrap();
+        let source = "
+            const signal = AbortSignal.timeout(10);
+            signal.onabort = () => (globalThis.aborted = true);
+            setTimeout(() => {}, 200);
+        ";
+
+        runtime.execute_script("<anonymous>", source).unwrap();
+        runtime.run_event_loop().unwrap();
+
+        let value = runtime.eval_to_json("globalThis.aborted");
+        assert_eq!(value.unwrap(), json!(true));
+    }
+
+    #[test]
+    fn test_import_meta_main() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let files = [
+            (
+                "entry.js",
+                "import { isMain } from './dep.js';
+                globalThis.meta = { entry: import.meta.main, dep: isMain };",
+            ),
+            ("dep.js", "export const isMain = import.meta.main;"),
+        ];
+
+        for (path, source) in files {
+            temp_dir.child(path).write_str(source).unwrap();
+        }
+
+        let entry = temp_dir.child("entry.js").display().to_string();
+        let mut runtime = JsRuntime::new().unwrap();
+
+        runtime.execute_module(&entry, None).unwrap();
+        runtime.run_event_loop().unwrap();
+
+        // Only the entry script is the main module.
+        let value = runtime.eval_to_json("globalThis.meta");
+        assert_eq!(value.unwrap(), json!({ "entry": true, "dep": false }));
+    }
+
+    /// Serves the same JavaScript source to every request (in the background).
+    fn serve_module(source: &'static str) -> String {
+        use std::io::Read;
+        use std::io::Write;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/javascript\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{source}",
+                    source.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        format!("http://{address}/mod.js")
+    }
+
+    #[test]
+    fn test_frozen_integrity_mismatch() {
+        let url = serve_module("export default 42;");
+        let import_map = json!({
+            "imports": {},
+            "integrity": { &url: "sha256-AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=" }
+        });
+
+        let options = JsRuntimeOptions {
+            frozen: true,
+            import_map: Some(ImportMap::parse_from_json(&import_map.to_string()).unwrap()),
+            ..Default::default()
+        };
+
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let entry = temp_dir.child("entry.js");
+        entry
+            .write_str(&format!("import value from '{url}';"))
+            .unwrap();
+
+        let mut runtime = JsRuntime::with_options(options).unwrap();
+        let entry = entry.display().to_string();
+
+        runtime.execute_module(&entry, None).unwrap();
+
+        // The (async) integrity check rejects the served source.
+        let error = runtime.run_event_loop().unwrap_err();
+        assert!(error.to_string().contains("Integrity check failed"));
+    }
+
+    /// Serves the given JavaScript sources by path, recording the requested paths.
+    fn serve_modules(
+        sources: &'static [(&'static str, &'static str)],
+    ) -> (String, mpsc::Receiver<String>) {
+        use std::io::Read;
+        use std::io::Write;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (requests_tx, requests_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 1024];
+                let length = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..length]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let source = sources
+                    .iter()
+                    .find(|(name, _)| path == format!("/{name}"))
+                    .map_or("", |(_, source)| source);
+
+                let _ = requests_tx.send(path);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/javascript\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{source}",
+                    source.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (format!("http://{address}"), requests_rx)
+    }
+
+    #[test]
+    fn test_frozen_import_order() {
+        const SOURCES: &[(&str, &str)] = &[
+            (
+                "a.js",
+                "import './d.js'; (globalThis.order ??= []).push('a');",
+            ),
+            ("b.js", "(globalThis.order ??= []).push('b');"),
+            ("c.js", "(globalThis.order ??= []).push('c');"),
+            ("d.js", "(globalThis.order ??= []).push('d');"),
+        ];
+
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let mut runs = vec![];
+
+        // Note: Every run gets a new server (and so a new origin), so the remote
+        // modules are fetched again instead of read from the cache.
+        for _ in 0..3 {
+            let (base, requests) = serve_modules(SOURCES);
+            let entry = temp_dir.child("entry.js");
+            let source = format!(
+                "import '{base}/c.js';
+                 import '{base}/a.js';
+                 import '{base}/b.js';"
+            );
+            entry.write_str(&source).unwrap();
+
+            let options = JsRuntimeOptions {
+                frozen: true,
+                ..Default::default()
+            };
+
+            let mut runtime = JsRuntime::with_options(options).unwrap();
+            let entry = entry.display().to_string();
+
+            runtime.execute_module(&entry, None).unwrap();
+            runtime.run_event_loop().unwrap();
+
+            let order = runtime.eval_to_json("globalThis.order").unwrap();
+            let fetches: Vec<String> = requests.try_iter().collect();
+            runs.push((fetches, order));
+        }
+
+        // Siblings are fetched sorted by their specifier, one at a time.
+        let fetches = ["/a.js", "/b.js", "/c.js", "/d.js"];
+        let order = json!(["c", "d", "a", "b"]);
+
+        for run in runs {
+            assert_eq!(run, (fetches.map(String::from).to_vec(), order.clone()));
+        }
+    }
+
+    #[test]
+    fn test_startup_snapshot() {
+        let creator = JsRuntime::for_snapshot().unwrap();
+        let blob = creator.create_snapshot().unwrap();
+        let blob: &'static [u8] = Box::leak(blob.into_boxed_slice());
+
+        let mode = StartupMode::Snapshot(blob);
+        let mut runtime = JsRuntime::create(JsRuntimeOptions::default(), mode).unwrap();
+
+        // The core environment (and its lazy globals) come from the snapshot.
+        let value = runtime.eval_to_json("typeof console.log + typeof TextEncoder");
+        assert_eq!(value.unwrap(), json!("functionfunction"));
+
+        // Core modules resolve to the instances restored into the module-map.
+        let source = "
+            import { EventEmitter } from 'events';
+            import path from 'path';
+            const emitter = new EventEmitter();
+            globalThis.result = [path.basename('/a/b.js'), emitter instanceof EventEmitter];
+        ";
+
+        runtime.execute_module("snapshot.js", Some(source)).unwrap();
+        runtime.run_event_loop().unwrap();
+
+        let value = runtime.eval_to_json("globalThis.result");
+        assert_eq!(value.unwrap(), json!(["b.js", true]));
+    }
+
+    #[test]
+    fn test_preload_module() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let files = [
+            ("first.js", "globalThis.order = ['first'];"),
+            ("second.js", "globalThis.order.push('second');"),
+            ("failing.js", "throw new Error('Preload failed');"),
+        ];
+
+        for (path, source) in files {
+            temp_dir.child(path).write_str(source).unwrap();
+        }
+
+        let path = |name: &str| temp_dir.child(name).display().to_string();
+        let mut runtime = JsRuntime::new().unwrap();
+
+        runtime.preload_module(&path("first.js")).unwrap();
+        runtime.preload_module(&path("second.js")).unwrap();
+
+        let value = runtime.eval_to_json("globalThis.order");
+        assert_eq!(value.unwrap(), json!(["first", "second"]));
+
+        let error = runtime.preload_module(&path("failing.js")).unwrap_err();
+        assert!(error.to_string().contains("Preload failed"));
+    }
+
+    #[test]
+    fn test_process_exit_request() {
+        let source = "setTimeout(() => process.exit(3), 0);";
+        let mut runtime = JsRuntime::new().unwrap();
+
+        runtime.execute_module("exit.js", Some(source)).unwrap();
+
+        // The runtime returns the request instead of exiting the process.
+        let error = runtime.run_event_loop().unwrap_err();
+        assert_eq!(error.downcast_ref::<ExitRequest>(), Some(&ExitRequest(3)));
+        assert_eq!(runtime.exit_code(), 3);
+
+        // Scripts that ask to exit return the request as well.
+        let error = runtime.eval_to_json("process.exit(5)").unwrap_err();
+        assert_eq!(error.downcast_ref::<ExitRequest>(), Some(&ExitRequest(5)));
+
+        // The runtime can still run code after a request.
+        assert_eq!(runtime.eval_to_json("1 + 1").unwrap(), json!(2));
+    }
+
+    #[test]
+    fn test_import_traces_on_every_exit() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let files = [
+            ("dep.js", "export default 1;"),
+            ("clean.js", "import './dep.js';"),
+            ("failing.js", "import './dep.js';\nthrow new Error('Oops');"),
+            ("exiting.js", "import './dep.js';\nprocess.exit(2);"),
+        ];
+
+        for (path, source) in files {
+            temp_dir.child(path).write_str(source).unwrap();
+        }
+
+        let run = |name: &str| {
+            let report = temp_dir.child(format!("{name}.json"));
+            let options = JsRuntimeOptions {
+                trace_imports: true,
+                trace_imports_file: Some(report.to_path_buf()),
+                ..Default::default()
+            };
+
+            let mut runtime = JsRuntime::with_options(options).unwrap();
+            let entry = temp_dir.child(name).display().to_string();
+            runtime.execute_module(&entry, None).unwrap();
+
+            let result = runtime.run_event_loop();
+            let report = fs::read_to_string(report.path()).unwrap();
+            let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+            (result, report)
+        };
+
+        for name in ["clean.js", "failing.js", "exiting.js"] {
+            let (result, report) = run(name);
+            assert_eq!(result.is_ok(), name == "clean.js");
+
+            // Both the entry and its import are traced.
+            let paths: Vec<_> = report
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|trace| trace["path"].as_str().unwrap().to_string())
+                .collect();
+
+            assert_eq!(paths.len(), 2);
+            assert!(paths.iter().any(|path| path.ends_with(name)));
+            assert!(paths.iter().any(|path| path.ends_with("dep.js")));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bindings::external_references;
 use crate::bindings::set_function_to;
 use crate::bindings::set_property_to;
 use crate::bindings::throw_exception;
@@ -72,6 +73,11 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![start_signal, cancel_signal]
+}
+
 struct SignalFuture(Rc<v8::Global<v8::Function>>);
 
 impl JsFuture for SignalFuture {
@@ -0,0 +1,35 @@
+// Startup Snapshot
+//
+// Saves the heap of a runtime with the core environment already loaded (see lib/main.js),
+// so new processes deserialize it instead of evaluating every core module again.
+// https://v8.dev/blog/custom-startup-snapshots
+
+use crate::runtime::JsRuntime;
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// The snapshot embedded by the build script (see `scripts/build-snapshot.sh`).
+#[cfg(dune_snapshot)]
+static STARTUP_SNAPSHOT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/dune.snapshot"));
+
+/// Returns the startup snapshot embedded in the binary.
+#[cfg(dune_snapshot)]
+pub fn startup_snapshot() -> Option<&'static [u8]> {
+    Some(STARTUP_SNAPSHOT)
+}
+
+/// Returns the startup snapshot embedded in the binary (built without one).
+#[cfg(not(dune_snapshot))]
+pub fn startup_snapshot() -> Option<&'static [u8]> {
+    None
+}
+
+/// Creates a startup snapshot and writes it to the given file.
+pub fn write_snapshot(path: &Path) -> Result<()> {
+    let runtime = JsRuntime::for_snapshot()?;
+    let blob = runtime.create_snapshot()?;
+
+    fs::write(path, blob)?;
+    Ok(())
+}
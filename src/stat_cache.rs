@@ -0,0 +1,194 @@
+// Stat Cache
+//
+// A bounded and short-lived cache of file-system lookups (metadata and canonical
+// paths) for callers that resolve the same paths over and over again, like the
+// module loader walking a large dependency graph.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::Metadata;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+/// How long a cached lookup is considered fresh.
+const TTL: Duration = Duration::from_secs(1);
+
+/// The maximum number of cached lookups (per lookup kind).
+const CAPACITY: usize = 10_000;
+
+/// A failed lookup, kept so it can be turned back into an `io::Error`.
+#[derive(Clone, Copy)]
+enum Failure {
+    Os(i32),
+    Kind(io::ErrorKind),
+}
+
+impl From<&io::Error> for Failure {
+    fn from(error: &io::Error) -> Self {
+        match error.raw_os_error() {
+            Some(code) => Failure::Os(code),
+            None => Failure::Kind(error.kind()),
+        }
+    }
+}
+
+impl From<Failure> for io::Error {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Os(code) => io::Error::from_raw_os_error(code),
+            Failure::Kind(kind) => io::Error::from(kind),
+        }
+    }
+}
+
+struct Entry<T> {
+    value: Result<T, Failure>,
+    created: Instant,
+}
+
+/// Cached lookups of a single kind, keyed by the requested path.
+struct Table<T> {
+    entries: HashMap<PathBuf, Entry<T>>,
+}
+
+impl<T: Clone> Table<T> {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&self, path: &Path) -> Option<io::Result<T>> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.created.elapsed() < TTL)
+            .map(|entry| entry.value.clone().map_err(io::Error::from))
+    }
+
+    fn insert(&mut self, path: &Path, value: &io::Result<T>) {
+        // Note: Missing paths aren't cached, since a file showing up is exactly
+        // what callers (like the module loader) wait to see.
+        if value
+            .as_ref()
+            .is_err_and(|e| e.kind() == io::ErrorKind::NotFound)
+        {
+            self.entries.remove(path);
+            return;
+        }
+
+        // Make room by dropping stale lookups (or everything, as a last resort).
+        if self.entries.len() >= CAPACITY {
+            self.entries
+                .retain(|_, entry| entry.created.elapsed() < TTL);
+        }
+        if self.entries.len() >= CAPACITY {
+            self.entries.clear();
+        }
+
+        let value = value.as_ref().cloned().map_err(Failure::from);
+        let created = Instant::now();
+
+        self.entries
+            .insert(path.to_path_buf(), Entry { value, created });
+    }
+}
+
+lazy_static! {
+    static ref METADATA: Mutex<Table<Metadata>> = Mutex::new(Table::new());
+    static ref REALPATHS: Mutex<Table<PathBuf>> = Mutex::new(Table::new());
+}
+
+/// Runs a lookup through a cache table.
+fn cached<T: Clone>(
+    table: &Mutex<Table<T>>,
+    path: &Path,
+    lookup: impl FnOnce(&Path) -> io::Result<T>,
+) -> io::Result<T> {
+    if let Some(value) = table.lock().unwrap().get(path) {
+        return value;
+    }
+
+    // Note: The lock isn't held while hitting the file-system, so concurrent
+    // lookups (from the thread-pool) don't wait on each other.
+    let value = lookup(path);
+    table.lock().unwrap().insert(path, &value);
+
+    value
+}
+
+/// Returns the (possibly cached) metadata of a path.
+pub fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    cached(&METADATA, path.as_ref(), fs::metadata)
+}
+
+/// Returns the (possibly cached) canonical form of a path.
+pub fn canonicalize<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    cached(&REALPATHS, path.as_ref(), fs::canonicalize)
+}
+
+/// Checks (through the cache) if the path points to a regular file.
+pub fn is_file<P: AsRef<Path>>(path: P) -> bool {
+    metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
+
+/// Drops the cached lookups of a path and everything under it.
+pub fn invalidate<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref();
+
+    // Note: Lookups are keyed by the path as requested, so a relative path also
+    // has to match the (absolute) paths the module loader uses.
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let matches = |key: &Path| key.starts_with(path) || key.starts_with(&absolute);
+
+    METADATA
+        .lock()
+        .unwrap()
+        .entries
+        .retain(|key, _| !matches(key));
+
+    REALPATHS.lock().unwrap().entries.retain(|key, entry| {
+        let target = entry.value.as_ref().ok();
+        !matches(key) && !target.is_some_and(|target| matches(target))
+    });
+}
+
+/// Drops all cached lookups.
+pub fn clear() {
+    METADATA.lock().unwrap().entries.clear();
+    REALPATHS.lock().unwrap().entries.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    #[test]
+    fn test_stat_cache_invalidation() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.child("module.js");
+
+        // Missing paths aren't cached, so new files show up right away.
+        assert!(!is_file(file.path()));
+        file.write_str("export default 42;").unwrap();
+        assert!(is_file(file.path()));
+
+        // Existing paths are cached until invalidated.
+        fs::remove_file(file.path()).unwrap();
+        assert!(is_file(file.path()));
+
+        // Invalidating a parent directory drops the lookups under it.
+        invalidate(dir.path());
+        assert!(!is_file(file.path()));
+        file.write_str("export default 42;").unwrap();
+
+        let realpath = canonicalize(file.path()).unwrap();
+        assert_eq!(realpath, fs::canonicalize(file.path()).unwrap());
+    }
+}
@@ -1,7 +1,14 @@
+use crate::bindings::external_references;
 use crate::bindings::set_function_to;
 use crate::bindings::throw_exception;
+use crate::process::request_exit;
 use std::io;
+use std::io::ErrorKind;
+use std::io::IsTerminal;
 use std::io::Write;
+use terminal_size::terminal_size_of;
+use terminal_size::Height;
+use terminal_size::Width;
 
 pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     // Create local JS object.
@@ -12,19 +19,72 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     set_function_to(scope, target, "read", read);
     set_function_to(scope, target, "clear", clear);
     set_function_to(scope, target, "callConsole", call_console);
+    set_function_to(scope, target, "isColorEnabled", is_color_enabled);
+    set_function_to(scope, target, "columns", columns);
+    set_function_to(scope, target, "rows", rows);
+    set_function_to(scope, target, "isTerminal", is_terminal);
 
     // Return v8 global handle.
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        write,
+        write_error,
+        read,
+        clear,
+        call_console,
+        is_color_enabled,
+        columns,
+        rows,
+        is_terminal
+    ]
+}
+
+/// The outcome of writing to a standard stream.
+#[derive(Debug, PartialEq)]
+enum WriteStatus {
+    Written,
+    // The reading end of the pipe has been closed (EPIPE).
+    Closed,
+}
+
+/// Writes (and flushes) bytes to a stream, detecting closed pipes.
+fn write_stream(stream: &mut impl Write, content: &[u8]) -> io::Result<WriteStatus> {
+    match stream.write_all(content).and_then(|_| stream.flush()) {
+        Ok(_) => Ok(WriteStatus::Written),
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(WriteStatus::Closed),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes bytes to the stdout stream.
+///
+/// Note: When stdout is piped to a program that stops reading early (e.g.
+/// `dune run app.js | head`) no output can be written anymore, so the program
+/// asks to exit quietly, like well-behaved Unix tools do.
+pub fn write_stdout(scope: &mut v8::HandleScope, content: &[u8]) {
+    match write_stream(&mut io::stdout().lock(), content) {
+        Ok(WriteStatus::Written) => {}
+        Ok(WriteStatus::Closed) => request_exit(scope, 0),
+        Err(e) => panic!("failed printing to stdout: {e}"),
+    }
+}
+
+/// Writes bytes to the stderr stream (output to a closed pipe is dropped).
+pub fn write_stderr(content: &[u8]) {
+    if let Err(e) = write_stream(&mut io::stderr().lock(), content) {
+        panic!("failed printing to stderr: {e}");
+    }
+}
+
 /// Writes data to the stdout stream.
 fn write(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
     // Convert string to bytes.
     let content = args.get(0).to_rust_string_lossy(scope);
-    let content = content.as_bytes();
-    // Flush bytes to stdout.
-    io::stdout().write_all(content).unwrap();
-    io::stdout().flush().unwrap();
+    write_stdout(scope, content.as_bytes());
 }
 
 /// Writes data to the stderr stream.
@@ -35,10 +95,7 @@ fn write_error(
 ) {
     // Convert string to bytes.
     let content = args.get(0).to_rust_string_lossy(scope);
-    let content = content.as_bytes();
-    // Flush bytes to stderr.
-    io::stderr().write_all(content).unwrap();
-    io::stderr().flush().unwrap();
+    write_stderr(content.as_bytes());
 }
 
 /// Reads (synchronously) a string from the stdin.
@@ -53,6 +110,77 @@ fn read(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, mut ret:
     ret.set(input.into());
 }
 
+/// Checks if colored output should be used.
+///
+/// Note: This follows the `colored` crate's decision (a terminal, no `NO_COLOR`)
+/// unless it was overridden with the `--color` or `--no-color` flags.
+fn is_color_enabled(
+    scope: &mut v8::HandleScope,
+    _: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let enabled = colored::control::SHOULD_COLORIZE.should_colorize();
+    rv.set(v8::Boolean::new(scope, enabled).into());
+}
+
+/// Returns the width of the terminal the stdout is attached to (if any).
+pub fn stdout_columns() -> Option<usize> {
+    terminal_size_of(io::stdout()).map(|(Width(width), _)| width as usize)
+}
+
+/// Returns the width of the terminal the stderr is attached to (if any).
+pub fn stderr_columns() -> Option<usize> {
+    terminal_size_of(io::stderr()).map(|(Width(width), _)| width as usize)
+}
+
+/// Returns the terminal size (columns, rows) of the stdout (fd 1) or the
+/// stderr (fd 2) stream, if it's attached to a terminal.
+fn terminal_size(fd: i32) -> Option<(usize, usize)> {
+    let size = match fd {
+        2 => terminal_size_of(io::stderr()),
+        _ => terminal_size_of(io::stdout()),
+    };
+    size.map(|(Width(width), Height(height))| (width as usize, height as usize))
+}
+
+/// Gets the terminal width of the stdout (fd 1) or the stderr (fd 2) stream.
+fn columns(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let fd = args.get(0).int32_value(scope).unwrap_or(1);
+
+    match terminal_size(fd) {
+        Some((columns, _)) => rv.set(v8::Integer::new(scope, columns as i32).into()),
+        None => rv.set(v8::undefined(scope).into()),
+    }
+}
+
+/// Gets the terminal height of the stdout (fd 1) or the stderr (fd 2) stream.
+fn rows(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    let fd = args.get(0).int32_value(scope).unwrap_or(1);
+
+    match terminal_size(fd) {
+        Some((_, rows)) => rv.set(v8::Integer::new(scope, rows as i32).into()),
+        None => rv.set(v8::undefined(scope).into()),
+    }
+}
+
+/// Checks if the stdin (fd 0), stdout (fd 1) or stderr (fd 2) is a terminal.
+fn is_terminal(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let is_terminal = match args.get(0).int32_value(scope).unwrap_or(1) {
+        0 => io::stdin().is_terminal(),
+        2 => io::stderr().is_terminal(),
+        _ => io::stdout().is_terminal(),
+    };
+    rv.set(v8::Boolean::new(scope, is_terminal).into());
+}
+
 /// Clears the terminal if the environment allows it.
 fn clear(scope: &mut v8::HandleScope, _: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
     if let Err(e) = clearscreen::clear() {
@@ -84,3 +212,43 @@ fn call_console(
     console_v8_method.call(scope, this.into(), &params);
     console_method.call(scope, this.into(), &params);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use std::process::Stdio;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_to_closed_pipe() {
+        // Spawn a reader that exits after the first few bytes.
+        let mut reader = Command::new("head")
+            .args(["-c", "16"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let mut pipe = reader.stdin.take().unwrap();
+        let chunk = vec![b'x'; 64 * 1024];
+
+        // Note: The first writes may be buffered by the OS before the reader
+        // goes away, so we're writing until the pipe is reported as closed.
+        let status = (0..1024)
+            .map(|_| write_stream(&mut pipe, &chunk).unwrap())
+            .find(|status| *status == WriteStatus::Closed);
+
+        reader.wait().unwrap();
+        assert_eq!(status, Some(WriteStatus::Closed));
+    }
+
+    #[test]
+    fn test_write_to_open_stream() {
+        let mut buffer = vec![];
+        let status = write_stream(&mut buffer, b"hello").unwrap();
+
+        assert_eq!(status, WriteStatus::Written);
+        assert_eq!(buffer, b"hello");
+    }
+}
@@ -0,0 +1,157 @@
+// Encoding APIs
+//
+// This module provides the native decoders behind the WHATWG TextDecoder.
+// https://encoding.spec.whatwg.org/#interface-textdecoder
+
+use crate::bindings::external_references;
+use crate::bindings::get_internal_ref;
+use crate::bindings::set_function_to;
+use crate::bindings::set_internal_ref_with_finalizer;
+use crate::bindings::throw_type_error;
+use encoding_rs::Decoder;
+use encoding_rs::DecoderResult;
+use encoding_rs::Encoding;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "normalizeEncoding", normalize_encoding);
+    set_function_to(scope, target, "createDecoder", create_decoder);
+    set_function_to(scope, target, "decode", decode);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![normalize_encoding, create_decoder, decode]
+}
+
+/// The Rust state behind a JavaScript TextDecoder instance.
+struct TextDecoderState {
+    encoding: &'static Encoding,
+    ignore_bom: bool,
+    decoder: Decoder,
+}
+
+/// Creates a fresh decoder honoring the `ignoreBOM` option.
+fn new_decoder(encoding: &'static Encoding, ignore_bom: bool) -> Decoder {
+    match ignore_bom {
+        true => encoding.new_decoder_without_bom_handling(),
+        false => encoding.new_decoder_with_bom_removal(),
+    }
+}
+
+/// Returns the canonical (lowercase) name of an encoding label.
+fn normalize_encoding(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the encoding label.
+    let label = args.get(0).to_rust_string_lossy(scope);
+
+    match Encoding::for_label(label.as_bytes()) {
+        Some(encoding) => {
+            let name = encoding.name().to_lowercase();
+            rv.set(v8::String::new(scope, &name).unwrap().into());
+        }
+        None => rv.set(v8::undefined(scope).into()),
+    }
+}
+
+/// Creates a (stateful) decoder for the given encoding.
+fn create_decoder(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the encoding and the BOM handling option.
+    let label = args.get(0).to_rust_string_lossy(scope);
+    let ignore_bom = args.get(1).boolean_value(scope);
+
+    let encoding = match Encoding::for_label(label.as_bytes()) {
+        Some(encoding) => encoding,
+        None => {
+            throw_type_error(scope, &format!("Unsupported encoding: \"{label}\""));
+            return;
+        }
+    };
+
+    let decoder_wrapper = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    decoder_wrapper.set_internal_field_count(1);
+
+    let decoder_wrapper = decoder_wrapper.new_instance(scope).unwrap();
+    let state = TextDecoderState {
+        encoding,
+        ignore_bom,
+        decoder: new_decoder(encoding, ignore_bom),
+    };
+
+    // Note: Decoders are created on hot paths (e.g. for every HTTP body), so the
+    // state is dropped as soon as the JS object is garbage collected.
+    set_internal_ref_with_finalizer(scope, decoder_wrapper, 0, state);
+
+    rv.set(decoder_wrapper.into());
+}
+
+/// Decodes a chunk of bytes, keeping partial sequences when streaming.
+fn decode(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get the decoder_wrap object.
+    let decoder_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Get data as ArrayBuffer.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+    let stream = args.get(2).boolean_value(scope);
+    let fatal = args.get(3).boolean_value(scope);
+
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    let state = get_internal_ref::<TextDecoderState>(scope, decoder_wrap, 0);
+    let last = !stream;
+
+    let capacity = match fatal {
+        true => state
+            .decoder
+            .max_utf8_buffer_length_without_replacement(buffer.len()),
+        false => state.decoder.max_utf8_buffer_length(buffer.len()),
+    };
+
+    let mut output = String::with_capacity(capacity.unwrap_or_default());
+
+    let malformed = match fatal {
+        true => {
+            let (result, _) =
+                state
+                    .decoder
+                    .decode_to_string_without_replacement(&buffer, &mut output, last);
+            matches!(result, DecoderResult::Malformed(_, _))
+        }
+        false => {
+            state.decoder.decode_to_string(&buffer, &mut output, last);
+            false
+        }
+    };
+
+    // Note: A non-streaming call (or a fatal error) ends the current decoding
+    // session, so the next call should start from a clean decoder.
+    if last || malformed {
+        state.decoder = new_decoder(state.encoding, state.ignore_bom);
+    }
+
+    if malformed {
+        throw_type_error(scope, "The encoded data was not valid.");
+        return;
+    }
+
+    rv.set(v8::String::new(scope, &output).unwrap().into());
+}
@@ -1,8 +1,18 @@
+use crate::bindings::external_references;
 use crate::bindings::set_function_to;
 use crate::runtime::JsFuture;
 use crate::runtime::JsRuntime;
+use crate::runtime::JsRuntimeState;
 use dune_event_loop::LoopHandle;
+use dune_event_loop::LoopInterruptHandle;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     // Create local JS object.
@@ -12,11 +22,23 @@ pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
     set_function_to(scope, target, "removeTimeout", remove_timeout);
     set_function_to(scope, target, "createImmediate", create_immediate);
     set_function_to(scope, target, "removeImmediate", remove_immediate);
+    set_function_to(scope, target, "createUnrefTimeout", create_unref_timeout);
 
     // Return v8 global handle.
     v8::Global::new(scope, target)
 }
 
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![
+        create_timeout,
+        remove_timeout,
+        create_immediate,
+        remove_immediate,
+        create_unref_timeout
+    ]
+}
+
 struct TimeoutFuture {
     cb: Rc<v8::Global<v8::Function>>,
     params: Rc<Vec<v8::Global<v8::Value>>>,
@@ -204,3 +226,127 @@ fn remove_immediate(
 
     state_rc.borrow().handle.remove_check(&id);
 }
+
+/// A timeout that doesn't keep the event-loop alive.
+struct UnrefTimer {
+    deadline: Instant,
+    cb: Rc<v8::Global<v8::Function>>,
+}
+
+/// Keeps track of the unref'd timeouts of a runtime.
+///
+/// Note: The event-loop's timers always keep it alive, so these timeouts live in
+/// the runtime instead. A (lazily started) thread interrupts the event-loop at
+/// their deadlines, and the expired ones are queued on the next tick.
+#[derive(Default)]
+pub struct UnrefTimers {
+    timers: HashMap<u32, UnrefTimer>,
+    waker: Option<mpsc::Sender<Instant>>,
+    next_id: u32,
+}
+
+impl UnrefTimers {
+    fn insert(
+        &mut self,
+        interrupt: &LoopInterruptHandle,
+        delay: Duration,
+        cb: Rc<v8::Global<v8::Function>>,
+    ) -> u32 {
+        let id = self.next_id;
+        let deadline = Instant::now() + delay;
+        let waker = self
+            .waker
+            .get_or_insert_with(|| spawn_waker(interrupt.clone()));
+
+        // Note: If the thread couldn't be started the timer fires on the next tick
+        // that happens anyway, which is the best we can do without keeping it alive.
+        let _ = waker.send(deadline);
+
+        self.timers.insert(id, UnrefTimer { deadline, cb });
+        self.next_id += 1;
+
+        id
+    }
+}
+
+/// Starts the thread that wakes up the event-loop at the timers' deadlines.
+fn spawn_waker(interrupt: LoopInterruptHandle) -> mpsc::Sender<Instant> {
+    let (sender, receiver) = mpsc::channel::<Instant>();
+
+    let _ = thread::Builder::new()
+        .name("dune-timers".into())
+        .spawn(move || {
+            let mut deadlines = BinaryHeap::new();
+            loop {
+                let message = match deadlines.peek() {
+                    Some(Reverse(deadline)) => {
+                        let timeout = deadline.saturating_duration_since(Instant::now());
+                        receiver.recv_timeout(timeout)
+                    }
+                    None => receiver
+                        .recv()
+                        .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+                };
+
+                match message {
+                    Ok(deadline) => deadlines.push(Reverse(deadline)),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let now = Instant::now();
+                        while deadlines.peek().is_some_and(|Reverse(d)| *d <= now) {
+                            deadlines.pop();
+                        }
+                        interrupt.interrupt();
+                    }
+                    // The runtime is gone, so there's nobody to wake up.
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+
+    sender
+}
+
+/// Moves the expired unref'd timeouts to the pending futures.
+pub fn queue_expired_timers(state: &mut JsRuntimeState) {
+    let now = Instant::now();
+    let timers = &mut state.unref_timers.timers;
+
+    let mut expired: Vec<(u32, Instant)> = timers
+        .iter()
+        .filter(|(_, timer)| timer.deadline <= now)
+        .map(|(id, timer)| (*id, timer.deadline))
+        .collect();
+
+    // Fire them in the order they were due.
+    expired.sort_by_key(|(id, deadline)| (*deadline, *id));
+
+    for (id, _) in expired {
+        let timer = timers.remove(&id).unwrap();
+        let future = TimeoutFuture {
+            cb: timer.cb,
+            params: Rc::new(vec![]),
+        };
+        state.pending_futures.push(Box::new(future));
+    }
+}
+
+/// Schedules a new timeout that doesn't keep the event-loop alive.
+fn create_unref_timeout(
+    scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    // Get timer's callback and expiration time in millis.
+    let callback = v8::Local::<v8::Function>::try_from(args.get(0)).unwrap();
+    let callback = Rc::new(v8::Global::new(scope, callback));
+    let millis = args.get(1).int32_value(scope).unwrap_or(0).max(0) as u64;
+
+    let state_rc = JsRuntime::state(scope);
+    let mut state = state_rc.borrow_mut();
+    let interrupt = state.interrupt_handle.clone();
+    let id = state
+        .unref_timers
+        .insert(&interrupt, Duration::from_millis(millis), callback);
+
+    rv.set(v8::Number::new(scope, id as f64).into());
+}
@@ -1,9 +1,13 @@
+use crate::loaders::Reload;
 use crate::modules::load_import;
 use crate::modules::resolve_import;
 use crate::modules::ImportMap;
 use crate::modules::CORE_MODULES;
+use anyhow::bail;
 use anyhow::Error;
 use anyhow::Result;
+use serde_json::Value;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
 use swc_atoms::Atom;
@@ -20,38 +24,115 @@ use swc_common::sync::Lrc;
 use swc_common::FileName;
 use swc_common::FilePathMapping;
 use swc_common::Globals;
+use swc_common::Mark;
 use swc_common::Span;
+use swc_common::DUMMY_SP;
+use swc_common::GLOBALS;
 use swc_ecma_ast::*;
 use swc_ecma_codegen::text_writer::JsWriter;
 use swc_ecma_codegen::Emitter;
 use swc_ecma_loader::resolve::Resolution;
+use swc_ecma_minifier::optimize;
+use swc_ecma_minifier::option::CompressOptions;
+use swc_ecma_minifier::option::ExtraOptions;
+use swc_ecma_minifier::option::MangleOptions;
+use swc_ecma_minifier::option::MinifyOptions;
 use swc_ecma_parser::parse_file_as_module;
 use swc_ecma_parser::EsSyntax;
 use swc_ecma_parser::Syntax;
+use swc_ecma_transforms_base::fixer::fixer;
+use swc_ecma_transforms_base::resolver;
+use swc_ecma_visit::Visit;
+use swc_ecma_visit::VisitMut;
+use swc_ecma_visit::VisitMutWith;
+use swc_ecma_visit::VisitWith;
 
 #[derive(Debug, Default, Clone)]
 pub struct Options {
-    pub skip_cache: bool,
+    pub reload: Reload,
+    // Removes whitespace and comments from the output (codegen only).
     pub minify: bool,
+    // Runs swc's `mangle` pass (shortens local and top-level names).
+    pub mangle: bool,
+    // Runs swc's `compress` passes (dead-code elimination, inlining, etc).
+    pub compress: bool,
+    // Keeps function and class names intact in the `mangle`/`compress` passes.
+    pub keep_names: bool,
+    // The ECMAScript version the minifier and codegen are allowed to emit.
+    pub target: Option<EsVersion>,
+    // Build-time constants (e.g. `process.env.NODE_ENV`) and their values.
+    pub define: Vec<(String, String)>,
     pub import_map: Option<ImportMap>,
 }
 
+/// Parses an ECMAScript version (e.g. `es2020`) used as the bundle's target.
+pub fn parse_target(value: &str) -> Result<EsVersion, String> {
+    serde_json::from_value(Value::String(value.to_lowercase()))
+        .map_err(|_| format!("unknown ECMAScript version \"{value}\" (e.g. es5, es2020, esnext)"))
+}
+
 pub fn run_bundle(entry: &str, options: &Options) -> Result<String> {
     // Create SWC globals and an LRC sourcemap.
     let globals = Globals::default();
     let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
 
+    // Note: Dynamically imported modules are bundled on their own, as chunks
+    // that are evaluated the first time they're imported.
+    let chunks = RefCell::new(vec![]);
+
+    let module = bundle_module(&globals, &cm, entry, options, &chunks)?;
+    let mut source = emit_module(cm.clone(), &module, options)?;
+
+    // Note: Chunks may import other chunks, so the list grows while bundling.
+    for index in 0.. {
+        let Some(path) = chunks.borrow().get(index).cloned() else {
+            break;
+        };
+        let module = bundle_module(&globals, &cm, &path, options, &chunks)?;
+        source.push_str(&lazy_chunk(cm.clone(), index, module, options)?);
+    }
+
+    // Note: The minifier runs on a freshly parsed copy of the bundle, since it
+    // requires the syntax contexts of its own (identifier) scope analysis.
+    if options.compress || options.mangle {
+        let module = GLOBALS.set(&globals, || minify_module(cm.clone(), source, options))?;
+        source = emit_module(cm, &module, options)?;
+    }
+
+    if !options.minify {
+        // Decorate output with the following messages.
+        let messages = [
+            format!("// Dune v{}\n", env!("CARGO_PKG_VERSION")),
+            "// It's not recommended to edit this code manually since it's generated by `dune bundle`\n\n".into()
+        ];
+        messages.iter().rev().for_each(|msg| {
+            source.insert_str(0, msg);
+        });
+    }
+
+    Ok(source)
+}
+
+/// Bundles a module along with its static imports.
+fn bundle_module(
+    globals: &Globals,
+    cm: &Lrc<SourceMap>,
+    entry: &str,
+    options: &Options,
+    chunks: &RefCell<Vec<String>>,
+) -> Result<Module> {
     // NOTE: Core modules are built-in to dune's binary so there is no point to pollute
     // the bundle with extra code that the runtime can load anyway.
     let external_modules: Vec<Atom> = CORE_MODULES.keys().map(|k| (*k).into()).collect();
 
     // Create the bundler.
     let mut bundler = Bundler::new(
-        &globals,
+        globals,
         cm.clone(),
         Loader {
             cm: cm.clone(),
             options,
+            chunks,
         },
         Resolver { options },
         Config {
@@ -73,12 +154,126 @@ pub fn run_bundle(entry: &str, options: &Options) -> Result<String> {
         .pop()
         .unwrap();
 
+    Ok(bundle.module)
+}
+
+/// Parses a `KEY=value` build-time constant, where the key is an identifier
+/// or a property path (e.g. `process.env.NODE_ENV`).
+pub fn parse_define(value: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = value.split_once('=') else {
+        return Err(format!("expected KEY=value, found \"{value}\""));
+    };
+
+    let is_identifier = |name: &str| {
+        let mut chars = name.chars();
+        matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    };
+
+    if !key.split('.').all(is_identifier) {
+        return Err(format!("invalid define key \"{key}\""));
+    }
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Converts a define's value into a literal (booleans, numbers and strings,
+/// either quoted or not).
+fn define_literal(value: &str) -> Lit {
+    let span = DUMMY_SP;
+    let string = |value: &str| {
+        Lit::Str(Str {
+            span,
+            value: value.into(),
+            raw: None,
+        })
+    };
+
+    let quoted = ['"', '\''].iter().find_map(|quote| {
+        value
+            .strip_prefix(*quote)
+            .and_then(|value| value.strip_suffix(*quote))
+    });
+
+    if let Some(value) = quoted {
+        return string(value);
+    }
+
+    match value {
+        "true" | "false" => Lit::Bool(Bool {
+            span,
+            value: value == "true",
+        }),
+        _ => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Lit::Num(Number {
+                span,
+                value: number,
+                raw: None,
+            }),
+            _ => string(value),
+        },
+    }
+}
+
+/// Returns the dotted path (e.g. `process.env.NODE_ENV`) of an expression
+/// made of an identifier and static property accesses.
+fn expr_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(MemberExpr { obj, prop, .. }) => {
+            let prop = match prop {
+                MemberProp::Ident(name) => name.sym.to_string(),
+                MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                    Expr::Lit(Lit::Str(value)) => value.value.to_string(),
+                    _ => return None,
+                },
+                MemberProp::PrivateName(_) => return None,
+            };
+            expr_path(obj).map(|obj| format!("{obj}.{prop}"))
+        }
+        _ => None,
+    }
+}
+
+/// Replaces build-time constants with their values, so the minifier can drop
+/// branches that are never taken.
+struct Define<'a> {
+    constants: &'a [(String, String)],
+}
+
+impl VisitMut for Define<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // Note: We're checking the expression before its children, otherwise
+        // `process.env` would be replaced before `process.env.NODE_ENV`.
+        let value = match expr {
+            Expr::Ident(_) | Expr::Member(_) => expr_path(expr).and_then(|path| {
+                self.constants
+                    .iter()
+                    .find(|(key, _)| *key == path)
+                    .map(|(_, value)| value)
+            }),
+            _ => None,
+        };
+
+        match value {
+            Some(value) => *expr = Expr::Lit(define_literal(value)),
+            None => expr.visit_mut_children_with(self),
+        }
+    }
+}
+
+/// Generates JavaScript code from an SWC module.
+fn emit_module(cm: Lrc<SourceMap>, module: &Module, options: &Options) -> Result<String> {
     let mut buf = vec![];
 
     {
         let mut cfg = swc_ecma_codegen::Config::default();
         cfg.minify = options.minify;
 
+        if let Some(target) = options.target {
+            cfg.target = target;
+        }
+
         let mut emitter = Emitter {
             cfg,
             cm: cm.clone(),
@@ -86,29 +281,62 @@ pub fn run_bundle(entry: &str, options: &Options) -> Result<String> {
             wr: Box::new(JsWriter::new(cm, "\n", &mut buf, None)),
         };
 
-        emitter.emit_module(&bundle.module)?;
+        emitter.emit_module(module)?;
     }
 
     // Build source from bytes.
-    let mut source = String::from_utf8(buf).unwrap();
+    Ok(String::from_utf8(buf).unwrap())
+}
 
-    if !options.minify {
-        // Decorate output with the following messages.
-        let messages = [
-            format!("// Dune v{}\n", env!("CARGO_PKG_VERSION")),
-            "// It's not recommended to edit this code manually since it's generated by `dune bundle`\n\n".into()
-        ];
-        messages.iter().rev().for_each(|msg| {
-            source.insert_str(0, msg);
-        });
-    }
+/// Runs swc's minifier passes (`compress` and/or `mangle`) on the bundle.
+fn minify_module(cm: Lrc<SourceMap>, source: String, options: &Options) -> Result<Module> {
+    let fm = cm.new_source_file(FileName::Anon.into(), source);
+    let module = parse_file_as_module(
+        &fm,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .map_err(|e| Error::msg(format!("{e:?}")))?;
 
-    Ok(source)
+    let unresolved_mark = Mark::new();
+    let top_level_mark = Mark::new();
+    let target = options.target.unwrap_or_else(EsVersion::latest);
+
+    let minify_options = MinifyOptions {
+        compress: options.compress.then(|| CompressOptions {
+            ecma: target,
+            module: true,
+            keep_classnames: options.keep_names,
+            keep_fnames: options.keep_names,
+            ..Default::default()
+        }),
+        mangle: options.mangle.then(|| MangleOptions {
+            top_level: Some(true),
+            keep_class_names: options.keep_names,
+            keep_fn_names: options.keep_names,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let extra = ExtraOptions {
+        unresolved_mark,
+        top_level_mark,
+        mangle_name_cache: None,
+    };
+
+    let program = Program::Module(module).apply(resolver(unresolved_mark, top_level_mark, false));
+    let program = optimize(program, cm, None, None, &minify_options, &extra).apply(fixer(None));
+
+    Ok(program.expect_module())
 }
 
 struct Loader<'s> {
     cm: Lrc<SourceMap>,
     options: &'s Options,
+    chunks: &'s RefCell<Vec<String>>,
 }
 
 impl Load for Loader<'_> {
@@ -120,15 +348,17 @@ impl Load for Loader<'_> {
         };
 
         // Try load the module's source-code.
-        let source = load_import(&specifier, self.options.skip_cache)?;
-        let path = FileName::Real(specifier.into());
+        let import_map = self.options.import_map.as_ref();
+        let integrity = import_map.and_then(|map| map.integrity(&specifier));
+        let source = load_import(&specifier, &self.options.reload, integrity.as_deref())?;
+        let path = FileName::Real(specifier.clone().into());
         let fm = self.cm.new_source_file(path.into(), source);
 
         let handler =
             Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(self.cm.clone()));
 
         // Parse JavaScript source into an SWC module.
-        let module = match parse_file_as_module(
+        let mut module = match parse_file_as_module(
             &fm,
             Syntax::Es(EsSyntax::default()),
             EsVersion::latest(),
@@ -141,6 +371,30 @@ impl Load for Loader<'_> {
             Err(_) => std::process::exit(1),
         };
 
+        let import_map = self.options.import_map.clone();
+        let unresolved = inline_dynamic_imports(&mut module, &specifier, import_map, self.chunks)?;
+
+        // Note: Imports with specifiers computed at runtime can't be bundled, so
+        // we're warning about them (they're not going to work offline).
+        for span in unresolved {
+            let message = "Dynamic import with a computed specifier won't be bundled";
+            handler.struct_span_warn(span, message).emit();
+        }
+
+        let import_map = self.options.import_map.clone();
+
+        for span in inline_import_meta_resolve(&mut module, &specifier, import_map) {
+            let message =
+                "import.meta.resolve() with a computed specifier resolves from the bundle";
+            handler.struct_span_warn(span, message).emit();
+        }
+
+        if !self.options.define.is_empty() {
+            module.visit_mut_with(&mut Define {
+                constants: &self.options.define,
+            });
+        }
+
         Ok(ModuleData {
             fm,
             module,
@@ -149,6 +403,438 @@ impl Load for Loader<'_> {
     }
 }
 
+/// Replaces the dynamic imports (with a static specifier) of a module with
+/// calls to the chunks the imported modules are bundled into.
+struct DynamicImports<'a> {
+    base: &'a str,
+    import_map: Option<ImportMap>,
+    chunks: &'a RefCell<Vec<String>>,
+    unresolved: Vec<Span>,
+    error: Option<Error>,
+}
+
+impl DynamicImports<'_> {
+    /// Returns the identifier of the chunk that loads the given module.
+    fn chunk(&self, path: String) -> Ident {
+        let mut chunks = self.chunks.borrow_mut();
+        let index = match chunks.iter().position(|chunk| *chunk == path) {
+            Some(index) => index,
+            None => {
+                chunks.push(path);
+                chunks.len() - 1
+            }
+        };
+        Ident::new_no_ctxt(chunk_name(index).into(), DUMMY_SP)
+    }
+}
+
+impl VisitMut for DynamicImports<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        // We're only interested in `import(...)` calls.
+        let Expr::Call(CallExpr {
+            span,
+            callee: Callee::Import(_),
+            args,
+            ..
+        }) = expr
+        else {
+            return;
+        };
+
+        let span = *span;
+
+        // Note: Specifiers that are computed at runtime can't be bundled.
+        let Some(specifier) = static_specifier(args) else {
+            self.unresolved.push(span);
+            return;
+        };
+
+        // Core modules are built-in to dune's binary.
+        if CORE_MODULES.contains_key(specifier.as_str()) {
+            return;
+        }
+
+        let import_map = self.import_map.clone();
+        let path = match resolve_import(Some(self.base), &specifier, true, import_map) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error.get_or_insert(e);
+                return;
+            }
+        };
+
+        // Rewrite the call to `<chunk>()`.
+        *expr = Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::new(Expr::Ident(self.chunk(path)))),
+            args: vec![],
+            ..Default::default()
+        });
+    }
+}
+
+/// Bundles dynamically imported modules along with the static ones, so
+/// bundles (and compiled executables) don't fetch any code at runtime.
+///
+/// Note: The imported modules are bundled as chunks (see `lazy_chunk`), so
+/// they're still evaluated the first time they're requested.
+fn inline_dynamic_imports(
+    module: &mut Module,
+    base: &str,
+    import_map: Option<ImportMap>,
+    chunks: &RefCell<Vec<String>>,
+) -> Result<Vec<Span>> {
+    let mut visitor = DynamicImports {
+        base,
+        import_map,
+        chunks,
+        unresolved: vec![],
+        error: None,
+    };
+    module.visit_mut_with(&mut visitor);
+
+    match visitor.error {
+        Some(e) => Err(e),
+        None => Ok(visitor.unresolved),
+    }
+}
+
+/// Returns the name of the function that loads a chunk.
+fn chunk_name(index: usize) -> String {
+    format!("__dune_chunk_{index}")
+}
+
+/// Generates a function that evaluates a bundled chunk on its first call and
+/// returns (a promise of) its namespace object, the same way `import()` does.
+///
+/// Note: A module that is also imported statically is bundled twice, so the
+/// chunk evaluates a copy of its own.
+fn lazy_chunk(
+    cm: Lrc<SourceMap>,
+    index: usize,
+    module: Module,
+    options: &Options,
+) -> Result<String> {
+    let mut chunk = LazyChunk {
+        name: chunk_name(index),
+        cm,
+        options,
+        body: String::new(),
+        exports: vec![],
+        spreads: vec![],
+        temporaries: 0,
+    };
+
+    for item in module.body {
+        match item {
+            ModuleItem::ModuleDecl(decl) => chunk.push_module_decl(decl)?,
+            ModuleItem::Stmt(stmt) => chunk.push_stmt(stmt)?,
+        }
+    }
+
+    Ok(chunk.finish())
+}
+
+/// The body of a lazy chunk (see `lazy_chunk`) and the exports of its module.
+struct LazyChunk<'a> {
+    name: String,
+    cm: Lrc<SourceMap>,
+    options: &'a Options,
+    body: String,
+    exports: Vec<(String, String)>,
+    spreads: Vec<String>,
+    temporaries: usize,
+}
+
+impl LazyChunk<'_> {
+    /// Returns a new (unique) variable name.
+    fn temporary(&mut self) -> String {
+        self.temporaries += 1;
+        format!("{}_{}", self.name, self.temporaries)
+    }
+
+    fn push_stmt(&mut self, stmt: Stmt) -> Result<()> {
+        let module = Module {
+            span: DUMMY_SP,
+            body: vec![ModuleItem::Stmt(stmt)],
+            shebang: None,
+        };
+        let source = emit_module(self.cm.clone(), &module, self.options)?;
+        self.body.push_str(&source);
+        Ok(())
+    }
+
+    /// Declares a variable holding the namespace of an (external) module.
+    fn push_namespace(&mut self, src: &str) -> String {
+        let namespace = self.temporary();
+        let line = format!("const {namespace} = await import({});\n", quote(src));
+        self.body.push_str(&line);
+        namespace
+    }
+
+    /// Exports the value of an expression as `default`.
+    fn push_default(&mut self, expr: Expr) -> Result<()> {
+        let local = self.temporary();
+        self.push_stmt(const_declaration(&local, expr))?;
+        self.exports.push(("default".into(), local));
+        Ok(())
+    }
+
+    // Note: Only core modules are left to import (using `import()`), while the
+    // exports become properties of the chunk's namespace object.
+    fn push_module_decl(&mut self, decl: ModuleDecl) -> Result<()> {
+        match decl {
+            ModuleDecl::Import(import) => {
+                let namespace = self.push_namespace(&import.src.value);
+                for specifier in import.specifiers {
+                    let (local, value) = match specifier {
+                        ImportSpecifier::Default(s) => (s.local, format!("{namespace}.default")),
+                        ImportSpecifier::Namespace(s) => (s.local, namespace.clone()),
+                        ImportSpecifier::Named(s) => {
+                            let imported = s.imported.as_ref().map(export_name);
+                            let imported = imported.unwrap_or_else(|| s.local.sym.to_string());
+                            (s.local, format!("{namespace}[{}]", quote(&imported)))
+                        }
+                    };
+                    let line = format!("const {} = {value};\n", local.sym);
+                    self.body.push_str(&line);
+                }
+            }
+            ModuleDecl::ExportDecl(export) => {
+                let names = declared_names(&export.decl);
+                self.exports
+                    .extend(names.into_iter().map(|name| (name.clone(), name)));
+                self.push_stmt(Stmt::Decl(export.decl))?;
+            }
+            ModuleDecl::ExportNamed(export) => {
+                let namespace = export.src.map(|src| self.push_namespace(&src.value));
+                for specifier in export.specifiers {
+                    let (exported, local) = match specifier {
+                        ExportSpecifier::Named(s) => {
+                            let local = export_name(&s.orig);
+                            let exported = s.exported.as_ref().map(export_name);
+                            (exported.unwrap_or_else(|| local.clone()), Some(local))
+                        }
+                        ExportSpecifier::Namespace(s) => (export_name(&s.name), None),
+                        ExportSpecifier::Default(s) => (s.exported.sym.to_string(), None),
+                    };
+                    let value = match (namespace.as_ref(), local) {
+                        (Some(namespace), Some(local)) => format!("{namespace}[{}]", quote(&local)),
+                        (Some(namespace), None) => namespace.clone(),
+                        (None, local) => local.unwrap_or_default(),
+                    };
+                    self.exports.push((exported, value));
+                }
+            }
+            ModuleDecl::ExportAll(export) => {
+                let namespace = self.push_namespace(&export.src.value);
+                self.spreads.push(namespace);
+            }
+            ModuleDecl::ExportDefaultExpr(export) => self.push_default(*export.expr)?,
+            ModuleDecl::ExportDefaultDecl(export) => match export.decl {
+                DefaultDecl::Fn(FnExpr {
+                    ident: Some(ident),
+                    function,
+                }) => {
+                    self.exports.push(("default".into(), ident.sym.to_string()));
+                    self.push_stmt(Stmt::Decl(Decl::Fn(FnDecl {
+                        ident,
+                        declare: false,
+                        function,
+                    })))?;
+                }
+                DefaultDecl::Class(ClassExpr {
+                    ident: Some(ident),
+                    class,
+                }) => {
+                    self.exports.push(("default".into(), ident.sym.to_string()));
+                    self.push_stmt(Stmt::Decl(Decl::Class(ClassDecl {
+                        ident,
+                        declare: false,
+                        class,
+                    })))?;
+                }
+                DefaultDecl::Fn(expr) => self.push_default(expr.into())?,
+                DefaultDecl::Class(expr) => self.push_default(expr.into())?,
+                DefaultDecl::TsInterfaceDecl(_) => {}
+            },
+            _ => bail!("Unsupported declaration in a dynamically imported module"),
+        }
+        Ok(())
+    }
+
+    /// Wraps the chunk's body into a function that runs it once.
+    fn finish(self) -> String {
+        let name = self.name;
+        let body = self.body;
+
+        // Note: Getters keep the exported bindings live (like a module namespace).
+        let spreads = self
+            .spreads
+            .iter()
+            .map(|namespace| format!("...{namespace}"));
+        let getters = self
+            .exports
+            .iter()
+            .map(|(exported, local)| format!("get {}() {{ return {local}; }}", quote(exported)));
+
+        let properties: Vec<_> = spreads
+            .chain(["[Symbol.toStringTag]: \"Module\"".to_string()])
+            .chain(getters)
+            .collect();
+
+        let namespace = format!("{{ __proto__: null, {} }}", properties.join(", "));
+
+        format!(
+            "var {name}_promise;\n\
+             function {name}() {{\n\
+             return {name}_promise || ({name}_promise = (async () => {{\n\
+             {body}\
+             return Object.freeze({namespace});\n\
+             }})());\n\
+             }}\n"
+        )
+    }
+}
+
+/// Returns a `const <name> = <init>;` declaration.
+fn const_declaration(name: &str, init: Expr) -> Stmt {
+    let name = Ident::new_no_ctxt(name.into(), DUMMY_SP);
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        kind: VarDeclKind::Const,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: Pat::Ident(name.into()),
+            init: Some(Box::new(init)),
+            definite: false,
+        }],
+        ..Default::default()
+    })))
+}
+
+/// Returns the names bound by a declaration.
+fn declared_names(decl: &Decl) -> Vec<String> {
+    let mut names = BindingNames::default();
+    decl.visit_with(&mut names);
+    names.0
+}
+
+/// Collects the identifiers bound by a declaration (skipping expressions,
+/// like default values, that may bind identifiers of their own).
+#[derive(Default)]
+struct BindingNames(Vec<String>);
+
+impl Visit for BindingNames {
+    fn visit_binding_ident(&mut self, ident: &BindingIdent) {
+        self.0.push(ident.sym.to_string());
+    }
+
+    fn visit_fn_decl(&mut self, decl: &FnDecl) {
+        self.0.push(decl.ident.sym.to_string());
+    }
+
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        self.0.push(decl.ident.sym.to_string());
+    }
+
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// Returns the name of an imported or exported binding.
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(value) => value.value.to_string(),
+    }
+}
+
+/// Returns a string as a (double-quoted) JavaScript string literal.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+/// Returns the specifier of an import call if it's a string literal.
+fn static_specifier(args: &[ExprOrSpread]) -> Option<Atom> {
+    match args {
+        [ExprOrSpread { spread: None, expr }] => match &**expr {
+            Expr::Lit(Lit::Str(value)) => Some(value.value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Resolves `import.meta.resolve(...)` calls (with a static specifier) at
+/// build time, relative to the module they're written in.
+struct ImportMetaResolve<'a> {
+    base: &'a str,
+    import_map: Option<ImportMap>,
+    unresolved: Vec<Span>,
+}
+
+impl VisitMut for ImportMetaResolve<'_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        expr.visit_mut_children_with(self);
+
+        let Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(callee),
+            args,
+            ..
+        }) = expr
+        else {
+            return;
+        };
+
+        // We're only interested in `import.meta.resolve(...)` calls.
+        let is_import_meta_resolve = match &**callee {
+            Expr::Member(MemberExpr { obj, prop, .. }) => {
+                matches!(&**obj, Expr::MetaProp(meta) if meta.kind == MetaPropKind::ImportMeta)
+                    && matches!(prop, MemberProp::Ident(name) if name.sym == "resolve")
+            }
+            _ => false,
+        };
+
+        if !is_import_meta_resolve {
+            return;
+        }
+
+        let import_map = self.import_map.clone();
+        let resolved = static_specifier(args).and_then(|specifier| {
+            resolve_import(Some(self.base), &specifier, false, import_map).ok()
+        });
+
+        let Some(resolved) = resolved else {
+            self.unresolved.push(*span);
+            return;
+        };
+
+        *expr = Expr::Lit(Lit::Str(Str {
+            span: *span,
+            value: resolved.into(),
+            raw: None,
+        }));
+    }
+}
+
+/// Replaces `import.meta.resolve(...)` calls with the resolved specifiers,
+/// since after bundling `import.meta` refers to the bundle itself.
+fn inline_import_meta_resolve(
+    module: &mut Module,
+    base: &str,
+    import_map: Option<ImportMap>,
+) -> Vec<Span> {
+    let mut visitor = ImportMetaResolve {
+        base,
+        import_map,
+        unresolved: vec![],
+    };
+    module.visit_mut_with(&mut visitor);
+    visitor.unresolved
+}
+
 struct Resolver<'a> {
     options: &'a Options,
 }
@@ -189,7 +875,16 @@ impl swc_bundler::Hook for Hook {
         let file_name = module.file_name.to_string();
         let file_name = resolve_import(None, &file_name, true, None)?;
 
-        // Compute .main and .url properties.
+        // Note: The `resolve` function is bound to the bundle, that's why calls
+        // with static specifiers are resolved when loading the module.
+        let import_meta = || {
+            Box::new(Expr::MetaProp(MetaPropExpr {
+                span,
+                kind: MetaPropKind::ImportMeta,
+            }))
+        };
+
+        // Compute .main, .url and .resolve properties.
         Ok(vec![
             KeyValueProp {
                 key: PropName::Ident(IdentName::new("url".into(), span)),
@@ -204,16 +899,152 @@ impl swc_bundler::Hook for Hook {
                 value: Box::new(if module.is_entry {
                     Expr::Member(MemberExpr {
                         span,
-                        obj: Box::new(Expr::MetaProp(MetaPropExpr {
-                            span,
-                            kind: MetaPropKind::ImportMeta,
-                        })),
+                        obj: import_meta(),
                         prop: MemberProp::Ident(IdentName::new("main".into(), span)),
                     })
                 } else {
                     Expr::Lit(Lit::Bool(Bool { span, value: false }))
                 }),
             },
+            KeyValueProp {
+                key: PropName::Ident(IdentName::new("resolve".into(), span)),
+                value: Box::new(Expr::Member(MemberExpr {
+                    span,
+                    obj: import_meta(),
+                    prop: MemberProp::Ident(IdentName::new("resolve".into(), span)),
+                })),
+            },
         ])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::JsRuntime;
+    use assert_fs::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bundle_dynamic_imports() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let entry = temp_dir.child("main.js");
+        let module = temp_dir.child("x.js");
+        let asset = temp_dir.child("y.json");
+
+        entry
+            .write_str("export const load = async () => (await import('./x.js')).value;")
+            .unwrap();
+        module
+            .write_str("export const value = import.meta.resolve('./y.json');")
+            .unwrap();
+        asset.write_str("{}").unwrap();
+
+        let entry = entry.to_string_lossy().to_string();
+        let source = run_bundle(&entry, &Options::default()).unwrap();
+
+        // The dynamic import should be part of the bundle (as a lazy chunk).
+        assert!(!source.contains("import("));
+        assert!(source.contains("function __dune_chunk_0()"));
+
+        // The import.meta.resolve() call should be resolved at build time.
+        assert!(!source.contains("import.meta.resolve("));
+        assert!(source.contains("y.json\""));
+    }
+
+    #[test]
+    fn test_bundle_lazy_chunks() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let entry = temp_dir.child("main.js");
+        let files = [
+            (
+                "main.js",
+                "globalThis.load = () => import('./x.js');\nglobalThis.order = ['main'];",
+            ),
+            (
+                "x.js",
+                "import { name } from './y.js';\nglobalThis.order.push(name);\nexport default 42;",
+            ),
+            ("y.js", "export const name = 'x';"),
+        ];
+
+        for (path, source) in files {
+            temp_dir.child(path).write_str(source).unwrap();
+        }
+
+        let entry = entry.to_string_lossy().to_string();
+        let source = run_bundle(&entry, &Options::default()).unwrap();
+
+        let mut runtime = JsRuntime::new().unwrap();
+        runtime.execute_module("bundle.js", Some(&source)).unwrap();
+        runtime.run_event_loop().unwrap();
+
+        // The chunk is only evaluated once it's imported.
+        let order = runtime.eval_to_json("globalThis.order").unwrap();
+        assert_eq!(order, json!(["main"]));
+
+        let script = "Promise.all([load(), load()]).then(([a, b]) => [a === b, a.default])";
+        let value = runtime.eval_to_json(script).unwrap();
+        assert_eq!(value, json!([true, 42]));
+
+        let order = runtime.eval_to_json("globalThis.order").unwrap();
+        assert_eq!(order, json!(["main", "x"]));
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("es2020"), Ok(EsVersion::Es2020));
+        assert_eq!(parse_target("ESNext"), Ok(EsVersion::EsNext));
+        assert!(parse_target("es2077").is_err());
+    }
+
+    #[test]
+    fn test_bundle_define() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let entry = temp_dir.child("main.js");
+
+        entry
+            .write_str(
+                "export function run() {
+                    if (process.env.NODE_ENV !== 'production') {
+                        console.log('development only');
+                    }
+                    return DEBUG ? 'debug' : 'done';
+                }",
+            )
+            .unwrap();
+
+        let options = Options {
+            compress: true,
+            define: vec![
+                ("process.env.NODE_ENV".into(), "production".into()),
+                ("DEBUG".into(), "false".into()),
+            ],
+            ..Default::default()
+        };
+
+        let entry = entry.to_string_lossy().to_string();
+        let source = run_bundle(&entry, &options).unwrap();
+
+        // The development-only branches should be eliminated.
+        assert!(!source.contains("development only"));
+        assert!(!source.contains("NODE_ENV"));
+        assert!(!source.contains("debug"));
+        assert!(source.contains("done"));
+    }
+
+    #[test]
+    fn test_parse_define() {
+        let define = parse_define("process.env.NODE_ENV=production");
+
+        assert_eq!(
+            define,
+            Ok(("process.env.NODE_ENV".into(), "production".into()))
+        );
+        assert!(parse_define("process.env.NODE_ENV").is_err());
+        assert!(parse_define("process..env=1").is_err());
+        assert!(matches!(define_literal("42"), Lit::Num(n) if n.value == 42.0));
+        assert!(matches!(define_literal("true"), Lit::Bool(b) if b.value));
+        assert!(matches!(define_literal("'42'"), Lit::Str(s) if s.value == "42"));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::dune_dir;
+use crate::info::format_size;
+use anyhow::bail;
+use anyhow::Result;
+use colored::*;
+use std::fs;
+use std::io;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+#[derive(Debug, Default, Clone)]
+pub struct CleanOptions {
+    /// Only remove modules whose URL starts with one of the prefixes.
+    pub prefixes: Vec<String>,
+    /// Only remove modules that were downloaded before this long ago.
+    pub older_than: Option<Duration>,
+    /// Skip the confirmation prompt.
+    pub yes: bool,
+}
+
+/// A URL import stored in the cache directory.
+#[derive(Debug)]
+struct CachedModule {
+    /// The sha1 hash of the URL (which is also the filename).
+    hash: String,
+    /// The URL of the module (unknown for modules cached by older versions).
+    url: Option<String>,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl CachedModule {
+    /// Returns the module's file and the metadata files stored next to it.
+    fn files(&self) -> Vec<PathBuf> {
+        let path = dune_dir::cache_dir().join(&self.hash);
+        vec![
+            path.with_extension("url"),
+            path.with_extension("integrity"),
+            path,
+        ]
+    }
+}
+
+/// Reads the cached URL imports (oldest first).
+fn read_cache() -> Result<Vec<CachedModule>> {
+    let directory = dune_dir::cache_dir();
+    if !directory.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut modules = vec![];
+
+    for entry in fs::read_dir(&directory)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        // Note: Metadata files (e.g. `<hash>.url`) are read along with the module.
+        if path.extension().is_some() || !path.is_file() {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        let url = fs::read_to_string(path.with_extension("url")).ok();
+
+        modules.push(CachedModule {
+            hash: entry.file_name().to_string_lossy().into(),
+            url,
+            size: metadata.len(),
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        });
+    }
+
+    modules.sort_by_key(|module| module.modified);
+    Ok(modules)
+}
+
+/// Returns how long ago the module was downloaded.
+fn age(module: &CachedModule) -> Duration {
+    SystemTime::now()
+        .duration_since(module.modified)
+        .unwrap_or_default()
+}
+
+/// Formats a duration with its largest unit (e.g. `3d`).
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Parses durations like `30s`, `15m`, `12h`, `7d` or `2w`.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = match amount.parse() {
+        Ok(amount) => amount,
+        Err(_) => bail!("Invalid duration \"{value}\" (e.g. 30s, 15m, 12h, 7d or 2w)"),
+    };
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "" | "d" => 86400,
+        "w" => 604800,
+        _ => bail!("Invalid duration \"{value}\" (e.g. 30s, 15m, 12h, 7d or 2w)"),
+    };
+
+    Ok(Duration::from_secs(amount * multiplier))
+}
+
+pub fn run_list() -> Result<()> {
+    let modules = read_cache()?;
+    let directory = dune_dir::cache_dir();
+
+    if modules.is_empty() {
+        println!("No cached modules in {}", directory.display());
+        return Ok(());
+    }
+
+    for module in &modules {
+        let url = match module.url.as_ref() {
+            Some(url) => url.normal(),
+            None => format!("(unknown URL) {}", module.hash).dimmed(),
+        };
+        println!(
+            "{:>10}  {:>4}  {}",
+            format_size(module.size as usize),
+            format_age(age(module)),
+            url
+        );
+    }
+
+    let size: u64 = modules.iter().map(|module| module.size).sum();
+
+    println!(
+        "\n{} {} module(s), {} in {}",
+        "Cached".green(),
+        modules.len(),
+        format_size(size as usize),
+        directory.display()
+    );
+
+    Ok(())
+}
+
+pub fn run_clean(options: &CleanOptions) -> Result<()> {
+    let modules: Vec<CachedModule> = read_cache()?
+        .into_iter()
+        .filter(|module| match options.prefixes.is_empty() {
+            true => true,
+            false => module
+                .url
+                .as_ref()
+                .is_some_and(|url| options.prefixes.iter().any(|p| url.starts_with(p))),
+        })
+        .filter(|module| match options.older_than {
+            Some(duration) => age(module) > duration,
+            None => true,
+        })
+        .collect();
+
+    if modules.is_empty() {
+        println!("Nothing to clean");
+        return Ok(());
+    }
+
+    let size: u64 = modules.iter().map(|module| module.size).sum();
+    let summary = format!(
+        "{} cached module(s), {}",
+        modules.len(),
+        format_size(size as usize)
+    );
+
+    if !options.yes && !confirm(&format!("Remove {summary}?"))? {
+        return Ok(());
+    }
+
+    for module in &modules {
+        for path in module.files() {
+            match fs::remove_file(&path) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => bail!("Failed to remove \"{}\": {e}", path.display()),
+            }
+        }
+    }
+
+    println!("{} {summary}", "Removed".green());
+
+    Ok(())
+}
+
+/// Asks the user to confirm a destructive action.
+fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!("Refusing to remove cached modules without confirmation (use --yes)");
+    }
+
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        // Group of tests to be run.
+        let tests = vec![
+            ("30s", Some(30)),
+            ("15m", Some(900)),
+            ("12h", Some(43200)),
+            ("7d", Some(604800)),
+            ("7", Some(604800)),
+            ("2w", Some(1209600)),
+            ("d", None),
+            ("10y", None),
+        ];
+
+        for (value, secs) in tests {
+            let duration = parse_duration(value).ok();
+            assert_eq!(duration, secs.map(Duration::from_secs));
+        }
+    }
+}
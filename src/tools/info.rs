@@ -0,0 +1,278 @@
+use crate::loaders::Reload;
+use crate::loaders::UrlModuleLoader;
+use crate::modules::load_import;
+use crate::modules::resolve_import;
+use crate::modules::ImportMap;
+use crate::modules::CORE_MODULES;
+use crate::vendor::find_static_imports;
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use swc_common::source_map::SourceMap;
+use swc_common::sync::Lrc;
+use swc_common::FilePathMapping;
+use url::Url;
+
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub reload: Reload,
+    pub import_map: Option<ImportMap>,
+    pub json: bool,
+}
+
+/// What we know about a module of the dependency graph.
+#[derive(Debug, Serialize)]
+struct ModuleInfo {
+    specifier: String,
+    loader: &'static str,
+    location: Option<PathBuf>,
+    size: usize,
+    dependencies: Vec<String>,
+}
+
+/// The dependency graph of an entry point (in JSON output).
+#[derive(Debug, Serialize)]
+struct GraphInfo<'a> {
+    root: &'a str,
+    size: usize,
+    modules: Vec<&'a ModuleInfo>,
+    circular: Vec<Vec<String>>,
+}
+
+pub fn run_info(entry: &str, options: &Options) -> Result<()> {
+    // Resolve the entry point the same way `dune run` does.
+    let import_map = options.import_map.clone();
+    let entry = resolve_import(None, entry, true, import_map.clone())
+        .or_else(|_| resolve_import(None, &format!("./{entry}"), true, import_map))?;
+
+    let graph = build_graph(&entry, options)?;
+    let circular = find_circular_imports(&entry, &graph);
+    let size = graph.values().map(|module| module.size).sum();
+
+    if options.json {
+        let info = GraphInfo {
+            root: &entry,
+            size,
+            modules: graph.values().collect(),
+            circular,
+        };
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let local = graph.values().filter(|m| m.loader == "fs").count();
+    let remote = graph.values().filter(|m| m.loader == "url").count();
+
+    println!("{} {}", "root:".bold(), entry);
+    println!(
+        "{} {} unique ({} local, {} remote)",
+        "modules:".bold(),
+        graph.len(),
+        local,
+        remote
+    );
+    println!("{} {}", "size:".bold(), format_size(size));
+    println!();
+
+    let mut printed = HashSet::new();
+    let mut ancestors = vec![];
+    print_tree(&entry, &graph, "", &mut ancestors, &mut printed);
+
+    if !circular.is_empty() {
+        println!();
+        println!("{}", "Circular imports:".yellow().bold());
+        for cycle in circular {
+            println!("  {}", cycle.join(" -> "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Walks the module graph (breadth first) without evaluating any module.
+fn build_graph(entry: &str, options: &Options) -> Result<BTreeMap<String, ModuleInfo>> {
+    let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+
+    let mut queue = VecDeque::from([entry.to_string()]);
+    let mut graph = BTreeMap::new();
+
+    while let Some(path) = queue.pop_front() {
+        if graph.contains_key(&path) {
+            continue;
+        }
+
+        // Core modules are built-in to dune's binary (so we stop there).
+        if let Some(source) = CORE_MODULES.get(path.as_str()) {
+            let module = ModuleInfo {
+                specifier: path.clone(),
+                loader: "core",
+                location: None,
+                size: source.len(),
+                dependencies: vec![],
+            };
+            graph.insert(path, module);
+            continue;
+        }
+
+        let integrity = options
+            .import_map
+            .as_ref()
+            .and_then(|map| map.integrity(&path));
+        let source = load_import(&path, &options.reload, integrity.as_deref())?;
+
+        let (loader, location) = match Url::parse(&path) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {
+                ("url", Some(UrlModuleLoader::cache_path(&path)))
+            }
+            _ => ("fs", Some(PathBuf::from(&path))),
+        };
+
+        let size = source.len();
+        let mut dependencies = vec![];
+
+        for specifier in find_static_imports(&cm, &path, source)? {
+            let import_map = options.import_map.clone();
+            let specifier = resolve_import(Some(&path), &specifier, false, import_map)?;
+            if !dependencies.contains(&specifier) {
+                queue.push_back(specifier.clone());
+                dependencies.push(specifier);
+            }
+        }
+
+        let module = ModuleInfo {
+            specifier: path.clone(),
+            loader,
+            location,
+            size,
+            dependencies,
+        };
+
+        graph.insert(path, module);
+    }
+
+    Ok(graph)
+}
+
+/// Returns every import (as a path from the importing module) that closes a cycle.
+fn find_circular_imports(entry: &str, graph: &BTreeMap<String, ModuleInfo>) -> Vec<Vec<String>> {
+    fn visit(
+        path: &str,
+        graph: &BTreeMap<String, ModuleInfo>,
+        ancestors: &mut Vec<String>,
+        visited: &mut HashSet<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(index) = ancestors.iter().position(|ancestor| ancestor == path) {
+            let mut cycle = ancestors[index..].to_vec();
+            cycle.push(path.to_string());
+            cycles.push(cycle);
+            return;
+        }
+
+        if !visited.insert(path.to_string()) {
+            return;
+        }
+
+        ancestors.push(path.to_string());
+        for dependency in &graph[path].dependencies {
+            visit(dependency, graph, ancestors, visited, cycles);
+        }
+        ancestors.pop();
+    }
+
+    let mut cycles = vec![];
+    visit(entry, graph, &mut vec![], &mut HashSet::new(), &mut cycles);
+    cycles
+}
+
+/// Prints the dependency tree, showing repeated modules (and cycles) only once.
+fn print_tree(
+    path: &str,
+    graph: &BTreeMap<String, ModuleInfo>,
+    prefix: &str,
+    ancestors: &mut Vec<String>,
+    printed: &mut HashSet<String>,
+) {
+    let module = &graph[path];
+    let details = match module.loader {
+        "core" => "(core)".to_string(),
+        _ => format!("({}, {})", module.loader, format_size(module.size)),
+    };
+
+    println!("{} {}", module.specifier, details.dimmed());
+
+    ancestors.push(path.to_string());
+    printed.insert(path.to_string());
+
+    let count = module.dependencies.len();
+    for (i, dependency) in module.dependencies.iter().enumerate() {
+        let last = i == count - 1;
+        let branch = if last { "└── " } else { "├── " };
+        let indent = if last { "    " } else { "│   " };
+
+        print!("{prefix}{branch}");
+
+        if ancestors.contains(dependency) {
+            println!("{} {}", dependency, "(circular)".yellow());
+            continue;
+        }
+
+        if printed.contains(dependency) && !graph[dependency].dependencies.is_empty() {
+            println!("{} {}", dependency, "*".dimmed());
+            continue;
+        }
+
+        let prefix = format!("{prefix}{indent}");
+        print_tree(dependency, graph, &prefix, ancestors, printed);
+    }
+
+    ancestors.pop();
+}
+
+/// Formats a byte count the way humans read it.
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes}B"),
+        1024..=1048575 => format!("{:.2}KB", bytes as f64 / 1024.0),
+        _ => format!("{:.2}MB", bytes as f64 / 1048576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(specifier: &str, dependencies: &[&str]) -> (String, ModuleInfo) {
+        let module = ModuleInfo {
+            specifier: specifier.into(),
+            loader: "fs",
+            location: None,
+            size: 0,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        };
+        (specifier.into(), module)
+    }
+
+    #[test]
+    fn test_find_circular_imports() {
+        let graph = BTreeMap::from([
+            module("/a.js", &["/b.js", "/c.js"]),
+            module("/b.js", &["/c.js"]),
+            module("/c.js", &["/a.js"]),
+        ]);
+
+        let cycles = find_circular_imports("/a.js", &graph);
+        assert_eq!(cycles, vec![vec!["/a.js", "/b.js", "/c.js", "/a.js"]]);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(2048), "2.00KB");
+        assert_eq!(format_size(3 * 1048576), "3.00MB");
+    }
+}
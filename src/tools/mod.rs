@@ -1,3 +1,6 @@
 pub mod bundle;
+pub mod cache;
 pub mod compile;
+pub mod info;
 pub mod upgrade;
+pub mod vendor;
@@ -0,0 +1,449 @@
+use crate::loaders::Reload;
+use crate::modules::load_import;
+use crate::modules::resolve_import;
+use crate::modules::ImportMap;
+use crate::modules::CORE_MODULES;
+use anyhow::bail;
+use anyhow::Result;
+use colored::*;
+use serde_json::json;
+use serde_json::Value;
+use sha2::Digest as _;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use swc_common::errors::ColorConfig;
+use swc_common::errors::Handler;
+use swc_common::source_map::SourceMap;
+use swc_common::sync::Lrc;
+use swc_common::FileName;
+use swc_common::FilePathMapping;
+use swc_ecma_ast::*;
+use swc_ecma_parser::parse_file_as_module;
+use swc_ecma_parser::EsSyntax;
+use swc_ecma_parser::Syntax;
+use url::Url;
+
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub reload: Reload,
+    pub output: PathBuf,
+    pub import_map: Option<ImportMap>,
+}
+
+pub fn run_vendor(entry: &str, options: &Options) -> Result<()> {
+    // Resolve the entry point the same way `dune run` does.
+    let import_map = options.import_map.clone();
+    let entry = resolve_import(None, entry, true, import_map.clone())
+        .or_else(|_| resolve_import(None, &format!("./{entry}"), true, import_map))?;
+
+    let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+
+    let mut queue = VecDeque::from([entry]);
+    let mut seen = HashSet::new();
+    let mut scopes = BTreeMap::new();
+    let mut exact = BTreeMap::new();
+    let mut vendored = 0;
+
+    // Walk the module graph (breadth first) starting from the entry point.
+    while let Some(path) = queue.pop_front() {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let integrity = options
+            .import_map
+            .as_ref()
+            .and_then(|map| map.integrity(&path));
+        let mut source = load_import(&path, &options.reload, integrity.as_deref())?;
+        let remote = remote_url(&path);
+        let mut rewrites = vec![];
+
+        for (specifier, range) in find_static_import_literals(&cm, &path, source.clone())? {
+            // Core modules are built-in to dune's binary.
+            if CORE_MODULES.contains_key(specifier.as_str()) {
+                continue;
+            }
+
+            let import_map = options.import_map.clone();
+            let resolved = resolve_import(Some(&path), &specifier, true, import_map)?;
+
+            // Note: Once vendored, the module is loaded from disk, where specifiers
+            // relative to its URL (e.g. `/stable/mod.js` or `./mod.js?target=es2022`)
+            // no longer point to the dependency, so they're rewritten.
+            if let (Some(url), Some(dependency)) = (remote.as_ref(), remote_url(&resolved)) {
+                if Url::parse(&specifier).is_err() {
+                    let (from, to) = (url_to_path(url), url_to_path(&dependency));
+                    rewrites.push((range, relative_specifier(&from, &to)));
+                }
+            }
+
+            queue.push_back(resolved);
+        }
+
+        // Write remote modules to the vendor directory.
+        if let Some(url) = remote {
+            let (origin, directory) = url_to_origin_directory(&url);
+            let relative = url_to_path(&url);
+            let destination = options.output.join(&relative);
+
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            // Apply the rewrites back to front, so the ranges stay valid.
+            for (range, specifier) in rewrites.into_iter().rev() {
+                source.replace_range(range, &serde_json::to_string(&specifier)?);
+            }
+
+            fs::write(&destination, &source)?;
+            scopes.insert(origin, directory);
+
+            // Note: The origin prefix can't map a query string to the (hashed)
+            // file name, so these URLs get an entry of their own.
+            if url.query().is_some() {
+                exact.insert(path.clone(), relative);
+            }
+
+            vendored += 1;
+        }
+    }
+
+    let import_map = build_import_map(&options.output, scopes, exact);
+    let import_map = serde_json::to_string_pretty(&import_map)?;
+    let import_map_path = options.output.join("import-map.json");
+
+    fs::create_dir_all(&options.output)?;
+    fs::write(&import_map_path, import_map)?;
+
+    println!(
+        "{} {} remote module(s) to {}",
+        "Vendored".green(),
+        vendored,
+        options.output.display()
+    );
+    println!(
+        "Run with `--import-map={}` to use them.",
+        import_map_path.display()
+    );
+
+    Ok(())
+}
+
+/// Builds an import map that redirects every vendored origin (and every vendored
+/// URL with a query string) locally.
+fn build_import_map(
+    output: &Path,
+    scopes: BTreeMap<String, String>,
+    exact: BTreeMap<String, PathBuf>,
+) -> Value {
+    let local = |relative: &Path| {
+        let target = output.join(relative);
+        let target = match output.is_absolute() {
+            true => target.display().to_string(),
+            false => format!("./{}", target.display()),
+        };
+        target.replace('\\', "/")
+    };
+
+    let scopes = scopes
+        .into_iter()
+        .map(|(origin, directory)| (origin, format!("{}/", local(Path::new(&directory)))));
+
+    let exact = exact
+        .into_iter()
+        .map(|(url, relative)| (url, local(&relative)));
+
+    let imports: BTreeMap<String, String> = scopes.chain(exact).collect();
+
+    json!({ "imports": imports })
+}
+
+/// Parses a module and returns the specifiers of its static imports/exports.
+pub fn find_static_imports(cm: &Lrc<SourceMap>, path: &str, source: String) -> Result<Vec<String>> {
+    let literals = find_static_import_literals(cm, path, source)?;
+    let specifiers = literals.into_iter().map(|(specifier, _)| specifier);
+    Ok(specifiers.collect())
+}
+
+/// Returns the specifiers of a module's static imports/exports, along with the
+/// byte range of their string literals (quotes included) in the source.
+fn find_static_import_literals(
+    cm: &Lrc<SourceMap>,
+    path: &str,
+    source: String,
+) -> Result<Vec<(String, Range<usize>)>> {
+    let fm = cm.new_source_file(FileName::Real(path.into()).into(), source);
+    let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
+
+    let module = match parse_file_as_module(
+        &fm,
+        Syntax::Es(EsSyntax::default()),
+        EsVersion::latest(),
+        None,
+        &mut vec![],
+    )
+    .map_err(|e| e.into_diagnostic(&handler).emit())
+    {
+        Ok(module) => module,
+        Err(_) => bail!(format!("Failed to parse module \"{path}\"")),
+    };
+
+    let specifiers = module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(decl)) => Some(&decl.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(decl)) => Some(&decl.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(decl)) => decl.src.as_ref(),
+            _ => None,
+        })
+        .map(|src| {
+            let start = (src.span.lo - fm.start_pos).0 as usize;
+            let end = (src.span.hi - fm.start_pos).0 as usize;
+            (src.value.to_string(), start..end)
+        })
+        .collect();
+
+    Ok(specifiers)
+}
+
+/// Returns the URL of a remote (http/https) module.
+fn remote_url(path: &str) -> Option<Url> {
+    Url::parse(path)
+        .ok()
+        .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+}
+
+/// Returns a relative specifier from a vendored module to another one.
+fn relative_specifier(from: &Path, to: &Path) -> String {
+    let from = from.parent().unwrap_or(Path::new(""));
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut segments = vec![String::from(".."); from.len() - common];
+    if segments.is_empty() {
+        segments.push(".".into());
+    }
+
+    let rest = to[common..].iter().map(|c| c.as_os_str().to_string_lossy());
+    segments.extend(rest.map(String::from));
+    segments.join("/")
+}
+
+/// Returns the import-map scope of a URL and the directory it's vendored under.
+fn url_to_origin_directory(url: &Url) -> (String, String) {
+    let origin = format!("{}/", url.origin().ascii_serialization());
+    let directory = match url.port() {
+        Some(port) => format!("{}_{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+
+    (origin, directory)
+}
+
+/// Maps a remote URL to a relative location inside the vendor directory.
+fn url_to_path(url: &Url) -> PathBuf {
+    let (_, directory) = url_to_origin_directory(url);
+    let mut path = Path::new(&directory).to_path_buf();
+
+    if let Some(segments) = url.path_segments() {
+        segments
+            .filter(|segment| !segment.is_empty())
+            .for_each(|segment| path.push(segment));
+    }
+
+    // Directory-like URLs use the 'index.js' convention.
+    if url.path().ends_with('/') {
+        path.push("index.js");
+    }
+
+    // Note: URLs that only differ in their query string (e.g. `?target=es2020`)
+    // are different modules, so a hash of the query goes into the file name.
+    if let Some(query) = url.query() {
+        let hash = Sha256::digest(query.as_bytes());
+        let hash: String = hash[..4].iter().map(|byte| format!("{byte:02x}")).collect();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        // Keep the module's extension (if any) last, since loaders depend on it.
+        let name = match Path::new(name.as_ref())
+            .extension()
+            .and_then(|e| e.to_str())
+        {
+            Some(ext) if MODULE_EXTENSIONS.contains(&ext) => {
+                let stem = name.trim_end_matches(&format!(".{ext}"));
+                format!("{stem}_{hash}.{ext}")
+            }
+            _ => format!("{name}_{hash}"),
+        };
+
+        path.set_file_name(name);
+    }
+
+    path
+}
+
+/// The file extensions the module loaders recognize.
+const MODULE_EXTENSIONS: [&str; 8] = ["js", "mjs", "cjs", "jsx", "ts", "tsx", "json", "wasm"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loaders::UrlModuleLoader;
+
+    #[test]
+    fn test_url_to_path() {
+        // Group of tests to be run.
+        let tests = vec![
+            (
+                "https://deno.land/std/path/mod.ts",
+                ("https://deno.land/", "deno.land/std/path/mod.ts"),
+            ),
+            (
+                "http://localhost:8080/lib/",
+                ("http://localhost:8080/", "localhost_8080/lib/index.js"),
+            ),
+            (
+                "https://esm.sh/preact@10.11.3",
+                ("https://esm.sh/", "esm.sh/preact@10.11.3"),
+            ),
+        ];
+
+        for (url, (origin, path)) in tests {
+            let url = Url::parse(url).unwrap();
+            assert_eq!(url_to_origin_directory(&url).0, origin);
+            assert_eq!(url_to_path(&url), Path::new(path));
+        }
+    }
+
+    #[test]
+    fn test_url_to_path_with_query() {
+        let path = |url: &str| url_to_path(&Url::parse(url).unwrap());
+
+        let plain = path("https://esm.sh/mod.js");
+        let es2020 = path("https://esm.sh/mod.js?target=es2020");
+        let es2022 = path("https://esm.sh/mod.js?target=es2022");
+
+        // Every query gets a file of its own, keeping the extension.
+        assert_ne!(plain, es2020);
+        assert_ne!(es2020, es2022);
+        assert_eq!(es2020.extension().unwrap(), "js");
+        assert_eq!(es2020.parent().unwrap(), Path::new("esm.sh"));
+
+        // The same URL always maps to the same file.
+        assert_eq!(es2020, path("https://esm.sh/mod.js?target=es2020"));
+
+        let bare = path("https://esm.sh/preact@10.11.3?target=es2022");
+        let name = bare.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("preact@10.11.3_"));
+    }
+
+    #[test]
+    fn test_build_import_map() {
+        let url = "https://esm.sh/mod.js?target=es2020";
+        let relative = url_to_path(&Url::parse(url).unwrap());
+
+        let scopes = BTreeMap::from([("https://esm.sh/".into(), "esm.sh".into())]);
+        let exact = BTreeMap::from([(url.to_string(), relative.clone())]);
+        let import_map = build_import_map(Path::new("vendor"), scopes, exact);
+
+        let local = format!("./vendor/{}", relative.display()).replace('\\', "/");
+        let expected = json!({
+            "imports": {
+                "https://esm.sh/": "./vendor/esm.sh/",
+                "https://esm.sh/mod.js?target=es2020": local,
+            }
+        });
+
+        assert_eq!(import_map, expected);
+
+        // The generated map resolves the query URL to the vendored file.
+        let text = serde_json::to_string(&import_map).unwrap();
+        let import_map = ImportMap::parse_from_json(&text).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        let resolved = import_map.lookup(url).unwrap();
+
+        assert_eq!(Path::new(&resolved), cwd.join("vendor").join(&relative));
+
+        // URLs without a query keep using the origin prefix.
+        let resolved = import_map.lookup("https://esm.sh/other.js").unwrap();
+        assert!(resolved.ends_with("vendor/esm.sh/other.js"));
+    }
+
+    #[test]
+    fn test_relative_specifier() {
+        let specifier = |from: &str, to: &str| relative_specifier(Path::new(from), Path::new(to));
+
+        assert_eq!(specifier("esm.sh/a/mod.js", "esm.sh/a/dep.js"), "./dep.js");
+        assert_eq!(
+            specifier("esm.sh/a/mod.js", "esm.sh/b/c/dep.js"),
+            "../b/c/dep.js"
+        );
+        assert_eq!(
+            specifier("esm.sh/mod.js", "deno.land/dep.js"),
+            "../deno.land/dep.js"
+        );
+    }
+
+    #[test]
+    fn test_vendor_rewrites_relative_imports() {
+        // Note: The host doesn't exist, so the modules can only come from the cache.
+        let sources = [
+            (
+                "https://vendor.dune.invalid/lib/mod.js",
+                "import a from '/stable/a.js';\nimport b from './b.js?target=es2022';",
+            ),
+            (
+                "https://vendor.dune.invalid/stable/a.js",
+                "export default 1;",
+            ),
+            (
+                "https://vendor.dune.invalid/lib/b.js?target=es2022",
+                "export default 2;",
+            ),
+        ];
+
+        for (url, source) in sources {
+            let path = UrlModuleLoader::cache_path(url);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        let output = assert_fs::TempDir::new().unwrap();
+        let options = Options {
+            output: output.to_path_buf(),
+            ..Default::default()
+        };
+
+        let result = run_vendor(sources[0].0, &options);
+
+        for (url, _) in sources {
+            fs::remove_file(UrlModuleLoader::cache_path(url)).unwrap();
+        }
+
+        result.unwrap();
+
+        // The vendored module imports its dependencies from the disk.
+        let entry = output.join("vendor.dune.invalid/lib/mod.js");
+        let source = fs::read_to_string(&entry).unwrap();
+        let query = url_to_path(&Url::parse(sources[2].0).unwrap());
+        let query = query.file_name().unwrap().to_string_lossy();
+
+        assert!(source.contains("import a from \"../stable/a.js\";"));
+        assert!(source.contains(&format!("import b from \"./{query}\";")));
+
+        let cm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+
+        for specifier in find_static_imports(&cm, "mod.js", source).unwrap() {
+            let entry = entry.display().to_string();
+            let path = resolve_import(Some(&entry), &specifier, true, None).unwrap();
+            assert!(Path::new(&path).is_file());
+        }
+    }
+}
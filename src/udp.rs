@@ -0,0 +1,243 @@
+// UDP APIs
+//
+// Datagram sockets, kept non-blocking and driven from JavaScript through the
+// readiness poller (see `poll.rs`): The socket is watched for readability and
+// drained with `recv` until it would block.
+
+use crate::bindings::external_references;
+use crate::bindings::get_internal_ref;
+use crate::bindings::set_function_to;
+use crate::bindings::set_internal_ref;
+use crate::bindings::set_property_to;
+use crate::bindings::throw_exception;
+use anyhow::anyhow;
+use anyhow::Result;
+use std::io;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::net::UdpSocket;
+
+#[cfg(target_family = "unix")]
+use std::os::unix::io::AsRawFd;
+
+#[cfg(target_family = "windows")]
+use std::os::windows::io::AsRawSocket;
+
+/// The largest payload a (IPv4) UDP datagram can carry.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+pub fn initialize(scope: &mut v8::HandleScope) -> v8::Global<v8::Object> {
+    // Create local JS object.
+    let target = v8::Object::new(scope);
+
+    set_function_to(scope, target, "bind", bind);
+    set_function_to(scope, target, "send", send);
+    set_function_to(scope, target, "recv", recv);
+    set_function_to(scope, target, "close", close);
+
+    // Return v8 global handle.
+    v8::Global::new(scope, target)
+}
+
+/// Native functions referenced from the startup snapshot.
+pub fn external_references() -> Vec<v8::ExternalReference<'static>> {
+    external_references![bind, send, recv, close]
+}
+
+/// Formats an IP and a PORT into a socket address string.
+fn format_address(ip: &str, port: &str) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{ip}]:{port}"),
+        _ => format!("{ip}:{port}"),
+    }
+}
+
+/// Creates a JS object describing a socket address.
+fn create_v8_address<'a>(
+    scope: &mut v8::HandleScope<'a>,
+    address: &SocketAddr,
+) -> v8::Local<'a, v8::Object> {
+    let family = match address.ip() {
+        IpAddr::V4(_) => "IPv4",
+        IpAddr::V6(_) => "IPv6",
+    };
+
+    let target = v8::Object::new(scope);
+    let port = v8::Integer::new(scope, address.port() as i32);
+    let family = v8::String::new(scope, family).unwrap();
+    let ip = v8::String::new(scope, &address.ip().to_string()).unwrap();
+
+    set_property_to(scope, target, "port", port.into());
+    set_property_to(scope, target, "family", family.into());
+    set_property_to(scope, target, "address", ip.into());
+
+    target
+}
+
+/// Returns the socket of a udp_wrap (or fails if it's closed).
+fn get_socket<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: v8::Local<v8::Value>,
+) -> Result<&'s UdpSocket> {
+    let udp_wrap = value.to_object(scope).unwrap();
+    get_internal_ref::<Option<UdpSocket>>(scope, udp_wrap, 0)
+        .as_ref()
+        .ok_or_else(|| anyhow!("Socket is closed."))
+}
+
+/// Binds a new (non-blocking) UDP socket to a local address.
+fn bind(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    // Get IP and PORT from arguments.
+    let ip = args.get(0).to_rust_string_lossy(scope);
+    let port = args.get(1).to_rust_string_lossy(scope);
+    let address = format_address(&ip, &port);
+
+    let socket = match bind_op(&address) {
+        Ok(socket) => socket,
+        Err(e) => {
+            throw_exception(scope, &e);
+            return;
+        }
+    };
+
+    // Note: The raw handle is what the readiness poller watches.
+    #[cfg(target_family = "unix")]
+    let fd = socket.as_raw_fd() as f64;
+    #[cfg(target_family = "windows")]
+    let fd = socket.as_raw_socket() as f64;
+
+    let host = socket.local_addr().unwrap();
+    let host = create_v8_address(scope, &host);
+
+    let udp_wrap = v8::ObjectTemplate::new(scope);
+
+    // Allocate space for the wrapped Rust type.
+    udp_wrap.set_internal_field_count(1);
+
+    let udp_wrap = udp_wrap.new_instance(scope).unwrap();
+    set_internal_ref(scope, udp_wrap, 0, Some(socket));
+
+    // Create a JavaScript socket info object.
+    let socket_info = v8::Object::new(scope);
+    let fd = v8::Number::new(scope, fd);
+
+    set_property_to(scope, socket_info, "wrap", udp_wrap.into());
+    set_property_to(scope, socket_info, "fd", fd.into());
+    set_property_to(scope, socket_info, "host", host.into());
+
+    rv.set(socket_info.into());
+}
+
+/// Sends a datagram to a remote address (returning the bytes sent).
+fn send(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    // Get the datagram's data and its destination.
+    let data: v8::Local<v8::ArrayBufferView> = args.get(1).try_into().unwrap();
+    let mut buffer = vec![0; data.byte_length()];
+    data.copy_contents(&mut buffer);
+
+    let ip = args.get(2).to_rust_string_lossy(scope);
+    let port = args.get(3).to_rust_string_lossy(scope);
+    let address = format_address(&ip, &port);
+
+    let result = get_socket(scope, args.get(0)).and_then(|s| send_op(s, &buffer, &address));
+
+    match result {
+        Ok(bytes) => rv.set(v8::Number::new(scope, bytes as f64).into()),
+        Err(e) => throw_exception(scope, &e),
+    }
+}
+
+/// Receives the next available datagram (or null if there isn't one).
+fn recv(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, mut rv: v8::ReturnValue) {
+    let result = get_socket(scope, args.get(0)).and_then(recv_op);
+
+    let (data, remote) = match result {
+        Ok(Some(datagram)) => datagram,
+        Ok(None) => return rv.set(v8::null(scope).into()),
+        Err(e) => return throw_exception(scope, &e),
+    };
+
+    // Note: The bytes are handed over to v8 without copying them again.
+    let store = data.into_boxed_slice();
+    let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(store).make_shared();
+    let data = v8::ArrayBuffer::with_backing_store(scope, &store);
+    let remote = create_v8_address(scope, &remote);
+
+    let datagram = v8::Object::new(scope);
+    set_property_to(scope, datagram, "data", data.into());
+    set_property_to(scope, datagram, "remote", remote.into());
+
+    rv.set(datagram.into());
+}
+
+/// Closes a UDP socket.
+fn close(scope: &mut v8::HandleScope, args: v8::FunctionCallbackArguments, _: v8::ReturnValue) {
+    // Get the udp_wrap object.
+    let udp_wrap = args.get(0).to_object(scope).unwrap();
+
+    // Note: Dropping the socket (taken out of the option) closes it.
+    if get_internal_ref::<Option<UdpSocket>>(scope, udp_wrap, 0)
+        .take()
+        .is_none()
+    {
+        throw_exception(scope, &anyhow!("Socket is closed."));
+    }
+}
+
+/// Pure rust implementation of binding a non-blocking UDP socket.
+fn bind_op(address: &str) -> Result<UdpSocket> {
+    let socket = UdpSocket::bind(address)?;
+    socket.set_nonblocking(true)?;
+    Ok(socket)
+}
+
+/// Pure rust implementation of sending a datagram.
+fn send_op(socket: &UdpSocket, data: &[u8], address: &str) -> Result<usize> {
+    socket.send_to(data, address).map_err(|e| anyhow!(e))
+}
+
+/// Pure rust implementation of receiving a datagram (None when it would block).
+fn recv_op(socket: &UdpSocket) -> Result<Option<(Vec<u8>, SocketAddr)>> {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    match socket.recv_from(&mut buffer) {
+        Ok((size, remote)) => {
+            buffer.truncate(size);
+            Ok(Some((buffer, remote)))
+        }
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+        Err(e) => Err(anyhow!(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_udp_loopback() {
+        let receiver = bind_op("127.0.0.1:0").unwrap();
+        let sender = bind_op("127.0.0.1:0").unwrap();
+        let address = receiver.local_addr().unwrap().to_string();
+
+        // Nothing has been sent yet.
+        assert!(recv_op(&receiver).unwrap().is_none());
+
+        assert_eq!(send_op(&sender, b"ping", &address).unwrap(), 4);
+
+        // Note: Loopback delivery is quick, but not synchronous.
+        let mut datagram = None;
+        for _ in 0..100 {
+            datagram = recv_op(&receiver).unwrap();
+            if datagram.is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let (data, remote) = datagram.unwrap();
+        assert_eq!(data, b"ping");
+        assert_eq!(remote, sender.local_addr().unwrap());
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::OnceLock;
+
+/// How much the runtime itself reports about what it's doing.
+///
+/// Note: This only affects dune's operational messages, the output of the
+/// program (e.g. `console.log`) is never suppressed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors are reported.
+    Quiet,
+    /// Messages like "Downloading ..." or "Debugger listening on ...".
+    #[default]
+    Normal,
+    /// Also traces module loads, cache hits and (in debug builds) event-loop ticks.
+    Verbose,
+}
+
+/// The verbosity level is set once, when the CLI arguments are parsed.
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Sets the runtime's verbosity for the rest of the process.
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+pub fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or_default()
+}
+
+/// Prints an operational message to stdout, unless running with `--quiet`.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::verbosity::verbosity() > $crate::verbosity::Verbosity::Quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Prints a diagnostic message to stderr, only when running with `--verbose`.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::verbosity::verbosity() == $crate::verbosity::Verbosity::Verbose {
+            use colored::Colorize;
+            eprintln!("{} {}", "[dune]".dimmed(), format!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use info;
+pub(crate) use trace;
@@ -1,4 +1,7 @@
+use crate::inspector::INSPECTOR_ID_ENV;
+use crate::loaders::Reload;
 use crate::modules::load_import;
+use crate::verbosity::info;
 use anyhow::bail;
 use anyhow::Result;
 use colored::*;
@@ -20,6 +23,7 @@ use std::process::Command;
 use std::sync::mpsc;
 use std::time::Duration;
 use std::time::Instant;
+use uuid::Uuid;
 
 const WATCH_EXTENSIONS: [&str; 4] = ["js", "jsx", "ts", "tsx"];
 
@@ -65,7 +69,10 @@ impl EventHandler for WatcherHandler {
 }
 
 /// Starts the file-system watcher.
-pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
+///
+/// Note: The `script_args` are the script (as given by the user) followed by
+/// the arguments passed to it.
+pub fn start(script: &str, script_args: &[String], watch_paths: Vec<String>) -> Result<()> {
     // Check if entry point is a local file.
     let windows_regex = Regex::new(r"^[a-zA-Z]:\\").unwrap();
 
@@ -74,21 +81,23 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
     }
 
     // Check if the script exists in the file-system.
-    if let Err(e) = load_import(script, true) {
+    if let Err(e) = load_import(script, &Reload::All, None) {
         bail!(e.to_string());
     }
 
-    // Remove the `--watch` CLI arguments.
+    // Keep dune's own CLI arguments (given before the script) except `--watch`.
+    let (raw_script, user_args) = script_args.split_first().unwrap();
     let mut args = env::args()
         .skip(2)
-        .filter(|arg| !script.contains(arg))
+        .take_while(|arg| arg != raw_script)
         .filter(|arg| !arg.starts_with("--watch"))
         .filter(|arg| !arg.starts_with("--watch="))
         .filter(|arg| !watch_paths.iter().any(|path| path == arg))
         .collect::<Vec<String>>();
 
     args.insert(0, "run".into());
-    args.insert(1, script.into());
+    args.push(script.into());
+    args.extend(user_args.iter().cloned());
 
     let (sender, receiver) = mpsc::channel::<PathBuf>();
 
@@ -104,7 +113,7 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
 
     if watch_paths.is_empty() {
         // Start watching the current working dir.
-        println!("{}", "[dune] Watching path: *.*".yellow());
+        info!("{}", "[dune] Watching path: *.*".yellow());
         match watcher.watch(Path::new("."), RecursiveMode::Recursive) {
             Ok(_) => {}
             Err(e) => bail!(e),
@@ -118,7 +127,7 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
             }
         }
 
-        println!(
+        info!(
             "{}",
             format!(
                 "[dune] watching path(s): {}",
@@ -135,10 +144,15 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
     let exe = std::env::current_exe().unwrap();
     let extension = if cfg!(windows) { "exe" } else { "" };
 
+    // Note: Every restart advertises the same inspector target, so an attached
+    // devtools frontend can reconnect (keeping its breakpoints).
+    let inspector_id = Uuid::new_v4().to_string();
+
     'outer: loop {
         // Run the main script as a child process.
         let mut process = match Command::new(exe.with_extension(extension))
             .args(&args)
+            .env(INSPECTOR_ID_ENV, &inspector_id)
             .spawn()
         {
             Ok(process) => process,
@@ -148,11 +162,13 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
         loop {
             // Check if we have a file change to handle.
             if receiver.recv_timeout(Duration::from_millis(250)).is_ok() {
-                println!(
+                info!(
                     "{}",
                     "[dune] File change detected! Restarting!".bright_blue()
                 );
                 process.kill().unwrap();
+                // Wait for the process to exit, so its ports are released.
+                let _ = process.wait();
                 continue 'outer;
             }
 
@@ -162,7 +178,7 @@ pub fn start(script: &str, watch_paths: Vec<String>) -> Result<()> {
                     Some(1) => "[dune] Process finished. Restarting on file change...".red(),
                     _ => "[dune] Process finished. Restarting on file change...".bright_blue(),
                 };
-                println!("{output}");
+                info!("{output}");
                 receiver.recv().unwrap();
                 continue 'outer;
             }
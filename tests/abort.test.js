@@ -0,0 +1,29 @@
+import test from 'test';
+import assert from 'assert';
+
+test('[ABORT] AbortSignal.timeout aborts after the delay.', async () => {
+  const signal = AbortSignal.timeout(5);
+  assert.false(signal.aborted);
+  // Note: The timeout alone doesn't keep the process alive.
+  const keepAlive = setTimeout(() => {}, 1000);
+  await new Promise((resolve) => signal.addEventListener('abort', resolve));
+  clearTimeout(keepAlive);
+  assert.true(signal.aborted);
+  assert.equal(signal.reason.name, 'TimeoutError');
+});
+
+test('[ABORT] AbortSignal.any aborts when any input aborts.', async () => {
+  const first = new AbortController();
+  const second = new AbortController();
+  const signal = AbortSignal.any([first.signal, second.signal]);
+  assert.false(signal.aborted);
+  second.abort('stop');
+  assert.true(signal.aborted);
+  assert.equal(signal.reason, second.signal.reason);
+});
+
+test('[ABORT] AbortSignal.any is aborted by an already aborted input.', () => {
+  const signal = AbortSignal.any([AbortSignal.abort('early')]);
+  assert.true(signal.aborted);
+  assert.equal(signal.reason.message, 'early');
+});
@@ -4,3 +4,17 @@ import assert from 'assert';
 test('[ARGS] CLI arguments is an array.', () => {
   assert.true(Array.isArray(process.argv));
 });
+
+test('[ARGS] First argument is the dune executable.', () => {
+  assert.string(process.execPath);
+  assert.string(process.argv0);
+  assert.equal(process.argv[0], process.execPath);
+});
+
+test('[ARGS] Second argument is the entry path.', () => {
+  assert.equal(process.argv[1], process.env.TEST_ENTRY_PATH);
+});
+
+test('[ARGS] The exit code is unset by default.', () => {
+  assert.equal(process.exitCode, undefined);
+});
@@ -0,0 +1,40 @@
+import test from 'test';
+import assert from 'assert';
+
+const boom = () => {
+  throw new TypeError('Value 42 is invalid');
+};
+
+test('[ASSERT] Thrown errors can be matched by class.', () => {
+  assert.throws(boom, TypeError);
+  assert.throws(() => assert.throws(boom, RangeError));
+});
+
+test('[ASSERT] Thrown errors can be matched by a regex.', () => {
+  assert.throws(boom, /value \d+/i);
+  assert.throws(() => assert.throws(boom, /unknown/));
+});
+
+test('[ASSERT] Thrown errors can be matched by a predicate.', () => {
+  assert.throws(boom, (error) => error.message.includes('42'));
+  assert.throws(() => assert.throws(boom, () => false));
+});
+
+test('[ASSERT] Assertions fail when nothing is thrown.', () => {
+  assert.throws(() => assert.throws(() => {}, TypeError), /didn't/);
+  assert.doesNotThrow(() => {});
+  assert.throws(() => assert.doesNotThrow(boom), /Value 42 is invalid/);
+});
+
+test('[ASSERT] Rejected promises can be matched.', async () => {
+  const promise = Promise.reject(new RangeError('Out of range'));
+  await assert.rejects(promise, RangeError);
+  await assert.rejects(async () => boom(), /invalid/);
+  await assert.rejects(assert.rejects(Promise.resolve(42)), /didn't/);
+});
+
+test('[ASSERT] Resolved promises pass doesNotReject.', async () => {
+  await assert.doesNotReject(Promise.resolve(42));
+  await assert.doesNotReject(async () => 42);
+  await assert.rejects(assert.doesNotReject(async () => boom()), /invalid/);
+});
@@ -0,0 +1,146 @@
+import test from 'test';
+import assert from 'assert';
+import { setTimeout as sleep } from 'timers/promises';
+import { debounce, throttle, retry, timeout, pLimit } from 'async';
+import { TaskGroup, withTaskGroup } from 'async';
+
+test('[ASYNC] Debounce should call once at the end of a burst.', async () => {
+  const calls = [];
+  const fn = debounce((value) => calls.push(value), 20);
+
+  fn(1);
+  fn(2);
+  fn(3);
+  assert.equal(calls, []);
+
+  await sleep(50);
+  assert.equal(calls, [3]);
+});
+
+test('[ASYNC] Debounce should support leading calls.', async () => {
+  const calls = [];
+  const leading = debounce((value) => calls.push(value), 20, {
+    leading: true,
+  });
+
+  leading(1);
+  leading(2);
+  assert.equal(calls, [1]);
+
+  await sleep(50);
+  assert.equal(calls, [1, 2]);
+
+  // A single call in a burst only runs on the leading edge.
+  leading(3);
+  await sleep(50);
+  assert.equal(calls, [1, 2, 3]);
+});
+
+test('[ASYNC] Debounce should skip trailing calls when disabled.', async () => {
+  const calls = [];
+  const fn = debounce((value) => calls.push(value), 20, {
+    leading: true,
+    trailing: false,
+  });
+
+  fn(1);
+  fn(2);
+  await sleep(50);
+  assert.equal(calls, [1]);
+
+  fn.cancel();
+});
+
+test('[ASYNC] Throttle should call at most once per window.', async () => {
+  const calls = [];
+  const fn = throttle((value) => calls.push(value), 30);
+
+  fn(1);
+  fn(2);
+  fn(3);
+  assert.equal(calls, [1]);
+
+  await sleep(80);
+  assert.equal(calls, [1, 3]);
+});
+
+test('[ASYNC] Retry should call the function until it succeeds.', async () => {
+  let attempts = 0;
+  const value = await retry(
+    () => {
+      if (++attempts < 3) throw new Error('Failed');
+      return 'done';
+    },
+    { minTimeout: 1 }
+  );
+
+  assert.equal(value, 'done');
+  assert.equal(attempts, 3);
+});
+
+test('[ASYNC] Timeout should reject and abort slow operations.', async () => {
+  let aborted = false;
+  const task = (signal) => {
+    signal.addEventListener('abort', () => (aborted = true));
+    return sleep(1000, undefined, { signal });
+  };
+
+  await assert.rejects(() => timeout(task, 10), /timed out/);
+  assert.true(aborted);
+  assert.equal(await timeout(Promise.resolve(42), 10), 42);
+});
+
+test('[ASYNC] Limiters should cap the concurrent functions.', async () => {
+  const limit = pLimit(2);
+  let running = 0;
+  let maxRunning = 0;
+
+  const task = async () => {
+    running++;
+    maxRunning = Math.max(maxRunning, running);
+    await sleep(5);
+    running--;
+  };
+
+  await Promise.all(Array.from({ length: 6 }, () => limit(task)));
+  assert.equal(maxRunning, 2);
+  assert.equal(limit.activeCount, 0);
+});
+
+test('[ASYNC] Promise.withResolvers should be available.', async () => {
+  const { promise, resolve, reject } = Promise.withResolvers();
+
+  assert.true(typeof reject === 'function');
+  resolve(42);
+  assert.equal(await promise, 42);
+});
+
+test('[ASYNC] Task groups should cancel the rest on failure.', async () => {
+  let cancelled = false;
+  const group = new TaskGroup();
+
+  group.spawn(async (signal) => {
+    signal.addEventListener('abort', () => (cancelled = true));
+    await sleep(1000, undefined, { signal });
+  });
+
+  group.spawn(async () => {
+    await sleep(5);
+    throw new Error('Task failed');
+  });
+
+  await assert.rejects(() => group.wait(), /Task failed/);
+  assert.true(cancelled);
+  assert.true(group.signal.aborted);
+  assert.throws(() => group.spawn(() => {}), Error);
+});
+
+test('[ASYNC] Task groups should resolve with every result.', async () => {
+  const results = await withTaskGroup(async (group) => {
+    const first = group.spawn(async () => 1);
+    const second = group.spawn(async (_, value) => value, 2);
+    return Promise.all([first, second]);
+  });
+
+  assert.equal(results, [1, 2]);
+});
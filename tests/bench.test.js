@@ -0,0 +1,14 @@
+import test from 'test';
+import assert from 'assert';
+import { BenchRunner, doNotOptimize } from 'bench';
+
+test('[BENCH] doNotOptimize returns the consumed value.', () => {
+  const value = { answer: 42 };
+  assert.equal(doNotOptimize(value), value);
+});
+
+test('[BENCH] Benchmarks must have unique names.', () => {
+  const runner = new BenchRunner();
+  runner.bench('sum', () => 1 + 1);
+  assert.throws(() => runner.bench('sum', () => 2 + 2), Error);
+});
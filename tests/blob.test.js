@@ -0,0 +1,33 @@
+import test from 'test';
+import assert from 'assert';
+
+test('[BLOB] Blobs can be created from mixed parts.', async () => {
+  const bytes = new Uint8Array([32, 119, 111, 114, 108, 100]);
+  const blob = new Blob(['hello', bytes], { type: 'Text/Plain' });
+  assert.equal(blob.size, 11);
+  assert.equal(blob.type, 'text/plain');
+  assert.equal(await blob.text(), 'hello world');
+});
+
+test('[BLOB] Blobs can be sliced.', async () => {
+  const blob = new Blob(['hello world']);
+  assert.equal(await blob.slice(6).text(), 'world');
+  assert.equal(await blob.slice(-5, -3).text(), 'wo');
+  assert.equal(blob.slice(4, 2).size, 0);
+});
+
+test('[BLOB] Blobs can be read as bytes and streams.', async () => {
+  const blob = new Blob([new Blob(['abc']), 'def']);
+  const buffer = await blob.arrayBuffer();
+  const chunks = [];
+  for await (const chunk of blob.stream()) chunks.push(chunk);
+  assert.equal(buffer.byteLength, 6);
+  assert.equal(new TextDecoder().decode(chunks[0]), 'abcdef');
+});
+
+test('[BLOB] Files have a name and a modification date.', () => {
+  const file = new File(['data'], 'notes.txt', { lastModified: 42 });
+  assert.instanceOf(file, Blob);
+  assert.equal(file.name, 'notes.txt');
+  assert.equal(file.lastModified, 42);
+});
@@ -0,0 +1,71 @@
+import test from 'test';
+import assert from 'assert';
+import { Buffer } from 'buffer';
+
+test('[BUFFER] Buffers should be Uint8Arrays.', () => {
+  const buffer = Buffer.from([1, 2, 3]);
+
+  assert.true(buffer instanceof Uint8Array);
+  assert.true(Buffer.isBuffer(buffer));
+  assert.true(Buffer.isBuffer(buffer.subarray(1)));
+  assert.false(Buffer.isBuffer(new Uint8Array(3)));
+  assert.equal(new TextDecoder().decode(Buffer.from('hello')), 'hello');
+});
+
+test('[BUFFER] Numbers should be read and written in both endians.', () => {
+  const buffer = Buffer.alloc(8);
+
+  assert.equal(buffer.writeUInt32BE(0xdeadbeef, 0), 4);
+  assert.equal(buffer.toString('hex', 0, 4), 'deadbeef');
+  assert.equal(buffer.readUInt32BE(0), 0xdeadbeef);
+  assert.equal(buffer.readUInt32LE(0), 0xefbeadde);
+
+  buffer.writeInt16LE(-2, 4);
+  assert.equal(buffer.readInt16LE(4), -2);
+  assert.equal(buffer.readUInt16BE(4), 0xfeff);
+
+  buffer.writeDoubleBE(1.5);
+  assert.equal(buffer.readDoubleBE(), 1.5);
+
+  buffer.writeBigInt64LE(-1n);
+  assert.equal(buffer.readBigUInt64LE(), 2n ** 64n - 1n);
+
+  buffer.writeIntBE(-123456, 0, 3);
+  assert.equal(buffer.readIntBE(0, 3), -123456);
+});
+
+test('[BUFFER] Numeric accessors should check their ranges.', () => {
+  const buffer = Buffer.alloc(4);
+
+  assert.throws(() => buffer.readUInt32BE(1), RangeError);
+  assert.throws(() => buffer.writeUInt8(256), RangeError);
+  assert.throws(() => buffer.writeInt8(-129), RangeError);
+});
+
+test('[BUFFER] Strings should round-trip through the encodings.', () => {
+  const text = 'héllo wörld 👋';
+  const buffer = Buffer.from(text);
+
+  assert.equal(buffer.toString('base64'), 'aMOpbGxvIHfDtnJsZCDwn5GL');
+  for (const encoding of ['base64', 'base64url', 'hex', 'utf8']) {
+    const encoded = buffer.toString(encoding);
+    assert.true(Buffer.from(encoded, encoding).equals(buffer));
+  }
+
+  assert.equal(Buffer.from('aGk', 'base64').toString(), 'hi');
+  assert.equal(Buffer.from('hi', 'ucs2').toString('utf16le'), 'hi');
+  assert.equal(Buffer.from('ÿ', 'latin1')[0], 0xff);
+  assert.equal(Buffer.byteLength(text), 18);
+});
+
+test('[BUFFER] Buffers should be concatenated, compared and searched.', () => {
+  const buffer = Buffer.concat([Buffer.from('hello '), Buffer.from('world')]);
+
+  assert.equal(buffer.toString(), 'hello world');
+  assert.equal(buffer.indexOf('world'), 6);
+  assert.true(buffer.includes(Buffer.from('lo w')));
+  assert.true(buffer.slice(0, 5).equals(Buffer.from('hello')));
+  assert.equal(Buffer.compare(Buffer.from('a'), Buffer.from('b')), -1);
+  assert.equal(Buffer.alloc(5, 'ab').toString(), 'ababa');
+  assert.throws(() => Buffer.from('data', 'unknown'), TypeError);
+});
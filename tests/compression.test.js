@@ -0,0 +1,44 @@
+import test from 'test';
+import assert from 'assert';
+
+const text = 'Hello World! '.repeat(500);
+
+async function readAll(stream) {
+  return new Response(stream).bytes();
+}
+
+test('[COMPRESSION] Data survives a compression roundtrip.', async () => {
+  for (const format of ['gzip', 'deflate', 'deflate-raw', 'br']) {
+    const source = ReadableStream.from([new TextEncoder().encode(text)]);
+    const bytes = await readAll(
+      source.pipeThrough(new CompressionStream(format))
+    );
+
+    const output = await readAll(
+      ReadableStream.from([bytes]).pipeThrough(new DecompressionStream(format))
+    );
+
+    assert.true(bytes.byteLength < text.length);
+    assert.equal(new TextDecoder().decode(output), text);
+  }
+});
+
+test('[COMPRESSION] Unknown formats should be rejected.', () => {
+  assert.throws(() => new CompressionStream('zstd'), TypeError);
+  assert.throws(() => new DecompressionStream('lzma'), TypeError);
+});
+
+test('[COMPRESSION] Invalid compressed data should error.', async () => {
+  const source = ReadableStream.from([new Uint8Array([1, 2, 3, 4])]);
+  const stream = source.pipeThrough(new DecompressionStream('gzip'));
+
+  await assert.rejects(() => readAll(stream), TypeError);
+});
+
+test('[COMPRESSION] A cancelled stream can not be written to.', async () => {
+  const stream = new CompressionStream('br');
+  const writer = stream.writable.getWriter();
+
+  await stream.readable.cancel();
+  await assert.rejects(() => writer.write(new TextEncoder().encode(text)));
+});
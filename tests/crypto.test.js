@@ -0,0 +1,30 @@
+import test from 'test';
+import assert from 'assert';
+import { randomBytes } from 'crypto';
+
+test('[CRYPTO] getRandomValues should fill integer arrays.', () => {
+  const array = new Uint32Array(8);
+
+  assert.equal(crypto.getRandomValues(array), array);
+  assert.true(array.some((value) => value !== 0));
+});
+
+test('[CRYPTO] getRandomValues should reject invalid arrays.', () => {
+  assert.throws(() => crypto.getRandomValues(new Float64Array(4)), TypeError);
+  assert.throws(() => crypto.getRandomValues(new Uint8Array(65537)), TypeError);
+});
+
+test('[CRYPTO] randomUUID should return version 4 UUIDs.', () => {
+  const pattern =
+    /^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/;
+
+  assert.true(pattern.test(crypto.randomUUID()));
+  assert.false(crypto.randomUUID() === crypto.randomUUID());
+});
+
+test('[CRYPTO] randomBytes should return a Uint8Array.', () => {
+  const bytes = randomBytes(16);
+
+  assert.true(bytes instanceof Uint8Array);
+  assert.equal(bytes.byteLength, 16);
+});
@@ -0,0 +1,64 @@
+import test from 'test';
+import assert from 'assert';
+import csv from 'csv';
+
+test('[CSV] Should parse rows as arrays.', () => {
+  const rows = csv.parse('a,b,c\n1,2,3\n');
+
+  assert.objectEqual(rows, [
+    ['a', 'b', 'c'],
+    ['1', '2', '3'],
+  ]);
+});
+
+test('[CSV] Should handle quoted fields with embedded newlines.', () => {
+  const rows = csv.parse('id,note\n1,"multi\nline, ""quoted"""\n2,x');
+
+  assert.objectEqual(rows, [
+    ['id', 'note'],
+    ['1', 'multi\nline, "quoted"'],
+    ['2', 'x'],
+  ]);
+});
+
+test('[CSV] Should return objects when headers are present.', () => {
+  const rows = csv.parse('name;age\nalice;30\nbob;41\n', {
+    delimiter: ';',
+    headers: true,
+  });
+
+  assert.objectEqual(rows, [
+    { name: 'alice', age: '30' },
+    { name: 'bob', age: '41' },
+  ]);
+});
+
+test('[CSV] Should parse a stream of chunks.', async () => {
+  const source = ReadableStream.from(['name,note\nalice,"a', ' b"\nbob,c\n']);
+  const rows = [];
+
+  for await (const row of csv.parseStream(source, { headers: true })) {
+    rows.push(row);
+  }
+
+  assert.objectEqual(rows, [
+    { name: 'alice', note: 'a b' },
+    { name: 'bob', note: 'c' },
+  ]);
+});
+
+test('[CSV] Should stringify rows and objects.', () => {
+  const arrays = csv.stringify([
+    ['a', 'b,c'],
+    [1, null],
+  ]);
+  const objects = csv.stringify([{ name: 'alice', note: 'say "hi"' }]);
+
+  assert.equal(arrays, 'a,"b,c"\n1,\n');
+  assert.equal(objects, 'name,note\nalice,"say ""hi"""\n');
+});
+
+test('[CSV] Should reject invalid dialects.', () => {
+  assert.throws(() => csv.parse('a', { delimiter: '::' }), TypeError);
+  assert.throws(() => csv.stringify([], { quote: ',' }), TypeError);
+});
@@ -0,0 +1,25 @@
+import test from 'test';
+import assert from 'assert';
+import dns from 'dns';
+
+test('[DNS] Resolves IP addresses without a lookup.', async () => {
+  const addresses = await dns.lookup('127.0.0.1');
+  assert.equal(addresses, [{ family: 'IPv4', address: '127.0.0.1' }]);
+});
+
+test('[DNS] Caches resolutions until flushed.', async () => {
+  dns.flushCache();
+  const first = await dns.lookup('localhost');
+  const second = await dns.lookup('localhost');
+
+  // Each caller gets its own copy of the cached resolution.
+  assert.equal(first, second);
+  assert.false(first[0] === second[0]);
+
+  dns.flushCache('localhost');
+  assert.equal(await dns.lookup('localhost', { cache: false }), first);
+});
+
+test('[DNS] Validates the cache options.', () => {
+  assert.throws(() => dns.setCacheOptions({ ttl: -1 }), TypeError);
+});
@@ -0,0 +1,36 @@
+import test from 'test';
+import assert from 'assert';
+
+test('[ENCODING] TextEncoder.encodeInto writes complete characters.', () => {
+  const encoder = new TextEncoder();
+  const dest = new Uint8Array(5);
+  const { read, written } = encoder.encodeInto('a€😀', dest);
+  assert.equal(read, 2);
+  assert.equal(written, 4);
+});
+
+test('[ENCODING] TextDecoder supports legacy encodings.', () => {
+  const latin1 = new TextDecoder('latin1');
+  const utf16 = new TextDecoder('utf-16le');
+  assert.equal(latin1.encoding, 'windows-1252');
+  const bytes = new Uint8Array([0x63, 0x61, 0x66, 0xe9]);
+  assert.equal(latin1.decode(bytes), 'café');
+  assert.equal(utf16.decode(new Uint8Array([0x68, 0, 0x69, 0])), 'hi');
+});
+
+test('[ENCODING] TextDecoder decodes streams across chunks.', () => {
+  const decoder = new TextDecoder();
+  const bytes = new TextEncoder().encode('€');
+  let output = decoder.decode(bytes.subarray(0, 1), { stream: true });
+  output += decoder.decode(bytes.subarray(1));
+  assert.equal(output, '€');
+});
+
+test('[ENCODING] TextDecoder handles the fatal and ignoreBOM options.', () => {
+  const fatal = new TextDecoder('utf-8', { fatal: true });
+  const keepBOM = new TextDecoder('utf-8', { ignoreBOM: true });
+  const bytes = new Uint8Array([0xef, 0xbb, 0xbf, 0x61]);
+  assert.throws(() => fatal.decode(new Uint8Array([0xff])), new TypeError());
+  assert.equal(new TextDecoder().decode(bytes), 'a');
+  assert.equal(keepBOM.decode(bytes), '\ufeffa');
+});
@@ -0,0 +1,71 @@
+import test from 'test';
+import assert from 'assert';
+
+test('[EVENTS] Listeners receive dispatched events.', () => {
+  const target = new EventTarget();
+  const received = [];
+  target.addEventListener('ping', (event) => received.push(event));
+  const event = new Event('ping');
+  assert.true(target.dispatchEvent(event));
+  assert.equal(received.length, 1);
+  assert.true(received[0] === event);
+  assert.true(event.target === target);
+  assert.true(event.currentTarget === null);
+});
+
+test('[EVENTS] Once listeners are invoked a single time.', () => {
+  const target = new EventTarget();
+  let calls = 0;
+  target.addEventListener('ping', () => calls++, { once: true });
+  target.dispatchEvent(new Event('ping'));
+  target.dispatchEvent(new Event('ping'));
+  assert.equal(calls, 1);
+});
+
+test('[EVENTS] Listeners are removed when the signal aborts.', () => {
+  const target = new EventTarget();
+  const controller = new AbortController();
+  let calls = 0;
+  const { signal } = controller;
+  target.addEventListener('ping', () => calls++, { signal });
+  target.dispatchEvent(new Event('ping'));
+  controller.abort();
+  target.dispatchEvent(new Event('ping'));
+  assert.equal(calls, 1);
+});
+
+test('[EVENTS] Capture listeners are invoked first.', () => {
+  const target = new EventTarget();
+  const order = [];
+  target.addEventListener('ping', () => order.push('bubble'));
+  target.addEventListener('ping', () => order.push('capture'), true);
+  target.dispatchEvent(new Event('ping'));
+  assert.equal(order, ['capture', 'bubble']);
+});
+
+test('[EVENTS] Passive listeners cannot cancel events.', () => {
+  const target = new EventTarget();
+  const cancel = (event) => event.preventDefault();
+  target.addEventListener('passive', cancel, { passive: true });
+  target.addEventListener('active', cancel);
+  const options = { cancelable: true };
+  assert.true(target.dispatchEvent(new Event('passive', options)));
+  assert.false(target.dispatchEvent(new Event('active', options)));
+});
+
+test('[EVENTS] Custom events carry their detail.', () => {
+  const target = new EventTarget();
+  let detail = null;
+  target.addEventListener('data', { handleEvent: (e) => (detail = e.detail) });
+  target.dispatchEvent(new CustomEvent('data', { detail: { id: 1 } }));
+  assert.equal(detail, { id: 1 });
+});
+
+test('[EVENTS] Abort signals are event targets.', () => {
+  const controller = new AbortController();
+  const types = [];
+  assert.true(controller.signal instanceof EventTarget);
+  controller.signal.onabort = (event) => types.push(event.type);
+  controller.abort();
+  assert.equal(types, ['abort']);
+});
@@ -0,0 +1,142 @@
+import test from 'test';
+import http from 'http';
+import assert from 'assert';
+
+test('[FETCH] Headers should be case-insensitive.', () => {
+  const headers = new Headers({ 'Content-Type': 'text/plain' });
+  headers.append('ACCEPT', 'text/html');
+  headers.append('accept', ' application/json ');
+
+  assert.equal(headers.get('content-type'), 'text/plain');
+  assert.equal(headers.get('Accept'), 'text/html, application/json');
+  assert.true(headers.has('CONTENT-TYPE'));
+  assert.equal(headers.get('missing'), null);
+
+  headers.delete('Content-Type');
+  assert.false(headers.has('content-type'));
+});
+
+test('[FETCH] Headers should keep Set-Cookie values apart.', () => {
+  const headers = new Headers([
+    ['set-cookie', 'a=1'],
+    ['Set-Cookie', 'b=2'],
+    ['x-custom', 'value'],
+  ]);
+
+  const names = [...headers.keys()];
+
+  assert.equal(headers.getSetCookie().length, 2);
+  assert.equal(names.join(','), 'set-cookie,set-cookie,x-custom');
+});
+
+test('[FETCH] Headers should reject invalid names and values.', () => {
+  assert.throws(() => new Headers({ 'in valid': 'value' }), TypeError);
+  assert.throws(() => new Headers({ name: 'a\r\nb' }), TypeError);
+});
+
+test('[FETCH] Responses can be read once.', async () => {
+  const response = new Response('Hello World', { status: 201 });
+
+  assert.true(response.ok);
+  assert.equal(response.status, 201);
+  const contentType = response.headers.get('content-type');
+  assert.equal(contentType, 'text/plain;charset=UTF-8');
+  assert.equal(await response.text(), 'Hello World');
+  assert.true(response.bodyUsed);
+
+  await assert.rejects(() => response.text(), TypeError);
+});
+
+test('[FETCH] Responses can be created from streams.', async () => {
+  const stream = ReadableStream.from(['Hello', ' ', 'World']);
+  const response = new Response(stream);
+  const bytes = await response.bytes();
+
+  assert.equal(bytes.byteLength, 11);
+});
+
+test('[FETCH] Response helpers should set the right headers.', async () => {
+  const json = Response.json({ a: 1 }, { status: 202 });
+  const redirect = Response.redirect('http://localhost/', 301);
+
+  assert.equal(json.headers.get('content-type'), 'application/json');
+  assert.equal((await json.json()).a, 1);
+  assert.equal(redirect.status, 301);
+  assert.equal(redirect.headers.get('location'), 'http://localhost/');
+  assert.equal(redirect.body, null);
+});
+
+test('[FETCH] Responses should validate their status.', () => {
+  assert.throws(() => new Response(null, { status: 99 }), RangeError);
+  assert.throws(() => new Response('body', { status: 204 }), TypeError);
+});
+
+test('[FETCH] Requests should not have bodies on GET.', async () => {
+  const request = new Request('http://localhost/', {
+    method: 'post',
+    body: 'data',
+  });
+
+  const copy = new Request(request, { headers: { 'x-copy': 'true' } });
+
+  assert.equal(copy.method, 'POST');
+  assert.equal(copy.headers.get('x-copy'), 'true');
+  assert.equal(await copy.text(), 'data');
+  assert.throws(() => new Request('/', { body: 'data' }), TypeError);
+});
+
+test('[FETCH] Interceptors run in order and can short-circuit.', async () => {
+  const calls = [];
+  const removeFirst = fetch.intercept(async (request, next) => {
+    calls.push('first');
+    const headers = new Headers(request.headers);
+    headers.set('x-token', 'secret');
+    const response = await next(new Request(request, { headers }));
+    return new Response(await response.text(), { status: 203 });
+  });
+
+  const removeSecond = fetch.intercept(async (request) => {
+    calls.push('second');
+    return new Response(request.headers.get('x-token'));
+  });
+
+  const response = await fetch('http://localhost/interceptors');
+  removeFirst();
+  removeSecond();
+
+  assert.equal(calls, ['first', 'second']);
+  assert.equal(response.status, 203);
+  assert.equal(await response.text(), 'secret');
+});
+
+test('[FETCH] Interceptor errors reject the fetch call.', async () => {
+  const remove = fetch.intercept(() => {
+    throw new Error('Blocked');
+  });
+
+  await assert.rejects(() => fetch('http://localhost/blocked'), /Blocked/);
+  remove();
+});
+
+test('[FETCH] Retries should resend requests after failures.', async () => {
+  let requests = 0;
+  const server = http.createServer(async (_, res) => {
+    requests++;
+    if (requests === 1) {
+      await res.writeHead(503, { 'retry-after': '0' });
+      return res.end('Unavailable');
+    }
+    res.end('Hello');
+  });
+
+  const { port } = await server.listen(0, '127.0.0.1');
+  const url = `http://127.0.0.1:${port}/`;
+
+  const response = await fetch(url, { agent: false, retry: { attempts: 2 } });
+  const text = await response.text();
+  await server.close();
+
+  assert.equal(response.status, 200);
+  assert.equal(text, 'Hello');
+  assert.equal(requests, 2);
+});
@@ -3,6 +3,16 @@ import assert from 'assert';
 import fs from 'fs';
 import { pipeline } from 'stream';
 
+// Runs `fn` with a fresh temporary directory, removed once it settles.
+async function withTempDir(fn) {
+  const dir = await fs.mkdtemp(`./tmp_test_${process.pid}_`);
+  try {
+    return await fn(dir);
+  } finally {
+    await fs.rm(dir, { recursive: true });
+  }
+}
+
 test('[FILE-SYSTEM] Reads current test file into a Uint8Array.', async () => {
   const content = await fs.readFile(import.meta.url);
   assert.true(content instanceof Uint8Array);
@@ -13,6 +23,12 @@ test('[FILE-SYSTEM] Reads current test file as a string.', async () => {
   assert.true(typeof content === 'string');
 });
 
+test('[FILE-SYSTEM] Rejects reading a file that does not exist.', async () => {
+  const missing = `./tmp_missing_${process.pid}.txt`;
+  await assert.rejects(() => fs.readFile(missing), /No such file|cannot find/);
+  assert.throws(() => fs.readFileSync(missing, 'utf-8'));
+});
+
 test('[FILE-SYSTEM] Reads current test file as stream.', async () => {
   let content = '';
   const stream = fs.createReadStream(import.meta.url, { encoding: 'utf-8' });
@@ -30,6 +46,143 @@ test('[FILE-SYSTEM] Writes data into a temp file.', async () => {
   assert.equal(content, data);
 });
 
+test('[FILE-SYSTEM] Rejects with an AbortError when the signal is already aborted.', async () => {
+  const controller = new AbortController();
+  controller.abort();
+  await assert.rejects(
+    () => fs.readdir('.', { signal: controller.signal }),
+    (error) => error.name === 'AbortError'
+  );
+});
+
+test('[FILE-SYSTEM] Appends data to a new and to an existing file.', async () => {
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/append.txt`;
+    const bytes = new TextEncoder().encode('__, second__');
+    await fs.appendFile(tempFile, 'first');
+    await fs.appendFile(tempFile, bytes.subarray(2, bytes.length - 2));
+    fs.appendFileSync(tempFile, ', third');
+    const content = await fs.readFile(tempFile, { encoding: 'utf-8' });
+    assert.equal(content, 'first, second, third');
+  });
+});
+
+test('[FILE-SYSTEM] Creates and reads a symbolic link.', async () => {
+  await withTempDir(async (dir) => {
+    const target = `${dir}/target.txt`;
+    const link = `${dir}/link.txt`;
+    await fs.writeFile(target, 'linked');
+    await fs.symlink('target.txt', link);
+    assert.equal(await fs.readlink(link), 'target.txt');
+    assert.true((await fs.stat(link)).isFile);
+    assert.false((await fs.stat(link)).isSymbolicLink);
+    assert.true((await fs.lstat(link)).isSymbolicLink);
+    assert.true(fs.lstatSync(link).isSymbolicLink);
+    await assert.rejects(() => fs.readlink(target));
+  });
+});
+
+test('[FILE-SYSTEM] Truncates and zero-extends a file.', async () => {
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/truncate.txt`;
+    await fs.writeFile(tempFile, 'hello world');
+    await fs.truncate(tempFile, 5);
+    assert.equal(await fs.readFile(tempFile, { encoding: 'utf-8' }), 'hello');
+
+    const file = await fs.open(tempFile, 'r+');
+    await fs.ftruncate(file, 7);
+    await file.close();
+
+    const content = await fs.readFile(tempFile);
+    assert.equal(Array.from(content.slice(5)), [0, 0]);
+  });
+});
+
+test('[FILE-SYSTEM] Changes the permissions of a file.', async () => {
+  if (process.platform === 'windows') return;
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/chmod.txt`;
+    await fs.writeFile(tempFile, '');
+    await fs.chmod(tempFile, 0o600);
+    assert.equal((await fs.stat(tempFile)).mode & 0o777, 0o600);
+    fs.chmodSync(tempFile, '644');
+    assert.equal(fs.statSync(tempFile).mode & 0o777, 0o644);
+  });
+});
+
+test('[FILE-SYSTEM] Checks the existence of (and the access to) a path.', async () => {
+  const { F_OK, R_OK } = fs.constants;
+  await fs.access(import.meta.url);
+  fs.accessSync(import.meta.url, F_OK | R_OK);
+  await assert.rejects(
+    () => fs.access(`./tmp_missing_${process.pid}`),
+    /No such file|cannot find/
+  );
+});
+
+test('[FILE-SYSTEM] Writes at a position of a file.', async () => {
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/write_at.txt`;
+    await fs.writeFile(tempFile, 'hello  ');
+    const file = await fs.open(tempFile, 'r+');
+    const bytes = await file.writeAt(new TextEncoder().encode('world'), 6);
+    await file.close();
+    assert.equal(bytes, 5);
+    const content = await fs.readFile(tempFile, { encoding: 'utf-8' });
+    assert.equal(content, 'hello world');
+  });
+});
+
+test('[FILE-SYSTEM] Changes the access and modification times of a file.', async () => {
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/utimes.txt`;
+    await fs.writeFile(tempFile, '');
+    await fs.utimes(tempFile, 1_000_000_000_000, 1_000_000_000_000);
+    assert.equal((await fs.stat(tempFile)).mtimeMs, 1_000_000_000_000);
+    fs.utimesSync(tempFile, new Date(0), new Date(86_400_000));
+    assert.equal(fs.statSync(tempFile).mtimeMs, 86_400_000);
+  });
+});
+
+test('[FILE-SYSTEM] Copies a file (unless the destination exists with COPYFILE_EXCL).', async () => {
+  await withTempDir(async (dir) => {
+    const source = `${dir}/source.txt`;
+    const destination = `${dir}/destination.txt`;
+    await fs.writeFile(source, 'copied');
+    assert.equal(await fs.copyFile(source, destination), 6);
+    const content = await fs.readFile(destination, { encoding: 'utf-8' });
+    assert.equal(content, 'copied');
+    await assert.rejects(
+      () => fs.copyFile(source, destination, fs.constants.COPYFILE_EXCL),
+      /exists/
+    );
+  });
+});
+
+test('[FILE-SYSTEM] Returns sizes, times and identifiers as BigInts.', async () => {
+  const stats = await fs.stat(import.meta.url, { bigint: true });
+  const numbers = fs.statSync(import.meta.url);
+  assert.equal(typeof stats.size, 'bigint');
+  assert.equal(typeof stats.mtimeMs, 'bigint');
+  assert.equal(stats.size, BigInt(numbers.size));
+  assert.equal(typeof numbers.size, 'number');
+  if (process.platform === 'linux') {
+    assert.equal(typeof stats.inode, 'bigint');
+    const lstats = fs.lstatSync(import.meta.url, { bigint: true });
+    assert.equal(typeof lstats.dev, 'bigint');
+  }
+});
+
+test('[FILE-SYSTEM] Creates unique temporary directories.', async () => {
+  await withTempDir(async (dir) => {
+    const prefix = `${dir}/tmp_`;
+    const paths = await Promise.all([fs.mkdtemp(prefix), fs.mkdtemp(prefix)]);
+    assert.true(paths[0] !== paths[1]);
+    assert.true(paths.every((path) => path.startsWith(prefix)));
+    assert.true(fs.statSync(paths[0]).isDirectory);
+  });
+});
+
 test('[FILE-SYSTEM] Crates a directory in current path.', async () => {
   const tempDir = `./tmp_${process.pid}`;
   await fs.mkdir(tempDir);
@@ -37,3 +190,209 @@ test('[FILE-SYSTEM] Crates a directory in current path.', async () => {
   await fs.rm(tempDir);
   assert.true(stat.isDirectory);
 });
+
+test('[FILE-SYSTEM] Polls a file for changes with watchFile.', async () => {
+  const tempFile = `./tmp_watch_${process.pid}.txt`;
+  await fs.writeFile(tempFile, 'a');
+
+  const changed = new Promise((resolve) => {
+    fs.watchFile(tempFile, { intervalMs: 10 }, (curr, prev) => {
+      resolve([curr, prev]);
+    });
+  });
+
+  // Give the poller a chance to record the initial statistics.
+  await new Promise((resolve) => setTimeout(resolve, 50));
+  await fs.writeFile(tempFile, 'abc');
+
+  const [curr, prev] = await changed;
+  fs.unwatchFile(tempFile);
+  await fs.rm(tempFile);
+
+  assert.equal(prev.size, 1);
+  assert.equal(curr.size, 3);
+});
+
+test('[FILE-SYSTEM] Reads a file line by line.', async () => {
+  const tempFile = `./tmp_lines_${process.pid}.txt`;
+  await fs.writeFile(tempFile, 'first\r\nsecond\n\nlast');
+
+  const lines = [];
+  for await (const line of fs.readLines(tempFile)) lines.push(line);
+  await fs.rm(tempFile);
+
+  assert.equal(lines, ['first', 'second', '', 'last']);
+});
+
+test('[FILE-SYSTEM] Caches stat results until invalidated.', async () => {
+  const tempFile = `./tmp_cache_${process.pid}.txt`;
+  await fs.writeFile(tempFile, 'a');
+
+  assert.equal((await fs.stat(tempFile, { cache: true })).size, 1);
+  await fs.writeFile(tempFile, 'abc');
+  assert.equal((await fs.stat(tempFile, { cache: true })).size, 1);
+  assert.equal((await fs.stat(tempFile)).size, 3);
+
+  fs.clearStatCache(tempFile);
+  assert.equal((await fs.stat(tempFile, { cache: true })).size, 3);
+  assert.true((await fs.realpath(tempFile)).endsWith(tempFile.slice(2)));
+
+  await fs.rm(tempFile);
+});
+
+test('[FILE-SYSTEM] Resolves a relative path with ".." segments.', async () => {
+  await withTempDir(async (dir) => {
+    await fs.mkdir(`${dir}/nested`);
+    const expected = await fs.realpath(dir);
+    const resolved = await fs.realpath(`${dir}/nested/../nested/..`);
+    assert.equal(resolved, expected);
+    assert.equal(fs.realpathSync(`${dir}/./nested/..`), expected);
+    assert.false(expected.startsWith('\\\\?\\'));
+  });
+});
+
+test('[FILE-SYSTEM] Iterates lazily over the entries of a directory.', async () => {
+  await withTempDir(async (dir) => {
+    await fs.mkdir(`${dir}/folder`);
+    await fs.writeFile(`${dir}/file.txt`, '');
+
+    const entries = [];
+    for await (const entry of await fs.opendir(dir)) entries.push(entry);
+    entries.sort((a, b) => a.name.localeCompare(b.name));
+
+    assert.equal(entries.map((entry) => entry.name), ['file.txt', 'folder']);
+    assert.true(entries[0].isFile && entries[1].isDirectory);
+
+    const handle = fs.opendirSync(dir);
+    handle.closeSync();
+    await assert.rejects(() => handle.read());
+  });
+});
+
+test('[FILE-SYSTEM] Reads directory entries with their file types.', async () => {
+  await withTempDir(async (dir) => {
+    await fs.mkdir(`${dir}/folder`);
+    await fs.writeFile(`${dir}/file.txt`, '');
+
+    const entries = await fs.readdir(dir, { withFileTypes: true });
+    entries.sort((a, b) => a.name.localeCompare(b.name));
+
+    assert.equal(entries.map((entry) => entry.name), ['file.txt', 'folder']);
+    assert.true(entries[0].isFile && !entries[0].isDirectory);
+    assert.true(entries[1].isDirectory && !entries[1].isSymbolicLink);
+
+    const names = fs.readdirSync(dir).sort();
+    assert.equal(names, ['file.txt', 'folder']);
+  });
+});
+
+test('[FILE-SYSTEM] Copies a nested directory recursively.', async () => {
+  await withTempDir(async (dir) => {
+    const source = `${dir}/source`;
+    const destination = `${dir}/destination`;
+    await fs.mkdir(`${source}/nested`, { recursive: true });
+    await fs.writeFile(`${source}/a.txt`, 'a');
+    await fs.writeFile(`${source}/nested/b.txt`, 'b');
+
+    await assert.rejects(() => fs.cp(source, destination), /EISDIR/);
+    await fs.cp(source, destination, { recursive: true });
+
+    const entries = fs.readdirSync(destination).sort();
+    assert.equal(entries, ['a.txt', 'nested']);
+    assert.equal(fs.readFileSync(`${destination}/a.txt`, 'utf-8'), 'a');
+    assert.equal(fs.readFileSync(`${destination}/nested/b.txt`, 'utf-8'), 'b');
+
+    const options = { recursive: true, force: false, errorOnExist: true };
+    assert.throws(() => fs.cpSync(source, destination, options));
+  });
+});
+
+test('[FILE-SYSTEM] Coalesces a burst of watch events for the same path.', async () => {
+  const events = [];
+  let tempFile;
+
+  await withTempDir(async (dir) => {
+    tempFile = `${dir}/debounce.txt`;
+    await fs.writeFile(tempFile, '');
+
+    const watcher = fs.watch(tempFile, { debounceMs: 100 });
+    const consumed = (async () => {
+      for await (const event of watcher) events.push(event);
+    })();
+
+    try {
+      for (let i = 0; i < 5; i++) await fs.writeFile(tempFile, `${i}`);
+      await new Promise((resolve) => setTimeout(resolve, 300));
+    } finally {
+      watcher.close();
+      await consumed;
+    }
+  });
+
+  // Note: The kind is the latest one seen (e.g. the close after the writes).
+  assert.equal(events.length, 1);
+  assert.true(events[0].paths[0].endsWith(tempFile.slice(2)));
+});
+
+test('[FILE-SYSTEM] Closing a watcher drops its debounced events.', async () => {
+  const events = [];
+
+  await withTempDir(async (dir) => {
+    const tempFile = `${dir}/debounce_close.txt`;
+    await fs.writeFile(tempFile, '');
+
+    // Note: The binding is used directly, since a closed FsWatcher ignores late events.
+    const binding = process.binding('fs');
+    const push = (event) => events.push(event);
+    const id = binding.watch(tempFile, false, push, 200);
+
+    await fs.writeFile(tempFile, 'changed');
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    binding.unwatch(id);
+    await new Promise((resolve) => setTimeout(resolve, 400));
+  });
+
+  assert.equal(events.length, 0);
+});
+
+test('[FILE-SYSTEM] Watches multiple directories with a single watcher.', async () => {
+  await withTempDir(async (dir) => {
+    const dirs = [`${dir}/a`, `${dir}/b`];
+    await Promise.all(dirs.map((path) => fs.mkdir(path)));
+
+    const roots = new Set();
+    const watcher = fs.watch(dirs);
+    const consumed = (async () => {
+      for await (const event of watcher) roots.add(event.root);
+    })();
+
+    try {
+      await fs.writeFile(`${dirs[0]}/file.txt`, 'a');
+      await fs.writeFile(`${dirs[1]}/file.txt`, 'b');
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    } finally {
+      watcher.close();
+      await consumed;
+    }
+
+    assert.equal([...roots].sort(), dirs);
+  });
+});
+
+test('[FILE-SYSTEM] File streams are created once per file.', async () => {
+  await withTempDir(async (dir) => {
+    await fs.writeFile(`${dir}/file.txt`, 'hello');
+    const file = await fs.open(`${dir}/file.txt`, 'r');
+
+    assert.true(file.readable === file.readable);
+    assert.true(file.writable === file.writable);
+
+    // Note: A locked stream is still returned (instead of a fresh one).
+    const reader = file.readable.getReader();
+    assert.true(file.readable.locked);
+
+    const { value } = await reader.read();
+    assert.equal(new TextDecoder().decode(value), 'hello');
+    await reader.cancel();
+  });
+});
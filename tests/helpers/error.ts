@@ -0,0 +1,7 @@
+interface ErrorOptions {
+  message: string;
+}
+
+export function createError(options: ErrorOptions): Error {
+  return new Error(options.message);
+}
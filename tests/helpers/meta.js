@@ -0,0 +1 @@
+export const isMain = import.meta.main;
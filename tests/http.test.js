@@ -0,0 +1,391 @@
+import test from 'test';
+import net from 'net';
+import path from 'path';
+import http from 'http';
+import assert from 'assert';
+
+const options = { timeout: 2000 };
+
+async function listen(server) {
+  const { port } = await server.listen(0, '127.0.0.1');
+  return `http://127.0.0.1:${port}`;
+}
+
+test('[HTTP] Server should reject large sized bodies.', options, async () => {
+  const server = http.createServer({ maxBodySize: 4 }, (_, res) => {
+    res.end('OK');
+  });
+
+  const url = await listen(server);
+  const { statusCode } = await http.request(url, {
+    method: 'POST',
+    body: 'Hello World',
+  });
+
+  await server.close();
+  assert.equal(statusCode, 413);
+});
+
+test('[HTTP] Server should reject large chunked bodies.', options, async () => {
+  let error;
+  const server = http.createServer({ maxBodySize: 4 }, async (req, res) => {
+    error = await req.text().catch((err) => err);
+    res.writeHead(error.status);
+    res.end();
+  });
+
+  const url = await listen(server);
+  const { statusCode } = await http.request(url, {
+    method: 'POST',
+    body: ['Hello', 'World'],
+  });
+
+  await server.close();
+  assert.equal(statusCode, 413);
+  assert.equal(error.code, 'ERR_BODY_TOO_LARGE');
+});
+
+test('[HTTP] Request should reject large responses.', options, async () => {
+  const server = http.createServer((_, res) => res.end('Hello World'));
+  const url = await listen(server);
+
+  await assert.rejects(
+    () => http.request(url, { maxBodySize: 4 }),
+    /maximum size of 4 bytes/
+  );
+
+  await server.close();
+});
+
+test('[HTTP] Request should allow bodies at the limit.', options, async () => {
+  const server = http.createServer((_, res) => res.end('Hello'));
+  const url = await listen(server);
+
+  const { body } = await http.request(url, { maxBodySize: 5 });
+  const text = await body.text();
+
+  await server.close();
+  assert.equal(text, 'Hello');
+});
+
+test('[HTTP] Server should reject large headers.', options, async () => {
+  const server = http.createServer({ maxHeaderSize: 1024 }, (_, res) => {
+    res.end('OK');
+  });
+
+  const url = await listen(server);
+  const { statusCode } = await http.request(url, {
+    headers: { 'x-large': 'A'.repeat(2048) },
+  });
+
+  await server.close();
+  assert.equal(statusCode, 431);
+});
+
+test('[HTTP] Server should close stalled connections.', options, async () => {
+  const server = http.createServer({ headersTimeout: 100 }, (_, res) => {
+    res.end('OK');
+  });
+
+  const { port } = await server.listen(0, '127.0.0.1');
+  const socket = new net.Socket();
+  const decoder = new TextDecoder();
+
+  await socket.connect(port, '127.0.0.1');
+  await socket.write('GET / HTTP/1.1\r\n');
+
+  const response = decoder.decode(await socket.read());
+
+  await server.close();
+  assert.true(response.startsWith('HTTP/1.1 408 Request Timeout'));
+});
+
+test('[HTTP] Request should reject large headers.', options, async () => {
+  const server = http.createServer((_, res) => {
+    res.setHeader('x-large', 'A'.repeat(2048));
+    res.end('OK');
+  });
+
+  const url = await listen(server);
+
+  await assert.rejects(
+    () => http.request(url, { maxHeaderSize: 1024 }),
+    /maximum size of 1024 bytes/
+  );
+
+  await server.close();
+});
+
+test('[HTTP] Server should handle pipelined requests.', options, async () => {
+  const server = http.createServer(async (req, res) => {
+    const body = await req.text();
+    res.end(`${req.url}:${body}`);
+  });
+
+  const { port } = await server.listen(0, '127.0.0.1');
+  const socket = new net.Socket();
+  const decoder = new TextDecoder();
+
+  await socket.connect(port, '127.0.0.1');
+  await socket.write(
+    'POST /a HTTP/1.1\r\nContent-Length: 3\r\n\r\nfoo' +
+      'GET /b HTTP/1.1\r\n\r\n' +
+      'POST /c HTTP/1.1\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbar'
+  );
+
+  let response = '';
+  for await (const data of socket) response += decoder.decode(data);
+
+  await server.close();
+
+  const bodies = response.match(/\/[a-c]:\w*/g);
+  assert.equal(bodies, ['/a:foo', '/b:', '/c:bar']);
+});
+
+test('[HTTP] Server should skip unread request bodies.', options, async () => {
+  const server = http.createServer((req, res) => res.end(req.url));
+  const { port } = await server.listen(0, '127.0.0.1');
+  const socket = new net.Socket();
+  const decoder = new TextDecoder();
+
+  await socket.connect(port, '127.0.0.1');
+  await socket.write(
+    'POST /a HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello' +
+      'GET /b HTTP/1.1\r\nConnection: close\r\n\r\n'
+  );
+
+  let response = '';
+  for await (const data of socket) response += decoder.decode(data);
+
+  await server.close();
+  assert.equal(response.match(/HTTP\/1.1 200 OK/g).length, 2);
+});
+
+test('[HTTP] Server should free the parsers of closed connections.', options, async () => {
+  const server = http.createServer((req, res) => res.end(req.url));
+  const { port } = await server.listen(0, '127.0.0.1');
+  const decoder = new TextDecoder();
+
+  for (let i = 0; i < 100; i++) {
+    const socket = new net.Socket();
+    await socket.connect(port, '127.0.0.1');
+    await socket.write(`GET /${i} HTTP/1.1\r\nConnection: close\r\n\r\n`);
+
+    let response = '';
+    for await (const data of socket) response += decoder.decode(data);
+    assert.true(response.endsWith(`/${i}`));
+  }
+
+  await server.close();
+
+  // A closed parser can't be used anymore (its state has been dropped).
+  const binding = process.binding('http_parser');
+  const parser = binding.createConnection();
+  binding.closeConnection(parser);
+  assert.throws(() => binding.pollConnection(parser), /has been closed/);
+});
+
+test('[HTTP] Cookie headers should be parsed.', () => {
+  const cookies = http.parseCookies('foo=bar; equation=E%3Dmc%5E2; foo=baz');
+  assert.equal(cookies, { foo: 'bar', equation: 'E=mc^2' });
+});
+
+test('[HTTP] Cookies should be serialized.', () => {
+  const cookie = http.serializeCookie('session', 'a b', {
+    maxAge: 60,
+    path: '/',
+    httpOnly: true,
+    sameSite: 'lax',
+  });
+
+  const expected = 'session=a%20b; Max-Age=60; Path=/; HttpOnly; SameSite=Lax';
+  assert.equal(cookie, expected);
+});
+
+test('[HTTP] Invalid cookies should not be serialized.', () => {
+  assert.throws(() => http.serializeCookie('in valid', 'value'), TypeError);
+  assert.throws(
+    () => http.serializeCookie('name', 'value', { path: '/; Secure' }),
+    TypeError
+  );
+});
+
+const fixtures = path.join(path.dirname(import.meta.url), 'fixtures');
+
+async function sendRaw(port, message) {
+  const socket = new net.Socket();
+  const decoder = new TextDecoder();
+
+  await socket.connect(port, '127.0.0.1');
+  await socket.write(message);
+
+  let response = '';
+  for await (const data of socket) response += decoder.decode(data);
+  return response;
+}
+
+test('[HTTP] ServeDir should serve files.', options, async () => {
+  const server = http.createServer(http.serveDir(fixtures));
+  const url = await listen(server);
+
+  const { statusCode, headers, body } = await http.request(`${url}/data.json`);
+  const data = await body.json();
+
+  await server.close();
+  assert.equal(statusCode, 200);
+  assert.true(headers['content-type'].startsWith('application/json'));
+  assert.true(typeof headers.etag === 'string');
+  assert.true(typeof data === 'object');
+});
+
+test('[HTTP] ServeDir should support range requests.', options, async () => {
+  const server = http.createServer(http.serveDir(fixtures));
+  const url = await listen(server);
+
+  const response = await http.request(`${url}/data.json`, {
+    headers: { range: 'bytes=0-0' },
+  });
+
+  const text = await response.body.text();
+
+  await server.close();
+  assert.equal(response.statusCode, 206);
+  assert.true(response.headers['content-range'].startsWith('bytes 0-0/'));
+  assert.equal(text, '{');
+});
+
+test('[HTTP] ServeDir should handle conditional GETs.', options, async () => {
+  const server = http.createServer(http.serveDir(fixtures));
+  const url = await listen(server);
+
+  const { headers, body } = await http.request(`${url}/data.json`);
+  await body.text();
+
+  const { statusCode } = await http.request(`${url}/data.json`, {
+    headers: { 'if-none-match': headers.etag },
+  });
+
+  await server.close();
+  assert.equal(statusCode, 304);
+});
+
+test('[HTTP] ServeDir should not escape the root.', options, async () => {
+  const server = http.createServer(http.serveDir(fixtures));
+  const { port } = await server.listen(0, '127.0.0.1');
+
+  const paths = ['/../http.test.js', '/%2e%2e/http.test.js', '/a%2F..%2F..'];
+  const responses = [];
+
+  for (const target of paths) {
+    const request = `GET ${target} HTTP/1.1\r\nConnection: close\r\n\r\n`;
+    responses.push(await sendRaw(port, request));
+  }
+
+  const missing = await http.request(`http://127.0.0.1:${port}/missing.txt`);
+
+  await server.close();
+  responses.forEach((res) => assert.true(res.startsWith('HTTP/1.1 403')));
+  assert.equal(missing.statusCode, 404);
+});
+
+test('[HTTP] Serve should handle fetch-style handlers.', options, async () => {
+  const server = http.serve(async (request) => {
+    const { name } = await request.json();
+    const headers = { 'x-method': request.method };
+    return Response.json({ hello: name }, { status: 201, headers });
+  });
+
+  const url = await listen(server);
+  const response = await fetch(`${url}/greet`, {
+    method: 'POST',
+    body: JSON.stringify({ name: 'dune' }),
+  });
+
+  const data = await response.json();
+
+  await server.close();
+  assert.equal(response.status, 201);
+  assert.equal(response.headers.get('x-method'), 'POST');
+  assert.equal(data.hello, 'dune');
+});
+
+test('[HTTP] Serve should respond with 500 on errors.', options, async () => {
+  const server = http.serve(() => 'not a response', {
+    onError: () => new Response('Oops', { status: 500 }),
+  });
+
+  const url = await listen(server);
+  const response = await fetch(url);
+  const text = await response.text();
+
+  await server.close();
+  assert.equal(response.status, 500);
+  assert.equal(text, 'Oops');
+});
+
+test('[HTTP] Serve should send each Set-Cookie header.', options, async () => {
+  const server = http.serve(() => {
+    const headers = new Headers();
+    headers.append('set-cookie', 'a=1');
+    headers.append('set-cookie', 'b=2');
+    return new Response(null, { status: 204, headers });
+  });
+
+  const { port } = await server.listen(0, '127.0.0.1');
+  const request = 'GET / HTTP/1.1\r\nConnection: close\r\n\r\n';
+  const response = await sendRaw(port, request);
+
+  await server.close();
+  assert.true(response.startsWith('HTTP/1.1 204'));
+  assert.true(response.includes('Set-Cookie: a=1\r\nSet-Cookie: b=2'));
+});
+
+test('[HTTP] ServeDir should gzip text files.', options, async () => {
+  const server = http.createServer(http.serveDir(fixtures));
+  const url = await listen(server);
+
+  const raw = await fetch(`${url}/lorem.txt`, {
+    headers: { 'accept-encoding': 'gzip' },
+    decompress: false,
+  });
+
+  const compressed = await raw.bytes();
+  const response = await fetch(`${url}/lorem.txt`);
+  const text = await response.text();
+
+  const identity = await http.request(`${url}/lorem.txt`);
+  await identity.body.text();
+
+  await server.close();
+  assert.equal(raw.headers.get('content-encoding'), 'gzip');
+  assert.equal(raw.headers.get('vary'), 'accept-encoding');
+  assert.true(compressed.byteLength < text.length);
+  assert.true(text.startsWith('Lorem ipsum'));
+  assert.equal(identity.headers['content-encoding'], undefined);
+});
+
+test('[HTTP] Agent should reuse keep-alive connections.', options, async () => {
+  let connections = 0;
+  const server = net.createServer((socket) => {
+    connections++;
+    socket.on('data', () => {
+      socket.write('HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nOK');
+    });
+  });
+
+  const { port } = await server.listen(0, '127.0.0.1');
+  const url = `http://127.0.0.1:${port}`;
+  const agent = new http.Agent();
+
+  for (let i = 0; i < 3; i++) {
+    const { body } = await http.request(url, { agent });
+    assert.equal(await body.text(), 'OK');
+  }
+
+  // Pooling can be disabled per request.
+  await http.request(url, { agent: false });
+
+  agent.destroy();
+  await server.close();
+  assert.equal(connections, 2);
+});
@@ -1,8 +1,10 @@
 import test from 'test';
 import assert from 'assert';
 import _ from 'https://cdn.skypack.dev/lodash';
+import { statSync } from 'node:fs';
 import data from './fixtures/data.json';
 import { num } from './helpers/function';
+import { isMain } from './helpers/meta';
 
 const options = { timeout: 5000 };
 
@@ -21,6 +23,22 @@ test('[IMPORTS] URL imports works.', options, () => {
   assert.equal(_.last(words), 'universe');
 });
 
+test('[IMPORTS] Node.js built-in imports map to core modules.', () => {
+  const stats = statSync(import.meta.url);
+  assert.true(stats.isFile);
+  assert.equal(import.meta.resolve('node:fs'), 'fs');
+  assert.throws(() => import.meta.resolve('node:vm'), new TypeError());
+});
+
+test('[IMPORTS] Lazy globals share the imported core modules.', async () => {
+  const { Buffer: ImportedBuffer } = await import('buffer');
+  const { Headers: ImportedHeaders } = await import('@web/fetch');
+
+  assert.true(Buffer === ImportedBuffer);
+  assert.true(Headers === ImportedHeaders);
+  assert.true(Object.keys(globalThis).includes('ReadableStream'));
+});
+
 test('[IMPORTS] JSON imports work.', options, () => {
   assert.equal(data?.fruit, 'Apple');
   assert.equal(data?.size, 'Large');
@@ -32,3 +50,34 @@ test('[IMPORTS] WASM imports work.', { ignore: true }, async () => {
   const { default: calc } = await import('./helpers/calc.wasm');
   assert.equal(calc.addTwo(2, 3), 5);
 });
+
+test('[IMPORTS] import.meta.resolve works.', () => {
+  const url = 'https://cdn.skypack.dev/lodash';
+  assert.equal(import.meta.resolve('fs'), 'fs');
+  assert.equal(import.meta.resolve(url), url);
+  const path = import.meta.resolve('./helpers/function.js');
+  assert.true(path.endsWith('function.js'));
+  assert.throws(() => import.meta.resolve(), new TypeError());
+  assert.throws(() => import.meta.resolve('unknown'), new TypeError());
+});
+
+test('[IMPORTS] import.meta.main is only set for the entry.', () => {
+  assert.false(isMain);
+  assert.false(import.meta.main);
+});
+
+test('[IMPORTS] Module graph snapshots include loaded modules.', () => {
+  const modules = process.binding('modules').snapshot();
+  const current = modules.find((m) => m.specifier === import.meta.url);
+  assert.equal(current.loader, 'fs');
+  assert.true(current.dependencies.includes('assert'));
+  const assertModule = modules.find((m) => m.specifier === 'assert');
+  assert.equal(assertModule.loader, 'core');
+});
+
+test('[IMPORTS] TypeScript stack traces point to the source.', async () => {
+  const { createError } = await import('./helpers/error.ts');
+  const { remapStackTrace } = process.binding('modules');
+  const stack = remapStackTrace(createError({ message: 'Oops!' }).stack);
+  assert.true(stack.includes('error.ts:6:'));
+});
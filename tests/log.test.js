@@ -0,0 +1,66 @@
+import test from 'test';
+import assert from 'assert';
+import { createLogger } from 'log';
+
+test('[LOG] Levels below the minimum level are disabled.', () => {
+  const logger = createLogger({ level: 'warn' });
+  assert.false(logger.isLevelEnabled('info'));
+  assert.true(logger.isLevelEnabled('error'));
+  logger.level = 'TRACE';
+  assert.equal(logger.level, 'trace');
+  assert.true(logger.isLevelEnabled('debug'));
+});
+
+test('[LOG] Unknown levels are rejected.', () => {
+  assert.throws(() => createLogger({ level: 'verbose' }), new TypeError());
+});
+
+test('[LOG] Unknown LOG_LEVEL values fall back to the default level.', () => {
+  process.env.LOG_LEVEL = 'verbose';
+  try {
+    assert.equal(createLogger().level, 'info');
+  } finally {
+    delete process.env.LOG_LEVEL;
+  }
+});
+
+test('[LOG] Child loggers inherit the level.', () => {
+  const logger = createLogger({ level: 'error' });
+  const child = logger.child({ requestId: 1 });
+  assert.equal(child.level, 'error');
+});
+
+test('[LOG] Records are serialized as JSON lines.', () => {
+  const { serialize } = process.binding('log');
+  const fields = { id: 1, tags: ['a'], skip: undefined, when: new Date(0) };
+  const record = JSON.parse(serialize('info', 'now', 'hello', fields));
+  assert.equal(record, {
+    level: 'info',
+    time: 'now',
+    msg: 'hello',
+    id: 1,
+    tags: ['a'],
+    skip: null,
+    when: '1970-01-01T00:00:00.000Z',
+  });
+});
+
+test('[LOG] Throwing values are serialized as placeholders.', () => {
+  const { serialize } = process.binding('log');
+  const fail = () => {
+    throw new Error('Oops');
+  };
+  const fields = {
+    id: 1,
+    get broken() {
+      return fail();
+    },
+    proxy: new Proxy({}, { ownKeys: fail }),
+    custom: { toJSON: fail },
+  };
+  const record = JSON.parse(serialize('info', 'now', 'hello', fields));
+  assert.equal(record.id, 1);
+  assert.equal(record.broken, '[Throws]');
+  assert.equal(record.proxy, '[Throws]');
+  assert.equal(record.custom, '[Throws]');
+});
@@ -0,0 +1,142 @@
+import test from 'test';
+import net from 'net';
+import dns from 'dns';
+import assert from 'assert';
+
+test('[NET] Watching file descriptors should validate arguments.', () => {
+  const listener = () => {};
+
+  assert.throws(() => net.watchFd(-1, { readable: true }, listener), TypeError);
+  assert.throws(() => net.watchFd(0, {}, listener), TypeError);
+  assert.throws(() => net.watchFd(0, { writable: false }, listener), TypeError);
+});
+
+test('[NET] Toggles TCP_NODELAY and SO_KEEPALIVE on connected sockets.', async () => {
+  if (process.platform === 'windows') return;
+
+  const server = net.createServer();
+  const { port } = await server.listen(0, '127.0.0.1');
+  const socket = new net.Socket();
+
+  await socket.connect(port, '127.0.0.1');
+  const accepted = await server.accept();
+
+  try {
+    socket.setNoDelay(true);
+    socket.setNoDelay(false);
+    socket.setKeepAlive(true, 30);
+    accepted.setNoDelay();
+  } finally {
+    await socket.destroy();
+    await accepted.destroy();
+    await server.close();
+  }
+
+  assert.throws(() => socket.setNoDelay(), /not connected/);
+});
+
+test('[NET] A slow socket readable stream receives all the data.', async () => {
+  const server = net.createServer();
+  const { port } = await server.listen(0, '127.0.0.1');
+  const socket = new net.Socket();
+
+  await socket.connect(port, '127.0.0.1');
+  const accepted = await server.accept();
+  const payload = new Uint8Array(1024 * 1024).fill(42);
+
+  // Note: The stream pauses the socket while the consumer is busy.
+  const sending = accepted.end(payload);
+  const reader = socket.readable.getReader();
+  let received = 0;
+
+  try {
+    assert.true(socket.readable === socket.readable);
+    assert.true(socket.writable === socket.writable);
+
+    for (;;) {
+      const { done, value } = await reader.read();
+      if (done) break;
+      received += value.byteLength;
+      await new Promise((resolve) => setTimeout(resolve, 1));
+    }
+    await sending;
+  } finally {
+    await accepted.destroy();
+    await server.close();
+  }
+
+  assert.equal(received, payload.byteLength);
+});
+
+test('[NET] Dual-stack hosts are tried starting with IPv6.', async () => {
+  const server6 = net.createServer();
+  let port;
+
+  try {
+    ({ port } = await server6.listen(0, '::1'));
+  } catch {
+    // The host has no IPv6 loopback.
+    return;
+  }
+
+  // Note: Both servers share the port, so only the address tells them apart.
+  const server4 = net.createServer();
+  await server4.listen(port, '127.0.0.1');
+
+  const lookup = dns.lookup;
+  const socket = new net.Socket();
+
+  dns.lookup = async () => [
+    { address: '127.0.0.1', family: 'IPv4' },
+    { address: '::1', family: 'IPv6' },
+  ];
+
+  try {
+    await socket.connect(port, 'dual-stack.test');
+    assert.equal(socket.remoteAddress, '::1');
+  } finally {
+    dns.lookup = lookup;
+    await socket.destroy();
+    await server6.close();
+    await server4.close();
+  }
+});
+
+test('[NET] A connection timeout gives up every in-flight attempt.', async () => {
+  const lookup = dns.lookup;
+  const socket = new net.Socket();
+  const start = Date.now();
+  let error;
+
+  // Note: TEST-NET addresses (RFC 5737) are never routed, so the attempts hang
+  // until they're closed (or fail right away on hosts without a default route).
+  dns.lookup = async () => [
+    { address: '192.0.2.1', family: 'IPv4' },
+    { address: '192.0.2.2', family: 'IPv4' },
+  ];
+
+  socket.setTimeout(100);
+
+  try {
+    await socket.connect(80, 'unreachable.test');
+  } catch (err) {
+    error = err;
+  } finally {
+    dns.lookup = lookup;
+  }
+
+  assert.true(error !== undefined);
+  assert.true(Date.now() - start < 2000);
+
+  // The socket isn't left in the connecting state.
+  const server = net.createServer();
+  const { port } = await server.listen(0, '127.0.0.1');
+
+  try {
+    await socket.connect(port, '127.0.0.1');
+    assert.equal(socket.remotePort, port);
+  } finally {
+    await socket.destroy();
+    await server.close();
+  }
+});
@@ -0,0 +1,62 @@
+import test from 'test';
+import assert from 'assert';
+import path, { posix, win32 } from 'path';
+
+test('[PATH] Joining paths normalizes the result.', () => {
+  assert.equal(posix.join('/a/b', '../c', './d'), '/a/c/d');
+  assert.equal(posix.join('a', '', 'b/'), 'a/b/');
+  assert.equal(posix.join(), '.');
+  assert.equal(win32.join('C:\\a', '..\\b', 'c/'), 'C:\\b\\c\\');
+});
+
+test('[PATH] Resolving paths returns absolute paths.', () => {
+  assert.equal(posix.resolve('/foo/bar', './baz'), '/foo/bar/baz');
+  assert.equal(posix.resolve('/foo/bar', '/tmp/file/'), '/tmp/file');
+  assert.equal(win32.resolve('C:\\foo', 'D:\\x', 'y'), 'D:\\x\\y');
+  assert.true(path.isAbsolute(path.resolve('file.txt')));
+  assert.equal(path.resolve('file.txt'), path.join(process.cwd(), 'file.txt'));
+});
+
+test('[PATH] Relative paths are computed between two paths.', () => {
+  const from = '/data/orandea/test/aaa';
+  const to = '/data/orandea/impl/bbb';
+  assert.equal(posix.relative(from, to), '../../impl/bbb');
+  assert.equal(posix.relative('/a', '/a'), '');
+  assert.equal(win32.relative('C:\\A\\b', 'c:\\a\\c'), '..\\c');
+  assert.equal(win32.relative('C:\\a', 'D:\\b'), 'D:\\b');
+});
+
+test('[PATH] The portions of a path can be extracted.', () => {
+  assert.equal(posix.dirname('/a/b/'), '/a');
+  assert.equal(posix.dirname('a'), '.');
+  assert.equal(posix.basename('/a/b.html', '.html'), 'b');
+  assert.equal(posix.extname('index.html'), '.html');
+  assert.equal(posix.extname('.bashrc'), '');
+  assert.equal(win32.dirname('C:\\a\\b'), 'C:\\a');
+  assert.equal(win32.basename('C:\\a\\b.txt'), 'b.txt');
+});
+
+test('[PATH] Paths can be parsed and formatted.', () => {
+  const parsed = posix.parse('/home/user/file.txt');
+  assert.equal(parsed, {
+    root: '/',
+    dir: '/home/user',
+    base: 'file.txt',
+    ext: '.txt',
+    name: 'file',
+  });
+  assert.equal(posix.format(parsed), '/home/user/file.txt');
+  assert.equal(win32.parse('C:\\dir\\file.txt').root, 'C:\\');
+});
+
+test('[PATH] Non string arguments are rejected.', () => {
+  assert.throws(() => path.join('a', 1), new TypeError());
+  assert.throws(() => path.basename(null), new TypeError());
+});
+
+test('[PATH] Platform namespaces are exposed.', () => {
+  assert.equal(posix.sep, '/');
+  assert.equal(win32.delimiter, ';');
+  assert.true(path.posix === posix);
+  assert.true(path.win32 === win32);
+});
@@ -0,0 +1,50 @@
+import test from 'test';
+import assert from 'assert';
+
+test('[PROCESS] Standard streams report if they are terminals.', () => {
+  assert.equal(typeof process.stdin.isTTY, 'boolean');
+  assert.equal(typeof process.stdout.isTTY, 'boolean');
+  assert.equal(typeof process.stderr.isTTY, 'boolean');
+});
+
+test('[PROCESS] Terminal size is only defined for terminals.', () => {
+  const { isTTY, columns, rows } = process.stdout;
+  assert.equal(typeof columns, isTTY ? 'number' : 'undefined');
+  assert.equal(typeof rows, isTTY ? 'number' : 'undefined');
+});
+
+test('[PROCESS] Standard streams are the same object on every access.', () => {
+  assert.true(process.stdout === process.stdout);
+  assert.true(process.stderr === process.stderr);
+});
+
+test('[PROCESS] Resize listeners can be added and removed.', () => {
+  const onResize = () => {};
+  process.stdout.on('resize', onResize);
+  assert.equal(process.stdout.listenerCount('resize'), 1);
+  process.stdout.off('resize', onResize);
+  assert.equal(process.stdout.listenerCount('resize'), 0);
+});
+
+test('[PROCESS] Process introspection properties are defined.', () => {
+  assert.number(process.pid);
+  assert.number(process.ppid);
+  assert.string(process.platform);
+  assert.string(process.arch);
+  assert.true(process.version.startsWith('v'));
+  assert.string(process.versions.dune);
+  assert.string(process.versions.v8);
+});
+
+test('[PROCESS] Resource limits can be read and set.', () => {
+  if (process.platform === 'windows') return;
+
+  const { soft, hard } = process.getrlimit('nofile');
+  assert.number(soft);
+  assert.number(hard);
+  assert.true(soft <= hard);
+
+  process.setrlimit('nofile', { soft });
+  assert.equal(process.getrlimit('nofile').soft, soft);
+  assert.throws(() => process.getrlimit('unknown'), TypeError);
+});
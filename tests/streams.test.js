@@ -21,3 +21,81 @@ test('[STREAMS] The stream pipeline completes.', async () => {
   await pipeline(inputStream$('Hello World!'), toUpperCase$, sink$);
   assert.equal(sentence, 'HELLOWORLD!');
 });
+
+test('[STREAMS] A ReadableStream can be read by a reader.', async () => {
+  const stream = new ReadableStream({
+    start(controller) {
+      controller.enqueue('a');
+      controller.enqueue('b');
+      controller.close();
+    },
+  });
+  const reader = stream.getReader();
+  assert.true(stream.locked);
+  assert.equal(await reader.read(), { value: 'a', done: false });
+  assert.equal(await reader.read(), { value: 'b', done: false });
+  assert.equal(await reader.read(), { value: undefined, done: true });
+});
+
+test('[STREAMS] A BYOB reader fills the provided buffer.', async () => {
+  const stream = new ReadableStream({
+    type: 'bytes',
+    start(controller) {
+      controller.enqueue(new Uint8Array([1, 2, 3, 4, 5]));
+      controller.close();
+    },
+  });
+  const reader = stream.getReader({ mode: 'byob' });
+  const first = await reader.read(new Uint8Array(2));
+  const second = await reader.read(new Uint8Array(8));
+  assert.equal(Array.from(first.value), [1, 2]);
+  assert.equal(Array.from(second.value), [3, 4, 5]);
+  assert.true((await reader.read(new Uint8Array(1))).done);
+});
+
+test('[STREAMS] Chunks can be piped through a TransformStream.', async () => {
+  const chunks = [];
+  const transform = new TransformStream({
+    transform: (chunk, controller) => controller.enqueue(chunk.toUpperCase()),
+  });
+  const writable = new WritableStream({ write: (chunk) => chunks.push(chunk) });
+  await ReadableStream.from(['hello', 'world'])
+    .pipeThrough(transform)
+    .pipeTo(writable);
+  assert.equal(chunks, ['HELLO', 'WORLD']);
+});
+
+test('[STREAMS] A TransformStream notifies its transformer on cancel.', async () => {
+  const reasons = [];
+  const cancel = (reason) => reasons.push(reason);
+
+  await new TransformStream({ cancel }).readable.cancel('cancelled');
+  await new TransformStream({ cancel }).writable.abort('aborted');
+
+  assert.equal(reasons, ['cancelled', 'aborted']);
+});
+
+test('[STREAMS] Cancelling a stream returns the source iterator.', async () => {
+  let returned = false;
+  const source = {
+    [Symbol.asyncIterator]: () => ({
+      next: async () => ({ value: 1, done: false }),
+      return: async () => (returned = true),
+    }),
+  };
+  for await (const _ of ReadableStream.from(source)) break;
+  assert.true(returned);
+});
+
+test('[STREAMS] A WritableStream applies backpressure.', async () => {
+  const stream = new WritableStream(
+    { write: () => new Promise((resolve) => setTimeout(resolve, 5)) },
+    { highWaterMark: 1 }
+  );
+  const writer = stream.getWriter();
+  writer.write('a');
+  assert.equal(writer.desiredSize, 0);
+  await writer.ready;
+  assert.equal(writer.desiredSize, 1);
+  await writer.close();
+});
@@ -1,5 +1,6 @@
 import test from 'test';
 import assert from 'assert';
+import timers from 'timers/promises';
 
 const options = { timeout: 500 };
 
@@ -60,3 +61,76 @@ test('[TIMERS] ClearImmediate should be supported.', options, () => {
   clearImmediate(id);
   assert.equal(data, 0);
 });
+
+test('[TIMERS] NextTick should accept params.', options, async () => {
+  const values = await new Promise((resolve) => {
+    process.nextTick((...args) => resolve(args), 'A', 'B');
+  });
+  assert.equal(values, ['A', 'B']);
+});
+
+test('[TIMERS] NextTick should run before promises.', options, async () => {
+  const order = [];
+  await new Promise((resolve) => {
+    setTimeout(() => {
+      Promise.resolve().then(() => order.push('promise'));
+      process.nextTick(() => {
+        order.push('tick');
+        process.nextTick(() => order.push('nested tick'));
+      });
+      setTimeout(resolve, 10);
+    }, 1);
+  });
+  assert.equal(order, ['tick', 'nested tick', 'promise']);
+});
+
+test('[TIMERS] NextTick from promises should run later.', options, async () => {
+  const order = [];
+  await new Promise((resolve) => {
+    setTimeout(() => {
+      Promise.resolve().then(() => {
+        process.nextTick(() => order.push('tick'));
+        order.push('first promise');
+      });
+      Promise.resolve().then(() => order.push('second promise'));
+      setTimeout(resolve, 10);
+    }, 1);
+  });
+  assert.equal(order, ['first promise', 'second promise', 'tick']);
+});
+
+test('[TIMERS] Promise timeouts resolve with a value.', options, async () => {
+  const value = await timers.setTimeout(5, 'done');
+  assert.equal(value, 'done');
+  assert.equal(await timers.setImmediate(42), 42);
+});
+
+test('[TIMERS] Promise timeouts can be aborted.', options, async () => {
+  const controller = new AbortController();
+  const { signal } = controller;
+  const promise = timers.setTimeout(1000, null, { signal });
+  controller.abort();
+  await assert.rejects(promise, (error) => error.name === 'AbortError');
+  const aborted = AbortSignal.abort();
+  await assert.rejects(timers.setImmediate(1, { signal: aborted }));
+});
+
+test('[TIMERS] Promise intervals are async iterators.', options, async () => {
+  const values = [];
+  for await (const value of timers.setInterval(5, 'tick')) {
+    values.push(value);
+    if (values.length === 3) break;
+  }
+  assert.equal(values, ['tick', 'tick', 'tick']);
+});
+
+test('[TIMERS] Promise intervals stop on abort.', options, async () => {
+  const controller = new AbortController();
+  const { signal } = controller;
+  const iterate = async () => {
+    for await (const _ of timers.setInterval(5, null, { signal })) {
+      controller.abort();
+    }
+  };
+  await assert.rejects(iterate, (error) => error.name === 'AbortError');
+});
@@ -0,0 +1,32 @@
+import test from 'test';
+import udp from 'udp';
+import assert from 'assert';
+
+test('[UDP] Sends and receives a datagram over the loopback interface.', async () => {
+  const receiver = await udp.bind(0, '127.0.0.1');
+  const sender = await udp.bind(0, '127.0.0.1');
+
+  try {
+    const received = new Promise((resolve) => {
+      receiver.recvStart((data, remote) => resolve([data, remote]));
+    });
+
+    const bytes = await sender.send('ping', receiver.address().port);
+    const [data, remote] = await received;
+
+    assert.equal(bytes, 4);
+    assert.equal(new TextDecoder().decode(data), 'ping');
+    assert.equal(remote.port, sender.address().port);
+  } finally {
+    receiver.close();
+    sender.close();
+  }
+});
+
+test('[UDP] Sending on a closed socket should throw.', async () => {
+  const socket = await udp.bind();
+  socket.close();
+
+  await assert.rejects(() => socket.send('ping', 53), /closed/);
+  assert.throws(() => socket.close());
+});
@@ -0,0 +1,71 @@
+import test from 'test';
+import assert from 'assert';
+import { parseArgs } from 'util';
+
+const options = {
+  verbose: { type: 'boolean', short: 'v' },
+  force: { type: 'boolean', short: 'f' },
+  port: { type: 'string', short: 'p', default: '80' },
+  tag: { type: 'string', short: 't', multiple: true },
+};
+
+test('[UTIL] parseArgs should parse long options.', () => {
+  const args = ['--verbose', '--port=8080', '--tag', 'a', '--tag=b'];
+  const { values, positionals } = parseArgs({ args, options });
+
+  assert.true(values.verbose);
+  assert.equal(values.port, '8080');
+  assert.objectEqual(values.tag, ['a', 'b']);
+  assert.equal(positionals.length, 0);
+});
+
+test('[UTIL] parseArgs should parse combined short flags.', () => {
+  const { values } = parseArgs({ args: ['-vf', '-p3000'], options });
+  const { values: other } = parseArgs({ args: ['-vfp', '4000'], options });
+
+  assert.true(values.verbose);
+  assert.true(values.force);
+  assert.equal(values.port, '3000');
+  assert.true(other.verbose && other.force);
+  assert.equal(other.port, '4000');
+});
+
+test('[UTIL] parseArgs should apply defaults and the terminator.', () => {
+  const { values, positionals } = parseArgs({
+    args: ['build', '--', '--verbose'],
+    options,
+    allowPositionals: true,
+  });
+
+  assert.equal(values.port, '80');
+  assert.equal(values.verbose, undefined);
+  assert.objectEqual(positionals, ['build', '--verbose']);
+});
+
+test('[UTIL] parseArgs should reject invalid arguments in strict mode.', () => {
+  const codes = [
+    [['--unknown'], 'ERR_PARSE_ARGS_UNKNOWN_OPTION'],
+    [['--port'], 'ERR_PARSE_ARGS_INVALID_OPTION_VALUE'],
+    [['--verbose=yes'], 'ERR_PARSE_ARGS_INVALID_OPTION_VALUE'],
+    [['positional'], 'ERR_PARSE_ARGS_UNEXPECTED_POSITIONAL'],
+  ];
+
+  for (const [args, code] of codes) {
+    assert.throws(
+      () => parseArgs({ args, options }),
+      (error) => error instanceof TypeError && error.code === code
+    );
+  }
+});
+
+test('[UTIL] parseArgs should accept unknown options in loose mode.', () => {
+  const { values, positionals } = parseArgs({
+    args: ['--color', '-x', 'file.txt'],
+    options,
+    strict: false,
+  });
+
+  assert.true(values.color);
+  assert.true(values.x);
+  assert.objectEqual(positionals, ['file.txt']);
+});